@@ -0,0 +1,67 @@
+#!/usr/bin/env sh
+# Compiles and tests every backend feature set, so neither build bit-rots.
+# Plain cargo, no CI specifics: run it from anywhere inside the workspace.
+set -eu
+
+cd "$(dirname "$0")/.."
+
+run() {
+    echo "==> cargo $*"
+    cargo "$@"
+}
+
+# zkper-integer alone: each backend on its own, then both together
+run check -p zkper-integer --all-targets --no-default-features --features backend-rug
+run check -p zkper-integer --all-targets --no-default-features --features backend-native
+run test -p zkper-integer --no-default-features --features backend-native
+run test -p zkper-integer --all-features
+
+# no_std + alloc: zkper-rand without OS entropy, zkper-integer with the word
+# backends only. A host build still links std for the tests, so also make
+# sure no normal dependency turns its std feature back on.
+run test -p zkper-rand --no-default-features
+if cargo tree -p zkper-rand --no-default-features -e normal,features | grep -q 'feature "std"' ||
+    cargo tree -p zkper-integer --no-default-features --features backend-native -e normal,features |
+    grep -q 'feature "std"'; then
+    echo "==> a dependency of the no_std builds enables std" >&2
+    exit 1
+fi
+
+# zkper-base's rayon-backed ECM, off by default for wasm and single-threaded builds
+run test -p zkper-base --lib --features parallel -- ecm::ecm::tests::parallel
+
+# wiping secrets through the zeroize crate, off by default
+run test -p zkper-groth16 --lib --features zeroize -- zeroize
+
+# the pure-Rust BLS12-381 and Groth16 verifier, on their own and against the
+# rug implementations. The rug-only tests, benches and examples need backend-rug.
+run check -p zkper-curves -p zkper-groth16 --all-targets --no-default-features --features wasm
+run test -p zkper-curves -p zkper-groth16 --no-default-features --features wasm
+run test -p zkper-curves --lib --features backend-native -- native
+run test -p zkper-groth16 --features backend-native --test native
+if cargo tree -p zkper-groth16 --no-default-features --features wasm -e normal |
+    grep -Eq ' (rug|gmp-mpfr-sys) '; then
+    echo "==> the pure-Rust verifier links GMP" >&2
+    exit 1
+fi
+
+# wasm32, without GMP
+if rustup target list --installed 2>/dev/null | grep -qx wasm32-unknown-unknown; then
+    run check -p zkper-rand --target wasm32-unknown-unknown --no-default-features --features wasm
+    run check -p zkper-integer --target wasm32-unknown-unknown --no-default-features --features backend-native,wasm
+    run check -p zkper-base --target wasm32-unknown-unknown --no-default-features --features backend-native,wasm
+    run check -p zkper-curves --target wasm32-unknown-unknown --no-default-features --features wasm
+    run check -p zkper-groth16 --target wasm32-unknown-unknown --no-default-features --features wasm
+else
+    echo "==> skipping wasm32 checks: rustup target add wasm32-unknown-unknown"
+fi
+
+# the top-level selection, forwarded to zkper-integer, zkper-curves and zkper-groth16
+run check -p zkper --all-targets
+run check -p zkper --all-targets --no-default-features --features backend-native
+if cargo tree -p zkper --no-default-features --features backend-native -e normal |
+    grep -Eq ' (rug|gmp-mpfr-sys) '; then
+    echo "==> the pure-Rust build links GMP" >&2
+    exit 1
+fi
+run check --workspace --all-targets --all-features
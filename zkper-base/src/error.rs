@@ -0,0 +1,163 @@
+//! One error type for applications embedding several zkper crates.
+//!
+//! Every crate keeps its own error enum, and [`Error`] wraps each of them in a
+//! variant, so callers can match on the crate a failure came from and still
+//! reach the precise variant. zkper-curves and zkper-groth16 sit above this
+//! crate, so their errors are boxed here and their `From` conversions live in
+//! those crates; [`Error::downcast_ref`] recovers them.
+//!
+//! Most of the workspace returns `anyhow::Result`. Converting an
+//! `anyhow::Error` keeps the precise variant when its root cause is an error
+//! of this crate or of zkper-integer, and keeps the `anyhow::Error` otherwise,
+//! which `downcast_ref` still looks into.
+
+use std::error::Error as StdError;
+
+use thiserror::Error;
+use zkper_integer::error::IntegerError;
+
+use crate::math::{
+    crt::errors::CrtError,
+    discrete_log::errors::DiscreteLogError,
+    factorization::{ecm::errors::ECMErrors, errors::FactorizationError},
+    ntt::errors::NttError,
+    primality::errors::PrimalityError,
+};
+
+/// A boxed error of a crate above zkper-base.
+pub type BoxedError = Box<dyn StdError + Send + Sync + 'static>;
+
+/// An error of any zkper crate, see the module documentation.
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    Integer(#[from] IntegerError),
+    #[error(transparent)]
+    Factorization(#[from] FactorizationError),
+    #[error(transparent)]
+    Ntt(#[from] NttError),
+    #[error(transparent)]
+    DiscreteLog(#[from] DiscreteLogError),
+    #[error(transparent)]
+    Primality(#[from] PrimalityError),
+    #[error(transparent)]
+    Crt(#[from] CrtError),
+    /// A `zkper_curves::error::CurveError`.
+    #[error(transparent)]
+    Curve(BoxedError),
+    /// A `zkper_groth16::error::Groth16Error`.
+    #[error(transparent)]
+    Groth16(BoxedError),
+    /// Any other failure.
+    #[error(transparent)]
+    Other(anyhow::Error),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl Error {
+    /// The wrapped error as an `E`, if it is one, whatever the variant.
+    pub fn downcast_ref<E: StdError + Send + Sync + 'static>(&self) -> Option<&E> {
+        match self {
+            Error::Integer(e) => (e as &dyn StdError).downcast_ref(),
+            Error::Factorization(e) => (e as &dyn StdError).downcast_ref(),
+            Error::Ntt(e) => (e as &dyn StdError).downcast_ref(),
+            Error::DiscreteLog(e) => (e as &dyn StdError).downcast_ref(),
+            Error::Primality(e) => (e as &dyn StdError).downcast_ref(),
+            Error::Crt(e) => (e as &dyn StdError).downcast_ref(),
+            Error::Curve(e) | Error::Groth16(e) => e.downcast_ref(),
+            Error::Other(e) => e.downcast_ref(),
+        }
+    }
+}
+
+impl From<ECMErrors> for Error {
+    fn from(e: ECMErrors) -> Self {
+        Error::Factorization(e.into())
+    }
+}
+
+impl From<anyhow::Error> for Error {
+    fn from(e: anyhow::Error) -> Self {
+        let e = match e.downcast::<IntegerError>() {
+            Ok(e) => return e.into(),
+            Err(e) => e,
+        };
+        let e = match e.downcast::<FactorizationError>() {
+            Ok(e) => return e.into(),
+            Err(e) => e,
+        };
+        let e = match e.downcast::<NttError>() {
+            Ok(e) => return e.into(),
+            Err(e) => e,
+        };
+        let e = match e.downcast::<DiscreteLogError>() {
+            Ok(e) => return e.into(),
+            Err(e) => e,
+        };
+        let e = match e.downcast::<PrimalityError>() {
+            Ok(e) => return e.into(),
+            Err(e) => e,
+        };
+        let e = match e.downcast::<CrtError>() {
+            Ok(e) => return e.into(),
+            Err(e) => e,
+        };
+        match e.downcast::<ECMErrors>() {
+            Ok(e) => e.into(),
+            Err(e) => Error::Other(e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zkper_integer::{backends::rug_backend::RugBackend, ZkperInteger};
+
+    use super::*;
+    use crate::math::factorization::check_factors;
+
+    #[test]
+    fn test_error_conversions() {
+        let n = ZkperInteger::<RugBackend>::from_i32;
+
+        let e: Error = n(6).invert(&n(9)).unwrap_err().into();
+        assert!(matches!(e, Error::Integer(IntegerError::NoInverse)));
+        assert_eq!(e.downcast_ref(), Some(&IntegerError::NoInverse));
+
+        let e: Error = check_factors(&n(12), &[n(4)]).unwrap_err().into();
+        assert!(matches!(
+            e,
+            Error::Factorization(FactorizationError::CompositeFactor)
+        ));
+        let e: Error = check_factors(&n(12), &[n(2)]).unwrap_err().into();
+        assert!(matches!(
+            e,
+            Error::Factorization(FactorizationError::IncompleteFactors)
+        ));
+
+        let e: Error = ECMErrors::NumberIsPrime.into();
+        assert!(matches!(
+            e,
+            Error::Factorization(FactorizationError::Ecm(ECMErrors::NumberIsPrime))
+        ));
+
+        let e: Error = anyhow::Error::from(NttError::SizeNotPowerOfTwo(3)).into();
+        assert!(matches!(e, Error::Ntt(NttError::SizeNotPowerOfTwo(3))));
+
+        let e: Error = anyhow::Error::from(DiscreteLogError::NotFound).into();
+        assert!(matches!(e, Error::DiscreteLog(DiscreteLogError::NotFound)));
+
+        let e: Error = anyhow::Error::from(PrimalityError::Composite).into();
+        assert!(matches!(e, Error::Primality(PrimalityError::Composite)));
+
+        let e: Error = anyhow::Error::from(CrtError::Empty).into();
+        assert!(matches!(e, Error::Crt(CrtError::Empty)));
+
+        // errors of other crates stay reachable
+        let e: Error = anyhow::Error::from(std::fmt::Error).into();
+        assert!(matches!(e, Error::Other(_)));
+        assert!(e.downcast_ref::<std::fmt::Error>().is_some());
+        assert!(e.downcast_ref::<IntegerError>().is_none());
+    }
+}
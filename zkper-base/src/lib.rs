@@ -1,2 +1,7 @@
+pub mod error;
 pub mod math;
 pub mod modulo;
+pub mod redact;
+pub mod transcript;
+
+pub use error::Error;
@@ -0,0 +1,18 @@
+use thiserror::Error;
+
+/// Errors of the Chinese remainder utilities.
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum CrtError {
+    /// No residues to combine.
+    #[error("No moduli given")]
+    Empty,
+    /// A modulus below 1.
+    #[error("Modulus at index {0} is not positive")]
+    InvalidModulus(usize),
+    /// Two moduli share a factor.
+    #[error("Modulus at index {0} is not coprime to the ones before it")]
+    NonCoprime(usize),
+    /// The residues do not match the moduli.
+    #[error("Got {actual} residues for {expected} moduli")]
+    LengthMismatch { expected: usize, actual: usize },
+}
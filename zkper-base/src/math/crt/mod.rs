@@ -0,0 +1,234 @@
+//! Chinese remainder theorem: recover x mod m_0 * ... * m_k from x mod m_i
+//! for pairwise coprime m_i.
+//!
+//! [`crt`] combines one set of residues. [`Garner`] precomputes the inverses
+//! of a fixed set of moduli and reconstructs many numbers from their residue
+//! number system (RNS) representation.
+
+pub mod errors;
+
+use zkper_integer::{traits::ZkperIntegerTrait, ZkperInteger};
+
+use self::errors::CrtError;
+
+/// a mod m in [0, m).
+fn reduce<T: ZkperIntegerTrait>(a: &ZkperInteger<T>, m: &ZkperInteger<T>) -> ZkperInteger<T> {
+    let r = a % m;
+    if r < ZkperInteger::zero() {
+        r + m
+    } else {
+        r
+    }
+}
+
+fn check_modulus<T: ZkperIntegerTrait>(m: &ZkperInteger<T>, index: usize) -> anyhow::Result<()> {
+    if *m < ZkperInteger::one() {
+        return Err(CrtError::InvalidModulus(index).into());
+    }
+    Ok(())
+}
+
+/// The x in [0, M) with x = a_i mod m_i for every (a_i, m_i) in `congruences`,
+/// together with M = m_0 * ... * m_k.
+pub fn crt<T: ZkperIntegerTrait>(
+    congruences: &[(ZkperInteger<T>, ZkperInteger<T>)],
+) -> anyhow::Result<(ZkperInteger<T>, ZkperInteger<T>)> {
+    let ((a, m), rest) = congruences.split_first().ok_or(CrtError::Empty)?;
+    check_modulus(m, 0)?;
+    let mut x = reduce(a, m);
+    let mut product = m.clone();
+
+    for (i, (a, m)) in rest.iter().enumerate() {
+        check_modulus(m, i + 1)?;
+        let inverse = reduce(&product, m)
+            .invert(m)
+            .map_err(|_| CrtError::NonCoprime(i + 1))?;
+        // x + product * t = a mod m
+        let t = reduce(&((reduce(a, m) + m - &reduce(&x, m)) * &inverse), m);
+        x += &(product.clone() * &t);
+        product *= m;
+    }
+    Ok((x, product))
+}
+
+/// Garner's algorithm for a fixed set of pairwise coprime moduli.
+#[derive(Debug, Clone)]
+pub struct Garner<T: ZkperIntegerTrait> {
+    moduli: Vec<ZkperInteger<T>>,
+    /// (m_0 * ... * m_(i-1))^-1 mod m_i, the first entry unused
+    inverses: Vec<ZkperInteger<T>>,
+    product: ZkperInteger<T>,
+}
+
+impl<T: ZkperIntegerTrait> Garner<T> {
+    pub fn new(moduli: Vec<ZkperInteger<T>>) -> anyhow::Result<Self> {
+        if moduli.is_empty() {
+            return Err(CrtError::Empty.into());
+        }
+        let mut inverses = Vec::with_capacity(moduli.len());
+        let mut product = ZkperInteger::one();
+        for (i, m) in moduli.iter().enumerate() {
+            check_modulus(m, i)?;
+            let inverse = reduce(&product, m)
+                .invert(m)
+                .map_err(|_| CrtError::NonCoprime(i))?;
+            inverses.push(inverse);
+            product *= m;
+        }
+        Ok(Self {
+            moduli,
+            inverses,
+            product,
+        })
+    }
+
+    pub fn moduli(&self) -> &[ZkperInteger<T>] {
+        &self.moduli
+    }
+
+    /// M, the product of the moduli.
+    pub fn product(&self) -> &ZkperInteger<T> {
+        &self.product
+    }
+
+    /// The residues of x, for any integer x.
+    pub fn decompose(&self, x: &ZkperInteger<T>) -> Vec<ZkperInteger<T>> {
+        self.moduli.iter().map(|m| reduce(x, m)).collect()
+    }
+
+    /// The x in [0, M) with the given residues, through its mixed-radix digits
+    /// v_i: x = v_0 + v_1 m_0 + v_2 m_0 m_1 + ...
+    pub fn reconstruct(&self, residues: &[ZkperInteger<T>]) -> anyhow::Result<ZkperInteger<T>> {
+        if residues.len() != self.moduli.len() {
+            return Err(CrtError::LengthMismatch {
+                expected: self.moduli.len(),
+                actual: residues.len(),
+            }
+            .into());
+        }
+
+        let mut digits: Vec<ZkperInteger<T>> = Vec::with_capacity(residues.len());
+        for (i, (a, m)) in residues.iter().zip(&self.moduli).enumerate() {
+            // x mod m_i from the digits so far, by Horner's rule
+            let mut partial = ZkperInteger::zero();
+            for (digit, radix) in digits.iter().zip(&self.moduli).rev() {
+                partial = reduce(&(partial * radix + digit), m);
+            }
+            let digit = reduce(&((reduce(a, m) + m - &partial) * &self.inverses[i]), m);
+            digits.push(digit);
+        }
+
+        let mut x = ZkperInteger::zero();
+        for (digit, radix) in digits.iter().zip(&self.moduli).rev() {
+            x = x * radix + digit;
+        }
+        Ok(x)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zkper_integer::{
+        backends::{rug_backend::RugBackend, u64_backend::U64Backend},
+        traits::ZkperIntegerTrait,
+        ZkperInteger,
+    };
+    use zkper_rand::TestRng;
+
+    use super::{crt, errors::CrtError, Garner};
+    use crate::math::primality::random_prime;
+
+    fn pairs<T: ZkperIntegerTrait>(
+        pairs: &[(u64, u64)],
+    ) -> Vec<(ZkperInteger<T>, ZkperInteger<T>)> {
+        pairs
+            .iter()
+            .map(|&(a, m)| (ZkperInteger::from(a), ZkperInteger::from(m)))
+            .collect()
+    }
+
+    #[test]
+    fn test_crt() {
+        // Sunzi's problem: x = 2 mod 3, 3 mod 5, 2 mod 7
+        let (x, m) = crt(&pairs::<RugBackend>(&[(2, 3), (3, 5), (2, 7)])).unwrap();
+        assert_eq!((x, m), (23u64.into(), 105u64.into()));
+
+        let (x, m) = crt(&pairs::<U64Backend>(&[(10, 11), (0, 13), (16, 17)])).unwrap();
+        assert_eq!((x, m), (1495u64.into(), 2431u64.into()));
+
+        // residues out of range and negative
+        let congruences = [
+            (
+                ZkperInteger::<RugBackend>::from(-1),
+                ZkperInteger::from(4u64),
+            ),
+            (ZkperInteger::from(14u64), ZkperInteger::from(9u64)),
+        ];
+        assert_eq!(crt(&congruences).unwrap().0, 23u64.into());
+    }
+
+    #[test]
+    fn test_garner_rns() {
+        let mut rng = TestRng::new();
+        let moduli: Vec<ZkperInteger<RugBackend>> = (0..8)
+            .map(|_| random_prime(61, &mut rng).unwrap())
+            .collect();
+        let garner = Garner::new(moduli.clone()).unwrap();
+
+        // multiply in RNS and reconstruct, with the product below M
+        let bound = ZkperInteger::one() << 200u32;
+        for _ in 0..10 {
+            let a = bound.random_below(&mut rng);
+            let b = bound.random_below(&mut rng);
+            let product: Vec<_> = garner
+                .decompose(&a)
+                .iter()
+                .zip(garner.decompose(&b))
+                .zip(garner.moduli())
+                .map(|((x, y), m)| (x * &y) % m)
+                .collect();
+            assert_eq!(garner.reconstruct(&product).unwrap(), a.clone() * &b);
+
+            let congruences: Vec<_> = garner
+                .decompose(&a)
+                .into_iter()
+                .zip(moduli.clone())
+                .collect();
+            assert_eq!(crt(&congruences).unwrap(), (a, garner.product().clone()));
+        }
+    }
+
+    #[test]
+    fn test_crt_errors() {
+        let err = |e: anyhow::Error| e.downcast::<CrtError>().unwrap();
+
+        assert_eq!(err(crt::<RugBackend>(&[]).unwrap_err()), CrtError::Empty);
+        assert_eq!(
+            err(crt(&pairs::<RugBackend>(&[(1, 6), (2, 5), (1, 4)])).unwrap_err()),
+            CrtError::NonCoprime(2)
+        );
+        assert_eq!(
+            err(crt(&pairs::<RugBackend>(&[(1, 6), (0, 0)])).unwrap_err()),
+            CrtError::InvalidModulus(1)
+        );
+
+        let moduli = |m: &[u64]| {
+            m.iter()
+                .map(|&m| ZkperInteger::<RugBackend>::from(m))
+                .collect()
+        };
+        assert_eq!(err(Garner::new(moduli(&[])).unwrap_err()), CrtError::Empty);
+        assert_eq!(
+            err(Garner::new(moduli(&[3, 5, 9])).unwrap_err()),
+            CrtError::NonCoprime(2)
+        );
+        let garner = Garner::new(moduli(&[3, 5, 7])).unwrap();
+        assert_eq!(
+            err(garner.reconstruct(&moduli(&[1, 2])).unwrap_err()),
+            CrtError::LengthMismatch {
+                expected: 3,
+                actual: 2
+            }
+        );
+    }
+}
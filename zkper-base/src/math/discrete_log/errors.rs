@@ -0,0 +1,12 @@
+use thiserror::Error;
+
+/// Errors of the discrete log utilities.
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum DiscreteLogError {
+    /// The search interval is empty.
+    #[error("Empty exponent range")]
+    EmptyRange,
+    /// No exponent in the range maps the base to the target.
+    #[error("Discrete log not found in range")]
+    NotFound,
+}
@@ -0,0 +1,194 @@
+//! Discrete logs with a bounded exponent modulo a prime: find x in a known
+//! range with g^x = h. Both algorithms take O(sqrt(range)) multiplications,
+//! so they suit exponents of up to about 64 bits, like the small messages of
+//! exponential ElGamal or the openings of a test commitment.
+
+pub mod errors;
+
+use std::collections::HashMap;
+
+use zkper_integer::{traits::ZkperIntegerTrait, ZkperInteger};
+
+use self::errors::DiscreteLogError;
+
+/// Jump sets the kangaroo method tries before giving up; each run misses
+/// with small probability, independently of the others.
+pub const KANGAROO_ATTEMPTS: u64 = 8;
+
+/// Baby-step giant-step: the least x in [0, bound) with g^x = h mod p, in
+/// O(sqrt(bound)) time and memory.
+pub fn baby_step_giant_step<T: ZkperIntegerTrait>(
+    g: &ZkperInteger<T>,
+    h: &ZkperInteger<T>,
+    modulus: &ZkperInteger<T>,
+    bound: u64,
+) -> anyhow::Result<u64> {
+    if bound == 0 {
+        return Err(DiscreteLogError::EmptyRange.into());
+    }
+    let m = bound.isqrt() + u64::from(bound.isqrt().pow(2) != bound);
+
+    // baby steps: g^j -> j, keeping the least j of every value
+    let mut table = HashMap::new();
+    let mut baby = ZkperInteger::one();
+    for j in 0..m {
+        table.entry(baby.clone()).or_insert(j);
+        baby = (baby * g) % modulus;
+    }
+
+    // giant steps: h * g^(-m i) for i = 0, 1, ...
+    let giant = g.pow_mod(&ZkperInteger::from(m), modulus).invert(modulus)?;
+    let mut gamma = h % modulus;
+    for i in 0..m {
+        if let Some(j) = table.get(&gamma) {
+            let x = i * m + j;
+            if x < bound {
+                return Ok(x);
+            }
+        }
+        gamma = (gamma * &giant) % modulus;
+    }
+    Err(DiscreteLogError::NotFound.into())
+}
+
+/// Pollard's kangaroo (lambda) method: an x in [lower, upper] with
+/// g^x = h mod p, in O(sqrt(upper - lower)) time and constant memory.
+///
+/// The method is probabilistic; it tries [`KANGAROO_ATTEMPTS`] jump sets
+/// before reporting [`DiscreteLogError::NotFound`], and every answer it
+/// returns is checked.
+pub fn pollard_kangaroo<T: ZkperIntegerTrait>(
+    g: &ZkperInteger<T>,
+    h: &ZkperInteger<T>,
+    modulus: &ZkperInteger<T>,
+    lower: u64,
+    upper: u64,
+) -> anyhow::Result<u64> {
+    if lower > upper {
+        return Err(DiscreteLogError::EmptyRange.into());
+    }
+    let h = h % modulus;
+    let width = upper - lower;
+
+    // jumps 2^0 .. 2^(k-1), whose mean 2^k / k is about sqrt(width) / 2
+    let k = (u64::BITS - width.leading_zeros()).div_ceil(2) + 1;
+    let jumps: Vec<u64> = (0..k).map(|i| 1 << i).collect();
+    let powers: Vec<_> = jumps
+        .iter()
+        .map(|&s| g.pow_mod(&ZkperInteger::from(s), modulus))
+        .collect();
+    // the tame kangaroo covers about `width` in total
+    let tame_steps = 2 * width.isqrt() + 1;
+
+    for attempt in 0..KANGAROO_ATTEMPTS {
+        let jump = |y: &ZkperInteger<T>| {
+            (y.stable_hash()
+                .wrapping_add(attempt.wrapping_mul(0x9e37_79b9_7f4a_7c15))
+                % k as u64) as usize
+        };
+
+        // the tame kangaroo starts at g^upper and sets a trap where it stops
+        let mut tame = g.pow_mod(&ZkperInteger::from(upper), modulus);
+        let mut tame_distance = 0u64;
+        for _ in 0..tame_steps {
+            let i = jump(&tame);
+            tame_distance += jumps[i];
+            tame = (tame * &powers[i]) % modulus;
+        }
+
+        // the wild kangaroo starts at h = g^x and falls into the trap once
+        // its path meets the tame one
+        let mut wild = h.clone();
+        let mut wild_distance = 0u64;
+        while wild_distance <= width.saturating_add(tame_distance) {
+            if wild == tame {
+                let x =
+                    u64::try_from(upper as u128 + tame_distance as u128 - wild_distance as u128);
+                match x {
+                    Ok(x)
+                        if (lower..=upper).contains(&x)
+                            && g.pow_mod(&ZkperInteger::from(x), modulus) == h =>
+                    {
+                        return Ok(x)
+                    }
+                    _ => break,
+                }
+            }
+            let i = jump(&wild);
+            wild_distance += jumps[i];
+            wild = (wild * &powers[i]) % modulus;
+        }
+    }
+    Err(DiscreteLogError::NotFound.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use zkper_integer::{
+        backends::{rug_backend::RugBackend, u128_backend::U128Backend},
+        traits::ZkperIntegerTrait,
+        ZkperInteger,
+    };
+
+    use super::{baby_step_giant_step, errors::DiscreteLogError, pollard_kangaroo};
+
+    fn check<T: ZkperIntegerTrait>(p: &ZkperInteger<T>, g: u64, exponents: &[u64]) {
+        let g = ZkperInteger::from(g);
+        for &x in exponents {
+            let h = g.pow_mod(&ZkperInteger::from(x), p);
+            assert_eq!(baby_step_giant_step(&g, &h, p, 1 << 20).unwrap(), x);
+            assert_eq!(
+                pollard_kangaroo(&g, &h, p, x.saturating_sub(1000), x + 50_000).unwrap(),
+                x
+            );
+        }
+    }
+
+    #[test]
+    fn test_discrete_log() {
+        // the BN254 scalar field, generator 5
+        let bn254_fr = ZkperInteger::<RugBackend>::from_str(
+            "21888242871839275222246405745257275088548364400416034343698204186575808495617",
+        );
+        check(&bn254_fr, 5, &[0, 1, 2, 1000, 123_456, 1_000_000]);
+
+        // the Goldilocks prime on the word backend, generator 7
+        let goldilocks = ZkperInteger::<U128Backend>::from_hex_str("0xffffffff00000001");
+        check(&goldilocks, 7, &[0, 3, 65_537, 999_999]);
+
+        // a wide kangaroo interval
+        let g = ZkperInteger::from(5u64);
+        let x = (1u64 << 32) + 0x1234_5678;
+        let h = g.pow_mod(&ZkperInteger::from(x), &bn254_fr);
+        assert_eq!(
+            pollard_kangaroo(&g, &h, &bn254_fr, 1 << 32, (1 << 32) + (1 << 30)).unwrap(),
+            x
+        );
+    }
+
+    #[test]
+    fn test_discrete_log_errors() {
+        let p = ZkperInteger::<RugBackend>::from(1_000_003u64);
+        let g = ZkperInteger::from(2u64);
+        let h = g.pow_mod(&ZkperInteger::from(5000u64), &p);
+        let err = |e: anyhow::Error| e.downcast::<DiscreteLogError>().unwrap();
+
+        assert_eq!(
+            err(baby_step_giant_step(&g, &h, &p, 0).unwrap_err()),
+            DiscreteLogError::EmptyRange
+        );
+        assert_eq!(
+            err(pollard_kangaroo(&g, &h, &p, 10, 9).unwrap_err()),
+            DiscreteLogError::EmptyRange
+        );
+        assert_eq!(
+            err(baby_step_giant_step(&g, &h, &p, 4000).unwrap_err()),
+            DiscreteLogError::NotFound
+        );
+        assert_eq!(
+            err(pollard_kangaroo(&g, &h, &p, 0, 4000).unwrap_err()),
+            DiscreteLogError::NotFound
+        );
+        assert_eq!(baby_step_giant_step(&g, &h, &p, 5001).unwrap(), 5000);
+    }
+}
@@ -0,0 +1,205 @@
+//! An on-disk cache of factorizations.
+//!
+//! Each entry is a file named after the SHA-256 of the number, listing its
+//! unique prime factors in decimal, one per line. Entries are parsed and
+//! checked with `check_factors` when read, and any failure counts as a miss,
+//! so a stale or corrupted file is factored again and overwritten rather than
+//! trusted.
+
+use std::{
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+use rand_core::RngCore;
+use sha2::{Digest, Sha256};
+use zkper_integer::{traits::ZkperIntegerTrait, ZkperInteger};
+use zkper_rand::ZkperRng;
+
+use super::{check_factors, find_generator_with_factors, get_factors_with_rng, FACTOR_SEED};
+
+/// Prefix of the hashed number, so the keys of other caches sharing the
+/// directory cannot collide with ours.
+const KEY_TAG: &[u8] = b"zkper-factors-v1";
+
+/// A directory of factorizations, see the module documentation.
+#[derive(Clone, Debug)]
+pub struct FactorCache {
+    dir: PathBuf,
+}
+
+impl FactorCache {
+    /// A cache in `dir`, which is created if it does not exist.
+    pub fn new(dir: impl AsRef<Path>) -> io::Result<Self> {
+        let dir = dir.as_ref().to_path_buf();
+        fs::create_dir_all(&dir)?;
+        Ok(Self { dir })
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// The unique prime factors of `m`, from the cache if they are there and
+    /// by `get_factors_with_rng` otherwise, storing the result.
+    pub fn get_factors<T: ZkperIntegerTrait>(
+        &self,
+        m: &ZkperInteger<T>,
+        rgen: &mut (impl RngCore + ?Sized),
+    ) -> anyhow::Result<Vec<ZkperInteger<T>>> {
+        if let Some(factors) = self.get(m) {
+            return Ok(factors);
+        }
+        let factors = get_factors_with_rng(m, rgen)?;
+        self.insert(m, &factors)?;
+        Ok(factors)
+    }
+
+    /// `find_generator` with the factors of `modulus - 1` taken from the
+    /// cache.
+    pub fn find_generator<T: ZkperIntegerTrait>(
+        &self,
+        modulus: &ZkperInteger<T>,
+    ) -> anyhow::Result<ZkperInteger<T>> {
+        let phi = modulus.clone() - 1;
+        let factors = self.get_factors(&phi, &mut ZkperRng::from_seed(FACTOR_SEED))?;
+        Ok(find_generator_with_factors(modulus, &factors))
+    }
+
+    /// The cached factors of `m`, if there is a valid entry for it.
+    pub fn get<T: ZkperIntegerTrait>(&self, m: &ZkperInteger<T>) -> Option<Vec<ZkperInteger<T>>> {
+        let contents = fs::read_to_string(self.path(m)).ok()?;
+        let factors = contents
+            .lines()
+            .map(ZkperInteger::try_from_str)
+            .collect::<Result<Vec<_>, _>>()
+            .ok()?;
+        check_factors(m, &factors).ok()?;
+        Some(factors)
+    }
+
+    /// Stores `factors` as the factors of `m`, replacing any previous entry.
+    /// The entry is written next to its final path and renamed into place,
+    /// so concurrent readers never see half of it.
+    pub fn insert<T: ZkperIntegerTrait>(
+        &self,
+        m: &ZkperInteger<T>,
+        factors: &[ZkperInteger<T>],
+    ) -> io::Result<()> {
+        let path = self.path(m);
+        let tmp = path.with_extension(format!("tmp{}", std::process::id()));
+
+        let written = Self::write_entry(&tmp, factors).and_then(|()| fs::rename(&tmp, &path));
+        if written.is_err() {
+            let _ = fs::remove_file(&tmp);
+        }
+        written
+    }
+
+    fn write_entry<T: ZkperIntegerTrait>(
+        path: &Path,
+        factors: &[ZkperInteger<T>],
+    ) -> io::Result<()> {
+        let mut file = fs::File::create(path)?;
+        for factor in factors {
+            writeln!(file, "{}", factor)?;
+        }
+        file.sync_all()
+    }
+
+    fn path<T: ZkperIntegerTrait>(&self, m: &ZkperInteger<T>) -> PathBuf {
+        let digest = Sha256::new()
+            .chain_update(KEY_TAG)
+            .chain_update(m.to_string())
+            .finalize();
+        let key: String = digest.iter().map(|byte| format!("{:02x}", byte)).collect();
+        self.dir.join(key).with_extension("txt")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zkper_integer::backends::{rug_backend::RugBackend, u64_backend::U64Backend};
+
+    use super::*;
+    use crate::math::factorization::{find_generator, get_factors};
+    use zkper_rand::TestRng;
+
+    fn temp_cache(name: &str) -> FactorCache {
+        let dir = std::env::temp_dir().join(format!("zkper-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        FactorCache::new(dir).unwrap()
+    }
+
+    #[test]
+    fn test_cache_round_trip() {
+        let cache = temp_cache("factor-cache");
+        let m = ZkperInteger::<RugBackend>::from(2u64 * 1000003 * 1000033);
+        assert!(cache.get(&m).is_none());
+
+        let factors = cache.get_factors(&m, &mut TestRng::new()).unwrap();
+        assert_eq!(factors, get_factors(&m).unwrap());
+        assert_eq!(cache.get(&m), Some(factors.clone()));
+
+        // the entry does not depend on the backend
+        let word = ZkperInteger::<U64Backend>::from(2u64 * 1000003 * 1000033);
+        assert_eq!(cache.get(&word).unwrap().len(), factors.len());
+
+        fs::remove_dir_all(cache.dir()).unwrap();
+    }
+
+    #[test]
+    fn test_cache_rejects_bad_entries() {
+        let cache = temp_cache("factor-cache-bad");
+        let m = ZkperInteger::<RugBackend>::from(1000003u64 * 1000033);
+
+        // a composite "factor" and an incomplete list are both refactored
+        cache.insert(&m, std::slice::from_ref(&m)).unwrap();
+        assert!(cache.get(&m).is_none());
+        cache.insert(&m, &[ZkperInteger::from(1000003u64)]).unwrap();
+        assert!(cache.get(&m).is_none());
+        fs::write(cache.path(&m), "1000003\nnot a number\n").unwrap();
+        assert!(cache.get(&m).is_none());
+        // digits a word backend cannot hold are a miss, not a panic
+        let word = ZkperInteger::<U64Backend>::from(1000003u64 * 1000033);
+        fs::write(cache.path(&word), "99999999999999999999\n").unwrap();
+        assert!(cache.get(&word).is_none());
+
+        let factors = cache.get_factors(&m, &mut TestRng::new()).unwrap();
+        assert_eq!(factors.len(), 2);
+        assert_eq!(cache.get(&m), Some(factors));
+
+        fs::remove_dir_all(cache.dir()).unwrap();
+    }
+
+    #[test]
+    fn test_failed_insert_leaves_no_temp_file() {
+        let cache = temp_cache("factor-cache-rename");
+        let m = ZkperInteger::<RugBackend>::from(1000003u64 * 1000033);
+
+        // a directory in the way of the entry makes the rename fail
+        fs::create_dir_all(cache.path(&m).join("blocker")).unwrap();
+        assert!(cache.insert(&m, &[ZkperInteger::from(1000003u64)]).is_err());
+        assert_eq!(fs::read_dir(cache.dir()).unwrap().count(), 1);
+
+        fs::remove_dir_all(cache.dir()).unwrap();
+    }
+
+    #[test]
+    fn test_cached_generator() {
+        let cache = temp_cache("factor-cache-generator");
+        // the BLS12-381 scalar field, whose multiplicative group 7 generates
+        let modulus = ZkperInteger::<RugBackend>::from_str(
+            "52435875175126190479447740508185965837690552500527637822603658699938581184513",
+        );
+
+        let g = cache.find_generator(&modulus).unwrap();
+        assert_eq!(g, ZkperInteger::from(7u64));
+        assert_eq!(g, find_generator(&modulus));
+        assert!(cache.get(&(modulus.clone() - 1)).is_some());
+        assert_eq!(cache.find_generator(&modulus).unwrap(), g);
+
+        fs::remove_dir_all(cache.dir()).unwrap();
+    }
+}
@@ -2,7 +2,15 @@ use super::errors::ECMErrors;
 use super::point::Point;
 
 use primal::Primes;
-use std::collections::HashMap;
+use rand_core::RngCore;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+#[cfg(feature = "parallel")]
+use std::sync::atomic::AtomicUsize;
+use std::{
+    collections::HashMap,
+    sync::atomic::{AtomicBool, Ordering},
+};
 use zkper_integer::traits::ZkperIntegerTrait;
 use zkper_integer::ZkperInteger;
 use zkper_rand::ZkperRng;
@@ -41,9 +49,70 @@ pub fn ecm_one_factor<T: ZkperIntegerTrait>(
     b1: usize,
     b2: usize,
     max_curve: usize,
-    rgen: &mut ZkperRng,
+    rgen: &mut (impl RngCore + ?Sized),
 ) -> Result<ZkperInteger<T>, ECMErrors> {
-    if b1 % 2 != 0 || b2 % 2 != 0 {
+    check_bounds(n, b1, b2)?;
+
+    let k = stage_one_multiplier(b1);
+    let running = AtomicBool::new(true);
+    for _ in 0..=max_curve {
+        let sigma = (n - 1).random_below(rgen);
+        if let Some(factor) = try_curve(n, &k, b1, b2, sigma, &running) {
+            return Ok(factor);
+        }
+    }
+
+    // ECM failed, Increase the bounds
+    Err(ECMErrors::ECMFailed)
+}
+
+/// `ecm_one_factor` with the curves spread over the rayon pool.
+///
+/// Curve i takes its parameter from stream i of a `ZkperRng` seeded with
+/// `seed`, so the curves tried do not depend on the scheduling. The first
+/// factor found stops the curves still running, and `progress` is called
+/// with the number of curves finished after each one that ran to the end.
+#[cfg(feature = "parallel")]
+pub fn ecm_one_factor_parallel<T, F>(
+    n: &ZkperInteger<T>,
+    b1: usize,
+    b2: usize,
+    max_curve: usize,
+    seed: u64,
+    progress: F,
+) -> Result<ZkperInteger<T>, ECMErrors>
+where
+    T: ZkperIntegerTrait + Send + Sync,
+    F: Fn(usize) + Sync,
+{
+    check_bounds(n, b1, b2)?;
+
+    let k = stage_one_multiplier(b1);
+    let rgen = ZkperRng::from_seed(seed);
+    let running = AtomicBool::new(true);
+    let finished = AtomicUsize::new(0);
+
+    (0..=max_curve as u64)
+        .into_par_iter()
+        .find_map_any(|curve| {
+            let sigma = (n - 1).random_below(&mut rgen.split(curve));
+            let factor = try_curve(n, &k, b1, b2, sigma, &running);
+            if factor.is_some() {
+                running.store(false, Ordering::Relaxed);
+            } else if running.load(Ordering::Relaxed) {
+                progress(finished.fetch_add(1, Ordering::Relaxed) + 1);
+            }
+            factor
+        })
+        .ok_or(ECMErrors::ECMFailed)
+}
+
+fn check_bounds<T: ZkperIntegerTrait>(
+    n: &ZkperInteger<T>,
+    b1: usize,
+    b2: usize,
+) -> Result<(), ECMErrors> {
+    if !b1.is_multiple_of(2) || !b2.is_multiple_of(2) {
         return Err(ECMErrors::BoundsNotEven);
     }
 
@@ -51,97 +120,110 @@ pub fn ecm_one_factor<T: ZkperIntegerTrait>(
         return Err(ECMErrors::NumberIsPrime);
     }
 
-    let mut curve = 0;
-    let d = (b2 as f64).sqrt() as usize;
-    let two_d = 2 * d;
-    let mut beta = vec![ZkperInteger::default(); d + 1];
-    let mut s: Vec<Point<T>> = vec![Point::<T>::default(); d + 1];
-    let mut k = ZkperInteger::one();
-    let three = ZkperInteger::three();
+    Ok(())
+}
 
+/// The product of the prime powers up to `b1`, the stage 1 scalar.
+fn stage_one_multiplier<T: ZkperIntegerTrait>(b1: usize) -> ZkperInteger<T> {
+    let mut k = ZkperInteger::one();
     for p in Primes::all().take_while(|&p| p <= b1) {
         k *= p.pow(b1.ilog(p));
     }
+    k
+}
 
-    while curve <= max_curve {
-        curve += 1;
-
-        // Suyama's Parametrization
-        let sigma = (n - 1).random_below(rgen);
-        let u = (&sigma * &sigma - ZkperInteger::from(5)) % n;
-        let v = (sigma * 4) % n;
-        let diff = &v - &u;
-        let u_3 = u.clone().pow_mod(&three, n);
-        let v_3 = v.clone().pow_mod(&three, n);
-
-        let c = match (ZkperInteger::four() * &u_3 * &v).invert(n) {
-            Ok(c) => {
-                (diff.pow_mod(&three, n) * (ZkperInteger::four() * &u + &v) * c
-                    - ZkperInteger::two())
-                    % n
-            }
-            _ => return Ok((ZkperInteger::four() * u_3 * v).gcd(n)),
-        };
+/// Both stages on the curve of Suyama parameter `sigma`. None when the curve
+/// finds no factor, or gives up early once `running` is cleared.
+fn try_curve<T: ZkperIntegerTrait>(
+    n: &ZkperInteger<T>,
+    k: &ZkperInteger<T>,
+    b1: usize,
+    b2: usize,
+    sigma: ZkperInteger<T>,
+    running: &AtomicBool,
+) -> Option<ZkperInteger<T>> {
+    if !running.load(Ordering::Relaxed) {
+        return None;
+    }
 
-        let a24 = (c + 2) * ZkperInteger::four().invert(n).unwrap() % n;
-        let q = Point::new(u_3, v_3, a24, n.clone());
-        let q = q.mont_ladder(&k);
-        let g = q.z_cord.clone().gcd(n);
+    let d = (b2 as f64).sqrt() as usize;
+    let two_d = 2 * d;
+    let three = ZkperInteger::three();
 
-        // Stage 1 factor
-        if &g != n && g.is_not_one() {
-            return Ok(g);
+    // Suyama's Parametrization
+    let u = (&sigma * &sigma - ZkperInteger::from(5)) % n;
+    let v = (sigma * 4) % n;
+    let diff = &v - &u;
+    let u_3 = u.clone().pow_mod(&three, n);
+    let v_3 = v.clone().pow_mod(&three, n);
+
+    let c = match (ZkperInteger::four() * &u_3 * &v).invert(n) {
+        Ok(c) => {
+            (diff.pow_mod(&three, n) * (ZkperInteger::four() * &u + &v) * c - ZkperInteger::two())
+                % n
         }
+        _ => return Some((ZkperInteger::four() * u_3 * v).gcd(n)),
+    };
 
-        // Stage 1 failure. Q.z = 0, Try another curve
-        if &g == n {
-            continue;
-        }
+    let a24 = (c + 2) * ZkperInteger::four().invert(n).unwrap() % n;
+    let q = Point::new(u_3, v_3, a24, n.clone());
+    let q = q.mont_ladder(k);
+    let g = q.z_cord.clone().gcd(n);
 
-        // Stage 2 - Improved Standard Continuation
-        s[1] = q.double();
-        s[2] = s[1].double();
-        beta[1] = (&s[1].x_cord * &s[1].z_cord) % n;
-        beta[2] = (&s[2].x_cord * &s[2].z_cord) % n;
+    // Stage 1 factor
+    if &g != n && g.is_not_one() {
+        return Some(g);
+    }
 
-        for d in 3..=(d) {
-            s[d] = s[d - 1].add(&s[1], &s[d - 2]);
-            beta[d] = (&s[d].x_cord * &s[d].z_cord) % n;
-        }
+    // Stage 1 failure. Q.z = 0, Try another curve
+    if &g == n || !running.load(Ordering::Relaxed) {
+        return None;
+    }
 
-        let mut g = ZkperInteger::one();
-        let b = b1 - 1;
-        let mut t = q.mont_ladder(&ZkperInteger::from(b - two_d));
-        let mut r = q.mont_ladder(&ZkperInteger::from(b));
-
-        let mut primes = Primes::all().skip_while(|&q| q < b);
-        for rr in (b..b2).step_by(two_d) {
-            let alpha = (&r.x_cord * &r.z_cord) % n;
-            for q in primes.by_ref().take_while(|&q| q <= rr + two_d) {
-                let delta = (q - rr) / 2;
-                let f =
-                    (&r.x_cord - &s[d].x_cord) * (&r.z_cord + &s[d].z_cord) - &alpha + &beta[delta];
-                g = (g * f) % n;
-            }
-            // Swap
-            std::mem::swap(&mut t, &mut r);
-            r = r.add(&s[d], &t);
-        }
-        g = g.gcd(n);
+    // Stage 2 - Improved Standard Continuation
+    let mut beta = vec![ZkperInteger::default(); d + 1];
+    let mut s: Vec<Point<T>> = vec![Point::<T>::default(); d + 1];
+    s[1] = q.double();
+    s[2] = s[1].double();
+    beta[1] = (&s[1].x_cord * &s[1].z_cord) % n;
+    beta[2] = (&s[2].x_cord * &s[2].z_cord) % n;
+
+    for d in 3..=(d) {
+        s[d] = s[d - 1].add(&s[1], &s[d - 2]);
+        beta[d] = (&s[d].x_cord * &s[d].z_cord) % n;
+    }
 
-        // Stage 2 Factor found
-        if &g != n && g.is_not_one() {
-            return Ok(g);
+    let mut g = ZkperInteger::one();
+    let b = b1 - 1;
+    let mut t = q.mont_ladder(&ZkperInteger::from(b - two_d));
+    let mut r = q.mont_ladder(&ZkperInteger::from(b));
+
+    let mut primes = Primes::all().skip_while(|&q| q < b);
+    for rr in (b..b2).step_by(two_d) {
+        if !running.load(Ordering::Relaxed) {
+            return None;
+        }
+        let alpha = (&r.x_cord * &r.z_cord) % n;
+        for q in primes.by_ref().take_while(|&q| q <= rr + two_d) {
+            let delta = (q - rr) / 2;
+            let f = (&r.x_cord - &s[d].x_cord) * (&r.z_cord + &s[d].z_cord) - &alpha + &beta[delta];
+            g = (g * f) % n;
         }
+        // Swap
+        std::mem::swap(&mut t, &mut r);
+        r = r.add(&s[d], &t);
     }
+    g = g.gcd(n);
 
-    // ECM failed, Increase the bounds
-    Err(ECMErrors::ECMFailed)
+    // Stage 2 Factor found
+    (&g != n && g.is_not_one()).then_some(g)
 }
 
 /// Optimal params retrieved from <https://gitlab.inria.fr/zimmerma/ecm>
+///
+/// On 32-bit targets the bounds are capped at the largest even usize.
 pub fn optimal_params(digits: usize) -> (usize, usize, usize) {
-    match digits {
+    let (b1, b2, max_curve): (u64, u64, usize) = match digits {
         1..=10 => (2_000, 160_000, 35),
         11..=15 => (5_000, 500_000, 500),
         16..=20 => (11_000, 1_900_000, 74),
@@ -154,7 +236,10 @@ pub fn optimal_params(digits: usize) -> (usize, usize, usize) {
         51..=55 => (110_000_000, 780_000_000_000, 17769),
         56..=60 => (260_000_000, 3_200_000_000_000, 42017),
         _ => (850_000_000, 16_000_000_000_000, 69408),
-    }
+    };
+
+    let cap = |bound: u64| usize::try_from(bound).unwrap_or(usize::MAX & !1);
+    (cap(b1), cap(b2), max_curve)
 }
 
 /// Performs factorization using Lenstra's Elliptic curve method.
@@ -239,6 +324,47 @@ mod tests {
         super::ecm(n)
     }
 
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn parallel_one_factor() {
+        let n = ZkperInteger::<RugBackend>::from(1000003u64 * 1000033);
+        let finished = AtomicUsize::new(0);
+        let factor = ecm_one_factor_parallel(&n, 2_000, 50_000, 50, 1234, |count| {
+            finished.fetch_max(count, Ordering::Relaxed);
+        })
+        .unwrap();
+        assert!(
+            factor == ZkperInteger::from(1000003u64) || factor == ZkperInteger::from(1000033u64)
+        );
+        assert!(finished.load(Ordering::Relaxed) <= 50);
+
+        // one thread draws every curve from a single stream instead of one
+        // stream per curve, so it tries other curves, but finds a factor too
+        let sequential =
+            ecm_one_factor(&n, 2_000, 50_000, 50, &mut ZkperRng::from_seed(1234)).unwrap();
+        assert!((n.clone() % sequential).is_zero());
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn parallel_reports_every_curve() {
+        // bounds far too small for 10-digit factors: every curve runs out
+        let n =
+            ZkperInteger::<RugBackend>::from(9312934919u64) * ZkperInteger::from(67777885039u64);
+        let finished = AtomicUsize::new(0);
+        let result = ecm_one_factor_parallel(&n, 10, 20, 15, 1234, |_| {
+            finished.fetch_add(1, Ordering::Relaxed);
+        });
+        assert!(matches!(result, Err(ECMErrors::ECMFailed)));
+        assert_eq!(finished.load(Ordering::Relaxed), 16);
+
+        let prime = ZkperInteger::<RugBackend>::from(67777885039u64);
+        let result = ecm_one_factor_parallel(&prime, 10, 20, 15, 1234, |_| {});
+        assert!(matches!(result, Err(ECMErrors::NumberIsPrime)));
+        let result = ecm_one_factor_parallel(&n, 11, 20, 15, 1234, |_| {});
+        assert!(matches!(result, Err(ECMErrors::BoundsNotEven)));
+    }
+
     #[test]
     fn sympy_1() {
         assert_eq!(
@@ -1,30 +1,73 @@
 // ref: https://github.com/skyf0l/ecm-rs/blob/main/src/ecm.rs
 
-use rand::{rngs::OsRng, Rng};
+use rand_core::RngCore;
 use zkper_integer::{traits::ZkperIntegerTrait, ZkperInteger};
 use zkper_rand::ZkperRng;
 
 use self::ecm::ecm_one_factor;
+#[cfg(feature = "parallel")]
+use self::ecm::ecm_one_factor_parallel;
+use super::errors::FactorizationError;
 
+#[allow(clippy::module_inception)]
 pub mod ecm;
 pub mod errors;
 pub mod point;
 
+/// Seed of the curves tried by `get_factor_ecm`.
+pub const ECM_SEED: u64 = 1234;
+
 /// Elliptic Curve Method (ECM) for factorization.
 /// This function attempts to find a single factor of the input number.
 pub fn get_factor_ecm<T: ZkperIntegerTrait>(
     n: &ZkperInteger<T>,
 ) -> anyhow::Result<ZkperInteger<T>> {
-    let digits = n.to_string().len();
+    get_factor_ecm_up_to(n, n.to_string().len())
+}
+
+/// `get_factor_ecm` with the bounds and curve count for factors of up to
+/// `digits` digits, instead of those for the whole of n.
+pub fn get_factor_ecm_up_to<T: ZkperIntegerTrait>(
+    n: &ZkperInteger<T>,
+    digits: usize,
+) -> anyhow::Result<ZkperInteger<T>> {
+    // the curves only have to be varied, not secret: a fixed seed keeps the
+    // factorization reproducible and off the OS entropy source
+    get_factor_ecm_with_rng(n, digits, &mut ZkperRng::from_seed(ECM_SEED))
+}
+
+/// `get_factor_ecm_up_to` with the curves drawn from `rgen`.
+pub fn get_factor_ecm_with_rng<T: ZkperIntegerTrait>(
+    n: &ZkperInteger<T>,
+    digits: usize,
+    rgen: &mut (impl RngCore + ?Sized),
+) -> anyhow::Result<ZkperInteger<T>> {
     let (b1, b2, max_curve) = ecm::optimal_params(digits);
 
-    // rgen.seed(&Integer::from(1234)); // You can change this seed if needed
-    let mut csprng = OsRng;
-    let mut rgen = ZkperRng::from_seed(csprng.gen::<u64>());
+    match ecm_one_factor(n, b1, b2, max_curve, rgen) {
+        Ok(factor) => Ok(factor),
+        Err(errors::ECMErrors::NumberIsPrime) => Ok(n.clone()),
+        Err(e) => Err(FactorizationError::from(e).into()),
+    }
+}
+
+/// `get_factor_ecm` with the curves run in parallel, calling `progress` with
+/// the number of curves finished so far.
+#[cfg(feature = "parallel")]
+pub fn get_factor_ecm_parallel<T, F>(
+    n: &ZkperInteger<T>,
+    progress: F,
+) -> anyhow::Result<ZkperInteger<T>>
+where
+    T: ZkperIntegerTrait + Send + Sync,
+    F: Fn(usize) + Sync,
+{
+    let digits = n.to_string().len();
+    let (b1, b2, max_curve) = ecm::optimal_params(digits);
 
-    match ecm_one_factor(n, b1, b2, max_curve, &mut rgen) {
+    match ecm_one_factor_parallel(n, b1, b2, max_curve, ECM_SEED, progress) {
         Ok(factor) => Ok(factor),
         Err(errors::ECMErrors::NumberIsPrime) => Ok(n.clone()),
-        Err(e) => Err(e.into()),
+        Err(e) => Err(FactorizationError::from(e).into()),
     }
 }
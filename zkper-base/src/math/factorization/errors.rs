@@ -0,0 +1,26 @@
+use thiserror::Error;
+
+use super::ecm::errors::ECMErrors;
+
+/// Errors of the factorization utilities.
+#[derive(Error, Debug)]
+pub enum FactorizationError {
+    /// A factor in the list is not prime.
+    #[error("Composite factor found")]
+    CompositeFactor,
+    /// The factors do not cover every prime of the number.
+    #[error("Incomplete factor list")]
+    IncompleteFactors,
+    /// The number is below 4, so it has no nontrivial factor.
+    #[error("Numbers below 4 have no nontrivial factor")]
+    TooSmall,
+    /// Pollard's rho spent its iteration budget without finding a factor.
+    #[error("Pollard's rho found no factor within {0} iterations")]
+    RhoBudgetExhausted(u64),
+    /// None of the quadratic sieve's dependencies split the number.
+    #[error("The quadratic sieve found no factor")]
+    QsFailed,
+    /// ECM could not split the number.
+    #[error(transparent)]
+    Ecm(#[from] ECMErrors),
+}
@@ -1,10 +1,30 @@
+pub mod cache;
 pub mod ecm;
+pub mod errors;
 pub mod pollards_rho;
+pub mod qs;
 pub mod traits;
-use std::ops::Neg;
+use rand_core::RngCore;
 use zkper_integer::{traits::ZkperIntegerTrait, ZkperInteger};
+use zkper_rand::ZkperRng;
 
-use self::{ecm::get_factor_ecm, pollards_rho::get_factor_pollard_rho};
+use self::{
+    ecm::{get_factor_ecm_up_to, get_factor_ecm_with_rng},
+    errors::FactorizationError,
+    pollards_rho::{pollard_rho_brent, RHO_ITERATIONS},
+    qs::{get_factor_qs, get_factor_qs_with_rng},
+};
+
+/// Seed of the generator `get_factors` hands to rho, ECM and the sieve.
+pub const FACTOR_SEED: u64 = 2468;
+
+/// From this many digits on, ECM only looks for factors of up to
+/// `ECM_DIGITS_BEFORE_QS` digits before the quadratic sieve takes over; its
+/// full bounds would take longer than sieving.
+pub const QS_DIGITS: usize = 60;
+
+/// See `QS_DIGITS`.
+pub const ECM_DIGITS_BEFORE_QS: usize = 25;
 
 /// Checks that the given list of factors contains all the unique primes of m.
 pub fn check_factors<T: ZkperIntegerTrait>(
@@ -14,7 +34,7 @@ pub fn check_factors<T: ZkperIntegerTrait>(
     let mut remaining = m.clone();
     for factor in factors {
         if !factor.is_prime() {
-            return Err(anyhow::anyhow!("Composite factor found"));
+            return Err(FactorizationError::CompositeFactor.into());
         }
         while (&remaining % factor).is_zero() {
             remaining /= factor;
@@ -23,21 +43,31 @@ pub fn check_factors<T: ZkperIntegerTrait>(
     if remaining.is_one() {
         Ok(())
     } else {
-        Err(anyhow::anyhow!("Incomplete factor list"))
+        Err(FactorizationError::IncompleteFactors.into())
     }
 }
 
+/// A generator of the multiplicative group of the prime `modulus`: the first
+/// of 2, p - 2, 3, p - 3, ... up to 20 that is a primitive root.
 pub fn find_generator<T: ZkperIntegerTrait>(modulus: &ZkperInteger<T>) -> ZkperInteger<T> {
+    let factors = get_factors(&(modulus.clone() - 1)).unwrap();
+    find_generator_with_factors(modulus, &factors)
+}
+
+/// `find_generator` with the unique prime factors of `modulus - 1` given.
+pub fn find_generator_with_factors<T: ZkperIntegerTrait>(
+    modulus: &ZkperInteger<T>,
+    factors: &[ZkperInteger<T>],
+) -> ZkperInteger<T> {
     let phi = modulus.clone() - 1;
-    let factors = get_factors(&phi).unwrap();
 
     for a in 2..=20usize {
         let candidate = ZkperInteger::from(a);
-        if is_primitive_root(&candidate, modulus, &phi, &factors) {
+        if is_primitive_root(&candidate, modulus, &phi, factors) {
             return candidate;
         }
-        let neg_candidate = candidate.neg();
-        if is_primitive_root(&neg_candidate, modulus, &phi, &factors) {
+        let neg_candidate = candidate.neg_mod(modulus);
+        if is_primitive_root(&neg_candidate, modulus, &phi, factors) {
             return neg_candidate;
         }
     }
@@ -95,6 +125,16 @@ pub fn primitive_root<T: ZkperIntegerTrait>(
 /// Returns a sorted vector of prime factors.
 pub fn get_factors<T: ZkperIntegerTrait>(
     m: &ZkperInteger<T>,
+) -> anyhow::Result<Vec<ZkperInteger<T>>> {
+    get_factors_with_rng(m, &mut ZkperRng::from_seed(FACTOR_SEED))
+}
+
+/// `get_factors` with every random choice of rho, ECM and the quadratic
+/// sieve drawn from `rgen`, so the same generator state always takes the
+/// same path to the factors.
+pub fn get_factors_with_rng<T: ZkperIntegerTrait>(
+    m: &ZkperInteger<T>,
+    rgen: &mut (impl RngCore + ?Sized),
 ) -> anyhow::Result<Vec<ZkperInteger<T>>> {
     let mut m_cpy = m.clone();
     if m_cpy.is_prime() {
@@ -123,20 +163,25 @@ pub fn get_factors<T: ZkperIntegerTrait>(
             break;
         }
 
-        // Try Pollard's Rho algorithm first
-        let mut factor: ZkperInteger<T> = get_factor_pollard_rho(&m_cpy);
-        if factor.is_one() || factor == m_cpy {
-            // If Pollard's Rho fails, try ECM factorization
-            factor = get_factor_ecm(&m_cpy)?;
-        }
+        // Try Pollard's Rho algorithm first, then ECM and the quadratic sieve
+        let factor = match pollard_rho_brent(&m_cpy, RHO_ITERATIONS, rgen) {
+            Ok(factor) => factor,
+            Err(_) => get_factor_ecm_qs_with_rng(&m_cpy, rgen)?,
+        };
+        // the methods may split off a composite divisor
+        let primes = if factor.is_prime() {
+            vec![factor]
+        } else {
+            get_factors_with_rng(&factor, rgen)?
+        };
 
-        // Remove all instances of this factor from m_cpy
-        let temp = &m_cpy % &factor;
-        while temp.is_zero() {
-            m_cpy /= &factor;
+        // Remove all instances of these factors from m_cpy
+        for factor in primes {
+            while (&m_cpy % &factor).is_zero() {
+                m_cpy /= &factor;
+            }
+            f.insert(factor);
         }
-
-        f.insert(factor);
     }
 
     // Convert the set of factors to a sorted vector
@@ -145,6 +190,44 @@ pub fn get_factors<T: ZkperIntegerTrait>(
     Ok(factors)
 }
 
+/// A factor of the composite `m` by ECM, and by the quadratic sieve if ECM
+/// finds none.
+pub fn get_factor_ecm_qs<T: ZkperIntegerTrait>(
+    m: &ZkperInteger<T>,
+) -> anyhow::Result<ZkperInteger<T>> {
+    get_factor_ecm_up_to(m, ecm_digits(m)).or_else(|_| get_factor_qs(m))
+}
+
+/// `get_factor_ecm_qs` with the curves and polynomials drawn from `rgen`.
+pub fn get_factor_ecm_qs_with_rng<T: ZkperIntegerTrait>(
+    m: &ZkperInteger<T>,
+    rgen: &mut (impl RngCore + ?Sized),
+) -> anyhow::Result<ZkperInteger<T>> {
+    get_factor_ecm_with_rng(m, ecm_digits(m), rgen).or_else(|_| get_factor_qs_with_rng(m, rgen))
+}
+
+/// The factor size ECM looks for in m before the quadratic sieve runs.
+fn ecm_digits<T: ZkperIntegerTrait>(m: &ZkperInteger<T>) -> usize {
+    let digits = m.to_string().len();
+    if digits >= QS_DIGITS {
+        ECM_DIGITS_BEFORE_QS
+    } else {
+        digits
+    }
+}
+
+/// |x - y| without going below zero, for unsigned backends.
+pub(crate) fn abs_diff<T: ZkperIntegerTrait>(
+    x: &ZkperInteger<T>,
+    y: &ZkperInteger<T>,
+) -> ZkperInteger<T> {
+    if x > y {
+        x - y
+    } else {
+        y - x
+    }
+}
+
 /// Checks if the given factors completely factorize the input number.
 ///
 /// # Arguments
@@ -172,7 +255,9 @@ pub fn check_factorization<T: ZkperIntegerTrait>(
 
 #[cfg(test)]
 mod test {
-    use zkper_integer::backends::rug_backend::RugBackend;
+    use zkper_integer::backends::{
+        rug_backend::RugBackend, u128_backend::U128Backend, u64_backend::U64Backend,
+    };
 
     use super::*;
 
@@ -190,4 +275,48 @@ mod test {
         println!("m: {} {:?}", m, factors);
         assert!(check_factorization(&m, &factors));
     }
+
+    #[test]
+    fn test_get_factors_with_rng() {
+        // 1000000007 * 1000000009, past the trial division primes
+        let m = ZkperInteger::<RugBackend>::from_str("1000000016000000063")
+            * ZkperInteger::from(2u64 * 3 * 0x1fffffffffe00001u64);
+        let factors = get_factors(&m).unwrap();
+        assert!(check_factorization(&m, &factors));
+
+        for seed in [0, 1, 99] {
+            let run = || get_factors_with_rng(&m, &mut ZkperRng::from_seed(seed)).unwrap();
+            assert_eq!(run(), factors);
+            assert_eq!(run(), run());
+        }
+    }
+
+    #[test]
+    fn test_get_factors_composite_split() {
+        // rho splits off 1000033 * 125527 in one piece
+        let m = ZkperInteger::<RugBackend>::from(1000003u64 * 1000033 * 125527);
+        let factors = get_factors(&m).unwrap();
+        check_factors(&m, &factors).unwrap();
+        assert_eq!(factors.len(), 3);
+    }
+
+    #[test]
+    fn test_get_factors_word_backends() {
+        let q = 0x1fffffffffe00001u64;
+        let rug_factors = get_factors(&ZkperInteger::<RugBackend>::from(q - 1)).unwrap();
+
+        let m = ZkperInteger::<U64Backend>::from(q - 1);
+        let factors = get_factors(&m).unwrap();
+        assert!(check_factorization(&m, &factors));
+        assert_eq!(factors.len(), rug_factors.len());
+        let (g, _) = primitive_root(&ZkperInteger::<U64Backend>::from(q), Some(factors)).unwrap();
+        let (rug_g, _) = primitive_root(&ZkperInteger::<RugBackend>::from(q), None).unwrap();
+        assert_eq!(g.to_string(), rug_g.to_string());
+
+        // a large prime factor left after trial division, found by rho
+        let m = ZkperInteger::<U128Backend>::from(2u64 * 1000003 * 1000033);
+        let factors = get_factors(&m).unwrap();
+        assert!(check_factorization(&m, &factors));
+        assert_eq!(factors.len(), 3);
+    }
 }
@@ -1,35 +1,100 @@
+use rand_core::RngCore;
+use zkper_rand::ZkperRng;
+
 use super::*;
 
-// Implements Pollard's Rho algorithm for factorization using rug::Integer.
-/// This function attempts to find a single factor of the input number.
-pub fn get_factor_pollard_rho<T: ZkperIntegerTrait>(m: &ZkperInteger<T>) -> ZkperInteger<T> {
+/// Seed of the polynomials tried by `get_factor_pollard_rho`.
+pub const RHO_SEED: u64 = 5678;
+
+/// Polynomial evaluations `get_factor_pollard_rho` spends before giving up,
+/// enough for factors up to about 40 bits.
+pub const RHO_ITERATIONS: u64 = 1 << 20;
+
+/// Differences multiplied together before taking one gcd.
+const GCD_BATCH: u64 = 128;
+
+/// Pollard's rho with the default seed and budget.
+/// This function attempts to find a single factor of the input number, and
+/// returns the number itself when it is prime.
+pub fn get_factor_pollard_rho<T: ZkperIntegerTrait>(
+    m: &ZkperInteger<T>,
+) -> anyhow::Result<ZkperInteger<T>> {
     if m.is_prime() {
-        return m.clone();
+        return Ok(m.clone());
     }
 
-    // Try different values of c in the polynomial x^2 + c
-    for i in 1..10usize {
-        let mut x = ZkperInteger::two();
-        let mut y = ZkperInteger::two();
-        let mut d = ZkperInteger::one();
+    // as for ECM, a fixed seed keeps the factorization reproducible
+    let mut rgen = ZkperRng::from_seed(RHO_SEED);
+    Ok(pollard_rho_brent(m, RHO_ITERATIONS, &mut rgen)?)
+}
 
-        let c = ZkperInteger::from(i);
+/// Brent's variant of Pollard's rho: a nontrivial factor of the composite
+/// `n`, trying polynomials x^2 + c with random c and starting points until
+/// `max_iterations` evaluations are spent.
+pub fn pollard_rho_brent<T: ZkperIntegerTrait>(
+    n: &ZkperInteger<T>,
+    max_iterations: u64,
+    rgen: &mut (impl RngCore + ?Sized),
+) -> Result<ZkperInteger<T>, FactorizationError> {
+    // the smallest composite is 4, and n - 3 must not underflow below
+    if n < &ZkperInteger::four() {
+        return Err(FactorizationError::TooSmall);
+    }
 
-        while d.is_zero() || &d == m {
-            // "Tortoise and hare" step
-            x = polynomial_pollards_rho(&x, &c, m);
-            y = polynomial_pollards_rho(&polynomial_pollards_rho(&y, &c, m), &c, m);
+    let mut iterations = 0;
+    while iterations < max_iterations {
+        // c in [1, n - 3], skipping 0 and -2 whose orbits are not random
+        let c = (n - 3u64).random_below(rgen) + 1u64;
+        let mut y = n.random_below(rgen);
+        let mut x = y.clone();
+        let mut ys = y.clone();
+        let mut q = ZkperInteger::one();
+        let mut g = ZkperInteger::one();
 
-            d = &x - &y;
-            d = d.abs().gcd(m);
+        // x waits at y's position after 1, 2, 4, ... steps while y walks
+        // on, and the differences of a batch share one gcd
+        let mut r = 1;
+        while g.is_one() && iterations < max_iterations {
+            x = y.clone();
+            for _ in 0..r {
+                y = polynomial_pollards_rho(&y, &c, n);
+            }
+            iterations += r;
 
-            if d.is_not_one() {
-                return d;
+            let mut k = 0;
+            while k < r && g.is_one() && iterations < max_iterations {
+                ys = y.clone();
+                let batch = GCD_BATCH.min(r - k);
+                for _ in 0..batch {
+                    y = polynomial_pollards_rho(&y, &c, n);
+                    q = (q * abs_diff(&x, &y)) % n;
+                }
+                g = q.gcd(n);
+                k += batch;
+                iterations += batch;
             }
+            r *= 2;
+        }
+
+        // the batch hit every prime of n at once: redo it one difference at
+        // a time, which stops within the batch
+        if &g == n {
+            loop {
+                ys = polynomial_pollards_rho(&ys, &c, n);
+                g = abs_diff(&x, &ys).gcd(n);
+                if g.is_not_one() {
+                    break;
+                }
+            }
+        }
+
+        // the cycle closed on n itself: retry with the next c
+        if g.is_not_one() && &g != n {
+            return Ok(g);
         }
     }
 
-    ZkperInteger::one() // Return 1 if no factor is found
+    Err(FactorizationError::RhoBudgetExhausted(max_iterations))
 }
 
 /// Polynomial function used in Pollard's Rho algorithm: f(x) = x^2 + c mod n
@@ -44,12 +109,80 @@ fn polynomial_pollards_rho<T: ZkperIntegerTrait>(
 #[cfg(test)]
 mod test {
     use super::*;
-    use zkper_integer::backends::rug_backend::RugBackend;
+    use zkper_integer::backends::{
+        rug_backend::RugBackend, u128_backend::U128Backend, u64_backend::U64Backend,
+    };
+    use zkper_rand::TestRng;
 
     #[test]
     fn test_get_factor_pollard_rho() {
         let prime = 0x1fffffffffe00001u64;
         let m = ZkperInteger::<RugBackend>::from(prime - 1);
-        assert_eq!(m.clone() % get_factor_pollard_rho(&m), ZkperInteger::zero());
+        let factor = get_factor_pollard_rho(&m).unwrap();
+        assert!(factor.is_not_one() && factor != m);
+        assert_eq!(m.clone() % factor, ZkperInteger::zero());
+
+        let m = ZkperInteger::<RugBackend>::from(prime);
+        assert_eq!(get_factor_pollard_rho(&m).unwrap(), m);
+    }
+
+    #[test]
+    fn test_get_factor_pollard_rho_word_backends() {
+        // x^2 has to fit the word, so the u64 backend takes a 27-bit m
+        let m = ZkperInteger::<U64Backend>::from(10007u64 * 10009);
+        let factor = get_factor_pollard_rho(&m).unwrap();
+        assert!(factor.is_not_one() && factor != m);
+        assert!((m % factor).is_zero());
+
+        let m = ZkperInteger::<U128Backend>::from(1000003u64 * 1000033);
+        let factor = get_factor_pollard_rho(&m).unwrap();
+        assert!(factor.is_not_one() && factor != m);
+        assert!((m % factor).is_zero());
+    }
+
+    #[test]
+    fn test_pollard_rho_brent() {
+        // two 31-bit primes, about 2^16 iterations away
+        let (p, q) = (2147483647u64, 2147483629u64);
+        let m = ZkperInteger::<RugBackend>::from(p) * ZkperInteger::from(q);
+        for seed in 0..4 {
+            let factor =
+                pollard_rho_brent(&m, RHO_ITERATIONS, &mut TestRng::from_seed(seed)).unwrap();
+            assert!(factor == ZkperInteger::from(p) || factor == ZkperInteger::from(q));
+        }
+
+        // a square, where both sides of the cycle meet at the same prime
+        let m = ZkperInteger::<RugBackend>::from(1000003u64 * 1000003);
+        let factor = pollard_rho_brent(&m, RHO_ITERATIONS, &mut TestRng::new()).unwrap();
+        assert_eq!(factor, ZkperInteger::from(1000003u64));
+    }
+
+    #[test]
+    fn test_pollard_rho_budget() {
+        // two 61-bit primes are far out of reach of a thousand iterations
+        let p = ZkperInteger::<RugBackend>::from((1u64 << 61) - 1);
+        let q = ZkperInteger::<RugBackend>::from(0x1fffffffffe00001u64);
+        let m = p * q;
+        let result = pollard_rho_brent(&m, 1000, &mut TestRng::new());
+        assert!(matches!(
+            result,
+            Err(FactorizationError::RhoBudgetExhausted(1000))
+        ));
+    }
+
+    #[test]
+    fn test_pollard_rho_small_inputs() {
+        // n - 3 would underflow on the unsigned backends
+        for n in 0..4u64 {
+            let result = pollard_rho_brent(
+                &ZkperInteger::<U64Backend>::from(n),
+                RHO_ITERATIONS,
+                &mut TestRng::new(),
+            );
+            assert!(matches!(result, Err(FactorizationError::TooSmall)));
+        }
+        let four = ZkperInteger::<U64Backend>::from(4u64);
+        let factor = pollard_rho_brent(&four, RHO_ITERATIONS, &mut TestRng::new());
+        assert_eq!(factor.unwrap(), ZkperInteger::two());
     }
 }
@@ -0,0 +1,618 @@
+//! The self-initializing quadratic sieve (SIQS). `get_factors` runs it on
+//! cofactors of `QS_DIGITS` digits and more, after a short ECM pass for small
+//! factors, and on smaller ones only when ECM with full bounds finds nothing.
+//!
+//! A relation is (ax + b)^2 ≡ Q(x) (mod n) with Q(x) = (ax + b)^2 - n
+//! smooth over the factor base, the primes p for which n is a square mod p.
+//! The leading coefficient a is a product of factor base primes close to
+//! sqrt(2n) / M, which keeps |Q(x)| / a below M sqrt(n / 2) on the sieve
+//! interval [-M, M], and each a gives 2^(s - 1) values of b stepped through
+//! in Gray code order. Once there are more relations than factor base
+//! primes, Gaussian elimination over GF(2) finds subsets whose Q(x) multiply
+//! to a square Y^2, and each subset splits n through gcd(X - Y, n), X the
+//! product of their ax + b, with probability one half.
+
+use std::collections::HashSet;
+
+use primal::Primes;
+use rand_core::RngCore;
+use zkper_integer::{traits::ZkperIntegerTrait, ZkperInteger};
+use zkper_rand::ZkperRng;
+
+use super::{abs_diff, errors::FactorizationError};
+
+/// Seed of the leading coefficients tried by `get_factor_qs`.
+pub const QS_SEED: u64 = 4321;
+
+/// Relations gathered beyond the factor base size; each extra one is another
+/// dependency, and another even chance of a factor.
+const EXTRA_RELATIONS: usize = 16;
+
+/// Primes below this are not sieved, the threshold leaves room for them.
+const SIEVE_MIN_PRIME: u64 = 30;
+
+/// Size of the primes a is ideally built from.
+const A_PRIME_SIZE: f64 = 2000.0;
+
+/// Leading coefficients tried in a row without finding a new one before
+/// giving up.
+const MAX_A_RETRIES: usize = 1000;
+
+/// Factor base size and sieve half-width M for an n of `digits` digits.
+pub fn qs_params(digits: usize) -> (usize, usize) {
+    match digits {
+        0..=20 => (60, 4_096),
+        21..=26 => (120, 8_192),
+        27..=32 => (200, 16_384),
+        33..=38 => (350, 32_768),
+        39..=44 => (600, 32_768),
+        45..=50 => (1_000, 65_536),
+        51..=56 => (1_600, 65_536),
+        57..=62 => (2_400, 98_304),
+        63..=68 => (3_600, 131_072),
+        69..=74 => (5_200, 196_608),
+        75..=80 => (7_500, 262_144),
+        81..=86 => (10_000, 327_680),
+        _ => (14_000, 393_216),
+    }
+}
+
+/// Quadratic sieve factorization.
+/// This function attempts to find a single factor of the composite input
+/// number, with the parameters for its size.
+pub fn get_factor_qs<T: ZkperIntegerTrait>(n: &ZkperInteger<T>) -> anyhow::Result<ZkperInteger<T>> {
+    // the polynomials only have to be varied: a fixed seed keeps the
+    // factorization reproducible, as for ECM
+    get_factor_qs_with_rng(n, &mut ZkperRng::from_seed(QS_SEED))
+}
+
+/// `get_factor_qs` with the polynomials drawn from `rgen`.
+pub fn get_factor_qs_with_rng<T: ZkperIntegerTrait>(
+    n: &ZkperInteger<T>,
+    rgen: &mut (impl RngCore + ?Sized),
+) -> anyhow::Result<ZkperInteger<T>> {
+    let (base_size, half_width) = qs_params(n.to_string().len());
+    Ok(siqs(n, base_size, half_width, rgen)?)
+}
+
+/// A prime of the factor base with a square root of n modulo it.
+#[derive(Clone, Copy, Debug)]
+struct BasePrime {
+    p: u64,
+    sqrt_n: u64,
+    log: u16,
+}
+
+/// (ax + b)^2 ≡ (-1)^negative * prod factors (mod n), with the exponents of
+/// the factor base primes by index.
+struct Relation<T: ZkperIntegerTrait> {
+    root: ZkperInteger<T>,
+    negative: bool,
+    factors: Vec<(usize, u32)>,
+}
+
+/// The quadratic sieve on the odd composite `n` with `base_size` factor base
+/// primes and the sieve interval [-half_width, half_width]. `n` must not be
+/// a prime power other than a square.
+pub fn siqs<T: ZkperIntegerTrait>(
+    n: &ZkperInteger<T>,
+    base_size: usize,
+    half_width: usize,
+    rgen: &mut (impl RngCore + ?Sized),
+) -> Result<ZkperInteger<T>, FactorizationError> {
+    if n.is_even() {
+        return Ok(ZkperInteger::two());
+    }
+    let root = isqrt(n);
+    if &root.square() == n {
+        return Ok(root);
+    }
+
+    // 2 and the odd primes p with n a square mod p
+    let mut base = Vec::with_capacity(base_size);
+    for p in Primes::all().map(|p| p as u64) {
+        if base.len() == base_size {
+            break;
+        }
+        let n_p = rem_u64(n, p);
+        if n_p == 0 {
+            return Ok(ZkperInteger::from(p));
+        }
+        if p == 2 || pow_mod(n_p, (p - 1) / 2, p) == 1 {
+            let log = (p as f64).log2().round() as u16;
+            base.push(BasePrime {
+                p,
+                sqrt_n: sqrt_mod(n_p, p),
+                log,
+            });
+        }
+    }
+
+    let relations = collect_relations(n, &base, half_width, rgen)?;
+
+    // one row per relation: the sign, then the exponents mod 2
+    let columns = base.len() + 1;
+    let rows: Vec<Vec<u64>> = relations
+        .iter()
+        .map(|relation| {
+            let mut row = vec![0u64; columns.div_ceil(64)];
+            let mut flip = |column: usize| row[column / 64] ^= 1 << (column % 64);
+            if relation.negative {
+                flip(0);
+            }
+            for &(index, exponent) in &relation.factors {
+                if exponent % 2 == 1 {
+                    flip(index + 1);
+                }
+            }
+            row
+        })
+        .collect();
+
+    for dependency in dependencies(&rows, columns) {
+        let mut x = ZkperInteger::one();
+        let mut exponents = vec![0u32; base.len()];
+        for &i in &dependency {
+            x = (x * &relations[i].root) % n;
+            for &(index, exponent) in &relations[i].factors {
+                exponents[index] += exponent;
+            }
+        }
+
+        // the signs cancel, and every exponent is even
+        let mut y = ZkperInteger::one();
+        for (prime, exponent) in base.iter().zip(exponents) {
+            if exponent > 0 {
+                let power =
+                    ZkperInteger::from(prime.p).pow_mod(&ZkperInteger::from(exponent / 2), n);
+                y = (y * &power) % n;
+            }
+        }
+
+        let g = abs_diff(&x, &y).gcd(n);
+        if g.is_not_one() && &g != n {
+            return Ok(g);
+        }
+    }
+
+    Err(FactorizationError::QsFailed)
+}
+
+/// Sieves polynomials until there are `EXTRA_RELATIONS` more relations than
+/// factor base primes.
+fn collect_relations<T: ZkperIntegerTrait>(
+    n: &ZkperInteger<T>,
+    base: &[BasePrime],
+    half_width: usize,
+    rgen: &mut (impl RngCore + ?Sized),
+) -> Result<Vec<Relation<T>>, FactorizationError> {
+    let wanted = base.len() + EXTRA_RELATIONS;
+    let width = 2 * half_width + 1;
+    let mut relations = Vec::with_capacity(wanted);
+    let mut roots_seen = HashSet::new();
+    let mut a_seen = HashSet::new();
+
+    // log2 |Q(x) / a| is about log2(M sqrt(n / 2)); the slack covers the
+    // unsieved small primes, the rounded logarithms and a few large powers
+    let target = (half_width as f64).log2() + n.bit_length() as f64 / 2.0 - 0.5;
+    let slack = 1.5 * (base[base.len() - 1].p as f64).log2();
+    let threshold = (target - slack).max(1.0) as u16;
+
+    let mut sieve = vec![0u16; width];
+    let mut starts = vec![[0usize; 2]; base.len()];
+    while relations.len() < wanted {
+        let a_indices = choose_a(n, base, half_width, rgen, &mut a_seen)?;
+        let a = a_indices.iter().fold(ZkperInteger::one(), |a, &i| {
+            a * &ZkperInteger::from(base[i].p)
+        });
+        let s = a_indices.len();
+
+        // B_j ≡ sqrt(n) mod q_j and ≡ 0 mod the other q, so any signed sum b
+        // of them has b^2 ≡ n mod a
+        let big_b: Vec<ZkperInteger<T>> = a_indices
+            .iter()
+            .map(|&i| {
+                let BasePrime { p: q, sqrt_n, .. } = base[i];
+                let a_over_q = &a / &ZkperInteger::from(q);
+                let mut gamma = mul_mod(sqrt_n, inv_mod(rem_u64(&a_over_q, q), q), q);
+                if gamma > q / 2 {
+                    gamma = q - gamma;
+                }
+                a_over_q * &ZkperInteger::from(gamma)
+            })
+            .collect();
+
+        // per prime: 1 / a, the B_j and s a, all mod p; None where p | a
+        let per_prime: Vec<Option<(u64, Vec<u64>, u64)>> = base
+            .iter()
+            .map(|prime| {
+                let a_p = rem_u64(&a, prime.p);
+                (a_p != 0).then(|| {
+                    let b_p = big_b.iter().map(|b| rem_u64(b, prime.p)).collect();
+                    (inv_mod(a_p, prime.p), b_p, a_p * s as u64 % prime.p)
+                })
+            })
+            .collect();
+
+        // b is kept as sum_j (sign_j B_j + a), which is positive and differs
+        // from the signed sum by s a, a shift of x by s
+        let mut signs = vec![true; s];
+        let mut b = big_b
+            .iter()
+            .fold(&a * &ZkperInteger::from(s as u64), |b, bj| b + bj);
+        for i in 0..1usize << (s - 1) {
+            if i > 0 {
+                let v = i.trailing_zeros() as usize;
+                let twice = big_b[v].clone() * 2;
+                b = if signs[v] { b - &twice } else { b + &twice };
+                signs[v] = !signs[v];
+            }
+
+            sieve.fill(0);
+            for ((prime, start), extra) in base.iter().zip(starts.iter_mut()).zip(&per_prime) {
+                let Some((a_inv, b_p, shift)) = extra else {
+                    continue;
+                };
+                let p = prime.p;
+                let b_mod = b_p
+                    .iter()
+                    .zip(&signs)
+                    .fold(*shift, |acc, (&bj, &positive)| {
+                        (acc + if positive { bj } else { p - bj }) % p
+                    });
+
+                // roots of (ax + b)^2 - n mod p, as offsets into the sieve
+                for (slot, t) in start.iter_mut().zip([prime.sqrt_n, p - prime.sqrt_n]) {
+                    let x = mul_mod(*a_inv, (t + p - b_mod) % p, p);
+                    *slot = ((x + half_width as u64) % p) as usize;
+                }
+                if p < SIEVE_MIN_PRIME {
+                    continue;
+                }
+                for &first in start.iter() {
+                    for cell in sieve.iter_mut().skip(first).step_by(p as usize) {
+                        *cell += prime.log;
+                    }
+                }
+            }
+
+            for (offset, _) in sieve.iter().enumerate().filter(|(_, &l)| l >= threshold) {
+                let x = offset as i64 - half_width as i64;
+                let Some(relation) = check_smooth(n, base, &a, &b, x, offset, &starts, &per_prime)
+                else {
+                    continue;
+                };
+                if roots_seen.insert(relation.root.clone()) {
+                    relations.push(relation);
+                }
+            }
+
+            if relations.len() >= wanted {
+                break;
+            }
+        }
+    }
+
+    Ok(relations)
+}
+
+/// Picks a new set of factor base indices whose primes multiply to about
+/// sqrt(2n) / M: all but the last at random around the ideal size, the last
+/// to land closest to the target.
+fn choose_a<T: ZkperIntegerTrait>(
+    n: &ZkperInteger<T>,
+    base: &[BasePrime],
+    half_width: usize,
+    rgen: &mut (impl RngCore + ?Sized),
+    seen: &mut HashSet<Vec<usize>>,
+) -> Result<Vec<usize>, FactorizationError> {
+    let target = (n.bit_length() as f64 + 1.0) / 2.0 - (half_width as f64).log2();
+    let largest = (base[base.len() * 2 / 3].p as f64).min(A_PRIME_SIZE);
+    let s = (target / largest.log2()).ceil().max(1.0) as usize;
+    let ideal = 2f64.powf(target / s as f64);
+
+    // the primes around the ideal size, skipping 2
+    let center = base.partition_point(|prime| (prime.p as f64) < ideal);
+    let spread = (s + 8).max(base.len() / 10);
+    let pool: Vec<usize> =
+        (center.saturating_sub(spread).max(1)..(center + spread).min(base.len())).collect();
+    let index_below = ZkperInteger::<T>::from(pool.len() as u64);
+
+    for _ in 0..MAX_A_RETRIES {
+        let mut chosen: Vec<usize> = Vec::with_capacity(s);
+        while chosen.len() + 1 < s.min(pool.len()) {
+            let i = pool[to_u64(&index_below.random_below(rgen)) as usize];
+            if !chosen.contains(&i) {
+                chosen.push(i);
+            }
+        }
+
+        let have: f64 = chosen.iter().map(|&i| (base[i].p as f64).log2()).sum();
+        let last = (1..base.len())
+            .filter(|i| !chosen.contains(i))
+            .min_by(|&i, &j| {
+                let miss = |k: usize| ((base[k].p as f64).log2() + have - target).abs();
+                miss(i).total_cmp(&miss(j))
+            });
+        let Some(last) = last else {
+            break;
+        };
+        chosen.push(last);
+        chosen.sort_unstable();
+        if seen.insert(chosen.clone()) {
+            return Ok(chosen);
+        }
+    }
+
+    Err(FactorizationError::QsFailed)
+}
+
+/// Trial divides Q(x) at sieve `offset` over the factor base, using the
+/// sieve roots to skip the primes that cannot divide it. None unless it
+/// factors completely.
+#[allow(clippy::too_many_arguments)]
+fn check_smooth<T: ZkperIntegerTrait>(
+    n: &ZkperInteger<T>,
+    base: &[BasePrime],
+    a: &ZkperInteger<T>,
+    b: &ZkperInteger<T>,
+    x: i64,
+    offset: usize,
+    starts: &[[usize; 2]],
+    per_prime: &[Option<(u64, Vec<u64>, u64)>],
+) -> Option<Relation<T>> {
+    // |ax + b| and Q(x) = (ax + b)^2 - n as a sign and a magnitude
+    let ax = a * &ZkperInteger::from(x.unsigned_abs());
+    let root = if x >= 0 { ax + b } else { abs_diff(&ax, b) };
+    let square = root.square();
+    let negative = &square < n;
+    let mut q = abs_diff(&square, n);
+
+    let mut factors = Vec::new();
+    for (index, prime) in base.iter().enumerate() {
+        let p = prime.p;
+        let sieved = p >= SIEVE_MIN_PRIME && per_prime[index].is_some();
+        if sieved && !starts[index].contains(&(offset % p as usize)) {
+            continue;
+        }
+        let p = ZkperInteger::from(p);
+        let mut exponent = 0;
+        while q.is_divisible(&p) {
+            q /= &p;
+            exponent += 1;
+        }
+        if exponent > 0 {
+            factors.push((index, exponent));
+        }
+    }
+
+    q.is_one().then_some(Relation {
+        root: root % n,
+        negative,
+        factors,
+    })
+}
+
+/// Subsets of `rows`, bitsets over `columns` columns, that sum to zero over
+/// GF(2), by Gaussian elimination keeping track of the rows combined.
+fn dependencies(rows: &[Vec<u64>], columns: usize) -> Vec<Vec<usize>> {
+    let mut matrix = rows.to_vec();
+    let mut history: Vec<Vec<u64>> = (0..rows.len())
+        .map(|i| {
+            let mut row = vec![0u64; rows.len().div_ceil(64)];
+            row[i / 64] |= 1 << (i % 64);
+            row
+        })
+        .collect();
+    let bit = |row: &[u64], i: usize| row[i / 64] >> (i % 64) & 1 == 1;
+
+    let mut pivot = 0;
+    for column in 0..columns {
+        let Some(found) = (pivot..rows.len()).find(|&r| bit(&matrix[r], column)) else {
+            continue;
+        };
+        matrix.swap(pivot, found);
+        history.swap(pivot, found);
+
+        let (done, rest) = matrix.split_at_mut(pivot + 1);
+        let (done_history, rest_history) = history.split_at_mut(pivot + 1);
+        for (row, row_history) in rest.iter_mut().zip(rest_history) {
+            if bit(row, column) {
+                xor_into(row, &done[pivot]);
+                xor_into(row_history, &done_history[pivot]);
+            }
+        }
+        pivot += 1;
+    }
+
+    // the rows left without a pivot are zero
+    history[pivot..]
+        .iter()
+        .map(|row| (0..rows.len()).filter(|&i| bit(row, i)).collect())
+        .collect()
+}
+
+fn xor_into(row: &mut [u64], other: &[u64]) {
+    for (word, other) in row.iter_mut().zip(other) {
+        *word ^= other;
+    }
+}
+
+/// The integer square root, rounded down, by Newton's method.
+fn isqrt<T: ZkperIntegerTrait>(n: &ZkperInteger<T>) -> ZkperInteger<T> {
+    if n.is_zero() {
+        return ZkperInteger::zero();
+    }
+    let mut x = ZkperInteger::one().shift_left(n.bit_length() as u64 / 2 + 1);
+    loop {
+        let y = (&x + &(n / &x)).shift_right(1);
+        if y >= x {
+            return x;
+        }
+        x = y;
+    }
+}
+
+/// The low 64 bits of `x`.
+fn to_u64<T: ZkperIntegerTrait>(x: &ZkperInteger<T>) -> u64 {
+    let mut bytes = [0u8; 8];
+    for (byte, value) in bytes.iter_mut().zip(x.to_bytes()) {
+        *byte = value;
+    }
+    u64::from_le_bytes(bytes)
+}
+
+fn rem_u64<T: ZkperIntegerTrait>(x: &ZkperInteger<T>, p: u64) -> u64 {
+    to_u64(&(x % &ZkperInteger::from(p)))
+}
+
+fn mul_mod(a: u64, b: u64, p: u64) -> u64 {
+    (a as u128 * b as u128 % p as u128) as u64
+}
+
+fn pow_mod(mut base: u64, mut exp: u64, p: u64) -> u64 {
+    let mut result = 1 % p;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = mul_mod(result, base, p);
+        }
+        base = mul_mod(base, base, p);
+        exp >>= 1;
+    }
+    result
+}
+
+/// 1 / a mod the prime p, by Fermat.
+fn inv_mod(a: u64, p: u64) -> u64 {
+    pow_mod(a, p - 2, p)
+}
+
+/// A square root of the quadratic residue `a` mod the prime p, by
+/// Tonelli-Shanks.
+fn sqrt_mod(a: u64, p: u64) -> u64 {
+    if p == 2 {
+        return a & 1;
+    }
+    let (mut q, mut s) = (p - 1, 0);
+    while q % 2 == 0 {
+        q /= 2;
+        s += 1;
+    }
+    if s == 1 {
+        return pow_mod(a, (p + 1) / 4, p);
+    }
+
+    let z = (2..p)
+        .find(|&z| pow_mod(z, (p - 1) / 2, p) == p - 1)
+        .unwrap();
+    let mut m = s;
+    let mut c = pow_mod(z, q, p);
+    let mut t = pow_mod(a, q, p);
+    let mut r = pow_mod(a, q.div_ceil(2), p);
+    while t != 1 {
+        let mut i = 0;
+        let mut t2 = t;
+        while t2 != 1 {
+            t2 = mul_mod(t2, t2, p);
+            i += 1;
+        }
+        let b = pow_mod(c, 1 << (m - i - 1), p);
+        m = i;
+        c = mul_mod(b, b, p);
+        t = mul_mod(t, c, p);
+        r = mul_mod(r, b, p);
+    }
+    r
+}
+
+#[cfg(test)]
+mod tests {
+    use zkper_integer::backends::rug_backend::RugBackend;
+
+    use super::*;
+    use crate::math::primality::random_prime;
+    use zkper_rand::TestRng;
+
+    #[test]
+    fn test_sqrt_mod() {
+        for p in Primes::all().skip(1).take(200).map(|p| p as u64) {
+            for a in 1..p.min(50) {
+                if pow_mod(a, (p - 1) / 2, p) == 1 {
+                    let r = sqrt_mod(a, p);
+                    assert_eq!(mul_mod(r, r, p), a, "sqrt({a}) mod {p}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_dependencies() {
+        // rows 0 ^ 1 ^ 3 = 0 and 2 = 4
+        let rows = [0b0011u64, 0b0110, 0b1001, 0b0101, 0b1001].map(|r| vec![r]);
+        let deps = dependencies(&rows, 4);
+        assert!(!deps.is_empty());
+        for dep in deps {
+            let sum = dep.iter().fold(0, |acc, &i| acc ^ rows[i][0]);
+            assert_eq!(sum, 0, "{dep:?}");
+        }
+    }
+
+    #[test]
+    fn test_isqrt() {
+        for v in [
+            0u64,
+            1,
+            2,
+            3,
+            4,
+            15,
+            16,
+            17,
+            1 << 40,
+            (1 << 40) + 1,
+            u64::MAX,
+        ] {
+            let r = to_u64(&isqrt(&ZkperInteger::<RugBackend>::from(v)));
+            assert!(r as u128 * r as u128 <= v as u128);
+            assert!((r as u128 + 1) * (r as u128 + 1) > v as u128);
+        }
+    }
+
+    #[test]
+    fn test_siqs() {
+        // 30 digits, out of reach of rho
+        let mut rng = TestRng::new();
+        let p: ZkperInteger<RugBackend> = random_prime(50, &mut rng).unwrap();
+        let q = random_prime(50, &mut rng).unwrap();
+        let n = &p * &q;
+        let factor = get_factor_qs(&n).unwrap();
+        assert!(factor == p || factor == q, "{factor}");
+
+        // 20 digits, with the smallest parameters
+        let (p, q) = (4294967291u64, 4294967279u64);
+        let n = ZkperInteger::<RugBackend>::from(p) * ZkperInteger::from(q);
+        let factor = get_factor_qs(&n).unwrap();
+        assert!(factor == ZkperInteger::from(p) || factor == ZkperInteger::from(q));
+    }
+
+    #[test]
+    fn test_siqs_trivial_inputs() {
+        let mut rgen = TestRng::new();
+        let square = ZkperInteger::<RugBackend>::from(1000003u64 * 1000003);
+        assert_eq!(
+            siqs(&square, 60, 4096, &mut rgen).unwrap(),
+            ZkperInteger::from(1000003u64)
+        );
+        let even = ZkperInteger::<RugBackend>::from(2u64 * 1000003);
+        assert_eq!(
+            siqs(&even, 60, 4096, &mut rgen).unwrap(),
+            ZkperInteger::two()
+        );
+        // a small prime of n turns up while building the factor base
+        let n = ZkperInteger::<RugBackend>::from(7u64 * 1000003);
+        assert_eq!(
+            siqs(&n, 60, 4096, &mut rgen).unwrap(),
+            ZkperInteger::from(7u64)
+        );
+    }
+}
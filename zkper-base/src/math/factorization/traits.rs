@@ -6,7 +6,7 @@ pub trait CompositeSplitter<T: ZkperIntegerTrait> {
 
     fn split(&self, n: &ZkperInteger<T>) -> (ZkperInteger<T>, ZkperInteger<T>) {
         let d1 = self.divisor(n);
-        let d2 = (n / &d1).into();
+        let d2 = n / &d1;
         if d1 < d2 {
             (d1, d2)
         } else {
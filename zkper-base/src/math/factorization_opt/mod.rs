@@ -1,8 +1,12 @@
 use primal::Sieve;
 use zkper_integer::{traits::ZkperIntegerTrait, ZkperInteger};
 
+use super::factorization::{get_factor_ecm_qs, pollards_rho::get_factor_pollard_rho};
+
 const SIEVE_LIMIT: usize = 1000000;
 
+/// A generator of the multiplicative group of the prime `modulus`, trying
+/// the small primes a and their negations p - a in turn.
 pub fn find_generator<T: ZkperIntegerTrait>(modulus: &ZkperInteger<T>) -> ZkperInteger<T> {
     let phi = modulus - 1u64;
     let factors = factor(&phi);
@@ -13,7 +17,7 @@ pub fn find_generator<T: ZkperIntegerTrait>(modulus: &ZkperInteger<T>) -> ZkperI
         if is_primitive_root(&candidate, modulus, &factors) {
             return candidate;
         }
-        let neg_candidate = -candidate;
+        let neg_candidate = candidate.neg_mod(modulus);
         if is_primitive_root(&neg_candidate, modulus, &factors) {
             return neg_candidate;
         }
@@ -58,7 +62,7 @@ pub fn factor<T: ZkperIntegerTrait>(n: &ZkperInteger<T>) -> Vec<(ZkperInteger<T>
         if m.is_one() {
             return factors;
         }
-        if m < ZkperInteger::from(SIEVE_LIMIT * SIEVE_LIMIT) {
+        if m < ZkperInteger::from(SIEVE_LIMIT as u64 * SIEVE_LIMIT as u64) {
             break;
         }
     }
@@ -95,27 +99,45 @@ pub fn pollard_rho_factor<T: ZkperIntegerTrait>(
     }
 }
 
+/// A nontrivial factor of the composite `n`: Brent's rho within its default
+/// budget, then ECM and the quadratic sieve.
 pub fn pollard_rho<T: ZkperIntegerTrait>(n: &ZkperInteger<T>) -> ZkperInteger<T> {
-    let mut x = ZkperInteger::two();
-    let mut y = ZkperInteger::two();
-    let mut d = ZkperInteger::one();
-
-    while d.is_one() {
-        x = (x.clone() * &x + 1) % n;
-        y = (y.clone() * &y + 1) % n;
-        y = (y.clone() * &y + 1) % n;
-        d = (x.clone() - &y).abs().gcd(n);
-    }
-
-    d
+    get_factor_pollard_rho(n)
+        .or_else(|_| get_factor_ecm_qs(n))
+        .expect("no method split a composite")
 }
 
 #[cfg(test)]
 mod tests {
-    use zkper_integer::{backends::rug_backend::RugBackend, ZkperInteger};
+    use zkper_integer::{
+        backends::{rug_backend::RugBackend, u64_backend::U64Backend},
+        ZkperInteger,
+    };
 
     use crate::math::factorization::find_generator;
 
+    #[test]
+    fn test_negated_generator() {
+        // 2 has order 3 mod 7, -2 = 5 generates; the word backends have no
+        // negative numbers, so the generator has to come back as a residue
+        let modulus = ZkperInteger::<U64Backend>::from(7u64);
+        assert_eq!(find_generator(&modulus), ZkperInteger::from(5u64));
+        assert_eq!(super::find_generator(&modulus), ZkperInteger::from(5u64));
+        let modulus = ZkperInteger::<RugBackend>::from(7u64);
+        assert_eq!(find_generator(&modulus), ZkperInteger::from(5u64));
+    }
+
+    #[test]
+    fn test_factor_semiprime() {
+        // a cofactor beyond the sieve, split by rho
+        let m = ZkperInteger::<RugBackend>::from(4u64 * 1000003 * 1000033);
+        let factors = super::factor(&m);
+        let expected = [(2u64, 2), (1000003, 1), (1000033, 1)]
+            .map(|(p, e)| (ZkperInteger::from(p), e))
+            .to_vec();
+        assert_eq!(factors, expected);
+    }
+
     #[test]
     pub fn test_gen_two_adic_primitive_root_of_unity() {
         let modulus = ZkperInteger::<RugBackend>::from_hex_str(
@@ -2,3 +2,15 @@ pub mod factorization;
 
 // optimized factorization
 pub mod factorization_opt;
+
+// number-theoretic transform
+pub mod ntt;
+
+// bounded discrete logs
+pub mod discrete_log;
+
+// primality tests and certificates
+pub mod primality;
+
+// Chinese remainder theorem
+pub mod crt;
@@ -0,0 +1,18 @@
+use thiserror::Error;
+
+/// Errors of the number-theoretic transform.
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum NttError {
+    /// The modulus is not prime.
+    #[error("NTT modulus is not prime")]
+    NotPrime,
+    /// The domain size is not a power of two.
+    #[error("NTT size {0} is not a power of two")]
+    SizeNotPowerOfTwo(usize),
+    /// p - 1 has no subgroup of the requested size.
+    #[error("NTT size 2^{log_size} exceeds the two-adicity {two_adicity} of the modulus")]
+    SizeTooLarge { log_size: u32, two_adicity: u32 },
+    /// The input does not have the size of the domain.
+    #[error("NTT input has {actual} values, expected {expected}")]
+    LengthMismatch { expected: usize, actual: usize },
+}
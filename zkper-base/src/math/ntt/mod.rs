@@ -0,0 +1,308 @@
+//! Number-theoretic transform over a prime field with a large two-adic
+//! subgroup, the modulus given as a plain [`ZkperInteger`].
+
+pub mod errors;
+
+use std::{
+    any::{Any, TypeId},
+    cell::RefCell,
+    collections::HashMap,
+    rc::Rc,
+};
+
+use zkper_integer::{traits::ZkperIntegerTrait, ZkperInteger};
+
+use self::errors::NttError;
+
+/// The two-adicity s of p - 1, with s = 0 for p = 2.
+pub fn two_adicity<T: ZkperIntegerTrait>(modulus: &ZkperInteger<T>) -> u32 {
+    (modulus.clone() - 1u64).find_first_one(0).unwrap_or(0)
+}
+
+/// The two-adicity s of p - 1 and a primitive 2^s-th root of unity, z^((p - 1) / 2^s)
+/// for the least quadratic non-residue z. Cached per backend and modulus for
+/// the life of the thread.
+pub fn two_adic_root_of_unity<T: ZkperIntegerTrait + 'static>(
+    modulus: &ZkperInteger<T>,
+) -> anyhow::Result<(u32, ZkperInteger<T>)> {
+    thread_local! {
+        static ROOTS: RefCell<HashMap<(TypeId, String), Rc<dyn Any>>> = RefCell::new(HashMap::new());
+    }
+
+    let key = (TypeId::of::<T>(), modulus.to_hex_string());
+    if let Some(root) = ROOTS.with(|roots| roots.borrow().get(&key).cloned()) {
+        let root = root
+            .downcast::<(u32, ZkperInteger<T>)>()
+            .expect("roots are keyed by their backend");
+        return Ok((*root).clone());
+    }
+
+    if !modulus.is_prime() {
+        return Err(NttError::NotPrime.into());
+    }
+    let s = two_adicity(modulus);
+    let p_minus_one = modulus.clone() - 1u64;
+    let half = p_minus_one.clone() >> 1u32;
+    let mut z = ZkperInteger::<T>::from(2u64);
+    // every prime above 2 has a non-residue below it
+    while s > 0 && z.pow_mod(&half, modulus) != p_minus_one {
+        z += 1u64;
+    }
+    let root = z.pow_mod(&(p_minus_one >> s), modulus);
+
+    ROOTS.with(|roots| roots.borrow_mut().insert(key, Rc::new((s, root.clone()))));
+    Ok((s, root))
+}
+
+/// A power-of-two evaluation domain with its twiddle factors precomputed.
+#[derive(Debug, Clone)]
+pub struct NttDomain<T: ZkperIntegerTrait> {
+    modulus: ZkperInteger<T>,
+    log_size: u32,
+    /// n^-1 mod p, to scale the inverse transform
+    size_inv: ZkperInteger<T>,
+    /// ω^i for i < n / 2
+    twiddles: Vec<ZkperInteger<T>>,
+    /// ω^-i for i < n / 2
+    inv_twiddles: Vec<ZkperInteger<T>>,
+}
+
+impl<T: ZkperIntegerTrait + 'static> NttDomain<T> {
+    /// The domain of `size` points modulo the prime `modulus`; `size` must be
+    /// a power of two dividing p - 1.
+    pub fn new(modulus: &ZkperInteger<T>, size: usize) -> anyhow::Result<Self> {
+        if !size.is_power_of_two() {
+            return Err(NttError::SizeNotPowerOfTwo(size).into());
+        }
+        let log_size = size.trailing_zeros();
+        let (two_adicity, root) = two_adic_root_of_unity(modulus)?;
+        if log_size > two_adicity {
+            return Err(NttError::SizeTooLarge {
+                log_size,
+                two_adicity,
+            }
+            .into());
+        }
+
+        // a primitive n-th root of unity
+        let mut omega = root;
+        for _ in log_size..two_adicity {
+            omega = (omega.clone() * &omega) % modulus;
+        }
+        let omega_inv = omega.invert(modulus)?;
+
+        Ok(Self {
+            modulus: modulus.clone(),
+            log_size,
+            size_inv: ZkperInteger::from(size).invert(modulus)?,
+            twiddles: Self::powers(&omega, size / 2, modulus),
+            inv_twiddles: Self::powers(&omega_inv, size / 2, modulus),
+        })
+    }
+
+    fn powers(
+        base: &ZkperInteger<T>,
+        count: usize,
+        modulus: &ZkperInteger<T>,
+    ) -> Vec<ZkperInteger<T>> {
+        let mut powers = Vec::with_capacity(count);
+        let mut power = ZkperInteger::one();
+        for _ in 0..count {
+            powers.push(power.clone());
+            power = (power * base) % modulus;
+        }
+        powers
+    }
+}
+
+impl<T: ZkperIntegerTrait> NttDomain<T> {
+    pub fn size(&self) -> usize {
+        1 << self.log_size
+    }
+
+    pub fn modulus(&self) -> &ZkperInteger<T> {
+        &self.modulus
+    }
+
+    /// The primitive n-th root of unity ω the domain is generated by.
+    pub fn root(&self) -> ZkperInteger<T> {
+        match self.twiddles.get(1) {
+            Some(omega) => omega.clone(),
+            // n = 2 has ω = -1, n = 1 has ω = 1
+            None if self.size() == 2 => self.modulus.clone() - 1u64,
+            None => ZkperInteger::one(),
+        }
+    }
+
+    /// Coefficients to evaluations: values[i] becomes the polynomial with
+    /// those coefficients evaluated at ω^i.
+    pub fn forward(&self, values: &mut [ZkperInteger<T>]) -> anyhow::Result<()> {
+        self.transform(values, &self.twiddles)
+    }
+
+    /// Evaluations at ω^i back to coefficients.
+    pub fn inverse(&self, values: &mut [ZkperInteger<T>]) -> anyhow::Result<()> {
+        self.transform(values, &self.inv_twiddles)?;
+        for value in values.iter_mut() {
+            *value = (value.clone() * &self.size_inv) % &self.modulus;
+        }
+        Ok(())
+    }
+
+    /// Iterative radix-2 Cooley-Tukey on the bit-reversed input.
+    fn transform(
+        &self,
+        values: &mut [ZkperInteger<T>],
+        twiddles: &[ZkperInteger<T>],
+    ) -> anyhow::Result<()> {
+        let n = self.size();
+        if values.len() != n {
+            return Err(NttError::LengthMismatch {
+                expected: n,
+                actual: values.len(),
+            }
+            .into());
+        }
+
+        let p = &self.modulus;
+        for value in values.iter_mut() {
+            let r = &*value % p;
+            *value = if r < ZkperInteger::zero() { r + p } else { r };
+        }
+
+        if n == 1 {
+            return Ok(());
+        }
+        for i in 0..n {
+            let j = i.reverse_bits() >> (usize::BITS - self.log_size);
+            if i < j {
+                values.swap(i, j);
+            }
+        }
+
+        let mut len = 2;
+        while len <= n {
+            let half = len / 2;
+            let step = n / len;
+            for start in (0..n).step_by(len) {
+                for k in 0..half {
+                    let u = values[start + k].clone();
+                    let v = (values[start + k + half].clone() * &twiddles[k * step]) % p;
+                    values[start + k] = (u.clone() + &v) % p;
+                    values[start + k + half] = (u + p - &v) % p;
+                }
+            }
+            len *= 2;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zkper_integer::{
+        backends::{rug_backend::RugBackend, u128_backend::U128Backend},
+        traits::ZkperIntegerTrait,
+        ZkperInteger,
+    };
+    use zkper_rand::TestRng;
+
+    use super::{errors::NttError, two_adic_root_of_unity, NttDomain};
+
+    fn evaluate<T: ZkperIntegerTrait>(
+        coeffs: &[ZkperInteger<T>],
+        x: &ZkperInteger<T>,
+        p: &ZkperInteger<T>,
+    ) -> ZkperInteger<T> {
+        coeffs
+            .iter()
+            .rev()
+            .fold(ZkperInteger::zero(), |acc, c| (acc * x + c) % p)
+    }
+
+    fn check_domain<T: ZkperIntegerTrait + 'static>(p: &ZkperInteger<T>, size: usize) {
+        let domain = NttDomain::new(p, size).unwrap();
+        let omega = domain.root();
+        assert!(omega.pow_mod(&ZkperInteger::from(size), p).is_one());
+        if size > 1 {
+            assert!(!omega.pow_mod(&ZkperInteger::from(size / 2), p).is_one());
+        }
+
+        let mut rng = TestRng::new();
+        let coeffs: Vec<_> = (0..size).map(|_| p.random_below(&mut rng)).collect();
+        let mut values = coeffs.clone();
+        domain.forward(&mut values).unwrap();
+        for (i, value) in values.iter().enumerate() {
+            let x = omega.pow_mod(&ZkperInteger::from(i), p);
+            assert_eq!(*value, evaluate(&coeffs, &x, p));
+        }
+        domain.inverse(&mut values).unwrap();
+        assert_eq!(values, coeffs);
+    }
+
+    #[test]
+    fn test_ntt_matches_evaluation() {
+        // 97 = 3 * 2^5 + 1
+        let p97 = ZkperInteger::<RugBackend>::from(97u64);
+        for size in [1, 2, 4, 8, 16, 32] {
+            check_domain(&p97, size);
+        }
+
+        let bls12_381_fr = ZkperInteger::<RugBackend>::from_hex_str(
+            "0x73eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000001",
+        );
+        check_domain(&bls12_381_fr, 64);
+        assert_eq!(two_adic_root_of_unity(&bls12_381_fr).unwrap().0, 32);
+
+        let goldilocks = ZkperInteger::<U128Backend>::from_hex_str("0xffffffff00000001");
+        check_domain(&goldilocks, 16);
+    }
+
+    #[test]
+    fn test_ntt_polynomial_product() {
+        let p = ZkperInteger::<RugBackend>::from(97u64);
+        let domain = NttDomain::new(&p, 8).unwrap();
+        let n = |v: u64| ZkperInteger::<RugBackend>::from(v);
+
+        // (1 + 2x + 3x^2) * (4 + 5x) = 4 + 13x + 22x^2 + 15x^3
+        let mut a: Vec<_> = [1, 2, 3, 0, 0, 0, 0, 0].map(n).to_vec();
+        let mut b: Vec<_> = [4, 5, 0, 0, 0, 0, 0, 0].map(n).to_vec();
+        domain.forward(&mut a).unwrap();
+        domain.forward(&mut b).unwrap();
+        let mut c: Vec<_> = a.iter().zip(&b).map(|(x, y)| (x * y) % &p).collect();
+        domain.inverse(&mut c).unwrap();
+        assert_eq!(c, [4, 13, 22, 15, 0, 0, 0, 0].map(n));
+    }
+
+    #[test]
+    fn test_ntt_errors() {
+        let p = ZkperInteger::<RugBackend>::from(97u64);
+        let err = |e: anyhow::Error| e.downcast::<NttError>().unwrap();
+
+        assert_eq!(
+            err(NttDomain::new(&p, 12).unwrap_err()),
+            NttError::SizeNotPowerOfTwo(12)
+        );
+        assert_eq!(
+            err(NttDomain::new(&p, 64).unwrap_err()),
+            NttError::SizeTooLarge {
+                log_size: 6,
+                two_adicity: 5
+            }
+        );
+        assert_eq!(
+            err(NttDomain::new(&ZkperInteger::<RugBackend>::from(91u64), 2).unwrap_err()),
+            NttError::NotPrime
+        );
+
+        let domain = NttDomain::new(&p, 4).unwrap();
+        let mut values = vec![ZkperInteger::one(); 3];
+        assert_eq!(
+            err(domain.forward(&mut values).unwrap_err()),
+            NttError::LengthMismatch {
+                expected: 4,
+                actual: 3
+            }
+        );
+    }
+}
@@ -0,0 +1,319 @@
+//! Primality certificates after Pocklington, and certified prime generation
+//! after Maurer.
+//!
+//! Pocklington's theorem: if n - 1 = F * R with F > sqrt(n) and fully
+//! factored, and every prime q of F has a witness a with a^(n-1) = 1 and
+//! gcd(a^((n-1)/q) - 1, n) = 1, then n is prime. The primes of F carry
+//! certificates of their own, down to numbers below 2^64, which
+//! deterministic Miller-Rabin settles.
+
+use rand_core::RngCore;
+use zkper_integer::{traits::ZkperIntegerTrait, ZkperInteger};
+
+use super::{bit_length, bpsw, errors::PrimalityError, miller_rabin_deterministic};
+
+/// Witnesses tried per prime of F before giving up on n.
+const MAX_WITNESS: u64 = 1000;
+
+/// Primes tried when factoring n - 1 by trial division.
+const TRIAL_PRIMES: usize = 10000;
+
+/// A proof that a number is prime, see the module documentation.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PrimeCertificate<T: ZkperIntegerTrait> {
+    /// A prime below 2^64, checked by deterministic Miller-Rabin.
+    Small(ZkperInteger<T>),
+    /// A prime n with Pocklington witnesses for the primes of F.
+    Pocklington {
+        n: ZkperInteger<T>,
+        factors: Vec<PocklingtonFactor<T>>,
+    },
+}
+
+/// A prime q of F, with its witness and its own certificate.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PocklingtonFactor<T: ZkperIntegerTrait> {
+    pub witness: ZkperInteger<T>,
+    pub certificate: PrimeCertificate<T>,
+}
+
+impl<T: ZkperIntegerTrait> PrimeCertificate<T> {
+    /// The prime the certificate is for.
+    pub fn n(&self) -> &ZkperInteger<T> {
+        match self {
+            PrimeCertificate::Small(n) | PrimeCertificate::Pocklington { n, .. } => n,
+        }
+    }
+
+    /// Checks the whole certificate, sub-certificates included.
+    pub fn verify(&self) -> bool {
+        match self {
+            PrimeCertificate::Small(n) => bit_length(n) <= 64 && miller_rabin_deterministic(n),
+            PrimeCertificate::Pocklington { n, factors } => {
+                let n_minus_one = n.clone() - 1u64;
+                let mut f = ZkperInteger::one();
+                for factor in factors {
+                    let q = factor.certificate.n();
+                    if !factor.certificate.verify() || !n_minus_one.is_divisible(q) {
+                        return false;
+                    }
+                    let mut rest = n_minus_one.clone();
+                    while rest.is_divisible(q) {
+                        rest /= q;
+                        f *= q;
+                    }
+                    if !is_witness(&factor.witness, q, n) {
+                        return false;
+                    }
+                }
+                f.clone() * &f > *n
+            }
+        }
+    }
+}
+
+/// Whether a^(n-1) = 1 and gcd(a^((n-1)/q) - 1, n) = 1.
+fn is_witness<T: ZkperIntegerTrait>(
+    a: &ZkperInteger<T>,
+    q: &ZkperInteger<T>,
+    n: &ZkperInteger<T>,
+) -> bool {
+    let n_minus_one = n.clone() - 1u64;
+    if !a.pow_mod(&n_minus_one, n).is_one() {
+        return false;
+    }
+    let b = a.pow_mod(&(n_minus_one / q), n);
+    // b = 0 would mean a shares a factor with n
+    !b.is_zero() && (b - 1u64).gcd(n).is_one()
+}
+
+/// Builds the Pocklington certificate of n from certified primes of n - 1,
+/// whose product with multiplicity must exceed sqrt(n).
+pub fn pocklington<T: ZkperIntegerTrait>(
+    n: &ZkperInteger<T>,
+    primes: Vec<PrimeCertificate<T>>,
+) -> anyhow::Result<PrimeCertificate<T>> {
+    let mut factors = Vec::with_capacity(primes.len());
+    for certificate in primes {
+        let q = certificate.n();
+        let witness = (2..MAX_WITNESS)
+            .map(ZkperInteger::from)
+            .find(|a| is_witness(a, q, n))
+            .ok_or(PrimalityError::Composite)?;
+        factors.push(PocklingtonFactor {
+            witness,
+            certificate,
+        });
+    }
+
+    let certificate = PrimeCertificate::Pocklington {
+        n: n.clone(),
+        factors,
+    };
+    if certificate.verify() {
+        Ok(certificate)
+    } else {
+        Err(PrimalityError::NotProvable.into())
+    }
+}
+
+/// Proves n prime. Below 2^64 this is deterministic Miller-Rabin; above, n - 1
+/// must factor over the first primes up to a cofactor that is itself
+/// provable, or small enough to leave out.
+pub fn prove_prime<T: ZkperIntegerTrait>(
+    n: &ZkperInteger<T>,
+) -> anyhow::Result<PrimeCertificate<T>> {
+    if bit_length(n) <= 64 {
+        return if miller_rabin_deterministic(n) {
+            Ok(PrimeCertificate::Small(n.clone()))
+        } else {
+            Err(PrimalityError::Composite.into())
+        };
+    }
+    if !bpsw(n) {
+        return Err(PrimalityError::Composite.into());
+    }
+
+    let mut cofactor = n.clone() - 1u64;
+    let mut f = ZkperInteger::<T>::one();
+    let mut primes = Vec::new();
+    for p in primal::Primes::all().take(TRIAL_PRIMES) {
+        let p = ZkperInteger::from(p);
+        if cofactor.is_divisible(&p) {
+            while cofactor.is_divisible(&p) {
+                cofactor /= &p;
+                f *= &p;
+            }
+            primes.push(PrimeCertificate::Small(p));
+        }
+    }
+
+    if f.clone() * &f <= *n {
+        if cofactor.is_one() || !bpsw(&cofactor) {
+            return Err(PrimalityError::NotProvable.into());
+        }
+        let certificate = prove_prime(&cofactor).map_err(|_| PrimalityError::NotProvable)?;
+        primes.push(certificate);
+    }
+    pocklington(n, primes)
+}
+
+/// A random certified prime of exactly `bits` bits, by Maurer's method: from
+/// a certified prime q of a little over half the bits, try n = 2 * r * q + 1
+/// for random r until n is prime, which q and 2 then prove.
+pub fn generate_certified_prime<T: ZkperIntegerTrait>(
+    bits: u32,
+    rng: &mut (impl RngCore + ?Sized),
+) -> anyhow::Result<PrimeCertificate<T>> {
+    generate_with(bits, rng, |_| true)
+}
+
+/// A random safe prime p = 2q + 1 of exactly `bits` bits, q prime, with a
+/// certificate for p that contains the one for q.
+pub fn generate_safe_prime<T: ZkperIntegerTrait>(
+    bits: u32,
+    rng: &mut (impl RngCore + ?Sized),
+) -> anyhow::Result<PrimeCertificate<T>> {
+    if bits < 3 {
+        return Err(PrimalityError::TooFewBits(bits).into());
+    }
+    let q = generate_with(bits - 1, rng, |q| bpsw(&(q.clone() * 2u64 + 1u64)))?;
+    let p = q.n().clone() * 2u64 + 1u64;
+    if bit_length(&p) <= 64 {
+        return Ok(PrimeCertificate::Small(p));
+    }
+    pocklington(&p, vec![PrimeCertificate::Small(ZkperInteger::two()), q])
+}
+
+/// A certified prime of `bits` bits accepted by `accept`.
+fn generate_with<T: ZkperIntegerTrait>(
+    bits: u32,
+    rng: &mut (impl RngCore + ?Sized),
+    accept: impl Fn(&ZkperInteger<T>) -> bool,
+) -> anyhow::Result<PrimeCertificate<T>> {
+    if bits < 2 {
+        return Err(PrimalityError::TooFewBits(bits).into());
+    }
+    let low = ZkperInteger::<T>::one() << (bits - 1);
+    let width = low.clone();
+
+    if bits <= 32 {
+        loop {
+            let n = width.random_below(rng) + &low;
+            if miller_rabin_deterministic(&n) && accept(&n) {
+                return Ok(PrimeCertificate::Small(n));
+            }
+        }
+    }
+
+    // q > 2^(bits / 2 + 1), so F = 2q > sqrt(n)
+    let q = generate_certified_prime::<T>(bits / 2 + 2, rng)?;
+    let two_q = q.n().clone() * 2u64;
+    // r in [2^(bits-1) / 2q, 2^bits / 2q), so n has `bits` bits
+    let r_low = (low.clone() + &two_q - 1u64) / &two_q;
+    let r_width = (low.clone() * 2u64 - 1u64) / &two_q - &r_low;
+    loop {
+        let n = (r_width.random_below(rng) + &r_low) * &two_q + 1u64;
+        if bpsw(&n) && accept(&n) {
+            return pocklington(
+                &n,
+                vec![PrimeCertificate::Small(ZkperInteger::two()), q.clone()],
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zkper_integer::{backends::rug_backend::RugBackend, ZkperInteger};
+    use zkper_rand::TestRng;
+
+    use super::*;
+    use crate::math::primality::errors::PrimalityError;
+
+    type Integer = ZkperInteger<RugBackend>;
+
+    #[test]
+    fn test_prove_prime() {
+        let err = |n: &Integer| {
+            prove_prime(n)
+                .unwrap_err()
+                .downcast::<PrimalityError>()
+                .unwrap()
+        };
+
+        // the BLS12-381 scalar field: p - 1 has a composite cofactor of about
+        // 200 bits past the trial primes
+        let fr = Integer::from_hex_str(
+            "0x73eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000001",
+        );
+        assert_eq!(err(&fr), PrimalityError::NotProvable);
+
+        // 2^127 - 1: p - 1 = 2 * 3^3 * 7^2 * 19 * 43 * 73 * 127 * 337 * 5419 * 92737 * 649657 * 77158673929
+        let m127 = (Integer::one() << 127u32) - 1u64;
+        let certificate = prove_prime(&m127).unwrap();
+        assert!(certificate.verify());
+
+        let err = |n: &Integer| {
+            prove_prime(n)
+                .unwrap_err()
+                .downcast::<PrimalityError>()
+                .unwrap()
+        };
+        assert_eq!(
+            err(&Integer::from(3215031751u64)),
+            PrimalityError::Composite
+        );
+        assert_eq!(err(&(m127.clone() * &m127)), PrimalityError::Composite);
+    }
+
+    #[test]
+    fn test_forged_certificates_fail() {
+        let m127 = (Integer::one() << 127u32) - 1u64;
+        let PrimeCertificate::Pocklington { n, mut factors } = prove_prime(&m127).unwrap() else {
+            panic!("2^127 - 1 needs a Pocklington certificate");
+        };
+
+        // a composite in place of n
+        let forged = PrimeCertificate::Pocklington {
+            n: n.clone() + 2u64,
+            factors: factors.clone(),
+        };
+        assert!(!forged.verify());
+
+        // too little of n - 1 covered
+        factors.truncate(1);
+        assert!(!PrimeCertificate::Pocklington { n, factors }.verify());
+
+        assert!(!PrimeCertificate::Small(Integer::from(3215031751u64)).verify());
+        // Small is only valid below 2^64
+        assert!(!PrimeCertificate::Small(m127).verify());
+    }
+
+    #[test]
+    fn test_generate_primes() {
+        let mut rng = TestRng::new();
+        for bits in [8, 32, 64, 128, 256] {
+            let certificate = generate_certified_prime::<RugBackend>(bits, &mut rng).unwrap();
+            assert!(certificate.verify());
+            assert_eq!(bit_length(certificate.n()), bits);
+        }
+
+        for bits in [3, 16, 64, 128] {
+            let certificate = generate_safe_prime::<RugBackend>(bits, &mut rng).unwrap();
+            assert!(certificate.verify());
+            let p = certificate.n();
+            assert_eq!(bit_length(p), bits);
+            let q = (p.clone() - 1u64) >> 1u32;
+            assert!(bpsw(&q));
+        }
+
+        assert_eq!(
+            generate_safe_prime::<RugBackend>(2, &mut rng)
+                .unwrap_err()
+                .downcast::<PrimalityError>()
+                .unwrap(),
+            PrimalityError::TooFewBits(2)
+        );
+    }
+}
@@ -0,0 +1,15 @@
+use thiserror::Error;
+
+/// Errors of the primality utilities.
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum PrimalityError {
+    /// The number is composite.
+    #[error("Number is composite")]
+    Composite,
+    /// Too little of n - 1 factors over small primes to build a Pocklington certificate.
+    #[error("Cannot factor enough of n - 1 to prove primality")]
+    NotProvable,
+    /// No prime of the requested kind has so few bits.
+    #[error("Too few bits: {0}")]
+    TooFewBits(u32),
+}
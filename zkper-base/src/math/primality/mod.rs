@@ -0,0 +1,320 @@
+//! Primality tests with explicit guarantees.
+//!
+//! The backends' `is_prime` runs a fixed number of rounds of whatever their
+//! library offers. This module gives the caller the choice: Miller-Rabin
+//! with as many random rounds as they like, Baillie-PSW, which has no known
+//! counterexample, and in [`certificate`] primality proofs that anyone can
+//! check. [`random_prime`] and [`random_safe_prime`] draw uniformly random
+//! primes of a given size.
+//!
+//! The arithmetic multiplies two residues before reducing them, so the word
+//! backends handle numbers of up to half their width.
+
+pub mod certificate;
+pub mod errors;
+pub mod random;
+
+pub use random::{random_prime, random_safe_prime};
+
+use rand_core::RngCore;
+use zkper_integer::{traits::ZkperIntegerTrait, ZkperInteger};
+
+/// Primes below 100, for trial division.
+const SMALL_PRIMES: [u64; 25] = [
+    2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53, 59, 61, 67, 71, 73, 79, 83, 89, 97,
+];
+
+/// Miller-Rabin to these bases decides primality exactly below 3.3 * 10^24,
+/// so for every u64.
+pub const DETERMINISTIC_BASES: [u64; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+
+/// Settles n by trial division when it is small or has a small factor.
+fn trial_division<T: ZkperIntegerTrait>(n: &ZkperInteger<T>) -> Option<bool> {
+    if *n < ZkperInteger::two() {
+        return Some(false);
+    }
+    for p in SMALL_PRIMES {
+        let p = ZkperInteger::from(p);
+        if *n == p {
+            return Some(true);
+        }
+        if n.is_divisible(&p) {
+            return Some(false);
+        }
+    }
+    // no factor below 100, so n < 100^2 is prime
+    (*n < ZkperInteger::from(10_000u64)).then_some(true)
+}
+
+/// Whether the odd n > 2 is a strong probable prime to `base`.
+pub fn is_strong_probable_prime<T: ZkperIntegerTrait>(
+    n: &ZkperInteger<T>,
+    base: &ZkperInteger<T>,
+) -> bool {
+    let n_minus_one = n.clone() - 1u64;
+    let s = n_minus_one.find_first_one(0).unwrap_or(0);
+    let mut x = base.pow_mod(&(n_minus_one.clone() >> s), n);
+    if x.is_one() || x == n_minus_one {
+        return true;
+    }
+    for _ in 1..s {
+        x = (x.clone() * &x) % n;
+        if x == n_minus_one {
+            return true;
+        }
+    }
+    false
+}
+
+/// Miller-Rabin to base 2 and then `rounds` random bases: a composite passes
+/// with probability below 4^-rounds.
+pub fn miller_rabin<T: ZkperIntegerTrait>(
+    n: &ZkperInteger<T>,
+    rounds: usize,
+    rng: &mut (impl RngCore + ?Sized),
+) -> bool {
+    if let Some(prime) = trial_division(n) {
+        return prime;
+    }
+    if !is_strong_probable_prime(n, &ZkperInteger::two()) {
+        return false;
+    }
+    // bases in [2, n - 2]
+    let range = n.clone() - 3u64;
+    (0..rounds).all(|_| is_strong_probable_prime(n, &(range.random_below(rng) + 2u64)))
+}
+
+/// Miller-Rabin to [`DETERMINISTIC_BASES`], exact below 3.3 * 10^24.
+pub fn miller_rabin_deterministic<T: ZkperIntegerTrait>(n: &ZkperInteger<T>) -> bool {
+    if let Some(prime) = trial_division(n) {
+        return prime;
+    }
+    DETERMINISTIC_BASES
+        .iter()
+        .all(|&base| is_strong_probable_prime(n, &ZkperInteger::from(base)))
+}
+
+/// The Jacobi symbol (a / n) for odd n > 0: 0, 1 or -1.
+pub fn jacobi<T: ZkperIntegerTrait>(a: &ZkperInteger<T>, n: &ZkperInteger<T>) -> i32 {
+    let mut a = a % n;
+    let mut n = n.clone();
+    let mut result = 1;
+    while !a.is_zero() {
+        while a.is_even() {
+            a >>= 1u32;
+            // (2 / n) = -1 exactly for n = 3, 5 mod 8
+            if matches!(low_byte(&n) & 7, 3 | 5) {
+                result = -result;
+            }
+        }
+        std::mem::swap(&mut a, &mut n);
+        // quadratic reciprocity
+        if low_byte(&a) & 3 == 3 && low_byte(&n) & 3 == 3 {
+            result = -result;
+        }
+        a = &a % &n;
+    }
+    if n.is_one() {
+        result
+    } else {
+        0
+    }
+}
+
+/// Strong Lucas probable prime test with Selfridge's parameters: the first
+/// D in 5, -7, 9, -11, ... with (D / n) = -1, P = 1 and Q = (1 - D) / 4.
+pub fn is_strong_lucas_probable_prime<T: ZkperIntegerTrait>(n: &ZkperInteger<T>) -> bool {
+    if n.is_even() || n.is_one() || n.is_zero() {
+        return *n == ZkperInteger::two();
+    }
+    // no D exists for squares
+    let root = isqrt(n);
+    if root.clone() * &root == *n {
+        return false;
+    }
+
+    // D and Q, as residues mod n
+    let mut d = 5u64;
+    let mut positive = true;
+    let (d_mod, q_mod) = loop {
+        let d_mod = if positive {
+            ZkperInteger::from(d)
+        } else {
+            n.clone() - d
+        };
+        match jacobi(&d_mod, n) {
+            -1 => {
+                // Q = (1 - D) / 4
+                let q_abs = ZkperInteger::from(if positive { (d - 1) / 4 } else { (d + 1) / 4 });
+                let q_mod = if positive { n.clone() - &q_abs } else { q_abs };
+                break (d_mod, q_mod);
+            }
+            // D shares a factor with n, which is prime only as |D| itself
+            0 => return *n == ZkperInteger::from(d),
+            _ => {}
+        }
+        d += 2;
+        positive = !positive;
+    };
+
+    let add = |a: &ZkperInteger<T>, b: &ZkperInteger<T>| (a + b) % n;
+    let sub = |a: &ZkperInteger<T>, b: &ZkperInteger<T>| (a + n - b) % n;
+    let mul = |a: &ZkperInteger<T>, b: &ZkperInteger<T>| (a * b) % n;
+    let half = |a: ZkperInteger<T>| {
+        if a.is_odd() {
+            (a + n) >> 1u32
+        } else {
+            a >> 1u32
+        }
+    };
+
+    // n + 1 = k * 2^s with k odd
+    let n_plus_one = n.clone() + 1u64;
+    let s = n_plus_one.find_first_one(0).unwrap_or(0);
+    let k = n_plus_one >> s;
+
+    // U_1 = 1, V_1 = P = 1, then the binary ladder over the bits of k
+    let (mut u, mut v, mut qk) = (ZkperInteger::one(), ZkperInteger::one(), q_mod.clone());
+    for i in (0..bit_length(&k).saturating_sub(1)).rev() {
+        // doubling
+        u = mul(&u, &v);
+        v = sub(&mul(&v, &v), &add(&qk, &qk));
+        qk = mul(&qk, &qk);
+        if (k.clone() >> i).is_odd() {
+            let u_next = half(add(&u, &v));
+            v = half(add(&mul(&d_mod, &u), &v));
+            u = u_next;
+            qk = mul(&qk, &q_mod);
+        }
+    }
+
+    if u.is_zero() || v.is_zero() {
+        return true;
+    }
+    for _ in 1..s {
+        v = sub(&mul(&v, &v), &add(&qk, &qk));
+        if v.is_zero() {
+            return true;
+        }
+        qk = mul(&qk, &qk);
+    }
+    false
+}
+
+/// Baillie-PSW: Miller-Rabin to base 2 and a strong Lucas test. Exact for
+/// every n below 2^64, with no composite known above.
+pub fn bpsw<T: ZkperIntegerTrait>(n: &ZkperInteger<T>) -> bool {
+    if let Some(prime) = trial_division(n) {
+        return prime;
+    }
+    is_strong_probable_prime(n, &ZkperInteger::two()) && is_strong_lucas_probable_prime(n)
+}
+
+/// floor(sqrt(n)) by Newton's method.
+pub fn isqrt<T: ZkperIntegerTrait>(n: &ZkperInteger<T>) -> ZkperInteger<T> {
+    if n.is_zero() {
+        return ZkperInteger::zero();
+    }
+    // start above the root, then decrease monotonically
+    let mut x = ZkperInteger::one() << bit_length(n).div_ceil(2);
+    loop {
+        let y = (x.clone() + &(n / &x)) >> 1u32;
+        if y >= x {
+            return x;
+        }
+        x = y;
+    }
+}
+
+pub(crate) fn bit_length<T: ZkperIntegerTrait>(n: &ZkperInteger<T>) -> u32 {
+    let bytes = n.to_bytes();
+    match bytes.iter().rposition(|&b| b != 0) {
+        Some(i) => 8 * i as u32 + (u8::BITS - bytes[i].leading_zeros()),
+        None => 0,
+    }
+}
+
+fn low_byte<T: ZkperIntegerTrait>(n: &ZkperInteger<T>) -> u8 {
+    n.to_bytes().first().copied().unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use zkper_integer::{
+        backends::{rug_backend::RugBackend, u128_backend::U128Backend},
+        traits::ZkperIntegerTrait,
+        ZkperInteger,
+    };
+    use zkper_rand::TestRng;
+
+    use super::*;
+
+    type Integer = ZkperInteger<RugBackend>;
+
+    // strong pseudoprimes to base 2, and 3215031751 to bases 2, 3, 5 and 7
+    const BASE_2_PSEUDOPRIMES: [u64; 5] = [2047, 3277, 4033, 4681, 3215031751];
+    // strong Lucas pseudoprimes for Selfridge's parameters
+    const LUCAS_PSEUDOPRIMES: [u64; 5] = [5459, 5777, 10877, 16109, 18971];
+
+    fn check_small<T: ZkperIntegerTrait>() {
+        let sieve = primal::Sieve::new(20_000);
+        let mut rng = TestRng::new();
+        for n in 0..20_000u64 {
+            let x = ZkperInteger::<T>::from(n);
+            let prime = sieve.is_prime(n as usize);
+            assert_eq!(bpsw(&x), prime, "bpsw({n})");
+            assert_eq!(miller_rabin_deterministic(&x), prime, "deterministic({n})");
+            assert_eq!(miller_rabin(&x, 2, &mut rng), prime, "miller_rabin({n})");
+            // a prime passes both halves of BPSW
+            if prime {
+                assert!(is_strong_lucas_probable_prime(&x), "lucas({n})");
+            }
+        }
+    }
+
+    #[test]
+    fn test_small_numbers() {
+        check_small::<RugBackend>();
+        check_small::<U128Backend>();
+    }
+
+    #[test]
+    fn test_pseudoprimes() {
+        for n in BASE_2_PSEUDOPRIMES.map(Integer::from) {
+            assert!(is_strong_probable_prime(&n, &Integer::two()));
+            assert!(!is_strong_lucas_probable_prime(&n));
+            assert!(!bpsw(&n));
+            assert!(!miller_rabin_deterministic(&n));
+        }
+        for n in LUCAS_PSEUDOPRIMES.map(Integer::from) {
+            assert!(is_strong_lucas_probable_prime(&n));
+            assert!(!bpsw(&n));
+        }
+
+        // 2^61 - 1 and the BLS12-381 scalar field modulus; 2^64 + 1 = 274177 * 67280421310721
+        assert!(bpsw(&Integer::from((1u64 << 61) - 1)));
+        let fr = Integer::from_hex_str(
+            "0x73eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000001",
+        );
+        assert!(bpsw(&fr));
+        assert!(miller_rabin(&fr, 10, &mut TestRng::new()));
+        let fermat_64 = (Integer::one() << 64u32) + 1u64;
+        assert!(!bpsw(&fermat_64));
+        assert!(!bpsw(&(fr.clone() * &fr)));
+    }
+
+    #[test]
+    fn test_jacobi_and_isqrt() {
+        let n = Integer::from;
+        // (a / 15) = (a / 3)(a / 5)
+        let expected = [0, 1, 1, 0, 1, 0, 0, -1, 1, 0, 0, -1, 0, -1, -1];
+        for (a, &j) in expected.iter().enumerate() {
+            assert_eq!(jacobi(&n(a as u64), &n(15)), j, "({a} / 15)");
+        }
+        for x in [0u64, 1, 2, 3, 4, 15, 16, 17, 1 << 40, (1 << 40) + 1] {
+            let r = isqrt(&n(x));
+            assert!(r.clone() * &r <= n(x));
+            assert!((r.clone() + 1u64) * &(r + 1u64) > n(x));
+        }
+    }
+}
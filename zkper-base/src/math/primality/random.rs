@@ -0,0 +1,128 @@
+//! Uniformly random primes and safe primes, for custom fields and RSA-style
+//! moduli.
+//!
+//! Candidates are drawn uniformly from the numbers of the requested size and
+//! rejected until one passes BPSW, so every prime of that size is equally
+//! likely. [`super::certificate`] generates primes with a proof instead, at
+//! the cost of that uniformity.
+
+use rand_core::RngCore;
+use zkper_integer::{traits::ZkperIntegerTrait, ZkperInteger};
+
+use super::{bpsw, errors::PrimalityError, is_strong_probable_prime, SMALL_PRIMES};
+
+/// A uniformly random prime of exactly `bits` bits.
+pub fn random_prime<T: ZkperIntegerTrait>(
+    bits: u32,
+    rng: &mut (impl RngCore + ?Sized),
+) -> anyhow::Result<ZkperInteger<T>> {
+    if bits < 2 {
+        return Err(PrimalityError::TooFewBits(bits).into());
+    }
+    let low = ZkperInteger::<T>::one() << (bits - 1);
+    loop {
+        let n = low.random_below(rng) + &low;
+        if bpsw(&n) {
+            return Ok(n);
+        }
+    }
+}
+
+/// A uniformly random safe prime p = 2q + 1, q prime, of exactly `bits` bits.
+pub fn random_safe_prime<T: ZkperIntegerTrait>(
+    bits: u32,
+    rng: &mut (impl RngCore + ?Sized),
+) -> anyhow::Result<ZkperInteger<T>> {
+    if bits < 3 {
+        return Err(PrimalityError::TooFewBits(bits).into());
+    }
+    // q has bits - 1 bits exactly when p has `bits`
+    let low = ZkperInteger::<T>::one() << (bits - 2);
+    loop {
+        let q = low.random_below(rng) + &low;
+        let p = q.clone() * 2u64 + 1u64;
+        // a small prime r divides p exactly when q = (r - 1) / 2 mod r
+        let sieved = SMALL_PRIMES.iter().any(|&r| {
+            let r = ZkperInteger::from(r);
+            (q.is_divisible(&r) && q != r) || (p.is_divisible(&r) && p != r)
+        });
+        if sieved {
+            continue;
+        }
+        // p is tested first, it fails base 2 far more cheaply than q fails BPSW
+        if is_strong_probable_prime(&p, &ZkperInteger::two()) && bpsw(&q) && bpsw(&p) {
+            return Ok(p);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use zkper_integer::{
+        backends::{rug_backend::RugBackend, u128_backend::U128Backend},
+        ZkperInteger,
+    };
+    use zkper_rand::TestRng;
+
+    use super::*;
+    use crate::math::primality::{bit_length, miller_rabin_deterministic};
+
+    #[test]
+    fn test_random_prime() {
+        let mut rng = TestRng::new();
+        for bits in [2, 3, 8, 32, 64, 128, 256, 512] {
+            let p = random_prime::<RugBackend>(bits, &mut rng).unwrap();
+            assert_eq!(bit_length(&p), bits);
+            assert!(p.is_prime());
+        }
+        for bits in [8, 32, 60] {
+            let p = random_prime::<U128Backend>(bits, &mut rng).unwrap();
+            assert_eq!(bit_length(&p), bits);
+            assert!(miller_rabin_deterministic(&p));
+        }
+    }
+
+    #[test]
+    fn test_random_safe_prime() {
+        let mut rng = TestRng::new();
+        for bits in [3, 4, 8, 32, 64, 128] {
+            let p = random_safe_prime::<RugBackend>(bits, &mut rng).unwrap();
+            assert_eq!(bit_length(&p), bits);
+            assert!(p.is_prime());
+            assert!(((p - 1u64) >> 1u32).is_prime());
+        }
+    }
+
+    #[test]
+    fn test_uniformity() {
+        // the 8-bit safe primes are 167, 179 and 227; each should come up about
+        // 200 times out of 600
+        let mut rng = TestRng::new();
+        let mut counts = HashMap::new();
+        for _ in 0..600 {
+            let p = random_safe_prime::<RugBackend>(8, &mut rng).unwrap();
+            *counts.entry(p).or_insert(0) += 1;
+        }
+        assert_eq!(counts.len(), 3);
+        for n in [167u64, 179, 227] {
+            let count = counts[&ZkperInteger::from(n)];
+            assert!((150..250).contains(&count), "{n} came up {count} times");
+        }
+    }
+
+    #[test]
+    fn test_too_few_bits() {
+        let mut rng = TestRng::new();
+        let err = |e: anyhow::Error| e.downcast::<PrimalityError>().unwrap();
+        assert_eq!(
+            err(random_prime::<RugBackend>(1, &mut rng).unwrap_err()),
+            PrimalityError::TooFewBits(1)
+        );
+        assert_eq!(
+            err(random_safe_prime::<RugBackend>(2, &mut rng).unwrap_err()),
+            PrimalityError::TooFewBits(2)
+        );
+    }
+}
@@ -0,0 +1,57 @@
+//! Debug wrappers for secret material.
+//!
+//! `Redacted` prints a constant marker instead of the value and
+//! `RedactedSlice` prints only the length, so toxic waste, witnesses and
+//! blinding factors never end up in logs. Enable the `debug-secrets` feature to
+//! print the wrapped values in full while debugging locally.
+
+use std::fmt;
+
+/// Shows `<redacted>` instead of the wrapped value.
+///
+/// No digest of the value is printed either: anyone reading the log could
+/// test guesses of a low-entropy secret against it.
+pub struct Redacted<T>(pub T);
+
+impl<T: fmt::Debug> fmt::Debug for Redacted<T> {
+    #[cfg(not(feature = "debug-secrets"))]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("<redacted>")
+    }
+
+    #[cfg(feature = "debug-secrets")]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// Shows only the number of elements of a secret slice.
+pub struct RedactedSlice<'a, T>(pub &'a [T]);
+
+impl<T: fmt::Debug> fmt::Debug for RedactedSlice<'_, T> {
+    #[cfg(not(feature = "debug-secrets"))]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "<{} redacted>", self.0.len())
+    }
+
+    #[cfg(feature = "debug-secrets")]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+#[cfg(all(test, not(feature = "debug-secrets")))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redacted_hides_value() {
+        let secret = 0x1234_5678_9abc_u64;
+        let shown = format!("{:?}", Redacted(&secret));
+        assert_eq!(shown, "<redacted>");
+        assert_eq!(format!("{:?}", Redacted(secret + 1)), shown);
+
+        let witness = vec![7u64, 11, 13];
+        assert_eq!(format!("{:?}", RedactedSlice(&witness)), "<3 redacted>");
+    }
+}
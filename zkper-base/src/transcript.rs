@@ -0,0 +1,297 @@
+//! Fiat–Shamir transcripts.
+//!
+//! A [`Transcript`] turns an interactive protocol into a non-interactive one:
+//! the prover and the verifier absorb the same messages in the same order and
+//! squeeze the same challenges out, which no party can steer without finding
+//! a collision of the hash.
+//!
+//! The hash is a [`TranscriptHash`], SHA-256 unless the protocol calls for
+//! another one. The state is a single digest, chained through every
+//! operation:
+//!
+//! ```text
+//! append:    state = H("append"    || state || len(label) || label || len(message) || message)
+//! challenge: state = H("challenge" || state || len(label) || label || len(output))
+//!            output block i = H("output" || state || i)
+//! ```
+//!
+//! Lengths are u64 and block counters u32, both big-endian. Every label and
+//! message is length-prefixed, so no two different sequences of operations
+//! hash the same input, and every challenge folds its own label and length
+//! into the state, so later challenges depend on all earlier ones.
+//!
+//! Types are absorbed through their [`Absorb`] encoding; zkper-curves
+//! implements it for field elements and points and squeezes scalars below the
+//! BLS12-381 scalar modulus on top of [`Transcript::challenge_bytes`].
+
+use core::{fmt, marker::PhantomData};
+
+use sha2::Digest;
+
+const APPEND_TAG: &[u8] = b"append";
+const CHALLENGE_TAG: &[u8] = b"challenge";
+const OUTPUT_TAG: &[u8] = b"output";
+
+/// A 32-byte digest.
+pub type Digest32 = [u8; 32];
+
+/// An incremental hash with a 32-byte output.
+pub trait TranscriptHash: Default {
+    /// Name of the hash, for error messages and file headers.
+    const NAME: &'static str;
+
+    fn update(&mut self, bytes: impl AsRef<[u8]>);
+
+    fn finalize(self) -> Digest32;
+
+    /// The digest of `bytes`.
+    fn digest(bytes: impl AsRef<[u8]>) -> Digest32 {
+        let mut hasher = Self::default();
+        hasher.update(bytes);
+        hasher.finalize()
+    }
+}
+
+/// SHA-256, the default.
+#[derive(Clone, Default)]
+pub struct Sha256Hash(sha2::Sha256);
+
+impl TranscriptHash for Sha256Hash {
+    const NAME: &'static str = "sha256";
+
+    fn update(&mut self, bytes: impl AsRef<[u8]>) {
+        self.0.update(bytes);
+    }
+
+    fn finalize(self) -> Digest32 {
+        self.0.finalize().into()
+    }
+}
+
+/// A value with a canonical byte encoding to absorb into a [`Transcript`].
+///
+/// Two values that are equal have to encode to the same bytes, and values that
+/// differ to different bytes, or the challenges stop binding them.
+pub trait Absorb {
+    fn transcript_bytes(&self) -> Vec<u8>;
+}
+
+impl Absorb for [u8] {
+    fn transcript_bytes(&self) -> Vec<u8> {
+        self.to_vec()
+    }
+}
+
+impl<const N: usize> Absorb for [u8; N] {
+    fn transcript_bytes(&self) -> Vec<u8> {
+        self.to_vec()
+    }
+}
+
+impl Absorb for Vec<u8> {
+    fn transcript_bytes(&self) -> Vec<u8> {
+        self.clone()
+    }
+}
+
+impl Absorb for u64 {
+    fn transcript_bytes(&self) -> Vec<u8> {
+        self.to_be_bytes().to_vec()
+    }
+}
+
+/// A hash chain over the messages of a protocol, hashed with `H`, see the
+/// module documentation.
+pub struct Transcript<H: TranscriptHash = Sha256Hash> {
+    state: Digest32,
+    _hash: PhantomData<H>,
+}
+
+impl Transcript {
+    /// A new SHA-256 transcript for the protocol named by `domain`.
+    /// Transcripts of different protocols never produce related challenges.
+    pub fn new(domain: &[u8]) -> Self {
+        Self::new_with(domain)
+    }
+}
+
+impl<H: TranscriptHash> Transcript<H> {
+    /// `new` over the hash `H`.
+    pub fn new_with(domain: &[u8]) -> Self {
+        let mut transcript = Self {
+            state: [0; 32],
+            _hash: PhantomData,
+        };
+        transcript.append_message(b"domain", domain);
+        transcript
+    }
+
+    /// Absorbs `message` under `label`.
+    pub fn append_message(&mut self, label: &[u8], message: &[u8]) {
+        let mut hasher = self.chain(APPEND_TAG, label);
+        hasher.update((message.len() as u64).to_be_bytes());
+        hasher.update(message);
+        self.state = hasher.finalize();
+    }
+
+    /// Absorbs the canonical encoding of `value` under `label`.
+    pub fn append<T: Absorb + ?Sized>(&mut self, label: &[u8], value: &T) {
+        self.append_message(label, &value.transcript_bytes());
+    }
+
+    /// Absorbs every value of `values` under `label`, prefixed by their count.
+    pub fn append_all<T: Absorb>(&mut self, label: &[u8], values: &[T]) {
+        self.append(label, &(values.len() as u64));
+        for value in values {
+            self.append(label, value);
+        }
+    }
+
+    /// Fills `output` with a challenge derived from everything absorbed so
+    /// far, `label` and the length of `output`.
+    pub fn challenge_bytes(&mut self, label: &[u8], output: &mut [u8]) {
+        let mut hasher = self.chain(CHALLENGE_TAG, label);
+        hasher.update((output.len() as u64).to_be_bytes());
+        self.state = hasher.finalize();
+
+        for (i, block) in output.chunks_mut(32).enumerate() {
+            let mut hasher = H::default();
+            hasher.update(OUTPUT_TAG);
+            hasher.update(self.state);
+            hasher.update((i as u32).to_be_bytes());
+            block.copy_from_slice(&hasher.finalize()[..block.len()]);
+        }
+    }
+
+    /// A challenge of `N` bytes, see `challenge_bytes`.
+    pub fn challenge_array<const N: usize>(&mut self, label: &[u8]) -> [u8; N] {
+        let mut output = [0; N];
+        self.challenge_bytes(label, &mut output);
+        output
+    }
+
+    fn chain(&self, tag: &[u8], label: &[u8]) -> H {
+        let mut hasher = H::default();
+        hasher.update(tag);
+        hasher.update(self.state);
+        hasher.update((label.len() as u64).to_be_bytes());
+        hasher.update(label);
+        hasher
+    }
+}
+
+// by hand, so that `H` needs none of these traits itself
+
+impl<H: TranscriptHash> Clone for Transcript<H> {
+    fn clone(&self) -> Self {
+        Self {
+            state: self.state,
+            _hash: PhantomData,
+        }
+    }
+}
+
+impl<H: TranscriptHash> PartialEq for Transcript<H> {
+    fn eq(&self, other: &Self) -> bool {
+        self.state == other.state
+    }
+}
+
+impl<H: TranscriptHash> Eq for Transcript<H> {}
+
+impl<H: TranscriptHash> fmt::Debug for Transcript<H> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Transcript")
+            .field("hash", &H::NAME)
+            .field("state", &self.state)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transcript() {
+        let run = |messages: &[(&[u8], &[u8])]| {
+            let mut transcript = Transcript::new(b"test");
+            for (label, message) in messages {
+                transcript.append_message(label, message);
+            }
+            transcript.challenge_array::<64>(b"challenge")
+        };
+
+        // deterministic
+        let challenge = run(&[(b"a", b"1"), (b"b", b"2")]);
+        assert_eq!(challenge, run(&[(b"a", b"1"), (b"b", b"2")]));
+
+        // order, labels and the split between label and message all count
+        assert_ne!(challenge, run(&[(b"b", b"2"), (b"a", b"1")]));
+        assert_ne!(challenge, run(&[(b"a", b"1"), (b"c", b"2")]));
+        assert_ne!(challenge, run(&[(b"a1", b""), (b"b", b"2")]));
+        assert_ne!(challenge, run(&[(b"a", b"1")]));
+
+        // the domain separates protocols
+        let mut other = Transcript::new(b"other");
+        other.append_message(b"a", b"1");
+        other.append_message(b"b", b"2");
+        assert_ne!(challenge, other.challenge_array::<64>(b"challenge"));
+
+        // a challenge moves the state, so the next one differs
+        let mut transcript = Transcript::new(b"test");
+        let first = transcript.challenge_array::<32>(b"c");
+        let second = transcript.challenge_array::<32>(b"c");
+        assert_ne!(first, second);
+
+        // a shorter challenge is not a prefix of a longer one
+        let mut short = Transcript::new(b"test");
+        let mut long = short.clone();
+        let short = short.challenge_array::<16>(b"c");
+        let long = long.challenge_array::<32>(b"c");
+        assert_ne!(short[..], long[..16]);
+
+        // typed values absorb their encoding
+        let mut typed = Transcript::new(b"test");
+        let mut raw = typed.clone();
+        typed.append(b"n", &7u64);
+        raw.append_message(b"n", &7u64.to_be_bytes());
+        assert_eq!(typed, raw);
+    }
+
+    #[derive(Default)]
+    struct XorHash(Digest32);
+
+    impl TranscriptHash for XorHash {
+        const NAME: &'static str = "xor";
+
+        fn update(&mut self, bytes: impl AsRef<[u8]>) {
+            for (i, byte) in bytes.as_ref().iter().enumerate() {
+                self.0[i % 32] ^= byte;
+            }
+        }
+
+        fn finalize(self) -> Digest32 {
+            self.0
+        }
+    }
+
+    #[test]
+    fn test_transcript_hash() {
+        let run = |transcript: &mut Transcript<_>| {
+            transcript.append_message(b"a", b"1");
+            transcript.challenge_array::<32>(b"c")
+        };
+        let sha256 = run(&mut Transcript::new(b"test"));
+        assert_eq!(
+            sha256,
+            run(&mut Transcript::<Sha256Hash>::new_with(b"test"))
+        );
+
+        // the chain runs over the chosen hash
+        let mut xor = Transcript::<XorHash>::new_with(b"test");
+        xor.append_message(b"a", b"1");
+        assert_ne!(xor.challenge_array::<32>(b"c"), sha256);
+        assert!(format!("{:?}", xor).contains("xor"));
+    }
+}
@@ -0,0 +1,37 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use zkper_curves::curves::bls12_381::{fields::fp2::Fp2, BLS12_381_BASE};
+use zkper_rand::TestRng;
+
+fn bench_fp2(c: &mut Criterion) {
+    let mut rng = TestRng::new();
+    let a = Fp2::random(&mut rng);
+    let b = Fp2::random(&mut rng);
+
+    c.bench_function("fp2_mul/karatsuba", |bench| {
+        bench.iter(|| black_box(&a).mul(black_box(&b)))
+    });
+    c.bench_function("fp2_mul/schoolbook", |bench| {
+        bench.iter(|| black_box(&a).mul_schoolbook(black_box(&b)))
+    });
+    c.bench_function("fp2_square/lazy", |bench| {
+        bench.iter(|| black_box(&a).square())
+    });
+    c.bench_function("fp2_square/schoolbook", |bench| {
+        bench.iter(|| black_box(&a).square_schoolbook())
+    });
+}
+
+fn bench_fp_pow(c: &mut Criterion) {
+    let mut rng = TestRng::new();
+    let backend = &*BLS12_381_BASE;
+    let base = backend.to_montgomery(&backend.sample_raw(&mut rng));
+    // the exponent of inversion by Fermat
+    let exp = backend.modulus() - 2u32;
+
+    c.bench_function("fp_mont_pow/p-2", |bench| {
+        bench.iter(|| backend.mont_pow(black_box(&base), black_box(&exp)))
+    });
+}
+
+criterion_group!(benches, bench_fp2, bench_fp_pow);
+criterion_main!(benches);
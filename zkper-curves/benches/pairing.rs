@@ -0,0 +1,42 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use zkper_curves::curves::bls12_381::{
+    curves::{g1::G1Projective, g1_affine::G1Affine, g2::G2Projective, g2_affine::G2Affine},
+    paring::{BLS12_381Pairing, G2Prepared},
+};
+use zkper_rand::TestRng;
+
+fn bench_pairing(c: &mut Criterion) {
+    let mut rng = TestRng::new();
+    let p: G1Affine = G1Projective::random(&mut rng).to_affine();
+    let q: G2Affine = G2Projective::random(&mut rng).to_affine();
+    let pairs: Vec<(G1Affine, G2Affine)> = (0..3)
+        .map(|_| {
+            (
+                G1Projective::random(&mut rng).to_affine(),
+                G2Projective::random(&mut rng).to_affine(),
+            )
+        })
+        .collect();
+    let pair_refs: Vec<(&G1Affine, &G2Affine)> = pairs.iter().map(|(p, q)| (p, q)).collect();
+    let f = BLS12_381Pairing::miller_loop(&p, &q);
+
+    c.bench_function("miller_loop", |b| {
+        b.iter(|| BLS12_381Pairing::miller_loop(black_box(&p), black_box(&q)))
+    });
+    let prepared = G2Prepared::from(&q);
+    c.bench_function("miller_loop_prepared", |b| {
+        b.iter(|| BLS12_381Pairing::miller_loop_prepared(black_box(&p), black_box(&prepared)))
+    });
+    c.bench_function("multi_miller_loop/3", |b| {
+        b.iter(|| BLS12_381Pairing::multi_miller_loop(black_box(&pair_refs)))
+    });
+    c.bench_function("final_exponentiation", |b| {
+        b.iter(|| BLS12_381Pairing::final_exponentiation(black_box(&f)))
+    });
+    c.bench_function("pairing", |b| {
+        b.iter(|| BLS12_381Pairing::pairing(black_box(&p), black_box(&q)))
+    });
+}
+
+criterion_group!(benches, bench_pairing);
+criterion_main!(benches);
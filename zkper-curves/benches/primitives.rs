@@ -0,0 +1,81 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use rug::Integer;
+use zkper_curves::{
+    curves::bls12_381::{
+        curves::{g1::G1Projective, g2::G2Projective},
+        fields::{fp12::Fp12, fp2::Fp2},
+        Bls12_381ScalarField, Scalar, BLS12_381_BASE, BLS12_381_SCALAR,
+    },
+    traits::field::FieldTrait,
+};
+use zkper_rand::TestRng;
+
+fn bench_fields(c: &mut Criterion) {
+    let mut rng = TestRng::new();
+    let base = &*BLS12_381_BASE;
+    let scalar = &*BLS12_381_SCALAR;
+    let (a, b) = (base.sample_raw(&mut rng), base.sample_raw(&mut rng));
+    let (am, bm) = (base.to_montgomery(&a), base.to_montgomery(&b));
+    let (x, y) = (scalar.sample_mont(&mut rng), scalar.sample_mont(&mut rng));
+
+    c.bench_function("fp_mont_mul", |bench| {
+        bench.iter(|| base.mont_mul(black_box(&am), black_box(&bm)))
+    });
+    c.bench_function("fr_mont_mul", |bench| {
+        bench.iter(|| scalar.mont_mul(black_box(&x), black_box(&y)))
+    });
+    c.bench_function("fp_invert", |bench| {
+        bench.iter(|| base.invert(black_box(a.clone())))
+    });
+    c.bench_function("fr_invert", |bench| {
+        bench.iter(|| scalar.invert(black_box(x.clone())))
+    });
+    let f = Fp2::random(&mut rng);
+    c.bench_function("fp2_invert", |bench| bench.iter(|| black_box(&f).invert()));
+
+    let (f, g) = (Fp12::random(&mut rng), Fp12::random(&mut rng));
+    c.bench_function("fp12_mul", |bench| {
+        bench.iter(|| black_box(&f).mul(black_box(&g)))
+    });
+    c.bench_function("fp12_square", |bench| bench.iter(|| black_box(&f).square()));
+    c.bench_function("fp12_invert", |bench| bench.iter(|| black_box(&f).invert()));
+}
+
+fn bench_groups(c: &mut Criterion) {
+    let mut rng = TestRng::new();
+    let k = Scalar::from(Bls12_381ScalarField::random(&mut rng));
+    let small = Scalar::from(Integer::from(u64::MAX));
+
+    let (p, q) = (
+        G1Projective::random(&mut rng),
+        G1Projective::random(&mut rng),
+    );
+    c.bench_function("g1_double", |bench| bench.iter(|| black_box(&p).double()));
+    c.bench_function("g1_add", |bench| {
+        bench.iter(|| black_box(&p).add(black_box(&q)))
+    });
+    c.bench_function("g1_mul_scalar", |bench| {
+        bench.iter(|| black_box(&p).mul_scalar(black_box(&k)))
+    });
+    c.bench_function("g1_mul_scalar/64_bit", |bench| {
+        bench.iter(|| black_box(&p).mul_scalar(black_box(&small)))
+    });
+
+    let (p, q) = (
+        G2Projective::random(&mut rng),
+        G2Projective::random(&mut rng),
+    );
+    c.bench_function("g2_double", |bench| bench.iter(|| black_box(&p).double()));
+    c.bench_function("g2_add", |bench| {
+        bench.iter(|| black_box(&p).add(black_box(&q)))
+    });
+    c.bench_function("g2_mul_scalar", |bench| {
+        bench.iter(|| black_box(&p).mul_scalar(black_box(&k)))
+    });
+    c.bench_function("g2_mul_scalar/gls", |bench| {
+        bench.iter(|| black_box(&p).mul_scalar_gls(black_box(&k)))
+    });
+}
+
+criterion_group!(benches, bench_fields, bench_groups);
+criterion_main!(benches);
@@ -0,0 +1,175 @@
+//! Emits BLS12-381 test vectors as JSON for checking other implementations
+//! against this crate.
+//!
+//! Usage: `cargo run -p zkper-curves --example test_vectors -- [--seed N] [--count N]`
+//!
+//! Inputs are drawn from a seeded `TestRng`, whose output does not depend on
+//! the version of `rand`, so a given crate version and seed always produce the
+//! same fixture. Field elements are big-endian hex,
+//! curve points use the uncompressed encodings of `G1Affine`/`G2Affine`, and a
+//! pairing result lists its twelve Fp coefficients from c0.c0.c0 to c1.c2.c1.
+
+use rug::{integer::Order, Integer};
+use serde_json::{json, Value};
+use zkper_curves::{
+    curves::bls12_381::{
+        curves::{g1::G1Projective, g2::G2Projective},
+        fields::{base::BASE_FIELD_BYTES, fp12::Fp12},
+        paring::BLS12_381Pairing,
+        Bls12_381BaseField, Bls12_381ScalarField, Scalar, BLS12_381_BASE, BLS12_381_SCALAR,
+    },
+    traits::field::FieldTrait,
+};
+use zkper_rand::TestRng;
+
+const SCALAR_BYTES: usize = 32;
+
+fn hex(bytes: &[u8]) -> String {
+    let digits: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+    format!("0x{}", digits)
+}
+
+fn int_hex(value: &Integer, width: usize) -> String {
+    let digits = value.to_digits::<u8>(Order::Msf);
+    let mut bytes = vec![0u8; width];
+    bytes[width - digits.len()..].copy_from_slice(&digits);
+    hex(&bytes)
+}
+
+fn fp12_hex(value: &Fp12) -> Vec<String> {
+    [&value.c0, &value.c1]
+        .into_iter()
+        .flat_map(|c| [&c.c0, &c.c1, &c.c2])
+        .flat_map(|c| [&c.c0, &c.c1])
+        .map(|c| int_hex(c, BASE_FIELD_BYTES))
+        .collect()
+}
+
+fn scalar_field(rng: &mut TestRng, count: usize) -> Vec<Value> {
+    let fr = |x: &Integer| int_hex(x, SCALAR_BYTES);
+    (0..count)
+        .map(|_| {
+            let a = Bls12_381ScalarField::random(rng);
+            let b = Bls12_381ScalarField::random(rng);
+            json!({
+                "a": fr(&a),
+                "b": fr(&b),
+                "add": fr(&BLS12_381_SCALAR.add(a.clone(), &b)),
+                "sub": fr(&BLS12_381_SCALAR.sub(a.clone(), &b)),
+                "mul": fr(&BLS12_381_SCALAR.mul(a.clone(), &b)),
+                "inv_a": BLS12_381_SCALAR.invert(a).map(|x| fr(&x)),
+            })
+        })
+        .collect()
+}
+
+fn base_field(rng: &mut TestRng, count: usize) -> Vec<Value> {
+    let fq = |x: &Integer| int_hex(x, BASE_FIELD_BYTES);
+    (0..count)
+        .map(|_| {
+            let a = Bls12_381BaseField::random(rng);
+            let b = Bls12_381BaseField::random(rng);
+            json!({
+                "a": fq(&a),
+                "b": fq(&b),
+                "add": fq(&BLS12_381_BASE.add(a.clone(), &b)),
+                "sub": fq(&BLS12_381_BASE.sub(a.clone(), &b)),
+                "mul": fq(&BLS12_381_BASE.mul(a.clone(), &b)),
+                "inv_a": BLS12_381_BASE.invert(a.clone()).map(|x| fq(&x)),
+                "sqrt_a": BLS12_381_BASE.sqrt(a).map(|x| fq(&x)),
+            })
+        })
+        .collect()
+}
+
+fn g1(rng: &mut TestRng, count: usize) -> Vec<Value> {
+    let point = |p: &G1Projective| hex(&p.to_affine().to_uncompressed());
+    (0..count)
+        .map(|_| {
+            let p = G1Projective::random(rng);
+            let q = G1Projective::random(rng);
+            let k = Scalar::from(Bls12_381ScalarField::random(rng));
+            json!({
+                "p": point(&p),
+                "q": point(&q),
+                "k": int_hex(&k.0, SCALAR_BYTES),
+                "add": point(&p.add(&q)),
+                "double": point(&p.double()),
+                "neg": point(&p.neg()),
+                "mul": point(&p.mul_scalar(&k)),
+            })
+        })
+        .collect()
+}
+
+fn g2(rng: &mut TestRng, count: usize) -> Vec<Value> {
+    let point = |p: &G2Projective| hex(&p.to_affine().to_uncompressed());
+    (0..count)
+        .map(|_| {
+            let p = G2Projective::random(rng);
+            let q = G2Projective::random(rng);
+            let k = Scalar::from(Bls12_381ScalarField::random(rng));
+            json!({
+                "p": point(&p),
+                "q": point(&q),
+                "k": int_hex(&k.0, SCALAR_BYTES),
+                "add": point(&p.add(&q)),
+                "double": point(&p.double()),
+                "neg": point(&p.neg()),
+                "mul": point(&p.mul_scalar(&k)),
+            })
+        })
+        .collect()
+}
+
+fn pairing(rng: &mut TestRng, count: usize) -> Vec<Value> {
+    (0..count)
+        .map(|_| {
+            let p = G1Projective::random(rng).to_affine();
+            let q = G2Projective::random(rng).to_affine();
+            let e = BLS12_381Pairing::pairing(&p, &q);
+            json!({
+                "p": hex(&p.to_uncompressed()),
+                "q": hex(&q.to_uncompressed()),
+                "e": fp12_hex(&e.0),
+            })
+        })
+        .collect()
+}
+
+fn main() {
+    let mut seed = 0u64;
+    let mut count = 4usize;
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let value = args.next();
+        let parsed = value.as_deref().and_then(|v| v.parse::<u64>().ok());
+        match (arg.as_str(), parsed) {
+            ("--seed", Some(v)) => seed = v,
+            ("--count", Some(v)) => count = v as usize,
+            _ => {
+                eprintln!("usage: test_vectors [--seed N] [--count N]");
+                std::process::exit(2);
+            }
+        }
+    }
+
+    let mut rng = TestRng::from_seed(seed);
+    let vectors = json!({
+        "crate": env!("CARGO_PKG_NAME"),
+        "version": env!("CARGO_PKG_VERSION"),
+        "curve": "BLS12-381",
+        "seed": seed,
+        "scalar_field": scalar_field(&mut rng, count),
+        "base_field": base_field(&mut rng, count),
+        "g1": g1(&mut rng, count),
+        "g2": g2(&mut rng, count),
+        "pairing": pairing(&mut rng, count),
+    });
+
+    println!(
+        "{}",
+        serde_json::to_string_pretty(&vectors).expect("JSON values always serialize")
+    );
+}
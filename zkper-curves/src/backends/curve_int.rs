@@ -0,0 +1,170 @@
+//! The integer type of the curve layer and its bridge to zkper-integer.
+//!
+//! Field and curve arithmetic is written against [`CurveInt`], today an alias
+//! of `rug::Integer`. Moving it onto a [`ZkperIntegerTrait`] backend happens
+//! one operation at a time: [`ModularOps`] expresses the modular arithmetic of
+//! [`MontgomeryBackend`] over any backend, and the tests below run it against
+//! the rug path on random field elements, so a backend can be swapped in once
+//! it agrees everywhere.
+//!
+//! [`CurveIntBackend`] is the backend the curve layer is routed through, rug's
+//! until another one agrees everywhere. The native backend is still a single
+//! 32-bit word, so it only runs the differential tests over small moduli.
+
+use rug::{integer::Order, Integer};
+use zkper_integer::{traits::ZkperIntegerTrait, ZkperInteger};
+
+use super::montgomery::MontgomeryBackend;
+
+/// The integer type of field elements and point coordinates.
+pub type CurveInt = Integer;
+
+/// The zkper-integer backend the curve layer is routed through.
+pub type CurveIntBackend = zkper_integer::backends::rug_backend::RugBackend;
+
+/// Converts a non-negative `value` to backend `T`, or None if `T` cannot
+/// represent it.
+pub fn to_zkper<T: ZkperIntegerTrait>(value: &CurveInt) -> Option<ZkperInteger<T>> {
+    if *value < 0 {
+        return None;
+    }
+
+    // little-endian, padded so fixed-width backends find every byte they read
+    let mut bytes = value.to_digits::<u8>(Order::Lsf);
+    bytes.resize(bytes.len().max(8), 0);
+    let converted = ZkperInteger::<T>::from_bytes(&bytes);
+
+    (from_zkper(&converted) == *value).then_some(converted)
+}
+
+/// Converts a non-negative value of backend `T` back.
+pub fn from_zkper<T: ZkperIntegerTrait>(value: &ZkperInteger<T>) -> CurveInt {
+    CurveInt::from_digits(&value.to_bytes(), Order::Lsf)
+}
+
+/// The modular arithmetic of [`MontgomeryBackend`] over backend `T`, on
+/// canonical representatives in [0, p).
+#[derive(Clone, Debug)]
+pub struct ModularOps<T: ZkperIntegerTrait> {
+    modulus: ZkperInteger<T>,
+}
+
+impl<T: ZkperIntegerTrait> ModularOps<T> {
+    /// None if `T` cannot represent `modulus`.
+    pub fn new(modulus: &CurveInt) -> Option<Self> {
+        Some(Self {
+            modulus: to_zkper(modulus)?,
+        })
+    }
+
+    pub fn modulus(&self) -> &ZkperInteger<T> {
+        &self.modulus
+    }
+
+    pub fn add(&self, a: &ZkperInteger<T>, b: &ZkperInteger<T>) -> ZkperInteger<T> {
+        self.reduce(&a.additive(b))
+    }
+
+    /// a + (p - b), which never goes below zero on unsigned backends.
+    pub fn sub(&self, a: &ZkperInteger<T>, b: &ZkperInteger<T>) -> ZkperInteger<T> {
+        self.reduce(&a.additive(&self.modulus.subtract(b)))
+    }
+
+    pub fn mul(&self, a: &ZkperInteger<T>, b: &ZkperInteger<T>) -> ZkperInteger<T> {
+        self.reduce(&a.multiply(b))
+    }
+
+    pub fn square(&self, a: &ZkperInteger<T>) -> ZkperInteger<T> {
+        self.mul(a, a)
+    }
+
+    pub fn pow(&self, a: &ZkperInteger<T>, exponent: &ZkperInteger<T>) -> ZkperInteger<T> {
+        a.pow_mod(exponent, &self.modulus)
+    }
+
+    pub fn invert(&self, a: &ZkperInteger<T>) -> Option<ZkperInteger<T>> {
+        a.invert(&self.modulus).ok()
+    }
+
+    fn reduce(&self, a: &ZkperInteger<T>) -> ZkperInteger<T> {
+        a.reminder(&self.modulus)
+    }
+}
+
+impl MontgomeryBackend {
+    /// The modular arithmetic of this field over backend `T`, or None if `T`
+    /// cannot represent the modulus.
+    pub fn modular_ops<T: ZkperIntegerTrait>(&self) -> Option<ModularOps<T>> {
+        ModularOps::new(self.modulus_ref())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zkper_rand::TestRng;
+
+    use super::*;
+    use crate::curves::bls12_381::{BLS12_381_BASE, BLS12_381_SCALAR};
+
+    /// Runs every operation of `ops` against `field` on random elements.
+    fn check_differential<T: ZkperIntegerTrait>(field: &MontgomeryBackend, rounds: usize) {
+        let ops = field.modular_ops::<T>().unwrap();
+        let mut rng = TestRng::new();
+        let lift = |value: &CurveInt| to_zkper::<T>(value).unwrap();
+
+        for _ in 0..rounds {
+            let a = field.sample_raw(&mut rng);
+            let b = field.sample_raw(&mut rng);
+            let (za, zb) = (lift(&a), lift(&b));
+
+            assert_eq!(from_zkper(&ops.add(&za, &zb)), field.add(a.clone(), &b));
+            assert_eq!(from_zkper(&ops.sub(&za, &zb)), field.sub(a.clone(), &b));
+            assert_eq!(from_zkper(&ops.mul(&za, &zb)), field.mul(a.clone(), &b));
+            assert_eq!(from_zkper(&ops.square(&za)), field.square(a.clone()));
+            assert_eq!(
+                from_zkper(&ops.pow(&za, &zb)),
+                a.clone().pow_mod(&b, field.modulus_ref()).unwrap()
+            );
+            assert_eq!(
+                ops.invert(&za).map(|inv| from_zkper(&inv)),
+                field.invert(a.clone())
+            );
+
+            // and through Montgomery form
+            let mont = field.mont_mul(&field.to_montgomery(&a), &field.to_montgomery(&b));
+            assert_eq!(from_zkper(&ops.mul(&za, &zb)), field.from_montgomery(&mont));
+        }
+    }
+
+    #[test]
+    fn test_conversions() {
+        let value = BLS12_381_BASE.modulus_ref().clone() - 1u32;
+        let converted = to_zkper::<CurveIntBackend>(&value);
+        assert_eq!(from_zkper(&converted.unwrap()), value);
+
+        assert!(to_zkper::<CurveIntBackend>(&CurveInt::from(-1)).is_none());
+        for small in [0u32, 1, 0xff, 0xffff_ffff] {
+            let small = CurveInt::from(small);
+            assert_eq!(
+                from_zkper(&to_zkper::<CurveIntBackend>(&small).unwrap()),
+                small
+            );
+        }
+    }
+
+    #[test]
+    fn test_rug_backend_differential() {
+        check_differential::<CurveIntBackend>(&BLS12_381_BASE, 32);
+        check_differential::<CurveIntBackend>(&BLS12_381_SCALAR, 32);
+    }
+
+    #[test]
+    fn test_native_backend_differential() {
+        use zkper_integer::backends::u32_backend::U32Backed;
+
+        // products of two elements have to fit in 32 bits
+        let field = MontgomeryBackend::new(CurveInt::from(65521), 1);
+        check_differential::<U32Backed>(&field, 256);
+        assert!(BLS12_381_BASE.modular_ops::<U32Backed>().is_none());
+    }
+}
@@ -0,0 +1,115 @@
+//! Compile-time parsing of hex constants into little-endian `u64` limbs.
+//!
+//! Used through the `fp!` and `fr!` macros so that a typo in a curve constant
+//! is a compile error instead of a panic on first use.
+
+#[cfg(feature = "backend-rug")]
+use rug::Integer;
+
+/// Parses a hex literal (optional `0x` prefix, `_` separators allowed) into
+/// `N` little-endian limbs. Panics, at compile time in const context, on an
+/// invalid digit or a value that does not fit.
+pub const fn from_hex<const N: usize>(hex: &str) -> [u64; N] {
+    let bytes = hex.as_bytes();
+    let start = if bytes.len() >= 2 && bytes[0] == b'0' && (bytes[1] == b'x' || bytes[1] == b'X') {
+        2
+    } else {
+        0
+    };
+    assert!(start < bytes.len(), "empty hex literal");
+
+    let mut limbs = [0u64; N];
+    let mut bit = 0;
+    let mut i = bytes.len();
+    while i > start {
+        i -= 1;
+        if bytes[i] == b'_' {
+            continue;
+        }
+
+        let digit = match bytes[i] {
+            b'0'..=b'9' => bytes[i] - b'0',
+            b'a'..=b'f' => bytes[i] - b'a' + 10,
+            b'A'..=b'F' => bytes[i] - b'A' + 10,
+            _ => panic!("invalid hex digit"),
+        } as u64;
+
+        if bit >= 64 * N {
+            assert!(digit == 0, "hex literal does not fit in the limbs");
+        } else {
+            limbs[bit / 64] |= digit << (bit % 64);
+        }
+        bit += 4;
+    }
+
+    limbs
+}
+
+/// a < b on little-endian limbs.
+pub const fn lt<const N: usize>(a: &[u64; N], b: &[u64; N]) -> bool {
+    let mut i = N;
+    while i > 0 {
+        i -= 1;
+        if a[i] != b[i] {
+            return a[i] < b[i];
+        }
+    }
+    false
+}
+
+/// Like `from_hex`, additionally requiring the value to be below `modulus`.
+pub const fn from_hex_below<const N: usize>(hex: &str, modulus: &[u64; N]) -> [u64; N] {
+    let limbs = from_hex::<N>(hex);
+    assert!(
+        lt(&limbs, modulus),
+        "constant is not reduced modulo the field modulus"
+    );
+    limbs
+}
+
+/// Converts little-endian limbs into an `Integer`.
+#[cfg(feature = "backend-rug")]
+pub fn to_integer(limbs: &[u64]) -> Integer {
+    Integer::from_digits(limbs, rug::integer::Order::Lsf)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_hex() {
+        const LIMBS: [u64; 2] = from_hex("0x1_0000000000000002");
+        assert_eq!(LIMBS, [2, 1]);
+
+        #[cfg(feature = "backend-rug")]
+        {
+            let hex = "73eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000001";
+            let limbs = from_hex::<4>(hex);
+            assert_eq!(
+                to_integer(&limbs),
+                Integer::from_str_radix(hex, 16).unwrap()
+            );
+        }
+        assert_eq!(from_hex::<4>("0x00ff"), [0xff, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_lt() {
+        assert!(lt(&[5, 1], &[0, 2]));
+        assert!(!lt(&[0, 2], &[5, 1]));
+        assert!(!lt(&[3, 3], &[3, 3]));
+    }
+
+    #[test]
+    #[should_panic(expected = "does not fit")]
+    fn test_from_hex_overflow() {
+        from_hex::<1>("0x10000000000000000");
+    }
+
+    #[test]
+    #[should_panic(expected = "not reduced")]
+    fn test_from_hex_below() {
+        from_hex_below::<1>("0x11", &[0x11]);
+    }
+}
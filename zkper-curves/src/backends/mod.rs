@@ -1 +1,7 @@
+#[cfg(feature = "backend-rug")]
+pub mod curve_int;
+pub mod limbs;
+#[cfg(feature = "backend-rug")]
 pub mod montgomery;
+#[cfg(feature = "backend-rug")]
+pub mod sampling;
@@ -1,53 +1,56 @@
-use num_traits::identities::One;
-use num_traits::Pow;
+pub mod rns;
+
+use super::{curve_int::CurveInt, sampling::sample_below};
 use rand_core::RngCore;
 use rug::integer::BorrowInteger;
 use rug::integer::MiniInteger;
-use rug::Integer;
+use std::borrow::Cow;
 use std::ops::Mul;
 use std::ops::Rem;
+use zkper_integer::pow::sliding_window_pow;
 
 pub trait MontgomeryExt {
-    fn from_montgomery_backend(&self, backend: &MontgomeryBackend) -> Integer;
+    #[allow(clippy::wrong_self_convention)]
+    fn from_montgomery_backend(&self, backend: &MontgomeryBackend) -> CurveInt;
 }
 
-impl MontgomeryExt for Integer {
-    fn from_montgomery_backend(&self, backend: &MontgomeryBackend) -> Integer {
+impl MontgomeryExt for CurveInt {
+    fn from_montgomery_backend(&self, backend: &MontgomeryBackend) -> CurveInt {
         backend.from_montgomery(self)
     }
 }
 
-pub const INTEGER_TWO: &'static Integer = {
+pub const INTEGER_TWO: &CurveInt = {
     const MINI: MiniInteger = MiniInteger::const_from_u8(2);
     const BORROW: BorrowInteger = MINI.borrow();
     BorrowInteger::const_deref(&BORROW)
 };
 
-pub const INTEGER_THREE: &'static Integer = {
+pub const INTEGER_THREE: &CurveInt = {
     const MINI: MiniInteger = MiniInteger::const_from_u8(3);
     const BORROW: BorrowInteger = MINI.borrow();
     BorrowInteger::const_deref(&BORROW)
 };
 
-pub const INTEGER_FOUR: &'static Integer = {
+pub const INTEGER_FOUR: &CurveInt = {
     const MINI: MiniInteger = MiniInteger::const_from_u8(4);
     const BORROW: BorrowInteger = MINI.borrow();
     BorrowInteger::const_deref(&BORROW)
 };
 
-pub const INTEGER_SEVEN: &'static Integer = {
+pub const INTEGER_SEVEN: &CurveInt = {
     const MINI: MiniInteger = MiniInteger::const_from_u8(7);
     const BORROW: BorrowInteger = MINI.borrow();
     BorrowInteger::const_deref(&BORROW)
 };
 
-pub const INTEGER_EIGHT: &'static Integer = {
+pub const INTEGER_EIGHT: &CurveInt = {
     const MINI: MiniInteger = MiniInteger::const_from_u8(8);
     const BORROW: BorrowInteger = MINI.borrow();
     BorrowInteger::const_deref(&BORROW)
 };
 
-pub const INTEGER_TWELVE: &'static Integer = {
+pub const INTEGER_TWELVE: &CurveInt = {
     const MINI: MiniInteger = MiniInteger::const_from_u8(12);
     const BORROW: BorrowInteger = MINI.borrow();
     BorrowInteger::const_deref(&BORROW)
@@ -55,36 +58,46 @@ pub const INTEGER_TWELVE: &'static Integer = {
 
 pub struct MontgomeryBackend {
     /// The modulus of the field.
-    pub modulus: Integer,
+    pub modulus: CurveInt,
     /// R = 2^(NUM_LIMBS*64) mod MODULUS
-    pub r: Integer, // one
+    pub r: CurveInt, // one
     /// R2 = R^2 mod MODULUS
-    pub r2: Integer,
+    pub r2: CurveInt,
 
     /// R_INV = R^(-1) mod MODULUS
-    pub r_inv: Integer,
+    pub r_inv: CurveInt,
     /// R3 = R^3 mod MODULUS
-    pub r3: Integer,
+    pub r3: CurveInt,
 
     /// INV = -MODULUS^{-1} mod 2^64
-    pub inv: Integer,
+    pub inv: CurveInt,
 
     /// (modulus + 1) / 4 if modulus % 4 == 3
-    pub modulus_plus_one_div_four: Option<Integer>,
+    pub modulus_plus_one_div_four: Option<CurveInt>,
 
     /// for fp2 sqrt (modulus - 3) / 4 if modulus % 4 == 3. ref: https://eprint.iacr.org/2012/685.pdf algorithm 9
-    pub fp2_sqrt_constant1: Option<Integer>,
-    pub fp2_sqrt_constant2: Option<Integer>,
+    pub fp2_sqrt_constant1: Option<CurveInt>,
+    pub fp2_sqrt_constant2: Option<CurveInt>,
+
+    /// two-adicity s of the modulus: modulus - 1 = t * 2^s with t odd
+    pub two_adicity: u32,
+    /// (t - 1) / 2 for the odd part t of modulus - 1
+    pub trace_minus_one_div_two: CurveInt,
+    /// z^t for the least quadratic non-residue z, a primitive 2^s-th root of
+    /// unity used by Tonelli–Shanks. None if no non-residue was found.
+    pub two_adic_root_of_unity: Option<CurveInt>,
+    /// (modulus - 5) / 8 if modulus % 8 == 5, for Atkin's square root
+    pub modulus_minus_five_div_eight: Option<CurveInt>,
 
     /// montgomery form of 3b
-    pub three_b_mont: Integer,
+    pub three_b_mont: CurveInt,
 
     /// limbs
     pub limbs: usize,
 }
 
 impl MontgomeryBackend {
-    pub fn new(modulus: Integer, limbs: u64) -> Self {
+    pub fn new(modulus: CurveInt, limbs: u64) -> Self {
         let r = Self::compute_r(&modulus, limbs);
         let r2 = r
             .clone()
@@ -102,17 +115,27 @@ impl MontgomeryBackend {
                 let c1 = (modulus.clone() + 1) / 4;
 
                 let p = modulus.clone();
-                let q: Integer = p ^ 2;
+                let q: CurveInt = p ^ 2;
 
                 // (q - 3) / 4
-                let q1: Integer = ((q.clone() - 3) / 4) + 1;
+                let q1: CurveInt = ((q.clone() - 3) / 4) + 1;
 
                 // (q - 1) / 2
-                let q2: Integer = (q - 1) / 2 + 1;
+                let q2: CurveInt = (q - 1) / 2 + 1;
 
                 (Some(c1), Some(q1), Some(q2))
             };
 
+        let (two_adicity, trace) = Self::compute_two_adicity(&modulus);
+        let trace_minus_one_div_two = (trace.clone() - 1u32) / 2u32;
+        let two_adic_root_of_unity = Self::compute_non_residue(&modulus)
+            .map(|z| z.pow_mod(&trace, &modulus).expect("z^t should be computed"));
+        let modulus_minus_five_div_eight = if modulus.mod_u(8) == 5 {
+            Some((modulus.clone() - 5u32) / 8u32)
+        } else {
+            None
+        };
+
         let three_b_mont = {
             let result = (INTEGER_TWELVE.clone() * &r2) % &modulus;
             (result * &r_inv) % &modulus
@@ -128,64 +151,68 @@ impl MontgomeryBackend {
             modulus_plus_one_div_four,
             fp2_sqrt_constant1,
             fp2_sqrt_constant2,
+            two_adicity,
+            trace_minus_one_div_two,
+            two_adic_root_of_unity,
+            modulus_minus_five_div_eight,
             three_b_mont,
             limbs: limbs as usize,
         }
     }
 
-    pub fn to_montgomery(&self, a: &Integer) -> Integer {
+    pub fn to_montgomery(&self, a: &CurveInt) -> CurveInt {
         self.mont_mul(a, &self.r2)
     }
 
-    pub fn from_montgomery(&self, a: &Integer) -> Integer {
-        self.mont_mul(a, Integer::ONE)
+    pub fn from_montgomery(&self, a: &CurveInt) -> CurveInt {
+        self.mont_reduction(&self.reduce_operand(a))
     }
 
-    pub fn modulus_ref(&self) -> &Integer {
+    pub fn modulus_ref(&self) -> &CurveInt {
         &self.modulus
     }
 
-    pub fn modulus(&self) -> Integer {
+    pub fn modulus(&self) -> CurveInt {
         self.modulus.clone()
     }
 
-    pub fn r_ref(&self) -> &Integer {
+    pub fn r_ref(&self) -> &CurveInt {
         &self.r
     }
 
-    pub fn r(&self) -> Integer {
+    pub fn r(&self) -> CurveInt {
         self.r.clone()
     }
 
-    pub fn r_inv_ref(&self) -> &Integer {
+    pub fn r_inv_ref(&self) -> &CurveInt {
         &self.r_inv
     }
 
-    pub fn r_inv(&self) -> Integer {
+    pub fn r_inv(&self) -> CurveInt {
         self.r_inv.clone()
     }
 
-    pub fn r2_ref(&self) -> &Integer {
+    pub fn r2_ref(&self) -> &CurveInt {
         &self.r2
     }
 
-    pub fn r2(&self) -> Integer {
+    pub fn r2(&self) -> CurveInt {
         self.r2.clone()
     }
 
-    pub fn r3_ref(&self) -> &Integer {
+    pub fn r3_ref(&self) -> &CurveInt {
         &self.r3
     }
 
-    pub fn r3(&self) -> Integer {
+    pub fn r3(&self) -> CurveInt {
         self.r3.clone()
     }
 
-    pub fn inv_ref(&self) -> &Integer {
+    pub fn inv_ref(&self) -> &CurveInt {
         &self.inv
     }
 
-    pub fn inv(&self) -> Integer {
+    pub fn inv(&self) -> CurveInt {
         self.inv.clone()
     }
 
@@ -194,20 +221,20 @@ impl MontgomeryBackend {
     }
 
     pub fn from_str_radix(s: &str, radix: i32, limbs: u64) -> Self {
-        let modulus = Integer::from_str_radix(s, radix).expect("invalid modulus");
+        let modulus = CurveInt::from_str_radix(s, radix).expect("invalid modulus");
         Self::new(modulus, limbs)
     }
 
     /// Compute R = 2^(NUM_LIMBS*64) mod MODULUS
-    fn compute_r(modulus: &Integer, limbs: u64) -> Integer {
+    fn compute_r(modulus: &CurveInt, limbs: u64) -> CurveInt {
         INTEGER_TWO
             .clone()
-            .pow_mod(&Integer::from(limbs * 64), modulus)
+            .pow_mod(&CurveInt::from(limbs * 64), modulus)
             .expect("R should be computed")
     }
 
     /// Compute -M^{-1} mod 2^64
-    fn compute_inv(modulus: &Integer) -> Integer {
+    fn compute_inv(modulus: &CurveInt) -> CurveInt {
         let modulus_64 = modulus.to_u64_wrapping();
         let mut inv = 1u64;
         for _ in 0..63 {
@@ -217,52 +244,47 @@ impl MontgomeryBackend {
         inv.wrapping_neg().into()
     }
 
-    /// Sample a random value in montgomery form
-    pub fn sample_mont<R: RngCore>(&self, rng: &mut R) -> Integer {
-        let bytes_needed = self.limbs * 16;
-        let mut bytes = vec![0u8; bytes_needed];
-        rng.fill_bytes(&mut bytes);
-
-        let order = if self.limbs == 4 {
-            rug::integer::Order::Lsf
-        } else {
-            rug::integer::Order::Msf
-        };
-        let d0 = Integer::from_digits(&bytes[..bytes_needed / 2], order);
-        let d1 = Integer::from_digits(&bytes[bytes_needed / 2..], order);
-
-        let out = self.mont_mul(&d0, &self.r2) + self.mont_mul(&d1, &self.r3);
-
-        out % &self.modulus
+    /// Compute (s, t) with modulus - 1 = t * 2^s and t odd
+    fn compute_two_adicity(modulus: &CurveInt) -> (u32, CurveInt) {
+        let p_minus_one = modulus.clone() - 1u32;
+        let s = p_minus_one.find_one(0).unwrap_or(0);
+        (s, p_minus_one >> s)
     }
 
-    /// sample a raw (non-Montgomery) value
-    pub fn sample_raw<R: RngCore>(&self, rng: &mut R) -> Integer {
-        let bytes_needed = self.limbs * 16;
-        let mut bytes = vec![0u8; bytes_needed];
-        rng.fill_bytes(&mut bytes);
+    /// The least quadratic non-residue, searched among 2..modulus
+    fn compute_non_residue(modulus: &CurveInt) -> Option<CurveInt> {
+        if modulus.is_even() || *modulus < 3 {
+            return None;
+        }
 
-        let order = if self.limbs == 4 {
-            rug::integer::Order::Lsf
-        } else {
-            rug::integer::Order::Msf
-        };
-        let d0 = Integer::from_digits(&bytes[..bytes_needed / 2], order);
-        let d1 = Integer::from_digits(&bytes[bytes_needed / 2..], order);
+        let mut z = INTEGER_TWO.clone();
+        while z < *modulus {
+            if z.jacobi(modulus) == -1 {
+                return Some(z);
+            }
+            z += 1;
+        }
+        None
+    }
 
-        let out = self.mont_mul(&d0, &self.r) + self.mont_mul(&d1, &self.r2);
+    /// A uniform random element in Montgomery form.
+    pub fn sample_mont<R: RngCore>(&self, rng: &mut R) -> CurveInt {
+        self.to_montgomery(&self.sample_raw(rng))
+    }
 
-        out % &self.modulus
+    /// A uniform random element, see `sampling::sample_below`.
+    pub fn sample_raw<R: RngCore>(&self, rng: &mut R) -> CurveInt {
+        sample_below(rng, &self.modulus)
     }
 
     // Operations
 
     /// new element
-    pub fn new_element(&self, value: Integer) -> Integer {
+    pub fn new_element(&self, value: CurveInt) -> CurveInt {
         // value % &self.modulus
 
         let modulus = &self.modulus;
-        let normalized = value.clone() % modulus;
+        let normalized = value % modulus;
         if normalized < 0 {
             normalized + modulus
         } else {
@@ -271,92 +293,217 @@ impl MontgomeryBackend {
     }
 
     /// Reduces the given value modulo the field's modulus
-    pub fn reduce(&self, value: &Integer) -> Integer {
+    pub fn reduce(&self, value: &CurveInt) -> CurveInt {
         self.new_element(value.clone())
     }
 
     /// Squares this element.
-    pub fn square(&self, a: Integer) -> Integer {
+    pub fn square(&self, a: CurveInt) -> CurveInt {
         a.clone() * a % &self.modulus
     }
 
     /// Squares this element in montegomery form.
-    pub fn mont_square(&self, input: &Integer) -> Integer {
+    pub fn mont_square(&self, input: &CurveInt) -> CurveInt {
         self.mont_mul(input, input)
     }
 
     /// cubic
-    pub fn cubic(&self, input: Integer) -> Integer {
+    pub fn cubic(&self, input: CurveInt) -> CurveInt {
         let square = self.square(input.clone());
         self.mul(square, &input)
     }
 
     /// cubic in montgomery form
-    pub fn mont_cubic(&self, a: &Integer) -> Integer {
+    pub fn mont_cubic(&self, a: &CurveInt) -> CurveInt {
         let square = self.mont_square(a);
         self.mont_mul(&square, a)
     }
 
     /// Exponentiates this element by a given exponent.
-    pub fn pow(&self, a: Integer, exp: &Integer) -> Integer {
+    pub fn pow(&self, a: CurveInt, exp: &CurveInt) -> CurveInt {
         a.pow_mod(exp, &self.modulus).unwrap()
     }
 
     /// Negates this element.
-    pub fn neg(&self, input: Integer) -> Integer {
+    pub fn neg(&self, input: CurveInt) -> CurveInt {
         self.new_element(-input)
     }
 
     /// Attempts to compute the square root of this element.
-    /// only fit for p ≡ 3 (mod 4) meet mont_sqrt req
-    pub fn sqrt(&self, input: Integer) -> Option<Integer> {
-        match &self.modulus_plus_one_div_four {
-            Some(exp) => {
-                let sqrt = self.pow(input.clone(), exp);
-                if self.square(sqrt.clone()) == input {
-                    Some(self.new_element(sqrt))
-                } else {
-                    None
+    ///
+    /// Uses the (p + 1) / 4 exponent if p ≡ 3 (mod 4), Atkin's algorithm if
+    /// p ≡ 5 (mod 8) and Tonelli–Shanks otherwise.
+    pub fn sqrt(&self, input: CurveInt) -> Option<CurveInt> {
+        let input = self.reduce(&input);
+        let two = INTEGER_TWO.clone();
+        let root = self.two_adic_root_of_unity.clone();
+
+        self.sqrt_with(
+            &input,
+            CurveInt::ONE,
+            &two,
+            root.as_ref(),
+            |a, b| self.mul(a.clone(), b),
+            |a, e| self.pow(a.clone(), e),
+        )
+    }
+
+    /// Square root shared by the standard and Montgomery forms, `one`, `two`
+    /// and `root` must be given in the same form as `input`.
+    fn sqrt_with<M, P>(
+        &self,
+        input: &CurveInt,
+        one: &CurveInt,
+        two: &CurveInt,
+        root: Option<&CurveInt>,
+        mul: M,
+        pow: P,
+    ) -> Option<CurveInt>
+    where
+        M: Fn(&CurveInt, &CurveInt) -> CurveInt,
+        P: Fn(&CurveInt, &CurveInt) -> CurveInt,
+    {
+        if input.is_zero() {
+            return Some(CurveInt::ZERO);
+        }
+
+        let candidate = if let Some(exp) = &self.modulus_plus_one_div_four {
+            pow(input, exp)
+        } else if let Some(exp) = &self.modulus_minus_five_div_eight {
+            // Atkin: a = (2x)^((p - 5) / 8), i = 2x * a^2, sqrt = x * a * (i - 1)
+            let two_x = mul(two, input);
+            let a = pow(&two_x, exp);
+            let i = mul(&two_x, &mul(&a, &a));
+            let i_minus_one = self.sub(i, one);
+            mul(&mul(input, &a), &i_minus_one)
+        } else {
+            self.tonelli_shanks(input, one, root?, &mul, &pow)?
+        };
+
+        if mul(&candidate, &candidate) == *input {
+            Some(candidate)
+        } else {
+            None
+        }
+    }
+
+    /// Tonelli–Shanks over the precomputed two-adicity data, None for non-residues.
+    fn tonelli_shanks<M, P>(
+        &self,
+        input: &CurveInt,
+        one: &CurveInt,
+        root: &CurveInt,
+        mul: &M,
+        pow: &P,
+    ) -> Option<CurveInt>
+    where
+        M: Fn(&CurveInt, &CurveInt) -> CurveInt,
+        P: Fn(&CurveInt, &CurveInt) -> CurveInt,
+    {
+        // w = x^((t - 1) / 2), r = x^((t + 1) / 2), b = x^t
+        let w = pow(input, &self.trace_minus_one_div_two);
+        let mut r = mul(input, &w);
+        let mut b = mul(&r, &w);
+        let mut c = root.clone();
+        let mut m = self.two_adicity;
+
+        while b != *one {
+            // least i with b^(2^i) = 1
+            let mut i = 0;
+            let mut b2i = b.clone();
+            while b2i != *one {
+                b2i = mul(&b2i, &b2i);
+                i += 1;
+                if i == m {
+                    return None;
                 }
             }
-            None => panic!("This sqrt implementation only works for p ≡ 3 (mod 4)"),
+
+            let mut e = c;
+            for _ in 0..(m - i - 1) {
+                e = mul(&e, &e);
+            }
+
+            m = i;
+            c = mul(&e, &e);
+            b = mul(&b, &c);
+            r = mul(&r, &e);
         }
-    }
 
-    /// Shanks if p ≡ 3 (mod 4)
+        Some(r)
+    }
 
     /// Computes the multiplicative inverse of this element, if it exists.
-    pub fn invert(&self, input: Integer) -> Option<Integer> {
+    pub fn invert(&self, input: CurveInt) -> Option<CurveInt> {
         input
             .invert(&self.modulus)
             .map(|v| self.new_element(v))
             .ok()
     }
 
+    /// Inverts every non-zero element of `values` in place with a single
+    /// field inversion (Montgomery's trick). Zeros are left as zero.
+    pub fn batch_invert(&self, values: &mut [CurveInt]) {
+        // prefix[i] is the product of the non-zero values[..=i]
+        let mut prefix = Vec::with_capacity(values.len());
+        let mut acc = CurveInt::from(1);
+        for value in values.iter() {
+            if !value.is_zero() {
+                acc = self.mul(acc, value);
+            }
+            prefix.push(acc.clone());
+        }
+
+        let Some(mut inv) = self.invert(acc) else {
+            return;
+        };
+        for i in (0..values.len()).rev() {
+            if values[i].is_zero() {
+                continue;
+            }
+
+            // inv = 1 / prefix[i], so 1 / values[i] = inv * prefix[i - 1]
+            let value_inv = match i.checked_sub(1) {
+                Some(j) => self.mul(inv.clone(), &prefix[j]),
+                None => inv.clone(),
+            };
+            inv = self.mul(inv, &values[i]);
+            values[i] = value_inv;
+        }
+    }
+
     /// Multiplies this element by another.
-    pub fn mul(&self, input: Integer, other: &Integer) -> Integer {
+    pub fn mul(&self, input: CurveInt, other: &CurveInt) -> CurveInt {
         input * other % &self.modulus
     }
 
     /// add two elements
-    pub fn add(&self, a: Integer, b: &Integer) -> Integer {
+    pub fn add(&self, a: CurveInt, b: &CurveInt) -> CurveInt {
         (a + b) % &self.modulus
     }
 
     /// sub two elements
-    pub fn sub(&self, a: Integer, b: &Integer) -> Integer {
+    pub fn sub(&self, a: CurveInt, b: &CurveInt) -> CurveInt {
         self.new_element(a - b)
     }
 
     // mont Operations
-    /// Montgomery reduction: Computes (t * r^-1) mod n
-    pub fn mont_reduction(&self, t: &Integer) -> Integer {
-        let k = &self.inv; // Use precomputed inv
-        let two64 = INTEGER_TWO.clone().pow(64);
-
-        let m: Integer = (t.clone() * k) % &two64; // Faster modulo 2^64
-        let mr = (m.clone() * &self.modulus) % &two64; // Faster modulo 2^64
-        let u = (t.clone() + mr.clone() * &self.modulus) / two64;
+    /// Montgomery reduction: Computes (t * r^-1) mod n for 0 <= t < n * r,
+    /// one 64-bit limb at a time.
+    ///
+    /// Each round adds the multiple m * n of the modulus that clears the lowest
+    /// limb of t, with m = t * inv mod 2^64, and shifts that limb out. After
+    /// `limbs` rounds t has been divided by r exactly and is below 2n.
+    pub fn mont_reduction(&self, t: &CurveInt) -> CurveInt {
+        debug_assert!(*t >= 0, "mont_reduction of a negative value");
+
+        let inv = self.inv.to_u64_wrapping();
+        let mut u = t.clone();
+        for _ in 0..self.limbs {
+            let m = u.to_u64_wrapping().wrapping_mul(inv);
+            u += &self.modulus * m;
+            u >>= 64;
+        }
 
         if u >= self.modulus {
             u - &self.modulus
@@ -366,60 +513,71 @@ impl MontgomeryBackend {
     }
 
     /// montgomery multiplication
-    pub fn mont_mul(&self, a: &Integer, b: &Integer) -> Integer {
-        let result = (a.clone() * b) % &self.modulus;
-        (result * &self.r_inv) % &self.modulus
-    }
-
-    pub fn mont_pow(&self, base: &Integer, exponent: &Integer) -> Integer {
-        let mut result = self.r();
-        let mut base = base.clone();
-        let mut exp = exponent.clone();
-        while !exp.is_zero() {
-            if exp.is_odd() {
-                result = self.mont_mul(&result, &base);
-            }
-            base = self.mont_mul(&base, &base);
-            exp >>= 1;
+    pub fn mont_mul(&self, a: &CurveInt, b: &CurveInt) -> CurveInt {
+        let a = self.reduce_operand(a);
+        let b = self.reduce_operand(b);
+        self.mont_reduction(&CurveInt::from(&*a * &*b))
+    }
+
+    /// `a` itself if it lies in [0, modulus), else its canonical residue, so
+    /// that products of operands stay below modulus * r.
+    fn reduce_operand<'a>(&self, a: &'a CurveInt) -> Cow<'a, CurveInt> {
+        if *a >= 0 && *a < self.modulus {
+            Cow::Borrowed(a)
+        } else {
+            Cow::Owned(self.reduce(a))
         }
-        result
+    }
+
+    /// base^exponent in Montgomery form, for a non-negative exponent, by
+    /// 4-bit sliding windows.
+    pub fn mont_pow(&self, base: &CurveInt, exponent: &CurveInt) -> CurveInt {
+        debug_assert!(*exponent >= 0, "mont_pow with a negative exponent");
+        sliding_window_pow(
+            base,
+            exponent.significant_bits(),
+            |i| exponent.get_bit(i),
+            self.r(),
+            |a, b| self.mont_mul(a, b),
+        )
     }
 
     /// Computes the square root of a value in Montgomery form
-    pub fn mont_sqrt(&self, input: &Integer) -> Option<Integer> {
-        // The exponent (p+1)/4 in standard form
-        match &self.modulus_plus_one_div_four {
-            // why using original value?
-            Some(exp) => {
-                // Perform exponentiation in Montgomery form
-                let sqrt = self.mont_pow(input, &exp);
-
-                // Verify the result in Montgomery form
-                let sqrt_squared = self.mont_mul(&sqrt, &sqrt);
-                if &sqrt_squared == input {
-                    Some(sqrt)
-                } else {
-                    None
-                }
-            }
-            None => panic!("This sqrt implementation only works for p ≡ 3 (mod 4)"),
-        }
+    pub fn mont_sqrt(&self, input: &CurveInt) -> Option<CurveInt> {
+        let two = self.to_montgomery(INTEGER_TWO);
+        let root = self
+            .two_adic_root_of_unity
+            .as_ref()
+            .map(|root| self.to_montgomery(root));
+
+        self.sqrt_with(
+            input,
+            self.r_ref(),
+            &two,
+            root.as_ref(),
+            |a, b| self.mont_mul(a, b),
+            |a, e| self.mont_pow(a, e),
+        )
     }
 
-    /// point operations    
+    /// point operations
     ///
-    ///// Normalizes projective coordinates to the form (X:Y:1)
-    pub fn normalize(&self, x: &Integer, y: &Integer, z: &Integer) -> (Integer, Integer, Integer) {
-        if z.is_one() {
-            return (x.clone(), y.clone(), z.clone());
-        }
-        let z_inv = z
-            .clone()
-            .invert(self.modulus_ref())
-            .expect("Z should be invertible");
-        let x_norm = (x.clone() * &z_inv) % self.modulus_ref();
-        let y_norm = (y.clone() * &z_inv) % self.modulus_ref();
-        (x_norm, y_norm, Integer::from(1))
+    /// Normalizes projective coordinates to the form (X:Y:1), or to (0:1:0)
+    /// when Z = 0. The coordinates are returned reduced whatever Z is.
+    pub fn normalize(
+        &self,
+        x: &CurveInt,
+        y: &CurveInt,
+        z: &CurveInt,
+    ) -> (CurveInt, CurveInt, CurveInt) {
+        let Some(z_inv) = self.invert(self.reduce(z)) else {
+            return (CurveInt::ZERO, CurveInt::from(1), CurveInt::ZERO);
+        };
+        (
+            self.mul(x.clone(), &z_inv),
+            self.mul(y.clone(), &z_inv),
+            CurveInt::from(1),
+        )
     }
 
     /// Doubles a point (X, Y, Z) in standard projective coordinates.
@@ -445,13 +603,13 @@ impl MontgomeryBackend {
     /// ref: https://leastauthority.com/static/publications/MoonMath080822.pdf Page 84 Algorithm 7 Projective short Weierstrass Addition Law
     pub fn double_standard(
         &self,
-        x: &Integer,
-        y: &Integer,
-        z: &Integer,
-    ) -> (Integer, Integer, Integer) {
+        x: &CurveInt,
+        y: &CurveInt,
+        z: &CurveInt,
+    ) -> (CurveInt, CurveInt, CurveInt) {
         // W = 3 * X^2 + a * Z^2, where a is the curve parameter (0 for BLS12-381)
         let x_sq = self.mul(x.clone(), x);
-        let w = self.mul(x_sq.clone(), &INTEGER_THREE);
+        let w = self.mul(x_sq.clone(), INTEGER_THREE);
 
         // S = Y * Z
         let s = self.mul(y.clone(), z);
@@ -462,26 +620,26 @@ impl MontgomeryBackend {
 
         // h = W^2 - 8 * B
         let w_sq = self.mul(w.clone(), &w);
-        let eight_b = self.mul(b.clone(), &INTEGER_EIGHT);
+        let eight_b = self.mul(b.clone(), INTEGER_EIGHT);
         let h = self.sub(w_sq, &eight_b);
 
         // X' = 2 * h * S
-        let two_h = self.mul(h.clone(), &INTEGER_TWO);
+        let two_h = self.mul(h.clone(), INTEGER_TWO);
         let x_prime = self.mul(two_h, &s);
 
         // Y' = W * (4 * B - h) - 8 * Y^2 * S^2
-        let four_b = self.mul(b, &INTEGER_FOUR);
+        let four_b = self.mul(b, INTEGER_FOUR);
         let four_b_minus_h = self.sub(four_b, &h);
         let w_term = self.mul(w, &four_b_minus_h);
         let y_sq = self.mul(y.clone(), y);
         let s_sq = self.mul(s.clone(), &s);
         let y_term = self.mul(y_sq, &s_sq);
-        let eight_y_term = self.mul(y_term, &INTEGER_EIGHT);
+        let eight_y_term = self.mul(y_term, INTEGER_EIGHT);
         let y_prime = self.sub(w_term, &eight_y_term);
 
         // Z' = 8 * S^3
         let s_cube = self.mul(s_sq, &s);
-        let z_prime = self.mul(s_cube, &INTEGER_EIGHT);
+        let z_prime = self.mul(s_cube, INTEGER_EIGHT);
 
         (x_prime, y_prime, z_prime)
     }
@@ -501,10 +659,10 @@ impl MontgomeryBackend {
     /// ref: https://eprint.iacr.org/2015/1060.pdf Algorithm 9
     pub fn double_mont(
         &self,
-        x: &Integer,
-        y: &Integer,
-        z: &Integer,
-    ) -> (Integer, Integer, Integer) {
+        x: &CurveInt,
+        y: &CurveInt,
+        z: &CurveInt,
+    ) -> (CurveInt, CurveInt, CurveInt) {
         // 1. t0 ← Y · Y
         let mut t0 = self.mont_mul(y, y);
 
@@ -573,13 +731,13 @@ impl MontgomeryBackend {
     /// ref: https://eprint.iacr.org/2015/1060.pdf Algorithm 7
     pub fn add_mont(
         &self,
-        x1: &Integer,
-        y1: &Integer,
-        z1: &Integer,
-        x2: &Integer,
-        y2: &Integer,
-        z2: &Integer,
-    ) -> (Integer, Integer, Integer) {
+        x1: &CurveInt,
+        y1: &CurveInt,
+        z1: &CurveInt,
+        x2: &CurveInt,
+        y2: &CurveInt,
+        z2: &CurveInt,
+    ) -> (CurveInt, CurveInt, CurveInt) {
         // 1. t0 ← X1 · X2
         let mut t0 = self.mont_mul(x1, x2);
 
@@ -694,16 +852,15 @@ impl MontgomeryBackend {
     /// A tuple `(X3, Y3, Z3)` representing the sum of the two points in standard form.
     ///
     /// ref: https://leastauthority.com/static/publications/MoonMath080822.pdf Page 84 Algorithm 7 Projective short Weierstrass Addition Law
-
     pub fn add_standard(
         &self,
-        x1: &Integer,
-        y1: &Integer,
-        z1: &Integer,
-        x2: &Integer,
-        y2: &Integer,
-        z2: &Integer,
-    ) -> (Integer, Integer, Integer) {
+        x1: &CurveInt,
+        y1: &CurveInt,
+        z1: &CurveInt,
+        x2: &CurveInt,
+        y2: &CurveInt,
+        z2: &CurveInt,
+    ) -> (CurveInt, CurveInt, CurveInt) {
         // Check if first point is the point at infinity
         if z1.is_zero() {
             return (x2.clone(), y2.clone(), z2.clone());
@@ -729,14 +886,14 @@ impl MontgomeryBackend {
         if v1 == v2 {
             if u1 != u2 {
                 // Points are inverses of each other, return point at infinity
-                return (Integer::ZERO, Integer::ONE.clone(), Integer::ZERO);
+                (CurveInt::ZERO, CurveInt::ONE.clone(), CurveInt::ZERO)
             } else {
                 if y1.is_zero() {
                     // Point is of order 2, return point at infinity
-                    return (Integer::ZERO, Integer::ONE.clone(), Integer::ZERO);
+                    (CurveInt::ZERO, CurveInt::ONE.clone(), CurveInt::ZERO)
                 } else {
                     // Points are the same, use point doubling
-                    return self.double_standard(x1, y1, z1);
+                    self.double_standard(x1, y1, z1)
                 }
             }
         } else {
@@ -767,7 +924,200 @@ impl MontgomeryBackend {
             // Z' = V^3 · W
             let z3 = self.mul(self.cubic(v), &w);
 
-            return (x3, y3, z3);
+            (x3, y3, z3)
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::curves::bls12_381::{BLS12_381_BASE, BLS12_381_SCALAR};
+    use num_traits::identities::One;
+    use zkper_rand::TestRng;
+
+    fn check_exhaustive(backend: &MontgomeryBackend) {
+        let p = backend.modulus().to_u64().unwrap();
+        for i in 0..p {
+            let a = CurveInt::from(i);
+            let is_square = a.is_zero() || a.jacobi(backend.modulus_ref()) == 1;
+
+            match backend.sqrt(a.clone()) {
+                Some(root) => {
+                    assert!(is_square);
+                    assert_eq!(backend.square(root), a);
+                }
+                None => assert!(!is_square),
+            }
+
+            let a_mont = backend.to_montgomery(&a);
+            match backend.mont_sqrt(&a_mont) {
+                Some(root) => assert_eq!(backend.mont_square(&root), a_mont),
+                None => assert!(!is_square),
+            }
+        }
+    }
+
+    #[test]
+    fn test_sqrt_small_moduli() {
+        // 3 mod 4, 5 mod 8 and 1 mod 8 with two-adicity 4 and 5
+        for p in [7u32, 13, 29, 17, 97] {
+            let backend = MontgomeryBackend::new(CurveInt::from(p), 1);
+            check_exhaustive(&backend);
+        }
+    }
+
+    /// Cross-checks the Montgomery operations against rug's modular
+    /// arithmetic on random elements.
+    fn check_montgomery(backend: &MontgomeryBackend) {
+        let mut rng = TestRng::new();
+        let p = backend.modulus_ref();
+        let r = CurveInt::from(1) << (64 * backend.limbs as u32);
+        assert_eq!(backend.r, CurveInt::from(&r % p));
+
+        for _ in 0..100 {
+            let a = backend.sample_raw(&mut rng);
+            let b = backend.sample_raw(&mut rng);
+            let a_mont = backend.to_montgomery(&a);
+            let b_mont = backend.to_montgomery(&b);
+
+            assert_eq!(a_mont, CurveInt::from(&a * &r) % p);
+            assert_eq!(backend.from_montgomery(&a_mont), a);
+
+            // a * b * r^-1, through the full product and through reduction
+            let product = CurveInt::from(&a * &b);
+            let expected = product.clone() * &backend.r_inv % p;
+            assert_eq!(backend.mont_mul(&a, &b), expected);
+            assert_eq!(backend.mont_reduction(&product), expected);
+            assert_eq!(
+                backend.from_montgomery(&backend.mont_mul(&a_mont, &b_mont)),
+                CurveInt::from(&a * &b) % p
+            );
+
+            // the largest input reduction accepts
+            let top = CurveInt::from(p * &r) - 1u32;
+            assert_eq!(
+                backend.mont_reduction(&top),
+                CurveInt::from(&top % p) * &backend.r_inv % p
+            );
+        }
+
+        // operands outside [0, p) are reduced first
+        let a = backend.sample_raw(&mut rng);
+        let b = backend.sample_raw(&mut rng);
+        let expected = backend.mont_mul(&a, &b);
+        assert_eq!(backend.mont_mul(&(a.clone() + p), &b), expected);
+        assert_eq!(backend.mont_mul(&(a - p), &b), expected);
+
+        // windowed exponentiation against rug, over short, long and edge exponents
+        let base = backend.sample_raw(&mut rng);
+        let base_mont = backend.to_montgomery(&base);
+        let exponents = [
+            CurveInt::new(),
+            CurveInt::from(1),
+            CurveInt::from(31u32),
+            CurveInt::from(u32::MAX),
+            p.clone() - 1u32,
+            p.clone() - 2u32,
+            (CurveInt::from(1) << 300u32) - 1u32,
+            backend.sample_raw(&mut rng),
+        ];
+        for exp in exponents {
+            assert_eq!(
+                backend.from_montgomery(&backend.mont_pow(&base_mont, &exp)),
+                backend.pow(base.clone(), &exp),
+                "exponent {exp}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_montgomery_consistency() {
+        check_montgomery(&BLS12_381_BASE);
+        check_montgomery(&BLS12_381_SCALAR);
+        check_montgomery(&MontgomeryBackend::new(CurveInt::from(97), 1));
+        check_montgomery(&MontgomeryBackend::new(
+            (CurveInt::from(1) << 255) - 19u32,
+            4,
+        ));
+    }
+
+    #[test]
+    fn test_two_adicity_data() {
+        assert_eq!(BLS12_381_SCALAR.two_adicity, 32);
+        assert_eq!(BLS12_381_BASE.two_adicity, 1);
+
+        // the root of unity has order exactly 2^32
+        let root = BLS12_381_SCALAR.two_adic_root_of_unity.clone().unwrap();
+        let order = CurveInt::from(1u64 << 32);
+        assert!(BLS12_381_SCALAR.pow(root.clone(), &order).is_one());
+        assert!(!BLS12_381_SCALAR.pow(root, &(order / 2u32)).is_one());
+    }
+
+    #[test]
+    fn test_sqrt_scalar_field() {
+        let mut rng = TestRng::new();
+        let backend = &*BLS12_381_SCALAR;
+        for _ in 0..10 {
+            let a = backend.square(backend.sample_raw(&mut rng));
+            let root = backend.sqrt(a.clone()).unwrap();
+            assert_eq!(backend.square(root), a);
+
+            let a_mont = backend.to_montgomery(&a);
+            let root = backend.mont_sqrt(&a_mont).unwrap();
+            assert_eq!(backend.mont_square(&root), a_mont);
+        }
+
+        // 7 generates the multiplicative group, so it is a non-residue
+        assert!(backend.sqrt(INTEGER_SEVEN.clone()).is_none());
+        assert!(backend
+            .mont_sqrt(&backend.to_montgomery(INTEGER_SEVEN))
+            .is_none());
+    }
+
+    #[test]
+    fn test_batch_invert() {
+        let mut rng = TestRng::new();
+        let backend = &*BLS12_381_BASE;
+
+        let mut values = (0..10)
+            .map(|_| backend.sample_raw(&mut rng))
+            .collect::<Vec<_>>();
+        values[0] = CurveInt::ZERO;
+        values[5] = CurveInt::ZERO;
+
+        let mut inverses = values.clone();
+        backend.batch_invert(&mut inverses);
+        for (value, inverse) in values.into_iter().zip(inverses) {
+            match backend.invert(value) {
+                Some(expected) => assert_eq!(inverse, expected),
+                None => assert!(inverse.is_zero()),
+            }
+        }
+
+        let mut empty: Vec<CurveInt> = vec![];
+        backend.batch_invert(&mut empty);
+    }
+
+    #[test]
+    fn test_sqrt_atkin() {
+        // 2^255 - 19 ≡ 5 (mod 8)
+        let p = (CurveInt::from(1) << 255) - 19u32;
+        let backend = MontgomeryBackend::new(p, 4);
+        assert!(backend.modulus_minus_five_div_eight.is_some());
+
+        let mut rng = TestRng::new();
+        for _ in 0..10 {
+            let a = backend.square(backend.sample_raw(&mut rng));
+            let root = backend.sqrt(a.clone()).unwrap();
+            assert_eq!(backend.square(root), a);
+
+            let a_mont = backend.to_montgomery(&a);
+            let root = backend.mont_sqrt(&a_mont).unwrap();
+            assert_eq!(backend.mont_square(&root), a_mont);
+        }
+
+        // 2 is a non-residue modulo a prime ≡ 5 (mod 8)
+        assert!(backend.sqrt(INTEGER_TWO.clone()).is_none());
+    }
+}
@@ -0,0 +1,461 @@
+//! Residue number system (RNS) arithmetic modulo a prime of up to 392 bits.
+//!
+//! An element is kept as its residues modulo two bases B and B' of seven
+//! 62-bit primes each, so every operation splits into independent word-sized
+//! lanes, the layout GPU kernels want. Multiplication is Montgomery
+//! multiplication with R = M, the product of B: the value x is stored as
+//! x * M mod p, plus a multiple of p.
+//!
+//! Montgomery multiplication computes q = -xy p^-1 mod M in B, then
+//! (xy + qp) / M in B', and moves values between the bases by exact base
+//! extension through mixed-radix digits. Its result is below 2p for inputs
+//! below 2^20 p, so sums and differences of a few products can be fed back
+//! without reduction.
+
+use crate::backends::{curve_int::CurveInt, limbs::to_integer};
+use crate::curves::bls12_381::BASE_MODULUS_LIMBS;
+
+/// Number of moduli in each base.
+pub const RNS_BASE_SIZE: usize = 7;
+
+/// The seven largest primes below 2^62, whose product M exceeds 2^433.
+pub const RNS_BASE: [u64; RNS_BASE_SIZE] = [
+    0x3fffffffffffffc7,
+    0x3fffffffffffffa9,
+    0x3fffffffffffff8b,
+    0x3fffffffffffff71,
+    0x3fffffffffffff67,
+    0x3fffffffffffff59,
+    0x3fffffffffffff55,
+];
+
+/// The next seven primes below 2^62, the extension base B'.
+pub const RNS_EXTENSION_BASE: [u64; RNS_BASE_SIZE] = [
+    0x3fffffffffffff3d,
+    0x3fffffffffffff35,
+    0x3ffffffffffffeef,
+    0x3ffffffffffffee1,
+    0x3ffffffffffffec3,
+    0x3ffffffffffffe45,
+    0x3ffffffffffffe1d,
+];
+
+/// The largest modulus the bases support: M and M' must exceed 2^40 p.
+const MAX_MODULUS_BITS: u32 = 392;
+
+/// Multiples of p added before subtracting, so differences stay positive for
+/// subtrahends below 2^16 p.
+const SUB_OFFSET_BITS: u32 = 16;
+
+fn mul_mod(a: u64, b: u64, m: u64) -> u64 {
+    ((a as u128 * b as u128) % m as u128) as u64
+}
+
+fn add_mod(a: u64, b: u64, m: u64) -> u64 {
+    // both below 2^62, so the sum cannot overflow
+    (a + b) % m
+}
+
+fn pow_mod(mut base: u64, mut exp: u64, m: u64) -> u64 {
+    let mut result = 1;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = mul_mod(result, base, m);
+        }
+        base = mul_mod(base, base, m);
+        exp >>= 1;
+    }
+    result
+}
+
+/// Inverse modulo a prime, by Fermat.
+fn inv_mod(a: u64, m: u64) -> u64 {
+    pow_mod(a % m, m - 2, m)
+}
+
+/// x mod m, for x >= 0.
+fn residue(x: &CurveInt, m: u64) -> u64 {
+    CurveInt::from(x % m)
+        .to_u64()
+        .expect("a residue fits in a word")
+}
+
+/// A set of pairwise coprime word-sized moduli, with the constants of exact
+/// base extension.
+#[derive(Debug, Clone)]
+pub struct RnsBase {
+    moduli: [u64; RNS_BASE_SIZE],
+    /// m_j^-1 mod m_i for j < i
+    garner: [[u64; RNS_BASE_SIZE]; RNS_BASE_SIZE],
+    product: CurveInt,
+}
+
+impl RnsBase {
+    pub fn new(moduli: [u64; RNS_BASE_SIZE]) -> Self {
+        let mut garner = [[0; RNS_BASE_SIZE]; RNS_BASE_SIZE];
+        for i in 0..RNS_BASE_SIZE {
+            for j in 0..i {
+                garner[i][j] = inv_mod(moduli[j], moduli[i]);
+            }
+        }
+        let product = moduli
+            .iter()
+            .fold(CurveInt::from(1), |acc, &m| acc * CurveInt::from(m));
+        Self {
+            moduli,
+            garner,
+            product,
+        }
+    }
+
+    pub fn moduli(&self) -> &[u64; RNS_BASE_SIZE] {
+        &self.moduli
+    }
+
+    /// The product of the moduli.
+    pub fn product(&self) -> &CurveInt {
+        &self.product
+    }
+
+    /// The residues of x.
+    pub fn decompose(&self, x: &CurveInt) -> [u64; RNS_BASE_SIZE] {
+        self.moduli.map(|m| residue(x, m))
+    }
+
+    /// Mixed-radix digits v_i of the x in [0, M) with the given residues:
+    /// x = v_0 + v_1 m_0 + v_2 m_0 m_1 + ...
+    fn mixed_radix(&self, residues: &[u64; RNS_BASE_SIZE]) -> [u64; RNS_BASE_SIZE] {
+        let mut digits = [0; RNS_BASE_SIZE];
+        for i in 0..RNS_BASE_SIZE {
+            let m = self.moduli[i];
+            let mut v = residues[i];
+            for (digit, inverse) in digits[..i].iter().zip(&self.garner[i]) {
+                // (v - v_j) * m_j^-1 mod m_i
+                let diff = add_mod(v, m - digit % m, m);
+                v = mul_mod(diff, *inverse, m);
+            }
+            digits[i] = v;
+        }
+        digits
+    }
+
+    /// The x in [0, M) with the given residues.
+    pub fn reconstruct(&self, residues: &[u64; RNS_BASE_SIZE]) -> CurveInt {
+        let digits = self.mixed_radix(residues);
+        let mut x = CurveInt::new();
+        for i in (0..RNS_BASE_SIZE).rev() {
+            x = x * self.moduli[i] + digits[i];
+        }
+        x
+    }
+
+    /// Exact base extension: the residues modulo `target` of the x in [0, M)
+    /// with the given residues, without leaving word arithmetic.
+    pub fn extend(
+        &self,
+        residues: &[u64; RNS_BASE_SIZE],
+        target: &RnsBase,
+    ) -> [u64; RNS_BASE_SIZE] {
+        let digits = self.mixed_radix(residues);
+        target.moduli.map(|t| {
+            let mut acc = 0;
+            for i in (0..RNS_BASE_SIZE).rev() {
+                acc = add_mod(mul_mod(acc, self.moduli[i] % t, t), digits[i] % t, t);
+            }
+            acc
+        })
+    }
+}
+
+/// An element in RNS Montgomery form, see the module documentation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RnsElement {
+    /// residues modulo B
+    pub base: [u64; RNS_BASE_SIZE],
+    /// residues modulo B'
+    pub extension: [u64; RNS_BASE_SIZE],
+}
+
+/// RNS Montgomery arithmetic modulo p.
+#[derive(Debug, Clone)]
+pub struct RnsBackend {
+    modulus: CurveInt,
+    base: RnsBase,
+    extension: RnsBase,
+    /// -p^-1 mod m_i
+    neg_p_inv: [u64; RNS_BASE_SIZE],
+    /// p mod m'_i
+    p_extension: [u64; RNS_BASE_SIZE],
+    /// M^-1 mod m'_i
+    m_inv_extension: [u64; RNS_BASE_SIZE],
+    /// 2^16 p, added before subtracting
+    sub_offset: RnsElement,
+    /// M mod p, for conversions
+    m_mod_p: CurveInt,
+    /// M^-1 mod p, for conversions
+    m_inv_mod_p: CurveInt,
+}
+
+impl RnsBackend {
+    /// The backend of an odd modulus of at most 392 bits.
+    pub fn new(modulus: CurveInt) -> Self {
+        assert!(
+            modulus.significant_bits() <= MAX_MODULUS_BITS,
+            "modulus too large for the RNS bases"
+        );
+        assert!(modulus.is_odd(), "RNS Montgomery form needs an odd modulus");
+        let base = RnsBase::new(RNS_BASE);
+        let extension = RnsBase::new(RNS_EXTENSION_BASE);
+
+        let neg_p_inv = RNS_BASE.map(|m| m - inv_mod(residue(&modulus, m), m));
+        let p_extension = extension.decompose(&modulus);
+        let m_inv_extension = RNS_EXTENSION_BASE.map(|m| inv_mod(residue(base.product(), m), m));
+
+        let offset = CurveInt::from(&modulus << SUB_OFFSET_BITS);
+        let sub_offset = RnsElement {
+            base: base.decompose(&offset),
+            extension: extension.decompose(&offset),
+        };
+
+        let m_mod_p = CurveInt::from(base.product() % &modulus);
+        let m_inv_mod_p = m_mod_p.clone().invert(&modulus).expect("M is coprime to p");
+
+        Self {
+            modulus,
+            base,
+            extension,
+            neg_p_inv,
+            p_extension,
+            m_inv_extension,
+            sub_offset,
+            m_mod_p,
+            m_inv_mod_p,
+        }
+    }
+
+    /// The backend of the BLS12-381 base field.
+    pub fn bls12_381_base() -> Self {
+        Self::new(to_integer(&BASE_MODULUS_LIMBS))
+    }
+
+    pub fn modulus(&self) -> &CurveInt {
+        &self.modulus
+    }
+
+    pub fn base(&self) -> &RnsBase {
+        &self.base
+    }
+
+    pub fn extension(&self) -> &RnsBase {
+        &self.extension
+    }
+
+    /// a * M mod p in RNS, from a in standard form.
+    pub fn to_rns(&self, a: &CurveInt) -> RnsElement {
+        let a = CurveInt::from(a * &self.m_mod_p) % &self.modulus;
+        let a = if a < 0 { a + &self.modulus } else { a };
+        RnsElement {
+            base: self.base.decompose(&a),
+            extension: self.extension.decompose(&a),
+        }
+    }
+
+    /// The element back in standard form, in [0, p).
+    pub fn from_rns(&self, a: &RnsElement) -> CurveInt {
+        let value = self.base.reconstruct(&a.base);
+        CurveInt::from(value * &self.m_inv_mod_p) % &self.modulus
+    }
+
+    pub fn zero(&self) -> RnsElement {
+        RnsElement {
+            base: [0; RNS_BASE_SIZE],
+            extension: [0; RNS_BASE_SIZE],
+        }
+    }
+
+    pub fn one(&self) -> RnsElement {
+        self.to_rns(&CurveInt::from(1))
+    }
+
+    pub fn add(&self, a: &RnsElement, b: &RnsElement) -> RnsElement {
+        RnsElement {
+            base: std::array::from_fn(|i| add_mod(a.base[i], b.base[i], RNS_BASE[i])),
+            extension: std::array::from_fn(|i| {
+                add_mod(a.extension[i], b.extension[i], RNS_EXTENSION_BASE[i])
+            }),
+        }
+    }
+
+    /// a - b + 2^16 p, for b below 2^16 p.
+    pub fn sub(&self, a: &RnsElement, b: &RnsElement) -> RnsElement {
+        let lane = |a: u64, b: u64, offset: u64, m: u64| add_mod(add_mod(a, offset, m), m - b, m);
+        RnsElement {
+            base: std::array::from_fn(|i| {
+                lane(a.base[i], b.base[i], self.sub_offset.base[i], RNS_BASE[i])
+            }),
+            extension: std::array::from_fn(|i| {
+                let m = RNS_EXTENSION_BASE[i];
+                lane(
+                    a.extension[i],
+                    b.extension[i],
+                    self.sub_offset.extension[i],
+                    m,
+                )
+            }),
+        }
+    }
+
+    /// Montgomery multiplication: a * b * M^-1 mod p, below 2p.
+    pub fn mul(&self, a: &RnsElement, b: &RnsElement) -> RnsElement {
+        // q = -ab p^-1 mod M, in B
+        let q: [u64; RNS_BASE_SIZE] = std::array::from_fn(|i| {
+            let m = RNS_BASE[i];
+            mul_mod(mul_mod(a.base[i], b.base[i], m), self.neg_p_inv[i], m)
+        });
+        let q_extension = self.base.extend(&q, &self.extension);
+
+        // r = (ab + qp) / M, exact in B' since r < 2p < M'
+        let r_extension: [u64; RNS_BASE_SIZE] = std::array::from_fn(|i| {
+            let m = RNS_EXTENSION_BASE[i];
+            let ab = mul_mod(a.extension[i], b.extension[i], m);
+            let qp = mul_mod(q_extension[i], self.p_extension[i], m);
+            mul_mod(add_mod(ab, qp, m), self.m_inv_extension[i], m)
+        });
+        let r_base = self.extension.extend(&r_extension, &self.base);
+
+        RnsElement {
+            base: r_base,
+            extension: r_extension,
+        }
+    }
+
+    pub fn square(&self, a: &RnsElement) -> RnsElement {
+        self.mul(a, a)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backends::montgomery::MontgomeryBackend;
+    use crate::backends::sampling::sample_below;
+    use zkper_rand::TestRng;
+
+    #[test]
+    fn test_bases() {
+        let p = to_integer(&BASE_MODULUS_LIMBS);
+        let backend = RnsBackend::bls12_381_base();
+        let bound = CurveInt::from(&p << 40u32);
+        assert!(*backend.base().product() > bound);
+        assert!(*backend.extension().product() > bound);
+
+        let all: Vec<u64> = RNS_BASE
+            .iter()
+            .chain(&RNS_EXTENSION_BASE)
+            .copied()
+            .collect();
+        for (i, &m) in all.iter().enumerate() {
+            assert!(m < 1 << 62);
+            assert_ne!(
+                CurveInt::from(m).is_probably_prime(30),
+                rug::integer::IsPrime::No
+            );
+            assert!(all[..i].iter().all(|&other| other != m));
+        }
+    }
+
+    #[test]
+    fn test_base_extension() {
+        let mut rng = TestRng::new();
+        let base = RnsBase::new(RNS_BASE);
+        let extension = RnsBase::new(RNS_EXTENSION_BASE);
+
+        let m = base.product().clone();
+        let edges = [
+            CurveInt::new(),
+            CurveInt::from(1),
+            CurveInt::from(RNS_BASE[0]),
+            CurveInt::from(u64::MAX),
+            m.clone() - 1u32,
+        ];
+        let random = (0..1000).map(|_| sample_below(&mut rng, &m));
+        for x in edges.into_iter().chain(random) {
+            let residues = base.decompose(&x);
+            assert_eq!(base.reconstruct(&residues), x);
+            assert_eq!(base.extend(&residues, &extension), extension.decompose(&x));
+        }
+    }
+
+    #[test]
+    fn test_conversions() {
+        let mut rng = TestRng::new();
+        let backend = RnsBackend::bls12_381_base();
+        let p = backend.modulus().clone();
+
+        let edges = [
+            CurveInt::new(),
+            CurveInt::from(1),
+            CurveInt::from(2),
+            p.clone() - 1u32,
+            p.clone() - 2u32,
+            CurveInt::from(1) << 200u32,
+        ];
+        let random = (0..1000).map(|_| sample_below(&mut rng, &p));
+        for a in edges.into_iter().chain(random) {
+            let rns = backend.to_rns(&a);
+            assert_eq!(backend.from_rns(&rns), a);
+            // the two bases hold the same value
+            let value = backend.base().reconstruct(&rns.base);
+            assert_eq!(backend.extension().decompose(&value), rns.extension);
+        }
+
+        // every residue of a small modulus
+        let small = RnsBackend::new(CurveInt::from(1009));
+        for a in 0..1009u32 {
+            let a = CurveInt::from(a);
+            assert_eq!(small.from_rns(&small.to_rns(&a)), a);
+        }
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let mut rng = TestRng::new();
+        let backend = RnsBackend::bls12_381_base();
+        let montgomery = MontgomeryBackend::new(backend.modulus().clone(), 6);
+        let p = backend.modulus().clone();
+
+        for _ in 0..200 {
+            let a = sample_below(&mut rng, &p);
+            let b = sample_below(&mut rng, &p);
+            let (x, y) = (backend.to_rns(&a), backend.to_rns(&b));
+
+            let product = backend.mul(&x, &y);
+            assert_eq!(backend.from_rns(&product), montgomery.mul(a.clone(), &b));
+            assert_eq!(
+                backend.from_rns(&backend.add(&x, &y)),
+                montgomery.add(a.clone(), &b)
+            );
+            assert_eq!(
+                backend.from_rns(&backend.sub(&x, &y)),
+                montgomery.sub(a.clone(), &b)
+            );
+            assert_eq!(
+                backend.from_rns(&backend.square(&x)),
+                montgomery.square(a.clone())
+            );
+
+            // products stay below 2p, so they chain with sums and differences
+            let value = backend.base().reconstruct(&product.base);
+            assert!(value < CurveInt::from(&p * 2u32));
+            let mixed = backend.mul(&backend.sub(&product, &x), &backend.add(&product, &y));
+            let ab = montgomery.mul(a.clone(), &b);
+            let expected = montgomery.mul(montgomery.sub(ab.clone(), &a), &montgomery.add(ab, &b));
+            assert_eq!(backend.from_rns(&mixed), expected);
+        }
+
+        assert_eq!(backend.from_rns(&backend.one()), CurveInt::from(1));
+        assert_eq!(backend.from_rns(&backend.zero()), CurveInt::new());
+        let a = backend.to_rns(&CurveInt::from(5));
+        assert_eq!(backend.from_rns(&backend.mul(&a, &backend.one())), 5);
+    }
+}
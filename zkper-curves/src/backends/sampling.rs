@@ -0,0 +1,131 @@
+//! Uniform sampling below an arbitrary modulus.
+//!
+//! [`sample_below`] draws exactly uniform values by rejection: it draws as
+//! many random bits as the modulus has and retries while the draw is not
+//! below it. Each draw succeeds with probability above 1/2, so it takes
+//! fewer than two draws on average, whatever the modulus.
+//!
+//! [`sample_below_wide`] never retries, for callers that need a fixed amount
+//! of randomness per sample: it reduces `WIDE_SECURITY_BITS` more bits than
+//! the modulus has, which leaves a statistical distance below
+//! 2^-WIDE_SECURITY_BITS from uniform.
+//!
+//! Both read the random bytes big-endian, independently of the size of the
+//! modulus.
+
+use rand_core::RngCore;
+use rug::integer::Order;
+
+use super::curve_int::CurveInt;
+
+/// Extra bits reduced by `sample_below_wide`.
+pub const WIDE_SECURITY_BITS: u32 = 128;
+
+/// A uniform value in [0, modulus), see the module documentation.
+///
+/// Panics if `modulus` is not positive.
+pub fn sample_below<R: RngCore>(rng: &mut R, modulus: &CurveInt) -> CurveInt {
+    assert!(*modulus > 0, "cannot sample below {modulus}");
+
+    let bits = modulus.significant_bits();
+    loop {
+        let value = sample_bits(rng, bits);
+        if value < *modulus {
+            return value;
+        }
+    }
+}
+
+/// A value in [0, modulus) within 2^-WIDE_SECURITY_BITS of uniform, from a
+/// fixed number of random bytes.
+///
+/// Panics if `modulus` is not positive.
+pub fn sample_below_wide<R: RngCore>(rng: &mut R, modulus: &CurveInt) -> CurveInt {
+    assert!(*modulus > 0, "cannot sample below {modulus}");
+
+    sample_bits(rng, modulus.significant_bits() + WIDE_SECURITY_BITS) % modulus
+}
+
+/// A uniform value in [0, 2^bits), from ceil(bits / 8) big-endian bytes.
+fn sample_bits<R: RngCore>(rng: &mut R, bits: u32) -> CurveInt {
+    let len = bits.div_ceil(8);
+    let mut bytes = vec![0u8; len as usize];
+    rng.fill_bytes(&mut bytes);
+    if let Some(top) = bytes.first_mut() {
+        // clear the bits of the leading byte above `bits`
+        *top &= 0xff >> (len * 8 - bits);
+    }
+    CurveInt::from_digits(&bytes, Order::Msf)
+}
+
+#[cfg(test)]
+mod tests {
+    use zkper_rand::TestRng;
+
+    use super::*;
+    use crate::curves::bls12_381::{BLS12_381_BASE, BLS12_381_SCALAR};
+
+    /// Pearson's chi-squared statistic of `samples` draws over [0, modulus).
+    fn chi_squared(
+        modulus: u32,
+        samples: usize,
+        sample: impl Fn(&mut TestRng, &CurveInt) -> CurveInt,
+    ) -> f64 {
+        let mut rng = TestRng::new();
+        let modulus_int = CurveInt::from(modulus);
+        let mut counts = vec![0usize; modulus as usize];
+        for _ in 0..samples {
+            let value = sample(&mut rng, &modulus_int);
+            assert!(value >= 0 && value < modulus_int);
+            counts[value.to_usize().unwrap()] += 1;
+        }
+
+        let expected = samples as f64 / modulus as f64;
+        counts
+            .iter()
+            .map(|&count| (count as f64 - expected).powi(2) / expected)
+            .sum()
+    }
+
+    #[test]
+    fn test_uniformity() {
+        // 99.9% quantiles of the chi-squared distribution with modulus - 1
+        // degrees of freedom: 5 -> 18.47, 12 -> 32.91, 256 -> 330.5
+        for (modulus, bound) in [(6, 18.47), (13, 32.91), (257, 330.5)] {
+            let samples = modulus as usize * 200;
+            let rejection = chi_squared(modulus, samples, sample_below);
+            let wide = chi_squared(modulus, samples, sample_below_wide);
+            assert!(rejection < bound, "{modulus}: {rejection}");
+            assert!(wide < bound, "{modulus}: {wide}");
+        }
+    }
+
+    #[test]
+    fn test_sample_below() {
+        let mut rng = TestRng::new();
+
+        for modulus in [BLS12_381_BASE.modulus_ref(), BLS12_381_SCALAR.modulus_ref()] {
+            // about 40% of the values below these moduli have their top bit
+            // set, which a draw that dropped the leading byte would miss
+            let top = modulus.significant_bits() - 1;
+            let mut top_set = 0;
+            for _ in 0..256 {
+                let value = sample_below(&mut rng, modulus);
+                assert!(value >= 0 && value < *modulus);
+                top_set += value.get_bit(top) as usize;
+
+                let wide = sample_below_wide(&mut rng, modulus);
+                assert!(wide >= 0 && wide < *modulus);
+            }
+            assert!((64..192).contains(&top_set), "{top_set}");
+        }
+
+        assert_eq!(sample_below(&mut rng, &CurveInt::from(1)), 0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_sample_below_zero() {
+        sample_below(&mut TestRng::new(), &CurveInt::ZERO);
+    }
+}
@@ -0,0 +1,121 @@
+//! Lazily initialized, self-checking global constants.
+//!
+//! A [`Constant`] runs its initializer once, on first use, and keeps either the
+//! value or the reason it was rejected. The initializers re-derive or sanity
+//! check what they build (the Montgomery parameters invert, a generator lies on
+//! its curve, a Frobenius coefficient is the power it claims to be), so a bad
+//! constant shows up as a [`ConstantError`] naming it, instead of a wrong
+//! result or a panic somewhere inside a pairing.
+
+use std::{fmt, ops::Deref, sync::OnceLock};
+
+use thiserror::Error;
+
+/// A global constant failed its checks during initialization.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("constant {name} is invalid: {reason}")]
+pub struct ConstantError {
+    pub name: &'static str,
+    pub reason: String,
+}
+
+impl ConstantError {
+    pub fn new(name: &'static str, reason: impl Into<String>) -> Self {
+        Self {
+            name,
+            reason: reason.into(),
+        }
+    }
+}
+
+/// Returns `Err(ConstantError)` for `name` unless `condition` holds.
+pub fn ensure(name: &'static str, condition: bool, reason: &str) -> Result<(), ConstantError> {
+    if condition {
+        Ok(())
+    } else {
+        Err(ConstantError::new(name, reason))
+    }
+}
+
+/// A `static` computed and validated on first access.
+///
+/// Dereferencing panics with the [`ConstantError`] if the checks failed; use
+/// [`Constant::try_get`] to handle it instead.
+pub struct Constant<T: 'static> {
+    name: &'static str,
+    init: fn() -> Result<T, ConstantError>,
+    cell: OnceLock<Result<T, ConstantError>>,
+}
+
+impl<T> Constant<T> {
+    pub const fn new(name: &'static str, init: fn() -> Result<T, ConstantError>) -> Self {
+        Self {
+            name,
+            init,
+            cell: OnceLock::new(),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// The value, initializing it on the first call.
+    pub fn try_get(&self) -> Result<&T, ConstantError> {
+        self.cell
+            .get_or_init(self.init)
+            .as_ref()
+            .map_err(Clone::clone)
+    }
+}
+
+impl<T> Deref for Constant<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        match self.try_get() {
+            Ok(value) => value,
+            Err(err) => panic!("{}", err),
+        }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Constant<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.cell.get() {
+            Some(Ok(value)) => value.fmt(f),
+            Some(Err(err)) => write!(f, "<{}>", err),
+            None => write!(f, "<{} uninitialized>", self.name),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static ANSWER: Constant<u64> = Constant::new("ANSWER", || Ok(42));
+    static BROKEN: Constant<u64> = Constant::new("BROKEN", || {
+        ensure("BROKEN", false, "arithmetic is off")?;
+        Ok(0)
+    });
+
+    #[test]
+    fn test_constant() {
+        assert_eq!(*ANSWER, 42);
+        assert_eq!(ANSWER.try_get(), Ok(&42));
+
+        let err = BROKEN.try_get().unwrap_err();
+        assert_eq!(err, ConstantError::new("BROKEN", "arithmetic is off"));
+        assert_eq!(
+            err.to_string(),
+            "constant BROKEN is invalid: arithmetic is off"
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "constant BROKEN is invalid")]
+    fn test_constant_deref_panics() {
+        let _ = *BROKEN;
+    }
+}
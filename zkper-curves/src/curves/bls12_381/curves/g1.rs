@@ -1,33 +1,98 @@
 use crate::{
-    backends::montgomery::INTEGER_FOUR,
-    curves::bls12_381::{BLS12_381_BASE, MILLER_LOOP_CONSTANT, MILLER_LOOP_CONSTANT_IS_NEG},
+    backends::{
+        limbs::to_integer,
+        montgomery::{INTEGER_EIGHT, INTEGER_FOUR, INTEGER_THREE, INTEGER_TWELVE},
+    },
+    constant::{ensure, Constant, ConstantError},
+    curves::bls12_381::{
+        Scalar, BLS12_381_BASE, MILLER_LOOP_CONSTANT, MILLER_LOOP_CONSTANT_IS_NEG,
+    },
+    fp,
 };
 
 use self::g1_affine::G1Affine;
 
 use super::*;
 
-lazy_static::lazy_static! {
-    /// The generators of G1 and G2 are computed by finding the lexicographically smallest valid x-coordinate,
-    /// and its lexicographically smallest y-coordinate and scaling it by the cofactor such that the result is not the point at infinity.
-    ///
-    /// x = 3685416753713387016781088315183077757961620795782546409894578378688607592378376318836054947676345821548104185464507
-    /// y = 1339506544944476473020471379941921221584933875938349620426543736416511423956333506472724655353366534992391756441569
-    pub static ref G1_GENERATOR_X: Integer = Integer::from_str_radix(
-        "3685416753713387016781088315183077757961620795782546409894578378688607592378376318836054947676345821548104185464507",
-        10
-    ).expect("failed to parse generator integer");
-    pub static ref G1_GENERATOR_Y: Integer = Integer::from_str_radix(
-        "1339506544944476473020471379941921221584933875938349620426543736416511423956333506472724655353366534992391756441569",
-        10
-    ).expect("failed to parse generator integer");
+/// The generators of G1 and G2 are computed by finding the lexicographically smallest valid x-coordinate,
+/// and its lexicographically smallest y-coordinate and scaling it by the cofactor such that the result is not the point at infinity.
+///
+/// x = 3685416753713387016781088315183077757961620795782546409894578378688607592378376318836054947676345821548104185464507
+/// y = 1339506544944476473020471379941921221584933875938349620426543736416511423956333506472724655353366534992391756441569
+pub static G1_GENERATOR_X: Constant<Bls12_381BaseField> = Constant::new("G1_GENERATOR_X", || {
+    Ok(checked_generator("G1_GENERATOR_X")?.x)
+});
+pub static G1_GENERATOR_Y: Constant<Bls12_381BaseField> = Constant::new("G1_GENERATOR_Y", || {
+    Ok(checked_generator("G1_GENERATOR_Y")?.y)
+});
+
+/// The generator, checked to lie on the curve.
+fn checked_generator(name: &'static str) -> Result<G1Affine, ConstantError> {
+    let generator = G1Affine {
+        x: Bls12_381BaseField(to_integer(&fp!(
+            "0x17f1d3a73197d7942695638c4fa9ac0fc3688c4f9774b905a14e3a3f171bac586c55e83ff97a1aeffb3af00adb22c6bb"
+        ))),
+        y: Bls12_381BaseField(to_integer(&fp!(
+            "0x08b3f481e3aaa0f1a09e30ed741d8ae4fcf5e095d5d00af600db18cb2c04b3edd03cc744a2888ae40caa232946c5e7e1"
+        ))),
+        infinity: false,
+    };
+    ensure(
+        name,
+        generator.is_on_curve(),
+        "generator is not on the curve",
+    )?;
+
+    Ok(generator)
+}
+
+/// The SvdW constants of E: y^2 = x^3 + 4 for Z = -3, the first candidate
+/// meeting the criteria of `find_z_svdw` (RFC 9380, appendix H.1), which are
+/// checked here.
+pub(crate) static G1_SVDW: Constant<Svdw<Integer>> = Constant::new("G1_SVDW", || {
+    let name = "G1_SVDW";
+    let f = BLS12_381_BASE.try_get()?;
+    let is_square = |x: &Integer| x.legendre(f.modulus_ref()) >= 0;
+
+    let z = f.neg(INTEGER_THREE.clone());
+    let c1 = g1_curve(&z);
+    let three_z2 = f.mul(f.square(z.clone()), INTEGER_THREE);
+    let c2 = f.mul(
+        f.neg(z.clone()),
+        &f.invert(Integer::from(2)).expect("2 is invertible"),
+    );
+    ensure(name, !c1.is_zero(), "g(Z) is zero")?;
+    ensure(
+        name,
+        is_square(&c1) || is_square(&g1_curve(&c2)),
+        "neither g(Z) nor g(-Z / 2) is a square",
+    )?;
+
+    let c3 = f
+        .sqrt(f.neg(f.mul(c1.clone(), &three_z2)))
+        .ok_or_else(|| ConstantError::new(name, "-g(Z) * 3Z^2 is not a square"))?;
+    let c3 = if c3.is_odd() { f.neg(c3) } else { c3 };
+    let c4 = f.mul(
+        f.neg(f.mul(c1.clone(), INTEGER_FOUR)),
+        &f.invert(three_z2)
+            .ok_or_else(|| ConstantError::new(name, "Z is zero"))?,
+    );
+    // -3Z^2 / 4g(Z) is the inverse of c4
+    ensure(name, is_square(&c4), "-3Z^2 / 4g(Z) is not a square")?;
+
+    Ok(Svdw { z, c1, c2, c3, c4 })
+});
+
+/// x^3 + 4
+fn g1_curve(x: &Integer) -> Integer {
+    BLS12_381_BASE.add(BLS12_381_BASE.cubic(x.clone()), INTEGER_FOUR)
 }
 
 #[derive(Clone, Debug)]
 pub struct G1Projective {
-    pub x: Integer,
-    pub y: Integer,
-    pub z: Integer,
+    pub x: Bls12_381BaseField,
+    pub y: Bls12_381BaseField,
+    pub z: Bls12_381BaseField,
 }
 
 impl G1Projective {
@@ -35,7 +100,7 @@ impl G1Projective {
         Self {
             x: G1_GENERATOR_X.clone(),
             y: G1_GENERATOR_Y.clone(),
-            z: Integer::ONE.clone(),
+            z: Bls12_381BaseField::from(1),
         }
     }
 }
@@ -46,9 +111,9 @@ impl From<G1Affine> for G1Projective {
             x: p.x.clone(),
             y: p.y.clone(),
             z: if p.infinity {
-                Integer::ZERO.clone()
+                Bls12_381BaseField::from(0)
             } else {
-                Integer::ONE.clone()
+                Bls12_381BaseField::from(1)
             },
         }
     }
@@ -56,6 +121,11 @@ impl From<G1Affine> for G1Projective {
 
 impl PartialEq for G1Projective {
     fn eq(&self, other: &Self) -> bool {
+        // every point with z = 0 is the identity, whatever its x and y
+        if self.is_identity() || other.is_identity() {
+            return self.is_identity() && other.is_identity();
+        }
+
         let self_normalized = self.normalize();
         let other_normalized = other.normalize();
 
@@ -73,9 +143,9 @@ impl<'a> From<&'a G1Affine> for G1Projective {
             x: p.x.clone(),
             y: p.y.clone(),
             z: if p.infinity {
-                Bls12_381BaseField::zero()
+                Bls12_381BaseField::from(0)
             } else {
-                Bls12_381BaseField::one()
+                Bls12_381BaseField::from(1)
             },
         }
     }
@@ -84,9 +154,9 @@ impl<'a> From<&'a G1Affine> for G1Projective {
 impl From<(rug::Integer, rug::Integer, rug::Integer)> for G1Projective {
     fn from(p: (rug::Integer, rug::Integer, rug::Integer)) -> G1Projective {
         G1Projective {
-            x: p.0,
-            y: p.1,
-            z: p.2,
+            x: Bls12_381BaseField(p.0),
+            y: Bls12_381BaseField(p.1),
+            z: Bls12_381BaseField(p.2),
         }
     }
 }
@@ -97,40 +167,65 @@ impl fmt::Display for G1Projective {
             write!(
                 f,
                 "G1Projective {{\n    x: {},\n    y: {},\n    z: {}\n}}",
-                self.x.to_string_radix(16),
-                self.y.to_string_radix(16),
-                self.z.to_string_radix(16),
+                self.x.0.to_string_radix(16),
+                self.y.0.to_string_radix(16),
+                self.z.0.to_string_radix(16),
             )
         } else {
             write!(
                 f,
                 "G1Projective({}, {}, {})",
-                self.x.to_string_radix(16),
-                self.y.to_string_radix(16),
-                self.z.to_string_radix(16),
+                self.x.0.to_string_radix(16),
+                self.y.0.to_string_radix(16),
+                self.z.0.to_string_radix(16),
             )
         }
     }
 }
 
 impl G1Projective {
-    pub fn new(x: Integer, y: Integer, z: Integer) -> Self {
+    pub fn new(x: Bls12_381BaseField, y: Bls12_381BaseField, z: Bls12_381BaseField) -> Self {
         Self { x, y, z }
     }
 
     pub fn to_tuple(&self) -> (Integer, Integer, Integer) {
-        (self.x.clone(), self.y.clone(), self.z.clone())
+        (self.x.0.clone(), self.y.0.clone(), self.z.0.clone())
     }
 
+    /// The affine point (X / Z, Y / Z), or the identity when Z = 0. This and
+    /// `batch_normalize`, which only shares the inversion, are the one way to
+    /// leave projective coordinates; every other conversion calls them.
     pub fn to_affine(&self) -> G1Affine {
-        self.into()
+        let z_inv = BLS12_381_BASE
+            .invert(BLS12_381_BASE.reduce(&self.z.0))
+            .unwrap_or(Integer::ZERO);
+        self.affine_with_z_inv(&z_inv)
+    }
+
+    /// (X z_inv, Y z_inv), where a zero `z_inv` stands for Z = 0.
+    fn affine_with_z_inv(&self, z_inv: &Integer) -> G1Affine {
+        if z_inv.is_zero() {
+            return G1Affine::identity();
+        }
+
+        G1Affine {
+            x: Bls12_381BaseField(BLS12_381_BASE.mul(self.x.0.clone(), z_inv)),
+            y: Bls12_381BaseField(BLS12_381_BASE.mul(self.y.0.clone(), z_inv)),
+            infinity: false,
+        }
     }
 
     pub fn from_str_hex(x: &str, y: &str, z: &str) -> Self {
         Self {
-            x: Integer::from_str_radix(x.strip_prefix("0x").unwrap_or(x), 16).unwrap(),
-            y: Integer::from_str_radix(y.strip_prefix("0x").unwrap_or(y), 16).unwrap(),
-            z: Integer::from_str_radix(z.strip_prefix("0x").unwrap_or(z), 16).unwrap(),
+            x: Bls12_381BaseField(
+                Integer::from_str_radix(x.strip_prefix("0x").unwrap_or(x), 16).unwrap(),
+            ),
+            y: Bls12_381BaseField(
+                Integer::from_str_radix(y.strip_prefix("0x").unwrap_or(y), 16).unwrap(),
+            ),
+            z: Bls12_381BaseField(
+                Integer::from_str_radix(z.strip_prefix("0x").unwrap_or(z), 16).unwrap(),
+            ),
         }
     }
 }
@@ -139,18 +234,18 @@ impl G1Projective {
     /// Returns the identity element (point at infinity).
     pub fn identity() -> Self {
         G1Projective {
-            x: Bls12_381BaseField::zero(),
-            y: Bls12_381BaseField::one(),
-            z: Bls12_381BaseField::zero(),
+            x: Bls12_381BaseField::from(0),
+            y: Bls12_381BaseField::from(1),
+            z: Bls12_381BaseField::from(0),
         }
     }
 
     /// Returns the identity element (point at infinity). in montgomery form
     pub fn identity_mont() -> Self {
         G1Projective {
-            x: Bls12_381BaseField::zero(),
-            y: Bls12_381BaseField::one(),
-            z: Bls12_381BaseField::zero(),
+            x: Bls12_381BaseField::from(0),
+            y: Bls12_381BaseField::from(1),
+            z: Bls12_381BaseField::from(0),
         }
         .to_montgomery()
     }
@@ -158,13 +253,13 @@ impl G1Projective {
     /// Returns true if this element is the identity (the point at infinity).
     #[inline]
     pub fn is_identity(&self) -> bool {
-        self.z.is_zero()
+        self.z.0.is_zero()
     }
 
     pub fn neg(&self) -> G1Projective {
         G1Projective {
             x: self.x.clone(),
-            y: Bls12_381BaseField::neg(self.y.clone()),
+            y: Bls12_381BaseField(Bls12_381BaseField::neg(self.y.0.clone())),
             z: self.z.clone(),
         }
     }
@@ -172,49 +267,85 @@ impl G1Projective {
     /// to montgomery form
     pub fn to_montgomery(&self) -> G1Projective {
         G1Projective {
-            x: BLS12_381_BASE.to_montgomery(&self.x),
-            y: BLS12_381_BASE.to_montgomery(&self.y),
-            z: BLS12_381_BASE.to_montgomery(&self.z),
+            x: Bls12_381BaseField(BLS12_381_BASE.to_montgomery(&self.x.0)),
+            y: Bls12_381BaseField(BLS12_381_BASE.to_montgomery(&self.y.0)),
+            z: Bls12_381BaseField(BLS12_381_BASE.to_montgomery(&self.z.0)),
         }
     }
 
     /// from montgomery form
     pub fn from_montgomery(&self) -> G1Projective {
         G1Projective {
-            x: BLS12_381_BASE.from_montgomery(&self.x),
-            y: BLS12_381_BASE.from_montgomery(&self.y),
-            z: BLS12_381_BASE.from_montgomery(&self.z),
+            x: Bls12_381BaseField(BLS12_381_BASE.from_montgomery(&self.x.0)),
+            y: Bls12_381BaseField(BLS12_381_BASE.from_montgomery(&self.y.0)),
+            z: Bls12_381BaseField(BLS12_381_BASE.from_montgomery(&self.z.0)),
         }
     }
 
-    /// normalize
+    /// Converts all points to affine form sharing one field inversion.
+    pub fn batch_normalize(points: &[G1Projective]) -> Vec<G1Affine> {
+        let mut z_invs = points
+            .iter()
+            .map(|p| BLS12_381_BASE.reduce(&p.z.0))
+            .collect::<Vec<_>>();
+        BLS12_381_BASE.batch_invert(&mut z_invs);
+
+        points
+            .iter()
+            .zip(z_invs)
+            .map(|(p, z_inv)| p.affine_with_z_inv(&z_inv))
+            .collect()
+    }
+
+    /// normalize, z = 1 unless this is the identity, which becomes (0, 1, 0)
     pub fn normalize(&self) -> G1Projective {
-        BLS12_381_BASE.normalize(&self.x, &self.y, &self.z).into()
+        self.to_affine().into()
+    }
+
+    /// Returns true if the point satisfies Y^2 Z = X^3 + 4 Z^3.
+    pub fn is_on_curve(&self) -> bool {
+        if self.is_identity() {
+            return true;
+        }
+
+        let z3 = BLS12_381_BASE.cubic(self.z.0.clone());
+        let lhs = BLS12_381_BASE.mul(BLS12_381_BASE.square(self.y.0.clone()), &self.z.0);
+        let rhs = BLS12_381_BASE.add(
+            BLS12_381_BASE.cubic(self.x.0.clone()),
+            &BLS12_381_BASE.mul(z3, INTEGER_FOUR),
+        );
+        lhs == rhs
     }
 
     /// Double this point
     pub fn double(&self) -> Self {
         BLS12_381_BASE
-            .double_standard(&self.x, &self.y, &self.z)
+            .double_standard(&self.x.0, &self.y.0, &self.z.0)
             .into()
     }
 
     /// Double this point in montgomery form
     pub fn double_mont(&self) -> Self {
-        BLS12_381_BASE.double_mont(&self.x, &self.y, &self.z).into()
+        BLS12_381_BASE
+            .double_mont(&self.x.0, &self.y.0, &self.z.0)
+            .into()
     }
 
     /// Add this point to another point
     pub fn add(&self, other: &G1Projective) -> Self {
         BLS12_381_BASE
-            .add_standard(&self.x, &self.y, &self.z, &other.x, &other.y, &other.z)
+            .add_standard(
+                &self.x.0, &self.y.0, &self.z.0, &other.x.0, &other.y.0, &other.z.0,
+            )
             .into()
     }
 
     /// Add this point to another point in montgomery form
     pub fn add_mont(&self, other: &G1Projective) -> Self {
         BLS12_381_BASE
-            .add_mont(&self.x, &self.y, &self.z, &other.x, &other.y, &other.z)
+            .add_mont(
+                &self.x.0, &self.y.0, &self.z.0, &other.x.0, &other.y.0, &other.z.0,
+            )
             .into()
     }
 
@@ -229,7 +360,13 @@ impl G1Projective {
     }
 
     /// Scalar multiplication of a G1Projective point
-    pub fn mul_scalar(&self, scalar: &Integer) -> Self {
+    pub fn mul_scalar(&self, scalar: &Scalar) -> Self {
+        self.mul_by_integer(&scalar.0)
+    }
+
+    /// Multiplies by an arbitrary non-negative integer, such as the group
+    /// order or a cofactor. Use `mul_scalar` for elements of Fr.
+    pub(crate) fn mul_by_integer(&self, scalar: &Integer) -> Self {
         if scalar.is_zero() {
             return G1Projective::identity();
         }
@@ -249,6 +386,115 @@ impl G1Projective {
         result
     }
 
+    /// Adds this point to another point with the complete formula for
+    /// a = 0. There are no special cases: the identity, doubling and
+    /// inverse points all take the same steps.
+    ///
+    /// ref: Algorithm 7, https://eprint.iacr.org/2015/1060.pdf
+    pub fn add_complete(&self, rhs: &G1Projective) -> Self {
+        let f = &*BLS12_381_BASE;
+        let b3 = INTEGER_TWELVE;
+
+        let t0 = f.mul(self.x.0.clone(), &rhs.x.0);
+        let t1 = f.mul(self.y.0.clone(), &rhs.y.0);
+        let t2 = f.mul(self.z.0.clone(), &rhs.z.0);
+        let t3 = f.mul(
+            f.add(self.x.0.clone(), &self.y.0),
+            &f.add(rhs.x.0.clone(), &rhs.y.0),
+        );
+        let t3 = f.sub(t3, &f.add(t0.clone(), &t1));
+        let t4 = f.mul(
+            f.add(self.y.0.clone(), &self.z.0),
+            &f.add(rhs.y.0.clone(), &rhs.z.0),
+        );
+        let t4 = f.sub(t4, &f.add(t1.clone(), &t2));
+        let y3 = f.mul(
+            f.add(self.x.0.clone(), &self.z.0),
+            &f.add(rhs.x.0.clone(), &rhs.z.0),
+        );
+        let y3 = f.sub(y3, &f.add(t0.clone(), &t2));
+        let t0 = f.add(f.add(t0.clone(), &t0), &t0);
+        let t2 = f.mul(t2, b3);
+        let z3 = f.add(t1.clone(), &t2);
+        let t1 = f.sub(t1, &t2);
+        let y3 = f.mul(y3, b3);
+        let x3 = f.sub(f.mul(t3.clone(), &t1), &f.mul(t4.clone(), &y3));
+        let y3 = f.add(f.mul(t1, &z3), &f.mul(y3, &t0));
+        let z3 = f.add(f.mul(z3, &t4), &f.mul(t0, &t3));
+
+        G1Projective {
+            x: Bls12_381BaseField(x3),
+            y: Bls12_381BaseField(y3),
+            z: Bls12_381BaseField(z3),
+        }
+    }
+
+    /// Doubles this point with the complete formula for a = 0.
+    ///
+    /// ref: Algorithm 9, https://eprint.iacr.org/2015/1060.pdf
+    pub fn double_complete(&self) -> Self {
+        let f = &*BLS12_381_BASE;
+
+        let t0 = f.square(self.y.0.clone());
+        let z3 = f.mul(t0.clone(), INTEGER_EIGHT);
+        let t1 = f.mul(self.y.0.clone(), &self.z.0);
+        let t2 = f.mul(f.square(self.z.0.clone()), INTEGER_TWELVE);
+        let x3 = f.mul(t2.clone(), &z3);
+        let y3 = f.add(t0.clone(), &t2);
+        let z3 = f.mul(t1, &z3);
+        let t0 = f.sub(t0, &f.mul(t2, INTEGER_THREE));
+        let y3 = f.add(x3, &f.mul(t0.clone(), &y3));
+        let x3 = f.mul(f.mul(t0, &self.x.0), &self.y.0);
+        let x3 = f.add(x3.clone(), &x3);
+
+        G1Projective {
+            x: Bls12_381BaseField(x3),
+            y: Bls12_381BaseField(y3),
+            z: Bls12_381BaseField(z3),
+        }
+    }
+
+    /// Scalar multiplication with the chosen execution strategy, see
+    /// [`Uniform`]. Use `Uniform::Ladder` for secret scalars.
+    pub fn mul_scalar_with(&self, scalar: &Scalar, uniform: Uniform) -> Self {
+        match uniform {
+            Uniform::Variable => self.mul_scalar(scalar),
+            Uniform::Ladder => self.mul_scalar_ladder(scalar),
+        }
+    }
+
+    /// Montgomery ladder over every bit of Fr, with complete formulas only.
+    pub fn mul_scalar_ladder(&self, scalar: &Scalar) -> Self {
+        let mut r0 = G1Projective::identity();
+        let mut r1 = self.clone();
+
+        for bit in ladder_bits(scalar) {
+            r0.conditional_swap(&mut r1, bit);
+            r1 = r0.add_complete(&r1);
+            r0 = r0.double_complete();
+            r0.conditional_swap(&mut r1, bit);
+        }
+
+        r0
+    }
+
+    /// Swaps the two points if `swap` is set, through the same field
+    /// operations either way.
+    fn conditional_swap(&mut self, other: &mut G1Projective, swap: bool) {
+        let f = &*BLS12_381_BASE;
+        let choice = Integer::from(swap);
+
+        for (a, b) in [
+            (&mut self.x.0, &mut other.x.0),
+            (&mut self.y.0, &mut other.y.0),
+            (&mut self.z.0, &mut other.z.0),
+        ] {
+            let delta = f.mul(f.sub(a.clone(), b), &choice);
+            *a = f.sub(std::mem::take(a), &delta);
+            *b = f.add(std::mem::take(b), &delta);
+        }
+    }
+
     /// Scalar multiplication of a G1Projective point in Montgomery form using binary expansion method
     pub fn mul_scalar_mont(&self, scalar: &Integer) -> Self {
         if scalar.is_zero() {
@@ -322,105 +568,106 @@ impl G1Projective {
         }
     }
 
-    /// Final exponentiation in the context of the Miller loop
-    /// This operation ensures that the point is in the correct subgroup
+    /// Clears the cofactor, mapping any point of the curve into G1.
     ///
     /// Multiplies by $(1 - z)$, where $z$ is the parameter of BLS12-381, which
     /// [suffices to clear](https://ia.cr/2019/403) the cofactor and map
     /// elliptic curve points to elements of $\mathbb{G}\_1$.
-    pub fn final_exponentiation(&self) -> G1Projective {
+    pub fn clear_cofactor(&self) -> G1Projective {
         self.sub(&self.frobenius_map())
     }
 
-    /// Final exponentiation in the context of the Miller loop in montgomery form
-    pub fn final_exponentiation_mont(&self) -> G1Projective {
+    /// `clear_cofactor` in montgomery form
+    pub fn clear_cofactor_mont(&self) -> G1Projective {
         self.sub_mont(&self.frobenius_map_mont())
     }
 
+    #[deprecated(note = "this is cofactor clearing, use `clear_cofactor`")]
+    pub fn final_exponentiation(&self) -> G1Projective {
+        self.clear_cofactor()
+    }
+
+    #[deprecated(note = "this is cofactor clearing, use `clear_cofactor_mont`")]
+    pub fn final_exponentiation_mont(&self) -> G1Projective {
+        self.clear_cofactor_mont()
+    }
+
+    /// The Shallue–van de Woestijne map (RFC 9380, section 6.6.1), sending
+    /// any field element to a point of the curve deterministically, without a
+    /// rejection loop. The point is not in G1 yet, see `map_to_group`.
+    pub fn map_to_curve(u: &Integer) -> G1Projective {
+        let svdw = &*G1_SVDW;
+        let f = &*BLS12_381_BASE;
+        let u = f.reduce(u);
+
+        let tv1 = f.mul(f.square(u.clone()), &svdw.c1);
+        let tv2 = f.add(Integer::from(1), &tv1);
+        let tv1 = f.sub(Integer::from(1), &tv1);
+        // inv0, zero maps to zero
+        let tv3 = f.invert(f.mul(tv1.clone(), &tv2)).unwrap_or_default();
+        let tv4 = f.mul(f.mul(f.mul(u.clone(), &tv1), &tv3), &svdw.c3);
+
+        let x1 = f.sub(svdw.c2.clone(), &tv4);
+        let x2 = f.add(svdw.c2.clone(), &tv4);
+        let x3 = f.add(
+            f.mul(f.square(f.mul(f.square(tv2), &tv3)), &svdw.c4),
+            &svdw.z,
+        );
+        let (x, y) = [x1, x2, x3]
+            .into_iter()
+            .find_map(|x| Some((x.clone(), f.sqrt(g1_curve(&x))?)))
+            .expect("one of g(x1), g(x2) and g(x3) is a square");
+        let y = if y.is_odd() == u.is_odd() {
+            y
+        } else {
+            f.neg(y)
+        };
+
+        G1Projective {
+            x: Bls12_381BaseField(x),
+            y: Bls12_381BaseField(y),
+            z: Bls12_381BaseField::from(1),
+        }
+    }
+
+    /// Maps two field elements into G1: `map_to_curve` of each, added, with
+    /// the cofactor cleared. With u0 and u1 from a hash to the field this is
+    /// the encoding of RFC 9380, and the result is uniform in G1.
+    pub fn map_to_group(u0: &Integer, u1: &Integer) -> G1Projective {
+        Self::map_to_curve(u0)
+            .add(&Self::map_to_curve(u1))
+            .clear_cofactor()
+    }
+
     /// Returns a random element in G1
     pub fn random<R: RngCore>(rng: &mut R) -> Self {
         loop {
-            let x: Bls12_381BaseField = rng.gen();
-
-            let flip_sign = rng.next_u32() % 2 != 0;
+            let u0 = Bls12_381BaseField::random(rng);
+            let u1 = Bls12_381BaseField::random(rng);
+            let point = Self::map_to_group(&u0, &u1);
 
-            // Compute y = sqrt(x^3 + 4)
-            let y_squared = Bls12_381BaseField::cubic(x.0.clone()) + INTEGER_FOUR;
-            let y_squared = Bls12_381BaseField::sqrt(y_squared);
-
-            if let Some(y) = y_squared {
-                let y = if flip_sign {
-                    Bls12_381BaseField::neg(y)
-                } else {
-                    y
-                };
-
-                let point = G1Projective {
-                    x: x.0,
-                    y,
-                    z: Bls12_381BaseField::one(),
-                };
-
-                // clear cofactor
-                let proj_point = point.final_exponentiation();
-
-                // Ensure the generated point is not the point at infinity
-                if !proj_point.is_identity() {
-                    return proj_point;
-                }
+            // Ensure the generated point is not the point at infinity
+            if !point.is_identity() {
+                return point;
             }
         }
     }
 
     /// Returns a random element in G1 in montgomery form
     pub fn random_mont<R: RngCore>(rng: &mut R) -> Self {
-        loop {
-            let x = Bls12_381BaseField::random_mont(rng);
-
-            let flip_sign = rng.next_u32() % 2 != 0;
-
-            // Compute y = sqrt(x^3 + 4)
-            let y_squared = BLS12_381_BASE.add(
-                BLS12_381_BASE.mont_cubic(&x),
-                &BLS12_381_BASE.to_montgomery(&INTEGER_FOUR),
-            );
-
-            let y_squared = BLS12_381_BASE.mont_sqrt(&y_squared);
-
-            if let Some(y) = y_squared {
-                let y = if flip_sign {
-                    Bls12_381BaseField::neg(y)
-                } else {
-                    y
-                };
-
-                // Create affine point
-                let point = G1Projective {
-                    x,
-                    y,
-                    z: Bls12_381BaseField::r().clone(),
-                };
-
-                // clear cofactor
-                let proj_point = point.final_exponentiation_mont();
-
-                // Ensure the generated point is not the point at infinity
-                if !proj_point.is_identity() {
-                    return proj_point;
-                }
-            }
-        }
+        Self::random(rng).to_montgomery()
     }
 }
 
 #[cfg(test)]
 mod tests {
 
-    use zkper_rand::ZkperRng;
+    use rand::Rng;
+    use zkper_rand::TestRng;
 
     use crate::{
-        backends::montgomery::{INTEGER_EIGHT, INTEGER_FOUR},
-        curves::bls12_381::{self, BLS12_381_BASE, BLS12_381_SCALAR},
+        backends::montgomery::INTEGER_FOUR,
+        curves::bls12_381::{Bls12_381ScalarField, BLS12_381_BASE, BLS12_381_SCALAR},
     };
 
     use super::*;
@@ -456,12 +703,247 @@ mod tests {
         println!("p3_raw_norm: {:#}", p3_raw_norm);
     }
 
+    #[test]
+    fn test_generator_and_identity() {
+        let g = G1Projective::generator();
+        let r = BLS12_381_SCALAR.modulus();
+        assert!(g.is_on_curve());
+        assert_eq!(G1Affine::generator().to_curve(), g);
+        assert!(!G1Projective::new(1.into(), 1.into(), 1.into()).is_on_curve());
+
+        // gen * order == identity
+        assert!(g.mul_by_integer(&r).is_identity());
+        assert_eq!(g.mul_scalar(&Scalar::from(r.clone() - 1u32)), g.neg());
+
+        let id = G1Projective::identity();
+        assert!(id.is_on_curve());
+        assert_eq!(id, id.clone());
+        assert_ne!(id, g);
+        assert!(id.to_affine().is_identity());
+        assert!(G1Affine::identity().to_curve().is_identity());
+        assert!(id.normalize().is_identity());
+        assert_eq!(g.add(&id), g);
+        assert_eq!(id.add(&g), g);
+        assert!(g.add(&g.neg()).is_identity());
+        assert!(id.double().is_identity());
+
+        let mut rng = TestRng::new();
+        for _ in 0..4 {
+            let a = Scalar::from(BLS12_381_SCALAR.sample_raw(&mut rng));
+            let b = Scalar::from(BLS12_381_SCALAR.sample_raw(&mut rng));
+            let ab = a.add(&b);
+            assert_eq!(g.mul_scalar(&ab), g.mul_scalar(&a).add(&g.mul_scalar(&b)));
+            assert!(g.mul_scalar(&a).is_on_curve());
+            assert!(G1Projective::random(&mut rng).is_on_curve());
+        }
+    }
+
+    #[test]
+    fn test_complete_formulas_and_ladder() {
+        let mut rng = TestRng::new();
+        let g = G1Projective::generator();
+        let p = G1Projective::random(&mut rng);
+        let id = G1Projective::identity();
+
+        // no special cases: identity, doubling and inverses
+        assert_eq!(g.add_complete(&p), g.add(&p));
+        assert_eq!(g.add_complete(&g), g.double());
+        assert_eq!(g.add_complete(&id), g);
+        assert_eq!(id.add_complete(&g), g);
+        assert!(id.add_complete(&id).is_identity());
+        assert!(g.add_complete(&g.neg()).is_identity());
+        assert_eq!(p.double_complete(), p.double());
+        assert!(id.double_complete().is_identity());
+
+        let r = BLS12_381_SCALAR.modulus();
+        let mut scalars = vec![
+            Scalar::from(Integer::ZERO),
+            Scalar::from(Integer::from(1)),
+            Scalar::from(r.clone() - 1u32),
+            // unreduced scalars are taken mod r
+            Bls12_381ScalarField(r + 5u32),
+        ];
+        for _ in 0..4 {
+            scalars.push(Scalar::from(BLS12_381_SCALAR.sample_raw(&mut rng)));
+        }
+        for scalar in &scalars {
+            let expected = p.mul_scalar(&Scalar::from(BLS12_381_SCALAR.reduce(&scalar.0)));
+            assert_eq!(p.mul_scalar_ladder(scalar), expected);
+            assert_eq!(p.mul_scalar_with(scalar, Uniform::Ladder), expected);
+            assert_eq!(p.mul_scalar_with(scalar, Uniform::Variable), expected);
+        }
+    }
+
+    #[test]
+    fn test_batch_normalize() {
+        let mut rng = TestRng::new();
+        let mut points = (0..8)
+            .map(|_| G1Projective::random(&mut rng).double())
+            .collect::<Vec<_>>();
+        points.insert(3, G1Projective::identity());
+
+        let affine = G1Projective::batch_normalize(&points);
+        assert_eq!(affine.len(), points.len());
+        for (p, a) in points.iter().zip(affine) {
+            assert_eq!(p.to_affine(), a);
+        }
+    }
+
+    #[test]
+    fn test_normalization_paths() {
+        let mut rng = TestRng::new();
+        let g = G1Projective::generator();
+        let p = BLS12_381_BASE.modulus_ref();
+
+        let mut points = vec![
+            G1Projective::identity(),
+            // Z = 0 with arbitrary X and Y is still the identity
+            G1Projective::new(5.into(), 7.into(), 0.into()),
+            g.add(&g.neg()),
+            // Z = p and Z = p + 1 are 0 and 1, just not reduced
+            G1Projective::new(g.x.clone(), g.y.clone(), Bls12_381BaseField(p.clone())),
+            G1Projective::new(
+                g.x.clone(),
+                g.y.clone(),
+                Bls12_381BaseField(p.clone() + 1u32),
+            ),
+            G1Projective::new(Bls12_381BaseField(g.x.0.clone() + p), g.y.clone(), 1.into()),
+            g.clone(),
+            g.neg(),
+            g.double(),
+        ];
+        for _ in 0..16 {
+            // the same point behind a random Z
+            let point = G1Projective::random(&mut rng);
+            let lambda = Bls12_381BaseField::from(BLS12_381_BASE.sample_raw(&mut rng));
+            points.push(G1Projective::new(
+                &point.x * &lambda,
+                &point.y * &lambda,
+                &point.z * &lambda,
+            ));
+            points.push(point);
+        }
+
+        let batch = G1Projective::batch_normalize(&points);
+        for (point, batched) in points.iter().zip(batch) {
+            let affine = point.to_affine();
+            assert_eq!(G1Affine::from(point), affine);
+            assert_eq!(G1Affine::from(point.clone()), affine);
+            assert_eq!(batched, affine);
+            assert_eq!(point.normalize(), G1Projective::from(&affine));
+            assert_eq!(
+                BLS12_381_BASE.normalize(&point.x.0, &point.y.0, &point.z.0),
+                G1Projective::from(&affine).to_tuple()
+            );
+
+            assert!(affine.is_on_curve());
+            assert_eq!(
+                G1Affine::from_uncompressed(&affine.to_uncompressed()),
+                Some(affine.clone())
+            );
+            assert_eq!(
+                G1Affine::from_compressed(&affine.to_compressed()),
+                Some(affine)
+            );
+        }
+    }
+
+    #[test]
+    fn test_clear_cofactor() {
+        let mut rng = TestRng::new();
+        let r = BLS12_381_SCALAR.modulus_ref();
+        // 1 - z
+        let h_eff = Integer::from(MILLER_LOOP_CONSTANT) + 1;
+
+        let mut found = 0;
+        while found < 5 {
+            let x: Bls12_381BaseField = rng.gen();
+            let y_squared = Bls12_381BaseField::cubic(x.0.clone()) + INTEGER_FOUR;
+            let Some(y) = Bls12_381BaseField::sqrt(y_squared) else {
+                continue;
+            };
+            found += 1;
+
+            // a point of the curve, almost surely outside G1
+            let point = G1Projective {
+                x,
+                y: Bls12_381BaseField(y),
+                z: Bls12_381BaseField::from(1),
+            };
+            assert!(point.is_on_curve());
+            assert!(!point.mul_by_integer(r).is_identity());
+
+            let cleared = point.clear_cofactor();
+            assert!(cleared.mul_by_integer(r).is_identity());
+            assert_eq!(
+                cleared.to_affine(),
+                point.mul_by_integer(&h_eff).to_affine()
+            );
+
+            let cleared_mont = point.to_montgomery().clear_cofactor_mont();
+            assert_eq!(
+                cleared_mont.from_montgomery().to_affine(),
+                cleared.to_affine()
+            );
+        }
+
+        // random points land in the r-torsion
+        for _ in 0..5 {
+            let p = G1Projective::random(&mut rng);
+            assert!(p.mul_by_integer(r).is_identity());
+            let p = G1Projective::random_mont(&mut rng).from_montgomery();
+            assert!(p.mul_by_integer(r).is_identity());
+        }
+
+        // G1 is fixed up to the scalar 1 - z
+        let g = G1Projective::generator();
+        assert_eq!(
+            g.clear_cofactor().to_affine(),
+            g.mul_by_integer(&h_eff).to_affine()
+        );
+    }
+
+    #[test]
+    fn test_map_to_curve() {
+        let mut rng = TestRng::new();
+        let r = BLS12_381_SCALAR.modulus_ref();
+        assert!(G1_SVDW.try_get().is_ok());
+
+        let p = BLS12_381_BASE.modulus();
+        let mut inputs = vec![Integer::from(0), Integer::from(1), p.clone() - 1u32];
+        inputs.extend((0..8).map(|_| Bls12_381BaseField::random(&mut rng)));
+        for u in &inputs {
+            let point = G1Projective::map_to_curve(u);
+            assert!(point.is_on_curve());
+            assert_eq!(point.to_affine(), G1Projective::map_to_curve(u).to_affine());
+            // the sign of y follows u
+            assert_eq!(point.y.0.is_odd(), u.is_odd());
+        }
+        // u and p + u are the same element
+        assert_eq!(
+            G1Projective::map_to_curve(&(p.clone() + 1u32)).to_affine(),
+            G1Projective::map_to_curve(&Integer::from(1)).to_affine()
+        );
+
+        let point = G1Projective::map_to_group(&inputs[3], &inputs[4]);
+        assert!(!point.is_identity());
+        assert!(point.mul_by_integer(r).is_identity());
+        assert_eq!(
+            point.to_affine(),
+            G1Projective::map_to_group(&inputs[3], &inputs[4]).to_affine()
+        );
+
+        let point = G1Projective::random_mont(&mut rng).from_montgomery();
+        assert!(point.is_on_curve());
+        assert!(point.mul_by_integer(r).is_identity());
+    }
+
     #[test]
     fn test_g1_projective_random() {
-        let mut rng = ZkperRng::new_test();
+        let mut rng = TestRng::new();
 
         let x: Bls12_381BaseField = rng.gen();
-        let flip_sign = rng.next_u32() % 2 != 0;
+        let flip_sign = !rng.next_u32().is_multiple_of(2);
 
         println!("x: {}", x);
         println!("flip_sign: {}", flip_sign);
@@ -481,9 +963,9 @@ mod tests {
 
             // Create affine point
             let point = G1Projective {
-                x: x.0,
-                y,
-                z: Bls12_381BaseField::one(),
+                x,
+                y: Bls12_381BaseField(y),
+                z: Bls12_381BaseField::from(1),
             };
 
             println!("point: {:#}", point);
@@ -508,9 +990,9 @@ mod tests {
             println!();
             println!();
 
-            let p1 = BLS12_381_BASE.new_element(point_add_mont_raw.x);
-            let p2 = BLS12_381_BASE.new_element(point_add_mont_raw.y);
-            let p3 = BLS12_381_BASE.new_element(point_add_mont_raw.z);
+            let p1 = BLS12_381_BASE.new_element(point_add_mont_raw.x.0);
+            let p2 = BLS12_381_BASE.new_element(point_add_mont_raw.y.0);
+            let p3 = BLS12_381_BASE.new_element(point_add_mont_raw.z.0);
 
             println!("p1: {:#}", p1.to_string_radix(16));
             println!("p2: {:#}", p2.to_string_radix(16));
@@ -539,7 +1021,7 @@ fn test_g1projective_mul_scalar() {
         "461e83d5dcef3cced2cba68661f99b6d19df5ccb367578dce250033e9f64310e93be9f44c4e5d311a081d99ecd5402a"
     );
 
-    let g1_scalar = g1.mul_scalar(&scalar);
+    let g1_scalar = g1.mul_scalar(&Scalar::from(scalar));
     println!("{:#}", g1_scalar);
 
     let out_ref = G1Projective::from_str_hex(
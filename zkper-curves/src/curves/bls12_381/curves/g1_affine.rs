@@ -1,11 +1,28 @@
 use self::g1::{G1Projective, G1_GENERATOR_X, G1_GENERATOR_Y};
 
 use super::*;
+use crate::{curves::bls12_381::fields::base::BASE_FIELD_BYTES, error::CurveError};
+
+/// Size of an uncompressed G1 point: x || y, each big-endian.
+pub const G1_UNCOMPRESSED_BYTES: usize = 2 * BASE_FIELD_BYTES;
+
+/// Size of a compressed G1 point: x with the flags in its top bits.
+pub const G1_COMPRESSED_BYTES: usize = BASE_FIELD_BYTES;
+
+/// Flag set in the most significant byte of a compressed encoding.
+pub(crate) const COMPRESSION_FLAG: u8 = 0x80;
+
+/// Flag set in the most significant byte of an encoded point at infinity.
+pub(crate) const INFINITY_FLAG: u8 = 0x40;
+
+/// Flag set in a compressed encoding when y is the lexicographically larger
+/// of the two roots.
+pub(crate) const SORT_FLAG: u8 = 0x20;
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct G1Affine {
-    pub x: Integer,
-    pub y: Integer,
+    pub x: Bls12_381BaseField,
+    pub y: Bls12_381BaseField,
     pub infinity: bool,
 }
 
@@ -28,8 +45,8 @@ impl G1Affine {
     #[inline]
     pub fn identity() -> Self {
         G1Affine {
-            x: Bls12_381BaseField::zero(),
-            y: Bls12_381BaseField::one(),
+            x: Bls12_381BaseField::from(0),
+            y: Bls12_381BaseField::from(1),
             infinity: true,
         }
     }
@@ -58,19 +75,7 @@ impl G1Affine {
 
 impl<'a> From<&'a G1Projective> for G1Affine {
     fn from(p: &'a G1Projective) -> G1Affine {
-        let zinv = Bls12_381BaseField::invert(p.z.clone()).unwrap_or(Integer::ZERO);
-        let x = Bls12_381BaseField::mul(p.x.clone(), &zinv);
-        let y = Bls12_381BaseField::mul(p.y.clone(), &zinv);
-
-        if zinv.is_zero() {
-            G1Affine::identity()
-        } else {
-            G1Affine {
-                x,
-                y,
-                infinity: false,
-            }
-        }
+        p.to_affine()
     }
 }
 
@@ -85,15 +90,15 @@ impl fmt::Display for G1Affine {
         write!(
             f,
             "G1Affine {{\n    x: {},\n    y: {},\n    infinity: {}\n}}",
-            self.x.to_string_radix(16),
-            self.y.to_string_radix(16),
+            self.x.0.to_string_radix(16),
+            self.y.0.to_string_radix(16),
             self.infinity
         )
     }
 }
 
 impl G1Affine {
-    pub fn new(x: Integer, y: Integer, infinity: bool) -> Self {
+    pub fn new(x: Bls12_381BaseField, y: Bls12_381BaseField, infinity: bool) -> Self {
         Self { x, y, infinity }
     }
 
@@ -101,4 +106,240 @@ impl G1Affine {
     pub fn to_curve(&self) -> G1Projective {
         self.into()
     }
+
+    pub fn neg(&self) -> G1Affine {
+        G1Affine {
+            x: self.x.clone(),
+            y: Bls12_381BaseField(if self.is_identity() {
+                Bls12_381BaseField::one()
+            } else {
+                Bls12_381BaseField::neg(self.y.0.clone())
+            }),
+            infinity: self.infinity,
+        }
+    }
+
+    /// Returns true if the point satisfies y^2 = x^3 + 4.
+    pub fn is_on_curve(&self) -> bool {
+        if self.infinity {
+            return true;
+        }
+
+        let lhs = Bls12_381BaseField::square(self.y.0.clone());
+        let rhs = Bls12_381BaseField::add(
+            Bls12_381BaseField::cubic(self.x.0.clone()),
+            &Integer::from(4),
+        );
+        lhs == rhs
+    }
+
+    /// Returns true if the point lies in the prime-order subgroup, i.e. [r]P is
+    /// the identity. Only meaningful for points on the curve.
+    pub fn is_torsion_free(&self) -> bool {
+        self.to_curve()
+            .mul_by_integer(BLS12_381_SCALAR.modulus_ref())
+            .is_identity()
+    }
+
+    /// Serialize as x || y (big-endian). The point at infinity is all zeros
+    /// with `INFINITY_FLAG` set in the first byte.
+    pub fn to_uncompressed(&self) -> [u8; G1_UNCOMPRESSED_BYTES] {
+        let mut bytes = [0u8; G1_UNCOMPRESSED_BYTES];
+        if self.infinity {
+            bytes[0] = INFINITY_FLAG;
+            return bytes;
+        }
+
+        bytes[..BASE_FIELD_BYTES].copy_from_slice(&Bls12_381BaseField::to_bytes_be(&self.x.0));
+        bytes[BASE_FIELD_BYTES..].copy_from_slice(&Bls12_381BaseField::to_bytes_be(&self.y.0));
+        bytes
+    }
+
+    /// Inverse of `to_uncompressed`. Returns None for non-canonical
+    /// encodings and points that are not on the curve.
+    pub fn from_uncompressed(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() != G1_UNCOMPRESSED_BYTES {
+            return None;
+        }
+
+        if bytes[0] & INFINITY_FLAG != 0 {
+            let rest_is_zero =
+                bytes[0] == INFINITY_FLAG && bytes[1..].iter().all(|byte| *byte == 0);
+            return rest_is_zero.then(G1Affine::identity);
+        }
+
+        let point = G1Affine {
+            x: Bls12_381BaseField(Bls12_381BaseField::from_bytes_be(
+                &bytes[..BASE_FIELD_BYTES],
+            )?),
+            y: Bls12_381BaseField(Bls12_381BaseField::from_bytes_be(
+                &bytes[BASE_FIELD_BYTES..],
+            )?),
+            infinity: false,
+        };
+
+        point.is_on_curve().then_some(point)
+    }
+
+    /// Serialize as x (big-endian) with `COMPRESSION_FLAG` set, and
+    /// `SORT_FLAG` set if y is the larger root. The point at infinity is all
+    /// zeros apart from the compression and infinity flags.
+    pub fn to_compressed(&self) -> [u8; G1_COMPRESSED_BYTES] {
+        let mut bytes = [0u8; G1_COMPRESSED_BYTES];
+        if self.infinity {
+            bytes[0] = COMPRESSION_FLAG | INFINITY_FLAG;
+            return bytes;
+        }
+
+        bytes.copy_from_slice(&Bls12_381BaseField::to_bytes_be(&self.x.0));
+        bytes[0] |= COMPRESSION_FLAG;
+        if Bls12_381BaseField::lexicographically_largest(&self.y.0) {
+            bytes[0] |= SORT_FLAG;
+        }
+        bytes
+    }
+
+    /// Inverse of `to_compressed`, recovering y with a square root. Returns
+    /// None for non-canonical encodings and x without a point on the curve.
+    pub fn from_compressed(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() != G1_COMPRESSED_BYTES || bytes[0] & COMPRESSION_FLAG == 0 {
+            return None;
+        }
+
+        let flags = bytes[0];
+        if flags & INFINITY_FLAG != 0 {
+            let rest_is_zero = flags == COMPRESSION_FLAG | INFINITY_FLAG
+                && bytes[1..].iter().all(|byte| *byte == 0);
+            return rest_is_zero.then(G1Affine::identity);
+        }
+
+        let mut x_bytes = [0u8; G1_COMPRESSED_BYTES];
+        x_bytes.copy_from_slice(bytes);
+        x_bytes[0] &= !(COMPRESSION_FLAG | SORT_FLAG);
+        let x = Bls12_381BaseField::from_bytes_be(&x_bytes)?;
+
+        G1Affine::from_x_coordinate(&x, flags & SORT_FLAG != 0).ok()
+    }
+
+    /// The point of the curve with x-coordinate `x`, taking the y that is
+    /// lexicographically largest when `y_sign` is set, like the sort flag of
+    /// compressed points. The point is not necessarily in G1.
+    pub fn from_x_coordinate(x: &Integer, y_sign: bool) -> Result<Self, CurveError> {
+        if x < &0 || x >= Bls12_381BaseField::modulus() {
+            return Err(CurveError::InvalidXCoordinate("G1"));
+        }
+
+        let y = Bls12_381BaseField::sqrt(Bls12_381BaseField::add(
+            Bls12_381BaseField::cubic(x.clone()),
+            &Integer::from(4),
+        ))
+        .ok_or(CurveError::InvalidXCoordinate("G1"))?;
+        let y = if Bls12_381BaseField::lexicographically_largest(&y) == y_sign {
+            y
+        } else {
+            Bls12_381BaseField::neg(y)
+        };
+
+        Ok(G1Affine {
+            x: Bls12_381BaseField(x.clone()),
+            y: Bls12_381BaseField(y),
+            infinity: false,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_uncompressed_round_trip() {
+        let mut rng = zkper_rand::TestRng::new();
+
+        for point in [
+            G1Affine::generator(),
+            G1Affine::identity(),
+            G1Projective::random(&mut rng).to_affine(),
+        ] {
+            let bytes = point.to_uncompressed();
+            assert_eq!(G1Affine::from_uncompressed(&bytes), Some(point));
+        }
+
+        let mut bytes = G1Affine::generator().to_uncompressed();
+        bytes[G1_UNCOMPRESSED_BYTES - 1] ^= 1;
+        assert_eq!(G1Affine::from_uncompressed(&bytes), None);
+
+        // x = p is not a canonical field element
+        let mut bytes = [0u8; G1_UNCOMPRESSED_BYTES];
+        bytes[..BASE_FIELD_BYTES].copy_from_slice(&Bls12_381BaseField::to_bytes_be(
+            Bls12_381BaseField::modulus(),
+        ));
+        assert_eq!(G1Affine::from_uncompressed(&bytes), None);
+    }
+
+    #[test]
+    fn test_from_x_coordinate() {
+        let generator = G1Affine::generator();
+        let sign = Bls12_381BaseField::lexicographically_largest(&generator.y.0);
+        assert_eq!(
+            G1Affine::from_x_coordinate(&generator.x.0, sign),
+            Ok(generator.clone())
+        );
+        assert_eq!(
+            G1Affine::from_x_coordinate(&generator.x.0, !sign),
+            Ok(generator.neg())
+        );
+
+        let shifted = generator.x.0.clone() + Bls12_381BaseField::modulus();
+        assert_eq!(
+            G1Affine::from_x_coordinate(&shifted, sign),
+            Err(CurveError::InvalidXCoordinate("G1"))
+        );
+        assert_eq!(
+            G1Affine::from_x_coordinate(&Integer::from(-1), sign),
+            Err(CurveError::InvalidXCoordinate("G1"))
+        );
+
+        // about half of the x-coordinates are not on the curve
+        let off_curve = (1u32..)
+            .map(Integer::from)
+            .find(|x| G1Affine::from_x_coordinate(x, false).is_err())
+            .unwrap();
+        assert!(Bls12_381BaseField::sqrt(Bls12_381BaseField::add(
+            Bls12_381BaseField::cubic(off_curve),
+            &Integer::from(4)
+        ))
+        .is_none());
+    }
+
+    #[test]
+    fn test_compressed_round_trip() {
+        let mut rng = zkper_rand::TestRng::new();
+
+        let point = G1Projective::random(&mut rng).to_affine();
+        for point in [
+            G1Affine::generator(),
+            G1Affine::identity(),
+            point.clone(),
+            point.neg(),
+        ] {
+            let bytes = point.to_compressed();
+            assert_eq!(G1Affine::from_compressed(&bytes), Some(point));
+        }
+
+        // the sort flag selects the other root
+        let mut bytes = point.to_compressed();
+        bytes[0] ^= SORT_FLAG;
+        assert_eq!(G1Affine::from_compressed(&bytes), Some(point.neg()));
+
+        // uncompressed and compressed encodings are not interchangeable
+        let mut bytes = point.to_compressed();
+        bytes[0] &= !COMPRESSION_FLAG;
+        assert_eq!(G1Affine::from_compressed(&bytes), None);
+
+        // infinity with extra bits set
+        let mut bytes = G1Affine::identity().to_compressed();
+        bytes[0] |= SORT_FLAG;
+        assert_eq!(G1Affine::from_compressed(&bytes), None);
+    }
 }
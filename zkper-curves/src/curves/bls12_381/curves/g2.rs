@@ -5,82 +5,135 @@ use rug::Integer;
 
 use crate::{
     backends::montgomery::{INTEGER_FOUR, INTEGER_TWELVE},
-    curves::bls12_381::{fields::fp2::Fp2, MILLER_LOOP_CONSTANT, MILLER_LOOP_CONSTANT_IS_NEG},
+    constant::{ensure, Constant, ConstantError},
+    curves::bls12_381::{
+        fields::fp2::{checked_nonresidue_power, Fp2},
+        Scalar, BLS12_381_BASE, BLS12_381_SCALAR, MILLER_LOOP_CONSTANT,
+        MILLER_LOOP_CONSTANT_IS_NEG,
+    },
+    fp,
 };
 
-use super::g2_affine::G2Affine;
-
-lazy_static::lazy_static! {
-    /// The generators of G1 and G2 are computed by finding the lexicographically smallest valid x-coordinate,
-    /// and its lexicographically smallest y-coordinate and scaling it by the cofactor such that the result is not the point at infinity.
-    ///
-    /// x = 3059144344244213709971259814753781636986470325476647558659373206291635324768958432433509563104347017837885763365758*u
-    ///     + 352701069587466618187139116011060144890029952792775240219908644239793785735715026873347600343865175952761926303160
-    /// y = 927553665492332455747201965776037880757740193453592970025027978793976877002675564980949289727957565575433344219582*u +
-    ///     1985150602287291935568054521177171638300868978215655730859378665066344726373823718423869104263333984641494340347905
-    pub static ref G2_GENERATOR_X: Fp2 = {
-        let c0 = Integer::from_str_radix(
-            "352701069587466618187139116011060144890029952792775240219908644239793785735715026873347600343865175952761926303160",
-            10
-        ).expect("failed to parse generator integer");
-        let c1 = Integer::from_str_radix(
-            "3059144344244213709971259814753781636986470325476647558659373206291635324768958432433509563104347017837885763365758",
-            10
-        ).expect("failed to parse generator integer");
-        let x = Fp2::from_integers(c0, c1);
-        x
+use super::{g2_affine::G2Affine, ladder_bits, Svdw, Uniform};
+
+/// The generators of G1 and G2 are computed by finding the lexicographically smallest valid x-coordinate,
+/// and its lexicographically smallest y-coordinate and scaling it by the cofactor such that the result is not the point at infinity.
+///
+/// x = 3059144344244213709971259814753781636986470325476647558659373206291635324768958432433509563104347017837885763365758*u
+///     + 352701069587466618187139116011060144890029952792775240219908644239793785735715026873347600343865175952761926303160
+/// y = 927553665492332455747201965776037880757740193453592970025027978793976877002675564980949289727957565575433344219582*u +
+///     1985150602287291935568054521177171638300868978215655730859378665066344726373823718423869104263333984641494340347905
+pub static G2_GENERATOR_X: Constant<Fp2> = Constant::new("G2_GENERATOR_X", || {
+    Ok(checked_generator("G2_GENERATOR_X")?.x)
+});
+pub static G2_GENERATOR_Y: Constant<Fp2> = Constant::new("G2_GENERATOR_Y", || {
+    Ok(checked_generator("G2_GENERATOR_Y")?.y)
+});
+
+/// The generator, checked to lie on the curve.
+fn checked_generator(name: &'static str) -> Result<G2Affine, ConstantError> {
+    let generator = G2Affine {
+        x: Fp2::from_u64_vec(
+            &fp!("0x024aa2b2f08f0a91260805272dc51051c6e47ad4fa403b02b4510b647ae3d1770bac0326a805bbefd48056c8c121bdb8"),
+            &fp!("0x13e02b6052719f607dacd3a088274f65596bd0d09920b61ab5da61bbdc7f5049334cf11213945d57e5ac7d055d042b7e"),
+        ),
+        y: Fp2::from_u64_vec(
+            &fp!("0x0ce5d527727d6e118cc9cdc6da2e351aadfd9baa8cbdd3a76d429a695160d12c923ac9cc3baca289e193548608b82801"),
+            &fp!("0x0606c4a02ea734cc32acd2b02bc28b99cb3e287e85a763af267492ab572e99ab3f370d275cec1da1aaa9075ff05f79be"),
+        ),
+        infinity: false,
     };
-    pub static ref G2_GENERATOR_Y: Fp2 = {
-        let c0 = Integer::from_str_radix(
-            "1985150602287291935568054521177171638300868978215655730859378665066344726373823718423869104263333984641494340347905",
-            10
-        ).expect("failed to parse generator integer");
-        let c1 = Integer::from_str_radix(
-            "927553665492332455747201965776037880757740193453592970025027978793976877002675564980949289727957565575433344219582",
-            10
-        ).expect("failed to parse generator integer");
-        let y = Fp2::from_integers(c0, c1);
-        y
-    };
-
+    ensure(
+        name,
+        generator.is_on_curve(),
+        "generator is not on the curve",
+    )?;
 
-    // PSI_X = 1/(u+1)^((p-1)/3)
-    pub static ref PSI_X: Fp2 = {
-        let c0 = Integer::ZERO;
-        let c1 = Integer::from_str_radix(
-            "4002409555221667392624310435006688643935503118305586438271171395842971157480381377015405980053539358417135540939437",
-            // "3216219264335650953089490096956247703352901229858663303777280467369712744216098189027420766571058176884680122779075", // montgomery form
-            10
-        ).expect("failed to parse PSI_X integer");
-        Fp2::from_integers(c0, c1)
-    };
-
-    // PSI_Y = 1/(u+1)^((p-1)/2)
-    pub static ref PSI_Y: Fp2 = {
-        let c0 = Integer::from_str_radix(
-            "2973677408986561043442465346520108879172042883009249989176415018091420807192182638567116318576472649347015917690530",
-            // "1821461487266245992767491788684378228087062278322214693001359809350238716280406307949636812899085786271837335624401", // montgomery form
-            10
-        ).expect("failed to parse PSI_Y integer");
-        let c1 = Integer::from_str_radix(
-            "1028732146235106349975324479215795277384839936929757896155643118032610843298655225875571310552543014690878354869257",
-            // "2180948067955421400650298037051525928469820541616793192330698326773792934210431556493050816229929877766056936935386", // montgomery form
-            10
-        ).expect("failed to parse PSI_Y integer");
-        Fp2::from_integers(c0, c1)
-    };
+    Ok(generator)
+}
 
-    // PSI_2_X = (u+1)^((1-p^2)/3)
-    pub static ref PSI_2_X: Fp2 = {
-        let c0 = Integer::from_str_radix(
-            "4002409555221667392624310435006688643935503118305586438271171395842971157480381377015405980053539358417135540939436",
-            10
-        ).expect("failed to parse PSI_Y integer");
-        let c1 = Integer::ZERO;
-        Fp2::from_integers(c0, c1)
-    };
+/// The SvdW constants of E': y^2 = x^3 + 4(u + 1) for Z = -1, the first
+/// candidate meeting the criteria of `find_z_svdw` (RFC 9380, appendix H.1),
+/// which are checked here.
+pub(crate) static G2_SVDW: Constant<Svdw<Fp2>> = Constant::new("G2_SVDW", || {
+    let name = "G2_SVDW";
+
+    let z = Fp2::one().neg();
+    let c1 = g2_curve(&z);
+    let three_z2 = z.square().mul_base(&Integer::from(3));
+    let c2 = z.neg().mul(
+        &Fp2::from_base(&Integer::from(2))
+            .invert()
+            .expect("2 is invertible"),
+    );
+    ensure(name, !c1.is_zero(), "g(Z) is zero")?;
+    ensure(
+        name,
+        c1.is_square() || g2_curve(&c2).is_square(),
+        "neither g(Z) nor g(-Z / 2) is a square",
+    )?;
+
+    let c3 = c1
+        .mul(&three_z2)
+        .neg()
+        .sqrt()
+        .ok_or_else(|| ConstantError::new(name, "-g(Z) * 3Z^2 is not a square"))?;
+    let c3 = if c3.sgn0() { c3.neg() } else { c3 };
+    let c4 = c1.mul_base(INTEGER_FOUR).neg().mul(
+        &three_z2
+            .invert()
+            .ok_or_else(|| ConstantError::new(name, "Z is zero"))?,
+    );
+    // -3Z^2 / 4g(Z) is the inverse of c4
+    ensure(name, c4.is_square(), "-3Z^2 / 4g(Z) is not a square")?;
+
+    Ok(Svdw { z, c1, c2, c3, c4 })
+});
+
+/// x^3 + 4(u + 1)
+fn g2_curve(x: &Fp2) -> Fp2 {
+    x.cubic().add_base(INTEGER_FOUR)
 }
 
+// PSI_X = 1/(u+1)^((p-1)/3)
+pub static PSI_X: Constant<Fp2> = Constant::new("PSI_X", || {
+    checked_nonresidue_power(
+        "PSI_X",
+        Fp2::from_u64_vec(
+            &fp!("0x0"),
+            &fp!("0x1a0111ea397fe699ec02408663d4de85aa0d857d89759ad4897d29650fb85f9b409427eb4f49fffd8bfd00000000aaad"),
+        ),
+        &((BLS12_381_BASE.modulus() - 1u32) / 3u32),
+        true,
+    )
+});
+
+// PSI_Y = 1/(u+1)^((p-1)/2)
+pub static PSI_Y: Constant<Fp2> = Constant::new("PSI_Y", || {
+    checked_nonresidue_power(
+        "PSI_Y",
+        Fp2::from_u64_vec(
+            &fp!("0x135203e60180a68ee2e9c448d77a2cd91c3dedd930b1cf60ef396489f61eb45e304466cf3e67fa0af1ee7b04121bdea2"),
+            &fp!("0x06af0e0437ff400b6831e36d6bd17ffe48395dabc2d3435e77f76e17009241c5ee67992f72ec05f4c81084fbede3cc09"),
+        ),
+        &((BLS12_381_BASE.modulus() - 1u32) / 2u32),
+        true,
+    )
+});
+
+// PSI_2_X = (u+1)^((1-p^2)/3)
+pub static PSI_2_X: Constant<Fp2> = Constant::new("PSI_2_X", || {
+    checked_nonresidue_power(
+        "PSI_2_X",
+        Fp2::from_u64_vec(
+            &fp!("0x1a0111ea397fe699ec02408663d4de85aa0d857d89759ad4897d29650fb85f9b409427eb4f49fffd8bfd00000000aaac"),
+            &fp!("0x0"),
+        ),
+        &((BLS12_381_BASE.modulus().square() - 1u32) / 3u32),
+        true,
+    )
+});
+
 /// This is an element of G2 represented in the projective coordinate space.
 #[derive(Clone, Debug)]
 pub struct G2Projective {
@@ -94,18 +147,18 @@ impl<'a> From<&'a G2Affine> for G2Projective {
         Self {
             x: value.x.clone(),
             y: value.y.clone(),
-            z: Fp2::one(),
+            z: if value.infinity {
+                Fp2::zero()
+            } else {
+                Fp2::one()
+            },
         }
     }
 }
 
 impl From<G2Affine> for G2Projective {
     fn from(value: G2Affine) -> Self {
-        Self {
-            x: value.x,
-            y: value.y,
-            z: Fp2::one(),
-        }
+        Self::from(&value)
     }
 }
 
@@ -138,25 +191,39 @@ impl fmt::Display for G2Projective {
             write!(
                 f,
                 "G2Projective {{\n    x: {},\n    y: {},\n    z: {}\n}}",
-                self.x.to_string(),
-                self.y.to_string(),
-                self.z.to_string(),
+                self.x, self.y, self.z,
             )
         } else {
-            write!(
-                f,
-                "G2Projective({}, {}, {})",
-                self.x.to_string(),
-                self.y.to_string(),
-                self.z.to_string(),
-            )
+            write!(f, "G2Projective({}, {}, {})", self.x, self.y, self.z,)
         }
     }
 }
 
+/// a * 3b, with b = 4(u + 1) the constant of the twist.
+fn mul_by_3b(a: &Fp2) -> Fp2 {
+    a.mul_base(INTEGER_TWELVE).mul_by_nonresidue()
+}
+
 impl G2Projective {
+    /// The affine point (X / Z, Y / Z), or the identity when Z = 0. This and
+    /// `batch_normalize`, which only shares the inversion, are the one way to
+    /// leave projective coordinates; every other conversion calls them.
     pub fn to_affine(&self) -> G2Affine {
-        self.normalize()
+        let z_inv = self.z.normalize().invert().unwrap_or(Fp2::zero());
+        self.affine_with_z_inv(&z_inv)
+    }
+
+    /// (X z_inv, Y z_inv), where a zero `z_inv` stands for Z = 0.
+    fn affine_with_z_inv(&self, z_inv: &Fp2) -> G2Affine {
+        if z_inv.is_zero() {
+            return G2Affine::identity();
+        }
+
+        G2Affine {
+            x: self.x.mul(z_inv),
+            y: self.y.mul(z_inv),
+            infinity: false,
+        }
     }
 
     pub fn to_mont(&self) -> G2Projective {
@@ -187,23 +254,33 @@ impl G2Projective {
         self.z.is_zero()
     }
 
-    pub fn normalize(&self) -> G2Affine {
+    /// Returns true if the point satisfies Y^2 Z = X^3 + 4(u + 1) Z^3.
+    pub fn is_on_curve(&self) -> bool {
         if self.is_identity() {
-            return G2Affine::identity();
+            return true;
         }
 
-        // Compute z_inv = 1/z
-        let z_inv = self.z.invert().expect("Z should be invertible");
+        let b = Fp2::from_integers(INTEGER_FOUR.clone(), INTEGER_FOUR.clone());
+        let lhs = self.y.square().mul(&self.z);
+        let rhs = self.x.cubic().add(&self.z.cubic().mul(&b));
+        lhs == rhs
+    }
+
+    /// Same as `to_affine`.
+    pub fn normalize(&self) -> G2Affine {
+        self.to_affine()
+    }
 
-        // Compute x_norm = x * z_inv, y_norm = y * z_inv
-        let x_norm = self.x.mul(&z_inv);
-        let y_norm = self.y.mul(&z_inv);
+    /// Converts all points to affine form sharing one field inversion.
+    pub fn batch_normalize(points: &[G2Projective]) -> Vec<G2Affine> {
+        let mut z_invs = points.iter().map(|p| p.z.normalize()).collect::<Vec<_>>();
+        Fp2::batch_invert(&mut z_invs);
 
-        G2Affine {
-            x: x_norm,
-            y: y_norm,
-            infinity: false,
-        }
+        points
+            .iter()
+            .zip(z_invs)
+            .map(|(p, z_inv)| p.affine_with_z_inv(&z_inv))
+            .collect()
     }
 
     /// Returns a fixed generator of the group.
@@ -223,21 +300,26 @@ impl G2Projective {
     }
 
     /// Computes the doubling of this point.
-    /// ref: Algorithm 9, https://eprint.iacr.org/2015/1060.pdf
-    ///
-    /// Todo: test algorithm used in g1 projective
     pub fn double(&self) -> Self {
         if self.is_identity() {
             return Self::identity();
         }
 
+        self.double_complete()
+    }
+
+    /// Doubles this point with the complete formula for a = 0, without the
+    /// shortcut for the identity.
+    ///
+    /// ref: Algorithm 9, https://eprint.iacr.org/2015/1060.pdf
+    pub fn double_complete(&self) -> Self {
         let t0 = self.y.square();
         let z3 = t0.clone().add(&t0);
         let z3 = z3.double();
         let z3 = z3.double();
         let t1 = self.y.mul(&self.z);
         let t2 = self.z.square();
-        let t2 = t2.mul_base(&INTEGER_TWELVE);
+        let t2 = mul_by_3b(&t2);
         let x3 = t2.mul(&z3);
         let y3 = t0.add(&t2);
         let z3 = t1.mul(&z3);
@@ -257,7 +339,8 @@ impl G2Projective {
         }
     }
 
-    /// Adds this point to another point.
+    /// Adds this point to another point. The formula is complete: the
+    /// identity, doubling and inverse points take the same steps.
     ///
     /// ref: Algorithm 7, https://eprint.iacr.org/2015/1060.pdf
     pub fn add(&self, rhs: &Self) -> Self {
@@ -281,10 +364,10 @@ impl G2Projective {
         let y3 = x3.sub(&y3);
         let x3 = t0.double();
         let t0 = x3.add(&t0);
-        let t2 = t2.mul_base(&INTEGER_TWELVE);
+        let t2 = mul_by_3b(&t2);
         let z3 = t1.add(&t2);
         let t1 = t1.sub(&t2);
-        let y3 = y3.mul_base(&INTEGER_TWELVE);
+        let y3 = mul_by_3b(&y3);
         let x3 = t4.mul(&y3);
         let t2 = t3.mul(&t1);
         let x3 = t2.sub(&x3);
@@ -320,10 +403,10 @@ impl G2Projective {
         let y3 = y3.add(&self.x);
         let x3 = t0.double();
         let t0 = x3.add(&t0);
-        let t2 = self.z.mul_base(&INTEGER_TWELVE);
+        let t2 = mul_by_3b(&self.z);
         let z3 = t1.add(&t2);
         let t1 = t1.sub(&t2);
-        let y3 = y3.mul_base(&INTEGER_TWELVE);
+        let y3 = mul_by_3b(&y3);
         let x3 = t4.mul(&y3);
         let t2 = t3.mul(&t1);
         let x3 = t2.sub(&x3);
@@ -353,8 +436,14 @@ impl G2Projective {
         self.add(&rhs.neg())
     }
 
-    /// Scalar multiplication of a G1Projective point
-    pub fn mul_scalar(&self, scalar: &Integer) -> Self {
+    /// Scalar multiplication of a G2Projective point
+    pub fn mul_scalar(&self, scalar: &Scalar) -> Self {
+        self.mul_by_integer(&scalar.0)
+    }
+
+    /// Multiplies by an arbitrary non-negative integer, such as the group
+    /// order or a cofactor. Use `mul_scalar` for elements of Fr.
+    pub(crate) fn mul_by_integer(&self, scalar: &Integer) -> Self {
         if scalar.is_zero() {
             return G2Projective::identity();
         }
@@ -374,6 +463,101 @@ impl G2Projective {
         result
     }
 
+    /// Scalar multiplication with the chosen execution strategy, see
+    /// [`Uniform`]. Use `Uniform::Ladder` for secret scalars.
+    pub fn mul_scalar_with(&self, scalar: &Scalar, uniform: Uniform) -> Self {
+        match uniform {
+            Uniform::Variable => self.mul_scalar(scalar),
+            Uniform::Ladder => self.mul_scalar_ladder(scalar),
+        }
+    }
+
+    /// Montgomery ladder over every bit of Fr, with complete formulas only.
+    pub fn mul_scalar_ladder(&self, scalar: &Scalar) -> Self {
+        let mut r0 = G2Projective::identity();
+        let mut r1 = self.clone();
+
+        for bit in ladder_bits(scalar) {
+            r0.conditional_swap(&mut r1, bit);
+            r1 = r0.add(&r1);
+            r0 = r0.double_complete();
+            r0.conditional_swap(&mut r1, bit);
+        }
+
+        r0
+    }
+
+    /// Splits a scalar into four 64-bit digits k0..k3 with
+    /// k = k0 + k1 |z| + k2 |z|^2 + k3 |z|^3 mod r.
+    ///
+    /// On G2, psi acts as multiplication by p = z mod r, so -psi multiplies by
+    /// |z|, and the basis (1, |z|, |z|^2, |z|^3) of the GLS lattice reduces any
+    /// scalar below r < |z|^4 to four digits of the size of z.
+    pub fn decompose_scalar(scalar: &Scalar) -> [u64; 4] {
+        let mut k = BLS12_381_SCALAR.reduce(&scalar.0);
+        let z = Integer::from(MILLER_LOOP_CONSTANT);
+        let mut digits = [0u64; 4];
+        for digit in digits.iter_mut() {
+            let (q, rem) = k.div_rem_euc(z.clone());
+            *digit = rem.to_u64().expect("digit below |z|");
+            k = q;
+        }
+        debug_assert!(k.is_zero(), "r < |z|^4");
+
+        digits
+    }
+
+    /// Multiplies a point of G2 by a scalar with the 4-dimensional GLS
+    /// method: the digits of `decompose_scalar` are applied to P, -psi(P),
+    /// psi^2(P) and -psi^3(P) at once, in 64 doublings instead of 255.
+    ///
+    /// Only correct for points of the prime-order subgroup, where psi acts as
+    /// z. Use `mul_scalar` for any other point of the curve.
+    pub fn mul_scalar_gls(&self, scalar: &Scalar) -> Self {
+        let digits = Self::decompose_scalar(scalar);
+
+        let phi = self.psi().neg();
+        let phi2 = self.psi2();
+        let phi3 = phi2.psi().neg();
+        let points = [self.clone(), phi, phi2, phi3];
+
+        // every sum of a subset of the four points, indexed by its bits
+        let mut table = vec![G2Projective::identity(); 16];
+        for index in 1..16usize {
+            let low = index.trailing_zeros() as usize;
+            table[index] = table[index & (index - 1)].add(&points[low]);
+        }
+
+        let mut acc = G2Projective::identity();
+        for bit in (0..64).rev() {
+            acc = acc.double();
+            let index = digits.iter().enumerate().fold(0, |index, (i, digit)| {
+                index | (((digit >> bit) & 1) as usize) << i
+            });
+            if index != 0 {
+                acc = acc.add(&table[index]);
+            }
+        }
+
+        acc
+    }
+
+    /// Swaps the two points if `swap` is set, through the same field
+    /// operations either way.
+    fn conditional_swap(&mut self, other: &mut G2Projective, swap: bool) {
+        let choice = Integer::from(swap);
+
+        for (a, b) in [
+            (&mut self.x, &mut other.x),
+            (&mut self.y, &mut other.y),
+            (&mut self.z, &mut other.z),
+        ] {
+            let delta = a.sub(b).mul_by_base(&choice);
+            *a = a.sub(&delta);
+            *b = b.add(&delta);
+        }
+    }
+
     /// Multiply `self` by `MILLER_LOOP_CONSTANT`, using double and add.
     pub fn mul_by_x(&self) -> G2Projective {
         let mut result = G2Projective::identity();
@@ -399,13 +583,13 @@ impl G2Projective {
 
     /// psi(P) is the untwist-Frobenius-twist endomorhism on E'(Fq2)
     pub fn psi(&self) -> Self {
-        let x = self.x.frobenius_map().mul(&PSI_X);
-        let y = self.y.frobenius_map().mul(&PSI_Y);
+        let x = self.x.frobenius_map(1).mul(&PSI_X);
+        let y = self.y.frobenius_map(1).mul(&PSI_Y);
 
         G2Projective {
             x,
             y,
-            z: self.z.frobenius_map(),
+            z: self.z.frobenius_map(1),
         }
     }
 
@@ -433,35 +617,56 @@ impl G2Projective {
         // [x^2]P + [x]ψ(P)
         let t0 = (x_p.add(&psi_p)).mul_by_x();
 
-        (psi2_p2.add(&t0)).sub(&x_p).sub(&psi_p).sub(&self)
+        (psi2_p2.add(&t0)).sub(&x_p).sub(&psi_p).sub(self)
     }
 
-    /// Returns a random element in G2
-    pub fn random<R: RngCore>(rng: &mut R) -> Self {
-        loop {
-            let x = Fp2::random(rng);
-
-            let flip_sign = rng.next_u32() % 2 != 0;
-
-            let y_squared = x.cubic().add_base(INTEGER_FOUR);
+    /// The Shallue–van de Woestijne map (RFC 9380, section 6.6.1), sending
+    /// any element of Fp2 to a point of the twist deterministically, without a
+    /// rejection loop. The point is not in G2 yet, see `map_to_group`.
+    pub fn map_to_curve(u: &Fp2) -> G2Projective {
+        let svdw = &*G2_SVDW;
+        let u = u.normalize();
+
+        let tv1 = u.square().mul(&svdw.c1);
+        let tv2 = Fp2::one().add(&tv1);
+        let tv1 = Fp2::one().sub(&tv1);
+        // inv0, zero maps to zero
+        let tv3 = tv1.mul(&tv2).invert().unwrap_or_else(Fp2::zero);
+        let tv4 = u.mul(&tv1).mul(&tv3).mul(&svdw.c3);
+
+        let x1 = svdw.c2.sub(&tv4);
+        let x2 = svdw.c2.add(&tv4);
+        let x3 = tv2.square().mul(&tv3).square().mul(&svdw.c4).add(&svdw.z);
+        let (x, y) = [x1, x2, x3]
+            .into_iter()
+            .find_map(|x| Some((x.clone(), g2_curve(&x).sqrt()?)))
+            .expect("one of g(x1), g(x2) and g(x3) is a square");
+        let y = if y.sgn0() == u.sgn0() { y } else { y.neg() };
 
-            let y = y_squared.sqrt();
-
-            if let Some(y) = y {
-                let y = if flip_sign { y.neg() } else { y };
+        G2Projective {
+            x,
+            y,
+            z: Fp2::one(),
+        }
+    }
 
-                let point = G2Projective {
-                    x,
-                    y,
-                    z: Fp2::one(),
-                };
+    /// Maps two elements of Fp2 into G2: `map_to_curve` of each, added, with
+    /// the cofactor cleared. With u0 and u1 from a hash to the field this is
+    /// the encoding of RFC 9380, and the result is uniform in G2.
+    pub fn map_to_group(u0: &Fp2, u1: &Fp2) -> G2Projective {
+        Self::map_to_curve(u0)
+            .add(&Self::map_to_curve(u1))
+            .clear_cofactor()
+    }
 
-                // clear cofactor
-                let proj_point = point.clear_cofactor();
+    /// Returns a random element in G2
+    pub fn random<R: RngCore>(rng: &mut R) -> Self {
+        loop {
+            let point = Self::map_to_group(&Fp2::random(rng), &Fp2::random(rng));
 
-                if !proj_point.is_identity() {
-                    return proj_point;
-                }
+            // Ensure the generated point is not the point at infinity
+            if !point.is_identity() {
+                return point;
             }
         }
     }
@@ -472,22 +677,238 @@ mod tests {
     use std::ops::Sub;
 
     use rug::Integer;
-    use zkper_rand::ZkperRng;
+    use zkper_rand::TestRng;
 
     use crate::{
         backends::montgomery::{INTEGER_THREE, INTEGER_TWO},
         curves::bls12_381::{
-            curves::{g2::G2Projective, g2_affine::G2Affine},
+            curves::{
+                g2::{G2Projective, G2_SVDW},
+                g2_affine::G2Affine,
+                Uniform,
+            },
             fields::fp2::Fp2,
-            BLS12_381_BASE,
+            Bls12_381ScalarField, Scalar, BLS12_381_BASE, BLS12_381_SCALAR, MILLER_LOOP_CONSTANT,
         },
     };
 
+    #[test]
+    fn test_generator_and_identity() {
+        let g = G2Projective::generator();
+        let r = BLS12_381_SCALAR.modulus();
+        assert!(g.is_on_curve());
+        assert_eq!(G2Affine::generator().to_curve(), g);
+        assert!(!G2Projective {
+            x: Fp2::one(),
+            y: Fp2::one(),
+            z: Fp2::one(),
+        }
+        .is_on_curve());
+
+        // gen * order == identity
+        assert!(g.mul_by_integer(&r).is_identity());
+        assert_eq!(g.mul_scalar(&Scalar::from(r.clone() - 1u32)), g.neg());
+
+        let id = G2Projective::identity();
+        assert!(id.is_on_curve());
+        assert_eq!(id, id.clone());
+        assert_ne!(id, g);
+        assert!(id.to_affine().is_identity());
+        assert!(G2Affine::identity().to_curve().is_identity());
+        assert_eq!(g.add(&id), g);
+        assert_eq!(id.add(&g), g);
+        assert!(g.add(&g.neg()).is_identity());
+        assert!(id.double().is_identity());
+
+        let mut rng = TestRng::new();
+        for _ in 0..2 {
+            let a = Scalar::from(BLS12_381_SCALAR.sample_raw(&mut rng));
+            let b = Scalar::from(BLS12_381_SCALAR.sample_raw(&mut rng));
+            let ab = a.add(&b);
+            assert_eq!(g.mul_scalar(&ab), g.mul_scalar(&a).add(&g.mul_scalar(&b)));
+            assert!(g.mul_scalar(&a).is_on_curve());
+            assert!(G2Projective::random(&mut rng).is_on_curve());
+        }
+    }
+
+    #[test]
+    fn test_ladder() {
+        let mut rng = TestRng::new();
+        let g = G2Projective::generator();
+        let p = G2Projective::random(&mut rng);
+        let id = G2Projective::identity();
+
+        // `add` is the complete formula: identity, doubling and inverses
+        assert_eq!(g.add(&g), g.double());
+        assert!(id.add(&id).is_identity());
+        assert_eq!(p.double_complete(), p.double());
+        assert!(id.double_complete().is_identity());
+
+        let r = BLS12_381_SCALAR.modulus();
+        let mut scalars = vec![
+            Scalar::from(Integer::ZERO),
+            Scalar::from(Integer::from(1)),
+            Scalar::from(r.clone() - 1u32),
+            // unreduced scalars are taken mod r
+            Bls12_381ScalarField(r + 5u32),
+        ];
+        for _ in 0..2 {
+            scalars.push(Scalar::from(BLS12_381_SCALAR.sample_raw(&mut rng)));
+        }
+        for scalar in &scalars {
+            let expected = p.mul_scalar(&Scalar::from(BLS12_381_SCALAR.reduce(&scalar.0)));
+            assert_eq!(p.mul_scalar_ladder(scalar), expected);
+            assert_eq!(p.mul_scalar_with(scalar, Uniform::Ladder), expected);
+            assert_eq!(p.mul_scalar_with(scalar, Uniform::Variable), expected);
+        }
+    }
+
+    #[test]
+    fn test_batch_normalize() {
+        let mut rng = TestRng::new();
+        let mut points = (0..4)
+            .map(|_| G2Projective::random(&mut rng).double())
+            .collect::<Vec<_>>();
+        points.push(G2Projective::identity());
+
+        let affine = G2Projective::batch_normalize(&points);
+        for (p, a) in points.iter().zip(affine) {
+            assert_eq!(p.to_affine(), a);
+        }
+    }
+
+    #[test]
+    fn test_normalization_paths() {
+        let mut rng = TestRng::new();
+        let g = G2Projective::generator();
+        let p = BLS12_381_BASE.modulus_ref();
+        let unreduced = |c: &Fp2, offset: u32| Fp2 {
+            c0: c.c0.clone() + Integer::from(p * offset),
+            c1: c.c1.clone(),
+        };
+
+        let mut points = vec![
+            G2Projective::identity(),
+            g.add(&g.neg()),
+            // Z = p and Z = p + 1 are 0 and 1, just not reduced
+            G2Projective {
+                z: unreduced(&Fp2::zero(), 1),
+                ..g.clone()
+            },
+            G2Projective {
+                z: unreduced(&Fp2::one(), 1),
+                ..g.clone()
+            },
+            g.clone(),
+            g.neg(),
+            g.double(),
+        ];
+        for _ in 0..8 {
+            // the same point behind a random Z
+            let point = G2Projective::random(&mut rng);
+            let lambda = Fp2::from_integers(
+                BLS12_381_BASE.sample_raw(&mut rng),
+                BLS12_381_BASE.sample_raw(&mut rng),
+            );
+            points.push(G2Projective {
+                x: point.x.mul(&lambda),
+                y: point.y.mul(&lambda),
+                z: point.z.mul(&lambda),
+            });
+            points.push(point);
+        }
+
+        let batch = G2Projective::batch_normalize(&points);
+        for (point, batched) in points.iter().zip(batch) {
+            let affine = point.to_affine();
+            assert_eq!(G2Affine::from(point), affine);
+            assert_eq!(G2Affine::from(point.clone()), affine);
+            assert_eq!(batched, affine);
+            assert_eq!(point.normalize(), affine);
+
+            assert!(affine.is_on_curve());
+            assert_eq!(
+                G2Affine::from_uncompressed(&affine.to_uncompressed()),
+                Some(affine.clone())
+            );
+            assert_eq!(
+                G2Affine::from_compressed(&affine.to_compressed()),
+                Some(affine)
+            );
+        }
+    }
+
     #[test]
     fn test_g2_random() {
-        let mut rng = ZkperRng::new_test();
+        let mut rng = TestRng::new();
         let g2 = G2Projective::random(&mut rng);
         println!("g2: {:#}", g2);
+
+        // random points land in the r-torsion
+        for _ in 0..3 {
+            let p = G2Projective::random(&mut rng);
+            assert!(p
+                .mul_by_integer(BLS12_381_SCALAR.modulus_ref())
+                .is_identity());
+        }
+    }
+
+    #[test]
+    fn test_map_to_curve() {
+        let mut rng = TestRng::new();
+        let r = BLS12_381_SCALAR.modulus_ref();
+        assert!(G2_SVDW.try_get().is_ok());
+
+        let mut inputs = vec![Fp2::zero(), Fp2::one(), Fp2::one().neg()];
+        inputs.extend((0..4).map(|_| Fp2::random(&mut rng)));
+        for u in &inputs {
+            let point = G2Projective::map_to_curve(u);
+            assert!(point.to_affine().is_on_curve());
+            assert_eq!(point.to_affine(), G2Projective::map_to_curve(u).to_affine());
+            // the sign of y follows u
+            assert_eq!(point.y.sgn0(), u.sgn0());
+        }
+
+        let point = G2Projective::map_to_group(&inputs[3], &inputs[4]);
+        assert!(!point.is_identity());
+        assert!(point.mul_by_integer(r).is_identity());
+        assert_eq!(
+            point.to_affine(),
+            G2Projective::map_to_group(&inputs[3], &inputs[4]).to_affine()
+        );
+    }
+
+    #[test]
+    fn test_mul_scalar_gls() {
+        let mut rng = TestRng::new();
+        let r = BLS12_381_SCALAR.modulus();
+        let z = Integer::from(MILLER_LOOP_CONSTANT);
+
+        let mut scalars = vec![
+            Scalar::from(Integer::from(0)),
+            Scalar::from(Integer::from(1)),
+            Scalar::from(z.clone()),
+            Scalar::from(r.clone() - 1u32),
+        ];
+        scalars.extend((0..4).map(|_| Scalar::from(BLS12_381_SCALAR.sample_raw(&mut rng))));
+        for scalar in &scalars {
+            let digits = G2Projective::decompose_scalar(scalar);
+            let recomposed = digits
+                .iter()
+                .rev()
+                .fold(Integer::ZERO, |acc, digit| acc * &z + digit);
+            assert_eq!(recomposed, BLS12_381_SCALAR.reduce(&scalar.0));
+        }
+
+        // psi is the endomorphism [z] on G2
+        let point = G2Projective::random(&mut rng);
+        assert_eq!(point.psi(), point.mul_by_x());
+
+        for point in [point, G2Projective::generator(), G2Projective::identity()] {
+            for scalar in &scalars {
+                assert_eq!(point.mul_scalar_gls(scalar), point.mul_scalar(scalar));
+            }
+        }
     }
 
     #[test]
@@ -496,6 +917,9 @@ mod tests {
 
         let clear_point = point.clear_cofactor();
         println!("clear_point: {:#}", clear_point);
+        let r = BLS12_381_SCALAR.modulus_ref();
+        assert!(!point.mul_by_integer(r).is_identity());
+        assert!(clear_point.mul_by_integer(r).is_identity());
 
         let g = G2Projective::generator();
         println!("gclear: {:#}", g.clear_cofactor());
@@ -606,7 +1030,7 @@ mod tests {
     fn gen_point() -> G2Projective {
         let z = gen_z();
 
-        let point = G2Projective {
+        G2Projective {
             x: Fp2::from_u64_vec(
                 &[
                     0xee4c8cb7c047eaf2,
@@ -647,8 +1071,7 @@ mod tests {
             )
             .from_mont(),
             z: z.square().mul(&z),
-        };
-        point
+        }
     }
 
     #[test]
@@ -789,8 +1212,8 @@ mod tests {
         let p_squared = modulus.clone().pow(2);
 
         // Calculate (p-1)/3 and (p-1)/2
-        let p_minus_1_div_3 = (p_squared.clone().sub(Integer::ONE)).div_exact(&INTEGER_THREE);
-        let p_minus_1_div_2 = (p_squared.clone().sub(Integer::ONE)).div_exact(&INTEGER_TWO);
+        let p_minus_1_div_3 = (p_squared.clone().sub(Integer::ONE)).div_exact(INTEGER_THREE);
+        let p_minus_1_div_2 = (p_squared.clone().sub(Integer::ONE)).div_exact(INTEGER_TWO);
 
         println!("p_minus_1_div_3: {:#?}", p_minus_1_div_3);
         println!("p_minus_1_div_2: {:#?}", p_minus_1_div_2);
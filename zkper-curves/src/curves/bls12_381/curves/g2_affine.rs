@@ -1,8 +1,26 @@
 use std::fmt::{self, Display};
 
-use crate::curves::bls12_381::fields::fp2::Fp2;
+use rug::Integer;
 
-use super::g2::{G2Projective, G2_GENERATOR_X, G2_GENERATOR_Y};
+use crate::{
+    curves::bls12_381::{
+        fields::{base::BASE_FIELD_BYTES, fp2::Fp2},
+        Bls12_381BaseField, BLS12_381_SCALAR,
+    },
+    error::CurveError,
+    traits::field::FieldTrait,
+};
+
+use super::{
+    g1_affine::{COMPRESSION_FLAG, INFINITY_FLAG, SORT_FLAG},
+    g2::{G2Projective, G2_GENERATOR_X, G2_GENERATOR_Y},
+};
+
+/// Size of an uncompressed G2 point: x.c1 || x.c0 || y.c1 || y.c0, each big-endian.
+pub const G2_UNCOMPRESSED_BYTES: usize = 4 * BASE_FIELD_BYTES;
+
+/// Size of a compressed G2 point: x.c1 || x.c0 with the flags in the top bits.
+pub const G2_COMPRESSED_BYTES: usize = 2 * BASE_FIELD_BYTES;
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct G2Affine {
@@ -13,20 +31,7 @@ pub struct G2Affine {
 
 impl<'a> From<&'a G2Projective> for G2Affine {
     fn from(p: &'a G2Projective) -> G2Affine {
-        let zinv = p.z.invert().unwrap_or(Fp2::zero());
-
-        if zinv.is_zero() {
-            return G2Affine::identity();
-        }
-
-        let x = p.x.mul(&zinv);
-        let y = p.y.mul(&zinv);
-
-        G2Affine {
-            x,
-            y,
-            infinity: false,
-        }
+        p.to_affine()
     }
 }
 
@@ -111,4 +116,223 @@ impl G2Affine {
             infinity: false,
         }
     }
+
+    /// Returns true if the point satisfies y^2 = x^3 + 4(u + 1).
+    pub fn is_on_curve(&self) -> bool {
+        if self.infinity {
+            return true;
+        }
+
+        let b = Fp2::from_integers(Integer::from(4), Integer::from(4));
+        self.y.square() == self.x.cubic().add(&b)
+    }
+
+    /// Returns true if the point lies in the prime-order subgroup, i.e. [r]Q is
+    /// the identity. Only meaningful for points on the curve.
+    pub fn is_torsion_free(&self) -> bool {
+        self.to_curve()
+            .mul_by_integer(BLS12_381_SCALAR.modulus_ref())
+            .is_identity()
+    }
+
+    /// Serialize as x.c1 || x.c0 || y.c1 || y.c0 (big-endian). The point at
+    /// infinity is all zeros with `INFINITY_FLAG` set in the first byte.
+    pub fn to_uncompressed(&self) -> [u8; G2_UNCOMPRESSED_BYTES] {
+        let mut bytes = [0u8; G2_UNCOMPRESSED_BYTES];
+        if self.infinity {
+            bytes[0] = INFINITY_FLAG;
+            return bytes;
+        }
+
+        for (chunk, coeff) in bytes
+            .chunks_mut(BASE_FIELD_BYTES)
+            .zip([&self.x.c1, &self.x.c0, &self.y.c1, &self.y.c0])
+        {
+            chunk.copy_from_slice(&Bls12_381BaseField::to_bytes_be(coeff));
+        }
+        bytes
+    }
+
+    /// Inverse of `to_uncompressed`. Returns None for non-canonical
+    /// encodings and points that are not on the curve.
+    pub fn from_uncompressed(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() != G2_UNCOMPRESSED_BYTES {
+            return None;
+        }
+
+        if bytes[0] & INFINITY_FLAG != 0 {
+            let rest_is_zero =
+                bytes[0] == INFINITY_FLAG && bytes[1..].iter().all(|byte| *byte == 0);
+            return rest_is_zero.then(G2Affine::identity);
+        }
+
+        let mut coeffs = bytes
+            .chunks(BASE_FIELD_BYTES)
+            .map(Bls12_381BaseField::from_bytes_be);
+        let x_c1 = coeffs.next()??;
+        let x_c0 = coeffs.next()??;
+        let y_c1 = coeffs.next()??;
+        let y_c0 = coeffs.next()??;
+
+        let point = G2Affine {
+            x: Fp2::from_integers(x_c0, x_c1),
+            y: Fp2::from_integers(y_c0, y_c1),
+            infinity: false,
+        };
+
+        point.is_on_curve().then_some(point)
+    }
+
+    /// Serialize as x.c1 || x.c0 (big-endian) with `COMPRESSION_FLAG` set,
+    /// and `SORT_FLAG` set if y is the larger root. The point at infinity is
+    /// all zeros apart from the compression and infinity flags.
+    pub fn to_compressed(&self) -> [u8; G2_COMPRESSED_BYTES] {
+        let mut bytes = [0u8; G2_COMPRESSED_BYTES];
+        if self.infinity {
+            bytes[0] = COMPRESSION_FLAG | INFINITY_FLAG;
+            return bytes;
+        }
+
+        bytes[..BASE_FIELD_BYTES].copy_from_slice(&Bls12_381BaseField::to_bytes_be(&self.x.c1));
+        bytes[BASE_FIELD_BYTES..].copy_from_slice(&Bls12_381BaseField::to_bytes_be(&self.x.c0));
+        bytes[0] |= COMPRESSION_FLAG;
+        if self.y.lexicographically_largest() {
+            bytes[0] |= SORT_FLAG;
+        }
+        bytes
+    }
+
+    /// Inverse of `to_compressed`, recovering y with a square root in Fp2.
+    /// Returns None for non-canonical encodings and x without a point on the
+    /// curve.
+    pub fn from_compressed(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() != G2_COMPRESSED_BYTES || bytes[0] & COMPRESSION_FLAG == 0 {
+            return None;
+        }
+
+        let flags = bytes[0];
+        if flags & INFINITY_FLAG != 0 {
+            let rest_is_zero = flags == COMPRESSION_FLAG | INFINITY_FLAG
+                && bytes[1..].iter().all(|byte| *byte == 0);
+            return rest_is_zero.then(G2Affine::identity);
+        }
+
+        let mut c1_bytes = [0u8; BASE_FIELD_BYTES];
+        c1_bytes.copy_from_slice(&bytes[..BASE_FIELD_BYTES]);
+        c1_bytes[0] &= !(COMPRESSION_FLAG | SORT_FLAG);
+        let x = Fp2::from_integers(
+            Bls12_381BaseField::from_bytes_be(&bytes[BASE_FIELD_BYTES..])?,
+            Bls12_381BaseField::from_bytes_be(&c1_bytes)?,
+        );
+
+        G2Affine::from_x_coordinate(&x, flags & SORT_FLAG != 0).ok()
+    }
+
+    /// The point of the twist with x-coordinate `x`, taking the y that is
+    /// lexicographically largest when `y_sign` is set, like the sort flag of
+    /// compressed points. The point is not necessarily in G2.
+    pub fn from_x_coordinate(x: &Fp2, y_sign: bool) -> Result<Self, CurveError> {
+        let modulus = Bls12_381BaseField::modulus();
+        if [&x.c0, &x.c1].iter().any(|c| **c < 0 || *c >= modulus) {
+            return Err(CurveError::InvalidXCoordinate("G2"));
+        }
+
+        let b = Fp2::from_integers(Integer::from(4), Integer::from(4));
+        let y = x
+            .cubic()
+            .add(&b)
+            .sqrt()
+            .ok_or(CurveError::InvalidXCoordinate("G2"))?;
+        let y = if y.lexicographically_largest() == y_sign {
+            y
+        } else {
+            y.neg()
+        };
+
+        let point = G2Affine {
+            x: x.clone(),
+            y,
+            infinity: false,
+        };
+
+        // guards against a square root that is not one
+        if !point.is_on_curve() {
+            return Err(CurveError::InvalidXCoordinate("G2"));
+        }
+        Ok(point)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_uncompressed_round_trip() {
+        let mut rng = zkper_rand::TestRng::new();
+
+        for point in [
+            G2Affine::generator(),
+            G2Affine::identity(),
+            G2Projective::random(&mut rng).to_affine(),
+        ] {
+            let bytes = point.to_uncompressed();
+            assert_eq!(G2Affine::from_uncompressed(&bytes), Some(point));
+        }
+
+        let mut bytes = G2Affine::generator().to_uncompressed();
+        bytes[G2_UNCOMPRESSED_BYTES - 1] ^= 1;
+        assert_eq!(G2Affine::from_uncompressed(&bytes), None);
+    }
+    #[test]
+    fn test_from_x_coordinate() {
+        let generator = G2Affine::generator();
+        let sign = generator.y.lexicographically_largest();
+        assert_eq!(
+            G2Affine::from_x_coordinate(&generator.x, sign),
+            Ok(generator.clone())
+        );
+        assert_eq!(
+            G2Affine::from_x_coordinate(&generator.x, !sign),
+            Ok(generator.neg())
+        );
+
+        let mut shifted = generator.x.clone();
+        shifted.c1 += Bls12_381BaseField::modulus();
+        assert_eq!(
+            G2Affine::from_x_coordinate(&shifted, sign),
+            Err(CurveError::InvalidXCoordinate("G2"))
+        );
+
+        let off_curve = (1u32..)
+            .map(|c0| Fp2::from_integers(Integer::from(c0), Integer::from(0)))
+            .find(|x| G2Affine::from_x_coordinate(x, false).is_err())
+            .unwrap();
+        let b = Fp2::from_integers(Integer::from(4), Integer::from(4));
+        assert!(!off_curve.cubic().add(&b).is_square());
+    }
+
+    #[test]
+    fn test_compressed_round_trip() {
+        let mut rng = zkper_rand::TestRng::new();
+
+        let point = G2Projective::random(&mut rng).to_affine();
+        for point in [
+            G2Affine::generator(),
+            G2Affine::identity(),
+            point.clone(),
+            point.neg(),
+        ] {
+            let bytes = point.to_compressed();
+            assert_eq!(G2Affine::from_compressed(&bytes), Some(point));
+        }
+
+        let mut bytes = point.to_compressed();
+        bytes[0] ^= SORT_FLAG;
+        assert_eq!(G2Affine::from_compressed(&bytes), Some(point.neg()));
+
+        let mut bytes = point.to_compressed();
+        bytes[0] &= !COMPRESSION_FLAG;
+        assert_eq!(G2Affine::from_compressed(&bytes), None);
+    }
 }
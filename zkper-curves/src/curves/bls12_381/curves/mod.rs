@@ -1,11 +1,49 @@
 use core::fmt;
-use rand::Rng;
 use rand::RngCore;
 use rug::Integer;
 
-use crate::{curves::bls12_381::Bls12_381BaseField, traits::field::FieldTrait};
+use crate::{
+    curves::bls12_381::{Bls12_381BaseField, Scalar, BLS12_381_SCALAR},
+    traits::field::FieldTrait,
+};
 
 pub mod g1;
 pub mod g1_affine;
 pub mod g2;
 pub mod g2_affine;
+
+/// Constants of the Shallue–van de Woestijne map to y^2 = x^3 + b (RFC 9380,
+/// section 6.6.1, with A = 0).
+pub(crate) struct Svdw<F> {
+    pub z: F,
+    /// g(Z)
+    pub c1: F,
+    /// -Z / 2
+    pub c2: F,
+    /// sqrt(-g(Z) * 3Z^2), the root with sgn0 = 0
+    pub c3: F,
+    /// -4g(Z) / 3Z^2
+    pub c4: F,
+}
+
+/// How `mul_scalar_with` walks the bits of a scalar.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Uniform {
+    /// Double-and-add over the set bits. The fastest, but the work done
+    /// depends on the scalar.
+    #[default]
+    Variable,
+    /// Montgomery ladder over every bit of Fr with complete formulas and
+    /// arithmetic conditional swaps: the same sequence of field operations
+    /// for every scalar. The rug limb arithmetic underneath is still not
+    /// constant time.
+    Ladder,
+}
+
+/// The bits of `scalar` mod r, most significant first, padded to the bit
+/// length of r so that every scalar takes the same number of ladder steps.
+fn ladder_bits(scalar: &Scalar) -> impl Iterator<Item = bool> {
+    let scalar = BLS12_381_SCALAR.reduce(&scalar.0);
+    let bits = BLS12_381_SCALAR.modulus_ref().significant_bits();
+    (0..bits).rev().map(move |i| scalar.get_bit(i))
+}
@@ -1,8 +1,20 @@
+use std::{
+    borrow::Cow,
+    cmp::Ordering,
+    ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign},
+};
+
 use crate::curves::bls12_381::BLS12_381_BASE;
 
 use super::*;
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+/// Size of a base field element in bytes.
+pub const BASE_FIELD_BYTES: usize = 48;
+
+/// An element of Fp. Values built through `From<Integer>` are reduced, and
+/// equality, ordering and serialization all go through the canonical
+/// representative in [0, p), whatever is stored in the public field.
+#[derive(Clone, Debug)]
 pub struct Bls12_381BaseField(pub Integer);
 
 impl Bls12_381BaseField {
@@ -12,19 +24,169 @@ impl Bls12_381BaseField {
             .map(|x| u64::from_str_radix(x.strip_prefix("0x").unwrap_or(x), 16).unwrap())
             .collect::<Vec<_>>();
 
-        let value = Integer::from_digits::<u64>(&u64_vec, rug::integer::Order::Lsf);
-        value
+        Integer::from_digits::<u64>(&u64_vec, rug::integer::Order::Lsf)
     }
 
     pub fn from_u64_vec(u64_vec: &[u64]) -> Integer {
-        let value = Integer::from_digits::<u64>(u64_vec, rug::integer::Order::Lsf);
-        value
+        Integer::from_digits::<u64>(u64_vec, rug::integer::Order::Lsf)
+    }
+
+    /// Big-endian encoding of a field element, left-padded to `BASE_FIELD_BYTES`.
+    pub fn to_bytes_be(input: &Integer) -> [u8; BASE_FIELD_BYTES] {
+        let digits = input.to_digits::<u8>(rug::integer::Order::Msf);
+        let mut bytes = [0u8; BASE_FIELD_BYTES];
+        bytes[BASE_FIELD_BYTES - digits.len()..].copy_from_slice(&digits);
+        bytes
+    }
+
+    /// Inverse of `to_bytes_be`, returns None if the value is not reduced.
+    pub fn from_bytes_be(bytes: &[u8]) -> Option<Integer> {
+        if bytes.len() != BASE_FIELD_BYTES {
+            return None;
+        }
+
+        let value = Integer::from_digits(bytes, rug::integer::Order::Msf);
+        if &value >= Self::modulus() {
+            return None;
+        }
+
+        Some(value)
     }
 }
 
 impl From<Integer> for Bls12_381BaseField {
     fn from(value: Integer) -> Self {
-        Self(value)
+        Self(BLS12_381_BASE.new_element(value))
+    }
+}
+
+impl From<u64> for Bls12_381BaseField {
+    fn from(value: u64) -> Self {
+        Integer::from(value).into()
+    }
+}
+
+impl Bls12_381BaseField {
+    /// The representative of this element in [0, p).
+    pub fn canonical(&self) -> Cow<'_, Integer> {
+        if self.0 >= 0 && &self.0 < Self::modulus() {
+            Cow::Borrowed(&self.0)
+        } else {
+            Cow::Owned(BLS12_381_BASE.reduce(&self.0))
+        }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.canonical().is_zero()
+    }
+
+    /// The parity of the canonical representative, `sgn0` of the
+    /// hash-to-curve specification (RFC 9380, section 4.1).
+    pub fn sgn0(&self) -> bool {
+        self.canonical().is_odd()
+    }
+
+    /// Canonical big-endian encoding, `BASE_FIELD_BYTES` long.
+    pub fn to_bytes(&self) -> [u8; BASE_FIELD_BYTES] {
+        Self::to_bytes_be(&self.canonical())
+    }
+
+    /// Inverse of `to_bytes`. Returns None for encodings of values >= p, so
+    /// every element has exactly one encoding.
+    pub fn from_bytes(bytes: &[u8; BASE_FIELD_BYTES]) -> Option<Self> {
+        Self::from_bytes_be(bytes).map(Self)
+    }
+}
+
+impl PartialEq for Bls12_381BaseField {
+    fn eq(&self, other: &Self) -> bool {
+        self.canonical() == other.canonical()
+    }
+}
+
+impl Eq for Bls12_381BaseField {}
+
+impl PartialOrd for Bls12_381BaseField {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Orders elements by their canonical representatives.
+impl Ord for Bls12_381BaseField {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.canonical().cmp(&other.canonical())
+    }
+}
+
+/// `$op` for every combination of owned and borrowed operands, and `$op_assign`.
+macro_rules! impl_binary_op {
+    ($op:ident, $method:ident, $op_assign:ident, $method_assign:ident, $backend:ident) => {
+        impl $op<&Bls12_381BaseField> for &Bls12_381BaseField {
+            type Output = Bls12_381BaseField;
+
+            fn $method(self, rhs: &Bls12_381BaseField) -> Bls12_381BaseField {
+                Bls12_381BaseField(
+                    BLS12_381_BASE.$backend(self.canonical().into_owned(), &rhs.canonical()),
+                )
+            }
+        }
+
+        impl $op<Bls12_381BaseField> for &Bls12_381BaseField {
+            type Output = Bls12_381BaseField;
+
+            fn $method(self, rhs: Bls12_381BaseField) -> Bls12_381BaseField {
+                self.$method(&rhs)
+            }
+        }
+
+        impl $op<&Bls12_381BaseField> for Bls12_381BaseField {
+            type Output = Bls12_381BaseField;
+
+            fn $method(self, rhs: &Bls12_381BaseField) -> Bls12_381BaseField {
+                (&self).$method(rhs)
+            }
+        }
+
+        impl $op<Bls12_381BaseField> for Bls12_381BaseField {
+            type Output = Bls12_381BaseField;
+
+            fn $method(self, rhs: Bls12_381BaseField) -> Bls12_381BaseField {
+                (&self).$method(&rhs)
+            }
+        }
+
+        impl $op_assign<&Bls12_381BaseField> for Bls12_381BaseField {
+            fn $method_assign(&mut self, rhs: &Bls12_381BaseField) {
+                *self = (&*self).$method(rhs);
+            }
+        }
+
+        impl $op_assign<Bls12_381BaseField> for Bls12_381BaseField {
+            fn $method_assign(&mut self, rhs: Bls12_381BaseField) {
+                *self = (&*self).$method(&rhs);
+            }
+        }
+    };
+}
+
+impl_binary_op!(Add, add, AddAssign, add_assign, add);
+impl_binary_op!(Sub, sub, SubAssign, sub_assign, sub);
+impl_binary_op!(Mul, mul, MulAssign, mul_assign, mul);
+
+impl Neg for &Bls12_381BaseField {
+    type Output = Bls12_381BaseField;
+
+    fn neg(self) -> Bls12_381BaseField {
+        Bls12_381BaseField(BLS12_381_BASE.neg(self.canonical().into_owned()))
+    }
+}
+
+impl Neg for Bls12_381BaseField {
+    type Output = Bls12_381BaseField;
+
+    fn neg(self) -> Bls12_381BaseField {
+        -&self
     }
 }
 
@@ -66,6 +228,12 @@ impl Bls12_381BaseField {
     pub fn sub(input: Integer, other: &Integer) -> Integer {
         BLS12_381_BASE.sub(input, other)
     }
+
+    /// Returns true if `input` is greater than its negation, i.e. above (p - 1) / 2.
+    pub fn lexicographically_largest(input: &Integer) -> bool {
+        let half = (BLS12_381_BASE.modulus() - 1u32) >> 1;
+        input > &half
+    }
 }
 
 impl FieldTrait for Bls12_381BaseField {
@@ -76,13 +244,13 @@ impl FieldTrait for Bls12_381BaseField {
         BLS12_381_BASE.sample_mont(rng)
     }
     fn modulus<'a>() -> &'a Integer {
-        &&BLS12_381_BASE.modulus_ref()
+        BLS12_381_BASE.modulus_ref()
     }
     fn r<'a>() -> &'a Integer {
-        &BLS12_381_BASE.r_ref()
+        BLS12_381_BASE.r_ref()
     }
     fn r2<'a>() -> &'a Integer {
-        &BLS12_381_BASE.r2_ref()
+        BLS12_381_BASE.r2_ref()
     }
     fn limbs() -> usize {
         BLS12_381_BASE.limbs()
@@ -130,9 +298,51 @@ mod tests {
         traits::field::FieldTrait,
     };
 
+    #[test]
+    fn test_field_element() {
+        let p = BLS12_381_BASE.modulus();
+        let mut rng = zkper_rand::TestRng::new();
+        let a = Bls12_381BaseField::from(Bls12_381BaseField::random(&mut rng));
+        let b = Bls12_381BaseField::from(Bls12_381BaseField::random(&mut rng));
+
+        // operators agree with the backend, for owned and borrowed operands
+        assert_eq!((&a + &b).0, Bls12_381BaseField::add(a.0.clone(), &b.0));
+        assert_eq!((&a - &b).0, Bls12_381BaseField::sub(a.0.clone(), &b.0));
+        assert_eq!((&a * &b).0, Bls12_381BaseField::mul(a.0.clone(), &b.0));
+        assert_eq!(a.clone() + b.clone() - b.clone(), a);
+        assert_eq!(-&a + &a, Bls12_381BaseField::from(0));
+        let mut c = a.clone();
+        c *= &b;
+        c += b.clone();
+        c -= &a;
+        assert_eq!(c, &a * &b + &b - &a);
+
+        // comparison and serialization use the canonical representative
+        let unreduced = Bls12_381BaseField(a.0.clone() + &p);
+        assert_eq!(unreduced, a);
+        assert_eq!(unreduced.to_bytes(), a.to_bytes());
+        assert_eq!(Bls12_381BaseField::from(a.0.clone() - &p), a);
+        assert!(Bls12_381BaseField::from(1) < Bls12_381BaseField::from(Integer::from(&p - 1u32)));
+        assert!(Bls12_381BaseField::from(Integer::from(-1)) > Bls12_381BaseField::from(2));
+
+        assert_eq!(
+            Bls12_381BaseField::from_bytes(&a.to_bytes()),
+            Some(a.clone())
+        );
+        let p_bytes = Bls12_381BaseField::to_bytes_be(&p);
+        assert_eq!(Bls12_381BaseField::from_bytes(&p_bytes), None);
+
+        // sgn0 is the parity, and flips under negation of a non-zero element
+        assert!(!Bls12_381BaseField::from(0).sgn0());
+        assert!(Bls12_381BaseField::from(1).sgn0());
+        assert!(!Bls12_381BaseField::from(Integer::from(-1)).sgn0());
+        assert_ne!(a.sgn0(), (-&a).sgn0());
+        assert_eq!(unreduced.sgn0(), a.sgn0());
+    }
+
     #[test]
     fn test_sqrt_constant() {
-        let d = vec![
+        let d = [
             "ee7fbfffffffeaab",
             "07aaffffac54ffff",
             "d9cc34a83dac3d89",
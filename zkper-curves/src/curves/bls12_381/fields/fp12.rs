@@ -1,14 +1,33 @@
 use std::fmt::{self, Display};
 
-use super::{fp2::Fp2, fp6::Fp6};
-
-lazy_static::lazy_static! {
-    // Fp2::NONRESIDUE^(((q^1) - 1) / 6)
-    pub static ref FROBENIUS_COEFF_FP12_C1: Fp2 =Fp2::from_strs(
-        "3850754370037169011952147076051364057158807420970682438676050522613628423219637725072182697113062777891589506424760",
-        "151655185184498381465642749684540099398075398968325446656007613510403227271200139370504932015952886146304766135027"
-    );
-}
+use rug::Integer;
+
+use crate::{constant::Constant, curves::bls12_381::BLS12_381_BASE, fp};
+
+use super::{
+    fp2::{checked_nonresidue_power, frobenius_coeffs, Fp2},
+    fp6::{Fp6, FP6_BYTES},
+};
+
+/// Size of a serialized Fp12 element: c1 || c0, each an Fp6 encoding.
+pub const FP12_BYTES: usize = 2 * FP6_BYTES;
+
+// Fp2::NONRESIDUE^(((q^k) - 1) / 6) for k = 0..12
+pub static FROBENIUS_COEFF_FP12_C1: Constant<[Fp2; 12]> = Constant::new(
+    "FROBENIUS_COEFF_FP12_C1",
+    || {
+        let coeff = checked_nonresidue_power(
+            "FROBENIUS_COEFF_FP12_C1",
+            Fp2::from_u64_vec(
+                &fp!("0x1904d3bf02bb0667c231beb4202c0d1f0fd603fd3cbd5f4f7b2443d784bab9c4f67ea53d63e7813d8d0775ed92235fb8"),
+                &fp!("0x00fc3e2b36c4e03288e9e902231f9fb854a14787b6c7b36fec0c8ec971f63c5f282d5ac14d6c7ec22cf78a126ddc4af3"),
+            ),
+            &((BLS12_381_BASE.modulus() - 1u32) / 6u32),
+            false,
+        )?;
+        frobenius_coeffs("FROBENIUS_COEFF_FP12_C1", coeff)
+    },
+);
 
 /// Represents an element of Fp12 as c0 + c1 * w
 /// where w is the cubic non-residue in Fp6.
@@ -30,6 +49,25 @@ impl Fp12 {
         Fp12 { c0, c1 }
     }
 
+    /// Canonical encoding c1 || c0: the twelve Fp coefficients, most
+    /// significant first, each big-endian.
+    pub fn to_bytes(&self) -> [u8; FP12_BYTES] {
+        let mut bytes = [0u8; FP12_BYTES];
+        bytes[..FP6_BYTES].copy_from_slice(&self.c1.to_bytes());
+        bytes[FP6_BYTES..].copy_from_slice(&self.c0.to_bytes());
+        bytes
+    }
+
+    /// Inverse of `to_bytes`, returns None for non-canonical encodings.
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() != FP12_BYTES {
+            return None;
+        }
+
+        let (c1, c0) = bytes.split_at(FP6_BYTES);
+        Some(Self::new(Fp6::from_bytes(c0)?, Fp6::from_bytes(c1)?))
+    }
+
     /// Returns the zero element of Fp12.
     pub fn zero() -> Self {
         Fp12 {
@@ -71,6 +109,11 @@ impl Fp12 {
         }
     }
 
+    /// Conjugates this element in place.
+    pub fn conjugate_assign(&mut self) {
+        self.c1 = self.c1.neg();
+    }
+
     /// Multiplies two Fp12 elements.
     pub fn mul(&self, other: &Fp12) -> Fp12 {
         let aa = self.c0.mul(&other.c0);
@@ -86,6 +129,11 @@ impl Fp12 {
         Fp12 { c0, c1 }
     }
 
+    /// Multiplies this element by `other` in place.
+    pub fn mul_assign(&mut self, other: &Fp12) {
+        *self = self.mul(other);
+    }
+
     /// opt mul only c0, c1, c4
     pub fn mul_by_c0_c1_c4(&self, c0: &Fp2, c1: &Fp2, c4: &Fp2) -> Fp12 {
         let aa = self.c0.mul_by_c0_c1(c0, c1);
@@ -99,6 +147,11 @@ impl Fp12 {
         Fp12 { c0, c1 }
     }
 
+    /// In-place `mul_by_c0_c1_c4`.
+    pub fn mul_by_c0_c1_c4_assign(&mut self, c0: &Fp2, c1: &Fp2, c4: &Fp2) {
+        *self = self.mul_by_c0_c1_c4(c0, c1, c4);
+    }
+
     /// Adds two Fp12 elements.
     pub fn add(&self, other: &Fp12) -> Fp12 {
         Fp12 {
@@ -150,16 +203,247 @@ impl Fp12 {
         Fp12 { c0, c1 }
     }
 
-    /// Raises this element to p.
-    pub fn frobenius_map(&self) -> Self {
-        let c0 = self.c0.frobenius_map();
-        let c1 = self.c1.frobenius_map();
+    /// Squares this element in place.
+    pub fn square_assign(&mut self) {
+        *self = self.square();
+    }
+
+    /// Computes the square of an Fp4 element: (a + bi)^2 = (a^2 - b^2) + (2ab)i
+    fn fp4_square(a: &Fp2, b: &Fp2) -> (Fp2, Fp2) {
+        let a_squared = a.square();
+        let b_squared = b.square();
+        let ab_mul_2 = a.add(b).square().sub(&a_squared).sub(&b_squared);
+
+        let c0 = a_squared.add(&b_squared.mul_by_nonresidue());
+        let c1 = ab_mul_2;
+
+        (c0, c1)
+    }
+
+    /// Performs efficient squaring in the cyclotomic subgroup
+    /// Based on "Faster Squaring in the Cyclotomic Subgroup of Sixth Degree Extensions"
+    /// by F. Beuchat et al. (https://eprint.iacr.org/2009/565.pdf)
+    ///
+    /// Only valid for elements of the cyclotomic subgroup, which contains the
+    /// output of the final exponentiation.
+    pub fn cyclotomic_square(&self) -> Fp12 {
+        let mut z0 = self.c0.c0.clone();
+        let mut z1 = self.c1.c1.clone();
+        let mut z2 = self.c1.c0.clone();
+        let mut z3 = self.c0.c2.clone();
+        let mut z4 = self.c0.c1.clone();
+        let mut z5 = self.c1.c2.clone();
+
+        let (t0, t1) = Self::fp4_square(&z0, &z1);
+
+        // For A
+        z0 = t0.sub(&z0);
+        z0 = z0.add(&z0).add(&t0);
 
-        // c1 = c1 * (u + 1)^((p - 1) / 6)
-        let c1 = c1.mul(&Fp6::from(FROBENIUS_COEFF_FP12_C1.clone()));
+        z1 = t1.add(&z1);
+        z1 = z1.add(&z1).add(&t1);
+
+        let (mut t0, t1) = Self::fp4_square(&z2, &z3);
+        let (t2, t3) = Self::fp4_square(&z4, &z5);
+
+        // For C
+        z4 = t0.sub(&z4);
+        z4 = z4.add(&z4).add(&t0);
+
+        z5 = t1.add(&z5);
+        z5 = z5.add(&z5).add(&t1);
+
+        // For B
+        t0 = t3.mul_by_nonresidue();
+        z2 = t0.add(&z2);
+        z2 = z2.add(&z2).add(&t0);
+
+        z3 = t2.sub(&z3);
+        z3 = z3.add(&z3).add(&t2);
+
+        Fp12 {
+            c0: Fp6 {
+                c0: z0,
+                c1: z4,
+                c2: z3,
+            },
+            c1: Fp6 {
+                c0: z2,
+                c1: z1,
+                c2: z5,
+            },
+        }
+    }
+
+    /// Karabina's compressed squaring in the cyclotomic subgroup
+    /// ("Squaring in Cyclotomic Subgroups", https://eprint.iacr.org/2010/542.pdf).
+    ///
+    /// Only the coefficients g1 = c0.c1, g2 = c0.c2, g3 = c1.c0 and g5 = c1.c2
+    /// are read and written; the others are left as zero and are recovered by
+    /// [`Self::decompress_karabina`] once the squarings are done.
+    pub fn cyclotomic_square_compressed(&self) -> Fp12 {
+        let g1 = &self.c0.c1;
+        let g2 = &self.c0.c2;
+        let g3 = &self.c1.c0;
+        let g5 = &self.c1.c2;
+
+        let g1_squared = g1.square();
+        let g2_squared = g2.square();
+        let g3_squared = g3.square();
+        let g5_squared = g5.square();
+        // 2 g1 g5 and 2 g2 g3
+        let g1g5_2 = g1.add(g5).square().sub(&g1_squared).sub(&g5_squared);
+        let g2g3_2 = g2.add(g3).square().sub(&g2_squared).sub(&g3_squared);
+
+        // h1 = 3 (g3^2 + ξ g2^2) - 2 g1
+        let t = g3_squared.add(&g2_squared.mul_by_nonresidue());
+        let h1 = t.sub(g1).double().add(&t);
+
+        // h2 = 3 (g1^2 + ξ g5^2) - 2 g2
+        let t = g1_squared.add(&g5_squared.mul_by_nonresidue());
+        let h2 = t.sub(g2).double().add(&t);
+
+        // h3 = 6 ξ g1 g5 + 2 g3
+        let t = g1g5_2.mul_by_nonresidue();
+        let h3 = t.add(g3).double().add(&t);
+
+        // h5 = 6 g2 g3 + 2 g5
+        let h5 = g2g3_2.add(g5).double().add(&g2g3_2);
+
+        Fp12 {
+            c0: Fp6 {
+                c0: Fp2::zero(),
+                c1: h1,
+                c2: h2,
+            },
+            c1: Fp6 {
+                c0: h3,
+                c1: Fp2::zero(),
+                c2: h5,
+            },
+        }
+    }
+
+    /// Recovers g0 = c0.c0 and g4 = c1.c1 of an element compressed by
+    /// [`Self::cyclotomic_square_compressed`]. Costs one inversion.
+    pub fn decompress_karabina(&self) -> Fp12 {
+        let mut values = [self.clone()];
+        Self::batch_decompress_karabina(&mut values);
+        let [value] = values;
+        value
+    }
+
+    /// Decompresses every element of `values` in place, sharing a single
+    /// inversion between them.
+    pub fn batch_decompress_karabina(values: &mut [Fp12]) {
+        // g4 = (ξ g5^2 + 3 g1^2 - 2 g2) / (4 g3), or 2 g1 g5 / g2 when g3 = 0
+        let (numerators, mut denominators): (Vec<Fp2>, Vec<Fp2>) = values
+            .iter()
+            .map(|value| {
+                let (g1, g2, g3, g5) = (&value.c0.c1, &value.c0.c2, &value.c1.c0, &value.c1.c2);
+                if g3.is_zero() {
+                    (g1.mul(g5).double(), g2.clone())
+                } else {
+                    let g1_squared = g1.square();
+                    let numerator = g5
+                        .square()
+                        .mul_by_nonresidue()
+                        .add(&g1_squared.sub(g2).double())
+                        .add(&g1_squared);
+                    (numerator, g3.double().double())
+                }
+            })
+            .unzip();
+        // a zero denominator only comes with a zero g4, as for one
+        Fp2::batch_invert(&mut denominators);
+
+        for ((value, numerator), denominator) in values.iter_mut().zip(numerators).zip(denominators)
+        {
+            let g4 = numerator.mul(&denominator);
+            let (g1, g2, g3, g5) = (&value.c0.c1, &value.c0.c2, &value.c1.c0, &value.c1.c2);
+
+            // g0 = ξ (2 g4^2 + g3 g5 - 3 g1 g2) + 1
+            let g1g2 = g1.mul(g2);
+            let g0 = g4
+                .square()
+                .sub(&g1g2)
+                .double()
+                .sub(&g1g2)
+                .add(&g3.mul(g5))
+                .mul_by_nonresidue()
+                .add(&Fp2::one());
+
+            value.c0.c0 = g0;
+            value.c1.c1 = g4;
+        }
+    }
+
+    /// Raises this element to p^power with one table lookup per
+    /// coefficient, whatever the power.
+    pub fn frobenius_map(&self, power: usize) -> Self {
+        let c0 = self.c0.frobenius_map(power);
+        let c1 = self.c1.frobenius_map(power);
+
+        // c1 = c1 * (u + 1)^((p^power - 1) / 6)
+        let c1 = c1.mul_by_fp2(&FROBENIUS_COEFF_FP12_C1[power % 12]);
 
         Fp12 { c0, c1 }
     }
+
+    /// Exponentiation by a non-negative power (variable time)
+    pub fn pow(&self, exponent: &Integer) -> Self {
+        let mut result = Self::one();
+        for i in (0..exponent.significant_bits()).rev() {
+            result = result.square();
+            if exponent.get_bit(i) {
+                result = result.mul(self);
+            }
+        }
+        result
+    }
+
+    /// The norm to Fp6, self * self^(p^6) = c0^2 - v c1^2.
+    pub fn norm(&self) -> Fp6 {
+        self.c0.square().sub(&self.c1.square().mul_by_nonresidue())
+    }
+
+    /// Returns true if this element is a square in Fp12, zero included, which
+    /// is the case exactly when its norm is a square in Fp6.
+    pub fn is_square(&self) -> bool {
+        self.norm().is_square()
+    }
+
+    /// Attempts to compute the square root of this element in Fp12 through
+    /// the norm to Fp6, as for any quadratic extension. Returns None if the
+    /// element is not a square.
+    ///
+    /// ref: https://eprint.iacr.org/2012/685.pdf Page 15 Algorithm 8
+    pub fn sqrt(&self) -> Option<Self> {
+        // (x w)^2 = x^2 v, so a non-square c0 has a root in c1
+        if self.c1.is_zero() {
+            return match self.c0.sqrt() {
+                Some(c0) => Some(Fp12::new(c0, Fp6::zero())),
+                None => {
+                    let v = Fp6::new(Fp2::zero(), Fp2::one(), Fp2::zero());
+                    let c1 = self.c0.mul(&v.invert()?).sqrt()?;
+                    Some(Fp12::new(Fp6::zero(), c1))
+                }
+            };
+        }
+
+        // x0^2 = (c0 ± sqrt(norm)) / 2 and x1 = c1 / (2 x0)
+        let lambda = self.norm().sqrt()?;
+        let half = Fp6::from(Fp2::from_base(&Integer::from(2))).invert()?;
+        let mut delta = self.c0.add(&lambda).mul(&half);
+        if !delta.is_square() {
+            delta = self.c0.sub(&lambda).mul(&half);
+        }
+        let x0 = delta.sqrt()?;
+        let x1 = self.c1.mul(&x0.add(&x0).invert()?);
+
+        let x = Fp12::new(x0, x1);
+        (x.square() == *self).then_some(x)
+    }
 }
 
 #[cfg(test)]
@@ -568,22 +852,95 @@ mod tests {
             a.clone().mul(&b).invert().unwrap()
         );
 
-        assert!(a != a.frobenius_map());
+        assert!(a != a.frobenius_map(1));
 
-        assert_eq!(
-            a,
-            a.frobenius_map()
-                .frobenius_map()
-                .frobenius_map()
-                .frobenius_map()
-                .frobenius_map()
-                .frobenius_map()
-                .frobenius_map()
-                .frobenius_map()
-                .frobenius_map()
-                .frobenius_map()
-                .frobenius_map()
-                .frobenius_map()
-        );
+        assert_eq!(a, (0..12).fold(a.clone(), |x, _| x.frobenius_map(1)));
+    }
+
+    #[test]
+    fn test_frobenius_map() {
+        use crate::curves::bls12_381::BLS12_381_BASE;
+
+        let mut rng = zkper_rand::TestRng::new();
+        let a = Fp12::random(&mut rng);
+
+        // every power matches repeated application of the p-power map
+        let mut expected = a.clone();
+        for power in 0..=24 {
+            assert_eq!(a.frobenius_map(power), expected, "power {power}");
+            assert_eq!(a.c0.frobenius_map(power), expected.c0, "power {power}");
+            expected = expected.frobenius_map(1);
+        }
+
+        let p2 = BLS12_381_BASE.modulus() * BLS12_381_BASE.modulus_ref();
+        assert_eq!(a.pow(&p2), a.frobenius_map(2));
+        assert_eq!(a.frobenius_map(6), a.conjugate());
+    }
+
+    #[test]
+    fn test_cyclotomic_square_compressed() {
+        use crate::curves::bls12_381::paring::BLS12_381Pairing;
+
+        let mut rng = zkper_rand::TestRng::new();
+        let a = BLS12_381Pairing::easy_part(&Fp12::random(&mut rng)).unwrap();
+
+        let mut expected = Vec::new();
+        let mut compressed = Vec::new();
+        let (mut x, mut y) = (a.clone(), a.clone());
+        for _ in 0..8 {
+            x = x.cyclotomic_square();
+            y = y.cyclotomic_square_compressed();
+            assert_eq!(x, a.pow(&(Integer::from(1) << (expected.len() + 1))));
+            assert_eq!(y.decompress_karabina(), x);
+            expected.push(x.clone());
+            compressed.push(y.clone());
+        }
+        Fp12::batch_decompress_karabina(&mut compressed);
+        assert_eq!(compressed, expected);
+
+        // the compressed form of one squares and decompresses to one
+        assert!(Fp12::one()
+            .cyclotomic_square_compressed()
+            .decompress_karabina()
+            .is_one());
+    }
+
+    #[test]
+    fn test_pow_sqrt() {
+        use crate::curves::bls12_381::{
+            curves::{g1_affine::G1Affine, g2_affine::G2Affine},
+            paring::BLS12_381Pairing,
+            BLS12_381_BASE, BLS12_381_SCALAR,
+        };
+
+        let mut rng = zkper_rand::TestRng::new();
+        let a = Fp12::random(&mut rng);
+
+        assert_eq!(a.pow(&Integer::ZERO), Fp12::one());
+        assert_eq!(a.pow(&Integer::from(5)), a.square().square().mul(&a));
+        assert_eq!(a.pow(BLS12_381_BASE.modulus_ref()), a.frobenius_map(1));
+        assert_eq!(a.norm(), a.mul(&a.conjugate()).c0);
+
+        // Gt has order r
+        let gt = BLS12_381Pairing::pairing(&G1Affine::generator(), &G2Affine::generator()).0;
+        assert!(gt.pow(BLS12_381_SCALAR.modulus_ref()).is_one());
+        assert!(!gt.pow(&Integer::from(2)).is_one());
+
+        let mut non_squares = 0;
+        for _ in 0..4 {
+            let x = Fp12::random(&mut rng);
+            let root = x.square().sqrt().unwrap();
+            assert!(root == x || root == x.neg());
+            assert_eq!(x.is_square(), x.sqrt().is_some());
+            non_squares += !x.is_square() as usize;
+        }
+        assert!(non_squares > 0);
+
+        // elements of Fp6 are squares in Fp12, through w when not in Fp6
+        let v = Fp6::new(Fp2::zero(), Fp2::one(), Fp2::zero());
+        let w = Fp12::new(Fp6::zero(), Fp6::one());
+        let root = Fp12::new(v.clone(), Fp6::zero()).sqrt().unwrap();
+        assert!(root == w || root == w.neg());
+        assert!(Fp12::new(v.neg(), Fp6::zero()).is_square());
     }
 }
@@ -1,10 +1,17 @@
-use crate::{curves::bls12_381::BLS12_381_BASE, traits::field::FieldTrait};
+use crate::{
+    constant::{ensure, ConstantError},
+    curves::bls12_381::BLS12_381_BASE,
+    traits::field::FieldTrait,
+};
 use rug::Integer;
 use std::{fmt::Display, str::FromStr};
 
-use super::base::Bls12_381BaseField;
+use super::base::{Bls12_381BaseField, BASE_FIELD_BYTES};
 use num_traits::One;
 
+/// Size of a serialized Fp2 element: c1 || c0, each big-endian.
+pub const FP2_BYTES: usize = 2 * BASE_FIELD_BYTES;
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Fp2 {
     pub c0: Integer, // Base field element c0
@@ -55,6 +62,30 @@ impl Fp2 {
         Self { c0, c1 }
     }
 
+    /// Canonical encoding c1 || c0, the order of G2 point encodings.
+    pub fn to_bytes(&self) -> [u8; FP2_BYTES] {
+        let mut bytes = [0u8; FP2_BYTES];
+        for (chunk, coeff) in bytes.chunks_mut(BASE_FIELD_BYTES).zip([&self.c1, &self.c0]) {
+            chunk.copy_from_slice(&Bls12_381BaseField::to_bytes_be(
+                &BLS12_381_BASE.reduce(coeff),
+            ));
+        }
+        bytes
+    }
+
+    /// Inverse of `to_bytes`, returns None for non-canonical encodings.
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() != FP2_BYTES {
+            return None;
+        }
+
+        let (c1, c0) = bytes.split_at(BASE_FIELD_BYTES);
+        Some(Self::from_integers(
+            Bls12_381BaseField::from_bytes_be(c0)?,
+            Bls12_381BaseField::from_bytes_be(c1)?,
+        ))
+    }
+
     pub fn from_mont(&self) -> Self {
         let c0 = BLS12_381_BASE.from_montgomery(&self.c0);
         let c1 = BLS12_381_BASE.from_montgomery(&self.c1);
@@ -117,10 +148,15 @@ impl Fp2 {
         }
     }
 
-    /// Raises this element to p.
+    /// Raises this element to p^power. Conjugation is the p-power map, so
+    /// even powers are the identity.
     /// ref: https://alicebob.cryptoland.net/the-frobenius-endomorphism-with-finite-fields/
-    pub fn frobenius_map(&self) -> Self {
-        self.conjugate()
+    pub fn frobenius_map(&self, power: usize) -> Self {
+        if power % 2 == 1 {
+            self.conjugate()
+        } else {
+            self.clone()
+        }
     }
 
     // Operations
@@ -159,7 +195,24 @@ impl Fp2 {
         }
     }
 
+    /// Karatsuba multiplication with lazy reduction: three full-width
+    /// products, combined before a single reduction per coefficient.
     pub fn mul(&self, rhs: &Fp2) -> Self {
+        //   c_0 = a_0 b_0 - a_1 b_1
+        //   c_1 = (a_0 + a_1)(b_0 + b_1) - a_0 b_0 - a_1 b_1
+        let a0b0 = Integer::from(&self.c0 * &rhs.c0);
+        let a1b1 = Integer::from(&self.c1 * &rhs.c1);
+        let sum = Integer::from(&self.c0 + &self.c1) * Integer::from(&rhs.c0 + &rhs.c1);
+
+        Self {
+            c1: BLS12_381_BASE.new_element(sum - &a0b0 - &a1b1),
+            c0: BLS12_381_BASE.new_element(a0b0 - a1b1),
+        }
+    }
+
+    /// The schoolbook product, four multiplications each reduced right away.
+    /// Kept as the reference for `mul` in tests and benchmarks.
+    pub fn mul_schoolbook(&self, rhs: &Fp2) -> Self {
         // F_{p^2} x F_{p^2} multiplication:
         //   c_0 = a_0 b_0 - a_1 b_1
         //   c_1 = a_0 b_1 + a_1 b_0
@@ -176,13 +229,11 @@ impl Fp2 {
         }
     }
 
+    /// Multiplies both coefficients by an element of Fp.
     pub fn mul_base(&self, base: &Integer) -> Self {
-        let a0b = BLS12_381_BASE.mul(self.c0.clone(), base);
-        let a1b = BLS12_381_BASE.mul(self.c1.clone(), base);
-
         Self {
-            c0: BLS12_381_BASE.sub(a0b.clone(), &a1b),
-            c1: BLS12_381_BASE.add(a0b, &a1b),
+            c0: BLS12_381_BASE.mul(self.c0.clone(), base),
+            c1: BLS12_381_BASE.mul(self.c1.clone(), base),
         }
     }
 
@@ -194,11 +245,9 @@ impl Fp2 {
         }
     }
 
+    /// Same as `mul_base`.
     pub fn mul_by_base(&self, fp: &Integer) -> Self {
-        Self {
-            c0: BLS12_381_BASE.mul(self.c0.clone(), fp),
-            c1: BLS12_381_BASE.mul(self.c1.clone(), fp),
-        }
+        self.mul_base(fp)
     }
 
     pub fn from_base(fp: &Integer) -> Self {
@@ -208,11 +257,23 @@ impl Fp2 {
         }
     }
 
+    /// Complex squaring with lazy reduction, two full-width products.
     pub fn square(&self) -> Self {
-        // Complex squaring for Fp2:
         // c0' = (c0 + c1) * (c0 - c1)
         // c1' = 2 * c0 * c1
+        let sum = Integer::from(&self.c0 + &self.c1);
+        let diff = Integer::from(&self.c0 - &self.c1);
+        let product = Integer::from(&self.c0 * &self.c1);
 
+        Self {
+            c0: BLS12_381_BASE.new_element(sum * diff),
+            c1: BLS12_381_BASE.new_element(product << 1),
+        }
+    }
+
+    /// Complex squaring reducing after every operation. Kept as the
+    /// reference for `square` in tests and benchmarks.
+    pub fn square_schoolbook(&self) -> Self {
         let a = BLS12_381_BASE.add(self.c0.clone(), &self.c1);
         let b = BLS12_381_BASE.sub(self.c0.clone(), &self.c1);
         let c = BLS12_381_BASE.add(self.c0.clone(), &self.c0); // 2 * c0
@@ -245,12 +306,41 @@ impl Fp2 {
         })
     }
 
+    /// Inverts every non-zero element of `values` in place with a single
+    /// inversion (Montgomery's trick). Zeros are left as zero.
+    pub fn batch_invert(values: &mut [Fp2]) {
+        let mut prefix = Vec::with_capacity(values.len());
+        let mut acc = Fp2::one();
+        for value in values.iter() {
+            if !value.is_zero() {
+                acc = acc.mul(value);
+            }
+            prefix.push(acc.clone());
+        }
+
+        let Some(mut inv) = acc.invert() else {
+            return;
+        };
+        for i in (0..values.len()).rev() {
+            if values[i].is_zero() {
+                continue;
+            }
+
+            let value_inv = match i.checked_sub(1) {
+                Some(j) => inv.mul(&prefix[j]),
+                None => inv.clone(),
+            };
+            inv = inv.mul(&values[i]);
+            values[i] = value_inv;
+        }
+    }
+
     /// Exponentiation by a large power (variable time)
     pub fn pow(&self, exponent: &Integer) -> Self {
         if exponent.is_zero() {
-            return Self::one();
+            Self::one()
         } else if exponent == &Integer::from(1) {
-            return self.clone();
+            self.clone()
         } else {
             let mut result = self.clone();
             let mut exp = exponent.clone();
@@ -279,6 +369,21 @@ impl Fp2 {
         }
     }
 
+    /// Returns true if this element is greater than its negation, comparing c1
+    /// first and c0 only when c1 is zero.
+    pub fn lexicographically_largest(&self) -> bool {
+        Bls12_381BaseField::lexicographically_largest(&self.c1)
+            || (self.c1.is_zero() && Bls12_381BaseField::lexicographically_largest(&self.c0))
+    }
+
+    /// `sgn0` of the hash-to-curve specification (RFC 9380, section 4.1):
+    /// the parity of c0, or of c1 when c0 is zero.
+    pub fn sgn0(&self) -> bool {
+        let c0 = Bls12_381BaseField::from(self.c0.clone());
+        let c1 = Bls12_381BaseField::from(self.c1.clone());
+        c0.sgn0() || (c0.is_zero() && c1.sgn0())
+    }
+
     /// Attempts to compute the square root of this element in Fp2.
     /// Returns None if the element is not a quadratic residue.
     /// for p = 3 mod 4
@@ -303,7 +408,7 @@ impl Fp2 {
         let alpha = a1.mul(&a1.mul(self));
 
         // 3: a0 ← a^q * α
-        let a0 = self.frobenius_map().mul(&alpha);
+        let a0 = self.frobenius_map(1).mul(&alpha);
 
         // 4-6: if a0 = -1 then return false
         if a0.eq(&Self::one().neg()) {
@@ -315,10 +420,10 @@ impl Fp2 {
 
         // 8-9: if α = -1 then x ← ix0
         if alpha.eq(&Self::one().neg()) {
-            return Some(Self {
+            Some(Self {
                 c0: BLS12_381_BASE.neg(x0.c1),
                 c1: x0.c0,
-            });
+            })
         } else {
             // 10-12: else b ← (1 + α)^((q-1)/2), x ← bx0
             let b = Self::one().add(&alpha).pow(
@@ -337,6 +442,53 @@ impl Fp2 {
             }
         }
     }
+
+    /// Returns true if this element is a square in Fp2, zero included. An
+    /// element is a square exactly when its norm c0^2 + c1^2 is one in Fp.
+    pub fn is_square(&self) -> bool {
+        let norm = BLS12_381_BASE.add(
+            BLS12_381_BASE.square(self.c0.clone()),
+            &BLS12_381_BASE.square(self.c1.clone()),
+        );
+        norm.legendre(BLS12_381_BASE.modulus_ref()) >= 0
+    }
+}
+
+/// Checks that `value` is (u + 1)^exponent, or its inverse when `inverted`,
+/// for the Frobenius and psi coefficients that are hardcoded as constants.
+pub(crate) fn checked_nonresidue_power(
+    name: &'static str,
+    value: Fp2,
+    exponent: &Integer,
+    inverted: bool,
+) -> Result<Fp2, ConstantError> {
+    let mut expected = Fp2::from_integers(Integer::from(1), Integer::from(1)).pow(exponent);
+    if inverted {
+        expected = expected
+            .invert()
+            .ok_or_else(|| ConstantError::new(name, "power of u + 1 is not invertible"))?;
+    }
+    ensure(name, value == expected, "does not match its power of u + 1")?;
+
+    Ok(value)
+}
+
+/// The Frobenius coefficients ξ^((p^k - 1) / d) for k = 0..12, indexed by k,
+/// from `coeff` = ξ^((p - 1) / d). Since ξ^((p^k - 1) / d) is the product of
+/// coeff^(p^j) for j < k, each entry is the previous one times a conjugate of
+/// `coeff`. The table closes: ξ^((p^12 - 1) / d) = 1.
+pub(crate) fn frobenius_coeffs(name: &'static str, coeff: Fp2) -> Result<[Fp2; 12], ConstantError> {
+    let mut table = std::array::from_fn(|_| Fp2::one());
+    for k in 1..12 {
+        table[k] = table[k - 1].mul(&coeff.frobenius_map(k - 1));
+    }
+    ensure(
+        name,
+        table[11].mul(&coeff.frobenius_map(11)).is_one(),
+        "the coefficient for p^12 is not one",
+    )?;
+
+    Ok(table)
 }
 
 #[cfg(test)]
@@ -351,7 +503,7 @@ mod tests {
 
         // (q - 3) / 4
         let p = super::BLS12_381_BASE.modulus();
-        let p2: Integer = p.clone() ^ 2 % p.clone();
+        let p2: Integer = p.clone() ^ (2 % p.clone());
 
         let q: Integer = (p2.clone() - 3) / 4;
         println!("q: {:#?}", q);
@@ -682,4 +834,63 @@ mod tests {
         );
         assert!(c.sqrt().is_none());
     }
+
+    #[test]
+    fn test_sgn0() {
+        let p = super::BLS12_381_BASE.modulus();
+        let sgn0 =
+            |c0: i32, c1: i32| Fp2::from_integers(Integer::from(c0), Integer::from(c1)).sgn0();
+
+        assert!(!sgn0(0, 0));
+        assert!(sgn0(1, 0));
+        assert!(!sgn0(2, 1));
+        assert!(sgn0(0, 1));
+        assert!(!sgn0(0, 2));
+        // -1 = p - 1 is even
+        let minus_one = Fp2::from_integers(p - 1u32, Integer::ZERO);
+        assert!(!minus_one.sgn0());
+    }
+
+    #[test]
+    fn test_is_square() {
+        let mut rng = zkper_rand::TestRng::new();
+        let mut non_squares = 0;
+        for _ in 0..16 {
+            let a = Fp2::random(&mut rng);
+            assert!(a.square().is_square());
+            assert_eq!(a.is_square(), a.sqrt().is_some());
+            non_squares += !a.is_square() as usize;
+        }
+        assert!(non_squares > 0);
+        assert!(Fp2::zero().is_square());
+
+        // u + 1 is the non-residue Fp6 is built with
+        assert!(!Fp2::from_integers(Integer::from(1), Integer::from(1)).is_square());
+    }
+
+    #[test]
+    fn test_lazy_reduction() {
+        use crate::curves::bls12_381::BLS12_381_BASE;
+
+        let mut rng = zkper_rand::TestRng::new();
+        for _ in 0..32 {
+            let a = Fp2::random(&mut rng);
+            let b = Fp2::random(&mut rng);
+            assert_eq!(a.mul(&b), a.mul_schoolbook(&b));
+            assert_eq!(a.square(), a.square_schoolbook());
+            assert_eq!(a.square(), a.mul(&a));
+        }
+
+        // a plain product with an element of Fp, not with it times (u + 1)
+        let a = gen_a();
+        let k = Integer::from(12);
+        assert_eq!(a.mul_base(&k), a.mul(&Fp2::from_base(&k)));
+        assert_eq!(a.mul_base(&k), a.mul_by_base(&k));
+
+        // the results are reduced whatever the representatives
+        let p = BLS12_381_BASE.modulus_ref();
+        let shifted = Fp2::from_integers(Integer::from(&a.c0 + p), Integer::from(&a.c1 - p));
+        assert_eq!(shifted.mul(&gen_b()), a.mul(&gen_b()));
+        assert_eq!(shifted.square(), a.square());
+    }
 }
@@ -1,24 +1,83 @@
 use std::fmt::{self, Display};
 
-use super::fp2::Fp2;
-
-lazy_static::lazy_static! {
-    // Fp2::NONRESIDUE^(((q^1) - 1) / 3)
-    pub static ref FROBENIUS_COEFF_FP6_C1: Fp2 =Fp2::from_strs(
-        "0",
-        "4002409555221667392624310435006688643935503118305586438271171395842971157480381377015405980053539358417135540939436"
-    );
-    // Fq2(u + 1)**(((2q^1) - 2) / 3)
-    pub static ref FROBENIUS_COEFF_FP6_C2: Fp2 = Fp2::from_strs(
-        "4002409555221667392624310435006688643935503118305586438271171395842971157480381377015405980053539358417135540939437",
-        "0"
-    );
-
-}
+use rug::{ops::Pow, Integer};
+
+use crate::{
+    constant::{ensure, Constant},
+    curves::bls12_381::BLS12_381_BASE,
+    fp,
+};
+
+use super::fp2::{checked_nonresidue_power, frobenius_coeffs, Fp2, FP2_BYTES};
+
+/// Size of a serialized Fp6 element: c2 || c1 || c0, each an Fp2 encoding.
+pub const FP6_BYTES: usize = 3 * FP2_BYTES;
+
+// Fp2::NONRESIDUE^(((q^k) - 1) / 3) for k = 0..12
+pub static FROBENIUS_COEFF_FP6_C1: Constant<[Fp2; 12]> = Constant::new(
+    "FROBENIUS_COEFF_FP6_C1",
+    || {
+        let coeff = checked_nonresidue_power(
+        "FROBENIUS_COEFF_FP6_C1",
+        Fp2::from_u64_vec(
+            &fp!("0x0"),
+            &fp!("0x1a0111ea397fe699ec02408663d4de85aa0d857d89759ad4897d29650fb85f9b409427eb4f49fffd8bfd00000000aaac"),
+        ),
+        &((BLS12_381_BASE.modulus() - 1u32) / 3u32),
+        false,
+    )?;
+        frobenius_coeffs("FROBENIUS_COEFF_FP6_C1", coeff)
+    },
+);
+// Fq2(u + 1)**(((2q^k) - 2) / 3) for k = 0..12
+pub static FROBENIUS_COEFF_FP6_C2: Constant<[Fp2; 12]> = Constant::new(
+    "FROBENIUS_COEFF_FP6_C2",
+    || {
+        let coeff = checked_nonresidue_power(
+        "FROBENIUS_COEFF_FP6_C2",
+        Fp2::from_u64_vec(
+            &fp!("0x1a0111ea397fe699ec02408663d4de85aa0d857d89759ad4897d29650fb85f9b409427eb4f49fffd8bfd00000000aaad"),
+            &fp!("0x0"),
+        ),
+        &((BLS12_381_BASE.modulus() * 2u32 - 2u32) / 3u32),
+        false,
+    )?;
+        frobenius_coeffs("FROBENIUS_COEFF_FP6_C2", coeff)
+    },
+);
+
+/// Two-adicity of p^6 - 1: p = 3 mod 4 and p + 1 = 4 mod 8.
+const FP6_TWO_ADICITY: u32 = 3;
+
+/// The odd t with p^6 - 1 = 2^3 * t.
+pub static FP6_SQRT_T: Constant<Integer> = Constant::new("FP6_SQRT_T", || {
+    let order = Integer::from(BLS12_381_BASE.modulus_ref().pow(6u32)) - 1u32;
+    let t = Integer::from(&order >> FP6_TWO_ADICITY);
+    ensure(
+        "FP6_SQRT_T",
+        t.is_odd() && Integer::from(&t << FP6_TWO_ADICITY) == order,
+        "p^6 - 1 is not 2^3 times an odd number",
+    )?;
+    Ok(t)
+});
+
+/// v^t, a primitive 2^3-th root of unity. It exists because v is not a square
+/// in Fp6, which is why w^2 = v defines Fp12.
+pub static FP6_ROOT_OF_UNITY: Constant<Fp6> = Constant::new("FP6_ROOT_OF_UNITY", || {
+    let v = Fp6::new(Fp2::zero(), Fp2::one(), Fp2::zero());
+    let root = v.pow(FP6_SQRT_T.try_get()?);
+    let half_order = (0..FP6_TWO_ADICITY - 1).fold(root.clone(), |x, _| x.square());
+    ensure(
+        "FP6_ROOT_OF_UNITY",
+        half_order == Fp6::one().neg(),
+        "v^t does not have order 2^3",
+    )?;
+    Ok(root)
+});
 
 #[test]
 fn test_c() {
-    println!("C1: {}", FROBENIUS_COEFF_FP6_C1.clone());
+    println!("C1: {}", FROBENIUS_COEFF_FP6_C1[1]);
 }
 
 /// Represents an element of Fp6 as c0 + c1 * v + c2 * v^2
@@ -56,6 +115,29 @@ impl Fp6 {
         Fp6 { c0, c1, c2 }
     }
 
+    /// Canonical encoding c2 || c1 || c0, most significant first.
+    pub fn to_bytes(&self) -> [u8; FP6_BYTES] {
+        let mut bytes = [0u8; FP6_BYTES];
+        for (chunk, coeff) in bytes
+            .chunks_mut(FP2_BYTES)
+            .zip([&self.c2, &self.c1, &self.c0])
+        {
+            chunk.copy_from_slice(&coeff.to_bytes());
+        }
+        bytes
+    }
+
+    /// Inverse of `to_bytes`, returns None for non-canonical encodings.
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() != FP6_BYTES {
+            return None;
+        }
+
+        let mut coeffs = bytes.chunks(FP2_BYTES).map(Fp2::from_bytes);
+        let (c2, c1, c0) = (coeffs.next()??, coeffs.next()??, coeffs.next()??);
+        Some(Self::new(c0, c1, c2))
+    }
+
     /// Returns the zero element of Fp6.
     pub fn zero() -> Self {
         Fp6 {
@@ -121,12 +203,12 @@ impl Fp6 {
 
     /// opt multiple without c2
     pub fn mul_by_c0_c1(&self, c0: &Fp2, c1: &Fp2) -> Self {
-        let a_a = self.c0.mul(&c0);
-        let b_b = self.c1.mul(&c1);
+        let a_a = self.c0.mul(c0);
+        let b_b = self.c1.mul(c1);
 
-        let t1 = self.c2.mul(&c1).mul_by_nonresidue().add(&a_a);
-        let t2 = c0.add(&c1).mul(&self.c0.add(&self.c1)).sub(&a_a).sub(&b_b);
-        let t3 = self.c2.mul(&c0).add(&b_b);
+        let t1 = self.c2.mul(c1).mul_by_nonresidue().add(&a_a);
+        let t2 = c0.add(c1).mul(&self.c0.add(&self.c1)).sub(&a_a).sub(&b_b);
+        let t3 = self.c2.mul(c0).add(&b_b);
 
         Fp6 {
             c0: t1,
@@ -138,9 +220,18 @@ impl Fp6 {
     /// opt multiple with c1
     pub fn mul_by_c1(&self, c1: &Fp2) -> Self {
         Fp6 {
-            c0: self.c2.mul(&c1).mul_by_nonresidue(),
-            c1: self.c0.mul(&c1),
-            c2: self.c1.mul(&c1),
+            c0: self.c2.mul(c1).mul_by_nonresidue(),
+            c1: self.c0.mul(c1),
+            c2: self.c1.mul(c1),
+        }
+    }
+
+    /// opt multiple with an Fp2 scalar
+    pub fn mul_by_fp2(&self, scalar: &Fp2) -> Self {
+        Fp6 {
+            c0: self.c0.mul(scalar),
+            c1: self.c1.mul(scalar),
+            c2: self.c2.mul(scalar),
         }
     }
 
@@ -215,39 +306,111 @@ impl Fp6 {
             let c0 = self.c0.square().sub(&c0);
 
             let c1 = self.c2.square().mul_by_nonresidue();
-            let c1 = c1.sub(&(&self.c0.mul(&self.c1)));
+            let c1 = c1.sub(&self.c0.mul(&self.c1));
 
             let c2 = self.c1.square();
-            let c2 = c2.sub(&(&self.c0.mul(&self.c2)));
+            let c2 = c2.sub(&self.c0.mul(&self.c2));
 
             let tmp = ((self.c1.mul(&c2)).add(&(self.c2.mul(&c1)))).mul_by_nonresidue();
             let tmp = tmp.add(&self.c0.mul(&c0));
 
-            match tmp.invert() {
-                Some(t) => Some(Fp6 {
-                    c0: t.mul(&c0),
-                    c1: t.mul(&c1),
-                    c2: t.mul(&c2),
-                }),
-                None => None,
-            }
+            tmp.invert().map(|t| Fp6 {
+                c0: t.mul(&c0),
+                c1: t.mul(&c1),
+                c2: t.mul(&c2),
+            })
         }
     }
 
-    /// Raises this element to p.
-    pub fn frobenius_map(&self) -> Self {
-        let c0 = self.c0.frobenius_map();
-        let c1 = self.c1.frobenius_map();
-        let c2 = self.c2.frobenius_map();
+    /// Raises this element to p^power with one multiplication per
+    /// coefficient, whatever the power.
+    pub fn frobenius_map(&self, power: usize) -> Self {
+        let c0 = self.c0.frobenius_map(power);
+        let c1 = self.c1.frobenius_map(power);
+        let c2 = self.c2.frobenius_map(power);
 
-        // c1 = c1 * (u + 1)^((p - 1) / 3)
-        let c1 = c1.mul(&FROBENIUS_COEFF_FP6_C1);
+        // c1 = c1 * (u + 1)^((p^power - 1) / 3)
+        let c1 = c1.mul(&FROBENIUS_COEFF_FP6_C1[power % 12]);
 
-        // c2 = c2 * (u + 1)^((2p - 2) / 3)
-        let c2 = c2.mul(&FROBENIUS_COEFF_FP6_C2);
+        // c2 = c2 * (u + 1)^((2p^power - 2) / 3)
+        let c2 = c2.mul(&FROBENIUS_COEFF_FP6_C2[power % 12]);
 
         Fp6 { c0, c1, c2 }
     }
+
+    /// Exponentiation by a non-negative power (variable time)
+    pub fn pow(&self, exponent: &Integer) -> Self {
+        let mut result = Self::one();
+        for i in (0..exponent.significant_bits()).rev() {
+            result = result.square();
+            if exponent.get_bit(i) {
+                result = result.mul(self);
+            }
+        }
+        result
+    }
+
+    /// The norm to Fp2, self * self^(p^2) * self^(p^4) =
+    /// c0^3 + ξ c1^3 + ξ^2 c2^3 - 3ξ c0 c1 c2.
+    pub fn norm(&self) -> Fp2 {
+        let t0 = self
+            .c0
+            .square()
+            .sub(&self.c1.mul(&self.c2).mul_by_nonresidue());
+        let t1 = self
+            .c2
+            .square()
+            .mul_by_nonresidue()
+            .sub(&self.c0.mul(&self.c1));
+        let t2 = self.c1.square().sub(&self.c0.mul(&self.c2));
+
+        self.c0
+            .mul(&t0)
+            .add(&self.c1.mul(&t2).add(&self.c2.mul(&t1)).mul_by_nonresidue())
+    }
+
+    /// Returns true if this element is a square in Fp6, zero included, which
+    /// is the case exactly when its norm is a square in Fp2.
+    pub fn is_square(&self) -> bool {
+        self.norm().is_square()
+    }
+
+    /// Attempts to compute the square root of this element in Fp6 with
+    /// Tonelli-Shanks, p^6 - 1 having only three factors of two. Returns None
+    /// if the element is not a square.
+    pub fn sqrt(&self) -> Option<Self> {
+        if self.is_zero() {
+            return Some(Self::zero());
+        }
+
+        // x = self^((t + 1) / 2), b = self^t
+        let y = self.pow(&Integer::from(&*FP6_SQRT_T >> 1));
+        let mut x = y.mul(self);
+        let mut b = x.mul(&y);
+        let mut c = FP6_ROOT_OF_UNITY.clone();
+        let mut m = FP6_TWO_ADICITY;
+
+        // x^2 = self * b holds throughout, b's order drops every round
+        while !b.is_one() {
+            let mut i = 0;
+            let mut b2 = b.clone();
+            while !b2.is_one() {
+                b2 = b2.square();
+                i += 1;
+                if i == m {
+                    return None;
+                }
+            }
+
+            let g = (0..m - i - 1).fold(c, |g, _| g.square());
+            x = x.mul(&g);
+            c = g.square();
+            b = b.mul(&c);
+            m = i;
+        }
+
+        Some(x)
+    }
 }
 
 #[cfg(test)]
@@ -459,4 +622,47 @@ mod tests {
 
         println!("T: {}", t);
     }
+
+    #[test]
+    fn test_pow_sqrt() {
+        use super::{FP6_ROOT_OF_UNITY, FP6_SQRT_T};
+        use crate::curves::bls12_381::BLS12_381_BASE;
+
+        assert!(FP6_SQRT_T.try_get().is_ok());
+        assert!(FP6_ROOT_OF_UNITY.try_get().is_ok());
+
+        let mut rng = zkper_rand::TestRng::new();
+        let a = Fp6::random(&mut rng);
+        let b = Fp6::random(&mut rng);
+
+        assert_eq!(a.pow(&Integer::ZERO), Fp6::one());
+        assert_eq!(a.pow(&Integer::from(3)), a.square().mul(&a));
+        assert_eq!(
+            a.pow(&Integer::from(1000)).mul(&a.pow(&Integer::from(234))),
+            a.pow(&Integer::from(1234))
+        );
+        assert_eq!(a.pow(BLS12_381_BASE.modulus_ref()), a.frobenius_map(1));
+
+        // the norm is multiplicative and lies in Fp2
+        assert_eq!(a.mul(&b).norm(), a.norm().mul(&b.norm()));
+        let frobenius2 = |x: &Fp6| x.frobenius_map(2);
+        assert_eq!(
+            Fp6::from(a.norm()),
+            a.mul(&frobenius2(&a)).mul(&frobenius2(&frobenius2(&a)))
+        );
+
+        let mut non_squares = 0;
+        for _ in 0..8 {
+            let x = Fp6::random(&mut rng);
+            let root = x.square().sqrt().unwrap();
+            assert!(root == x || root == x.neg());
+            assert_eq!(x.is_square(), x.sqrt().is_some());
+            non_squares += !x.is_square() as usize;
+        }
+        assert!(non_squares > 0);
+        assert_eq!(Fp6::zero().sqrt(), Some(Fp6::zero()));
+
+        // v is not a square, so Fp12 = Fp6[w] / (w^2 - v) is a field
+        assert!(!Fp6::new(Fp2::zero(), Fp2::one(), Fp2::zero()).is_square());
+    }
 }
@@ -1,10 +1,28 @@
 use super::*;
-use crate::{backends::montgomery::INTEGER_SEVEN, curves::bls12_381::BLS12_381_SCALAR};
-use std::str::FromStr;
+use crate::{
+    backends::{limbs::to_integer, montgomery::INTEGER_SEVEN},
+    constant::{ensure, Constant},
+    curves::bls12_381::BLS12_381_SCALAR,
+    fr,
+};
+use zkper_base::redact::Redacted;
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+/// Size of a scalar field element in bytes.
+pub const SCALAR_FIELD_BYTES: usize = 32;
+
+#[derive(Clone, PartialEq, Eq)]
 pub struct Bls12_381ScalarField(pub Integer);
 
+/// Scalars are as often secret as not, so the value is only shown with the
+/// `debug-secrets` feature. `Display` prints it regardless.
+impl std::fmt::Debug for Bls12_381ScalarField {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("Bls12_381ScalarField")
+            .field(&Redacted(&self.0))
+            .finish()
+    }
+}
+
 impl Bls12_381ScalarField {
     pub fn from_raw(val: [u64; 4]) -> Self {
         Self(Integer::from_digits(&val, rug::integer::Order::Lsf))
@@ -15,11 +33,11 @@ impl Bls12_381ScalarField {
     }
 
     pub fn square(&self) -> Self {
-        BLS12_381_SCALAR.square(self.0.clone()).into()
+        Self(BLS12_381_SCALAR.square(self.0.clone()))
     }
 
     pub fn mul(&self, other: &Self) -> Self {
-        BLS12_381_SCALAR.mul(self.0.clone(), &other.0).into()
+        Self(BLS12_381_SCALAR.mul(self.0.clone(), &other.0))
     }
 
     pub fn mul_assign(&mut self, other: &Self) {
@@ -27,38 +45,123 @@ impl Bls12_381ScalarField {
     }
 
     pub fn add(&self, other: &Self) -> Self {
-        BLS12_381_SCALAR.add(self.0.clone(), &other.0).into()
+        Self(BLS12_381_SCALAR.add(self.0.clone(), &other.0))
     }
 
     pub fn add_assign(&mut self, other: &Self) {
         self.0 = BLS12_381_SCALAR.add(self.0.clone(), &other.0);
     }
+
+    /// Canonical big-endian encoding of the value reduced mod r.
+    pub fn to_bytes(&self) -> [u8; SCALAR_FIELD_BYTES] {
+        let digits = BLS12_381_SCALAR
+            .reduce(&self.0)
+            .to_digits::<u8>(rug::integer::Order::Msf);
+        let mut bytes = [0u8; SCALAR_FIELD_BYTES];
+        bytes[SCALAR_FIELD_BYTES - digits.len()..].copy_from_slice(&digits);
+        bytes
+    }
+
+    /// Inverse of `to_bytes`, returns None for encodings of values >= r.
+    pub fn from_bytes(bytes: &[u8; SCALAR_FIELD_BYTES]) -> Option<Self> {
+        let value = Integer::from_digits(bytes, rug::integer::Order::Msf);
+        (&value < BLS12_381_SCALAR.modulus_ref()).then_some(Self(value))
+    }
 }
 
-lazy_static::lazy_static! {
-    /// 2^s root of unity computed by GENERATOR^t
-    static ref TWO_ADIC_ROOT_OF_UNITY: Integer = Integer::from_str(
-        "10238227357739495823651030575849232062558860180284477541189508159991286009131",
-    )
-    .unwrap();
+/// Overwrites every allocated limb with ones, then sets the value to 0. The
+/// mask is exactly as wide as the allocation, so GMP copies it in place
+/// rather than reallocating, and the secret limbs are not left behind.
+#[cfg(feature = "zeroize")]
+impl zeroize::Zeroize for Bls12_381ScalarField {
+    fn zeroize(&mut self) {
+        use rug::Assign;
+
+        let mask = (Integer::from(1) << self.0.capacity() as u32) - 1u32;
+        self.0.assign(&mask);
+        self.0.assign(0);
+    }
 }
 
+#[cfg(feature = "zeroize")]
+impl Drop for Bls12_381ScalarField {
+    fn drop(&mut self) {
+        zeroize::Zeroize::zeroize(self);
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl zeroize::ZeroizeOnDrop for Bls12_381ScalarField {}
+
+/// 2^s root of unity computed by GENERATOR^t, checked to have order exactly 2^s
+pub(crate) static TWO_ADIC_ROOT_OF_UNITY: Constant<Integer> =
+    Constant::new("TWO_ADIC_ROOT_OF_UNITY", || {
+        let root = to_integer(&fr!(
+            "0x16a2a19edfe81f20d09b681922c813b4b63683508c2280b93829971f439f0d2b"
+        ));
+
+        let modulus = BLS12_381_SCALAR.modulus_ref();
+        let half_order = Integer::from(1) << (Bls12_381ScalarField::TWO_ADICITY - 1);
+        let minus_one = Integer::from(modulus - 1u32);
+        ensure(
+            "TWO_ADIC_ROOT_OF_UNITY",
+            root.clone().pow_mod(&half_order, modulus).ok() == Some(minus_one),
+            "root does not have order 2^TWO_ADICITY",
+        )?;
+
+        Ok(root)
+    });
+
+/// Primitive cube root of unity GENERATOR^((r - 1) / 3), checked to have order exactly 3
+pub(crate) static CUBE_ROOT_OF_UNITY: Constant<Integer> =
+    Constant::new("CUBE_ROOT_OF_UNITY", || {
+        let modulus = BLS12_381_SCALAR.modulus_ref();
+        let order = Integer::from(modulus - 1u32);
+        let (cofactor, rem) = order.div_rem(Integer::from(3));
+        ensure("CUBE_ROOT_OF_UNITY", rem == 0, "3 does not divide r - 1")?;
+        ensure(
+            "CUBE_ROOT_OF_UNITY",
+            !cofactor.is_divisible_u(3),
+            "THREE_ADICITY is not 1",
+        )?;
+
+        let root = BLS12_381_SCALAR.pow(
+            Bls12_381ScalarField::MULTIPLICATIVE_GENERATOR.clone(),
+            &cofactor,
+        );
+        ensure(
+            "CUBE_ROOT_OF_UNITY",
+            root != 1 && BLS12_381_SCALAR.pow(root.clone(), &Integer::from(3)) == 1,
+            "root does not have order 3",
+        )?;
+
+        Ok(root)
+    });
+
 impl Bls12_381ScalarField {
     /// Let `N` be the size of the multiplicative group defined by the field.
     /// Then `TWO_ADICITY` is the two-adicity of `N`, i.e. the integer `s`
     /// such that `N = 2^s * t` for some odd integer `t`.
     pub const TWO_ADICITY: u32 = 32;
 
+    /// The largest `m` such that `3^m` divides `N`.
+    pub const THREE_ADICITY: u32 = 1;
+
     pub const MULTIPLICATIVE_GENERATOR: &'static Integer = INTEGER_SEVEN;
 
     pub fn two_adic_root_of_unity() -> Integer {
         TWO_ADIC_ROOT_OF_UNITY.clone()
     }
+
+    pub fn cube_root_of_unity() -> Integer {
+        CUBE_ROOT_OF_UNITY.clone()
+    }
 }
 
+/// Reduces `value` mod r, so every `Scalar` built from an `Integer` is in [0, r).
 impl From<Integer> for Bls12_381ScalarField {
     fn from(value: Integer) -> Self {
-        Self(value)
+        Self(BLS12_381_SCALAR.new_element(value))
     }
 }
 
@@ -76,13 +179,13 @@ impl FieldTrait for Bls12_381ScalarField {
         BLS12_381_SCALAR.sample_mont(rng)
     }
     fn modulus<'a>() -> &'a Integer {
-        &&BLS12_381_SCALAR.modulus_ref()
+        BLS12_381_SCALAR.modulus_ref()
     }
     fn r<'a>() -> &'a Integer {
-        &BLS12_381_SCALAR.r_ref()
+        BLS12_381_SCALAR.r_ref()
     }
     fn r2<'a>() -> &'a Integer {
-        &BLS12_381_SCALAR.r2_ref()
+        BLS12_381_SCALAR.r2_ref()
     }
     fn limbs() -> usize {
         BLS12_381_SCALAR.limbs()
@@ -120,12 +223,16 @@ impl Distribution<Bls12_381ScalarField> for Standard {
     }
 }
 
-#[cfg(test)]
+#[cfg(all(test, not(feature = "debug-secrets")))]
 mod tests {
-    use rug::Integer;
-    use zkper_base::math::factorization_opt::find_generator;
+    use super::*;
 
-    use crate::{curves::bls12_381::Bls12_381ScalarField, traits::field::FieldTrait};
+    #[test]
+    fn test_debug_is_redacted() {
+        let secret = Bls12_381ScalarField::from(Integer::from(0x1234_5678_9abc_u64));
+        assert_eq!(format!("{:?}", secret), "Bls12_381ScalarField(<redacted>)");
+        assert_eq!(format!("{}", secret), "123456789abc");
+    }
 
     // #[test]
     // fn test_two_adic_root_of_unity() {
@@ -1,13 +1,47 @@
 use std::fmt::{self, Display};
 
-use rug::Integer;
+use rand::RngCore;
 
-use super::fp12::Fp12;
+use super::fp12::{Fp12, FP12_BYTES};
+use crate::{
+    constant::{ensure, Constant},
+    curves::bls12_381::{
+        curves::{g1_affine::G1Affine, g2_affine::G2Affine},
+        paring::BLS12_381Pairing,
+        Bls12_381ScalarField, Scalar, BLS12_381_SCALAR,
+    },
+    traits::field::FieldTrait,
+};
+
+/// Size of a serialized Gt element: the twelve Fp coefficients, most
+/// significant first, each big-endian.
+pub const GT_BYTES: usize = FP12_BYTES;
+
+/// e(G1, G2) for the fixed generators, a generator of Gt.
+static GT_GENERATOR: Constant<TargetField> = Constant::new("GT_GENERATOR", || {
+    let generator = BLS12_381Pairing::pairing(&G1Affine::generator(), &G2Affine::generator());
+    ensure(
+        "GT_GENERATOR",
+        !generator.is_identity(),
+        "pairing of the generators is degenerate",
+    )?;
+    Ok(generator)
+});
 
 /// Represents an element of the target group of the pairing.
+///
+/// Gt is written additively to match G1 and G2: `add` is the Fp12 product,
+/// `neg` the inverse and `mul_scalar` exponentiation. `mul`, `inverse` and
+/// `pow` are the multiplicative spellings of the same operations.
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct TargetField(pub Fp12);
 
+impl Default for TargetField {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
 impl Display for TargetField {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{}", self.0)
@@ -15,6 +49,28 @@ impl Display for TargetField {
 }
 
 impl TargetField {
+    /// Wraps an Fp12 element, returning `None` unless it lies in the order r
+    /// subgroup that pairings map into.
+    pub fn from_fp12(value: Fp12) -> Option<Self> {
+        let element = TargetField(value);
+        element.is_in_subgroup().then_some(element)
+    }
+
+    /// Returns the fixed generator e(G1, G2).
+    pub fn generator() -> Self {
+        GT_GENERATOR.clone()
+    }
+
+    /// Returns a uniformly random element other than the identity.
+    pub fn random<R: RngCore>(rng: &mut R) -> Self {
+        loop {
+            let scalar = Bls12_381ScalarField::random(rng);
+            if !scalar.is_zero() {
+                return Self::generator().pow(&Scalar::from(scalar));
+            }
+        }
+    }
+
     /// Returns the multiplicative identity element of TargetField.
     pub fn one() -> Self {
         TargetField(Fp12::one())
@@ -37,9 +93,9 @@ impl TargetField {
         self.0.is_zero()
     }
 
-    /// Raises this element to p.
-    pub fn frobenius_map(&self) -> Self {
-        TargetField(self.0.frobenius_map())
+    /// Raises this element to p^power.
+    pub fn frobenius_map(&self, power: usize) -> Self {
+        TargetField(self.0.frobenius_map(power))
     }
 
     /// Computes the square of this element.
@@ -52,40 +108,170 @@ impl TargetField {
         TargetField(self.0.square())
     }
 
-    /// Neg
+    /// Inverse in Gt, which is the conjugate since Gt lies in the cyclotomic
+    /// subgroup.
     pub fn neg(&self) -> Self {
         TargetField(self.0.conjugate())
     }
 
-    /// Add
+    /// Multiplicative spelling of `neg`.
+    pub fn inverse(&self) -> Self {
+        self.neg()
+    }
+
+    /// Group operation, the Fp12 product.
     pub fn add(&self, other: &Self) -> Self {
         TargetField(self.0.mul(&other.0))
     }
 
+    /// Multiplicative spelling of `add`.
+    pub fn mul(&self, other: &Self) -> Self {
+        self.add(other)
+    }
+
     /// Sub
     pub fn sub(&self, other: &Self) -> Self {
         self.add(&other.neg())
     }
 
-    /// Mul
-    /// Scalar multiplication of this element.
-    pub fn mul_scalar(&self, scalar: &Integer) -> Self {
-        if scalar.is_zero() {
-            return Self::identity();
+    /// Scalar multiplication of this element, i.e. exponentiation.
+    ///
+    /// The scalar is reduced mod r, so negative scalars work as expected, and
+    /// squarings use the cheaper cyclotomic squaring.
+    pub fn mul_scalar(&self, scalar: &Scalar) -> Self {
+        let scalar = BLS12_381_SCALAR.reduce(&scalar.0);
+        let mut result = Fp12::one();
+        for i in (0..scalar.significant_bits()).rev() {
+            result = result.cyclotomic_square();
+            if scalar.get_bit(i) {
+                result = result.mul(&self.0);
+            }
         }
 
-        let mut result = Self::identity();
-        let mut temp = self.clone();
-        let mut scalar_bits = scalar.clone();
+        TargetField(result)
+    }
 
-        while !scalar_bits.is_zero() {
-            if scalar_bits.is_odd() {
-                result = result.add(&temp);
-            }
-            temp = temp.double();
-            scalar_bits >>= 1;
-        }
+    /// Raises this element to `exp`, the multiplicative spelling of `mul_scalar`.
+    pub fn pow(&self, exp: &Scalar) -> Self {
+        self.mul_scalar(exp)
+    }
+
+    /// Checks that the element is non-zero and of order dividing r, i.e. that it
+    /// can be the result of a pairing.
+    ///
+    /// Unlike `pow`, this makes no assumption about the element, so it uses
+    /// generic Fp12 exponentiation and the unreduced exponent.
+    pub fn is_in_subgroup(&self) -> bool {
+        !self.is_zero() && self.0.pow(BLS12_381_SCALAR.modulus_ref()).is_one()
+    }
+
+    /// Serialize the twelve coefficients, most significant first:
+    /// c1.c2.c1 || c1.c2.c0 || c1.c1.c1 || ... || c0.c0.c0, each big-endian.
+    pub fn to_bytes(&self) -> [u8; GT_BYTES] {
+        self.0.to_bytes()
+    }
+
+    /// Inverse of `to_bytes`. Returns None for non-canonical encodings and
+    /// elements outside Gt.
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        Self::from_fp12(Fp12::from_bytes(bytes)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rug::Integer;
+    use zkper_rand::ZkperRng;
+
+    #[test]
+    fn test_group_operations() {
+        let mut rng = ZkperRng::new_test();
+        let g = TargetField::generator();
+        let a = TargetField::random(&mut rng);
+        let b = TargetField::random(&mut rng);
+
+        assert_eq!(a.mul(&TargetField::identity()), a);
+        assert_eq!(a.mul(&a.inverse()), TargetField::identity());
+        assert_eq!(a.mul(&b), b.mul(&a));
+        assert_eq!(a.sub(&a), TargetField::default());
+        assert_ne!(a, b);
+
+        // exponentiation agrees with repeated multiplication and is reduced mod r
+        let five = Integer::from(5);
+        let expected = (0..5).fold(TargetField::one(), |acc, _| acc.mul(&g));
+        assert_eq!(g.pow(&Scalar::from(five.clone())), expected);
+        assert_eq!(
+            g.pow(&Bls12_381ScalarField(
+                five.clone() + BLS12_381_SCALAR.modulus_ref()
+            )),
+            expected
+        );
+        assert!(g
+            .pow(&Bls12_381ScalarField(BLS12_381_SCALAR.modulus()))
+            .is_identity());
+
+        // Scalar::from reduces into [0, r)
+        assert_eq!(
+            Scalar::from(five.clone() + BLS12_381_SCALAR.modulus_ref()).0,
+            five
+        );
+        assert_eq!(
+            Scalar::from(Integer::from(-5)).0,
+            BLS12_381_SCALAR.modulus() - 5u32
+        );
+        assert_eq!(g.pow(&Scalar::from(Integer::from(-5))), expected.inverse());
+
+        // e(aP, Q) == e(P, Q)^a
+        let k = Scalar::from(Integer::from(0x1234_5678_u64));
+        let p = crate::curves::bls12_381::curves::g1::G1Projective::generator()
+            .mul_scalar(&k)
+            .to_affine();
+        assert_eq!(
+            BLS12_381Pairing::pairing(&p, &G2Affine::generator()),
+            g.pow(&k)
+        );
+    }
+
+    #[test]
+    fn test_bytes_round_trip() {
+        let mut rng = ZkperRng::new_test();
+        let a = TargetField::random(&mut rng);
+
+        let bytes = a.to_bytes();
+        assert_eq!(TargetField::from_bytes(&bytes), Some(a.clone()));
+        assert_eq!(
+            TargetField::from_bytes(&TargetField::one().to_bytes()),
+            Some(TargetField::one())
+        );
+        // c0.c0.c0 is the last coefficient
+        assert_eq!(TargetField::one().to_bytes()[GT_BYTES - 1], 1);
+
+        assert_eq!(TargetField::from_bytes(&bytes[1..]), None);
+        // not reduced
+        let mut unreduced = bytes;
+        unreduced[0] = 0xff;
+        assert_eq!(TargetField::from_bytes(&unreduced), None);
+        // outside Gt
+        let mut outside = bytes;
+        outside[GT_BYTES - 1] ^= 1;
+        assert_eq!(TargetField::from_bytes(&outside), None);
+    }
+
+    #[test]
+    fn test_subgroup() {
+        let gt = BLS12_381Pairing::pairing(&G1Affine::generator(), &G2Affine::generator());
+        assert!(gt.is_in_subgroup());
+        assert!(TargetField::one().is_in_subgroup());
+        assert!(!TargetField::zero().is_in_subgroup());
+        assert_eq!(TargetField::from_fp12(gt.0.clone()), Some(gt.clone()));
+
+        // a random Fp12 element is almost never in the r-torsion
+        let mut rng = ZkperRng::new_test();
+        assert_eq!(TargetField::from_fp12(Fp12::random(&mut rng)), None);
 
-        result
+        let seven = Scalar::from(Integer::from(7));
+        assert_eq!(gt.pow(&seven), gt.mul_scalar(&seven));
+        assert_eq!(gt.pow(&Scalar::from(Integer::ZERO)), TargetField::one());
     }
 }
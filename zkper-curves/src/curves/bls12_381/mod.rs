@@ -1,24 +1,166 @@
-use crate::backends::montgomery::MontgomeryBackend;
+use rug::{integer::IsPrime, Integer};
+
+use crate::{
+    backends::{
+        limbs::{from_hex, to_integer},
+        montgomery::MontgomeryBackend,
+    },
+    constant::{ensure, Constant, ConstantError},
+    curves::tower::{validate_tower, TowerConstants},
+};
 
 pub mod curves;
 pub mod fields;
+pub mod params;
 pub mod paring;
 
 pub use fields::base::Bls12_381BaseField;
 pub use fields::scalar::Bls12_381ScalarField;
 
+/// An element of Fr, the exponent of G1, G2 and Gt. Curve APIs take this
+/// rather than a bare `Integer` so a base field value cannot be passed by
+/// mistake.
+pub type Scalar = Bls12_381ScalarField;
+
+/// An element of Fp, a coordinate of a G1 point.
+pub type BaseFieldElement = Bls12_381BaseField;
+
 /// This is equal to the frobenius trace of the BLS12 381 curve minus one or seed value z.
 /// x = -15132376222941642752
 pub const MILLER_LOOP_CONSTANT: u64 = 0xd201_0000_0001_0000;
 pub const MILLER_LOOP_CONSTANT_IS_NEG: bool = true;
 
-lazy_static::lazy_static! {
-    pub static ref BLS12_381_SCALAR: MontgomeryBackend = MontgomeryBackend::from_str_radix(
-        "52435875175126190479447740508185965837690552500527637822603658699938581184513", 10, 4
-    );
-    pub static ref BLS12_381_BASE: MontgomeryBackend = MontgomeryBackend::from_str_radix(
-        "4002409555221667393417789825735904156556882819939007885332058136124031650490837864442687629129015664037894272559787", 10, 6
-    );
+/// r = 52435875175126190479447740508185965837690552500527637822603658699938581184513
+pub const SCALAR_MODULUS_LIMBS: [u64; 4] =
+    from_hex("0x73eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000001");
+
+/// p = 4002409555221667393417789825735904156556882819939007885332058136124031650490837864442687629129015664037894272559787
+pub const BASE_MODULUS_LIMBS: [u64; 6] = from_hex(
+    "0x1a0111ea397fe69a4b1ba7b6434bacd764774b84f38512bf6730d2a0f6b0f6241eabfffeb153ffffb9feffffffffaaab",
+);
+
+/// Limbs of a base field constant, parsed and checked to be below p at compile time.
+///
+/// ```
+/// use zkper_curves::fp;
+///
+/// const FOUR: [u64; 6] = fp!("0x4");
+/// assert_eq!(FOUR, [4, 0, 0, 0, 0, 0]);
+/// ```
+#[macro_export]
+macro_rules! fp {
+    ($hex:literal) => {
+        const {
+            $crate::backends::limbs::from_hex_below::<6>(
+                $hex,
+                &$crate::curves::bls12_381::BASE_MODULUS_LIMBS,
+            )
+        }
+    };
+}
+
+/// Limbs of a scalar field constant, parsed and checked to be below r at compile time.
+#[macro_export]
+macro_rules! fr {
+    ($hex:literal) => {
+        const {
+            $crate::backends::limbs::from_hex_below::<4>(
+                $hex,
+                &$crate::curves::bls12_381::SCALAR_MODULUS_LIMBS,
+            )
+        }
+    };
+}
+
+pub static BLS12_381_SCALAR: Constant<MontgomeryBackend> =
+    Constant::new("BLS12_381_SCALAR", || {
+        checked_backend("BLS12_381_SCALAR", &SCALAR_MODULUS_LIMBS)
+    });
+pub static BLS12_381_BASE: Constant<MontgomeryBackend> = Constant::new("BLS12_381_BASE", || {
+    checked_backend("BLS12_381_BASE", &BASE_MODULUS_LIMBS)
+});
+
+/// Builds a Montgomery backend over the limbs and checks that its parameters
+/// are consistent: the modulus is prime, R * R^-1 = 1 and R^2 = R * R.
+fn checked_backend(name: &'static str, limbs: &[u64]) -> Result<MontgomeryBackend, ConstantError> {
+    let modulus = to_integer(limbs);
+    ensure(
+        name,
+        modulus.is_probably_prime(30) != IsPrime::No,
+        "modulus is not prime",
+    )?;
+
+    let backend = MontgomeryBackend::new(modulus, limbs.len() as u64);
+    let modulus = backend.modulus_ref();
+    ensure(
+        name,
+        Integer::from(backend.r_ref() * backend.r_inv_ref()) % modulus == 1,
+        "R * R^-1 != 1",
+    )?;
+    ensure(
+        name,
+        Integer::from(backend.r_ref().square_ref()) % modulus == *backend.r2_ref(),
+        "R^2 does not match R",
+    )?;
+
+    Ok(backend)
+}
+
+/// Forces every BLS12-381 constant and returns the first one failing its checks.
+///
+/// The constants validate themselves on first use anyway; call this at startup
+/// to fail early with a clear error instead.
+pub fn validate_constants() -> Result<(), ConstantError> {
+    use curves::{g1, g2};
+    use fields::{fp12, fp6, scalar};
+
+    BLS12_381_SCALAR.try_get()?;
+    BLS12_381_BASE.try_get()?;
+    scalar::TWO_ADIC_ROOT_OF_UNITY.try_get()?;
+    scalar::CUBE_ROOT_OF_UNITY.try_get()?;
+    g1::G1_GENERATOR_X.try_get()?;
+    g1::G1_GENERATOR_Y.try_get()?;
+    g2::G2_GENERATOR_X.try_get()?;
+    g2::G2_GENERATOR_Y.try_get()?;
+    g1::G1_SVDW.try_get()?;
+    g2::G2_SVDW.try_get()?;
+    for psi in [&g2::PSI_X, &g2::PSI_Y, &g2::PSI_2_X] {
+        psi.try_get()?;
+    }
+    for coeff in [
+        &fp6::FROBENIUS_COEFF_FP6_C1,
+        &fp6::FROBENIUS_COEFF_FP6_C2,
+        &fp12::FROBENIUS_COEFF_FP12_C1,
+    ] {
+        coeff.try_get()?;
+    }
+    params::FINAL_EXPONENT.try_get()?;
+    validate_tower(&tower_constants()?)?;
+
+    Ok(())
+}
+
+/// The field tower constants of BLS12-381: β = -1, ξ = u + 1 and the
+/// Frobenius coefficient tables used by Fp6 and Fp12.
+pub fn tower_constants() -> Result<TowerConstants, ConstantError> {
+    use fields::{fp12, fp2::Fp2, fp6};
+
+    let coeffs = |table: &[Fp2; 12]| {
+        table
+            .iter()
+            .map(|c| (c.c0.clone(), c.c1.clone()))
+            .collect::<Vec<_>>()
+    };
+
+    Ok(TowerConstants {
+        name: "BLS12-381",
+        modulus: BLS12_381_BASE.try_get()?.modulus(),
+        fp2_nonresidue: Integer::from(-1),
+        fp6_nonresidue: (Integer::from(1), Integer::from(1)),
+        frobenius_coeff_fp6_c1: coeffs(fp6::FROBENIUS_COEFF_FP6_C1.try_get()?),
+        frobenius_coeff_fp6_c2: coeffs(fp6::FROBENIUS_COEFF_FP6_C2.try_get()?),
+        frobenius_coeff_fp12_c1: coeffs(fp12::FROBENIUS_COEFF_FP12_C1.try_get()?),
+    })
 }
 
 #[cfg(test)]
@@ -27,7 +169,7 @@ mod tests {
     use rand::Rng;
     use rug::Integer;
     use tests::fields::scalar::Bls12_381ScalarField;
-    use zkper_rand::ZkperRng;
+    use zkper_rand::TestRng;
 
     #[test]
     fn test_sqrt_neg_square() {
@@ -51,23 +193,23 @@ mod tests {
 
     #[test]
     fn test_rng_gen() {
-        let mut rng = ZkperRng::new_test();
+        let mut rng = TestRng::new();
         let scalar: Bls12_381ScalarField = rng.gen();
         println!("scalar: {}", scalar.0.to_string_radix(16));
 
-        let mut rng = ZkperRng::new_test();
+        let mut rng = TestRng::new();
         let base: Bls12_381BaseField = rng.gen();
         println!("base: {}", base.0.to_string_radix(16));
     }
 
     #[test]
     fn test_sample() {
-        let mut rng = ZkperRng::new_test();
+        let mut rng = TestRng::new();
 
         let scalar = super::BLS12_381_SCALAR.sample_raw(&mut rng);
         println!("scalar: {}", scalar.to_string_radix(16));
 
-        let mut rng = ZkperRng::new_test();
+        let mut rng = TestRng::new();
 
         let base = super::BLS12_381_BASE.sample_raw(&mut rng);
         println!("base: {}", base.to_string_radix(16));
@@ -89,7 +231,7 @@ mod tests {
         println!("inv: {}", inv);
         println!("limbs: {}", limbs);
 
-        let mut rng = ZkperRng::new_test();
+        let mut rng = TestRng::new();
         let scalar = BLS12_381_SCALAR.sample_mont(&mut rng);
         println!("scalar: {}", scalar.to_string_radix(16));
         let scalar_from_mont = BLS12_381_SCALAR.from_montgomery(&scalar);
@@ -111,7 +253,7 @@ mod tests {
         println!("inv_base: {}", inv_base);
         println!("limbs_base: {}", limbs_base);
 
-        let mut rng = ZkperRng::new_test();
+        let mut rng = TestRng::new();
         let base = BLS12_381_BASE.sample_mont(&mut rng);
         println!("base: {}", base.to_string_radix(16));
         let base_from_mont = BLS12_381_BASE.from_montgomery(&base);
@@ -124,9 +266,57 @@ mod tests {
         let constant_is_neg = MILLER_LOOP_CONSTANT_IS_NEG;
 
         assert_eq!(constant, 15132376222941642752);
-        assert_eq!(constant_is_neg, true);
+        assert!(constant_is_neg);
 
         println!("constant: {}", constant);
         println!("constant_is_neg: {}", constant_is_neg);
     }
+
+    #[test]
+    fn test_limb_constants_match_decimal() {
+        use crate::curves::bls12_381::{
+            curves::{g1::G1_GENERATOR_X, g2::G2_GENERATOR_Y},
+            fields::fp12::FROBENIUS_COEFF_FP12_C1,
+        };
+
+        let dec = |s: &str| Integer::from_str_radix(s, 10).unwrap();
+        assert_eq!(
+            BLS12_381_SCALAR.modulus(),
+            dec("52435875175126190479447740508185965837690552500527637822603658699938581184513")
+        );
+        assert_eq!(
+            BLS12_381_BASE.modulus(),
+            dec("4002409555221667393417789825735904156556882819939007885332058136124031650490837864442687629129015664037894272559787")
+        );
+        assert_eq!(
+            G1_GENERATOR_X.0,
+            dec("3685416753713387016781088315183077757961620795782546409894578378688607592378376318836054947676345821548104185464507")
+        );
+        assert_eq!(
+            G2_GENERATOR_Y.c1,
+            dec("927553665492332455747201965776037880757740193453592970025027978793976877002675564980949289727957565575433344219582")
+        );
+        assert_eq!(
+            FROBENIUS_COEFF_FP12_C1[1].c1,
+            dec("151655185184498381465642749684540099398075398968325446656007613510403227271200139370504932015952886146304766135027")
+        );
+    }
+
+    #[test]
+    fn test_validate_constants() {
+        validate_constants().unwrap();
+
+        // a wrong hardcoded coefficient is reported by name
+        let err = fields::fp2::checked_nonresidue_power(
+            "PSI_X",
+            fields::fp2::Fp2::one(),
+            &((BLS12_381_BASE.modulus() - 1u32) / 3u32),
+            true,
+        )
+        .unwrap_err();
+        assert_eq!(err.name, "PSI_X");
+
+        let err = checked_backend("COMPOSITE", &[15]).err().unwrap();
+        assert_eq!(err.reason, "modulus is not prime");
+    }
 }
@@ -0,0 +1,184 @@
+//! Characteristic and embedding-degree facts for BLS12-381.
+//!
+//! Everything here is derived from the curve parameter x and the two moduli,
+//! so external verifiers and auditors can cross-check the final exponentiation
+//! and the subgroup checks against independently computed values.
+//!
+//! With x = -0xd201000000010000:
+//!
+//! * r = x^4 - x^2 + 1
+//! * p = (x - 1)^2 * r / 3 + x
+//! * (p^12 - 1) / r = (p^6 - 1) * (p^2 + 1) * (p^4 - p^2 + 1) / r
+//!
+//! The hard part of the final exponentiation is computed with the
+//! Hayashida–Hayasaka–Teruya decomposition
+//! `3 * (p^4 - p^2 + 1) / r = (x - 1)^2 * (x + p) * (x^2 + p^2 - 1) + 3`,
+//! so the pairing equals the Miller loop output raised to
+//! `3 * (p^12 - 1) / r`, see `pairing_exponent`.
+
+use rug::{ops::Pow, Integer};
+
+use crate::constant::{ensure, Constant};
+
+use super::{BLS12_381_BASE, BLS12_381_SCALAR, MILLER_LOOP_CONSTANT, MILLER_LOOP_CONSTANT_IS_NEG};
+
+/// Embedding degree of the curve.
+pub const EMBEDDING_DEGREE: u32 = 12;
+
+/// The signed curve parameter x.
+pub static X: Constant<Integer> = Constant::new("X", || {
+    Ok(if MILLER_LOOP_CONSTANT_IS_NEG {
+        -Integer::from(MILLER_LOOP_CONSTANT)
+    } else {
+        Integer::from(MILLER_LOOP_CONSTANT)
+    })
+});
+
+/// p mod r. Equal to x mod r, since the trace of Frobenius is x + 1.
+pub static P_MOD_R: Constant<Integer> = Constant::new("P_MOD_R", || Ok(Integer::from(p() % r())));
+
+/// p^2 mod r.
+pub static P2_MOD_R: Constant<Integer> =
+    Constant::new("P2_MOD_R", || Ok(Integer::from(p().square_ref()) % r()));
+
+/// Easy part of the final exponent: (p^6 - 1) * (p^2 + 1).
+pub static FINAL_EXPONENT_EASY: Constant<Integer> = Constant::new("FINAL_EXPONENT_EASY", || {
+    let p2 = Integer::from(p().square_ref());
+    let p6 = p().clone().pow(6u32);
+    Ok((p6 - 1u32) * (p2 + 1u32))
+});
+
+/// Hard part of the final exponent: (p^4 - p^2 + 1) / r.
+pub static FINAL_EXPONENT_HARD: Constant<Integer> = Constant::new("FINAL_EXPONENT_HARD", || {
+    let p2 = Integer::from(p().square_ref());
+    let p4 = Integer::from(p2.square_ref());
+    let cyclotomic = p4 - p2 + 1u32;
+    ensure(
+        "FINAL_EXPONENT_HARD",
+        cyclotomic.is_divisible(r()),
+        "r does not divide p^4 - p^2 + 1",
+    )?;
+    Ok(cyclotomic / r())
+});
+
+/// Full final exponent: (p^12 - 1) / r.
+pub static FINAL_EXPONENT: Constant<Integer> = Constant::new("FINAL_EXPONENT", || {
+    Ok(Integer::from(
+        FINAL_EXPONENT_EASY.try_get()? * FINAL_EXPONENT_HARD.try_get()?,
+    ))
+});
+
+/// The base field modulus p.
+pub fn p() -> &'static Integer {
+    BLS12_381_BASE.modulus_ref()
+}
+
+/// The prime subgroup order r.
+pub fn r() -> &'static Integer {
+    BLS12_381_SCALAR.modulus_ref()
+}
+
+/// r recomputed from x: x^4 - x^2 + 1.
+pub fn r_from_x() -> Integer {
+    let x2 = Integer::from(X.square_ref());
+    Integer::from(x2.square_ref()) - x2 + 1u32
+}
+
+/// p recomputed from x: (x - 1)^2 * (x^4 - x^2 + 1) / 3 + x.
+pub fn p_from_x() -> Integer {
+    let x_minus_one = Integer::from(&*X - 1u32);
+    let numerator = x_minus_one.square() * r_from_x();
+    numerator / 3u32 + &*X
+}
+
+/// Cofactor of G1: (x - 1)^2 / 3.
+pub fn g1_cofactor() -> Integer {
+    Integer::from(&*X - 1u32).square() / 3u32
+}
+
+/// Cofactor of G2: (x^8 - 4x^7 + 5x^6 - 4x^4 + 6x^3 - 4x^2 - 4x + 13) / 9.
+pub fn g2_cofactor() -> Integer {
+    let x = &*X;
+    let pow = |e: u32| x.clone().pow(e);
+    let numerator = pow(8) - pow(7) * 4u32 + pow(6) * 5u32 - pow(4) * 4u32 + pow(3) * 6u32
+        - pow(2) * 4u32
+        - Integer::from(x * 4u32)
+        + 13u32;
+    numerator / 9u32
+}
+
+/// 3 * (p^4 - p^2 + 1) / r written in x and p: (x - 1)^2 * (x + p) * (x^2 + p^2 - 1) + 3.
+pub fn hard_part_from_x() -> Integer {
+    let x = &*X;
+    let x_minus_one_sq = Integer::from(x - 1u32).square();
+    let x_plus_p = Integer::from(x + p());
+    let x2_p2_minus_one = Integer::from(x.square_ref()) + Integer::from(p().square_ref()) - 1u32;
+    x_minus_one_sq * x_plus_p * x2_p2_minus_one + 3u32
+}
+
+/// The exponent actually applied by `BLS12_381Pairing::final_exponentiation`:
+/// (p^6 - 1) * (p^2 + 1) * hard_part_from_x() = 3 * (p^12 - 1) / r.
+pub fn pairing_exponent() -> Integer {
+    &*FINAL_EXPONENT_EASY * hard_part_from_x()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::curves::bls12_381::{
+        curves::{g1_affine::G1Affine, g2_affine::G2Affine},
+        fields::fp12::Fp12,
+        paring::BLS12_381Pairing,
+    };
+
+    fn fp12_pow(base: &Fp12, exp: &Integer) -> Fp12 {
+        let mut acc = Fp12::one();
+        for i in (0..exp.significant_bits()).rev() {
+            acc = acc.square();
+            if exp.get_bit(i) {
+                acc = acc.mul(base);
+            }
+        }
+        acc
+    }
+
+    #[test]
+    fn test_parameters_from_x() {
+        assert_eq!(&r_from_x(), r());
+        assert_eq!(&p_from_x(), p());
+        assert_eq!(Integer::from(p() % 4u32), 3);
+        assert_eq!(Integer::from(p() % 3u32), 1);
+
+        // the trace of Frobenius is x + 1, so p ≡ x (mod r)
+        assert_eq!(*P_MOD_R, Integer::from(&*X + r()));
+        assert_eq!(*P2_MOD_R, Integer::from(X.square_ref()) % r());
+    }
+
+    #[test]
+    fn test_cofactors() {
+        // #E(Fp) = h1 * r = p + 1 - t with t = x + 1
+        let order = Integer::from(p() - &*X);
+        assert_eq!(g1_cofactor() * r(), order);
+        assert_eq!(
+            g2_cofactor().to_string_radix(16),
+            "5d543a95414e7f1091d50792876a202cd91de4547085abaa68a205b2e5a7ddfa628f1cb4d9e82ef21537e293a6691ae1616ec6e786f0c70cf1c38e31c7238e5"
+        );
+    }
+
+    #[test]
+    fn test_final_exponent_factorization() {
+        let p12 = p().clone().pow(EMBEDDING_DEGREE);
+        assert_eq!(Integer::from(&*FINAL_EXPONENT * r()), p12 - 1u32);
+        assert_eq!(
+            hard_part_from_x(),
+            Integer::from(&*FINAL_EXPONENT_HARD * 3u32)
+        );
+    }
+
+    #[test]
+    fn test_final_exponentiation_matches_exponent() {
+        let f = BLS12_381Pairing::miller_loop(&G1Affine::generator(), &G2Affine::generator());
+        let expected = fp12_pow(&f, &pairing_exponent());
+        assert_eq!(BLS12_381Pairing::final_exponentiation(&f).0, expected);
+    }
+}
@@ -1,9 +1,86 @@
 use super::{
     curves::{g1_affine::G1Affine, g2::G2Projective, g2_affine::G2Affine},
-    fields::{fp12::Fp12, fp2::Fp2, fp6::Fp6, target::TargetField},
+    fields::{fp12::Fp12, fp2::Fp2, target::TargetField},
     BLS12_381_BASE, MILLER_LOOP_CONSTANT, MILLER_LOOP_CONSTANT_IS_NEG,
 };
 
+/// Coefficients (a, b, c) of a line function ax + by + c = 0 in G2.
+type LineCoefficients = (Fp2, Fp2, Fp2);
+
+/// The steps of the Miller loop over the bits of x, in order.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum MillerStep {
+    /// T = 2T, with the tangent line at T
+    Doubling,
+    /// T = T + Q, with the line through T and Q
+    Addition,
+    /// f = f^2
+    Square,
+}
+
+/// Walks the bits of x / 2 below the leading one: a doubling for every bit,
+/// an addition for every set bit and a squaring in between, then a last
+/// doubling.
+fn miller_steps() -> impl Iterator<Item = MillerStep> {
+    let x = MILLER_LOOP_CONSTANT >> 1;
+    let bits = u64::BITS - x.leading_zeros();
+    (0..bits - 1)
+        .rev()
+        .flat_map(move |i| {
+            let addition = ((x >> i) & 1 == 1).then_some(MillerStep::Addition);
+            std::iter::once(MillerStep::Doubling)
+                .chain(addition)
+                .chain(std::iter::once(MillerStep::Square))
+        })
+        .chain(std::iter::once(MillerStep::Doubling))
+}
+
+/// The line coefficients of every step of the Miller loop for a fixed G2
+/// point, so that pairings against it only evaluate the lines at the G1
+/// point.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct G2Prepared {
+    coeffs: Vec<LineCoefficients>,
+    infinity: bool,
+}
+
+impl G2Prepared {
+    pub fn is_identity(&self) -> bool {
+        self.infinity
+    }
+}
+
+impl From<&G2Affine> for G2Prepared {
+    fn from(q: &G2Affine) -> Self {
+        if q.is_identity() {
+            return G2Prepared {
+                coeffs: Vec::new(),
+                infinity: true,
+            };
+        }
+
+        let mut current = G2Projective::from(q);
+        let coeffs = miller_steps()
+            .filter_map(|step| match step {
+                MillerStep::Doubling => Some(BLS12_381Pairing::doubling_step(&mut current)),
+                MillerStep::Addition => Some(BLS12_381Pairing::addition_step(&mut current, q)),
+                MillerStep::Square => None,
+            })
+            .collect();
+
+        G2Prepared {
+            coeffs,
+            infinity: false,
+        }
+    }
+}
+
+impl From<G2Affine> for G2Prepared {
+    fn from(q: G2Affine) -> Self {
+        G2Prepared::from(&q)
+    }
+}
+
 pub struct BLS12_381Pairing;
 
 impl BLS12_381Pairing {
@@ -16,12 +93,45 @@ impl BLS12_381Pairing {
             return TargetField::one();
         }
 
-        let miller_loop_result = Self::miller_loop(&g1_point, &g2_point);
+        let miller_loop_result = Self::miller_loop(g1_point, g2_point);
         // println!("miller_loop_result: {:#}", miller_loop_result);
 
         Self::final_exponentiation(&miller_loop_result)
     }
 
+    /// Computes the product ∏_i e(P_i, Q_i) with one shared Miller loop and a
+    /// single final exponentiation. Pairs containing an identity point
+    /// contribute one and are skipped.
+    pub fn multi_pairing(pairs: &[(&G1Affine, &G2Affine)]) -> TargetField {
+        let pairs: Vec<(&G1Affine, &G2Affine)> = pairs
+            .iter()
+            .filter(|(p, q)| !p.is_identity() && !q.is_identity())
+            .copied()
+            .collect();
+        if pairs.is_empty() {
+            return TargetField::one();
+        }
+
+        Self::final_exponentiation(&Self::multi_miller_loop(&pairs))
+    }
+
+    /// Checks ∏_i e(P_i, Q_i) == 1.
+    ///
+    /// An equation e(A, B) == e(C, D) is checked as e(A, B) * e(-C, D) == 1.
+    /// Every point must be on its curve and in the prime-order subgroup,
+    /// otherwise the check fails regardless of the product.
+    pub fn pairing_product_is_one(pairs: &[(G1Affine, G2Affine)]) -> bool {
+        let valid = pairs.iter().all(|(p, q)| {
+            p.is_on_curve() && q.is_on_curve() && p.is_torsion_free() && q.is_torsion_free()
+        });
+        if !valid {
+            return false;
+        }
+
+        let pairs: Vec<(&G1Affine, &G2Affine)> = pairs.iter().map(|(p, q)| (p, q)).collect();
+        Self::multi_pairing(&pairs).is_identity()
+    }
+
     /// Performs the multi-Miller loop for the optimal ate pairing on BLS12-381.
     ///
     /// This function computes the product of multiple pairings simultaneously,
@@ -34,65 +144,65 @@ impl BLS12_381Pairing {
     /// - u is the BLS parameter (x in this case)
     /// - f_{u,Q_i} is the function arising from Miller's algorithm
     ///
-    /// The loop is optimized for the BLS12-381 curve parameters.
+    /// The loop is optimized for the BLS12-381 curve parameters. A G2 point
+    /// that appears in several pairs has its line coefficients computed once.
     pub fn multi_miller_loop(pairs: &[(&G1Affine, &G2Affine)]) -> Fp12 {
-        let mut f = Fp12::one();
-        let mut found_one = false;
-        let mut current_points: Vec<G2Projective> =
-            pairs.iter().map(|(_, q)| G2Projective::from(*q)).collect();
-
-        for i in (0..64).rev() {
-            let bit = ((MILLER_LOOP_CONSTANT >> 1) >> i) & 1 == 1;
-
-            if !found_one {
-                if bit {
-                    found_one = true;
+        let mut distinct: Vec<&G2Affine> = Vec::new();
+        let indices: Vec<usize> = pairs
+            .iter()
+            .map(|(_, q)| match distinct.iter().position(|d| d == q) {
+                Some(i) => i,
+                None => {
+                    distinct.push(q);
+                    distinct.len() - 1
                 }
-                continue;
-            }
+            })
+            .collect();
+        let prepared: Vec<G2Prepared> = distinct.into_iter().map(G2Prepared::from).collect();
+
+        let pairs: Vec<(&G1Affine, &G2Prepared)> = pairs
+            .iter()
+            .zip(indices)
+            .map(|((p, _), i)| (*p, &prepared[i]))
+            .collect();
+        Self::multi_miller_loop_prepared(&pairs)
+    }
 
-            f = Self::multi_doubling_step(&mut current_points, &f, pairs);
+    /// The Miller loop of e(P, Q) for a prepared Q, see [`G2Prepared`].
+    pub fn miller_loop_prepared(p: &G1Affine, q: &G2Prepared) -> Fp12 {
+        Self::multi_miller_loop_prepared(&[(p, q)])
+    }
 
-            if bit {
-                f = Self::multi_addition_step(&mut current_points, pairs, &f);
-            }
+    /// The multi-Miller loop over prepared G2 points: only the lines are
+    /// evaluated at each P_i. Pairs containing an identity point contribute
+    /// one.
+    pub fn multi_miller_loop_prepared(pairs: &[(&G1Affine, &G2Prepared)]) -> Fp12 {
+        let mut lines: Vec<(&G1Affine, std::slice::Iter<LineCoefficients>)> = pairs
+            .iter()
+            .filter(|(p, q)| !p.is_identity() && !q.is_identity())
+            .map(|(p, q)| (*p, q.coeffs.iter()))
+            .collect();
 
-            f = f.square();
+        let mut f = Fp12::one();
+        for step in miller_steps() {
+            match step {
+                MillerStep::Square => f.square_assign(),
+                MillerStep::Doubling | MillerStep::Addition => {
+                    for (p, coeffs) in lines.iter_mut() {
+                        let line = coeffs.next().expect("one line per step");
+                        Self::evaluate_line(&mut f, line, p);
+                    }
+                }
+            }
         }
 
-        f = Self::multi_doubling_step(&mut current_points, &f, pairs);
-
         if MILLER_LOOP_CONSTANT_IS_NEG {
-            f = f.conjugate();
+            f.conjugate_assign();
         }
 
         f
     }
 
-    fn multi_doubling_step(
-        current_points: &mut [G2Projective],
-        f: &Fp12,
-        pairs: &[(&G1Affine, &G2Affine)],
-    ) -> Fp12 {
-        let mut result = f.clone();
-        for (current, (p, _)) in current_points.iter_mut().zip(pairs.iter()) {
-            result = Self::doubling_step(current, &result, p);
-        }
-        result
-    }
-
-    fn multi_addition_step(
-        current_points: &mut [G2Projective],
-        pairs: &[(&G1Affine, &G2Affine)],
-        f: &Fp12,
-    ) -> Fp12 {
-        let mut result = f.clone();
-        for (current, (p, q)) in current_points.iter_mut().zip(pairs.iter()) {
-            result = Self::addition_step(current, q, &result, p);
-        }
-        result
-    }
-
     /// Performs the final exponentiation to convert the result of a Miller loop
     /// into an element of the target group Gt.
     ///
@@ -100,135 +210,80 @@ impl BLS12_381Pairing {
     /// where p is the field characteristic and r is the group order.
     ///
     /// This is split into two parts:
-    /// 1. Easy part: f^(p^6 - 1) * (p^2 + 1)
-    /// 2. Hard part: f^((p^4 - p^2 + 1) / r)
+    /// 1. Easy part: f^(p^6 - 1) * (p^2 + 1), see [`Self::easy_part`]
+    /// 2. Hard part: f^((p^4 - p^2 + 1) / r), see [`Self::hard_part`]
     pub fn final_exponentiation(miller_loop_result: &Fp12) -> TargetField {
-        // Easy part of final exponentiation
-        // f^(p^6 - 1) * (p^2 + 1)
-        let mut t0 = miller_loop_result
-            .clone()
-            .frobenius_map()
-            .frobenius_map()
-            .frobenius_map()
-            .frobenius_map()
-            .frobenius_map()
-            .frobenius_map();
-
-        if let Some(mut t1) = miller_loop_result.invert() {
-            let mut t2 = t0.mul(&t1);
-            t1 = t2.clone();
-            t2 = t2.frobenius_map().frobenius_map();
-            t2 = t2.mul(&t1);
-            t1 = Self::cyclotomic_square(&t2).conjugate();
-            let mut t3 = Self::cyclotomic_exp(&t2);
-            let mut t4 = Self::cyclotomic_square(&t3);
-            let mut t5 = t1.mul(&t3);
-            t1 = Self::cyclotomic_exp(&t5);
-            t0 = Self::cyclotomic_exp(&t1);
-            let mut t6 = Self::cyclotomic_exp(&t0);
-            t6 = t6.mul(&t4);
-            t4 = Self::cyclotomic_exp(&t6);
-            t5 = t5.conjugate();
-            t4 = t4.mul(&t5.mul(&t2));
-            t5 = t2.conjugate();
-            t1 = t1.mul(&t2).frobenius_map().frobenius_map().frobenius_map();
-            t6 = t6.mul(&t5).frobenius_map();
-            t3 = t3
-                .mul(&t0)
-                .frobenius_map()
-                .frobenius_map()
-                .mul(&t1)
-                .mul(&t6);
-
-            TargetField(t3.mul(&t4))
-        } else {
+        match Self::easy_part(miller_loop_result) {
+            Some(f) => TargetField(Self::hard_part(&f)),
             // This should never happen for valid input
-            TargetField(Fp12::one())
+            None => TargetField(Fp12::one()),
         }
     }
 
-    /// Computes the square of an Fp4 element: (a + bi)^2 = (a^2 - b^2) + (2ab)i
-    fn fp4_square(a: &Fp2, b: &Fp2) -> (Fp2, Fp2) {
-        let a_squared = a.square();
-        let b_squared = b.square();
-        let ab_mul_2 = a.add(&b).square().sub(&a_squared).sub(&b_squared);
-
-        let c0 = a_squared.add(&b_squared.mul_by_nonresidue());
-        let c1 = ab_mul_2;
-
-        (c0, c1)
+    /// f^((p^6 - 1) * (p^2 + 1)), which maps f into the cyclotomic subgroup.
+    /// Returns None if f is zero.
+    pub fn easy_part(f: &Fp12) -> Option<Fp12> {
+        let t0 = f.frobenius_map(6);
+        let t1 = f.invert()?;
+        let t2 = t0.mul(&t1);
+        Some(t2.frobenius_map(2).mul(&t2))
     }
 
-    /// Performs efficient squaring in the cyclotomic subgroup
-    /// Based on "Faster Squaring in the Cyclotomic Subgroup of Sixth Degree Extensions"
-    /// by F. Beuchat et al. (https://eprint.iacr.org/2009/565.pdf)
-    fn cyclotomic_square(f: &Fp12) -> Fp12 {
-        let mut z0 = f.c0.c0.clone();
-        let mut z1 = f.c1.c1.clone();
-        let mut z2 = f.c1.c0.clone();
-        let mut z3 = f.c0.c2.clone();
-        let mut z4 = f.c0.c1.clone();
-        let mut z5 = f.c1.c2.clone();
-
-        let (t0, t1) = Self::fp4_square(&z0, &z1);
-
-        // For A
-        z0 = t0.sub(&z0);
-        z0 = z0.add(&z0).add(&t0);
-
-        z1 = t1.add(&z1);
-        z1 = z1.add(&z1).add(&t1);
-
-        let (mut t0, t1) = Self::fp4_square(&z2, &z3);
-        let (t2, t3) = Self::fp4_square(&z4, &z5);
-
-        // For C
-        z4 = t0.sub(&z4);
-        z4 = z4.add(&z4).add(&t0);
-
-        z5 = t1.add(&z5);
-        z5 = z5.add(&z5).add(&t1);
-
-        // For B
-        t0 = t3.mul_by_nonresidue();
-        z2 = t0.add(&z2);
-        z2 = z2.add(&z2).add(&t0);
-
-        z3 = t2.sub(&z3);
-        z3 = z3.add(&z3).add(&t2);
-
-        Fp12 {
-            c0: Fp6 {
-                c0: z0,
-                c1: z4,
-                c2: z3,
-            },
-            c1: Fp6 {
-                c0: z2,
-                c1: z1,
-                c2: z5,
-            },
-        }
+    /// The hard part of the final exponentiation, for f in the cyclotomic
+    /// subgroup. Computes f^(3 (p^4 - p^2 + 1) / r) with five
+    /// exponentiations by x, following Hayashida, Hayasaka and Teruya
+    /// (https://eprint.iacr.org/2020/875.pdf); the factor 3 is coprime to r
+    /// and leaves the pairing non-degenerate.
+    pub fn hard_part(f: &Fp12) -> Fp12 {
+        let t2 = f;
+        let mut t1 = t2.cyclotomic_square().conjugate();
+        let mut t3 = Self::cyclotomic_exp(t2);
+        let mut t4 = t3.cyclotomic_square();
+        let mut t5 = t1.mul(&t3);
+        t1 = Self::cyclotomic_exp(&t5);
+        let t0 = Self::cyclotomic_exp(&t1);
+        let mut t6 = Self::cyclotomic_exp(&t0);
+        t6 = t6.mul(&t4);
+        t4 = Self::cyclotomic_exp(&t6);
+        t5 = t5.conjugate();
+        t4 = t4.mul(&t5.mul(t2));
+        t5 = t2.conjugate();
+        t1 = t1.mul(t2).frobenius_map(3);
+        t6 = t6.mul(&t5).frobenius_map(1);
+        t3 = t3.mul(&t0).frobenius_map(2).mul(&t1).mul(&t6);
+
+        t3.mul(&t4)
     }
 
     /// Performs exponentiation by x in the cyclotomic subgroup
     /// where x is the BLS parameter
+    ///
+    /// x has six bits set, so the squarings run on Karabina's compressed
+    /// form and only the six powers base^(2^i) that get multiplied are
+    /// decompressed, all of them with one shared inversion.
     fn cyclotomic_exp(base: &Fp12) -> Fp12 {
-        let mut result = Fp12::one();
-        let mut found_one = false;
-
-        for i in (0..64).rev() {
-            if found_one {
-                result = Self::cyclotomic_square(&result);
+        let mut powers = Vec::with_capacity(MILLER_LOOP_CONSTANT.count_ones() as usize);
+        let mut power = base.clone();
+        for i in 0..64 - MILLER_LOOP_CONSTANT.leading_zeros() {
+            if i > 0 {
+                power = power.cyclotomic_square_compressed();
             }
-
             if ((MILLER_LOOP_CONSTANT >> i) & 1) == 1 {
-                found_one = true;
-                result = result.mul(base);
+                powers.push(power.clone());
             }
         }
 
-        result.conjugate()
+        // base itself is uncompressed; decompressing it again is harmless
+        Fp12::batch_decompress_karabina(&mut powers);
+        let result = powers
+            .iter()
+            .fold(Fp12::one(), |result, power| result.mul(power));
+
+        if MILLER_LOOP_CONSTANT_IS_NEG {
+            result.conjugate()
+        } else {
+            result
+        }
     }
 
     /// Performs the Miller loop for the optimal ate pairing on BLS12-381.
@@ -240,60 +295,14 @@ impl BLS12_381Pairing {
     ///
     /// The loop is optimized for the BLS12-381 curve parameters.
     pub fn miller_loop(p: &G1Affine, q: &G2Affine) -> Fp12 {
-        let mut f = Fp12::one();
-        let mut found_one = false;
-        let mut current = G2Projective::from(q.clone());
-
-        for i in (0..64).rev() {
-            let bit = ((MILLER_LOOP_CONSTANT >> 1) >> i) & 1 == 1;
-
-            if !found_one {
-                if bit {
-                    found_one = true;
-                }
-                continue;
-            }
-
-            f = Self::doubling_step(&mut current, &f, p);
-
-            if bit {
-                f = Self::addition_step(&mut current, q, &f, p);
-            }
-
-            f = f.square();
-        }
-
-        f = Self::doubling_step(&mut current, &f, p);
-
-        if MILLER_LOOP_CONSTANT_IS_NEG {
-            f = f.conjugate();
-        }
-
-        f
+        Self::multi_miller_loop(&[(p, q)])
     }
 
     /// Performs the addition step in Miller's algorithm.
     ///
-    /// This step computes the line function arising from adding two points in G2
-    /// and evaluates it at the G1 point.
-    ///
-    /// Formula: l_{T,Q}(P) where T is the current G2 point, Q is the fixed G2 point,
-    /// and P is the G1 point.
-    fn addition_step(current: &mut G2Projective, q: &G2Affine, f: &Fp12, p: &G1Affine) -> Fp12 {
-        let line_coeffs = Self::compute_addition_coefficients(current, q);
-
-        // println!(
-        //     "line_coeffs: {}\n {}\n {}\n",
-        //     line_coeffs.0, line_coeffs.1, line_coeffs.2
-        // );
-
-        Self::evaluate_line(f.clone(), &line_coeffs, p)
-    }
-
-    /// Computes the coefficients of the line function for point addition in G2.
-    ///
-    /// Returns (a, b, c) where the line function is ax + by + c = 0.
-    fn compute_addition_coefficients(r: &mut G2Projective, q: &G2Affine) -> (Fp2, Fp2, Fp2) {
+    /// Sets T to T + Q and returns the coefficients of the line through T and
+    /// Q, to be evaluated at the G1 point by `evaluate_line`.
+    fn addition_step(r: &mut G2Projective, q: &G2Affine) -> LineCoefficients {
         let zsquared = r.z.square();
         let ysquared = q.y.square();
         let t0 = zsquared.mul(&q.x);
@@ -330,41 +339,31 @@ impl BLS12_381Pairing {
         (t10, t1, t9)
     }
 
-    /// Performs the doubling step in Miller's algorithm.
-    ///
-    /// This step computes the line function arising from doubling a point in G2
-    /// and evaluates it at the G1 point.
-    ///
-    /// Formula: l_{T,T}(P) where T is the current G2 point and P is the G1 point.
-    fn doubling_step(current: &mut G2Projective, f: &Fp12, p: &G1Affine) -> Fp12 {
-        let line_coeffs = Self::compute_doubling_coefficients(current);
-        Self::evaluate_line(f.clone(), &line_coeffs, p)
-    }
-
     /// Evaluates the line function l(P) = a * y_p + b * x_p + c at the point P.
     ///
     /// Parameters:
     /// - line_coeffs: (a, b, c) coefficients of the line function
     /// - p: Point in G1 to evaluate the line function at
-    /// - f: Current value of the Miller loop accumulator
-    fn evaluate_line(f: Fp12, line_coeffs: &(Fp2, Fp2, Fp2), p: &G1Affine) -> Fp12 {
+    /// - f: Miller loop accumulator, multiplied by the line in place
+    fn evaluate_line(f: &mut Fp12, line_coeffs: &LineCoefficients, p: &G1Affine) {
         let mut c0 = line_coeffs.0.clone();
         let mut c1 = line_coeffs.1.clone();
 
-        c0.c0 = BLS12_381_BASE.mul(c0.c0, &p.y);
-        c0.c1 = BLS12_381_BASE.mul(c0.c1, &p.y);
+        c0.c0 = BLS12_381_BASE.mul(c0.c0, &p.y.0);
+        c0.c1 = BLS12_381_BASE.mul(c0.c1, &p.y.0);
 
-        c1.c0 = BLS12_381_BASE.mul(c1.c0, &p.x);
-        c1.c1 = BLS12_381_BASE.mul(c1.c1, &p.x);
+        c1.c0 = BLS12_381_BASE.mul(c1.c0, &p.x.0);
+        c1.c1 = BLS12_381_BASE.mul(c1.c1, &p.x.0);
 
         // Use the optimized multiplication function
-        f.mul_by_c0_c1_c4(&line_coeffs.2, &c1, &c0)
+        f.mul_by_c0_c1_c4_assign(&line_coeffs.2, &c1, &c0);
     }
 
-    /// Computes the coefficients of the line function for point doubling in G2.
+    /// Performs the doubling step in Miller's algorithm.
     ///
-    /// Returns (a, b, c) where the line function is ax + by + c = 0.
-    fn compute_doubling_coefficients(r: &mut G2Projective) -> (Fp2, Fp2, Fp2) {
+    /// Sets T to 2T and returns the coefficients of the tangent line at T, to
+    /// be evaluated at the G1 point by `evaluate_line`.
+    fn doubling_step(r: &mut G2Projective) -> LineCoefficients {
         let tmp0 = r.x.square();
         let tmp1 = r.y.square();
         let tmp2 = tmp1.square();
@@ -438,14 +437,14 @@ impl BLS12_381Pairing {
 
 #[cfg(test)]
 mod tests {
-    use rug::Integer;
 
     use crate::curves::bls12_381::{
         curves::{g1::G1Projective, g1_affine::G1Affine, g2::G2Projective, g2_affine::G2Affine},
-        fields::target::TargetField,
-        paring::BLS12_381Pairing,
-        Bls12_381ScalarField,
+        fields::{fp12::Fp12, fp2::Fp2, fp6::Fp6, target::TargetField},
+        paring::{BLS12_381Pairing, G2Prepared},
+        Bls12_381BaseField, Bls12_381ScalarField, BLS12_381_BASE, BLS12_381_SCALAR,
     };
+    use rug::Integer;
 
     #[test]
     fn test_bilinearity() {
@@ -462,13 +461,13 @@ mod tests {
 
         let c = a.mul(&b);
 
-        let g = G1Projective::generator().mul_scalar(&a.0).to_affine();
-        let h = G2Projective::generator().mul_scalar(&b.0).to_affine();
+        let g = G1Projective::generator().mul_scalar(&a).to_affine();
+        let h = G2Projective::generator().mul_scalar(&b).to_affine();
 
         let pairing = BLS12_381Pairing::pairing(&g, &h);
         assert!(pairing != TargetField::one());
 
-        let expected = G1Projective::generator().mul_scalar(&c.0).to_affine();
+        let expected = G1Projective::generator().mul_scalar(&c).to_affine();
 
         assert_eq!(
             pairing,
@@ -477,7 +476,198 @@ mod tests {
         assert_eq!(
             pairing,
             BLS12_381Pairing::pairing(&G1Affine::generator(), &G2Affine::generator())
-                .mul_scalar(&c.0)
+                .mul_scalar(&c)
         );
     }
+    #[test]
+    fn test_miller_steps() {
+        use super::{miller_steps, MillerStep};
+
+        // x = -0xd201000000010000: 63 doublings, one addition for each set
+        // bit below the leading one and a squaring between iterations
+        let steps: Vec<MillerStep> = miller_steps().collect();
+        let count = |kind| steps.iter().filter(|&&step| step == kind).count();
+        assert_eq!(count(MillerStep::Doubling), 63);
+        assert_eq!(count(MillerStep::Addition), 5);
+        assert_eq!(count(MillerStep::Square), 62);
+        assert_eq!(steps.first(), Some(&MillerStep::Doubling));
+        assert_eq!(steps.last(), Some(&MillerStep::Doubling));
+        assert!(steps
+            .windows(2)
+            .all(|w| w[0] != MillerStep::Square || w[1] == MillerStep::Doubling));
+    }
+
+    #[test]
+    fn test_pairing_known_answer() {
+        // e(G1, G2) as computed by RELIC, the vector of the pairing crate's
+        // test_pairing_result_against_relic
+        let coeffs: Vec<Integer> = [
+            "2819105605953691245277803056322684086884703000473961065716485506033588504203831029066448642358042597501014294104502",
+            "1323968232986996742571315206151405965104242542339680722164220900812303524334628370163366153839984196298685227734799",
+            "2987335049721312504428602988447616328830341722376962214011674875969052835043875658579425548512925634040144704192135",
+            "3879723582452552452538684314479081967502111497413076598816163759028842927668327542875108457755966417881797966271311",
+            "261508182517997003171385743374653339186059518494239543139839025878870012614975302676296704930880982238308326681253",
+            "231488992246460459663813598342448669854473942105054381511346786719005883340876032043606739070883099647773793170614",
+            "3993582095516422658773669068931361134188738159766715576187490305611759126554796569868053818105850661142222948198557",
+            "1074773511698422344502264006159859710502164045911412750831641680783012525555872467108249271286757399121183508900634",
+            "2727588299083545686739024317998512740561167011046940249988557419323068809019137624943703910267790601287073339193943",
+            "493643299814437640914745677854369670041080344349607504656543355799077485536288866009245028091988146107059514546594",
+            "734401332196641441839439105942623141234148957972407782257355060229193854324927417865401895596108124443575283868655",
+            "2348330098288556420918672502923664952620152483128593484301759394583320358354186482723629999370241674973832318248497",
+        ]
+        .iter()
+        .map(|c| c.parse().unwrap())
+        .collect();
+        let fp2 = |i: usize| Fp2 {
+            c0: coeffs[2 * i].clone(),
+            c1: coeffs[2 * i + 1].clone(),
+        };
+        let expected = Fp12::new(
+            Fp6::new(fp2(0), fp2(1), fp2(2)),
+            Fp6::new(fp2(3), fp2(4), fp2(5)),
+        );
+
+        let (p, q) = (G1Affine::generator(), G2Affine::generator());
+        assert_eq!(BLS12_381Pairing::pairing(&p, &q).0, expected);
+        assert_eq!(
+            BLS12_381Pairing::final_exponentiation(&BLS12_381Pairing::multi_miller_loop(&[(
+                &p, &q
+            )]))
+            .0,
+            expected
+        );
+
+        // e(2 G1, G2) = e(G1, 2 G2) = e(G1, G2)^2, through the multi loop too
+        let p2 = G1Projective::generator().double().to_affine();
+        let q2 = G2Projective::generator().double().to_affine();
+        let squared = expected.square();
+        assert_eq!(BLS12_381Pairing::pairing(&p2, &q).0, squared);
+        assert_eq!(BLS12_381Pairing::pairing(&p, &q2).0, squared);
+        assert_eq!(
+            BLS12_381Pairing::multi_pairing(&[(&p, &q), (&p, &q)]).0,
+            squared
+        );
+    }
+
+    #[test]
+    fn test_miller_loop_prepared() {
+        let p = G1Affine::generator();
+        let q = G2Affine::generator();
+        let ap = G1Projective::generator()
+            .mul_scalar(&Bls12_381ScalarField::from_raw([3, 0, 0, 0]))
+            .to_affine();
+        let bq = G2Projective::generator()
+            .mul_scalar(&Bls12_381ScalarField::from_raw([5, 0, 0, 0]))
+            .to_affine();
+
+        let prepared = G2Prepared::from(q.clone());
+        assert_eq!(
+            BLS12_381Pairing::miller_loop_prepared(&p, &prepared),
+            BLS12_381Pairing::miller_loop(&p, &q)
+        );
+
+        // a repeated G2 point shares its coefficients
+        let expected = BLS12_381Pairing::miller_loop(&p, &q)
+            .mul(&BLS12_381Pairing::miller_loop(&ap, &bq))
+            .mul(&BLS12_381Pairing::miller_loop(&ap, &q));
+        assert_eq!(
+            BLS12_381Pairing::multi_miller_loop(&[(&p, &q), (&ap, &bq), (&ap, &q)]),
+            expected
+        );
+        assert_eq!(
+            BLS12_381Pairing::multi_miller_loop_prepared(&[
+                (&p, &prepared),
+                (&ap, &G2Prepared::from(&bq)),
+                (&ap, &prepared),
+            ]),
+            expected
+        );
+
+        let identity = G2Prepared::from(G2Affine::identity());
+        assert!(identity.is_identity());
+        assert!(BLS12_381Pairing::miller_loop_prepared(&p, &identity).is_one());
+        assert!(BLS12_381Pairing::miller_loop_prepared(&G1Affine::identity(), &prepared).is_one());
+    }
+
+    #[test]
+    fn test_final_exponentiation_parts() {
+        let mut rng = zkper_rand::TestRng::new();
+        let f = Fp12::random(&mut rng);
+        let p = BLS12_381_BASE.modulus();
+        let r = BLS12_381_SCALAR.modulus();
+
+        let p2 = p.clone().square();
+        let easy = BLS12_381Pairing::easy_part(&f).unwrap();
+        assert_eq!(
+            easy,
+            f.pow(&((p2.clone() * &p2 * &p2 - 1u32) * (p2.clone() + 1u32)))
+        );
+        assert!(BLS12_381Pairing::easy_part(&Fp12::zero()).is_none());
+
+        let hard = BLS12_381Pairing::hard_part(&easy);
+        let exponent = (p2.clone() * &p2 - &p2 + 1u32) / &r * 3u32;
+        assert_eq!(hard, easy.pow(&exponent));
+        assert_eq!(BLS12_381Pairing::final_exponentiation(&f).0, hard);
+        assert!(hard.pow(&r).is_one());
+    }
+
+    #[test]
+    fn test_pairing_product_is_one() {
+        let a = Bls12_381ScalarField::from_raw([9, 10, 11, 12]);
+        let p = G1Affine::generator();
+        let q = G2Affine::generator();
+        let ap = G1Projective::generator().mul_scalar(&a).to_affine();
+        let aq = G2Projective::generator().mul_scalar(&a).to_affine();
+
+        // e(aP, Q) * e(-P, aQ) == 1
+        assert!(BLS12_381Pairing::pairing_product_is_one(&[
+            (ap.clone(), q.clone()),
+            (p.neg(), aq.clone()),
+        ]));
+        assert!(!BLS12_381Pairing::pairing_product_is_one(&[
+            (ap.clone(), q.clone()),
+            (p.clone(), aq.clone()),
+        ]));
+        assert_eq!(
+            BLS12_381Pairing::multi_pairing(&[(&ap, &q), (&p, &aq)]),
+            BLS12_381Pairing::pairing(&p, &q).pow(&a).double()
+        );
+
+        // identity pairs contribute nothing
+        assert!(BLS12_381Pairing::pairing_product_is_one(&[]));
+        assert!(BLS12_381Pairing::pairing_product_is_one(&[
+            (G1Affine::identity(), q.clone()),
+            (p.clone(), G2Affine::identity()),
+        ]));
+    }
+
+    #[test]
+    fn test_pairing_product_rejects_small_subgroup() {
+        // a point on the curve whose cofactor has not been cleared
+        let mut x = Integer::from(1);
+        let point = loop {
+            let rhs =
+                Bls12_381BaseField::add(Bls12_381BaseField::cubic(x.clone()), &Integer::from(4));
+            if let Some(y) = Bls12_381BaseField::sqrt(rhs) {
+                break G1Affine::new(x.into(), y.into(), false);
+            }
+            x += 1;
+        };
+        assert!(point.is_on_curve());
+        assert!(!point.is_torsion_free());
+        assert!(G1Affine::generator().is_torsion_free());
+        assert!(G2Affine::generator().is_torsion_free());
+
+        // e(T, Q) * e(-T, Q) == 1 holds algebraically but T is rejected
+        assert!(!BLS12_381Pairing::pairing_product_is_one(&[
+            (point.clone(), G2Affine::generator()),
+            (point.neg(), G2Affine::generator()),
+        ]));
+
+        let off_curve = G1Affine::new(1.into(), 1.into(), false);
+        assert!(!BLS12_381Pairing::pairing_product_is_one(&[(
+            off_curve,
+            G2Affine::generator()
+        )]));
+    }
 }
@@ -1 +1,2 @@
 pub mod bls12_381;
+pub mod tower;
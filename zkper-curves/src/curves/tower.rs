@@ -0,0 +1,217 @@
+//! Consistency checks for the constants of a pairing field tower.
+//!
+//! Pairing-friendly curves with embedding degree 12 build Fp12 as
+//!
+//! ```text
+//! Fp2  = Fp[u]  / (u^2 - β)
+//! Fp6  = Fp2[v] / (v^3 - ξ)
+//! Fp12 = Fp6[w] / (w^2 - v)
+//! ```
+//!
+//! and hardcode a handful of constants derived from β and ξ. A wrong constant
+//! does not fail loudly; it yields a wrong pairing. [`validate_tower`] checks
+//! every constant of a [`TowerConstants`] against its defining equation with
+//! plain arithmetic modulo p, independently of the curve's own field types, so
+//! the same checks cover every curve listed in [`TOWERS`].
+
+use rug::{integer::IsPrime, ops::RemRounding, Integer};
+
+use crate::constant::{ensure, ConstantError};
+
+use super::bls12_381;
+
+/// An element c0 + c1 u of Fp2, as plain integers.
+pub type Fp2Coeffs = (Integer, Integer);
+
+/// The constants of a degree-12 tower, see the module documentation.
+#[derive(Clone, Debug)]
+pub struct TowerConstants {
+    /// Name of the curve, reported in errors.
+    pub name: &'static str,
+    /// The characteristic p.
+    pub modulus: Integer,
+    /// β, a non-square in Fp.
+    pub fp2_nonresidue: Integer,
+    /// ξ, neither a square nor a cube in Fp2.
+    pub fp6_nonresidue: Fp2Coeffs,
+    /// ξ^((p^k - 1) / 3) for k = 0, 1, ...
+    pub frobenius_coeff_fp6_c1: Vec<Fp2Coeffs>,
+    /// ξ^((2p^k - 2) / 3) for k = 0, 1, ...
+    pub frobenius_coeff_fp6_c2: Vec<Fp2Coeffs>,
+    /// ξ^((p^k - 1) / 6) for k = 0, 1, ...
+    pub frobenius_coeff_fp12_c1: Vec<Fp2Coeffs>,
+}
+
+/// Every tower in the crate, checked by `validate_towers`.
+pub const TOWERS: &[fn() -> Result<TowerConstants, ConstantError>] = &[bls12_381::tower_constants];
+
+/// Validates every tower in [`TOWERS`].
+pub fn validate_towers() -> Result<(), ConstantError> {
+    for tower in TOWERS {
+        validate_tower(&tower()?)?;
+    }
+    Ok(())
+}
+
+/// Checks that the constants of `tower` satisfy their defining equations:
+/// p is prime, β is not a square in Fp, ξ is neither a square nor a cube in
+/// Fp2 (so v^3 - ξ and w^2 - v are irreducible), and every Frobenius
+/// coefficient is the power of ξ it stands for.
+pub fn validate_tower(tower: &TowerConstants) -> Result<(), ConstantError> {
+    let name = tower.name;
+    let p = &tower.modulus;
+    ensure(
+        name,
+        p.is_probably_prime(30) != IsPrime::No,
+        "modulus is not prime",
+    )?;
+
+    let fp2 = Fp2Arith {
+        p,
+        beta: reduce(&tower.fp2_nonresidue, p),
+    };
+    ensure(
+        name,
+        fp2.beta.jacobi(p) == -1,
+        "the Fp2 non-residue is a square in Fp",
+    )?;
+
+    let xi = fp2.reduce(&tower.fp6_nonresidue);
+    let p2_minus_one = Integer::from(p.square_ref()) - 1u32;
+    ensure(
+        name,
+        p2_minus_one.is_divisible_u(3) && !fp2.pow(&xi, &(p2_minus_one.clone() / 3u32)).is_one(),
+        "the Fp6 non-residue is a cube in Fp2",
+    )?;
+    // v^((p^6 - 1) / 2) = ξ^((p^6 - 1) / 6) is an odd power of ξ^((p^2 - 1) / 2),
+    // so v is a square in Fp6 exactly when ξ is one in Fp2
+    ensure(
+        name,
+        !fp2.pow(&xi, &(p2_minus_one / 2u32)).is_one(),
+        "the Fp6 non-residue is a square in Fp2",
+    )?;
+
+    let tables = [
+        (
+            "FROBENIUS_COEFF_FP6_C1",
+            &tower.frobenius_coeff_fp6_c1,
+            1u32,
+            3u32,
+        ),
+        (
+            "FROBENIUS_COEFF_FP6_C2",
+            &tower.frobenius_coeff_fp6_c2,
+            2,
+            3,
+        ),
+        (
+            "FROBENIUS_COEFF_FP12_C1",
+            &tower.frobenius_coeff_fp12_c1,
+            1,
+            6,
+        ),
+    ];
+    for (table, coeffs, numerator, denominator) in tables {
+        let mut p_power = Integer::from(1);
+        for (k, coeff) in coeffs.iter().enumerate() {
+            // numerator * (p^k - 1) / denominator
+            let exponent = (p_power.clone() - 1u32) * numerator / denominator;
+            if fp2.reduce(coeff) != fp2.pow(&xi, &exponent) {
+                return Err(ConstantError::new(
+                    name,
+                    format!("{table}[{k}] is not ξ^({numerator}(p^{k} - 1) / {denominator})"),
+                ));
+            }
+            p_power *= p;
+        }
+    }
+
+    Ok(())
+}
+
+fn reduce(value: &Integer, p: &Integer) -> Integer {
+    value.clone().rem_euc(p)
+}
+
+/// Fp2 arithmetic over bare integers, for checking constants without the
+/// curve's field types.
+struct Fp2Arith<'a> {
+    p: &'a Integer,
+    beta: Integer,
+}
+
+#[derive(Clone, PartialEq, Eq)]
+struct Element(Integer, Integer);
+
+impl Element {
+    fn is_one(&self) -> bool {
+        self.0 == 1 && self.1.is_zero()
+    }
+}
+
+impl<'a> Fp2Arith<'a> {
+    fn reduce(&self, (c0, c1): &Fp2Coeffs) -> Element {
+        Element(reduce(c0, self.p), reduce(c1, self.p))
+    }
+
+    fn mul(&self, a: &Element, b: &Element) -> Element {
+        let c0 = Integer::from(&a.0 * &b.0) + Integer::from(&a.1 * &b.1) * &self.beta;
+        let c1 = Integer::from(&a.0 * &b.1) + Integer::from(&a.1 * &b.0);
+        Element(reduce(&c0, self.p), reduce(&c1, self.p))
+    }
+
+    fn pow(&self, base: &Element, exponent: &Integer) -> Element {
+        let mut result = Element(Integer::from(1), Integer::ZERO);
+        for i in (0..exponent.significant_bits()).rev() {
+            result = self.mul(&result, &result);
+            if exponent.get_bit(i) {
+                result = self.mul(&result, base);
+            }
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_towers() {
+        validate_towers().unwrap();
+
+        let tower = bls12_381::tower_constants().unwrap();
+        assert_eq!(tower.frobenius_coeff_fp12_c1.len(), 12);
+
+        // a wrong Frobenius coefficient is named with its power
+        let mut wrong = tower.clone();
+        wrong.frobenius_coeff_fp6_c2[5] = wrong.frobenius_coeff_fp6_c2[4].clone();
+        let err = validate_tower(&wrong).unwrap_err();
+        assert_eq!(err.name, "BLS12-381");
+        assert!(err.reason.contains("FROBENIUS_COEFF_FP6_C2[5]"), "{err}");
+
+        // -1 is a non-square modulo p = 3 mod 4, but 4 is a square
+        let mut wrong = tower.clone();
+        wrong.fp2_nonresidue = Integer::from(4);
+        assert!(validate_tower(&wrong).is_err());
+
+        // u + 1 is neither a square nor a cube, but (u + 1)^6 is both
+        let mut wrong = tower.clone();
+        let fp2 = Fp2Arith {
+            p: &tower.modulus,
+            beta: tower.modulus.clone() - 1u32,
+        };
+        let xi = fp2.pow(&fp2.reduce(&tower.fp6_nonresidue), &Integer::from(6));
+        wrong.fp6_nonresidue = (xi.0, xi.1);
+        wrong.frobenius_coeff_fp6_c1.clear();
+        wrong.frobenius_coeff_fp6_c2.clear();
+        wrong.frobenius_coeff_fp12_c1.clear();
+        let err = validate_tower(&wrong).unwrap_err();
+        assert_eq!(err.reason, "the Fp6 non-residue is a cube in Fp2");
+
+        // a composite modulus
+        let mut wrong = tower;
+        wrong.modulus *= 3u32;
+        assert!(validate_tower(&wrong).is_err());
+    }
+}
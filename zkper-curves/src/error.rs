@@ -0,0 +1,56 @@
+use thiserror::Error;
+
+use crate::constant::ConstantError;
+
+/// Errors of the curve layer.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum CurveError {
+    #[error(transparent)]
+    Constant(#[from] ConstantError),
+    /// Bytes that do not decode to a point of the named group.
+    #[error("Invalid {0} point")]
+    InvalidPoint(&'static str),
+    /// An x-coordinate that is not reduced, or for which x^3 + b has no
+    /// square root on the named curve.
+    #[error("Invalid {0} x-coordinate")]
+    InvalidXCoordinate(&'static str),
+    /// Text that is not the canonical hex encoding of a value of the named
+    /// type.
+    #[error("Invalid {0} hex encoding")]
+    InvalidHex(&'static str),
+}
+
+impl From<CurveError> for zkper_base::Error {
+    fn from(e: CurveError) -> Self {
+        zkper_base::Error::Curve(Box::new(e))
+    }
+}
+
+impl From<ConstantError> for zkper_base::Error {
+    fn from(e: ConstantError) -> Self {
+        CurveError::from(e).into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_into_base_error() {
+        let e: zkper_base::Error = CurveError::InvalidPoint("G1").into();
+        assert!(matches!(e, zkper_base::Error::Curve(_)));
+        assert_eq!(
+            e.downcast_ref::<CurveError>(),
+            Some(&CurveError::InvalidPoint("G1"))
+        );
+        assert_eq!(e.to_string(), "Invalid G1 point");
+
+        let constant = ConstantError::new("G1_GENERATOR", "not on the curve");
+        let e: zkper_base::Error = constant.clone().into();
+        assert_eq!(
+            e.downcast_ref::<CurveError>(),
+            Some(&CurveError::Constant(constant))
+        );
+    }
+}
@@ -0,0 +1,197 @@
+//! `LowerHex` and `FromStr` for the field and curve types.
+//!
+//! Every type has one textual form: `0x` followed by the lowercase hex of its
+//! canonical byte encoding, so the width is fixed per type. Field elements
+//! are big-endian, extension fields most significant coefficient first, and
+//! points compressed. Parsing accepts exactly that form and rejects what the
+//! byte decoders reject, and points outside the prime-order subgroup.
+
+use std::{fmt, str::FromStr};
+
+use crate::{
+    curves::bls12_381::{
+        curves::{g1::G1Projective, g1_affine::G1Affine, g2::G2Projective, g2_affine::G2Affine},
+        fields::{fp12::Fp12, fp2::Fp2, fp6::Fp6, target::TargetField},
+        Bls12_381BaseField, Bls12_381ScalarField,
+    },
+    error::CurveError,
+};
+
+/// The lowercase hex digits of `bytes`, without a prefix.
+pub(crate) fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Inverse of `to_hex`, returns None for an odd number of digits or a
+/// character that is not one.
+pub(crate) fn from_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) || !hex.is_ascii() {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).ok())
+        .collect()
+}
+
+pub(crate) fn g1_from_bytes(bytes: &[u8]) -> Option<G1Affine> {
+    G1Affine::from_compressed(bytes).filter(G1Affine::is_torsion_free)
+}
+
+pub(crate) fn g2_from_bytes(bytes: &[u8]) -> Option<G2Affine> {
+    G2Affine::from_compressed(bytes).filter(G2Affine::is_torsion_free)
+}
+
+/// Implements both traits for `$ty` through an encoder to bytes and a decoder
+/// returning `Option`.
+macro_rules! hex_via_bytes {
+    ($ty:ty, $name:literal, $encode:expr, $decode:expr) => {
+        impl fmt::LowerHex for $ty {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                let encode: fn(&$ty) -> _ = $encode;
+                write!(f, "0x{}", to_hex(&encode(self)))
+            }
+        }
+
+        impl FromStr for $ty {
+            type Err = CurveError;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                let decode: fn(&[u8]) -> Option<$ty> = $decode;
+                s.strip_prefix("0x")
+                    .and_then(from_hex)
+                    .and_then(|bytes| decode(&bytes))
+                    .ok_or(CurveError::InvalidHex($name))
+            }
+        }
+    };
+}
+
+hex_via_bytes!(
+    Bls12_381BaseField,
+    "Fp",
+    Bls12_381BaseField::to_bytes,
+    |bytes| Bls12_381BaseField::from_bytes(bytes.try_into().ok()?)
+);
+hex_via_bytes!(
+    Bls12_381ScalarField,
+    "Fr",
+    Bls12_381ScalarField::to_bytes,
+    |bytes| Bls12_381ScalarField::from_bytes(bytes.try_into().ok()?)
+);
+hex_via_bytes!(Fp2, "Fp2", Fp2::to_bytes, Fp2::from_bytes);
+hex_via_bytes!(Fp6, "Fp6", Fp6::to_bytes, Fp6::from_bytes);
+hex_via_bytes!(Fp12, "Fp12", Fp12::to_bytes, Fp12::from_bytes);
+hex_via_bytes!(
+    TargetField,
+    "Gt",
+    TargetField::to_bytes,
+    TargetField::from_bytes
+);
+hex_via_bytes!(G1Affine, "G1", G1Affine::to_compressed, g1_from_bytes);
+hex_via_bytes!(G2Affine, "G2", G2Affine::to_compressed, g2_from_bytes);
+hex_via_bytes!(
+    G1Projective,
+    "G1",
+    |p| p.to_affine().to_compressed(),
+    |bytes| g1_from_bytes(bytes).map(|p| p.to_curve())
+);
+hex_via_bytes!(
+    G2Projective,
+    "G2",
+    |p| p.to_affine().to_compressed(),
+    |bytes| g2_from_bytes(bytes).map(|p| p.to_curve())
+);
+
+#[cfg(test)]
+mod tests {
+    use rug::Integer;
+    use zkper_rand::TestRng;
+
+    use super::*;
+    use crate::traits::field::FieldTrait;
+
+    /// Formats `value`, checks the width and parses it back.
+    fn round_trip<T>(value: &T, bytes: usize)
+    where
+        T: fmt::LowerHex + FromStr<Err = CurveError> + PartialEq + fmt::Debug,
+    {
+        let hex = format!("{:x}", value);
+        assert_eq!(hex.len(), 2 + 2 * bytes);
+        assert!(hex.starts_with("0x"));
+        assert_eq!(&hex.parse::<T>().unwrap(), value);
+    }
+
+    #[test]
+    fn test_round_trips() {
+        let mut rng = TestRng::new();
+        let g1 = G1Projective::random(&mut rng);
+        let g2 = G2Projective::random(&mut rng);
+        let gt = TargetField::random(&mut rng);
+
+        round_trip(&Bls12_381BaseField::from(Integer::from(1)), 48);
+        round_trip(&Bls12_381ScalarField::from(Integer::from(1)), 32);
+        round_trip(
+            &Bls12_381ScalarField::from(Bls12_381ScalarField::random(&mut rng)),
+            32,
+        );
+        round_trip(&gt.0.c0.c1, 96);
+        round_trip(&gt.0.c0, 288);
+        round_trip(&gt.0, 576);
+        round_trip(&gt, 576);
+        round_trip(&g1, 48);
+        round_trip(&g1.to_affine(), 48);
+        round_trip(&G1Affine::identity(), 48);
+        round_trip(&g2, 96);
+        round_trip(&g2.to_affine(), 96);
+        round_trip(&G2Affine::identity(), 96);
+
+        assert_eq!(
+            format!("{:x}", G1Affine::generator()),
+            "0x97f1d3a73197d7942695638c4fa9ac0fc3688c4f9774b905a14e3a3f171bac586c55e83ff97a1aeffb3af00adb22c6bb"
+        );
+        assert_eq!(
+            format!("{:x}", Bls12_381ScalarField::from(Integer::from(0xab))),
+            format!("0x{:0>64}", "ab")
+        );
+    }
+
+    #[test]
+    fn test_rejects_non_canonical_text() {
+        let one = format!("{:x}", Bls12_381ScalarField::from(Integer::from(1)));
+        assert!(one.parse::<Bls12_381ScalarField>().is_ok());
+        // the prefix and the full width are required
+        assert!(one[2..].parse::<Bls12_381ScalarField>().is_err());
+        assert!("0x1".parse::<Bls12_381ScalarField>().is_err());
+        assert!(format!("{}0", one).parse::<Bls12_381ScalarField>().is_err());
+        assert_eq!(
+            one.parse::<Bls12_381BaseField>().unwrap_err(),
+            CurveError::InvalidHex("Fp")
+        );
+
+        // values at or above the modulus
+        let r = format!(
+            "0x{:0>64}",
+            Bls12_381ScalarField::modulus().to_string_radix(16)
+        );
+        assert!(r.parse::<Bls12_381ScalarField>().is_err());
+        let p = format!("0x{}", Bls12_381BaseField::modulus().to_string_radix(16));
+        assert!(p.parse::<Bls12_381BaseField>().is_err());
+        assert!(format!("0x{}", "zz".repeat(96)).parse::<Fp2>().is_err());
+
+        // a point of the curve outside G1
+        let point = (1u32..)
+            .find_map(|x| G1Affine::from_x_coordinate(&Integer::from(x), false).ok())
+            .unwrap();
+        assert!(!point.is_torsion_free());
+        assert_eq!(
+            format!("{:x}", point).parse::<G1Affine>().unwrap_err(),
+            CurveError::InvalidHex("G1")
+        );
+
+        // Fp12 elements outside Gt
+        let two = format!("{:x}", Fp12::one().add(&Fp12::one()));
+        assert!(two.parse::<Fp12>().is_ok());
+        assert!(two.parse::<TargetField>().is_err());
+    }
+}
@@ -1,3 +1,16 @@
+#[cfg(not(any(feature = "backend-rug", feature = "backend-native")))]
+compile_error!("zkper-curves needs at least one of the `backend-rug` or `backend-native` features");
+
 pub mod backends;
+pub mod constant;
+#[cfg(feature = "backend-rug")]
 pub mod curves;
+pub mod error;
+#[cfg(feature = "backend-rug")]
+mod hex;
+#[cfg(feature = "backend-native")]
+pub mod native;
+#[cfg(all(feature = "backend-rug", feature = "serde"))]
+mod serde_impls;
+#[cfg(feature = "backend-rug")]
 pub mod traits;
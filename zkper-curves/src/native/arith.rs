@@ -0,0 +1,244 @@
+//! Multi-precision arithmetic on little-endian `u64` limbs, the building block
+//! of the Montgomery fields in this module.
+//!
+//! Everything is generic over the limb count and `const` where it can be, so
+//! the Montgomery parameters of a modulus are derived at compile time.
+
+use crate::backends::limbs::lt;
+
+/// a + b + carry, returning the sum and the carry out.
+#[inline(always)]
+pub(crate) const fn adc(a: u64, b: u64, carry: u64) -> (u64, u64) {
+    let t = a as u128 + b as u128 + carry as u128;
+    (t as u64, (t >> 64) as u64)
+}
+
+/// a - b - borrow, returning the difference and the borrow out (0 or 1).
+#[inline(always)]
+pub(crate) const fn sbb(a: u64, b: u64, borrow: u64) -> (u64, u64) {
+    let t = (a as u128).wrapping_sub(b as u128 + borrow as u128);
+    (t as u64, (t >> 127) as u64)
+}
+
+/// a + b * c + carry, returning the low word and the high word.
+#[inline(always)]
+pub(crate) const fn mac(a: u64, b: u64, c: u64, carry: u64) -> (u64, u64) {
+    let t = a as u128 + (b as u128) * (c as u128) + carry as u128;
+    (t as u64, (t >> 64) as u64)
+}
+
+pub(crate) const fn is_zero<const N: usize>(a: &[u64; N]) -> bool {
+    let mut i = 0;
+    while i < N {
+        if a[i] != 0 {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+/// a - b, wrapping, and the borrow out.
+pub(crate) const fn sub_with_borrow<const N: usize>(a: &[u64; N], b: &[u64; N]) -> ([u64; N], u64) {
+    let mut out = [0u64; N];
+    let mut borrow = 0;
+    let mut i = 0;
+    while i < N {
+        (out[i], borrow) = sbb(a[i], b[i], borrow);
+        i += 1;
+    }
+    (out, borrow)
+}
+
+/// a + b, wrapping, and the carry out.
+pub(crate) const fn add_with_carry<const N: usize>(a: &[u64; N], b: &[u64; N]) -> ([u64; N], u64) {
+    let mut out = [0u64; N];
+    let mut carry = 0;
+    let mut i = 0;
+    while i < N {
+        (out[i], carry) = adc(a[i], b[i], carry);
+        i += 1;
+    }
+    (out, carry)
+}
+
+/// a + b mod m, for a, b < m.
+pub(crate) const fn add_mod<const N: usize>(a: &[u64; N], b: &[u64; N], m: &[u64; N]) -> [u64; N] {
+    let (sum, carry) = add_with_carry(a, b);
+    if carry != 0 || !lt(&sum, m) {
+        sub_with_borrow(&sum, m).0
+    } else {
+        sum
+    }
+}
+
+/// a - b mod m, for a, b < m.
+pub(crate) const fn sub_mod<const N: usize>(a: &[u64; N], b: &[u64; N], m: &[u64; N]) -> [u64; N] {
+    let (diff, borrow) = sub_with_borrow(a, b);
+    if borrow != 0 {
+        add_with_carry(&diff, m).0
+    } else {
+        diff
+    }
+}
+
+/// a * b * R^-1 mod m with R = 2^(64 N), by coarsely integrated operand
+/// scanning. Needs a * b < m * R, which holds for a < R and b < m, and m
+/// below 2^(64 N - 1).
+pub(crate) const fn mont_mul<const N: usize>(
+    a: &[u64; N],
+    b: &[u64; N],
+    m: &[u64; N],
+    inv: u64,
+) -> [u64; N] {
+    let mut t = [0u64; N];
+    let mut hi = 0u64;
+    let mut i = 0;
+    while i < N {
+        let mut carry = 0;
+        let mut j = 0;
+        while j < N {
+            (t[j], carry) = mac(t[j], a[j], b[i], carry);
+            j += 1;
+        }
+        let (hi_lo, hi_hi) = adc(hi, carry, 0);
+
+        let k = t[0].wrapping_mul(inv);
+        let (_, mut carry) = mac(t[0], k, m[0], 0);
+        let mut j = 1;
+        while j < N {
+            (t[j - 1], carry) = mac(t[j], k, m[j], carry);
+            j += 1;
+        }
+        let (top, carry) = adc(hi_lo, carry, 0);
+        t[N - 1] = top;
+        hi = hi_hi + carry;
+        i += 1;
+    }
+
+    if hi != 0 || !lt(&t, m) {
+        sub_with_borrow(&t, m).0
+    } else {
+        t
+    }
+}
+
+/// -m^-1 mod 2^64, by Newton iteration on the lowest limb.
+pub(crate) const fn mont_inv(m0: u64) -> u64 {
+    let mut x = 1u64;
+    let mut i = 0;
+    while i < 6 {
+        x = x.wrapping_mul(2u64.wrapping_sub(m0.wrapping_mul(x)));
+        i += 1;
+    }
+    x.wrapping_neg()
+}
+
+/// 2^k mod m, by doubling.
+pub(crate) const fn pow2_mod<const N: usize>(k: usize, m: &[u64; N]) -> [u64; N] {
+    let mut out = [0u64; N];
+    out[0] = 1;
+    let mut i = 0;
+    while i < k {
+        out = add_mod(&out, &out, m);
+        i += 1;
+    }
+    out
+}
+
+/// a + small, wrapping.
+pub(crate) const fn add_small<const N: usize>(a: &[u64; N], small: u64) -> [u64; N] {
+    let mut b = [0u64; N];
+    b[0] = small;
+    add_with_carry(a, &b).0
+}
+
+/// a - small, wrapping.
+pub(crate) const fn sub_small<const N: usize>(a: &[u64; N], small: u64) -> [u64; N] {
+    let mut b = [0u64; N];
+    b[0] = small;
+    sub_with_borrow(a, &b).0
+}
+
+/// a >> shift, for shift < 64.
+pub(crate) const fn shr<const N: usize>(a: &[u64; N], shift: u32) -> [u64; N] {
+    let mut out = [0u64; N];
+    let mut i = 0;
+    while i < N {
+        out[i] = a[i] >> shift;
+        if shift > 0 && i + 1 < N {
+            out[i] |= a[i + 1] << (64 - shift);
+        }
+        i += 1;
+    }
+    out
+}
+
+/// The bits of `exponent`, most significant first, without leading zeros.
+pub(crate) fn bits_msb_first(exponent: &[u64]) -> impl Iterator<Item = bool> + '_ {
+    let len = exponent
+        .iter()
+        .rposition(|limb| *limb != 0)
+        .map_or(0, |top| {
+            64 * top + 64 - exponent[top].leading_zeros() as usize
+        });
+    (0..len)
+        .rev()
+        .map(move |bit| (exponent[bit / 64] >> (bit % 64)) & 1 == 1)
+}
+
+/// Big-endian bytes of the limbs.
+pub(crate) fn to_bytes_be<const N: usize, const B: usize>(limbs: &[u64; N]) -> [u8; B] {
+    let mut out = [0u8; B];
+    for (i, byte) in out.iter_mut().rev().enumerate() {
+        *byte = (limbs[i / 8] >> (8 * (i % 8))) as u8;
+    }
+    out
+}
+
+/// Limbs of big-endian bytes, at most 8 N of them.
+pub(crate) fn from_bytes_be<const N: usize>(bytes: &[u8]) -> [u64; N] {
+    debug_assert!(bytes.len() <= 8 * N);
+    let mut out = [0u64; N];
+    for (i, byte) in bytes.iter().rev().enumerate() {
+        out[i / 8] |= (*byte as u64) << (8 * (i % 8));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mont_mul() {
+        // m = 2^127 - 1, R = 2^128
+        let m = [u64::MAX, u64::MAX >> 1];
+        let inv = mont_inv(m[0]);
+        assert_eq!(m[0].wrapping_mul(inv), u64::MAX);
+
+        let r2 = pow2_mod(256, &m);
+        let to_mont = |a: [u64; 2]| mont_mul(&a, &r2, &m, inv);
+        let from_mont = |a: [u64; 2]| mont_mul(&a, &[1, 0], &m, inv);
+
+        let a = [0x1234_5678_9abc_def0, 0x0fed_cba9_8765_4321];
+        let b = [3, 0];
+        assert_eq!(from_mont(to_mont(a)), a);
+        let product = from_mont(mont_mul(&to_mont(a), &to_mont(b), &m, inv));
+        assert_eq!(product, add_mod(&add_mod(&a, &a, &m), &a, &m));
+    }
+
+    #[test]
+    fn test_bytes_and_bits() {
+        let limbs = [0x0102_0304_0506_0708, 0x0a0b];
+        let bytes: [u8; 10] = to_bytes_be(&limbs);
+        assert_eq!(bytes, [0x0a, 0x0b, 1, 2, 3, 4, 5, 6, 7, 8]);
+        assert_eq!(from_bytes_be::<2>(&bytes), limbs);
+
+        let bits: Vec<bool> = bits_msb_first(&[0b101, 0]).collect();
+        assert_eq!(bits, [true, false, true]);
+        assert_eq!(bits_msb_first(&[0, 0]).count(), 0);
+        assert_eq!(shr(&[0, 1], 2), [1 << 62, 0]);
+        assert_eq!(sub_small(&add_small(&[u64::MAX, 0], 1), 1), [u64::MAX, 0]);
+    }
+}
@@ -0,0 +1,221 @@
+//! Short Weierstrass arithmetic on y^2 = x^3 + b, shared by G1 over Fp and
+//! G2 over Fp2.
+
+use super::{arith::bits_msb_first, field::Field, scalar::Scalar};
+
+/// A field with the curve coefficient b of its BLS12-381 curve.
+pub trait CurveField: Field {
+    /// b of y^2 = x^3 + b.
+    fn b() -> Self;
+}
+
+/// A point in affine coordinates.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Affine<F> {
+    pub x: F,
+    pub y: F,
+    pub infinity: bool,
+}
+
+/// A point in Jacobian coordinates, (X / Z^2, Y / Z^3), the identity for Z = 0.
+#[derive(Clone, Copy, Debug)]
+pub struct Jacobian<F> {
+    pub x: F,
+    pub y: F,
+    pub z: F,
+}
+
+impl<F: CurveField> Affine<F> {
+    pub fn identity() -> Self {
+        Self {
+            x: F::zero(),
+            y: F::one(),
+            infinity: true,
+        }
+    }
+
+    pub fn is_identity(&self) -> bool {
+        self.infinity
+    }
+
+    /// Whether y^2 = x^3 + b. The identity is on the curve.
+    pub fn is_on_curve(&self) -> bool {
+        self.infinity || self.y.square() == self.x.square() * self.x + F::b()
+    }
+
+    /// Whether r * self is the identity, that is the point is in the
+    /// prime-order subgroup.
+    pub fn is_torsion_free(&self) -> bool {
+        self.to_jacobian().mul_limbs(&Scalar::MODULUS).is_identity()
+    }
+
+    pub fn neg(&self) -> Self {
+        Self {
+            y: if self.infinity { self.y } else { -self.y },
+            ..*self
+        }
+    }
+
+    pub fn to_jacobian(&self) -> Jacobian<F> {
+        if self.infinity {
+            return Jacobian::identity();
+        }
+        Jacobian {
+            x: self.x,
+            y: self.y,
+            z: F::one(),
+        }
+    }
+
+    pub fn mul(&self, scalar: &Scalar) -> Jacobian<F> {
+        self.to_jacobian().mul(scalar)
+    }
+}
+
+impl<F: CurveField> Jacobian<F> {
+    pub fn identity() -> Self {
+        Self {
+            x: F::zero(),
+            y: F::one(),
+            z: F::zero(),
+        }
+    }
+
+    pub fn is_identity(&self) -> bool {
+        self.z.is_zero()
+    }
+
+    pub fn to_affine(&self) -> Affine<F> {
+        match self.z.invert() {
+            None => Affine::identity(),
+            Some(z_inv) => {
+                let z_inv2 = z_inv.square();
+                Affine {
+                    x: self.x * z_inv2,
+                    y: self.y * z_inv2 * z_inv,
+                    infinity: false,
+                }
+            }
+        }
+    }
+
+    pub fn neg(&self) -> Self {
+        Self {
+            y: -self.y,
+            ..*self
+        }
+    }
+
+    /// 2 * self, by dbl-2009-l for a = 0.
+    pub fn double(&self) -> Self {
+        if self.is_identity() {
+            return *self;
+        }
+
+        let a = self.x.square();
+        let b = self.y.square();
+        let c = b.square();
+        let d = ((self.x + b).square() - a - c).double();
+        let e = a.double() + a;
+        let f = e.square();
+        let x = f - d.double();
+        let y = e * (d - x) - c.double().double().double();
+        let z = (self.y * self.z).double();
+        Self { x, y, z }
+    }
+
+    /// self + other, by add-2007-bl.
+    pub fn add(&self, other: &Self) -> Self {
+        if self.is_identity() {
+            return *other;
+        }
+        if other.is_identity() {
+            return *self;
+        }
+
+        let z1z1 = self.z.square();
+        let z2z2 = other.z.square();
+        let u1 = self.x * z2z2;
+        let u2 = other.x * z1z1;
+        let s1 = self.y * other.z * z2z2;
+        let s2 = other.y * self.z * z1z1;
+
+        let h = u2 - u1;
+        let r = (s2 - s1).double();
+        if h.is_zero() {
+            return if r.is_zero() {
+                self.double()
+            } else {
+                Self::identity()
+            };
+        }
+
+        let i = h.double().square();
+        let j = h * i;
+        let v = u1 * i;
+        let x = r.square() - j - v.double();
+        let y = r * (v - x) - (s1 * j).double();
+        let z = ((self.z + other.z).square() - z1z1 - z2z2) * h;
+        Self { x, y, z }
+    }
+
+    pub fn mul(&self, scalar: &Scalar) -> Self {
+        self.mul_limbs(&scalar.to_canonical())
+    }
+
+    /// self * k for little-endian limbs k, double-and-add from the top bit.
+    pub fn mul_limbs(&self, k: &[u64]) -> Self {
+        let mut out = Self::identity();
+        for bit in bits_msb_first(k) {
+            out = out.double();
+            if bit {
+                out = out.add(self);
+            }
+        }
+        out
+    }
+}
+
+impl<F: CurveField> PartialEq for Jacobian<F> {
+    fn eq(&self, other: &Self) -> bool {
+        self.to_affine() == other.to_affine()
+    }
+}
+
+impl<F: CurveField> Eq for Jacobian<F> {}
+
+/// Flag set in the most significant byte of a compressed encoding.
+pub(crate) const COMPRESSION_FLAG: u8 = 0x80;
+
+/// Flag set in the most significant byte of an encoded point at infinity.
+pub(crate) const INFINITY_FLAG: u8 = 0x40;
+
+/// Flag set in a compressed encoding when y is the lexicographically larger
+/// of the two roots.
+pub(crate) const SORT_FLAG: u8 = 0x20;
+
+/// Reads the flags of an encoding of `len` bytes. Returns None for a bad
+/// length or a wrong compression flag, Some(None) for a well-formed
+/// identity and Some(Some(sort flag)) for any other point.
+pub(crate) fn decode_flags(bytes: &[u8], len: usize, compressed: bool) -> Option<Option<bool>> {
+    if bytes.len() != len || (bytes[0] & COMPRESSION_FLAG != 0) != compressed {
+        return None;
+    }
+
+    let flags = bytes[0];
+    if flags & INFINITY_FLAG != 0 {
+        let expected = if compressed {
+            COMPRESSION_FLAG | INFINITY_FLAG
+        } else {
+            INFINITY_FLAG
+        };
+        let rest_is_zero = flags == expected && bytes[1..].iter().all(|byte| *byte == 0);
+        return rest_is_zero.then_some(None);
+    }
+
+    let sort = flags & SORT_FLAG != 0;
+    if !compressed && sort {
+        return None;
+    }
+    Some(Some(sort))
+}
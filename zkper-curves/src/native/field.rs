@@ -0,0 +1,187 @@
+//! Prime fields in Montgomery form on fixed-size limbs, and the `Field`
+//! operations the curve arithmetic is written against.
+
+use std::ops::{Add, Mul, Neg, Sub};
+
+/// The field operations `Jacobian` and the pairing need from Fp and Fp2.
+pub trait Field:
+    Copy
+    + Eq
+    + std::fmt::Debug
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Neg<Output = Self>
+{
+    fn zero() -> Self;
+    fn one() -> Self;
+    fn is_zero(&self) -> bool;
+    fn square(&self) -> Self;
+    fn double(&self) -> Self;
+    /// The multiplicative inverse, None for zero.
+    fn invert(&self) -> Option<Self>;
+}
+
+/// Defines a prime field of `$limbs` 64-bit limbs, `$bytes` bytes encoded,
+/// with elements kept in Montgomery form. The Montgomery parameters are
+/// derived from `$modulus` at compile time.
+macro_rules! montgomery_field {
+    ($(#[$attr:meta])* $name:ident, $limbs:literal, $bytes:literal, $modulus:expr) => {
+        $(#[$attr])*
+        #[derive(Clone, Copy, PartialEq, Eq)]
+        pub struct $name(pub(crate) [u64; $limbs]);
+
+        impl $name {
+            /// The modulus, little-endian limbs.
+            pub const MODULUS: [u64; $limbs] = $modulus;
+            /// Size of the big-endian encoding.
+            pub const BYTES: usize = $bytes;
+            const INV: u64 = $crate::native::arith::mont_inv(Self::MODULUS[0]);
+            /// R mod m, the Montgomery form of one.
+            const R: [u64; $limbs] = $crate::native::arith::pow2_mod(64 * $limbs, &Self::MODULUS);
+            const R2: [u64; $limbs] =
+                $crate::native::arith::pow2_mod(2 * 64 * $limbs, &Self::MODULUS);
+            const R3: [u64; $limbs] =
+                $crate::native::arith::pow2_mod(3 * 64 * $limbs, &Self::MODULUS);
+
+            pub const ZERO: Self = Self([0; $limbs]);
+            pub const ONE: Self = Self(Self::R);
+
+            /// The element of canonical limbs below the modulus.
+            pub const fn from_canonical(limbs: [u64; $limbs]) -> Option<Self> {
+                if !$crate::backends::limbs::lt(&limbs, &Self::MODULUS) {
+                    return None;
+                }
+                Some(Self($crate::native::arith::mont_mul(
+                    &limbs,
+                    &Self::R2,
+                    &Self::MODULUS,
+                    Self::INV,
+                )))
+            }
+
+            pub const fn from_u64(value: u64) -> Self {
+                let mut limbs = [0u64; $limbs];
+                limbs[0] = value;
+                Self($crate::native::arith::mont_mul(
+                    &limbs,
+                    &Self::R2,
+                    &Self::MODULUS,
+                    Self::INV,
+                ))
+            }
+
+            /// The canonical limbs, out of Montgomery form.
+            pub const fn to_canonical(&self) -> [u64; $limbs] {
+                let mut one = [0u64; $limbs];
+                one[0] = 1;
+                $crate::native::arith::mont_mul(&self.0, &one, &Self::MODULUS, Self::INV)
+            }
+
+            /// The value of `bytes`, big-endian, reduced mod m. Takes up to
+            /// twice the limbs of the field.
+            pub fn from_bytes_wide(bytes: &[u8]) -> Self {
+                assert!(bytes.len() <= 2 * 8 * $limbs, "too many bytes to reduce");
+                let split = bytes.len().saturating_sub(8 * $limbs);
+                let hi = $crate::native::arith::from_bytes_be::<$limbs>(&bytes[..split]);
+                let lo = $crate::native::arith::from_bytes_be::<$limbs>(&bytes[split..]);
+                // lo * R + hi * R^2 is the Montgomery form of lo + hi * R
+                let mul = |a, b| $crate::native::arith::mont_mul(a, b, &Self::MODULUS, Self::INV);
+                Self(mul(&lo, &Self::R2)) + Self(mul(&hi, &Self::R3))
+            }
+
+            /// Inverse of `to_bytes_be`, None if the value is not reduced.
+            pub fn from_bytes_be(bytes: &[u8]) -> Option<Self> {
+                if bytes.len() != $bytes {
+                    return None;
+                }
+                Self::from_canonical($crate::native::arith::from_bytes_be(bytes))
+            }
+
+            /// Big-endian encoding of the canonical value.
+            pub fn to_bytes_be(&self) -> [u8; $bytes] {
+                $crate::native::arith::to_bytes_be(&self.to_canonical())
+            }
+
+            /// self^exponent for little-endian exponent limbs.
+            pub fn pow(&self, exponent: &[u64]) -> Self {
+                let mut out = Self::ONE;
+                for bit in $crate::native::arith::bits_msb_first(exponent) {
+                    out = out * out;
+                    if bit {
+                        out = out * *self;
+                    }
+                }
+                out
+            }
+        }
+
+        impl std::fmt::Debug for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(f, "0x")?;
+                for byte in self.to_bytes_be() {
+                    write!(f, "{byte:02x}")?;
+                }
+                Ok(())
+            }
+        }
+
+        impl std::ops::Add for $name {
+            type Output = Self;
+            fn add(self, rhs: Self) -> Self {
+                Self($crate::native::arith::add_mod(&self.0, &rhs.0, &Self::MODULUS))
+            }
+        }
+
+        impl std::ops::Sub for $name {
+            type Output = Self;
+            fn sub(self, rhs: Self) -> Self {
+                Self($crate::native::arith::sub_mod(&self.0, &rhs.0, &Self::MODULUS))
+            }
+        }
+
+        impl std::ops::Mul for $name {
+            type Output = Self;
+            fn mul(self, rhs: Self) -> Self {
+                Self($crate::native::arith::mont_mul(
+                    &self.0,
+                    &rhs.0,
+                    &Self::MODULUS,
+                    Self::INV,
+                ))
+            }
+        }
+
+        impl std::ops::Neg for $name {
+            type Output = Self;
+            fn neg(self) -> Self {
+                Self::ZERO - self
+            }
+        }
+
+        impl $crate::native::field::Field for $name {
+            fn zero() -> Self {
+                Self::ZERO
+            }
+            fn one() -> Self {
+                Self::ONE
+            }
+            fn is_zero(&self) -> bool {
+                $crate::native::arith::is_zero(&self.0)
+            }
+            fn square(&self) -> Self {
+                *self * *self
+            }
+            fn double(&self) -> Self {
+                *self + *self
+            }
+            fn invert(&self) -> Option<Self> {
+                // Fermat: self^(m - 2)
+                const EXPONENT: [u64; $limbs] = $crate::native::arith::sub_small(&$name::MODULUS, 2);
+                (!self.is_zero()).then(|| self.pow(&EXPONENT))
+            }
+        }
+    };
+}
+
+pub(crate) use montgomery_field;
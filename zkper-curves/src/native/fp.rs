@@ -0,0 +1,65 @@
+//! The base field Fp of BLS12-381.
+
+use super::{
+    arith::{add_small, shr, sub_small},
+    field::{montgomery_field, Field},
+};
+use crate::backends::limbs::{from_hex, lt};
+
+montgomery_field!(
+    /// An element of Fp, in Montgomery form.
+    Fp,
+    6,
+    48,
+    from_hex(
+        "0x1a0111ea397fe69a4b1ba7b6434bacd764774b84f38512bf6730d2a0f6b0f6241eabfffeb153ffffb9feffffffffaaab"
+    )
+);
+
+/// (p + 1) / 4, the exponent of the square root since p = 3 mod 4.
+const SQRT_EXPONENT: [u64; 6] = shr(&add_small(&Fp::MODULUS, 1), 2);
+
+/// (p - 1) / 2, the largest value that is not lexicographically largest.
+pub(crate) const HALF_MODULUS: [u64; 6] = shr(&sub_small(&Fp::MODULUS, 1), 1);
+
+impl Fp {
+    /// A square root, None if the element is not a square.
+    pub fn sqrt(&self) -> Option<Self> {
+        let root = self.pow(&SQRT_EXPONENT);
+        (root.square() == *self).then_some(root)
+    }
+
+    /// Whether the canonical value is above (p - 1) / 2, the sort order of
+    /// compressed points.
+    pub fn lexicographically_largest(&self) -> bool {
+        lt(&HALF_MODULUS, &self.to_canonical())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fp_arithmetic() {
+        let two = Fp::from_u64(2);
+        let three = Fp::from_u64(3);
+        assert_eq!(two + three, Fp::from_u64(5));
+        assert_eq!(two - three, -Fp::ONE);
+        assert_eq!(two * three, Fp::from_u64(6));
+        assert_eq!(three.invert().unwrap() * three, Fp::ONE);
+        assert_eq!(Fp::ZERO.invert(), None);
+
+        let four = Fp::from_u64(4);
+        let root = four.sqrt().unwrap();
+        assert!(root == two || root == -two);
+        // -1 is not a square for p = 3 mod 4
+        assert_eq!((-Fp::ONE).sqrt(), None);
+
+        assert_eq!(Fp::from_bytes_be(&two.to_bytes_be()), Some(two));
+        let modulus: [u8; 48] = super::super::arith::to_bytes_be(&Fp::MODULUS);
+        assert_eq!(Fp::from_bytes_be(&modulus), None);
+        assert!((-Fp::ONE).lexicographically_largest());
+        assert!(!Fp::ONE.lexicographically_largest());
+    }
+}
@@ -0,0 +1,130 @@
+//! Fp12 = Fp6[w] / (w^2 - v), the field of the pairing values.
+
+use std::ops::{Add, Mul, Neg, Sub};
+
+use super::{arith::bits_msb_first, field::Field, fp::Fp, fp6::Fp6};
+
+/// c0 + c1 * w.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Fp12 {
+    pub c0: Fp6,
+    pub c1: Fp6,
+}
+
+impl Fp12 {
+    pub const fn new(c0: Fp6, c1: Fp6) -> Self {
+        Self { c0, c1 }
+    }
+
+    /// c1 || c0, the twelve Fp coefficients most significant first, like
+    /// `crate::curves::bls12_381::fields::fp12::Fp12::to_bytes`.
+    pub fn to_bytes(&self) -> [u8; 12 * Fp::BYTES] {
+        let mut bytes = [0u8; 12 * Fp::BYTES];
+        bytes[..6 * Fp::BYTES].copy_from_slice(&self.c1.to_bytes());
+        bytes[6 * Fp::BYTES..].copy_from_slice(&self.c0.to_bytes());
+        bytes
+    }
+
+    /// self^(p^6), which maps w to -w.
+    pub fn conjugate(&self) -> Self {
+        Self::new(self.c0, -self.c1)
+    }
+
+    /// self^exponent for little-endian exponent limbs.
+    pub fn pow(&self, exponent: &[u64]) -> Self {
+        let mut out = Self::one();
+        for bit in bits_msb_first(exponent) {
+            out = out.square();
+            if bit {
+                out = out * *self;
+            }
+        }
+        out
+    }
+}
+
+impl Add for Fp12 {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self {
+        Self::new(self.c0 + rhs.c0, self.c1 + rhs.c1)
+    }
+}
+
+impl Sub for Fp12 {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self {
+        Self::new(self.c0 - rhs.c0, self.c1 - rhs.c1)
+    }
+}
+
+impl Mul for Fp12 {
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self {
+        // Karatsuba with w^2 = v
+        let v0 = self.c0 * rhs.c0;
+        let v1 = self.c1 * rhs.c1;
+        let cross = (self.c0 + self.c1) * (rhs.c0 + rhs.c1);
+        Self::new(v0 + v1.mul_by_nonresidue(), cross - v0 - v1)
+    }
+}
+
+impl Neg for Fp12 {
+    type Output = Self;
+    fn neg(self) -> Self {
+        Self::new(-self.c0, -self.c1)
+    }
+}
+
+impl Field for Fp12 {
+    fn zero() -> Self {
+        Self::new(Fp6::zero(), Fp6::zero())
+    }
+    fn one() -> Self {
+        Self::new(Fp6::one(), Fp6::zero())
+    }
+    fn is_zero(&self) -> bool {
+        self.c0.is_zero() && self.c1.is_zero()
+    }
+    fn square(&self) -> Self {
+        *self * *self
+    }
+    fn double(&self) -> Self {
+        *self + *self
+    }
+    fn invert(&self) -> Option<Self> {
+        // (c0 - c1 w) / (c0^2 - v c1^2)
+        let norm = self.c0.square() - self.c1.square().mul_by_nonresidue();
+        norm.invert()
+            .map(|inv| Self::new(self.c0 * inv, -(self.c1 * inv)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::native::fp2::Fp2;
+
+    fn element(seed: u64) -> Fp12 {
+        let fp2 = |i: u64| Fp2::new(Fp::from_u64(seed + i), Fp::from_u64(seed * i + 1));
+        Fp12::new(
+            Fp6::new(fp2(1), fp2(2), fp2(3)),
+            Fp6::new(fp2(4), fp2(5), fp2(6)),
+        )
+    }
+
+    #[test]
+    fn test_fp12_arithmetic() {
+        let (a, b, c) = (element(3), element(8), element(21));
+        assert_eq!(a * b, b * a);
+        assert_eq!((a * b) * c, a * (b * c));
+        assert_eq!(a * (b + c), a * b + a * c);
+        assert_eq!(a.invert().unwrap() * a, Fp12::one());
+        assert_eq!(a.pow(&[5]), a * a * a * a * a);
+
+        // w^2 = v and v^3 = ξ, so w^6 = u + 1
+        let w = Fp12::new(Fp6::zero(), Fp6::one());
+        let xi = Fp2::new(Fp::ONE, Fp::ONE);
+        let expected = Fp12::new(Fp6::new(xi, Fp2::zero(), Fp2::zero()), Fp6::zero());
+        assert_eq!(w.pow(&[6]), expected);
+    }
+}
@@ -0,0 +1,175 @@
+//! Fp2 = Fp[u] / (u^2 + 1).
+
+use std::ops::{Add, Mul, Neg, Sub};
+
+use super::{
+    arith::{bits_msb_first, shr, sub_small},
+    field::Field,
+    fp::Fp,
+};
+
+/// (p - 3) / 4, see `Fp2::sqrt`.
+const SQRT_EXPONENT_1: [u64; 6] = shr(&sub_small(&Fp::MODULUS, 3), 2);
+
+/// (p - 1) / 2, see `Fp2::sqrt`.
+const SQRT_EXPONENT_2: [u64; 6] = shr(&sub_small(&Fp::MODULUS, 1), 1);
+
+/// c0 + c1 * u.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Fp2 {
+    pub c0: Fp,
+    pub c1: Fp,
+}
+
+impl Fp2 {
+    pub const fn new(c0: Fp, c1: Fp) -> Self {
+        Self { c0, c1 }
+    }
+
+    /// c1 || c0, each big-endian.
+    pub fn to_bytes(&self) -> [u8; 2 * Fp::BYTES] {
+        let mut bytes = [0u8; 2 * Fp::BYTES];
+        bytes[..Fp::BYTES].copy_from_slice(&self.c1.to_bytes_be());
+        bytes[Fp::BYTES..].copy_from_slice(&self.c0.to_bytes_be());
+        bytes
+    }
+
+    /// Inverse of `to_bytes`, None for non-canonical encodings.
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() != 2 * Fp::BYTES {
+            return None;
+        }
+        Some(Self::new(
+            Fp::from_bytes_be(&bytes[Fp::BYTES..])?,
+            Fp::from_bytes_be(&bytes[..Fp::BYTES])?,
+        ))
+    }
+
+    /// Multiplies by the non-residue ξ = u + 1 of Fp6.
+    pub fn mul_by_nonresidue(&self) -> Self {
+        Self::new(self.c0 - self.c1, self.c0 + self.c1)
+    }
+
+    /// self^exponent for little-endian exponent limbs.
+    pub fn pow(&self, exponent: &[u64]) -> Self {
+        let mut out = Self::one();
+        for bit in bits_msb_first(exponent) {
+            out = out.square();
+            if bit {
+                out = out * *self;
+            }
+        }
+        out
+    }
+
+    /// A square root, None if the element is not a square. Algorithm 9 of
+    /// <https://eprint.iacr.org/2012/685.pdf>, for p = 3 mod 4.
+    pub fn sqrt(&self) -> Option<Self> {
+        let a1 = self.pow(&SQRT_EXPONENT_1);
+        let alpha = a1.square() * *self;
+        let x0 = a1 * *self;
+
+        let root = if alpha == -Self::one() {
+            // multiply by u
+            Self::new(-x0.c1, x0.c0)
+        } else {
+            (alpha + Self::one()).pow(&SQRT_EXPONENT_2) * x0
+        };
+
+        (root.square() == *self).then_some(root)
+    }
+
+    /// The sort order of compressed G2 points: c1 is lexicographically
+    /// largest, or c1 is zero and c0 is.
+    pub fn lexicographically_largest(&self) -> bool {
+        self.c1.lexicographically_largest()
+            || (self.c1.is_zero() && self.c0.lexicographically_largest())
+    }
+}
+
+impl Add for Fp2 {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self {
+        Self::new(self.c0 + rhs.c0, self.c1 + rhs.c1)
+    }
+}
+
+impl Sub for Fp2 {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self {
+        Self::new(self.c0 - rhs.c0, self.c1 - rhs.c1)
+    }
+}
+
+impl Mul for Fp2 {
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self {
+        // Karatsuba: (a0 + a1 u)(b0 + b1 u) with u^2 = -1
+        let v0 = self.c0 * rhs.c0;
+        let v1 = self.c1 * rhs.c1;
+        let cross = (self.c0 + self.c1) * (rhs.c0 + rhs.c1);
+        Self::new(v0 - v1, cross - v0 - v1)
+    }
+}
+
+impl Neg for Fp2 {
+    type Output = Self;
+    fn neg(self) -> Self {
+        Self::new(-self.c0, -self.c1)
+    }
+}
+
+impl Field for Fp2 {
+    fn zero() -> Self {
+        Self::new(Fp::ZERO, Fp::ZERO)
+    }
+    fn one() -> Self {
+        Self::new(Fp::ONE, Fp::ZERO)
+    }
+    fn is_zero(&self) -> bool {
+        self.c0.is_zero() && self.c1.is_zero()
+    }
+    fn square(&self) -> Self {
+        // (a0 + a1)(a0 - a1) + 2 a0 a1 u
+        let product = self.c0 * self.c1;
+        Self::new((self.c0 + self.c1) * (self.c0 - self.c1), product.double())
+    }
+    fn double(&self) -> Self {
+        *self + *self
+    }
+    fn invert(&self) -> Option<Self> {
+        // (a0 - a1 u) / (a0^2 + a1^2)
+        let norm = self.c0.square() + self.c1.square();
+        norm.invert()
+            .map(|inv| Self::new(self.c0 * inv, -(self.c1 * inv)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fp2_arithmetic() {
+        let a = Fp2::new(Fp::from_u64(3), Fp::from_u64(5));
+        let b = Fp2::new(Fp::from_u64(7), Fp::from_u64(11));
+        // (3 + 5u)(7 + 11u) = 21 - 55 + (33 + 35)u
+        assert_eq!(a * b, Fp2::new(-Fp::from_u64(34), Fp::from_u64(68)));
+        assert_eq!(a.square(), a * a);
+        assert_eq!(a.invert().unwrap() * a, Fp2::one());
+        assert_eq!(Fp2::zero().invert(), None);
+
+        let square = a.square();
+        let root = square.sqrt().unwrap();
+        assert!(root == a || root == -a);
+        // u = (1 + u)^2 / 2 is a square, so is -1 = u^2
+        let u = Fp2::new(Fp::ZERO, Fp::ONE);
+        assert_eq!(u.sqrt().unwrap().square(), u);
+        assert_eq!((-Fp2::one()).sqrt().unwrap().square(), -Fp2::one());
+
+        // ξ is not a square in Fp2, so x^6 - ξ stays irreducible
+        let xi = Fp2::one().mul_by_nonresidue();
+        assert_eq!(xi, Fp2::new(Fp::ONE, Fp::ONE));
+        assert_eq!(xi.sqrt(), None);
+    }
+}
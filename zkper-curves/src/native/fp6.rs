@@ -0,0 +1,95 @@
+//! Fp6 = Fp2[v] / (v^3 - ξ) with ξ = u + 1.
+
+use std::ops::{Add, Mul, Neg, Sub};
+
+use super::{field::Field, fp::Fp, fp2::Fp2};
+
+/// c0 + c1 * v + c2 * v^2.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Fp6 {
+    pub c0: Fp2,
+    pub c1: Fp2,
+    pub c2: Fp2,
+}
+
+impl Fp6 {
+    pub const fn new(c0: Fp2, c1: Fp2, c2: Fp2) -> Self {
+        Self { c0, c1, c2 }
+    }
+
+    /// c2 || c1 || c0, see `Fp2::to_bytes`.
+    pub fn to_bytes(&self) -> [u8; 6 * Fp::BYTES] {
+        let mut bytes = [0u8; 6 * Fp::BYTES];
+        for (chunk, coeff) in bytes
+            .chunks_mut(2 * Fp::BYTES)
+            .zip([&self.c2, &self.c1, &self.c0])
+        {
+            chunk.copy_from_slice(&coeff.to_bytes());
+        }
+        bytes
+    }
+
+    /// Multiplies by v, the non-residue of Fp12.
+    pub fn mul_by_nonresidue(&self) -> Self {
+        Self::new(self.c2.mul_by_nonresidue(), self.c0, self.c1)
+    }
+}
+
+impl Add for Fp6 {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self {
+        Self::new(self.c0 + rhs.c0, self.c1 + rhs.c1, self.c2 + rhs.c2)
+    }
+}
+
+impl Sub for Fp6 {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self {
+        Self::new(self.c0 - rhs.c0, self.c1 - rhs.c1, self.c2 - rhs.c2)
+    }
+}
+
+impl Mul for Fp6 {
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self {
+        let (a, b) = (self, rhs);
+        Self::new(
+            a.c0 * b.c0 + (a.c1 * b.c2 + a.c2 * b.c1).mul_by_nonresidue(),
+            a.c0 * b.c1 + a.c1 * b.c0 + (a.c2 * b.c2).mul_by_nonresidue(),
+            a.c0 * b.c2 + a.c1 * b.c1 + a.c2 * b.c0,
+        )
+    }
+}
+
+impl Neg for Fp6 {
+    type Output = Self;
+    fn neg(self) -> Self {
+        Self::new(-self.c0, -self.c1, -self.c2)
+    }
+}
+
+impl Field for Fp6 {
+    fn zero() -> Self {
+        Self::new(Fp2::zero(), Fp2::zero(), Fp2::zero())
+    }
+    fn one() -> Self {
+        Self::new(Fp2::one(), Fp2::zero(), Fp2::zero())
+    }
+    fn is_zero(&self) -> bool {
+        self.c0.is_zero() && self.c1.is_zero() && self.c2.is_zero()
+    }
+    fn square(&self) -> Self {
+        *self * *self
+    }
+    fn double(&self) -> Self {
+        *self + *self
+    }
+    fn invert(&self) -> Option<Self> {
+        let t0 = self.c0.square() - (self.c1 * self.c2).mul_by_nonresidue();
+        let t1 = self.c2.square().mul_by_nonresidue() - self.c0 * self.c1;
+        let t2 = self.c1.square() - self.c0 * self.c2;
+        let norm = self.c0 * t0 + (self.c2 * t1 + self.c1 * t2).mul_by_nonresidue();
+        norm.invert()
+            .map(|inv| Self::new(t0 * inv, t1 * inv, t2 * inv))
+    }
+}
@@ -0,0 +1,159 @@
+//! G1, the prime-order subgroup of E(Fp): y^2 = x^3 + 4.
+
+use super::{
+    curve::{
+        decode_flags, Affine, CurveField, Jacobian, COMPRESSION_FLAG, INFINITY_FLAG, SORT_FLAG,
+    },
+    field::Field,
+    fp::Fp,
+};
+use crate::backends::limbs::from_hex;
+
+pub type G1Affine = Affine<Fp>;
+pub type G1Projective = Jacobian<Fp>;
+
+/// Size of an uncompressed G1 point: x || y, each big-endian.
+pub const G1_UNCOMPRESSED_BYTES: usize = 2 * Fp::BYTES;
+
+/// Size of a compressed G1 point: x with the flags in its top bits.
+pub const G1_COMPRESSED_BYTES: usize = Fp::BYTES;
+
+impl CurveField for Fp {
+    fn b() -> Self {
+        Fp::from_u64(4)
+    }
+}
+
+impl G1Affine {
+    pub fn generator() -> Self {
+        let coordinate = |hex| Fp::from_canonical(from_hex(hex)).expect("reduced coordinate");
+        Self {
+            x: coordinate(
+                "0x17f1d3a73197d7942695638c4fa9ac0fc3688c4f9774b905a14e3a3f171bac586c55e83ff97a1aeffb3af00adb22c6bb",
+            ),
+            y: coordinate(
+                "0x08b3f481e3aaa0f1a09e30ed741d8ae4fcf5e095d5d00af600db18cb2c04b3edd03cc744a2888ae40caa232946c5e7e1",
+            ),
+            infinity: false,
+        }
+    }
+
+    /// x || y, big-endian, or `INFINITY_FLAG` followed by zeros.
+    pub fn to_uncompressed(&self) -> [u8; G1_UNCOMPRESSED_BYTES] {
+        let mut bytes = [0u8; G1_UNCOMPRESSED_BYTES];
+        if self.infinity {
+            bytes[0] = INFINITY_FLAG;
+            return bytes;
+        }
+
+        bytes[..Fp::BYTES].copy_from_slice(&self.x.to_bytes_be());
+        bytes[Fp::BYTES..].copy_from_slice(&self.y.to_bytes_be());
+        bytes
+    }
+
+    /// Inverse of `to_uncompressed`. Returns None for non-canonical
+    /// encodings and points that are not on the curve.
+    pub fn from_uncompressed(bytes: &[u8]) -> Option<Self> {
+        if decode_flags(bytes, G1_UNCOMPRESSED_BYTES, false)?.is_none() {
+            return Some(Self::identity());
+        }
+
+        let point = Self {
+            x: Fp::from_bytes_be(&bytes[..Fp::BYTES])?,
+            y: Fp::from_bytes_be(&bytes[Fp::BYTES..])?,
+            infinity: false,
+        };
+        point.is_on_curve().then_some(point)
+    }
+
+    /// x with `COMPRESSION_FLAG` set, and `SORT_FLAG` set if y is the larger
+    /// root.
+    pub fn to_compressed(&self) -> [u8; G1_COMPRESSED_BYTES] {
+        let mut bytes = [0u8; G1_COMPRESSED_BYTES];
+        if self.infinity {
+            bytes[0] = COMPRESSION_FLAG | INFINITY_FLAG;
+            return bytes;
+        }
+
+        bytes.copy_from_slice(&self.x.to_bytes_be());
+        bytes[0] |= COMPRESSION_FLAG;
+        if self.y.lexicographically_largest() {
+            bytes[0] |= SORT_FLAG;
+        }
+        bytes
+    }
+
+    /// Inverse of `to_compressed`, recovering y with a square root. Returns
+    /// None for non-canonical encodings and x without a point on the curve.
+    pub fn from_compressed(bytes: &[u8]) -> Option<Self> {
+        let Some(sort) = decode_flags(bytes, G1_COMPRESSED_BYTES, true)? else {
+            return Some(Self::identity());
+        };
+
+        let mut x_bytes = [0u8; G1_COMPRESSED_BYTES];
+        x_bytes.copy_from_slice(bytes);
+        x_bytes[0] &= !(COMPRESSION_FLAG | SORT_FLAG);
+        let x = Fp::from_bytes_be(&x_bytes)?;
+
+        let y = (x.square() * x + Fp::b()).sqrt()?;
+        let y = if y.lexicographically_largest() == sort {
+            y
+        } else {
+            -y
+        };
+        Some(Self {
+            x,
+            y,
+            infinity: false,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::native::scalar::Scalar;
+
+    #[test]
+    fn test_g1_arithmetic() {
+        let g = G1Affine::generator();
+        assert!(g.is_on_curve());
+        assert!(g.is_torsion_free());
+
+        let g = g.to_jacobian();
+        let three = g.double().add(&g);
+        assert_eq!(three, g.mul(&Scalar::from_u64(3)));
+        assert_eq!(three.add(&g.neg()), g.double());
+        assert!(g.mul(&-Scalar::ONE).add(&g).is_identity());
+        assert!(g.add(&G1Projective::identity()) == g);
+    }
+
+    #[test]
+    fn test_g1_encoding() {
+        let g = G1Affine::generator();
+        for point in [
+            g,
+            g.neg(),
+            g.mul(&Scalar::from_u64(5)).to_affine(),
+            G1Affine::identity(),
+        ] {
+            assert_eq!(
+                G1Affine::from_compressed(&point.to_compressed()),
+                Some(point)
+            );
+            assert_eq!(
+                G1Affine::from_uncompressed(&point.to_uncompressed()),
+                Some(point)
+            );
+        }
+
+        let mut bytes = g.to_compressed();
+        assert_eq!(G1Affine::from_uncompressed(&bytes), None);
+        bytes[0] &= !COMPRESSION_FLAG;
+        assert_eq!(G1Affine::from_compressed(&bytes), None);
+
+        let mut bytes = g.to_uncompressed();
+        bytes[95] ^= 1;
+        assert_eq!(G1Affine::from_uncompressed(&bytes), None);
+    }
+}
@@ -0,0 +1,168 @@
+//! G2, the prime-order subgroup of the twist E'(Fp2): y^2 = x^3 + 4(u + 1).
+
+use super::{
+    curve::{
+        decode_flags, Affine, CurveField, Jacobian, COMPRESSION_FLAG, INFINITY_FLAG, SORT_FLAG,
+    },
+    field::Field,
+    fp::Fp,
+    fp2::Fp2,
+};
+use crate::backends::limbs::from_hex;
+
+pub type G2Affine = Affine<Fp2>;
+pub type G2Projective = Jacobian<Fp2>;
+
+/// Size of an uncompressed G2 point: x.c1 || x.c0 || y.c1 || y.c0, each
+/// big-endian.
+pub const G2_UNCOMPRESSED_BYTES: usize = 4 * Fp::BYTES;
+
+/// Size of a compressed G2 point: x.c1 || x.c0 with the flags in the top
+/// bits.
+pub const G2_COMPRESSED_BYTES: usize = 2 * Fp::BYTES;
+
+impl CurveField for Fp2 {
+    fn b() -> Self {
+        Fp2::new(Fp::from_u64(4), Fp::from_u64(4))
+    }
+}
+
+impl G2Affine {
+    pub fn generator() -> Self {
+        let coordinate = |c0, c1| {
+            let fp = |hex| Fp::from_canonical(from_hex(hex)).expect("reduced coordinate");
+            Fp2::new(fp(c0), fp(c1))
+        };
+        Self {
+            x: coordinate(
+                "0x024aa2b2f08f0a91260805272dc51051c6e47ad4fa403b02b4510b647ae3d1770bac0326a805bbefd48056c8c121bdb8",
+                "0x13e02b6052719f607dacd3a088274f65596bd0d09920b61ab5da61bbdc7f5049334cf11213945d57e5ac7d055d042b7e",
+            ),
+            y: coordinate(
+                "0x0ce5d527727d6e118cc9cdc6da2e351aadfd9baa8cbdd3a76d429a695160d12c923ac9cc3baca289e193548608b82801",
+                "0x0606c4a02ea734cc32acd2b02bc28b99cb3e287e85a763af267492ab572e99ab3f370d275cec1da1aaa9075ff05f79be",
+            ),
+            infinity: false,
+        }
+    }
+
+    /// x || y, or `INFINITY_FLAG` followed by zeros.
+    pub fn to_uncompressed(&self) -> [u8; G2_UNCOMPRESSED_BYTES] {
+        let mut bytes = [0u8; G2_UNCOMPRESSED_BYTES];
+        if self.infinity {
+            bytes[0] = INFINITY_FLAG;
+            return bytes;
+        }
+
+        bytes[..2 * Fp::BYTES].copy_from_slice(&self.x.to_bytes());
+        bytes[2 * Fp::BYTES..].copy_from_slice(&self.y.to_bytes());
+        bytes
+    }
+
+    /// Inverse of `to_uncompressed`. Returns None for non-canonical
+    /// encodings and points that are not on the twist.
+    pub fn from_uncompressed(bytes: &[u8]) -> Option<Self> {
+        if decode_flags(bytes, G2_UNCOMPRESSED_BYTES, false)?.is_none() {
+            return Some(Self::identity());
+        }
+
+        let point = Self {
+            x: Fp2::from_bytes(&bytes[..2 * Fp::BYTES])?,
+            y: Fp2::from_bytes(&bytes[2 * Fp::BYTES..])?,
+            infinity: false,
+        };
+        point.is_on_curve().then_some(point)
+    }
+
+    /// x with `COMPRESSION_FLAG` set, and `SORT_FLAG` set if y is the larger
+    /// root.
+    pub fn to_compressed(&self) -> [u8; G2_COMPRESSED_BYTES] {
+        let mut bytes = [0u8; G2_COMPRESSED_BYTES];
+        if self.infinity {
+            bytes[0] = COMPRESSION_FLAG | INFINITY_FLAG;
+            return bytes;
+        }
+
+        bytes.copy_from_slice(&self.x.to_bytes());
+        bytes[0] |= COMPRESSION_FLAG;
+        if self.y.lexicographically_largest() {
+            bytes[0] |= SORT_FLAG;
+        }
+        bytes
+    }
+
+    /// Inverse of `to_compressed`, recovering y with a square root in Fp2.
+    /// Returns None for non-canonical encodings and x without a point on the
+    /// twist.
+    pub fn from_compressed(bytes: &[u8]) -> Option<Self> {
+        let Some(sort) = decode_flags(bytes, G2_COMPRESSED_BYTES, true)? else {
+            return Some(Self::identity());
+        };
+
+        let mut x_bytes = [0u8; G2_COMPRESSED_BYTES];
+        x_bytes.copy_from_slice(bytes);
+        x_bytes[0] &= !(COMPRESSION_FLAG | SORT_FLAG);
+        let x = Fp2::from_bytes(&x_bytes)?;
+
+        let y = (x.square() * x + Fp2::b()).sqrt()?;
+        let y = if y.lexicographically_largest() == sort {
+            y
+        } else {
+            -y
+        };
+        Some(Self {
+            x,
+            y,
+            infinity: false,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::native::scalar::Scalar;
+
+    #[test]
+    fn test_g2_arithmetic() {
+        let g = G2Affine::generator();
+        assert!(g.is_on_curve());
+        assert!(g.is_torsion_free());
+
+        let g = g.to_jacobian();
+        assert_eq!(g.double().add(&g), g.mul(&Scalar::from_u64(3)));
+        assert!(g.mul(&-Scalar::ONE).add(&g).is_identity());
+    }
+
+    #[test]
+    fn test_g2_encoding() {
+        let g = G2Affine::generator();
+        for point in [
+            g,
+            g.neg(),
+            g.mul(&Scalar::from_u64(5)).to_affine(),
+            G2Affine::identity(),
+        ] {
+            assert_eq!(
+                G2Affine::from_compressed(&point.to_compressed()),
+                Some(point)
+            );
+            assert_eq!(
+                G2Affine::from_uncompressed(&point.to_uncompressed()),
+                Some(point)
+            );
+        }
+
+        // a point of the twist outside G2: the cofactor of G2 is not 1
+        let x = (1..)
+            .map(|i| Fp2::new(Fp::from_u64(i), Fp::ONE))
+            .find(|x| (x.square() * *x + Fp2::b()).sqrt().is_some())
+            .unwrap();
+        let mut bytes = [0u8; G2_COMPRESSED_BYTES];
+        bytes[..2 * Fp::BYTES].copy_from_slice(&x.to_bytes());
+        bytes[0] |= COMPRESSION_FLAG;
+        let point = G2Affine::from_compressed(&bytes).unwrap();
+        assert!(point.is_on_curve());
+        assert!(!point.is_torsion_free());
+    }
+}
@@ -0,0 +1,84 @@
+//! A second, pure-Rust implementation of BLS12-381, without rug or GMP.
+//!
+//! This is a parallel implementation of what `crate::curves` already
+//! provides, kept apart on purpose: the curve types there compute on
+//! `rug::Integer`, which does not build for wasm32, and this module has to.
+//! It holds just what checking a pairing equation takes: Fp and Fr in
+//! Montgomery form on `u64` limbs, the Fp12 tower, G1 and G2 with the same
+//! byte encodings as `G1Affine::to_compressed` and friends, and a
+//! product-of-pairings check. Its results are tested against
+//! `crate::curves` whenever both are built, so the two cannot drift apart.
+//!
+//! Built by the `backend-native` feature. None of it runs in constant time;
+//! it only ever sees public values.
+
+pub(crate) mod arith;
+pub mod curve;
+pub mod field;
+pub mod fp;
+pub mod fp12;
+pub mod fp2;
+pub mod fp6;
+pub mod g1;
+pub mod g2;
+pub mod pairing;
+pub mod scalar;
+
+pub use g1::{G1Affine, G1Projective};
+pub use g2::{G2Affine, G2Projective};
+pub use pairing::pairing_product_is_one;
+pub use scalar::Scalar;
+
+/// Differential tests against the rug-backed curve types.
+#[cfg(all(test, feature = "backend-rug"))]
+mod tests {
+    use super::*;
+    use crate::curves::bls12_381::{
+        curves::{g1::G1Projective as RugG1, g2::G2Projective as RugG2},
+        paring::BLS12_381Pairing,
+        Scalar as RugScalar,
+    };
+    use rand::Rng;
+    use zkper_rand::TestRng;
+
+    #[test]
+    fn test_native_matches_rug() {
+        let mut rng = TestRng::new();
+        for _ in 0..3 {
+            let k: RugScalar = rng.gen();
+            let native_k = Scalar::from_bytes_be(&k.to_bytes()).unwrap();
+            assert_eq!(native_k.to_bytes_be(), k.to_bytes());
+
+            let rug_p = RugG1::random(&mut rng).to_affine();
+            let rug_q = RugG2::random(&mut rng).to_affine();
+            let p = G1Affine::from_compressed(&rug_p.to_compressed()).unwrap();
+            let q = G2Affine::from_compressed(&rug_q.to_compressed()).unwrap();
+            assert_eq!(p.to_uncompressed(), rug_p.to_uncompressed());
+            assert_eq!(q.to_uncompressed(), rug_q.to_uncompressed());
+            assert_eq!(
+                G1Affine::from_uncompressed(&rug_p.to_uncompressed()),
+                Some(p)
+            );
+            assert_eq!(
+                G2Affine::from_uncompressed(&rug_q.to_uncompressed()),
+                Some(q)
+            );
+
+            let kp = RugG1::from(rug_p.clone()).mul_scalar(&k).to_affine();
+            let kq = RugG2::from(rug_q.clone()).mul_scalar(&k).to_affine();
+            assert_eq!(
+                p.mul(&native_k).to_affine().to_compressed(),
+                kp.to_compressed()
+            );
+            assert_eq!(
+                q.mul(&native_k).to_affine().to_compressed(),
+                kq.to_compressed()
+            );
+
+            assert_eq!(
+                pairing::pairing(&p, &q).to_bytes(),
+                BLS12_381Pairing::pairing(&rug_p, &rug_q).to_bytes()
+            );
+        }
+    }
+}
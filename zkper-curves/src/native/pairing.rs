@@ -0,0 +1,166 @@
+//! The optimal ate pairing of BLS12-381, with the same values as
+//! `crate::curves::bls12_381::paring::BLS12_381Pairing::pairing`.
+//!
+//! The Miller loop runs over |x| with the G2 point in affine coordinates on
+//! the twist, and evaluates each line at P after mapping it to E(Fp12) by
+//! (x, y) -> (x / w^2, y / w^3). Lines are scaled by w^3 and vertical lines
+//! are dropped: both lie in proper subfields of Fp12, which the final
+//! exponentiation sends to one. A conjugation at the end accounts for the
+//! sign of x = -|x|.
+//!
+//! The final exponentiation raises to (p^6 - 1) * (p^2 + 1) with a
+//! conjugation, an inversion and a power of p^2, then to the hard part by
+//! plain square-and-multiply. Like the rug pairing, it uses
+//! 3 * (p^4 - p^2 + 1) / r for the hard part, see
+//! `crate::curves::bls12_381::params`. Simple rather than fast: a verifier
+//! computes one final exponentiation per proof.
+
+use super::{
+    arith::bits_msb_first, field::Field, fp::Fp, fp12::Fp12, fp2::Fp2, fp6::Fp6, g1::G1Affine,
+    g2::G2Affine,
+};
+use crate::backends::limbs::from_hex;
+
+/// |x| for the curve parameter x = -0xd201000000010000.
+const X: u64 = 0xd201_0000_0001_0000;
+
+/// p^2.
+const P_SQUARED: [u64; 12] = from_hex(
+    "0x2a437a4b8c35fc74bd278eaa22f25e9e2dc90e50e7046b466e59e49349e8bd050a62cfd16ddca6ef53149330978ef011d68619c86185c7b292e85a87091a04966bf91ed3e71b743162c338362113cfd7ced6b1d76382eab26aa00001c718e39",
+);
+
+/// 3 * (p^4 - p^2 + 1) / r.
+const HARD_EXPONENT: [u64; 20] = from_hex(
+    "0x2e3941b88177054237aa494c159cdc7b69b9acc2cc45eabcf13296f7a83fce8d69012b6d183f47e3ae662e47a24ea0b0f5836ba82b62de877c5e22f26394116163cbf00bf566e46c9ae9625394f5946a6a2b0ab1c4752637d47f639b68a630b415c7da454d48638c72178bc76a791c6574220c23e5b6cc8a65dbc1cc35fe5a554e727d129be6a3b116bba59a18123aefcb3800a61a66d5af444bdcaaab2f6b",
+);
+
+/// The line of slope `lambda` through `t`, evaluated at `p` and scaled by
+/// w^3: (λ x_T - y_T) - λ x_P v + y_P v w.
+fn line(lambda: Fp2, t: &G2Affine, p: &G1Affine) -> Fp12 {
+    let c0 = Fp6::new(
+        lambda * t.x - t.y,
+        -(lambda * Fp2::new(p.x, Fp::ZERO)),
+        Fp2::zero(),
+    );
+    let c1 = Fp6::new(Fp2::zero(), Fp2::new(p.y, Fp::ZERO), Fp2::zero());
+    Fp12::new(c0, c1)
+}
+
+/// Adds the line through `t` and `q` to `f` and moves `t` to `t + q`, or
+/// the tangent at `t` and `2 t` for `q = t`.
+fn step(f: &mut Fp12, t: &mut G2Affine, q: &G2Affine, p: &G1Affine) {
+    let (numerator, denominator) = if t == q {
+        let x2 = t.x.square();
+        (x2.double() + x2, t.y.double())
+    } else {
+        (t.y - q.y, t.x - q.x)
+    };
+    let lambda = numerator
+        * denominator
+            .invert()
+            .expect("multiples of a G2 point below r are neither its negation nor of order 2");
+
+    *f = *f * line(lambda, t, p);
+    let x = lambda.square() - t.x - q.x;
+    let y = lambda * (t.x - x) - t.y;
+    *t = G2Affine {
+        x,
+        y,
+        infinity: false,
+    };
+}
+
+/// The product of the Miller loops of the pairs, skipping pairs with an
+/// identity point. The points must be in G1 and G2.
+pub fn multi_miller_loop(pairs: &[(G1Affine, G2Affine)]) -> Fp12 {
+    let pairs: Vec<_> = pairs
+        .iter()
+        .filter(|(p, q)| !p.is_identity() && !q.is_identity())
+        .collect();
+    let mut ts: Vec<G2Affine> = pairs.iter().map(|(_, q)| *q).collect();
+
+    let mut f = Fp12::one();
+    for bit in bits_msb_first(&[X]).skip(1) {
+        f = f.square();
+        for ((p, _), t) in pairs.iter().zip(ts.iter_mut()) {
+            let current = *t;
+            step(&mut f, t, &current, p);
+        }
+        if bit {
+            for ((p, q), t) in pairs.iter().zip(ts.iter_mut()) {
+                step(&mut f, t, q, p);
+            }
+        }
+    }
+    // f_{x, Q} = 1 / f_{|x|, Q} up to vertical lines, and after the final
+    // exponentiation conjugating is inverting
+    f.conjugate()
+}
+
+/// Raises a Miller loop output to 3 * (p^12 - 1) / r.
+pub fn final_exponentiation(f: &Fp12) -> Fp12 {
+    // lines never vanish at points of G1, so neither does their product
+    let f = f.conjugate() * f.invert().expect("Miller loop output is non-zero");
+    let f = f.pow(&P_SQUARED) * f;
+    f.pow(&HARD_EXPONENT)
+}
+
+/// The pairing of `p` and `q`, which must be in G1 and G2.
+pub fn pairing(p: &G1Affine, q: &G2Affine) -> Fp12 {
+    final_exponentiation(&multi_miller_loop(&[(*p, *q)]))
+}
+
+/// Checks ∏_i e(P_i, Q_i) == 1, like `BLS12_381Pairing::pairing_product_is_one`.
+///
+/// Every point must be on its curve and in the prime-order subgroup,
+/// otherwise the check fails regardless of the product.
+pub fn pairing_product_is_one(pairs: &[(G1Affine, G2Affine)]) -> bool {
+    let valid = pairs.iter().all(|(p, q)| {
+        p.is_on_curve() && q.is_on_curve() && p.is_torsion_free() && q.is_torsion_free()
+    });
+
+    valid && final_exponentiation(&multi_miller_loop(pairs)) == Fp12::one()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::native::scalar::Scalar;
+
+    #[test]
+    fn test_pairing_bilinearity() {
+        let g1 = G1Affine::generator();
+        let g2 = G2Affine::generator();
+        let (a, b) = (Scalar::from_u64(6), Scalar::from_u64(35));
+
+        let base = pairing(&g1, &g2);
+        assert_ne!(base, Fp12::one());
+        // an element of the order r subgroup of Fp12
+        assert_eq!(base.pow(&Scalar::MODULUS), Fp12::one());
+
+        let lhs = pairing(&g1.mul(&a).to_affine(), &g2.mul(&b).to_affine());
+        assert_eq!(lhs, base.pow(&(a * b).to_canonical()));
+
+        // e(a P, b Q) * e(-a b P, Q) = 1
+        let ab_neg = g1.mul(&(a * b)).to_affine().neg();
+        assert!(pairing_product_is_one(&[
+            (g1.mul(&a).to_affine(), g2.mul(&b).to_affine()),
+            (ab_neg, g2),
+        ]));
+        assert!(!pairing_product_is_one(&[(g1, g2), (g1, g2)]));
+        assert!(pairing_product_is_one(&[
+            (G1Affine::identity(), g2),
+            (g1, G2Affine::identity()),
+        ]));
+
+        // a point off its curve fails the check whatever the product
+        let off_curve = G1Affine {
+            y: g1.y + Fp::ONE,
+            ..g1
+        };
+        assert!(!pairing_product_is_one(&[(
+            off_curve,
+            G2Affine::identity()
+        )]));
+    }
+}
@@ -0,0 +1,42 @@
+//! The scalar field Fr of BLS12-381, the exponents of G1, G2 and Gt.
+
+use super::field::montgomery_field;
+use crate::backends::limbs::from_hex;
+
+montgomery_field!(
+    /// An element of Fr, in Montgomery form.
+    Scalar,
+    4,
+    32,
+    from_hex("0x73eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000001")
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::native::field::Field;
+
+    #[test]
+    fn test_scalar_bytes() {
+        let seven = Scalar::from_u64(7);
+        assert_eq!(seven.to_canonical(), [7, 0, 0, 0]);
+        assert_eq!(Scalar::from_bytes_be(&seven.to_bytes_be()), Some(seven));
+        assert_eq!(
+            (-Scalar::ONE).to_canonical(),
+            crate::native::arith::sub_small(&Scalar::MODULUS, 1)
+        );
+        assert_eq!(
+            Scalar::from_u64(3).invert().unwrap() * Scalar::from_u64(3),
+            Scalar::ONE
+        );
+
+        // r * 2^256 + 5 reduces to 5
+        let modulus: [u8; 32] = crate::native::arith::to_bytes_be(&Scalar::MODULUS);
+        let mut wide = [0u8; 64];
+        wide[..32].copy_from_slice(&modulus);
+        wide[63] = 5;
+        assert_eq!(Scalar::from_bytes_wide(&wide), Scalar::from_u64(5));
+        assert_eq!(Scalar::from_bytes_wide(&modulus), Scalar::ZERO);
+        assert_eq!(Scalar::from_bytes_be(&modulus), None);
+    }
+}
@@ -0,0 +1,191 @@
+//! `Serialize` and `Deserialize` for the curve types, behind the `serde`
+//! feature.
+//!
+//! Every type goes through its canonical byte encoding: points compressed,
+//! field elements as big-endian coefficients, most significant first.
+//! Human-readable formats such as JSON get the bytes as a lowercase hex
+//! string, binary formats get them as bytes. Deserializing rejects what the
+//! byte decoders reject, and points outside the prime-order subgroup.
+
+use std::fmt;
+
+use serde::{
+    de::{self, SeqAccess, Visitor},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+
+use crate::{
+    curves::bls12_381::{
+        curves::{g1::G1Projective, g1_affine::G1Affine, g2::G2Projective, g2_affine::G2Affine},
+        fields::{fp12::Fp12, fp2::Fp2, fp6::Fp6, target::TargetField},
+    },
+    hex::{from_hex, g1_from_bytes, g2_from_bytes, to_hex},
+};
+
+fn serialize_bytes<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+    if serializer.is_human_readable() {
+        serializer.serialize_str(&to_hex(bytes))
+    } else {
+        serializer.serialize_bytes(bytes)
+    }
+}
+
+fn deserialize_bytes<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+    if deserializer.is_human_readable() {
+        let hex = String::deserialize(deserializer)?;
+        from_hex(&hex).ok_or_else(|| de::Error::custom("invalid hex string"))
+    } else {
+        deserializer.deserialize_bytes(BytesVisitor)
+    }
+}
+
+/// Accepts a byte string, or a sequence of bytes from formats without one.
+struct BytesVisitor;
+
+impl<'de> Visitor<'de> for BytesVisitor {
+    type Value = Vec<u8>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a byte string")
+    }
+
+    fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+        Ok(v.to_vec())
+    }
+
+    fn visit_byte_buf<E: de::Error>(self, v: Vec<u8>) -> Result<Self::Value, E> {
+        Ok(v)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+        while let Some(byte) = seq.next_element()? {
+            bytes.push(byte);
+        }
+        Ok(bytes)
+    }
+}
+
+/// Implements both traits for `$ty` through an encoder to bytes and a decoder
+/// returning `Option`.
+macro_rules! serde_via_bytes {
+    ($ty:ty, $name:literal, $encode:expr, $decode:expr) => {
+        impl Serialize for $ty {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                let encode: fn(&$ty) -> _ = $encode;
+                serialize_bytes(&encode(self), serializer)
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $ty {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let decode: fn(&[u8]) -> Option<$ty> = $decode;
+                let bytes = deserialize_bytes(deserializer)?;
+                decode(&bytes)
+                    .ok_or_else(|| de::Error::custom(concat!("invalid ", $name, " encoding")))
+            }
+        }
+    };
+}
+
+serde_via_bytes!(Fp2, "Fp2", Fp2::to_bytes, Fp2::from_bytes);
+serde_via_bytes!(Fp6, "Fp6", Fp6::to_bytes, Fp6::from_bytes);
+serde_via_bytes!(Fp12, "Fp12", Fp12::to_bytes, Fp12::from_bytes);
+serde_via_bytes!(
+    TargetField,
+    "Gt",
+    TargetField::to_bytes,
+    TargetField::from_bytes
+);
+serde_via_bytes!(G1Affine, "G1", G1Affine::to_compressed, g1_from_bytes);
+serde_via_bytes!(G2Affine, "G2", G2Affine::to_compressed, g2_from_bytes);
+serde_via_bytes!(
+    G1Projective,
+    "G1",
+    |p| p.to_affine().to_compressed(),
+    |bytes| g1_from_bytes(bytes).map(|p| p.to_curve())
+);
+serde_via_bytes!(
+    G2Projective,
+    "G2",
+    |p| p.to_affine().to_compressed(),
+    |bytes| g2_from_bytes(bytes).map(|p| p.to_curve())
+);
+
+#[cfg(test)]
+mod tests {
+    use rug::Integer;
+    use serde::de::{
+        value::{BytesDeserializer, Error, SeqDeserializer},
+        DeserializeOwned,
+    };
+    use zkper_rand::TestRng;
+
+    use super::*;
+
+    fn round_trip<T: Serialize + DeserializeOwned + PartialEq + fmt::Debug>(value: &T) {
+        let json = serde_json::to_string(value).unwrap();
+        assert_eq!(&serde_json::from_str::<T>(&json).unwrap(), value);
+    }
+
+    #[test]
+    fn test_bytes_visitor() {
+        // the deserializers of serde::de::value claim to be human-readable,
+        // so the visitor is driven directly
+        let bytes = G1Affine::generator().to_compressed();
+        let visited = BytesDeserializer::<Error>::new(&bytes)
+            .deserialize_bytes(BytesVisitor)
+            .unwrap();
+        assert_eq!(visited, bytes);
+        let visited = SeqDeserializer::<_, Error>::new(bytes.iter().copied())
+            .deserialize_bytes(BytesVisitor)
+            .unwrap();
+        assert_eq!(g1_from_bytes(&visited), Some(G1Affine::generator()));
+    }
+
+    #[test]
+    fn test_round_trips() {
+        let mut rng = TestRng::new();
+        let g1 = G1Projective::random(&mut rng);
+        let g2 = G2Projective::random(&mut rng);
+        let gt = TargetField::random(&mut rng);
+
+        round_trip(&g1);
+        round_trip(&g1.to_affine());
+        round_trip(&G1Affine::identity());
+        round_trip(&g2);
+        round_trip(&g2.to_affine());
+        round_trip(&G2Affine::identity());
+        round_trip(&gt);
+        round_trip(&gt.0);
+        round_trip(&gt.0.c0);
+        round_trip(&gt.0.c0.c1);
+
+        // the compressed encoding of the generator, as in other implementations
+        assert_eq!(
+            serde_json::to_string(&G1Affine::generator()).unwrap(),
+            "\"97f1d3a73197d7942695638c4fa9ac0fc3688c4f9774b905a14e3a3f171bac586c55e83ff97a1aeffb3af00adb22c6bb\""
+        );
+    }
+
+    #[test]
+    fn test_rejects_invalid_encodings() {
+        assert!(serde_json::from_str::<Fp2>("\"00\"").is_err());
+        assert!(serde_json::from_str::<G1Affine>("\"zz\"").is_err());
+        assert!(serde_json::from_str::<TargetField>("1").is_err());
+
+        // a point of the curve outside G1
+        let point = (1u32..)
+            .find_map(|x| G1Affine::from_x_coordinate(&Integer::from(x), false).ok())
+            .unwrap();
+        assert!(!point.is_torsion_free());
+        let json = serde_json::to_string(&point).unwrap();
+        assert!(serde_json::from_str::<G1Affine>(&json).is_err());
+
+        // Fp12 elements outside Gt are fine as field elements
+        let one_plus = Fp12::one().add(&Fp12::one());
+        round_trip(&one_plus);
+        let json = serde_json::to_string(&one_plus).unwrap();
+        assert!(serde_json::from_str::<TargetField>(&json).is_err());
+    }
+}
@@ -0,0 +1,120 @@
+//! Platform independent hashes of field elements and points.
+//!
+//! The derived `Hash` of a rug `Integer` hashes its limbs, which depend on
+//! the limb size and byte order of the platform. [`StableHashTrait`] hashes
+//! the values through [`StableHasher`] instead, and the `Hash` impls of
+//! [`Fp2`], [`G1Affine`] and [`G2Affine`] go through it.
+
+use std::hash::{Hash, Hasher};
+
+use rug::{integer::Order, Integer};
+use zkper_integer::hash::StableHasher;
+
+use crate::curves::bls12_381::{
+    curves::{g1_affine::G1Affine, g2_affine::G2Affine},
+    fields::{base::Bls12_381BaseField, fp2::Fp2, scalar::Bls12_381ScalarField},
+};
+
+/// A hash that is the same on every platform, for maps and transcripts.
+pub trait StableHashTrait {
+    /// Feeds the canonical encoding of `self` into `hasher`.
+    fn stable_hash_into(&self, hasher: &mut StableHasher);
+
+    fn stable_hash(&self) -> u64 {
+        let mut hasher = StableHasher::new();
+        self.stable_hash_into(&mut hasher);
+        hasher.finish()
+    }
+}
+
+impl StableHashTrait for Integer {
+    fn stable_hash_into(&self, hasher: &mut StableHasher) {
+        hasher.write_integer(*self < 0, &self.to_digits::<u8>(Order::Msf));
+    }
+}
+
+impl StableHashTrait for Bls12_381ScalarField {
+    fn stable_hash_into(&self, hasher: &mut StableHasher) {
+        self.0.stable_hash_into(hasher);
+    }
+}
+
+impl StableHashTrait for Bls12_381BaseField {
+    fn stable_hash_into(&self, hasher: &mut StableHasher) {
+        self.canonical().stable_hash_into(hasher);
+    }
+}
+
+impl StableHashTrait for Fp2 {
+    fn stable_hash_into(&self, hasher: &mut StableHasher) {
+        self.c0.stable_hash_into(hasher);
+        self.c1.stable_hash_into(hasher);
+    }
+}
+
+impl StableHashTrait for G1Affine {
+    fn stable_hash_into(&self, hasher: &mut StableHasher) {
+        hasher.write(&[self.infinity as u8]);
+        self.x.stable_hash_into(hasher);
+        self.y.stable_hash_into(hasher);
+    }
+}
+
+impl StableHashTrait for G2Affine {
+    fn stable_hash_into(&self, hasher: &mut StableHasher) {
+        hasher.write(&[self.infinity as u8]);
+        self.x.stable_hash_into(hasher);
+        self.y.stable_hash_into(hasher);
+    }
+}
+
+macro_rules! impl_hash {
+    ($($t:ty),*) => {$(
+        impl Hash for $t {
+            fn hash<H: Hasher>(&self, state: &mut H) {
+                state.write_u64(self.stable_hash());
+            }
+        }
+    )*};
+}
+
+impl_hash!(Fp2, G1Affine, G2Affine);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stable_hash() {
+        // the same digest as the backend-independent integers
+        let value = Integer::from(0x1234);
+        assert_eq!(
+            value.stable_hash(),
+            zkper_integer::hash::stable_hash(false, &[0x12, 0x34])
+        );
+        assert_ne!(
+            Integer::from(-5).stable_hash(),
+            Integer::from(5).stable_hash()
+        );
+
+        // the coordinates are length-prefixed, so moving bytes between them
+        // changes the digest
+        let a = Fp2 {
+            c0: Integer::from(0x12),
+            c1: Integer::from(0x34),
+        };
+        let b = Fp2 {
+            c0: Integer::from(0x1234),
+            c1: Integer::ZERO,
+        };
+        assert_ne!(a.stable_hash(), b.stable_hash());
+
+        let g1 = G1Affine::generator();
+        assert_eq!(g1.stable_hash(), g1.clone().stable_hash());
+        assert_ne!(g1.stable_hash(), g1.neg().stable_hash());
+        assert_ne!(g1.stable_hash(), G1Affine::identity().stable_hash());
+
+        let g2 = G2Affine::generator();
+        assert_ne!(g2.stable_hash(), g2.neg().stable_hash());
+    }
+}
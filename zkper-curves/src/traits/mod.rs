@@ -1 +1,3 @@
 pub mod field;
+pub mod hash;
+pub mod transcript;
@@ -0,0 +1,135 @@
+//! Field elements and points in Fiat–Shamir transcripts.
+//!
+//! Implements [`Absorb`] for the BLS12-381 types with their canonical
+//! encodings: scalars as 32 big-endian bytes, base field elements as 48,
+//! Fp2 as c0 || c1, and points compressed, projective ones after
+//! normalization. [`TranscriptTrait`] squeezes challenges in Fr.
+
+use rug::{integer::Order, Integer};
+use zkper_base::transcript::{Absorb, Transcript, TranscriptHash};
+
+use crate::curves::bls12_381::{
+    curves::{g1::G1Projective, g1_affine::G1Affine, g2::G2Projective, g2_affine::G2Affine},
+    fields::{base::Bls12_381BaseField, fp2::Fp2},
+    Scalar, BLS12_381_BASE, BLS12_381_SCALAR,
+};
+
+/// Size of the canonical encoding of a scalar.
+pub const SCALAR_BYTES: usize = 32;
+
+/// Bytes squeezed per scalar challenge. Reducing 512 bits modulo the 255-bit
+/// r leaves a distance of about 2^-257 from uniform.
+const WIDE_CHALLENGE_BYTES: usize = 64;
+
+/// Challenges in the BLS12-381 scalar field.
+pub trait TranscriptTrait {
+    /// A scalar challenge, uniform below the scalar modulus up to a
+    /// negligible bias.
+    fn challenge_scalar(&mut self, label: &[u8]) -> Scalar;
+
+    /// `n` scalar challenges under the same label.
+    fn challenge_scalars(&mut self, label: &[u8], n: usize) -> Vec<Scalar> {
+        (0..n).map(|_| self.challenge_scalar(label)).collect()
+    }
+}
+
+impl<H: TranscriptHash> TranscriptTrait for Transcript<H> {
+    fn challenge_scalar(&mut self, label: &[u8]) -> Scalar {
+        let wide = self.challenge_array::<WIDE_CHALLENGE_BYTES>(label);
+        Scalar::from(BLS12_381_SCALAR.reduce(&Integer::from_digits(&wide, Order::Msf)))
+    }
+}
+
+impl Absorb for Scalar {
+    fn transcript_bytes(&self) -> Vec<u8> {
+        let digits = BLS12_381_SCALAR.reduce(&self.0).to_digits::<u8>(Order::Msf);
+        let mut bytes = vec![0u8; SCALAR_BYTES];
+        bytes[SCALAR_BYTES - digits.len()..].copy_from_slice(&digits);
+        bytes
+    }
+}
+
+impl Absorb for Bls12_381BaseField {
+    fn transcript_bytes(&self) -> Vec<u8> {
+        self.to_bytes().to_vec()
+    }
+}
+
+impl Absorb for Fp2 {
+    fn transcript_bytes(&self) -> Vec<u8> {
+        [&self.c0, &self.c1]
+            .into_iter()
+            .flat_map(|c| Bls12_381BaseField::to_bytes_be(&BLS12_381_BASE.reduce(c)))
+            .collect()
+    }
+}
+
+impl Absorb for G1Affine {
+    fn transcript_bytes(&self) -> Vec<u8> {
+        self.to_compressed().to_vec()
+    }
+}
+
+impl Absorb for G2Affine {
+    fn transcript_bytes(&self) -> Vec<u8> {
+        self.to_compressed().to_vec()
+    }
+}
+
+impl Absorb for G1Projective {
+    fn transcript_bytes(&self) -> Vec<u8> {
+        self.to_affine().transcript_bytes()
+    }
+}
+
+impl Absorb for G2Projective {
+    fn transcript_bytes(&self) -> Vec<u8> {
+        self.to_affine().transcript_bytes()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zkper_rand::TestRng;
+
+    use super::*;
+    use crate::curves::bls12_381::Bls12_381ScalarField;
+
+    #[test]
+    fn test_transcript_challenges() {
+        let mut rng = TestRng::new();
+        let g1 = G1Projective::random(&mut rng);
+        let g2 = G2Projective::random(&mut rng);
+        let s = Scalar::from(BLS12_381_SCALAR.sample_raw(&mut rng));
+
+        let run = |g1: &G1Projective| {
+            let mut transcript = Transcript::new(b"test");
+            transcript.append(b"g1", g1);
+            transcript.append(b"g2", &g2);
+            transcript.append(b"s", &s);
+            transcript.challenge_scalars(b"c", 4)
+        };
+
+        let challenges = run(&g1);
+        assert_eq!(challenges.len(), 4);
+        for c in &challenges {
+            assert!(c.0 >= 0 && c.0 < *BLS12_381_SCALAR.modulus_ref());
+        }
+        assert_ne!(challenges[0], challenges[1]);
+
+        // a projective point absorbs like its affine form, whatever its Z
+        let lambda = Bls12_381BaseField::from(BLS12_381_BASE.sample_raw(&mut rng));
+        let rescaled = G1Projective::new(&g1.x * &lambda, &g1.y * &lambda, &g1.z * &lambda);
+        assert_eq!(run(&rescaled), challenges);
+        assert_ne!(run(&g1.double()), challenges);
+
+        // canonical encodings: an unreduced scalar absorbs like its value
+        let unreduced = Bls12_381ScalarField(s.0.clone() + BLS12_381_SCALAR.modulus_ref());
+        assert_eq!(unreduced.transcript_bytes(), s.transcript_bytes());
+        assert_eq!(s.transcript_bytes().len(), SCALAR_BYTES);
+        assert_eq!(
+            Fp2::one().transcript_bytes()[..48],
+            Bls12_381BaseField::from(1u64).transcript_bytes()[..]
+        );
+    }
+}
@@ -0,0 +1,425 @@
+//! Property tests of the algebraic laws the curve arithmetic relies on:
+//! field axioms for Fp, Fp2, Fp6 and Fp12, group laws for G1 and G2,
+//! bilinearity of the pairing and the Montgomery round trips.
+//!
+//! Every strategy maps an arbitrary seed to `ZkperRng::from_seed(seed)` and
+//! samples its value from that rng. `PROPTEST_CASES` overrides the number of
+//! cases of each law, e.g. `PROPTEST_CASES=1000 cargo test --release --test
+//! algebraic_laws`.
+
+use std::fmt::Debug;
+
+use proptest::{
+    prelude::*,
+    test_runner::{Config, TestRunner},
+};
+use rug::Integer;
+use zkper_curves::{
+    curves::bls12_381::{
+        curves::{g1::G1Projective, g2::G2Projective},
+        fields::{fp12::Fp12, fp2::Fp2, fp6::Fp6},
+        paring::BLS12_381Pairing,
+        Bls12_381ScalarField, Scalar, BLS12_381_BASE,
+    },
+    traits::field::FieldTrait,
+};
+use zkper_rand::ZkperRng;
+
+/// Values sampled by `random` from the rng of an arbitrary seed.
+fn seeded<T: Debug>(random: fn(&mut ZkperRng) -> T) -> impl Strategy<Value = T> {
+    any::<u64>().prop_map(move |seed| random(&mut ZkperRng::from_seed(seed)))
+}
+
+/// Runs `law` on `cases` values of `strategy`, or `PROPTEST_CASES` of them
+/// when it is set.
+fn check<S: Strategy>(name: &str, cases: u32, strategy: S, law: impl Fn(S::Value) -> bool) {
+    let config = Config::default();
+    let config = Config {
+        cases: std::env::var("PROPTEST_CASES").map_or(cases, |_| config.cases),
+        failure_persistence: None,
+        ..config
+    };
+    let result = TestRunner::new(config).run(&strategy, |value| {
+        prop_assert!(law(value));
+        Ok(())
+    });
+    if let Err(e) = result {
+        panic!("{}: {}", name, e);
+    }
+}
+
+trait Field: Clone + Debug + PartialEq + Sized {
+    const CASES: u32;
+    fn random(rng: &mut ZkperRng) -> Self;
+    fn zero() -> Self;
+    fn one() -> Self;
+    fn add(&self, rhs: &Self) -> Self;
+    fn sub(&self, rhs: &Self) -> Self;
+    fn mul(&self, rhs: &Self) -> Self;
+    fn neg(&self) -> Self;
+    fn square(&self) -> Self;
+    fn invert(&self) -> Option<Self>;
+}
+
+/// Fp, through the reduced representatives the base field operations take.
+#[derive(Clone, Debug, PartialEq)]
+struct Fp(Integer);
+
+impl Field for Fp {
+    const CASES: u32 = 64;
+    fn random(rng: &mut ZkperRng) -> Self {
+        Fp(BLS12_381_BASE.sample_raw(rng))
+    }
+    fn zero() -> Self {
+        Fp(Integer::ZERO)
+    }
+    fn one() -> Self {
+        Fp(Integer::from(1))
+    }
+    fn add(&self, rhs: &Self) -> Self {
+        Fp(BLS12_381_BASE.add(self.0.clone(), &rhs.0))
+    }
+    fn sub(&self, rhs: &Self) -> Self {
+        Fp(BLS12_381_BASE.sub(self.0.clone(), &rhs.0))
+    }
+    fn mul(&self, rhs: &Self) -> Self {
+        Fp(BLS12_381_BASE.mul(self.0.clone(), &rhs.0))
+    }
+    fn neg(&self) -> Self {
+        Fp(BLS12_381_BASE.neg(self.0.clone()))
+    }
+    fn square(&self) -> Self {
+        Fp(BLS12_381_BASE.square(self.0.clone()))
+    }
+    fn invert(&self) -> Option<Self> {
+        BLS12_381_BASE.invert(self.0.clone()).map(Fp)
+    }
+}
+
+macro_rules! impl_field {
+    ($ty:ty, $cases:expr) => {
+        impl Field for $ty {
+            const CASES: u32 = $cases;
+            fn random(rng: &mut ZkperRng) -> Self {
+                <$ty>::random(rng)
+            }
+            fn zero() -> Self {
+                <$ty>::zero()
+            }
+            fn one() -> Self {
+                <$ty>::one()
+            }
+            fn add(&self, rhs: &Self) -> Self {
+                <$ty>::add(self, rhs)
+            }
+            fn sub(&self, rhs: &Self) -> Self {
+                <$ty>::sub(self, rhs)
+            }
+            fn mul(&self, rhs: &Self) -> Self {
+                <$ty>::mul(self, rhs)
+            }
+            fn neg(&self) -> Self {
+                <$ty>::neg(self)
+            }
+            fn square(&self) -> Self {
+                <$ty>::square(self)
+            }
+            fn invert(&self) -> Option<Self> {
+                <$ty>::invert(self)
+            }
+        }
+    };
+}
+
+impl_field!(Fp2, 32);
+impl_field!(Fp6, 16);
+impl_field!(Fp12, 8);
+
+fn field_laws<F: Field>(field: &str) {
+    let law = |name: &str| format!("{}: {}", field, name);
+    let one = || seeded(F::random);
+    let two = || (seeded(F::random), seeded(F::random));
+    let three = || (seeded(F::random), seeded(F::random), seeded(F::random));
+
+    check(
+        &law("addition is associative"),
+        F::CASES,
+        three(),
+        |(a, b, c)| a.add(&b).add(&c) == a.add(&b.add(&c)),
+    );
+    check(
+        &law("addition is commutative"),
+        F::CASES,
+        two(),
+        |(a, b)| a.add(&b) == b.add(&a),
+    );
+    check(
+        &law("additive identity and inverse"),
+        F::CASES,
+        one(),
+        |a| a.add(&F::zero()) == a && a.add(&a.neg()) == F::zero() && a.sub(&a) == F::zero(),
+    );
+    check(
+        &law("subtraction adds the negation"),
+        F::CASES,
+        two(),
+        |(a, b)| a.sub(&b) == a.add(&b.neg()),
+    );
+    check(
+        &law("multiplication is associative"),
+        F::CASES,
+        three(),
+        |(a, b, c)| a.mul(&b).mul(&c) == a.mul(&b.mul(&c)),
+    );
+    check(
+        &law("multiplication is commutative"),
+        F::CASES,
+        two(),
+        |(a, b)| a.mul(&b) == b.mul(&a),
+    );
+    check(
+        &law("multiplication distributes"),
+        F::CASES,
+        three(),
+        |(a, b, c)| a.mul(&b.add(&c)) == a.mul(&b).add(&a.mul(&c)),
+    );
+    check(
+        &law("squaring multiplies by itself"),
+        F::CASES,
+        one(),
+        |a| a.square() == a.mul(&a),
+    );
+    check(
+        &law("multiplicative identity and inverse"),
+        F::CASES,
+        one(),
+        |a| {
+            let inverse = a.invert().expect("a random element is not zero");
+            a.mul(&F::one()) == a && a.mul(&inverse) == F::one() && inverse.invert() == Some(a)
+        },
+    );
+    assert!(
+        F::zero().invert().is_none(),
+        "{}",
+        law("zero is invertible")
+    );
+}
+
+#[test]
+fn test_fp_laws() {
+    field_laws::<Fp>("Fp");
+}
+
+#[test]
+fn test_fp2_laws() {
+    field_laws::<Fp2>("Fp2");
+}
+
+#[test]
+fn test_fp6_laws() {
+    field_laws::<Fp6>("Fp6");
+}
+
+#[test]
+fn test_fp12_laws() {
+    field_laws::<Fp12>("Fp12");
+}
+
+trait Group: Clone + Debug + PartialEq + Sized {
+    const CASES: u32;
+    fn random(rng: &mut ZkperRng) -> Self;
+    fn identity() -> Self;
+    fn add(&self, rhs: &Self) -> Self;
+    fn double(&self) -> Self;
+    fn neg(&self) -> Self;
+    fn mul_scalar(&self, scalar: &Scalar) -> Self;
+    fn is_on_curve(&self) -> bool;
+}
+
+macro_rules! impl_group {
+    ($ty:ty, $cases:expr) => {
+        impl Group for $ty {
+            const CASES: u32 = $cases;
+            fn random(rng: &mut ZkperRng) -> Self {
+                <$ty>::random(rng)
+            }
+            fn identity() -> Self {
+                <$ty>::identity()
+            }
+            fn add(&self, rhs: &Self) -> Self {
+                <$ty>::add(self, rhs)
+            }
+            fn double(&self) -> Self {
+                <$ty>::double(self)
+            }
+            fn neg(&self) -> Self {
+                <$ty>::neg(self)
+            }
+            fn mul_scalar(&self, scalar: &Scalar) -> Self {
+                <$ty>::mul_scalar(self, scalar)
+            }
+            fn is_on_curve(&self) -> bool {
+                <$ty>::is_on_curve(self)
+            }
+        }
+    };
+}
+
+impl_group!(G1Projective, 8);
+impl_group!(G2Projective, 4);
+
+fn random_scalar(rng: &mut ZkperRng) -> Scalar {
+    Scalar::from(Bls12_381ScalarField::random(rng))
+}
+
+fn group_laws<G: Group>(group: &str) {
+    let law = |name: &str| format!("{}: {}", group, name);
+    let point = || seeded(G::random);
+    let scalar = || seeded(random_scalar);
+
+    check(
+        &law("addition is associative"),
+        G::CASES,
+        (point(), point(), point()),
+        |(p, q, r)| p.add(&q).add(&r) == p.add(&q.add(&r)),
+    );
+    check(
+        &law("addition is commutative"),
+        G::CASES,
+        (point(), point()),
+        |(p, q)| p.add(&q) == q.add(&p),
+    );
+    check(&law("identity and inverse"), G::CASES, point(), |p| {
+        p.add(&G::identity()) == p && G::identity().add(&p) == p && p.add(&p.neg()) == G::identity()
+    });
+    check(
+        &law("doubling adds the point to itself"),
+        G::CASES,
+        point(),
+        |p| p.double() == p.add(&p) && p.double().is_on_curve(),
+    );
+    check(
+        &law("scalars distribute over points"),
+        G::CASES,
+        (point(), point(), scalar()),
+        |(p, q, a)| p.add(&q).mul_scalar(&a) == p.mul_scalar(&a).add(&q.mul_scalar(&a)),
+    );
+    check(
+        &law("points distribute over scalars"),
+        G::CASES,
+        (point(), scalar(), scalar()),
+        |(p, a, b)| p.mul_scalar(&a.add(&b)) == p.mul_scalar(&a).add(&p.mul_scalar(&b)),
+    );
+    check(
+        &law("scalar multiplication composes"),
+        G::CASES,
+        (point(), scalar(), scalar()),
+        |(p, a, b)| p.mul_scalar(&a.mul(&b)) == p.mul_scalar(&a).mul_scalar(&b),
+    );
+    check(
+        &law("the group order annihilates"),
+        G::CASES,
+        point(),
+        |p| {
+            let minus_one = Scalar::from(Integer::from(Bls12_381ScalarField::modulus() - 1u32));
+            p.mul_scalar(&minus_one) == p.neg()
+                && p.mul_scalar(&Scalar::from(Integer::ZERO)) == G::identity()
+        },
+    );
+}
+
+#[test]
+fn test_g1_laws() {
+    group_laws::<G1Projective>("G1");
+}
+
+#[test]
+fn test_g2_laws() {
+    group_laws::<G2Projective>("G2");
+}
+
+#[test]
+fn test_pairing_bilinearity() {
+    check(
+        "e(aP, bQ) = e(P, Q)^(ab)",
+        2,
+        (
+            seeded(G1Projective::random),
+            seeded(G2Projective::random),
+            seeded(random_scalar),
+            seeded(random_scalar),
+        ),
+        |(p, q, a, b)| {
+            let e = BLS12_381Pairing::pairing(&p.to_affine(), &q.to_affine());
+            let lhs = BLS12_381Pairing::pairing(
+                &p.mul_scalar(&a).to_affine(),
+                &q.mul_scalar(&b).to_affine(),
+            );
+            lhs == e.pow(&a.mul(&b)) && !e.is_identity()
+        },
+    );
+    check(
+        "e(P + P', Q) = e(P, Q) e(P', Q)",
+        2,
+        (
+            seeded(G1Projective::random),
+            seeded(G1Projective::random),
+            seeded(G2Projective::random),
+        ),
+        |(p, p2, q)| {
+            let q = q.to_affine();
+            BLS12_381Pairing::pairing(&p.add(&p2).to_affine(), &q)
+                == BLS12_381Pairing::pairing(&p.to_affine(), &q)
+                    .mul(&BLS12_381Pairing::pairing(&p2.to_affine(), &q))
+        },
+    );
+}
+
+#[test]
+fn test_montgomery_round_trips() {
+    let fp = || seeded(|rng| BLS12_381_BASE.sample_raw(rng));
+
+    check(
+        "Fp: from_montgomery inverts to_montgomery",
+        64,
+        (fp(), fp()),
+        |(a, b)| {
+            let (a_mont, b_mont) = (
+                BLS12_381_BASE.to_montgomery(&a),
+                BLS12_381_BASE.to_montgomery(&b),
+            );
+            BLS12_381_BASE.from_montgomery(&a_mont) == a
+                && BLS12_381_BASE.from_montgomery(&BLS12_381_BASE.mont_mul(&a_mont, &b_mont))
+                    == BLS12_381_BASE.mul(a, &b)
+        },
+    );
+    check(
+        "Fr: from_mont inverts to_mont",
+        64,
+        seeded(|rng| Bls12_381ScalarField::from(Bls12_381ScalarField::random(rng))),
+        |a| Bls12_381ScalarField::from_mont(&a.to_mont()) == a.0,
+    );
+    check(
+        "Fp2: from_mont inverts to_mont",
+        32,
+        seeded(Fp2::random),
+        |a| a.to_mont().from_mont() == a,
+    );
+    check(
+        "G1: Montgomery arithmetic matches",
+        8,
+        (seeded(G1Projective::random), seeded(G1Projective::random)),
+        |(p, q)| {
+            let (p_mont, q_mont) = (p.to_montgomery(), q.to_montgomery());
+            p_mont.from_montgomery() == p
+                && p_mont.add_mont(&q_mont).from_montgomery() == p.add(&q)
+                && p_mont.double_mont().from_montgomery() == p.double()
+        },
+    );
+    check(
+        "G2: from_mont inverts to_mont",
+        8,
+        seeded(G2Projective::random),
+        |p| p.to_mont().from_mont() == p,
+    );
+}
@@ -0,0 +1,284 @@
+use std::{fmt::Debug, marker::PhantomData};
+
+use rand_core::RngCore;
+use zkper_integer::ZkperInteger;
+
+use crate::traits::ZkperFieldTrait;
+
+use super::characteristic_power;
+
+/// Parameters of a cubic extension F[v] / (v^3 - β).
+pub trait CubicExtensionConfig: Clone + Debug + PartialEq + Eq {
+    type BaseField: ZkperFieldTrait;
+
+    /// β, must be a cubic non-residue in the base field.
+    fn non_residue() -> Self::BaseField;
+
+    /// β * x, override when β has a cheaper shape (e.g. u + 1 over Fp2).
+    fn mul_base_by_non_residue(x: &Self::BaseField) -> Self::BaseField {
+        x.mul(&Self::non_residue())
+    }
+
+    /// Frobenius hook: with e = p^power, the (c1, c2) such that
+    /// v^e = c1 * v^(e mod 3) and v^(2e) = c2 * v^(2e mod 3).
+    ///
+    /// Computed on demand, override with a precomputed table for speed.
+    fn frobenius_coeffs(power: usize) -> (Self::BaseField, Self::BaseField) {
+        let e = characteristic_power::<Self::BaseField>(power);
+        let e2 = e.clone() * 2u64;
+        let three = ZkperInteger::three();
+        let exp1 = e.clone() - &(e % &three);
+        let exp2 = e2.clone() - &(e2 % &three);
+
+        let beta = Self::non_residue();
+        (beta.pow(&(exp1 / 3u64)), beta.pow(&(exp2 / 3u64)))
+    }
+}
+
+/// An element c0 + c1 * v + c2 * v^2 of a cubic extension.
+#[derive(Debug, Clone)]
+pub struct CubicExtension<C: CubicExtensionConfig> {
+    pub c0: C::BaseField,
+    pub c1: C::BaseField,
+    pub c2: C::BaseField,
+    _config: PhantomData<C>,
+}
+
+impl<C: CubicExtensionConfig> CubicExtension<C> {
+    pub fn new(c0: C::BaseField, c1: C::BaseField, c2: C::BaseField) -> Self {
+        Self {
+            c0,
+            c1,
+            c2,
+            _config: PhantomData,
+        }
+    }
+
+    /// Embeds a base field element.
+    pub fn from_base(c0: C::BaseField) -> Self {
+        Self::new(c0, C::BaseField::zero(), C::BaseField::zero())
+    }
+
+    pub fn mul_by_base(&self, rhs: &C::BaseField) -> Self {
+        Self::new(self.c0.mul(rhs), self.c1.mul(rhs), self.c2.mul(rhs))
+    }
+}
+
+impl<C: CubicExtensionConfig> PartialEq for CubicExtension<C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.c0 == other.c0 && self.c1 == other.c1 && self.c2 == other.c2
+    }
+}
+
+impl<C: CubicExtensionConfig> Eq for CubicExtension<C> {}
+
+impl<C: CubicExtensionConfig> ZkperFieldTrait for CubicExtension<C> {
+    type Integer = <C::BaseField as ZkperFieldTrait>::Integer;
+
+    fn zero() -> Self {
+        Self::from_base(C::BaseField::zero())
+    }
+
+    fn one() -> Self {
+        Self::from_base(C::BaseField::one())
+    }
+
+    fn is_zero(&self) -> bool {
+        self.c0.is_zero() && self.c1.is_zero() && self.c2.is_zero()
+    }
+
+    fn add(&self, rhs: &Self) -> Self {
+        Self::new(
+            self.c0.add(&rhs.c0),
+            self.c1.add(&rhs.c1),
+            self.c2.add(&rhs.c2),
+        )
+    }
+
+    fn sub(&self, rhs: &Self) -> Self {
+        Self::new(
+            self.c0.sub(&rhs.c0),
+            self.c1.sub(&rhs.c1),
+            self.c2.sub(&rhs.c2),
+        )
+    }
+
+    /// Karatsuba over three coefficients, reducing with v^3 = β.
+    fn mul(&self, rhs: &Self) -> Self {
+        let (a0, a1, a2) = (&self.c0, &self.c1, &self.c2);
+        let (b0, b1, b2) = (&rhs.c0, &rhs.c1, &rhs.c2);
+
+        let v0 = a0.mul(b0);
+        let v1 = a1.mul(b1);
+        let v2 = a2.mul(b2);
+
+        // c0 = v0 + β((a1 + a2)(b1 + b2) - v1 - v2)
+        let c0 = C::mul_base_by_non_residue(&a1.add(a2).mul(&b1.add(b2)).sub(&v1).sub(&v2));
+        let c0 = c0.add(&v0);
+        // c1 = (a0 + a1)(b0 + b1) - v0 - v1 + β v2
+        let c1 = a0
+            .add(a1)
+            .mul(&b0.add(b1))
+            .sub(&v0)
+            .sub(&v1)
+            .add(&C::mul_base_by_non_residue(&v2));
+        // c2 = (a0 + a2)(b0 + b2) - v0 + v1 - v2
+        let c2 = a0.add(a2).mul(&b0.add(b2)).sub(&v0).add(&v1).sub(&v2);
+
+        Self::new(c0, c1, c2)
+    }
+
+    fn neg(&self) -> Self {
+        Self::new(self.c0.neg(), self.c1.neg(), self.c2.neg())
+    }
+
+    fn inverse(&self) -> Option<Self> {
+        let (a0, a1, a2) = (&self.c0, &self.c1, &self.c2);
+
+        // t0 = a0^2 - β a1 a2, t1 = β a2^2 - a0 a1, t2 = a1^2 - a0 a2
+        let t0 = a0.square().sub(&C::mul_base_by_non_residue(&a1.mul(a2)));
+        let t1 = C::mul_base_by_non_residue(&a2.square()).sub(&a0.mul(a1));
+        let t2 = a1.square().sub(&a0.mul(a2));
+
+        // the norm: a0 t0 + β (a2 t1 + a1 t2)
+        let norm = a0
+            .mul(&t0)
+            .add(&C::mul_base_by_non_residue(&a2.mul(&t1).add(&a1.mul(&t2))));
+        let inv = norm.inverse()?;
+
+        Some(Self::new(t0.mul(&inv), t1.mul(&inv), t2.mul(&inv)))
+    }
+
+    fn characteristic() -> ZkperInteger<Self::Integer> {
+        C::BaseField::characteristic()
+    }
+
+    fn degree() -> u64 {
+        3 * C::BaseField::degree()
+    }
+
+    fn frobenius_map(&self, power: usize) -> Self {
+        let (coeff1, coeff2) = C::frobenius_coeffs(power);
+        let c0 = self.c0.frobenius_map(power);
+        let c1 = self.c1.frobenius_map(power).mul(&coeff1);
+        let c2 = self.c2.frobenius_map(power).mul(&coeff2);
+
+        // p^power ≡ 2 (mod 3) swaps the v and v^2 slots
+        let e = characteristic_power::<C::BaseField>(power);
+        if (e % &ZkperInteger::three()).is_one() {
+            Self::new(c0, c1, c2)
+        } else {
+            Self::new(c0, c2, c1)
+        }
+    }
+
+    fn random<R: RngCore + ?Sized>(rng: &mut R) -> Self {
+        Self::new(
+            C::BaseField::random(rng),
+            C::BaseField::random(rng),
+            C::BaseField::random(rng),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zkper_integer::backends::rug_backend::RugBackend;
+    use zkper_modular::zkper_prime;
+
+    use super::*;
+    use crate::{
+        extensions::{QuadraticExtension, QuadraticExtensionConfig},
+        ZkperFieldElement,
+    };
+    use zkper_rand::TestRng;
+
+    zkper_prime!(Prime7 = "0x7");
+    // 11 ≡ 2 (mod 3), so the Frobenius on F11^6 swaps the v and v^2 slots
+    zkper_prime!(Prime11 = "0xb");
+
+    type F7 = ZkperFieldElement<RugBackend, Prime7>;
+    type F11 = ZkperFieldElement<RugBackend, Prime11>;
+
+    /// v^3 = 3, the cubes mod 7 are {0, 1, 6}
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    struct F343Config;
+
+    impl CubicExtensionConfig for F343Config {
+        type BaseField = F7;
+
+        fn non_residue() -> F7 {
+            F7::from(3u64)
+        }
+    }
+
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    struct F121Config;
+
+    impl QuadraticExtensionConfig for F121Config {
+        type BaseField = F11;
+
+        fn non_residue() -> F11 {
+            F11::one().neg()
+        }
+    }
+
+    type F121 = QuadraticExtension<F121Config>;
+
+    /// v^3 = u + 2 over F121; u + 1 as in BLS12-381 is a cube here
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    struct F11_6Config;
+
+    impl CubicExtensionConfig for F11_6Config {
+        type BaseField = F121;
+
+        fn non_residue() -> F121 {
+            F121::new(F11::from(2u64), F11::one())
+        }
+    }
+
+    type F343 = CubicExtension<F343Config>;
+    type F11_6 = CubicExtension<F11_6Config>;
+
+    #[test]
+    fn test_cubic_extension_exhaustive() {
+        assert_eq!(F343::order(), 343u64.into());
+
+        for i in 0..343u64 {
+            let a = F343::new(F7::from(i % 7), F7::from(i / 7 % 7), F7::from(i / 49));
+            assert_eq!(a.frobenius_map(1), a.pow(&7u64.into()));
+            assert_eq!(a.frobenius_map(3), a);
+
+            match a.inverse() {
+                Some(inv) => assert_eq!(a.mul(&inv), F343::one()),
+                None => assert!(a.is_zero()),
+            }
+        }
+    }
+
+    #[test]
+    fn test_tower_over_quadratic() {
+        // u + 2 has to be a cubic non-residue in F121
+        let beta = F11_6Config::non_residue();
+        assert!(!beta.pow(&40u64.into()).is_one());
+        assert_eq!(F11_6::degree(), 6);
+
+        let mut rng = TestRng::new();
+        let p = F11_6::characteristic();
+        for _ in 0..10 {
+            let a = F11_6::random(&mut rng);
+            let b = F11_6::random(&mut rng);
+
+            assert_eq!(a.mul(&b), b.mul(&a));
+            assert_eq!(a.add(&b).mul(&a), a.square().add(&b.mul(&a)));
+            assert_eq!(a.mul(&a.inverse().unwrap()), F11_6::one());
+
+            assert_eq!(a.frobenius_map(1), a.pow(&p));
+            assert_eq!(a.frobenius_map(2), a.frobenius_map(1).frobenius_map(1));
+            assert_eq!(a.frobenius_map(6), a);
+
+            let square = a.square();
+            assert_eq!(square.sqrt().unwrap().square(), square);
+        }
+    }
+}
@@ -0,0 +1,25 @@
+//! Generic extension field constructors.
+//!
+//! `QuadraticExtension` builds F[u] / (u^2 - β) and `CubicExtension` builds
+//! F[v] / (v^3 - β) over any `ZkperFieldTrait` base, so a tower such as the
+//! BLS12-381 Fp2 -> Fp6 -> Fp12 is expressed by nesting configs.
+
+use zkper_integer::ZkperInteger;
+
+use crate::traits::ZkperFieldTrait;
+
+pub mod cubic;
+pub mod quadratic;
+
+pub use cubic::{CubicExtension, CubicExtensionConfig};
+pub use quadratic::{QuadraticExtension, QuadraticExtensionConfig};
+
+/// p^power for the characteristic p of `F`.
+pub(crate) fn characteristic_power<F: ZkperFieldTrait>(power: usize) -> ZkperInteger<F::Integer> {
+    let p = F::characteristic();
+    let mut result = ZkperInteger::one();
+    for _ in 0..power {
+        result *= &p;
+    }
+    result
+}
@@ -0,0 +1,254 @@
+use std::{fmt::Debug, marker::PhantomData};
+
+use rand_core::RngCore;
+use zkper_integer::ZkperInteger;
+
+use crate::traits::ZkperFieldTrait;
+
+use super::characteristic_power;
+
+/// Parameters of a quadratic extension F[u] / (u^2 - β).
+pub trait QuadraticExtensionConfig: Clone + Debug + PartialEq + Eq {
+    type BaseField: ZkperFieldTrait;
+
+    /// β, must be a quadratic non-residue in the base field.
+    fn non_residue() -> Self::BaseField;
+
+    /// β * x, override when β has a cheaper shape (e.g. -1).
+    fn mul_base_by_non_residue(x: &Self::BaseField) -> Self::BaseField {
+        x.mul(&Self::non_residue())
+    }
+
+    /// Frobenius hook: the c with u^(p^power) = c * u, i.e. β^((p^power - 1) / 2).
+    ///
+    /// Computed on demand, override with a precomputed table for speed.
+    fn frobenius_coeff(power: usize) -> Self::BaseField {
+        let exp = (characteristic_power::<Self::BaseField>(power) - 1u64) >> 1u32;
+        Self::non_residue().pow(&exp)
+    }
+}
+
+/// An element c0 + c1 * u of a quadratic extension.
+#[derive(Debug, Clone)]
+pub struct QuadraticExtension<C: QuadraticExtensionConfig> {
+    pub c0: C::BaseField,
+    pub c1: C::BaseField,
+    _config: PhantomData<C>,
+}
+
+impl<C: QuadraticExtensionConfig> QuadraticExtension<C> {
+    pub fn new(c0: C::BaseField, c1: C::BaseField) -> Self {
+        Self {
+            c0,
+            c1,
+            _config: PhantomData,
+        }
+    }
+
+    /// Embeds a base field element.
+    pub fn from_base(c0: C::BaseField) -> Self {
+        Self::new(c0, C::BaseField::zero())
+    }
+
+    pub fn mul_by_base(&self, rhs: &C::BaseField) -> Self {
+        Self::new(self.c0.mul(rhs), self.c1.mul(rhs))
+    }
+
+    /// c0 - c1 * u
+    pub fn conjugate(&self) -> Self {
+        Self::new(self.c0.clone(), self.c1.neg())
+    }
+
+    /// c0^2 - β * c1^2, the norm down to the base field.
+    pub fn norm(&self) -> C::BaseField {
+        self.c0
+            .square()
+            .sub(&C::mul_base_by_non_residue(&self.c1.square()))
+    }
+}
+
+impl<C: QuadraticExtensionConfig> PartialEq for QuadraticExtension<C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.c0 == other.c0 && self.c1 == other.c1
+    }
+}
+
+impl<C: QuadraticExtensionConfig> Eq for QuadraticExtension<C> {}
+
+impl<C: QuadraticExtensionConfig> ZkperFieldTrait for QuadraticExtension<C> {
+    type Integer = <C::BaseField as ZkperFieldTrait>::Integer;
+
+    fn zero() -> Self {
+        Self::new(C::BaseField::zero(), C::BaseField::zero())
+    }
+
+    fn one() -> Self {
+        Self::new(C::BaseField::one(), C::BaseField::zero())
+    }
+
+    fn is_zero(&self) -> bool {
+        self.c0.is_zero() && self.c1.is_zero()
+    }
+
+    fn add(&self, rhs: &Self) -> Self {
+        Self::new(self.c0.add(&rhs.c0), self.c1.add(&rhs.c1))
+    }
+
+    fn sub(&self, rhs: &Self) -> Self {
+        Self::new(self.c0.sub(&rhs.c0), self.c1.sub(&rhs.c1))
+    }
+
+    /// Karatsuba: (a0 + a1 u)(b0 + b1 u) = a0 b0 + β a1 b1 + (a0 b1 + a1 b0) u
+    fn mul(&self, rhs: &Self) -> Self {
+        let v0 = self.c0.mul(&rhs.c0);
+        let v1 = self.c1.mul(&rhs.c1);
+        let c0 = v0.add(&C::mul_base_by_non_residue(&v1));
+        let c1 = self
+            .c0
+            .add(&self.c1)
+            .mul(&rhs.c0.add(&rhs.c1))
+            .sub(&v0)
+            .sub(&v1);
+        Self::new(c0, c1)
+    }
+
+    fn neg(&self) -> Self {
+        Self::new(self.c0.neg(), self.c1.neg())
+    }
+
+    /// (a0 + a1 u)^-1 = (a0 - a1 u) / (a0^2 - β a1^2)
+    fn inverse(&self) -> Option<Self> {
+        let inv = self.norm().inverse()?;
+        Some(self.conjugate().mul_by_base(&inv))
+    }
+
+    fn characteristic() -> ZkperInteger<Self::Integer> {
+        C::BaseField::characteristic()
+    }
+
+    fn degree() -> u64 {
+        2 * C::BaseField::degree()
+    }
+
+    fn frobenius_map(&self, power: usize) -> Self {
+        let c0 = self.c0.frobenius_map(power);
+        let c1 = self.c1.frobenius_map(power).mul(&C::frobenius_coeff(power));
+        Self::new(c0, c1)
+    }
+
+    fn random<R: RngCore + ?Sized>(rng: &mut R) -> Self {
+        Self::new(C::BaseField::random(rng), C::BaseField::random(rng))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zkper_curves::curves::bls12_381::fields::fp2::Fp2;
+    use zkper_integer::backends::rug_backend::RugBackend;
+    use zkper_modular::zkper_prime;
+
+    use super::*;
+    use crate::ZkperFieldElement;
+    use zkper_rand::TestRng;
+
+    zkper_prime!(Prime7 = "0x7");
+    zkper_prime!(
+        Bls12_381Base = "0x1a0111ea397fe69a4b1ba7b6434bacd764774b84f38512bf6730d2a0f6b0f6241eabfffeb153ffffb9feffffffffaaab"
+    );
+
+    type F7 = ZkperFieldElement<RugBackend, Prime7>;
+    type Fp = ZkperFieldElement<RugBackend, Bls12_381Base>;
+
+    /// u^2 = -1, a non-residue since 7 ≡ 3 (mod 4)
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    struct F49Config;
+
+    impl QuadraticExtensionConfig for F49Config {
+        type BaseField = F7;
+
+        fn non_residue() -> F7 {
+            F7::one().neg()
+        }
+    }
+
+    /// The BLS12-381 Fp2, u^2 = -1
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    struct Fp2Config;
+
+    impl QuadraticExtensionConfig for Fp2Config {
+        type BaseField = Fp;
+
+        fn non_residue() -> Fp {
+            Fp::one().neg()
+        }
+
+        fn mul_base_by_non_residue(x: &Fp) -> Fp {
+            x.neg()
+        }
+    }
+
+    type F49 = QuadraticExtension<F49Config>;
+    type GenericFp2 = QuadraticExtension<Fp2Config>;
+
+    fn all_f49() -> impl Iterator<Item = F49> {
+        (0..49u64).map(|i| F49::new(F7::from(i % 7), F7::from(i / 7)))
+    }
+
+    fn to_fp2(a: &GenericFp2) -> Fp2 {
+        Fp2::from_hexs(
+            &a.c0.value.value.to_hex_string(),
+            &a.c1.value.value.to_hex_string(),
+        )
+    }
+
+    #[test]
+    fn test_quadratic_extension_exhaustive() {
+        assert_eq!(F49::order(), 49u64.into());
+
+        let mut squares = 0;
+        for a in all_f49() {
+            assert_eq!(a.frobenius_map(1), a.pow(&7u64.into()));
+            assert_eq!(a.frobenius_map(2), a);
+            assert_eq!(a.pow(&49u64.into()), a);
+
+            if a.is_zero() {
+                assert!(a.inverse().is_none());
+                continue;
+            }
+            assert_eq!(a.mul(&a.inverse().unwrap()), F49::one());
+
+            match a.sqrt() {
+                Some(root) => {
+                    assert_eq!(root.square(), a);
+                    squares += 1;
+                }
+                None => assert_eq!(a.legendre(), -1),
+            }
+        }
+        assert_eq!(squares, 24);
+
+        // every element of the base field is a square in F49
+        for i in 1..7u64 {
+            assert!(F49::from_base(F7::from(i)).sqrt().is_some());
+        }
+    }
+
+    #[test]
+    fn test_matches_bls12_381_fp2() {
+        let mut rng = TestRng::new();
+        for _ in 0..5 {
+            let a = GenericFp2::random(&mut rng);
+            let b = GenericFp2::random(&mut rng);
+            let (fa, fb) = (to_fp2(&a), to_fp2(&b));
+
+            assert_eq!(to_fp2(&a.mul(&b)), fa.mul(&fb));
+            assert_eq!(to_fp2(&a.square()), fa.square());
+            assert_eq!(to_fp2(&a.sub(&b)), fa.sub(&fb));
+            assert_eq!(to_fp2(&a.inverse().unwrap()), fa.invert().unwrap());
+            assert_eq!(to_fp2(&a.frobenius_map(1)), fa.frobenius_map(1));
+
+            let square = a.square();
+            assert_eq!(square.sqrt().unwrap().square(), square);
+        }
+    }
+}
@@ -0,0 +1,76 @@
+use super::*;
+use std::{
+    iter::Sum,
+    ops::{Add, AddAssign},
+};
+use zkper_modular::ZkperModularInteger;
+
+impl<T: ZkperIntegerTrait, P: ZkperPrimeTrait<T>> Add for ZkperFieldElement<T, P> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        (self.value + rhs.value).into()
+    }
+}
+
+impl<T: ZkperIntegerTrait, P: ZkperPrimeTrait<T>> Add<&ZkperFieldElement<T, P>>
+    for ZkperFieldElement<T, P>
+{
+    type Output = Self;
+
+    fn add(self, rhs: &Self) -> Self::Output {
+        (self.value + &rhs.value).into()
+    }
+}
+
+impl<T: ZkperIntegerTrait, P: ZkperPrimeTrait<T>> Add<&ZkperFieldElement<T, P>>
+    for &ZkperFieldElement<T, P>
+{
+    type Output = ZkperFieldElement<T, P>;
+
+    fn add(self, rhs: &ZkperFieldElement<T, P>) -> Self::Output {
+        (&self.value + &rhs.value).into()
+    }
+}
+
+impl<T: ZkperIntegerTrait, P: ZkperPrimeTrait<T>> Add<ZkperFieldElement<T, P>>
+    for &ZkperFieldElement<T, P>
+{
+    type Output = ZkperFieldElement<T, P>;
+
+    fn add(self, rhs: ZkperFieldElement<T, P>) -> Self::Output {
+        (&self.value + rhs.value).into()
+    }
+}
+
+impl<T: ZkperIntegerTrait, P: ZkperPrimeTrait<T>> AddAssign for ZkperFieldElement<T, P> {
+    fn add_assign(&mut self, rhs: Self) {
+        self.value += rhs.value;
+    }
+}
+
+impl<T: ZkperIntegerTrait, P: ZkperPrimeTrait<T>> AddAssign<&ZkperFieldElement<T, P>>
+    for ZkperFieldElement<T, P>
+{
+    fn add_assign(&mut self, rhs: &Self) {
+        self.value += &rhs.value;
+    }
+}
+
+impl<T: ZkperIntegerTrait, P: ZkperPrimeTrait<T>> Sum for ZkperFieldElement<T, P> {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.map(|x| x.value)
+            .sum::<ZkperModularInteger<T, P>>()
+            .into()
+    }
+}
+
+impl<'a, T: ZkperIntegerTrait, P: ZkperPrimeTrait<T>> Sum<&'a ZkperFieldElement<T, P>>
+    for ZkperFieldElement<T, P>
+{
+    fn sum<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+        iter.map(|x| &x.value)
+            .sum::<ZkperModularInteger<T, P>>()
+            .into()
+    }
+}
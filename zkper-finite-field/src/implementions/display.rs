@@ -0,0 +1,8 @@
+use super::*;
+use std::fmt;
+
+impl<T: ZkperIntegerTrait, P: ZkperPrimeTrait<T>> fmt::Display for ZkperFieldElement<T, P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.value)
+    }
+}
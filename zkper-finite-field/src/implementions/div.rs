@@ -0,0 +1,56 @@
+use super::*;
+use std::ops::{Div, DivAssign};
+
+// Division is multiplication by the inverse and panics on a zero divisor,
+// use `inverse` directly to handle that case.
+impl<T: ZkperIntegerTrait, P: ZkperPrimeTrait<T>> Div for ZkperFieldElement<T, P> {
+    type Output = Self;
+
+    fn div(self, rhs: Self) -> Self::Output {
+        (self.value / rhs.value).into()
+    }
+}
+
+impl<T: ZkperIntegerTrait, P: ZkperPrimeTrait<T>> Div<&ZkperFieldElement<T, P>>
+    for ZkperFieldElement<T, P>
+{
+    type Output = Self;
+
+    fn div(self, rhs: &Self) -> Self::Output {
+        (self.value / &rhs.value).into()
+    }
+}
+
+impl<T: ZkperIntegerTrait, P: ZkperPrimeTrait<T>> Div<&ZkperFieldElement<T, P>>
+    for &ZkperFieldElement<T, P>
+{
+    type Output = ZkperFieldElement<T, P>;
+
+    fn div(self, rhs: &ZkperFieldElement<T, P>) -> Self::Output {
+        (&self.value / &rhs.value).into()
+    }
+}
+
+impl<T: ZkperIntegerTrait, P: ZkperPrimeTrait<T>> Div<ZkperFieldElement<T, P>>
+    for &ZkperFieldElement<T, P>
+{
+    type Output = ZkperFieldElement<T, P>;
+
+    fn div(self, rhs: ZkperFieldElement<T, P>) -> Self::Output {
+        (&self.value / rhs.value).into()
+    }
+}
+
+impl<T: ZkperIntegerTrait, P: ZkperPrimeTrait<T>> DivAssign for ZkperFieldElement<T, P> {
+    fn div_assign(&mut self, rhs: Self) {
+        self.value /= rhs.value;
+    }
+}
+
+impl<T: ZkperIntegerTrait, P: ZkperPrimeTrait<T>> DivAssign<&ZkperFieldElement<T, P>>
+    for ZkperFieldElement<T, P>
+{
+    fn div_assign(&mut self, rhs: &Self) {
+        self.value /= &rhs.value;
+    }
+}
@@ -0,0 +1,54 @@
+use super::*;
+use zkper_integer::ZkperInteger;
+use zkper_modular::ZkperModularInteger;
+
+impl<T: ZkperIntegerTrait, P: ZkperPrimeTrait<T>> From<u32> for ZkperFieldElement<T, P> {
+    fn from(value: u32) -> Self {
+        Self::new(ZkperInteger::from(value))
+    }
+}
+
+impl<T: ZkperIntegerTrait, P: ZkperPrimeTrait<T>> From<i32> for ZkperFieldElement<T, P> {
+    fn from(value: i32) -> Self {
+        Self::new(ZkperInteger::from(value))
+    }
+}
+
+impl<T: ZkperIntegerTrait, P: ZkperPrimeTrait<T>> From<usize> for ZkperFieldElement<T, P> {
+    fn from(value: usize) -> Self {
+        Self::new(ZkperInteger::from(value))
+    }
+}
+
+impl<T: ZkperIntegerTrait, P: ZkperPrimeTrait<T>> From<ZkperInteger<T>>
+    for ZkperFieldElement<T, P>
+{
+    fn from(value: ZkperInteger<T>) -> Self {
+        Self::new(value)
+    }
+}
+
+impl<T: ZkperIntegerTrait, P: ZkperPrimeTrait<T>> From<&ZkperInteger<T>>
+    for ZkperFieldElement<T, P>
+{
+    fn from(value: &ZkperInteger<T>) -> Self {
+        Self::new(value.clone())
+    }
+}
+
+impl<T: ZkperIntegerTrait, P: ZkperPrimeTrait<T>> From<ZkperFieldElement<T, P>>
+    for ZkperModularInteger<T, P>
+{
+    fn from(value: ZkperFieldElement<T, P>) -> Self {
+        value.value
+    }
+}
+
+// the canonical representative in [0, p)
+impl<T: ZkperIntegerTrait, P: ZkperPrimeTrait<T>> From<ZkperFieldElement<T, P>>
+    for ZkperInteger<T>
+{
+    fn from(value: ZkperFieldElement<T, P>) -> Self {
+        value.value.value
+    }
+}
@@ -0,0 +1,12 @@
+use crate::ZkperFieldElement;
+use zkper_integer::traits::ZkperIntegerTrait;
+use zkper_modular::traits::ZkperPrimeTrait;
+
+pub mod display;
+pub mod from;
+
+pub mod add;
+pub mod div;
+pub mod mul;
+pub mod neg;
+pub mod sub;
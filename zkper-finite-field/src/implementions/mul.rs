@@ -0,0 +1,76 @@
+use super::*;
+use std::{
+    iter::Product,
+    ops::{Mul, MulAssign},
+};
+use zkper_modular::ZkperModularInteger;
+
+impl<T: ZkperIntegerTrait, P: ZkperPrimeTrait<T>> Mul for ZkperFieldElement<T, P> {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        (self.value * rhs.value).into()
+    }
+}
+
+impl<T: ZkperIntegerTrait, P: ZkperPrimeTrait<T>> Mul<&ZkperFieldElement<T, P>>
+    for ZkperFieldElement<T, P>
+{
+    type Output = Self;
+
+    fn mul(self, rhs: &Self) -> Self::Output {
+        (self.value * &rhs.value).into()
+    }
+}
+
+impl<T: ZkperIntegerTrait, P: ZkperPrimeTrait<T>> Mul<&ZkperFieldElement<T, P>>
+    for &ZkperFieldElement<T, P>
+{
+    type Output = ZkperFieldElement<T, P>;
+
+    fn mul(self, rhs: &ZkperFieldElement<T, P>) -> Self::Output {
+        (&self.value * &rhs.value).into()
+    }
+}
+
+impl<T: ZkperIntegerTrait, P: ZkperPrimeTrait<T>> Mul<ZkperFieldElement<T, P>>
+    for &ZkperFieldElement<T, P>
+{
+    type Output = ZkperFieldElement<T, P>;
+
+    fn mul(self, rhs: ZkperFieldElement<T, P>) -> Self::Output {
+        (&self.value * rhs.value).into()
+    }
+}
+
+impl<T: ZkperIntegerTrait, P: ZkperPrimeTrait<T>> MulAssign for ZkperFieldElement<T, P> {
+    fn mul_assign(&mut self, rhs: Self) {
+        self.value *= rhs.value;
+    }
+}
+
+impl<T: ZkperIntegerTrait, P: ZkperPrimeTrait<T>> MulAssign<&ZkperFieldElement<T, P>>
+    for ZkperFieldElement<T, P>
+{
+    fn mul_assign(&mut self, rhs: &Self) {
+        self.value *= &rhs.value;
+    }
+}
+
+impl<T: ZkperIntegerTrait, P: ZkperPrimeTrait<T>> Product for ZkperFieldElement<T, P> {
+    fn product<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.map(|x| x.value)
+            .product::<ZkperModularInteger<T, P>>()
+            .into()
+    }
+}
+
+impl<'a, T: ZkperIntegerTrait, P: ZkperPrimeTrait<T>> Product<&'a ZkperFieldElement<T, P>>
+    for ZkperFieldElement<T, P>
+{
+    fn product<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+        iter.map(|x| &x.value)
+            .product::<ZkperModularInteger<T, P>>()
+            .into()
+    }
+}
@@ -0,0 +1,18 @@
+use super::*;
+use std::ops::Neg;
+
+impl<T: ZkperIntegerTrait, P: ZkperPrimeTrait<T>> Neg for ZkperFieldElement<T, P> {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        (-self.value).into()
+    }
+}
+
+impl<T: ZkperIntegerTrait, P: ZkperPrimeTrait<T>> Neg for &ZkperFieldElement<T, P> {
+    type Output = ZkperFieldElement<T, P>;
+
+    fn neg(self) -> Self::Output {
+        (-&self.value).into()
+    }
+}
@@ -0,0 +1,54 @@
+use super::*;
+use std::ops::{Sub, SubAssign};
+
+impl<T: ZkperIntegerTrait, P: ZkperPrimeTrait<T>> Sub for ZkperFieldElement<T, P> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        (self.value - rhs.value).into()
+    }
+}
+
+impl<T: ZkperIntegerTrait, P: ZkperPrimeTrait<T>> Sub<&ZkperFieldElement<T, P>>
+    for ZkperFieldElement<T, P>
+{
+    type Output = Self;
+
+    fn sub(self, rhs: &Self) -> Self::Output {
+        (self.value - &rhs.value).into()
+    }
+}
+
+impl<T: ZkperIntegerTrait, P: ZkperPrimeTrait<T>> Sub<&ZkperFieldElement<T, P>>
+    for &ZkperFieldElement<T, P>
+{
+    type Output = ZkperFieldElement<T, P>;
+
+    fn sub(self, rhs: &ZkperFieldElement<T, P>) -> Self::Output {
+        (&self.value - &rhs.value).into()
+    }
+}
+
+impl<T: ZkperIntegerTrait, P: ZkperPrimeTrait<T>> Sub<ZkperFieldElement<T, P>>
+    for &ZkperFieldElement<T, P>
+{
+    type Output = ZkperFieldElement<T, P>;
+
+    fn sub(self, rhs: ZkperFieldElement<T, P>) -> Self::Output {
+        (&self.value - rhs.value).into()
+    }
+}
+
+impl<T: ZkperIntegerTrait, P: ZkperPrimeTrait<T>> SubAssign for ZkperFieldElement<T, P> {
+    fn sub_assign(&mut self, rhs: Self) {
+        self.value -= rhs.value;
+    }
+}
+
+impl<T: ZkperIntegerTrait, P: ZkperPrimeTrait<T>> SubAssign<&ZkperFieldElement<T, P>>
+    for ZkperFieldElement<T, P>
+{
+    fn sub_assign(&mut self, rhs: &Self) {
+        self.value -= &rhs.value;
+    }
+}
@@ -1,14 +1,185 @@
+use rand_core::RngCore;
 use traits::ZkperFieldTrait;
-use zkper_integer::traits::ZkperIntegerTrait;
+use zkper_integer::{traits::ZkperIntegerTrait, ZkperInteger};
 use zkper_modular::{traits::ZkperPrimeTrait, ZkperModularInteger};
 
 pub mod backends;
+pub mod extensions;
+pub mod implementions;
 pub mod traits;
 pub mod utils;
 
+/// An element of the prime field Fp, p = `P::value()`.
 #[derive(Debug, Clone)]
-pub struct ZkperFieldElement<T: ZkperIntegerTrait, P: ZkperPrimeTrait<T>, F: ZkperFieldTrait<T, P>>
-{
+pub struct ZkperFieldElement<T: ZkperIntegerTrait, P: ZkperPrimeTrait<T>> {
     pub value: ZkperModularInteger<T, P>,
-    pub _field: std::marker::PhantomData<F>,
+}
+
+impl<T: ZkperIntegerTrait, P: ZkperPrimeTrait<T>> ZkperFieldElement<T, P> {
+    pub fn new(value: ZkperInteger<T>) -> Self {
+        ZkperModularInteger::new(value).into()
+    }
+}
+
+impl<T: ZkperIntegerTrait, P: ZkperPrimeTrait<T>> From<ZkperModularInteger<T, P>>
+    for ZkperFieldElement<T, P>
+{
+    fn from(value: ZkperModularInteger<T, P>) -> Self {
+        Self { value }
+    }
+}
+
+impl<T: ZkperIntegerTrait, P: ZkperPrimeTrait<T>> From<u64> for ZkperFieldElement<T, P> {
+    fn from(value: u64) -> Self {
+        Self::new(ZkperInteger::from(value))
+    }
+}
+
+impl<T: ZkperIntegerTrait, P: ZkperPrimeTrait<T>> PartialEq for ZkperFieldElement<T, P> {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+impl<T: ZkperIntegerTrait, P: ZkperPrimeTrait<T>> Eq for ZkperFieldElement<T, P> {}
+
+impl<T: ZkperIntegerTrait, P: ZkperPrimeTrait<T>> ZkperFieldTrait for ZkperFieldElement<T, P> {
+    type Integer = T;
+
+    fn zero() -> Self {
+        ZkperModularInteger::zero().into()
+    }
+
+    fn one() -> Self {
+        ZkperModularInteger::one().into()
+    }
+
+    fn is_zero(&self) -> bool {
+        self.value.is_zero()
+    }
+
+    fn add(&self, rhs: &Self) -> Self {
+        (self.value.clone() + &rhs.value).into()
+    }
+
+    fn sub(&self, rhs: &Self) -> Self {
+        (self.value.clone() - &rhs.value).into()
+    }
+
+    fn mul(&self, rhs: &Self) -> Self {
+        (self.value.clone() * &rhs.value).into()
+    }
+
+    fn neg(&self) -> Self {
+        (-&self.value).into()
+    }
+
+    fn inverse(&self) -> Option<Self> {
+        self.value.inverse().map(Self::from)
+    }
+
+    fn characteristic() -> ZkperInteger<T> {
+        P::value()
+    }
+
+    fn degree() -> u64 {
+        1
+    }
+
+    /// x^p = x in the prime field.
+    fn frobenius_map(&self, _power: usize) -> Self {
+        self.clone()
+    }
+
+    fn random<R: RngCore + ?Sized>(rng: &mut R) -> Self {
+        ZkperModularInteger::random(rng).into()
+    }
+
+    fn pow(&self, exp: &ZkperInteger<T>) -> Self {
+        self.value.pow(exp).into()
+    }
+
+    fn legendre(&self) -> i32 {
+        self.value.legendre()
+    }
+
+    fn sqrt(&self) -> Option<Self> {
+        self.value.sqrt().map(Self::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zkper_integer::backends::rug_backend::RugBackend;
+    use zkper_modular::zkper_prime;
+    use zkper_rand::TestRng;
+
+    use super::*;
+
+    zkper_prime!(Prime7 = "0x7");
+
+    type F7 = ZkperFieldElement<RugBackend, Prime7>;
+
+    #[test]
+    fn test_prime_field_arithmetic() {
+        let a = F7::from(3u64);
+        let b = F7::from(5u64);
+
+        assert_eq!(a.add(&b), F7::from(1u64));
+        assert_eq!(a.sub(&b), F7::from(5u64));
+        assert_eq!(a.mul(&b), F7::from(1u64));
+        assert_eq!(a.neg(), F7::from(4u64));
+        assert_eq!(a.inverse().unwrap(), b);
+        assert!(F7::zero().inverse().is_none());
+        assert_eq!(a.pow(&6u64.into()), F7::one());
+        assert_eq!(a.frobenius_map(1), a);
+        assert_eq!(F7::order(), 7u64.into());
+        assert_eq!(F7::degree(), 1);
+    }
+
+    #[test]
+    fn test_operators() {
+        let a = F7::from(3u64);
+        let b = F7::from(5u32);
+
+        // the same results as the ZkperFieldTrait methods
+        assert_eq!(a.clone() + b.clone(), ZkperFieldTrait::add(&a, &b));
+        assert_eq!(&a - &b, ZkperFieldTrait::sub(&a, &b));
+        assert_eq!(&a * b.clone(), ZkperFieldTrait::mul(&a, &b));
+        assert_eq!(-&a, ZkperFieldTrait::neg(&a));
+        assert_eq!(-a.clone(), F7::from(-3));
+        assert_eq!(&a / &b * &b, a);
+        assert_eq!(a.clone() / b.clone(), F7::from(2usize));
+
+        let mut c = a.clone();
+        c += &b;
+        c *= b.clone();
+        c -= &a;
+        c /= a.clone();
+        assert_eq!(c, F7::from(3u64));
+
+        let values = [a.clone(), b.clone(), F7::from(6u64)];
+        assert_eq!(values.iter().sum::<F7>(), F7::from(0u64));
+        assert_eq!(values.into_iter().product::<F7>(), F7::from(6u64));
+
+        assert_eq!(F7::from(ZkperInteger::from(10u64)), a);
+        assert_eq!(ZkperInteger::from(b.clone()), 5u64.into());
+        assert_eq!(ZkperModularInteger::from(b.clone()), b.value);
+        assert_eq!(b.to_string(), "5");
+    }
+
+    #[test]
+    fn test_prime_field_sqrt() {
+        for i in 0..7u64 {
+            let a = F7::from(i);
+            match a.sqrt() {
+                Some(root) => assert_eq!(root.square(), a),
+                None => assert_eq!(a.legendre(), -1),
+            }
+        }
+
+        let mut rng = TestRng::new();
+        let a = F7::random(&mut rng);
+        assert_eq!(a.square().sqrt().unwrap().square(), a.square());
+    }
 }
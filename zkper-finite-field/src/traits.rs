@@ -1,10 +1,141 @@
-use std::{fmt::Debug, hash::Hash};
+use std::fmt::Debug;
 
-use zkper_integer::traits::ZkperIntegerTrait;
-use zkper_modular::traits::ZkperPrimeTrait;
+use rand_core::RngCore;
+use zkper_integer::{traits::ZkperIntegerTrait, ZkperInteger};
+use zkper_rand::ZkperRng;
 
 /// define behavior of a finite field
-pub trait ZkperFieldTrait<T: ZkperIntegerTrait, P: ZkperPrimeTrait<T>>:
-    Clone + Sized + Hash + Default + Debug
-{
+///
+/// Implemented by prime field elements (`ZkperFieldElement`) and by the
+/// generic extensions in `extensions`, so towers such as Fp2 -> Fp6 -> Fp12
+/// can be built by nesting.
+pub trait ZkperFieldTrait: Clone + Sized + Debug + PartialEq + Eq {
+    /// Integer backend of the underlying prime field.
+    type Integer: ZkperIntegerTrait;
+
+    fn zero() -> Self;
+    fn one() -> Self;
+    fn is_zero(&self) -> bool;
+    fn is_one(&self) -> bool {
+        *self == Self::one()
+    }
+
+    fn add(&self, rhs: &Self) -> Self;
+    fn sub(&self, rhs: &Self) -> Self;
+    fn mul(&self, rhs: &Self) -> Self;
+    fn neg(&self) -> Self;
+    fn double(&self) -> Self {
+        self.add(self)
+    }
+    fn square(&self) -> Self {
+        self.mul(self)
+    }
+
+    /// Multiplicative inverse, None for zero.
+    fn inverse(&self) -> Option<Self>;
+
+    /// The characteristic p of the field.
+    fn characteristic() -> ZkperInteger<Self::Integer>;
+
+    /// Degree of the field over its prime subfield.
+    fn degree() -> u64;
+
+    /// Number of elements, p^degree.
+    fn order() -> ZkperInteger<Self::Integer> {
+        let p = Self::characteristic();
+        let mut order = ZkperInteger::one();
+        for _ in 0..Self::degree() {
+            order *= &p;
+        }
+        order
+    }
+
+    /// Frobenius hook: raises the element to p^power.
+    fn frobenius_map(&self, power: usize) -> Self;
+
+    fn random<R: RngCore + ?Sized>(rng: &mut R) -> Self;
+
+    fn pow(&self, exp: &ZkperInteger<Self::Integer>) -> Self {
+        let mut result = Self::one();
+        let mut base = self.clone();
+        let mut exp = exp.clone();
+        while !exp.is_zero() {
+            if exp.is_odd() {
+                result = result.mul(&base);
+            }
+            base = base.square();
+            exp >>= 1u32;
+        }
+        result
+    }
+
+    /// Euler's criterion over the whole field: 0, 1 or -1.
+    fn legendre(&self) -> i32 {
+        if self.is_zero() {
+            return 0;
+        }
+
+        let exp = (Self::order() - 1u64) >> 1u32;
+        if self.pow(&exp).is_one() {
+            1
+        } else {
+            -1
+        }
+    }
+
+    /// Square root by Tonelli–Shanks over a field of order q, None if the
+    /// element is a non-residue. Only valid for odd characteristic.
+    fn sqrt(&self) -> Option<Self> {
+        if self.is_zero() {
+            return Some(Self::zero());
+        }
+        if self.legendre() != 1 {
+            return None;
+        }
+
+        // q - 1 = t * 2^s with t odd
+        let mut t = Self::order() - 1u64;
+        let mut s = 0u32;
+        while t.is_even() {
+            t >>= 1u32;
+            s += 1;
+        }
+
+        // elements of a proper subfield are all squares in even-degree
+        // extensions, so search with a fixed-seed rng instead of 2, 3, ...
+        let mut rng = ZkperRng::from_seed(0);
+        let z = loop {
+            let z = Self::random(&mut rng);
+            if z.legendre() == -1 {
+                break z;
+            }
+        };
+
+        let mut m = s;
+        let mut c = z.pow(&t);
+        let mut b = self.pow(&t);
+        let mut r = self.pow(&((t + 1u64) >> 1u32));
+
+        while !b.is_one() {
+            // least i with b^(2^i) = 1
+            let mut i = 0;
+            let mut b2i = b.clone();
+            while !b2i.is_one() {
+                b2i = b2i.square();
+                i += 1;
+            }
+
+            let mut e = c;
+            for _ in 0..(m - i - 1) {
+                e = e.square();
+            }
+
+            m = i;
+            c = e.square();
+            b = b.mul(&c);
+            r = r.mul(&e);
+        }
+
+        Some(r)
+    }
 }
@@ -0,0 +1,68 @@
+//! Every multiplication path at the sizes around the thresholds of
+//! `zkper_integer::limb_mul`.
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use rand_core::RngCore;
+use zkper_integer::limb_mul::{
+    mul_6, mul_karatsuba, mul_schoolbook, mul_toom3, square_karatsuba, square_schoolbook,
+    square_toom3,
+};
+use zkper_rand::TestRng;
+
+type Mul = fn(&[u64], &[u64], &mut [u64]);
+type Square = fn(&[u64], &mut [u64]);
+
+const SIZES: [usize; 9] = [6, 16, 32, 64, 96, 128, 256, 512, 1024];
+
+fn limbs(rng: &mut TestRng, n: usize) -> Vec<u64> {
+    (0..n).map(|_| rng.next_u64()).collect()
+}
+
+fn bench_mul(c: &mut Criterion) {
+    let mut rng = TestRng::new();
+    let mut group = c.benchmark_group("limb_mul");
+    for n in SIZES {
+        let (a, b) = (limbs(&mut rng, n), limbs(&mut rng, n));
+        let mut out = vec![0; 2 * n];
+        let paths: [(&str, Mul); 3] = [
+            ("schoolbook", mul_schoolbook),
+            ("karatsuba", mul_karatsuba),
+            ("toom3", mul_toom3),
+        ];
+        for (name, f) in paths {
+            group.bench_with_input(BenchmarkId::new(name, n), &n, |bench, _| {
+                bench.iter(|| f(black_box(&a), black_box(&b), &mut out))
+            });
+        }
+    }
+
+    let a: [u64; 6] = core::array::from_fn(|_| rng.next_u64());
+    let b: [u64; 6] = core::array::from_fn(|_| rng.next_u64());
+    group.bench_function("mul_6", |bench| {
+        bench.iter(|| mul_6(black_box(&a), black_box(&b)))
+    });
+    group.finish();
+}
+
+fn bench_square(c: &mut Criterion) {
+    let mut rng = TestRng::new();
+    let mut group = c.benchmark_group("limb_square");
+    for n in SIZES {
+        let a = limbs(&mut rng, n);
+        let mut out = vec![0; 2 * n];
+        let paths: [(&str, Square); 3] = [
+            ("schoolbook", square_schoolbook),
+            ("karatsuba", square_karatsuba),
+            ("toom3", square_toom3),
+        ];
+        for (name, f) in paths {
+            group.bench_with_input(BenchmarkId::new(name, n), &n, |bench, _| {
+                bench.iter(|| f(black_box(&a), &mut out))
+            });
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_mul, bench_square);
+criterion_main!(benches);
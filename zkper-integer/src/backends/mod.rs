@@ -1,7 +1,180 @@
 pub use super::traits;
-use crate::ZkperIntegerTrait;
-use std::{cmp::Ordering, str::FromStr};
-use zkper_rand::ZkperRng;
+use crate::{error::IntegerError, ZkperIntegerTrait};
+use core::{cmp::Ordering, str::FromStr};
 
+#[cfg(feature = "backend-rug")]
 pub mod rug_backend;
+#[cfg(feature = "backend-native")]
+pub mod u128_backend;
+#[cfg(feature = "backend-native")]
 pub mod u32_backend;
+#[cfg(feature = "backend-native")]
+pub mod u64_backend;
+#[cfg(feature = "backend-native")]
+mod word;
+
+#[cfg(test)]
+mod tests {
+    use crate::ZkperInteger;
+
+    use super::*;
+    use zkper_rand::TestRng;
+
+    // every enabled backend has to agree on the same small-number arithmetic
+    fn check_backend<T: ZkperIntegerTrait>() {
+        let n = |i: i32| ZkperInteger::<T>::from_i32(i);
+
+        assert_eq!(n(6).multiply(&n(7)), n(42));
+        assert_eq!(n(42).subtract(&n(2)).divide(&n(8)), n(5));
+        assert_eq!(n(3).pow_mod(&n(4), &n(17)), n(13));
+        assert_eq!(n(40).reduce(&n(17)), n(6));
+        assert_eq!(n(34).reduce(&n(17)), n(0));
+        assert_eq!(n(3).neg_mod(&n(17)), n(14));
+        assert_eq!(n(20).neg_mod(&n(17)), n(14));
+        assert_eq!(n(17).neg_mod(&n(17)), n(0));
+        assert_eq!(n(3).invert(&n(17)).unwrap(), n(6));
+        assert_eq!(n(84).gcd(&n(36)), n(12));
+        assert_eq!(n(1).shift_left(5).shift_right_32(2), n(8));
+        assert!(n(97).is_prime());
+        assert!(n(9).is_odd() && !n(9).is_prime());
+        assert_eq!(ZkperInteger::<T>::from_hex_str("0x2a"), n(42));
+        assert_eq!(
+            n(0x1234).stable_hash(),
+            crate::hash::stable_hash(false, &[0x12, 0x34])
+        );
+        assert_eq!(n(0).stable_hash(), crate::hash::stable_hash(false, &[]));
+
+        let bits = n(0b1011);
+        assert_eq!(bits.bit_length(), 4);
+        assert!(bits.test_bit(1) && !bits.test_bit(2) && !bits.test_bit(31));
+        assert_eq!(bits.set_bit(2, true), n(0b1111));
+        assert_eq!(bits.set_bit(0, false), n(0b1010));
+        assert_eq!(bits.set_bit(31, false), bits);
+        assert_eq!(bits.count_ones(), 3);
+        assert_eq!(bits.to_bits_le(), [true, true, false, true]);
+        assert_eq!(bits.to_bits_be(), [true, false, true, true]);
+        assert_eq!(n(0).bit_length(), 0);
+        assert!(n(0).to_bits_le().is_empty());
+        assert_eq!(n(0).set_bit(5, true), n(32));
+    }
+
+    #[cfg(feature = "backend-rug")]
+    #[test]
+    fn test_rug_backend() {
+        check_backend::<rug_backend::RugBackend>();
+
+        // bits of the absolute value, the sign kept
+        let n = ZkperInteger::<rug_backend::RugBackend>::from_i32;
+        assert_eq!(n(-1).reduce(&n(17)), n(16));
+        assert_eq!(n(-18).reduce(&n(17)), n(16));
+        assert_eq!(n(-3).neg_mod(&n(17)), n(3));
+        assert_eq!(n(-3).pow_mod(&n(3), &n(17)), n(-27).reduce(&n(17)));
+        assert_eq!(n(-11).bit_length(), 4);
+        assert_eq!(n(-11).count_ones(), 3);
+        assert_eq!(n(-11).set_bit(2, true), n(-15));
+        assert_eq!(n(-11).to_bits_le(), n(11).to_bits_le());
+        assert!(n(1).set_bit(200, true).test_bit(200));
+    }
+
+    #[cfg(feature = "backend-native")]
+    #[test]
+    fn test_native_backend() {
+        check_backend::<u32_backend::U32Backed>();
+        check_backend::<u64_backend::U64Backend>();
+        check_backend::<u128_backend::U128Backend>();
+    }
+
+    // the word backends compute modulo full-width moduli without wrapping
+    #[cfg(feature = "backend-native")]
+    fn check_word_backend<T: ZkperIntegerTrait>(modulus: &str, bits: u64) {
+        let n = |s: &str| ZkperInteger::<T>::from_str(s);
+        let p = n(modulus);
+        let p_minus_one = p.subtract(&n("1"));
+
+        assert!(p.is_prime());
+        assert!(!p_minus_one.is_prime());
+        // Fermat, through products far wider than the word
+        assert_eq!(p_minus_one.pow_mod(&p_minus_one, &p), n("1"));
+        assert_eq!(n("3").pow_mod(&p_minus_one, &p), n("1"));
+        let inverse = p_minus_one.invert(&p).unwrap();
+        assert_eq!(inverse, p_minus_one);
+        let inverse = n("12345").invert(&p).unwrap();
+        assert_eq!(n("12345").pow_mod(&p.subtract(&n("2")), &p), inverse);
+        assert!(n("6").invert(&n("9")).is_err());
+
+        // round trips, short inputs padded
+        assert_eq!(ZkperInteger::<T>::from_bytes(&p.to_bytes()), p);
+        assert_eq!(ZkperInteger::<T>::from_bytes(&[0x2a]), n("42"));
+        assert_eq!(
+            n("1").shift_left(bits - 1).find_first_one(0),
+            Some(bits as u32 - 1)
+        );
+
+        let mut rng = TestRng::new();
+        for _ in 0..64 {
+            assert!(p.random_below(&mut rng) < p);
+            assert!(n("3").random_below(&mut rng) < n("3"));
+        }
+    }
+
+    #[cfg(feature = "backend-native")]
+    #[test]
+    fn test_word_backends() {
+        // the largest primes below 2^64 and 2^128
+        check_word_backend::<u64_backend::U64Backend>("18446744073709551557", 64);
+        check_word_backend::<u128_backend::U128Backend>(
+            "340282366920938463463374607431768211297",
+            128,
+        );
+        check_word_backend::<u128_backend::U128Backend>("18446744073709551557", 128);
+
+        // Carmichael numbers and strong pseudoprimes to small bases
+        for composite in ["561", "3215031751", "3825123056546413051"] {
+            assert!(!ZkperInteger::<u64_backend::U64Backend>::from_str(composite).is_prime());
+            assert!(!ZkperInteger::<u128_backend::U128Backend>::from_str(composite).is_prime());
+        }
+    }
+
+    #[cfg(feature = "backend-native")]
+    #[test]
+    #[should_panic(expected = "subtraction overflow")]
+    fn test_word_backend_underflow() {
+        let n = ZkperInteger::<u64_backend::U64Backend>::from_i32;
+        let _ = n(1).subtract(&n(2));
+    }
+
+    #[cfg(feature = "backend-native")]
+    #[test]
+    #[should_panic(expected = "set_bit overflow")]
+    fn test_word_backend_set_bit_overflow() {
+        let _ = ZkperInteger::<u64_backend::U64Backend>::from_i32(1).set_bit(64, true);
+    }
+
+    #[cfg(feature = "backend-native")]
+    #[test]
+    #[should_panic(expected = "multiplication overflow")]
+    fn test_word_backend_overflow() {
+        let n = ZkperInteger::<u64_backend::U64Backend>::from;
+        let _ = n(1u64 << 32).multiply(&n(1u64 << 32));
+    }
+
+    #[cfg(feature = "backend-native")]
+    #[test]
+    fn test_word_backend_try_from_str() {
+        use crate::error::IntegerError;
+
+        let parse = ZkperInteger::<u64_backend::U64Backend>::try_from_str;
+        assert_eq!(
+            parse("18446744073709551615").unwrap(),
+            ZkperInteger::from(u64::MAX)
+        );
+        assert_eq!(
+            parse("18446744073709551616").unwrap_err(),
+            IntegerError::InvalidString
+        );
+        assert_eq!(parse("12a").unwrap_err(), IntegerError::InvalidString);
+        assert!(
+            ZkperInteger::<u128_backend::U128Backend>::try_from_str("18446744073709551616").is_ok()
+        );
+    }
+}
@@ -1,28 +1,31 @@
 use super::*;
 
 use num_traits::One;
+use rand_core::RngCore;
 use rug::{
     integer::{BorrowInteger, MiniInteger},
+    ops::RemRounding,
     rand::ThreadRandState,
     Integer,
 };
+use zkper_rand::implements::rug::RugRng;
 
 #[derive(Debug, Clone, Hash, Default)]
 pub struct RugBackend(pub Integer);
 
-pub const INTEGER_TWO: &'static Integer = {
+pub const INTEGER_TWO: &Integer = {
     const MINI: MiniInteger = MiniInteger::const_from_u8(2);
     const BORROW: BorrowInteger = MINI.borrow();
     BorrowInteger::const_deref(&BORROW)
 };
 
-pub const INTEGER_THREE: &'static Integer = {
+pub const INTEGER_THREE: &Integer = {
     const MINI: MiniInteger = MiniInteger::const_from_u8(3);
     const BORROW: BorrowInteger = MINI.borrow();
     BorrowInteger::const_deref(&BORROW)
 };
 
-pub const INTEGER_FOUR: &'static Integer = {
+pub const INTEGER_FOUR: &Integer = {
     const MINI: MiniInteger = MiniInteger::const_from_u8(4);
     const BORROW: BorrowInteger = MINI.borrow();
     BorrowInteger::const_deref(&BORROW)
@@ -46,6 +49,12 @@ impl ZkperIntegerTrait for RugBackend {
         Self(value)
     }
 
+    fn try_from_str(s: &str) -> Result<Self, IntegerError> {
+        Integer::from_str(s)
+            .map(Self)
+            .map_err(|_| IntegerError::InvalidString)
+    }
+
     fn from_u64(u: u64) -> Self {
         let value = Integer::from(u);
         Self(value)
@@ -123,9 +132,14 @@ impl ZkperIntegerTrait for RugBackend {
     }
 
     fn pow_mod(&self, exp: &Self, modulus: &Self) -> Self {
+        // mpz_powm already works with sliding windows, see crate::pow
         Self(self.0.clone().pow_mod(&exp.0, &modulus.0).unwrap())
     }
 
+    fn reduce(&self, modulus: &Self) -> Self {
+        Self(RemRounding::rem_euc(self.0.clone(), &modulus.0))
+    }
+
     fn is_divisible(&self, other: &Self) -> bool {
         self.0.is_divisible(&other.0)
     }
@@ -139,7 +153,7 @@ impl ZkperIntegerTrait for RugBackend {
             .clone()
             .invert(&modulus.0)
             .map(Self)
-            .map_err(|e| anyhow::anyhow!(e))
+            .map_err(|_| IntegerError::NoInverse.into())
     }
 
     fn to_string(&self) -> String {
@@ -166,8 +180,9 @@ impl ZkperIntegerTrait for RugBackend {
         Self(Integer::from(u))
     }
 
-    fn random_below(&self, rng: &mut ZkperRng) -> Self {
-        let mut rng: ThreadRandState<'_> = ThreadRandState::new_custom(rng);
+    fn random_below<R: RngCore + ?Sized>(&self, rng: &mut R) -> Self {
+        let mut rng = RugRng(rng);
+        let mut rng: ThreadRandState<'_> = ThreadRandState::new_custom(&mut rng);
         self.0.clone().random_below(&mut rng).into()
     }
 
@@ -193,6 +208,27 @@ impl ZkperIntegerTrait for RugBackend {
         Self(self.0.clone() << (n as usize))
     }
 
+    fn bit_length(&self) -> u32 {
+        self.0.significant_bits()
+    }
+
+    fn test_bit(&self, i: u32) -> bool {
+        self.0.as_abs().get_bit(i)
+    }
+
+    fn set_bit(&self, i: u32, value: bool) -> Self {
+        let mut magnitude = self.0.clone().abs();
+        magnitude.set_bit(i, value);
+        if self.0 < 0 {
+            magnitude = -magnitude;
+        }
+        Self(magnitude)
+    }
+
+    fn count_ones(&self) -> u32 {
+        self.0.as_abs().count_ones().unwrap_or(0)
+    }
+
     fn to_bytes(&self) -> Vec<u8> {
         self.0.to_digits(rug::integer::Order::Lsf)
     }
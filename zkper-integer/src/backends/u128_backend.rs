@@ -0,0 +1,33 @@
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use rand_core::RngCore;
+
+use super::word::{word_backend, MILLER_RABIN_BASES};
+use super::*;
+
+/// A single u128, for moduli up to 128 bits without GMP.
+#[derive(Debug, Clone, Hash, Default)]
+pub struct U128Backend(pub u128);
+
+impl U128Backend {
+    /// a * b mod m by double-and-add, as there is no wider word to widen to.
+    fn mul_mod(a: u128, b: u128, m: u128) -> u128 {
+        let add_mod = |a: u128, b: u128| if a >= m - b { a - (m - b) } else { a + b };
+
+        let (mut a, mut b) = (a % m, b % m);
+        let mut result = 0;
+        while b > 0 {
+            if b & 1 == 1 {
+                result = add_mod(result, a);
+            }
+            a = add_mod(a, a);
+            b >>= 1;
+        }
+        result
+    }
+}
+
+word_backend!(U128Backend, u128);
@@ -1,4 +1,9 @@
-use rand::RngCore;
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use rand_core::RngCore;
 
 use super::*;
 
@@ -18,6 +23,12 @@ impl ZkperIntegerTrait for U32Backed {
         Self(value)
     }
 
+    fn try_from_str(s: &str) -> Result<Self, IntegerError> {
+        u32::from_str(s)
+            .map(Self)
+            .map_err(|_| IntegerError::InvalidString)
+    }
+
     fn from_u64(u: u64) -> Self {
         Self(u as u32)
     }
@@ -95,19 +106,29 @@ impl ZkperIntegerTrait for U32Backed {
     }
 
     fn pow_mod(&self, exp: &Self, modulus: &Self) -> Self {
-        Self(self.0.wrapping_pow(exp.0) % modulus.0)
+        // in u64, the products of two residues fit
+        let modulus = modulus.0 as u64;
+        let exp = exp.0;
+        let result = crate::pow::sliding_window_pow(
+            &(self.0 as u64 % modulus),
+            u32::BITS - exp.leading_zeros(),
+            |i| (exp >> i) & 1 == 1,
+            1 % modulus,
+            |a, b| a * b % modulus,
+        );
+        Self(result as u32)
     }
 
     fn is_divisible(&self, other: &Self) -> bool {
-        self.0 % other.0 == 0
+        self.0.is_multiple_of(other.0)
     }
 
     fn is_prime(&self) -> bool {
         if self.0 <= 1 {
             return false;
         }
-        for i in 2..=(self.0 as f64).sqrt() as u32 {
-            if self.0 % i == 0 {
+        for i in 2..=self.0.isqrt() {
+            if self.0.is_multiple_of(i) {
                 return false;
             }
         }
@@ -120,7 +141,7 @@ impl ZkperIntegerTrait for U32Backed {
                 return Ok(Self(i));
             }
         }
-        Err(anyhow::anyhow!("No modular inverse found"))
+        Err(IntegerError::NoInverse.into())
     }
 
     fn to_string(&self) -> String {
@@ -154,17 +175,12 @@ impl ZkperIntegerTrait for U32Backed {
         Self(u as u32)
     }
 
-    fn random_below(&self, rng: &mut ZkperRng) -> Self {
+    fn random_below<R: RngCore + ?Sized>(&self, rng: &mut R) -> Self {
         Self(rng.next_u32() % self.0)
     }
 
     fn find_first_one(&self, start: u32) -> Option<u32> {
-        for i in start..32 {
-            if (self.0 & (1 << i)) != 0 {
-                return Some(i);
-            }
-        }
-        None
+        (start..32).find(|i| (self.0 & (1 << i)) != 0)
     }
 
     fn shr_32(&self, n: u32) -> Self {
@@ -183,6 +199,27 @@ impl ZkperIntegerTrait for U32Backed {
         Self(self.0 << n)
     }
 
+    fn bit_length(&self) -> u32 {
+        u32::BITS - self.0.leading_zeros()
+    }
+
+    fn test_bit(&self, i: u32) -> bool {
+        i < u32::BITS && (self.0 >> i) & 1 == 1
+    }
+
+    fn set_bit(&self, i: u32, value: bool) -> Self {
+        if i >= u32::BITS {
+            assert!(!value, "set_bit overflow in U32Backed");
+            return Self(self.0);
+        }
+        let bit = 1 << i;
+        Self(if value { self.0 | bit } else { self.0 & !bit })
+    }
+
+    fn count_ones(&self) -> u32 {
+        self.0.count_ones()
+    }
+
     fn to_bytes(&self) -> Vec<u8> {
         self.0.to_le_bytes().to_vec()
     }
@@ -194,10 +231,10 @@ impl ZkperIntegerTrait for U32Backed {
     }
 
     fn is_even(&self) -> bool {
-        self.0 % 2 == 0
+        self.0.is_multiple_of(2)
     }
 
     fn is_odd(&self) -> bool {
-        self.0 % 2 != 0
+        !self.0.is_multiple_of(2)
     }
 }
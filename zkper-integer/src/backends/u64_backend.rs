@@ -0,0 +1,22 @@
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use rand_core::RngCore;
+
+use super::word::{word_backend, MILLER_RABIN_BASES};
+use super::*;
+
+/// A single u64, for moduli up to 64 bits without GMP.
+#[derive(Debug, Clone, Hash, Default)]
+pub struct U64Backend(pub u64);
+
+impl U64Backend {
+    /// a * b mod m, widened to u128.
+    fn mul_mod(a: u64, b: u64, m: u64) -> u64 {
+        (a as u128 * b as u128 % m as u128) as u64
+    }
+}
+
+word_backend!(U64Backend, u64);
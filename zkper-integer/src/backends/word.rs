@@ -0,0 +1,310 @@
+//! Shared implementation of the single-word backends.
+//!
+//! [`word_backend!`] implements [`ZkperIntegerTrait`] for a newtype over an
+//! unsigned machine word. Plain arithmetic is checked: a result the word
+//! cannot hold, negative ones included, panics instead of silently wrapping,
+//! since a wrapped value is a wrong answer to every caller of this trait.
+//! Modular arithmetic works on the full width of the word, through the
+//! overflow-free `mul_mod` each backend provides.
+
+/// Bases of the Miller-Rabin test, deterministic below 3.3 * 10^24.
+pub(crate) const MILLER_RABIN_BASES: [u64; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+
+macro_rules! word_backend {
+    ($name:ident, $word:ty) => {
+        impl $name {
+            const BITS: u32 = <$word>::BITS;
+            const BYTES: usize = (<$word>::BITS / 8) as usize;
+
+            fn checked(value: Option<$word>, op: &str) -> Self {
+                Self(value.unwrap_or_else(|| panic!("{} overflow in {}", op, stringify!($name))))
+            }
+
+            fn pow_mod_word(base: $word, exp: $word, modulus: $word) -> $word {
+                $crate::pow::sliding_window_pow(
+                    &(base % modulus),
+                    Self::BITS - exp.leading_zeros(),
+                    |i| (exp >> i) & 1 == 1,
+                    1 % modulus,
+                    |&a, &b| Self::mul_mod(a, b, modulus),
+                )
+            }
+
+            /// Strong probable prime test to base `a`, for odd n > a.
+            fn is_strong_probable_prime(n: $word, a: $word) -> bool {
+                let s = (n - 1).trailing_zeros();
+                let mut x = Self::pow_mod_word(a, (n - 1) >> s, n);
+                if x == 1 || x == n - 1 {
+                    return true;
+                }
+                for _ in 1..s {
+                    x = Self::mul_mod(x, x, n);
+                    if x == n - 1 {
+                        return true;
+                    }
+                }
+                false
+            }
+        }
+
+        impl ZkperIntegerTrait for $name {
+            fn from_hex_str(hex_str: &str) -> Self {
+                let value =
+                    <$word>::from_str_radix(hex_str.strip_prefix("0x").unwrap_or(hex_str), 16)
+                        .expect("Invalid hex string");
+
+                Self(value)
+            }
+
+            fn from_str(s: &str) -> Self {
+                let value = <$word>::from_str(s).expect("Invalid string");
+                Self(value)
+            }
+
+            fn try_from_str(s: &str) -> Result<Self, IntegerError> {
+                <$word>::from_str(s)
+                    .map(Self)
+                    .map_err(|_| IntegerError::InvalidString)
+            }
+
+            fn from_u64(u: u64) -> Self {
+                Self::checked(<$word>::try_from(u).ok(), "conversion")
+            }
+
+            fn from_u32(u: u32) -> Self {
+                Self::checked(<$word>::try_from(u).ok(), "conversion")
+            }
+
+            fn from_i32(u: i32) -> Self {
+                Self::checked(<$word>::try_from(u).ok(), "conversion")
+            }
+
+            fn one() -> Self {
+                Self(1)
+            }
+
+            fn is_one(&self) -> bool {
+                self.0 == 1
+            }
+
+            fn zero() -> Self {
+                Self(0)
+            }
+
+            fn is_zero(&self) -> bool {
+                self.0 == 0
+            }
+
+            fn two() -> Self {
+                Self(2)
+            }
+
+            fn three() -> Self {
+                Self(3)
+            }
+
+            fn four() -> Self {
+                Self(4)
+            }
+
+            fn sub(&self, rhs: &Self) -> Self {
+                Self::checked(self.0.checked_sub(rhs.0), "subtraction")
+            }
+
+            fn add(&self, rhs: &Self) -> Self {
+                Self::checked(self.0.checked_add(rhs.0), "addition")
+            }
+
+            fn be_added(&self, rhs: Self) -> Self {
+                rhs.add(self)
+            }
+
+            fn add_u64(&self, rhs: u64) -> Self {
+                self.add(&Self::from_u64(rhs))
+            }
+
+            fn neg(&self) -> Self {
+                Self::checked((0 as $word).checked_sub(self.0), "negation")
+            }
+
+            fn div(&self, rhs: &Self) -> Self {
+                Self(self.0 / rhs.0)
+            }
+
+            fn mul(&self, rhs: &Self) -> Self {
+                Self::checked(self.0.checked_mul(rhs.0), "multiplication")
+            }
+
+            fn be_muled(&self, rhs: Self) -> Self {
+                rhs.mul(self)
+            }
+
+            fn rem(&self, rhs: &Self) -> Self {
+                Self(self.0 % rhs.0)
+            }
+
+            fn compare(&self, other: &Self) -> Ordering {
+                self.0.cmp(&other.0)
+            }
+
+            fn pow_mod(&self, exp: &Self, modulus: &Self) -> Self {
+                Self(Self::pow_mod_word(self.0, exp.0, modulus.0))
+            }
+
+            fn gcd(&self, other: &Self) -> Self {
+                let mut a = self.0;
+                let mut b = other.0;
+                while b != 0 {
+                    let t = b;
+                    b = a % b;
+                    a = t;
+                }
+                Self(a)
+            }
+
+            fn is_divisible(&self, other: &Self) -> bool {
+                self.0.is_multiple_of(other.0)
+            }
+
+            fn is_divisible_by_u64(&self, other: u64) -> bool {
+                self.is_divisible(&Self::from_u64(other))
+            }
+
+            /// Miller-Rabin over `MILLER_RABIN_BASES`: exact for every u64,
+            /// a strong probable prime test above 3.3 * 10^24.
+            fn is_prime(&self) -> bool {
+                let n = self.0;
+                if n < 2 {
+                    return false;
+                }
+                for base in MILLER_RABIN_BASES {
+                    let base = base as $word;
+                    if n == base {
+                        return true;
+                    }
+                    if n.is_multiple_of(base) {
+                        return false;
+                    }
+                }
+                MILLER_RABIN_BASES
+                    .iter()
+                    .all(|&base| Self::is_strong_probable_prime(n, base as $word))
+            }
+
+            /// Extended Euclid on the magnitudes of the Bezout coefficients,
+            /// whose signs alternate, so nothing goes below zero or above the
+            /// modulus.
+            fn invert(&self, modulus: &Self) -> anyhow::Result<Self> {
+                let m = modulus.0;
+                if m == 0 {
+                    return Err(IntegerError::NoInverse.into());
+                }
+                let (mut r0, mut r1) = (m, self.0 % m);
+                let (mut t0, mut t1) = (0 as $word, 1 as $word);
+                // whether t0 stands for a negative coefficient
+                let mut negative = true;
+                while r1 != 0 {
+                    let q = r0 / r1;
+                    (r0, r1) = (r1, r0 - q * r1);
+                    (t0, t1) = (t1, t0 + q * t1);
+                    negative = !negative;
+                }
+                if r0 != 1 {
+                    return Err(IntegerError::NoInverse.into());
+                }
+                let inverse = if negative { m - t0 } else { t0 };
+                Ok(Self(inverse % m))
+            }
+
+            /// Rejection sampling over the bit length of `self`, exactly
+            /// uniform.
+            fn random_below<R: RngCore + ?Sized>(&self, rng: &mut R) -> Self {
+                assert!(self.0 > 0, "cannot sample below 0");
+                let bits = Self::BITS - (self.0 - 1).leading_zeros();
+                let mask = <$word>::MAX.checked_shr(Self::BITS - bits).unwrap_or(0);
+                let mut bytes = [0u8; (<$word>::BITS / 8) as usize];
+                loop {
+                    rng.fill_bytes(&mut bytes);
+                    let value = <$word>::from_le_bytes(bytes) & mask;
+                    if value < self.0 {
+                        return Self(value);
+                    }
+                }
+            }
+
+            fn to_string(&self) -> String {
+                self.0.to_string()
+            }
+
+            fn to_hex_string(&self) -> String {
+                format!("{:x}", self.0)
+            }
+
+            fn find_first_one(&self, start: u32) -> Option<u32> {
+                (start..Self::BITS).find(|&i| (self.0 >> i) & 1 == 1)
+            }
+
+            fn shr_32(&self, n: u32) -> Self {
+                Self(self.0.checked_shr(n).unwrap_or(0))
+            }
+
+            fn shr(&self, n: u64) -> Self {
+                self.shr_32(n.try_into().unwrap_or(u32::MAX))
+            }
+
+            fn shl_32(&self, n: u32) -> Self {
+                // every bit shifted out has to be zero
+                let fits = self.0 == 0 || n <= self.0.leading_zeros();
+                Self::checked(fits.then(|| self.0.checked_shl(n).unwrap_or(0)), "shift")
+            }
+
+            fn shl(&self, n: u64) -> Self {
+                self.shl_32(n.try_into().unwrap_or(u32::MAX))
+            }
+
+            fn bit_length(&self) -> u32 {
+                Self::BITS - self.0.leading_zeros()
+            }
+
+            fn test_bit(&self, i: u32) -> bool {
+                i < Self::BITS && (self.0 >> i) & 1 == 1
+            }
+
+            /// Panics when setting a bit past the width of the word.
+            fn set_bit(&self, i: u32, value: bool) -> Self {
+                if i >= Self::BITS {
+                    return Self::checked((!value).then_some(self.0), "set_bit");
+                }
+                let bit = (1 as $word) << i;
+                Self(if value { self.0 | bit } else { self.0 & !bit })
+            }
+
+            fn count_ones(&self) -> u32 {
+                self.0.count_ones()
+            }
+
+            fn to_bytes(&self) -> Vec<u8> {
+                self.0.to_le_bytes().to_vec()
+            }
+
+            /// Little-endian, zero-padded when short; bytes past the width of
+            /// the word are ignored.
+            fn from_bytes(bytes: &[u8]) -> Self {
+                let mut array = [0u8; Self::BYTES];
+                let len = bytes.len().min(Self::BYTES);
+                array[..len].copy_from_slice(&bytes[..len]);
+                Self(<$word>::from_le_bytes(array))
+            }
+
+            fn is_even(&self) -> bool {
+                self.0.is_multiple_of(2)
+            }
+
+            fn is_odd(&self) -> bool {
+                !self.0.is_multiple_of(2)
+            }
+        }
+    };
+}
+
+pub(crate) use word_backend;
@@ -0,0 +1,22 @@
+use core::fmt;
+
+/// Errors of the integer backends.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IntegerError {
+    /// The value shares a factor with the modulus.
+    NoInverse,
+    /// The string is not a decimal number the backend can hold.
+    InvalidString,
+}
+
+// written out instead of derived, as thiserror 1 needs std
+impl fmt::Display for IntegerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IntegerError::NoInverse => f.write_str("No modular inverse found"),
+            IntegerError::InvalidString => f.write_str("Invalid integer string"),
+        }
+    }
+}
+
+impl core::error::Error for IntegerError {}
@@ -0,0 +1,83 @@
+//! Hashing integers the same way on every platform and backend.
+//!
+//! The `Hash` a backend derives feeds whatever it stores into the hasher, the
+//! limbs of a rug `Integer` for instance, whose size and byte order differ
+//! between platforms. A [`StableHasher`] instead sees every integer in one
+//! canonical encoding: a sign byte, the length of the magnitude as a
+//! big-endian u32, then the magnitude in big-endian bytes without leading
+//! zeros. The digest is 64-bit FNV-1a over those bytes.
+
+/// 64-bit FNV-1a over a canonical encoding, see the module documentation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StableHasher(u64);
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+impl Default for StableHasher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StableHasher {
+    pub fn new() -> Self {
+        Self(FNV_OFFSET_BASIS)
+    }
+
+    pub fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 = (self.0 ^ byte as u64).wrapping_mul(FNV_PRIME);
+        }
+    }
+
+    /// Feeds an integer given by its sign and big-endian magnitude. Leading
+    /// zero bytes of `magnitude` are ignored and zero is never negative.
+    pub fn write_integer(&mut self, negative: bool, magnitude: &[u8]) {
+        let start = magnitude
+            .iter()
+            .position(|&byte| byte != 0)
+            .unwrap_or(magnitude.len());
+        let magnitude = &magnitude[start..];
+
+        self.write(&[(negative && !magnitude.is_empty()) as u8]);
+        self.write(&(magnitude.len() as u32).to_be_bytes());
+        self.write(magnitude);
+    }
+
+    pub fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+/// The stable hash of a single integer.
+pub fn stable_hash(negative: bool, magnitude: &[u8]) -> u64 {
+    let mut hasher = StableHasher::new();
+    hasher.write_integer(negative, magnitude);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stable_hash() {
+        // FNV-1a test vectors
+        let mut hasher = StableHasher::new();
+        assert_eq!(hasher.finish(), 0xcbf29ce484222325);
+        hasher.write(b"a");
+        assert_eq!(hasher.finish(), 0xaf63dc4c8601ec8c);
+
+        // fixed digests, whatever the platform
+        assert_eq!(stable_hash(false, &[]), 0xe4bc4fd9252be94f);
+        assert_eq!(stable_hash(false, &[0x12, 0x34]), 0x88aea814c02f4683);
+
+        assert_eq!(
+            stable_hash(false, &[0, 0, 0x12, 0x34]),
+            stable_hash(false, &[0x12, 0x34])
+        );
+        assert_eq!(stable_hash(true, &[0, 0]), stable_hash(false, &[]));
+        assert_ne!(stable_hash(true, &[1]), stable_hash(false, &[1]));
+    }
+}
@@ -1,5 +1,5 @@
 use super::*;
-use std::ops::{Add, AddAssign};
+use core::ops::{Add, AddAssign};
 
 // Implement Add for ZkperInteger<T>
 impl<T: ZkperIntegerTrait> Add for ZkperInteger<T> {
@@ -1,4 +1,5 @@
-use std::cmp::Ordering;
+use core::cmp::Ordering;
+use core::hash::{Hash, Hasher};
 
 use super::*;
 
@@ -12,7 +13,7 @@ impl<T: ZkperIntegerTrait> Eq for ZkperInteger<T> {}
 
 impl<T: ZkperIntegerTrait> PartialOrd for ZkperInteger<T> {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.0.compare(&other.0))
+        Some(self.cmp(other))
     }
 }
 
@@ -21,3 +22,11 @@ impl<T: ZkperIntegerTrait> Ord for ZkperInteger<T> {
         self.0.compare(&other.0)
     }
 }
+
+// through the stable hash, so maps keyed by integers behave the same on
+// every platform and backend
+impl<T: ZkperIntegerTrait> Hash for ZkperInteger<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_u64(self.stable_hash());
+    }
+}
@@ -1,5 +1,5 @@
 use super::*;
-use std::fmt;
+use core::fmt;
 
 impl<T: ZkperIntegerTrait> fmt::Binary for ZkperInteger<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -14,7 +14,7 @@ impl<T: ZkperIntegerTrait> fmt::Binary for ZkperInteger<T> {
 // Implement Display for ZkperInteger<T>
 impl<T: ZkperIntegerTrait> fmt::Display for ZkperInteger<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", self.to_string())
+        write!(f, "{}", self.0.to_string())
     }
 }
 
@@ -1,9 +1,9 @@
-use std::ops::Rem;
+use core::ops::Rem;
 
 use super::*;
 
 // Implement Rem trait for ZkperInteger
-impl<T: ZkperIntegerTrait> std::ops::Rem for ZkperInteger<T> {
+impl<T: ZkperIntegerTrait> core::ops::Rem for ZkperInteger<T> {
     type Output = Self;
 
     fn rem(self, rhs: Self) -> Self::Output {
@@ -29,7 +29,7 @@ impl<T: ZkperIntegerTrait> Rem<&ZkperInteger<T>> for &ZkperInteger<T> {
     }
 }
 
-impl<T: ZkperIntegerTrait> std::ops::Rem<&ZkperInteger<T>> for ZkperInteger<T> {
+impl<T: ZkperIntegerTrait> core::ops::Rem<&ZkperInteger<T>> for ZkperInteger<T> {
     type Output = ZkperInteger<T>;
 
     fn rem(self, rhs: &ZkperInteger<T>) -> Self::Output {
@@ -38,13 +38,13 @@ impl<T: ZkperIntegerTrait> std::ops::Rem<&ZkperInteger<T>> for ZkperInteger<T> {
 }
 
 // Implement RemAssign trait for ZkperInteger
-impl<T: ZkperIntegerTrait> std::ops::RemAssign for ZkperInteger<T> {
+impl<T: ZkperIntegerTrait> core::ops::RemAssign for ZkperInteger<T> {
     fn rem_assign(&mut self, rhs: Self) {
         *self = self.reminder(&rhs);
     }
 }
 
-impl<T: ZkperIntegerTrait> std::ops::RemAssign<&ZkperInteger<T>> for ZkperInteger<T> {
+impl<T: ZkperIntegerTrait> core::ops::RemAssign<&ZkperInteger<T>> for ZkperInteger<T> {
     fn rem_assign(&mut self, rhs: &ZkperInteger<T>) {
         *self = self.reminder(rhs);
     }
@@ -1,5 +1,5 @@
 use super::*;
-use std::ops::{Shl, ShlAssign};
+use core::ops::{Shl, ShlAssign};
 
 impl<T: ZkperIntegerTrait> Shl<u32> for ZkperInteger<T> {
     type Output = Self;
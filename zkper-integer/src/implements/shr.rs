@@ -1,5 +1,5 @@
 use super::*;
-use std::ops::{Shr, ShrAssign};
+use core::ops::{Shr, ShrAssign};
 
 impl<T: ZkperIntegerTrait> Shr<u32> for ZkperInteger<T> {
     type Output = Self;
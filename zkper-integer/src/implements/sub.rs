@@ -1,5 +1,5 @@
 use super::*;
-use std::ops::{Sub, SubAssign};
+use core::ops::{Sub, SubAssign};
 
 // Implement Sub for ZkperInteger<T>
 impl<T: ZkperIntegerTrait> Sub for ZkperInteger<T> {
@@ -1,15 +1,30 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+use alloc::{string::String, vec::Vec};
+use error::IntegerError;
+use rand_core::RngCore;
 use traits::ZkperIntegerTrait;
-use zkper_rand::ZkperRng;
+
+#[cfg(not(any(feature = "backend-rug", feature = "backend-native")))]
+compile_error!(
+    "zkper-integer needs at least one of the `backend-rug` or `backend-native` features"
+);
 
 pub mod backends;
+pub mod error;
+pub mod hash;
 pub mod implements;
+pub mod limb_mul;
+pub mod pow;
 pub mod traits;
 
 // different integer backends
 // some place to save integers from different backends
 // later: will implement transfer between different backends.
 //        e.g. from cpu to gpu
-#[derive(Debug, Clone, Hash)]
+#[derive(Debug, Clone)]
 pub struct ZkperInteger<T: ZkperIntegerTrait>(T);
 
 impl<T: ZkperIntegerTrait> ZkperInteger<T> {
@@ -17,10 +32,15 @@ impl<T: ZkperIntegerTrait> ZkperInteger<T> {
         Self(T::from_i32(i))
     }
 
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(s: &str) -> Self {
         Self(T::from_str(s))
     }
 
+    pub fn try_from_str(s: &str) -> Result<Self, IntegerError> {
+        T::try_from_str(s).map(Self)
+    }
+
     pub fn new(integer: T) -> Self {
         Self(integer)
     }
@@ -105,6 +125,14 @@ impl<T: ZkperIntegerTrait> ZkperInteger<T> {
         Self(self.0.pow_mod(&exp.0, &modulus.0))
     }
 
+    pub fn reduce(&self, modulus: &Self) -> Self {
+        Self(self.0.reduce(&modulus.0))
+    }
+
+    pub fn neg_mod(&self, modulus: &Self) -> Self {
+        Self(self.0.neg_mod(&modulus.0))
+    }
+
     pub fn is_divisible(&self, other: &Self) -> bool {
         self.0.is_divisible(&other.0)
     }
@@ -113,14 +141,14 @@ impl<T: ZkperIntegerTrait> ZkperInteger<T> {
         self.0.is_prime()
     }
 
-    pub fn to_string(&self) -> String {
-        self.0.to_string()
-    }
-
     pub fn to_hex_string(&self) -> String {
         self.0.to_hex_string()
     }
 
+    pub fn stable_hash(&self) -> u64 {
+        self.0.stable_hash()
+    }
+
     pub fn abs(&self) -> Self {
         Self(self.0.abs())
     }
@@ -133,7 +161,7 @@ impl<T: ZkperIntegerTrait> ZkperInteger<T> {
         Self(self.0.gcd(&other.0))
     }
 
-    pub fn random_below(&self, rng: &mut ZkperRng) -> Self {
+    pub fn random_below<R: RngCore + ?Sized>(&self, rng: &mut R) -> Self {
         Self(self.0.random_below(rng))
     }
 
@@ -157,6 +185,30 @@ impl<T: ZkperIntegerTrait> ZkperInteger<T> {
         Self(self.0.shl(n))
     }
 
+    pub fn bit_length(&self) -> u32 {
+        self.0.bit_length()
+    }
+
+    pub fn test_bit(&self, i: u32) -> bool {
+        self.0.test_bit(i)
+    }
+
+    pub fn set_bit(&self, i: u32, value: bool) -> Self {
+        Self(self.0.set_bit(i, value))
+    }
+
+    pub fn count_ones(&self) -> u32 {
+        self.0.count_ones()
+    }
+
+    pub fn to_bits_le(&self) -> Vec<bool> {
+        self.0.to_bits_le()
+    }
+
+    pub fn to_bits_be(&self) -> Vec<bool> {
+        self.0.to_bits_be()
+    }
+
     pub fn to_bytes(&self) -> Vec<u8> {
         self.0.to_bytes()
     }
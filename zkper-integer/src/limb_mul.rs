@@ -0,0 +1,499 @@
+//! Multiplication of little-endian `u64` limb slices, for fixed-limb
+//! integers.
+//!
+//! Schoolbook multiplication costs n^2 limb products. Karatsuba splits both
+//! operands in halves and gets by with three half-size products, Toom-3
+//! splits them in thirds and needs five third-size products, so each wins
+//! above a size where its extra additions pay off. [`mul`] and [`square`]
+//! pick the algorithm by size with the thresholds below, measured with the
+//! `limb_mul` benchmark. Squaring computes every cross product once instead
+//! of twice, which moves its thresholds up.
+//!
+//! The general Karatsuba and Toom-3 allocate their intermediate values, so
+//! they only pay off at hundreds of limbs. Six limbs, a BLS12-381 base field
+//! element, get their own Karatsuba in [`mul_6`], which works on the stack
+//! and beats the schoolbook product by a few nanoseconds.
+
+use alloc::{vec, vec::Vec};
+use core::cmp::Ordering;
+
+/// Balanced products of at least this many limbs use Karatsuba.
+pub const KARATSUBA_THRESHOLD: usize = 96;
+
+/// Balanced products of at least this many limbs use Toom-3.
+pub const TOOM3_THRESHOLD: usize = 512;
+
+/// Squares of at least this many limbs use Karatsuba.
+pub const KARATSUBA_SQUARE_THRESHOLD: usize = 128;
+
+/// Squares of at least this many limbs use Toom-3.
+pub const TOOM3_SQUARE_THRESHOLD: usize = 512;
+
+/// out = a * b, choosing the algorithm by size; `out` holds
+/// a.len() + b.len() limbs.
+pub fn mul(a: &[u64], b: &[u64], out: &mut [u64]) {
+    assert_eq!(out.len(), a.len() + b.len());
+    let (long, short) = if a.len() >= b.len() { (a, b) } else { (b, a) };
+
+    if let (Ok(a), Ok(b)) = (a.try_into(), b.try_into()) {
+        out.copy_from_slice(&mul_6(a, b))
+    } else if short.len() < KARATSUBA_THRESHOLD {
+        mul_schoolbook(a, b, out)
+    } else if long.len() != short.len() {
+        mul_unbalanced(long, short, out)
+    } else if short.len() < TOOM3_THRESHOLD {
+        mul_karatsuba(a, b, out)
+    } else {
+        mul_toom3(a, b, out)
+    }
+}
+
+/// out = a^2, choosing the algorithm by size; `out` holds 2 * a.len() limbs.
+pub fn square(a: &[u64], out: &mut [u64]) {
+    if a.len() < KARATSUBA_SQUARE_THRESHOLD {
+        square_schoolbook(a, out)
+    } else if a.len() < TOOM3_SQUARE_THRESHOLD {
+        square_karatsuba(a, out)
+    } else {
+        square_toom3(a, out)
+    }
+}
+
+/// out = a * b by the schoolbook method.
+pub fn mul_schoolbook(a: &[u64], b: &[u64], out: &mut [u64]) {
+    assert_eq!(out.len(), a.len() + b.len());
+    out.fill(0);
+    for (i, &ai) in a.iter().enumerate() {
+        let mut carry = 0;
+        for (j, &bj) in b.iter().enumerate() {
+            let t = ai as u128 * bj as u128 + out[i + j] as u128 + carry as u128;
+            out[i + j] = t as u64;
+            carry = (t >> 64) as u64;
+        }
+        out[i + b.len()] = carry;
+    }
+}
+
+/// out = a^2 by the schoolbook method: the cross products once, doubled,
+/// then the squares of the limbs on the diagonal.
+pub fn square_schoolbook(a: &[u64], out: &mut [u64]) {
+    let n = a.len();
+    assert_eq!(out.len(), 2 * n);
+    out.fill(0);
+    for (i, &ai) in a.iter().enumerate() {
+        let mut carry = 0;
+        for (j, &aj) in a.iter().enumerate().skip(i + 1) {
+            let t = ai as u128 * aj as u128 + out[i + j] as u128 + carry as u128;
+            out[i + j] = t as u64;
+            carry = (t >> 64) as u64;
+        }
+        out[i + n] = carry;
+    }
+
+    let mut top = 0;
+    for limb in out.iter_mut() {
+        (*limb, top) = (*limb << 1 | top, *limb >> 63);
+    }
+
+    let mut carry = 0;
+    for (i, &ai) in a.iter().enumerate() {
+        let sq = ai as u128 * ai as u128;
+        let lo = out[2 * i] as u128 + (sq as u64) as u128 + carry;
+        out[2 * i] = lo as u64;
+        let hi = out[2 * i + 1] as u128 + (sq >> 64) + (lo >> 64);
+        out[2 * i + 1] = hi as u64;
+        carry = hi >> 64;
+    }
+}
+
+/// out = a * b by Karatsuba, for operands of the same length of at least 2.
+pub fn mul_karatsuba(a: &[u64], b: &[u64], out: &mut [u64]) {
+    karatsuba(a, b, out, false)
+}
+
+/// out = a^2 by Karatsuba, for at least 2 limbs.
+pub fn square_karatsuba(a: &[u64], out: &mut [u64]) {
+    karatsuba(a, a, out, true)
+}
+
+/// out = a * b by Toom-3, for operands of the same length of at least 3.
+pub fn mul_toom3(a: &[u64], b: &[u64], out: &mut [u64]) {
+    toom3(a, b, out, false)
+}
+
+/// out = a^2 by Toom-3, for at least 3 limbs.
+pub fn square_toom3(a: &[u64], out: &mut [u64]) {
+    toom3(a, a, out, true)
+}
+
+/// a * b for six limbs: one Karatsuba level over 3x3 schoolbook products,
+/// 27 limb products instead of 36 and no allocation.
+pub fn mul_6(a: &[u64; 6], b: &[u64; 6]) -> [u64; 12] {
+    let (a0, a1) = a.split_at(3);
+    let (b0, b1) = b.split_at(3);
+
+    let mut out = [0; 12];
+    mul_schoolbook(a0, b0, &mut out[..6]);
+    mul_schoolbook(a1, b1, &mut out[6..]);
+
+    // (a0 + a1)(b0 + b1), with the carries out of the sums added separately
+    let (mut sa, mut sb) = ([0; 3], [0; 3]);
+    let ca = add_same(a0, a1, &mut sa);
+    let cb = add_same(b0, b1, &mut sb);
+    let mut mid = [0; 7];
+    mul_schoolbook(&sa, &sb, &mut mid[..6]);
+    if ca {
+        add_at(&mut mid, &sb, 3);
+    }
+    if cb {
+        add_at(&mut mid, &sa, 3);
+    }
+    if ca && cb {
+        add_at(&mut mid, &[1], 6);
+    }
+
+    sub_at(&mut mid, &out[..6], 0);
+    sub_at(&mut mid, &out[6..], 0);
+    add_at(&mut out, &mid, 3);
+    out
+}
+
+/// The long operand in chunks of the short one's length.
+fn mul_unbalanced(long: &[u64], short: &[u64], out: &mut [u64]) {
+    out.fill(0);
+    for (k, chunk) in long.chunks(short.len()).enumerate() {
+        add_at(out, &product(chunk, short, false), k * short.len());
+    }
+}
+
+/// a0 b0 + ((a0 + a1)(b0 + b1) - a0 b0 - a1 b1) x + a1 b1 x^2, with x the
+/// limb size of the low halves.
+fn karatsuba(a: &[u64], b: &[u64], out: &mut [u64], square: bool) {
+    let n = a.len();
+    assert!(n >= 2 && b.len() == n && out.len() == 2 * n);
+    let half = n / 2;
+    let (a0, a1) = a.split_at(half);
+    let (b0, b1) = b.split_at(half);
+
+    let z0 = product(a0, b0, square);
+    let z2 = product(a1, b1, square);
+    let sa = add(a0, a1);
+    let mut z1 = if square {
+        product(&sa, &sa, true)
+    } else {
+        product(&sa, &add(b0, b1), false)
+    };
+    sub_at(&mut z1, &z0, 0);
+    sub_at(&mut z1, &z2, 0);
+
+    out[..2 * half].copy_from_slice(&z0);
+    out[2 * half..].copy_from_slice(&z2);
+    add_at(out, &z1, half);
+}
+
+/// Evaluates both operands, split in thirds, at 0, 1, -1, 2 and infinity,
+/// multiplies pointwise and interpolates the five coefficients back.
+fn toom3(a: &[u64], b: &[u64], out: &mut [u64], square: bool) {
+    let n = a.len();
+    assert!(n >= 3 && b.len() == n && out.len() == 2 * n);
+    let k = n.div_ceil(3);
+
+    let pa = evaluate(a, k);
+    let pb = if square { pa.clone() } else { evaluate(b, k) };
+    let [r0, r1, rm1, r2, rinf] = [0, 1, 2, 3, 4].map(|i| pa[i].mul(&pb[i], square));
+
+    // r(1) - r(-1) = 2 (c1 + c3), r(-1) = c0 - c1 + c2 - c3 + c4 and
+    // r(2) = c0 + 2 c1 + 4 c2 + 8 c3 + 16 c4
+    let s1 = r1.sub(&rm1).div_exact(2);
+    let c2 = rm1.add(&s1).sub(&r0).sub(&rinf);
+    let c3 = r2
+        .sub(&r0)
+        .sub(&c2.scale(4))
+        .sub(&rinf.scale(16))
+        .sub(&s1.scale(2))
+        .div_exact(6);
+    let c1 = s1.sub(&c3);
+
+    out.fill(0);
+    for (i, c) in [r0, c1, c2, c3, rinf].iter().enumerate() {
+        debug_assert!(!c.negative);
+        add_at(out, &c.magnitude, i * k);
+    }
+}
+
+/// The value at 0, 1, -1, 2 and infinity of x0 + x1 t + x2 t^2, with x split
+/// into limbs [0, k), [k, 2k) and the rest.
+fn evaluate(x: &[u64], k: usize) -> [Signed; 5] {
+    let (x0, rest) = x.split_at(k);
+    let (x1, x2) = rest.split_at(k);
+    let (x0, x1, x2) = (Signed::from(x0), Signed::from(x1), Signed::from(x2));
+
+    let even = x0.add(&x2);
+    let p1 = even.add(&x1);
+    let pm1 = even.sub(&x1);
+    let p2 = p1.add(&x2).scale(2).sub(&x0);
+    [x0, p1, pm1, p2, x2]
+}
+
+/// a * b, or a^2 when `square` is set, in a new vector.
+fn product(a: &[u64], b: &[u64], square: bool) -> Vec<u64> {
+    let mut out = vec![0; a.len() + b.len()];
+    if square {
+        self::square(a, &mut out);
+    } else {
+        mul(a, b, &mut out);
+    }
+    out
+}
+
+/// out = a + b for operands of the same length, returning the carry.
+fn add_same(a: &[u64], b: &[u64], out: &mut [u64]) -> bool {
+    let mut carry = false;
+    for ((o, &x), &y) in out.iter_mut().zip(a).zip(b) {
+        let (s, c1) = x.overflowing_add(y);
+        let (s, c2) = s.overflowing_add(carry as u64);
+        *o = s;
+        carry = c1 || c2;
+    }
+    carry
+}
+
+/// a + b, one limb longer than the longer operand.
+fn add(a: &[u64], b: &[u64]) -> Vec<u64> {
+    let (long, short) = if a.len() >= b.len() { (a, b) } else { (b, a) };
+    let mut out = long.to_vec();
+    out.push(0);
+    add_at(&mut out, short, 0);
+    out
+}
+
+/// a - b for a >= b.
+fn sub(a: &[u64], b: &[u64]) -> Vec<u64> {
+    let mut out = a.to_vec();
+    sub_at(&mut out, b, 0);
+    out
+}
+
+/// out[offset..] += x; panics if the sum does not fit.
+fn add_at(out: &mut [u64], x: &[u64], offset: usize) {
+    let x = trim(x);
+    let mut carry = false;
+    let mut i = 0;
+    while i < x.len() || carry {
+        let (s, c1) = out[offset + i].overflowing_add(x.get(i).copied().unwrap_or(0));
+        let (s, c2) = s.overflowing_add(carry as u64);
+        out[offset + i] = s;
+        carry = c1 || c2;
+        i += 1;
+    }
+}
+
+/// out[offset..] -= x; panics if the difference is negative.
+fn sub_at(out: &mut [u64], x: &[u64], offset: usize) {
+    let x = trim(x);
+    let mut borrow = false;
+    let mut i = 0;
+    while i < x.len() || borrow {
+        let (d, b1) = out[offset + i].overflowing_sub(x.get(i).copied().unwrap_or(0));
+        let (d, b2) = d.overflowing_sub(borrow as u64);
+        out[offset + i] = d;
+        borrow = b1 || b2;
+        i += 1;
+    }
+}
+
+/// x without its leading zero limbs.
+fn trim(x: &[u64]) -> &[u64] {
+    &x[..x.iter().rposition(|&limb| limb != 0).map_or(0, |i| i + 1)]
+}
+
+fn cmp(a: &[u64], b: &[u64]) -> Ordering {
+    let (a, b) = (trim(a), trim(b));
+    a.len()
+        .cmp(&b.len())
+        .then_with(|| a.iter().rev().cmp(b.iter().rev()))
+}
+
+/// A sign and a magnitude, for the negative values Toom-3 evaluates to.
+#[derive(Clone, Debug)]
+struct Signed {
+    negative: bool,
+    magnitude: Vec<u64>,
+}
+
+impl Signed {
+    /// Zero is never negative.
+    fn new(negative: bool, mut magnitude: Vec<u64>) -> Self {
+        magnitude.truncate(trim(&magnitude).len());
+        Self {
+            negative: negative && !magnitude.is_empty(),
+            magnitude,
+        }
+    }
+
+    fn from(x: &[u64]) -> Self {
+        Self::new(false, x.to_vec())
+    }
+
+    fn add(&self, other: &Self) -> Self {
+        if self.negative == other.negative {
+            return Self::new(self.negative, add(&self.magnitude, &other.magnitude));
+        }
+        match cmp(&self.magnitude, &other.magnitude) {
+            Ordering::Less => Self::new(other.negative, sub(&other.magnitude, &self.magnitude)),
+            _ => Self::new(self.negative, sub(&self.magnitude, &other.magnitude)),
+        }
+    }
+
+    fn sub(&self, other: &Self) -> Self {
+        self.add(&Self::new(!other.negative, other.magnitude.clone()))
+    }
+
+    fn mul(&self, other: &Self, square: bool) -> Self {
+        Self::new(
+            self.negative != other.negative,
+            product(&self.magnitude, &other.magnitude, square),
+        )
+    }
+
+    fn scale(&self, factor: u64) -> Self {
+        Self::new(self.negative, product(&self.magnitude, &[factor], false))
+    }
+
+    /// Division by a `divisor` known to divide the value.
+    fn div_exact(&self, divisor: u64) -> Self {
+        let mut quotient = self.magnitude.clone();
+        let mut rem = 0u128;
+        for limb in quotient.iter_mut().rev() {
+            let current = rem << 64 | *limb as u128;
+            *limb = (current / divisor as u128) as u64;
+            rem = current % divisor as u128;
+        }
+        debug_assert_eq!(rem, 0);
+        Self::new(self.negative, quotient)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand_core::RngCore;
+    use zkper_rand::TestRng;
+
+    use super::*;
+
+    type Mul = fn(&[u64], &[u64], &mut [u64]);
+    type Square = fn(&[u64], &mut [u64]);
+
+    /// Every path with the smallest length it takes.
+    const MULS: [(&str, Mul, usize); 4] = [
+        ("schoolbook", mul_schoolbook, 0),
+        ("karatsuba", mul_karatsuba, 2),
+        ("toom3", mul_toom3, 3),
+        ("mul", mul, 0),
+    ];
+    const SQUARES: [(&str, Square, usize); 4] = [
+        ("schoolbook", square_schoolbook, 0),
+        ("karatsuba", square_karatsuba, 2),
+        ("toom3", square_toom3, 3),
+        ("square", square, 0),
+    ];
+
+    /// Random limbs, all ones and a single high bit, the last two to stress
+    /// the carries.
+    fn inputs(rng: &mut TestRng, n: usize) -> [Vec<u64>; 3] {
+        let mut high = vec![0; n];
+        if let Some(last) = high.last_mut() {
+            *last = 1 << 63;
+        }
+        [
+            (0..n).map(|_| rng.next_u64()).collect(),
+            vec![u64::MAX; n],
+            high,
+        ]
+    }
+
+    fn sizes() -> impl Iterator<Item = usize> {
+        (1..=40).chain([47, 64, 95, 96, 97, 130, 200, 520])
+    }
+
+    #[test]
+    fn test_paths_agree() {
+        let mut rng = TestRng::new();
+        for n in sizes() {
+            for (a, b) in inputs(&mut rng, n)
+                .iter()
+                .zip(inputs(&mut rng, n).iter().rev())
+            {
+                let mut expected = vec![0; 2 * n];
+                mul_schoolbook(a, b, &mut expected);
+                let mut out = vec![0; 2 * n];
+                for (name, f, _) in MULS.into_iter().filter(|(.., min)| n >= *min) {
+                    f(a, b, &mut out);
+                    assert_eq!(out, expected, "{name} on {n} limbs");
+                }
+
+                mul_schoolbook(a, a, &mut expected);
+                for (name, f, _) in SQUARES.into_iter().filter(|(.., min)| n >= *min) {
+                    f(a, &mut out);
+                    assert_eq!(out, expected, "{name} square on {n} limbs");
+                }
+            }
+        }
+    }
+
+    #[cfg(feature = "backend-rug")]
+    mod against_rug {
+        use rug::{integer::Order, Integer};
+
+        use super::*;
+
+        fn int(limbs: &[u64]) -> Integer {
+            Integer::from_digits(limbs, Order::Lsf)
+        }
+
+        #[test]
+        fn test_mul_and_square() {
+            let mut rng = TestRng::new();
+            for n in sizes() {
+                for (a, b) in inputs(&mut rng, n).iter().zip(inputs(&mut rng, n).iter()) {
+                    let mut out = vec![0; 2 * n];
+                    for (name, f, _) in MULS.into_iter().filter(|(.., min)| n >= *min) {
+                        f(a, b, &mut out);
+                        assert_eq!(int(&out), int(a) * int(b), "{name} on {n} limbs");
+                    }
+                    for (name, f, _) in SQUARES.into_iter().filter(|(.., min)| n >= *min) {
+                        f(a, &mut out);
+                        assert_eq!(int(&out), int(a).square(), "{name} square on {n} limbs");
+                    }
+                }
+            }
+        }
+
+        #[test]
+        fn test_unbalanced() {
+            let mut rng = TestRng::new();
+            for (m, n) in [(1, 30), (24, 25), (30, 100), (97, 200), (6, 128)] {
+                let a: Vec<u64> = (0..m).map(|_| rng.next_u64()).collect();
+                let b: Vec<u64> = (0..n).map(|_| rng.next_u64()).collect();
+                let mut out = vec![0; m + n];
+                mul(&a, &b, &mut out);
+                assert_eq!(int(&out), int(&a) * int(&b), "{m} x {n}");
+                mul(&b, &a, &mut out);
+                assert_eq!(int(&out), int(&a) * int(&b), "{n} x {m}");
+            }
+        }
+
+        #[test]
+        fn test_mul_6() {
+            let mut rng = TestRng::new();
+            for _ in 0..1000 {
+                let a: [u64; 6] = core::array::from_fn(|_| rng.next_u64());
+                let b: [u64; 6] = core::array::from_fn(|_| rng.next_u64());
+                assert_eq!(int(&mul_6(&a, &b)), int(&a) * int(&b));
+            }
+            let max = [u64::MAX; 6];
+            assert_eq!(int(&mul_6(&max, &max)), int(&max).square());
+            assert_eq!(mul_6(&max, &[0; 6]), [0; 12]);
+        }
+    }
+}
@@ -1,6 +1,9 @@
-use std::{cmp::Ordering, fmt::Debug, hash::Hash};
+use alloc::{string::String, vec::Vec};
+use core::{cmp::Ordering, fmt::Debug, hash::Hash};
 
-use zkper_rand::ZkperRng;
+use rand_core::RngCore;
+
+use crate::error::IntegerError;
 
 /// define behavior of zkper integer
 pub trait ZkperIntegerTrait: Clone + Sized + Hash + Default + Debug {
@@ -10,6 +13,8 @@ pub trait ZkperIntegerTrait: Clone + Sized + Hash + Default + Debug {
     fn from_u64(u: u64) -> Self;
     fn from_hex_str(hex_str: &str) -> Self;
     fn from_str(s: &str) -> Self;
+    /// `from_str` returning an error instead of panicking.
+    fn try_from_str(s: &str) -> Result<Self, IntegerError>;
 
     // compare
     // Comparison method
@@ -46,6 +51,25 @@ pub trait ZkperIntegerTrait: Clone + Sized + Hash + Default + Debug {
     fn shl_32(&self, n: u32) -> Self;
     fn shl(&self, n: u64) -> Self;
 
+    // single bits, all of the absolute value
+    /// The number of bits up to the highest set one, 0 for zero.
+    fn bit_length(&self) -> u32;
+    fn test_bit(&self, i: u32) -> bool;
+    /// A copy with bit `i` set to `value`, keeping the sign.
+    fn set_bit(&self, i: u32, value: bool) -> Self;
+    fn count_ones(&self) -> u32;
+    /// The `bit_length` bits, least significant first.
+    fn to_bits_le(&self) -> Vec<bool> {
+        (0..self.bit_length()).map(|i| self.test_bit(i)).collect()
+    }
+    /// The `bit_length` bits, most significant first.
+    fn to_bits_be(&self) -> Vec<bool> {
+        (0..self.bit_length())
+            .rev()
+            .map(|i| self.test_bit(i))
+            .collect()
+    }
+
     // basic operations
     fn sub(&self, rhs: &Self) -> Self;
     fn add(&self, rhs: &Self) -> Self;
@@ -53,6 +77,8 @@ pub trait ZkperIntegerTrait: Clone + Sized + Hash + Default + Debug {
     fn add_u64(&self, rhs: u64) -> Self {
         self.add(&Self::from_u64(rhs))
     }
+    /// Signed negation. The word backends are unsigned and panic, or wrap
+    /// for `U32Backed`, on anything but zero; residues go through `neg_mod`.
     fn neg(&self) -> Self;
     fn div(&self, rhs: &Self) -> Self;
     fn mul(&self, rhs: &Self) -> Self;
@@ -63,7 +89,28 @@ pub trait ZkperIntegerTrait: Clone + Sized + Hash + Default + Debug {
     fn rem(&self, rhs: &Self) -> Self;
 
     // extra for modular arithmetic
+    /// self^exp mod a positive `modulus`, in [0, modulus). A negative `self`
+    /// stands for its residue.
     fn pow_mod(&self, exp: &Self, modulus: &Self) -> Self;
+    /// The residue of `self` in [0, modulus) for a positive `modulus`, also
+    /// for negative values, where `rem` keeps the sign of `self`.
+    fn reduce(&self, modulus: &Self) -> Self {
+        let r = self.rem(modulus);
+        if r.compare(&Self::zero()) == Ordering::Less {
+            r.add(modulus)
+        } else {
+            r
+        }
+    }
+    /// The residue of -self in [0, modulus), on every backend.
+    fn neg_mod(&self, modulus: &Self) -> Self {
+        let r = self.reduce(modulus);
+        if r.is_zero() {
+            r
+        } else {
+            modulus.sub(&r)
+        }
+    }
     fn gcd(&self, other: &Self) -> Self;
     fn is_divisible(&self, other: &Self) -> bool {
         self.div(other).is_zero()
@@ -75,13 +122,23 @@ pub trait ZkperIntegerTrait: Clone + Sized + Hash + Default + Debug {
     fn invert(&self, modulus: &Self) -> anyhow::Result<Self>;
 
     // rand
-    fn random_below(&self, rng: &mut ZkperRng) -> Self;
+    fn random_below<R: RngCore + ?Sized>(&self, rng: &mut R) -> Self;
 
     // for signed integers
     fn abs(&self) -> Self {
         self.clone()
     }
 
+    /// A hash of the value that is the same on every platform and backend,
+    /// see [`crate::hash`].
+    fn stable_hash(&self) -> u64 {
+        let negative = self.compare(&Self::zero()) == Ordering::Less;
+        // to_bytes is little-endian
+        let mut magnitude = self.abs().to_bytes();
+        magnitude.reverse();
+        crate::hash::stable_hash(negative, &magnitude)
+    }
+
     fn is_even(&self) -> bool {
         self.is_zero() || self.shr_32(0).is_zero()
     }
@@ -0,0 +1,46 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use zkper_integer::backends::rug_backend::RugBackend;
+use zkper_modular::{
+    barrett::BarrettContext,
+    primes::{Bls12_381Fr, Bn254Fq},
+    traits::{DeclaredPrime, ZkperPrimeTrait},
+};
+use zkper_rand::TestRng;
+
+fn bench_prime<P: ZkperPrimeTrait<RugBackend> + DeclaredPrime>(c: &mut Criterion, name: &str) {
+    let mut rng = TestRng::new();
+    let a = P::random(&mut rng);
+    let b = P::random(&mut rng);
+
+    let barrett = BarrettContext::new(P::value());
+    let montgomery = P::montgomery::<RugBackend>();
+    let (am, bm) = (montgomery.to_montgomery(&a), montgomery.to_montgomery(&b));
+
+    c.bench_function(&format!("{name}_mul/division"), |bench| {
+        bench.iter(|| P::multiply(black_box(&a), black_box(&b)))
+    });
+    c.bench_function(&format!("{name}_mul/barrett"), |bench| {
+        bench.iter(|| barrett.mul(black_box(&a), black_box(&b)))
+    });
+    c.bench_function(&format!("{name}_mul/montgomery"), |bench| {
+        bench.iter(|| montgomery.mul(black_box(&am), black_box(&bm)))
+    });
+    // one multiplication with the conversions Montgomery form needs around it
+    c.bench_function(&format!("{name}_mul/montgomery_with_conversion"), |bench| {
+        bench.iter(|| {
+            let product = montgomery.mul(
+                &montgomery.to_montgomery(black_box(&a)),
+                &montgomery.to_montgomery(black_box(&b)),
+            );
+            montgomery.from_montgomery(&product)
+        })
+    });
+}
+
+fn bench_reduction(c: &mut Criterion) {
+    bench_prime::<Bls12_381Fr>(c, "bls12_381_fr");
+    bench_prime::<Bn254Fq>(c, "bn254_fq");
+}
+
+criterion_group!(benches, bench_reduction);
+criterion_main!(benches);
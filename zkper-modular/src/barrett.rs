@@ -0,0 +1,143 @@
+use std::{any::Any, any::TypeId, cell::RefCell, collections::HashMap, rc::Rc};
+
+use zkper_integer::{traits::ZkperIntegerTrait, ZkperInteger};
+
+use crate::traits::ZkperPrimeTrait;
+
+/// Precomputed constants for Barrett reduction modulo p.
+///
+/// Unlike Montgomery multiplication, it works on elements in their usual
+/// form, so there is nothing to convert in or out.
+#[derive(Debug, Clone)]
+pub struct BarrettContext<T: ZkperIntegerTrait> {
+    modulus: ZkperInteger<T>,
+    /// bit length of p
+    k: u32,
+    /// floor(4^k / p)
+    mu: ZkperInteger<T>,
+    /// 4^k, the bound below which `reduce` takes the Barrett path
+    bound: ZkperInteger<T>,
+}
+
+impl<T: ZkperIntegerTrait> BarrettContext<T> {
+    pub fn new(modulus: ZkperInteger<T>) -> Self {
+        let k = modulus.bit_length();
+        let bound = ZkperInteger::one() << (2 * k);
+        let mu = bound.clone() / &modulus;
+        Self {
+            modulus,
+            k,
+            mu,
+            bound,
+        }
+    }
+
+    pub fn modulus(&self) -> &ZkperInteger<T> {
+        &self.modulus
+    }
+
+    /// Reduce into the canonical range [0, p). Values in [0, 4^k), products
+    /// of two reduced elements included, need two multiplications and at
+    /// most two subtractions; anything else falls back to division.
+    pub fn reduce(&self, a: &ZkperInteger<T>) -> ZkperInteger<T> {
+        if *a < ZkperInteger::zero() || *a >= self.bound {
+            let r = a % &self.modulus;
+            return if r < ZkperInteger::zero() {
+                r + &self.modulus
+            } else {
+                r
+            };
+        }
+
+        // q underestimates floor(a / p) by at most 2
+        let q = ((a.clone() >> (self.k - 1)) * &self.mu) >> (self.k + 1);
+        let mut r = a.clone() - q * &self.modulus;
+        while r >= self.modulus {
+            r -= &self.modulus;
+        }
+        r
+    }
+
+    /// a * b mod p, for a and b in [0, p).
+    pub fn mul(&self, a: &ZkperInteger<T>, b: &ZkperInteger<T>) -> ZkperInteger<T> {
+        self.reduce(&(a.clone() * b))
+    }
+}
+
+/// The Barrett context of `P` on backend `T`, built on first use and kept
+/// for the life of the thread.
+pub fn cached<T, P>() -> Rc<BarrettContext<T>>
+where
+    T: ZkperIntegerTrait + 'static,
+    P: ZkperPrimeTrait<T> + 'static,
+{
+    thread_local! {
+        static CONTEXTS: RefCell<HashMap<TypeId, Rc<dyn Any>>> = RefCell::new(HashMap::new());
+    }
+
+    CONTEXTS.with(|contexts| {
+        let context = contexts
+            .borrow_mut()
+            .entry(TypeId::of::<(T, P)>())
+            .or_insert_with(|| Rc::new(BarrettContext::new(P::value())))
+            .clone();
+        context
+            .downcast()
+            .expect("contexts are keyed by their backend")
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use zkper_integer::{backends::rug_backend::RugBackend, ZkperInteger};
+    use zkper_rand::TestRng;
+
+    use super::BarrettContext;
+    use crate::{declare_prime, primes::Bls12_381Fr, traits::ZkperPrimeTrait, ZkperModularInteger};
+
+    declare_prime!(
+        Bls12_381FrBarrett = "0x73eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000001",
+        two_adicity = 32,
+        generator = 7,
+        reduction = barrett,
+    );
+
+    type Fr = ZkperModularInteger<RugBackend, Bls12_381Fr>;
+    type FrBarrett = ZkperModularInteger<RugBackend, Bls12_381FrBarrett>;
+
+    #[test]
+    fn test_barrett_reduce() {
+        let p = <Bls12_381Fr as ZkperPrimeTrait<RugBackend>>::value();
+        let barrett = BarrettContext::new(p.clone());
+        let mut rng = TestRng::new();
+        for _ in 0..100 {
+            let a = p.random_below(&mut rng);
+            let b = p.random_below(&mut rng);
+            assert_eq!(barrett.mul(&a, &b), (a.clone() * &b) % &p);
+        }
+
+        // the division fallback
+        let huge = p.clone() * &p * &p + 5u64;
+        assert_eq!(barrett.reduce(&huge), 5u64.into());
+        assert_eq!(barrett.reduce(&ZkperInteger::from(-1)), p.clone() - 1u64);
+        assert_eq!(barrett.reduce(&p), ZkperInteger::zero());
+    }
+
+    #[test]
+    fn test_barrett_backed_prime() {
+        let mut rng = TestRng::new();
+        for _ in 0..20 {
+            let a = Fr::random(&mut rng);
+            let b = Fr::random(&mut rng);
+            let (a2, b2) = (
+                FrBarrett::from(a.value.clone()),
+                FrBarrett::from(b.value.clone()),
+            );
+
+            assert_eq!((a.clone() * &b).value, (a2.clone() * &b2).value);
+            assert_eq!((a.clone() - &b).value, (a2.clone() - &b2).value);
+            assert_eq!(a.inverse().unwrap().value, a2.inverse().unwrap().value);
+            assert_eq!(a.square().sqrt().is_some(), a2.square().sqrt().is_some());
+        }
+    }
+}
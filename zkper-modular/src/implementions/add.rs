@@ -1,5 +1,8 @@
 use super::*;
-use std::ops::{Add, AddAssign};
+use std::{
+    iter::Sum,
+    ops::{Add, AddAssign},
+};
 
 impl<T: ZkperIntegerTrait, P: ZkperPrimeTrait<T>> Add for ZkperModularInteger<T, P> {
     type Output = Self;
@@ -32,3 +35,37 @@ impl<T: ZkperIntegerTrait, P: ZkperPrimeTrait<T>> AddAssign<&ZkperModularInteger
         self.value = P::additive(&self.value, &rhs.value);
     }
 }
+
+impl<T: ZkperIntegerTrait, P: ZkperPrimeTrait<T>> Add<&ZkperModularInteger<T, P>>
+    for &ZkperModularInteger<T, P>
+{
+    type Output = ZkperModularInteger<T, P>;
+
+    fn add(self, rhs: &ZkperModularInteger<T, P>) -> Self::Output {
+        P::additive(&self.value, &rhs.value).into()
+    }
+}
+
+impl<T: ZkperIntegerTrait, P: ZkperPrimeTrait<T>> Add<ZkperModularInteger<T, P>>
+    for &ZkperModularInteger<T, P>
+{
+    type Output = ZkperModularInteger<T, P>;
+
+    fn add(self, rhs: ZkperModularInteger<T, P>) -> Self::Output {
+        P::additive(&self.value, &rhs.value).into()
+    }
+}
+
+impl<T: ZkperIntegerTrait, P: ZkperPrimeTrait<T>> Sum for ZkperModularInteger<T, P> {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::zero(), |acc, x| acc + x)
+    }
+}
+
+impl<'a, T: ZkperIntegerTrait, P: ZkperPrimeTrait<T>> Sum<&'a ZkperModularInteger<T, P>>
+    for ZkperModularInteger<T, P>
+{
+    fn sum<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+        iter.fold(Self::zero(), |acc, x| acc + x)
+    }
+}
@@ -0,0 +1,9 @@
+use super::*;
+
+impl<T: ZkperIntegerTrait, P: ZkperPrimeTrait<T>> PartialEq for ZkperModularInteger<T, P> {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+impl<T: ZkperIntegerTrait, P: ZkperPrimeTrait<T>> Eq for ZkperModularInteger<T, P> {}
@@ -0,0 +1,9 @@
+use super::*;
+use std::fmt;
+
+// Implement Display for ZkperModularInteger<T, P>
+impl<T: ZkperIntegerTrait, P: ZkperPrimeTrait<T>> fmt::Display for ZkperModularInteger<T, P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.value)
+    }
+}
@@ -0,0 +1,57 @@
+use super::*;
+use std::ops::{Div, DivAssign};
+
+// Division is multiplication by the inverse and panics on a zero divisor,
+// use `inverse` directly to handle that case.
+impl<T: ZkperIntegerTrait, P: ZkperPrimeTrait<T>> Div for ZkperModularInteger<T, P> {
+    type Output = Self;
+
+    fn div(self, rhs: Self) -> Self::Output {
+        self / &rhs
+    }
+}
+
+impl<T: ZkperIntegerTrait, P: ZkperPrimeTrait<T>> Div<&ZkperModularInteger<T, P>>
+    for ZkperModularInteger<T, P>
+{
+    type Output = Self;
+
+    fn div(self, rhs: &Self) -> Self::Output {
+        let inv = P::inverse(&rhs.value).expect("division by zero");
+        P::multiply(&self.value, &inv).into()
+    }
+}
+
+impl<T: ZkperIntegerTrait, P: ZkperPrimeTrait<T>> DivAssign for ZkperModularInteger<T, P> {
+    fn div_assign(&mut self, rhs: Self) {
+        *self = self.clone() / &rhs;
+    }
+}
+
+impl<T: ZkperIntegerTrait, P: ZkperPrimeTrait<T>> DivAssign<&ZkperModularInteger<T, P>>
+    for ZkperModularInteger<T, P>
+{
+    fn div_assign(&mut self, rhs: &Self) {
+        *self = self.clone() / rhs;
+    }
+}
+
+impl<T: ZkperIntegerTrait, P: ZkperPrimeTrait<T>> Div<&ZkperModularInteger<T, P>>
+    for &ZkperModularInteger<T, P>
+{
+    type Output = ZkperModularInteger<T, P>;
+
+    fn div(self, rhs: &ZkperModularInteger<T, P>) -> Self::Output {
+        self.clone() / rhs
+    }
+}
+
+impl<T: ZkperIntegerTrait, P: ZkperPrimeTrait<T>> Div<ZkperModularInteger<T, P>>
+    for &ZkperModularInteger<T, P>
+{
+    type Output = ZkperModularInteger<T, P>;
+
+    fn div(self, rhs: ZkperModularInteger<T, P>) -> Self::Output {
+        self.clone() / &rhs
+    }
+}
@@ -0,0 +1,43 @@
+use super::*;
+use zkper_integer::ZkperInteger;
+
+impl<T: ZkperIntegerTrait, P: ZkperPrimeTrait<T>> From<u64> for ZkperModularInteger<T, P> {
+    fn from(value: u64) -> Self {
+        Self::new(ZkperInteger::from(value))
+    }
+}
+
+impl<T: ZkperIntegerTrait, P: ZkperPrimeTrait<T>> From<u32> for ZkperModularInteger<T, P> {
+    fn from(value: u32) -> Self {
+        Self::new(ZkperInteger::from(value))
+    }
+}
+
+impl<T: ZkperIntegerTrait, P: ZkperPrimeTrait<T>> From<i32> for ZkperModularInteger<T, P> {
+    fn from(value: i32) -> Self {
+        Self::new(ZkperInteger::from(value))
+    }
+}
+
+impl<T: ZkperIntegerTrait, P: ZkperPrimeTrait<T>> From<usize> for ZkperModularInteger<T, P> {
+    fn from(value: usize) -> Self {
+        Self::new(ZkperInteger::from(value))
+    }
+}
+
+impl<T: ZkperIntegerTrait, P: ZkperPrimeTrait<T>> From<&ZkperInteger<T>>
+    for ZkperModularInteger<T, P>
+{
+    fn from(value: &ZkperInteger<T>) -> Self {
+        Self::new(value.clone())
+    }
+}
+
+// the canonical representative in [0, p)
+impl<T: ZkperIntegerTrait, P: ZkperPrimeTrait<T>> From<ZkperModularInteger<T, P>>
+    for ZkperInteger<T>
+{
+    fn from(value: ZkperModularInteger<T, P>) -> Self {
+        value.value
+    }
+}
@@ -1,9 +1,12 @@
 use crate::{traits::ZkperPrimeTrait, ZkperModularInteger};
 use zkper_integer::traits::ZkperIntegerTrait;
 
+pub mod compare;
+pub mod display;
 pub mod from;
 
 pub mod add;
+pub mod div;
 pub mod mul;
 pub mod neg;
 pub mod sub;
@@ -1,5 +1,8 @@
 use super::*;
-use std::ops::{Mul, MulAssign};
+use std::{
+    iter::Product,
+    ops::{Mul, MulAssign},
+};
 
 impl<T: ZkperIntegerTrait, P: ZkperPrimeTrait<T>> Mul for ZkperModularInteger<T, P> {
     type Output = Self;
@@ -32,3 +35,37 @@ impl<T: ZkperIntegerTrait, P: ZkperPrimeTrait<T>> MulAssign<&ZkperModularInteger
         self.value = P::multiply(&self.value, &rhs.value);
     }
 }
+
+impl<T: ZkperIntegerTrait, P: ZkperPrimeTrait<T>> Mul<&ZkperModularInteger<T, P>>
+    for &ZkperModularInteger<T, P>
+{
+    type Output = ZkperModularInteger<T, P>;
+
+    fn mul(self, rhs: &ZkperModularInteger<T, P>) -> Self::Output {
+        P::multiply(&self.value, &rhs.value).into()
+    }
+}
+
+impl<T: ZkperIntegerTrait, P: ZkperPrimeTrait<T>> Mul<ZkperModularInteger<T, P>>
+    for &ZkperModularInteger<T, P>
+{
+    type Output = ZkperModularInteger<T, P>;
+
+    fn mul(self, rhs: ZkperModularInteger<T, P>) -> Self::Output {
+        P::multiply(&self.value, &rhs.value).into()
+    }
+}
+
+impl<T: ZkperIntegerTrait, P: ZkperPrimeTrait<T>> Product for ZkperModularInteger<T, P> {
+    fn product<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::one(), |acc, x| acc * x)
+    }
+}
+
+impl<'a, T: ZkperIntegerTrait, P: ZkperPrimeTrait<T>> Product<&'a ZkperModularInteger<T, P>>
+    for ZkperModularInteger<T, P>
+{
+    fn product<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+        iter.fold(Self::one(), |acc, x| acc * x)
+    }
+}
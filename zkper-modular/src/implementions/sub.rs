@@ -33,3 +33,23 @@ impl<T: ZkperIntegerTrait, P: ZkperPrimeTrait<T>> SubAssign<&ZkperModularInteger
         self.value = P::subtract(&self.value, &rhs.value);
     }
 }
+
+impl<T: ZkperIntegerTrait, P: ZkperPrimeTrait<T>> Sub<&ZkperModularInteger<T, P>>
+    for &ZkperModularInteger<T, P>
+{
+    type Output = ZkperModularInteger<T, P>;
+
+    fn sub(self, rhs: &ZkperModularInteger<T, P>) -> Self::Output {
+        P::subtract(&self.value, &rhs.value).into()
+    }
+}
+
+impl<T: ZkperIntegerTrait, P: ZkperPrimeTrait<T>> Sub<ZkperModularInteger<T, P>>
+    for &ZkperModularInteger<T, P>
+{
+    type Output = ZkperModularInteger<T, P>;
+
+    fn sub(self, rhs: ZkperModularInteger<T, P>) -> Self::Output {
+        P::subtract(&self.value, &rhs.value).into()
+    }
+}
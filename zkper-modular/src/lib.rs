@@ -1,13 +1,21 @@
-use std::{marker::PhantomData, ops::Add};
+use std::marker::PhantomData;
 
+use rand_core::RngCore;
 use traits::ZkperPrimeTrait;
 use zkper_integer::{traits::ZkperIntegerTrait, ZkperInteger};
 
 pub mod backends;
+pub mod barrett;
 pub mod implementions;
+pub mod montgomery;
 pub mod prime;
+pub mod primes;
 pub mod traits;
 
+// re-exported so `zkper_prime!` and `declare_prime!` can name the integer types from any crate
+pub use zkper_integer;
+
+/// An integer modulo the prime `P`, always kept in the canonical range [0, p).
 #[derive(Debug, Clone)]
 pub struct ZkperModularInteger<T: ZkperIntegerTrait, P: ZkperPrimeTrait<T>> {
     pub value: ZkperInteger<T>,
@@ -23,32 +31,261 @@ impl<T: ZkperIntegerTrait, P: ZkperPrimeTrait<T>> From<ZkperInteger<T>>
 }
 
 impl<T: ZkperIntegerTrait, P: ZkperPrimeTrait<T>> ZkperModularInteger<T, P> {
+    /// Creates a new element, reducing `value` modulo p.
     pub fn new(value: ZkperInteger<T>) -> Self {
         Self {
-            value: value,
+            value: P::reduce(&value),
             _prime: PhantomData,
         }
     }
+
+    pub fn zero() -> Self {
+        Self::new(ZkperInteger::zero())
+    }
+
+    pub fn one() -> Self {
+        Self::new(ZkperInteger::one())
+    }
+
+    /// The modulus p.
+    pub fn modulus() -> ZkperInteger<T> {
+        P::value()
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.value.is_zero()
+    }
+
+    pub fn is_one(&self) -> bool {
+        self.value.is_one()
+    }
+
+    pub fn square(&self) -> Self {
+        Self::new(P::square(&self.value))
+    }
+
+    pub fn double(&self) -> Self {
+        Self::new(P::additive(&self.value, &self.value))
+    }
+
+    pub fn pow(&self, exp: &ZkperInteger<T>) -> Self {
+        Self::new(P::power(&self.value, exp))
+    }
+
+    /// Multiplicative inverse, None for zero.
+    pub fn inverse(&self) -> Option<Self> {
+        P::inverse(&self.value).map(Self::new)
+    }
+
+    /// A square root of this element, None if it is a non-residue.
+    pub fn sqrt(&self) -> Option<Self> {
+        P::sqrt(&self.value).map(Self::new)
+    }
+
+    /// Legendre symbol: 0 for zero, 1 for non-zero squares, -1 otherwise.
+    pub fn legendre(&self) -> i32 {
+        P::legendre(&self.value)
+    }
+
+    pub fn random<R: RngCore + ?Sized>(rng: &mut R) -> Self {
+        Self::new(P::random(rng))
+    }
 }
 
-// #[cfg(test)]
-// mod tests {
-//     use zkper_integer::{
-//         backends::rug_backend::RugBackend, traits::ZkperIntegerTrait, ZkperInteger,
-//     };
+/// Declares a zero-sized prime marker type usable with every integer backend.
+///
+/// ```
+/// use zkper_integer::backends::rug_backend::RugBackend;
+/// use zkper_modular::{zkper_prime, ZkperModularInteger};
+///
+/// zkper_prime!(pub Prime17 = "0x11");
+///
+/// let a = ZkperModularInteger::<RugBackend, Prime17>::from(20u64);
+/// assert_eq!(a.value, 3u64.into());
+/// ```
+#[macro_export]
+macro_rules! zkper_prime {
+    ($(#[$meta:meta])* $vis:vis $name:ident = $hex:literal) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+        $vis struct $name;
 
-//     use crate::{traits::ZkperPrimeTrait, ZkperModularInteger};
+        impl<T: $crate::zkper_integer::traits::ZkperIntegerTrait> $crate::traits::ZkperPrimeTrait<T>
+            for $name
+        {
+            fn value() -> $crate::zkper_integer::ZkperInteger<T> {
+                $crate::zkper_integer::ZkperInteger::from_hex_str($hex)
+            }
+        }
+    };
+}
 
-//     struct Prime17;
+/// Declares a prime marker like [`zkper_prime!`], along with its
+/// [`DeclaredPrime`](crate::traits::DeclaredPrime) constants. The bit length
+/// and Montgomery constant are derived from the modulus at compile time, and
+/// the declared two-adicity is checked against it.
+///
+/// Ending with `reduction = barrett` backs the prime's reduction, and so its
+/// multiplication, with a cached [`BarrettContext`](crate::barrett::BarrettContext).
+///
+/// ```
+/// use zkper_integer::backends::rug_backend::RugBackend;
+/// use zkper_modular::{declare_prime, traits::DeclaredPrime, ZkperModularInteger};
+///
+/// declare_prime!(pub Prime97 = "0x61", two_adicity = 5, generator = 5);
+/// declare_prime!(pub Prime193 = "0xc1", two_adicity = 6, generator = 5, reduction = barrett);
+///
+/// assert_eq!(Prime97::BITS, 7);
+/// let a = ZkperModularInteger::<RugBackend, Prime97>::from(100u64);
+/// assert_eq!(a.value, 3u64.into());
+/// let b = ZkperModularInteger::<RugBackend, Prime193>::from(200u64);
+/// assert_eq!((b.clone() * &b).value, 49u64.into());
+/// ```
+#[macro_export]
+macro_rules! declare_prime {
+    (@constants $name:ident, $hex:literal, $two_adicity:literal, $generator:literal) => {
+        impl $crate::traits::DeclaredPrime for $name {
+            const MODULUS: &'static str = $hex;
+            const BITS: u32 = $crate::prime::hex_bits($hex);
+            const TWO_ADICITY: u32 = $two_adicity;
+            const GENERATOR: u64 = $generator;
+            const MONTGOMERY_INV: u64 =
+                $crate::prime::montgomery_inv($crate::prime::hex_low_u64($hex));
+        }
+
+        const _: () = {
+            let low = $crate::prime::hex_low_u64($hex);
+            // the low limb pins down any two-adicity below 64
+            assert!(
+                low == 1 || (low - 1).trailing_zeros() == $two_adicity,
+                "two_adicity does not match the modulus"
+            );
+        };
+    };
+    (
+        $(#[$meta:meta])* $vis:vis $name:ident = $hex:literal,
+        two_adicity = $two_adicity:literal,
+        generator = $generator:literal,
+        reduction = barrett $(,)?
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+        $vis struct $name;
+
+        impl<T: $crate::zkper_integer::traits::ZkperIntegerTrait + 'static>
+            $crate::traits::ZkperPrimeTrait<T> for $name
+        {
+            fn value() -> $crate::zkper_integer::ZkperInteger<T> {
+                $crate::zkper_integer::ZkperInteger::from_hex_str($hex)
+            }
+
+            fn barrett() -> Option<std::rc::Rc<$crate::barrett::BarrettContext<T>>> {
+                Some($crate::barrett::cached::<T, Self>())
+            }
+        }
+
+        $crate::declare_prime!(@constants $name, $hex, $two_adicity, $generator);
+    };
+    (
+        $(#[$meta:meta])* $vis:vis $name:ident = $hex:literal,
+        two_adicity = $two_adicity:literal,
+        generator = $generator:literal $(,)?
+    ) => {
+        $crate::zkper_prime!($(#[$meta])* $vis $name = $hex);
+        $crate::declare_prime!(@constants $name, $hex, $two_adicity, $generator);
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use zkper_integer::{backends::rug_backend::RugBackend, ZkperInteger};
+    use zkper_rand::TestRng;
+
+    use crate::ZkperModularInteger;
+
+    zkper_prime!(Prime17 = "0x11");
+    // 2^5 * 3 + 1, exercises Tonelli–Shanks with s = 5
+    zkper_prime!(Prime97 = "0x61");
+    // the BLS12-381 scalar field, two-adicity 32
+    zkper_prime!(
+        Bls12_381Scalar = "0x73eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000001"
+    );
+
+    type F17 = ZkperModularInteger<RugBackend, Prime17>;
+    type F97 = ZkperModularInteger<RugBackend, Prime97>;
+    type Fr = ZkperModularInteger<RugBackend, Bls12_381Scalar>;
+
+    #[test]
+    fn test_zkper_modular_integer() {
+        let a = F17::from(10u64);
+        let b = F17::from(ZkperInteger::from(-3));
+        assert_eq!(b.value, 14u64.into());
+
+        assert_eq!((a.clone() + &b).value, 7u64.into());
+        assert_eq!((a.clone() - &b).value, 13u64.into());
+        assert_eq!((a.clone() * &b).value, 4u64.into());
+        assert_eq!((-a.clone()).value, 7u64.into());
+        assert_eq!((a.clone() / &b * &b), a);
+        assert_eq!(a.pow(&16u64.into()), F17::one());
+        assert_eq!(a.inverse().unwrap() * &a, F17::one());
+        assert!(F17::zero().inverse().is_none());
+    }
+
+    #[test]
+    fn test_operators_on_references() {
+        let a = F17::from(10u64);
+        let b = F17::from(5usize);
+
+        assert_eq!(&a + &b, F17::from(15u64));
+        assert_eq!(&a - b.clone(), F17::from(5u64));
+        assert_eq!(&a * &b, F17::from(16u64));
+        assert_eq!(&a / &b, F17::from(2u64));
+        assert_eq!(&a / b.clone() * &b, a);
+
+        let mut c = a.clone();
+        c += &b;
+        c -= b.clone();
+        c *= &b;
+        c /= b.clone();
+        assert_eq!(c, a);
 
-//     impl<T: ZkperIntegerTrait> ZkperPrimeTrait<T> for Prime17 {
-//         fn value() -> ZkperInteger<T> {
-//             ZkperInteger::from(17)
-//         }
-//     }
+        let values = [a.clone(), b.clone(), F17::from(3u64)];
+        assert_eq!(values.iter().sum::<F17>(), F17::from(1u64));
+        assert_eq!(values.into_iter().product::<F17>(), F17::from(14u64));
+        assert_eq!(F17::from(&ZkperInteger::from(18u64)), F17::one());
+        assert_eq!(ZkperInteger::from(a), 10u64.into());
+    }
+
+    #[test]
+    fn test_legendre_and_sqrt() {
+        for i in 0..17u64 {
+            let a = F17::from(i);
+            match a.sqrt() {
+                Some(root) => {
+                    assert_eq!(root.square(), a);
+                    assert!(a.legendre() >= 0);
+                }
+                None => assert_eq!(a.legendre(), -1),
+            }
+        }
 
-//     #[test]
-//     fn test_zkper_modular_integer() {
-//         let modular_int = ZkperModularInteger::<RugBackend, Prime17>::new(ZkperInteger::from(10));
-//     }
-// }
+        for i in 0..97u64 {
+            let a = F97::from(i);
+            if let Some(root) = a.sqrt() {
+                assert_eq!(root.square(), a);
+            } else {
+                assert_eq!(a.legendre(), -1);
+            }
+        }
+    }
+
+    #[test]
+    fn test_random_sqrt_large_two_adicity() {
+        let mut rng = TestRng::new();
+        for _ in 0..10 {
+            let a = Fr::random(&mut rng).square();
+            let root = a.sqrt().unwrap();
+            assert_eq!(root.square(), a);
+        }
+    }
+}
@@ -0,0 +1,91 @@
+use zkper_integer::{traits::ZkperIntegerTrait, ZkperInteger};
+
+/// Precomputed constants for Montgomery multiplication modulo an odd p.
+///
+/// Elements in Montgomery form are a * R mod p with R = 2^(64 * limbs), and
+/// the reduction works one 64-bit limb at a time.
+#[derive(Debug, Clone)]
+pub struct MontgomeryContext<T: ZkperIntegerTrait> {
+    modulus: ZkperInteger<T>,
+    limbs: u32,
+    /// -p^-1 mod 2^64
+    inv: u64,
+    /// R mod p, the Montgomery form of one
+    r: ZkperInteger<T>,
+    /// R^2 mod p, to convert into Montgomery form
+    r2: ZkperInteger<T>,
+}
+
+impl<T: ZkperIntegerTrait> MontgomeryContext<T> {
+    /// Builds the context of an odd modulus, computing every constant at runtime.
+    pub fn new(modulus: ZkperInteger<T>) -> Self {
+        let bits = modulus.to_hex_string().len() as u32 * 4;
+        let limbs = bits.div_ceil(64).max(1);
+        let inv = crate::prime::montgomery_inv(low_limb(&modulus));
+        Self::with_constants(modulus, limbs, inv)
+    }
+
+    /// Builds the context from constants known ahead of time, as
+    /// [`DeclaredPrime`](crate::traits::DeclaredPrime) provides them.
+    pub fn with_constants(modulus: ZkperInteger<T>, limbs: u32, inv: u64) -> Self {
+        let r = (ZkperInteger::<T>::one() << (64 * limbs)) % &modulus;
+        let r2 = (r.clone() * &r) % &modulus;
+        Self {
+            modulus,
+            limbs,
+            inv,
+            r,
+            r2,
+        }
+    }
+
+    pub fn modulus(&self) -> &ZkperInteger<T> {
+        &self.modulus
+    }
+
+    /// The Montgomery form of one, R mod p.
+    pub fn one(&self) -> &ZkperInteger<T> {
+        &self.r
+    }
+
+    /// a * R mod p, for a in [0, p).
+    pub fn to_montgomery(&self, a: &ZkperInteger<T>) -> ZkperInteger<T> {
+        self.reduce(a.clone() * &self.r2)
+    }
+
+    /// a * R^-1 mod p, the inverse of [`Self::to_montgomery`].
+    pub fn from_montgomery(&self, a: &ZkperInteger<T>) -> ZkperInteger<T> {
+        self.reduce(a.clone())
+    }
+
+    /// a * b * R^-1 mod p: the Montgomery form of the product of two
+    /// elements in Montgomery form.
+    pub fn mul(&self, a: &ZkperInteger<T>, b: &ZkperInteger<T>) -> ZkperInteger<T> {
+        self.reduce(a.clone() * b)
+    }
+
+    pub fn square(&self, a: &ZkperInteger<T>) -> ZkperInteger<T> {
+        self.mul(a, a)
+    }
+
+    /// REDC: t * R^-1 mod p for t < p * R, clearing one low limb per round.
+    fn reduce(&self, mut t: ZkperInteger<T>) -> ZkperInteger<T> {
+        for _ in 0..self.limbs {
+            let m = low_limb(&t).wrapping_mul(self.inv);
+            t = (t + self.modulus.clone() * m) >> 64u32;
+        }
+        if t >= self.modulus {
+            t - &self.modulus
+        } else {
+            t
+        }
+    }
+}
+
+fn low_limb<T: ZkperIntegerTrait>(a: &ZkperInteger<T>) -> u64 {
+    let mut bytes = [0u8; 8];
+    let le = a.to_bytes();
+    let len = le.len().min(8);
+    bytes[..len].copy_from_slice(&le[..len]);
+    u64::from_le_bytes(bytes)
+}
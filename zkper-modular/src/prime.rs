@@ -16,3 +16,58 @@ use crate::traits::ZkperPrimeTrait;
 // Define ZkperPrime struct
 #[derive(Debug, Clone, Hash)]
 pub struct ZkperPrime<T: ZkperIntegerTrait, P: ZkperPrimeTrait<T>>(PhantomData<(T, P)>);
+
+/// Hex digits of `hex`, without its optional `0x` prefix.
+const fn hex_digits(hex: &str) -> &[u8] {
+    match hex.as_bytes() {
+        [b'0', b'x' | b'X', rest @ ..] => rest,
+        digits => digits,
+    }
+}
+
+const fn hex_digit(c: u8) -> u64 {
+    match c {
+        b'0'..=b'9' => (c - b'0') as u64,
+        b'a'..=b'f' => (c - b'a' + 10) as u64,
+        b'A'..=b'F' => (c - b'A' + 10) as u64,
+        _ => panic!("invalid hex digit"),
+    }
+}
+
+/// Bit length of the number written in `hex`, at compile time.
+pub const fn hex_bits(hex: &str) -> u32 {
+    let digits = hex_digits(hex);
+    let mut i = 0;
+    while i < digits.len() && hex_digit(digits[i]) == 0 {
+        i += 1;
+    }
+    if i == digits.len() {
+        return 0;
+    }
+    4 * (digits.len() - i - 1) as u32 + (u64::BITS - hex_digit(digits[i]).leading_zeros())
+}
+
+/// Lowest 64 bits of the number written in `hex`, at compile time.
+pub const fn hex_low_u64(hex: &str) -> u64 {
+    let digits = hex_digits(hex);
+    let mut low = 0u64;
+    let mut i = 0;
+    while i < 16 && i < digits.len() {
+        low |= hex_digit(digits[digits.len() - 1 - i]) << (4 * i);
+        i += 1;
+    }
+    low
+}
+
+/// -p^-1 mod 2^64 from the lowest limb of an odd p, by Newton iteration.
+pub const fn montgomery_inv(low: u64) -> u64 {
+    assert!(low & 1 == 1, "Montgomery form needs an odd modulus");
+    // each step doubles the number of correct low bits, starting from 1
+    let mut inv = 1u64;
+    let mut i = 0;
+    while i < 6 {
+        inv = inv.wrapping_mul(2u64.wrapping_sub(low.wrapping_mul(inv)));
+        i += 1;
+    }
+    inv.wrapping_neg()
+}
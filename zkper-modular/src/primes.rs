@@ -0,0 +1,145 @@
+//! Primes in common use, ready for [`ZkperModularInteger`](crate::ZkperModularInteger).
+//!
+//! ```
+//! use zkper_integer::backends::rug_backend::RugBackend;
+//! use zkper_modular::{primes::Goldilocks, ZkperModularInteger};
+//!
+//! type F = ZkperModularInteger<RugBackend, Goldilocks>;
+//! assert_eq!(F::from(u64::MAX).value, 0xffff_fffeu64.into());
+//! ```
+
+use crate::declare_prime;
+
+declare_prime!(
+    /// The BLS12-381 scalar field.
+    pub Bls12_381Fr = "0x73eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000001",
+    two_adicity = 32,
+    generator = 7,
+);
+
+declare_prime!(
+    /// The BLS12-381 base field.
+    pub Bls12_381Fq = "0x1a0111ea397fe69a4b1ba7b6434bacd764774b84f38512bf6730d2a0f6b0f6241eabfffeb153ffffb9feffffffffaaab",
+    two_adicity = 1,
+    generator = 2,
+);
+
+declare_prime!(
+    /// The BN254 scalar field.
+    pub Bn254Fr = "0x30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000001",
+    two_adicity = 28,
+    generator = 5,
+);
+
+declare_prime!(
+    /// The BN254 base field.
+    pub Bn254Fq = "0x30644e72e131a029b85045b68181585d97816a916871ca8d3c208c16d87cfd47",
+    two_adicity = 1,
+    generator = 3,
+);
+
+declare_prime!(
+    /// The Goldilocks prime 2^64 - 2^32 + 1.
+    pub Goldilocks = "0xffffffff00000001",
+    two_adicity = 32,
+    generator = 7,
+);
+
+declare_prime!(
+    /// The Mersenne prime 2^61 - 1.
+    pub Mersenne61 = "0x1fffffffffffffff",
+    two_adicity = 1,
+    generator = 37,
+);
+
+#[cfg(test)]
+mod tests {
+    use zkper_integer::{
+        backends::{rug_backend::RugBackend, u128_backend::U128Backend},
+        traits::ZkperIntegerTrait,
+        ZkperInteger,
+    };
+    use zkper_rand::TestRng;
+
+    use super::*;
+    use crate::traits::{DeclaredPrime, ZkperPrimeTrait};
+
+    fn check_prime<T: ZkperIntegerTrait, P: ZkperPrimeTrait<T> + DeclaredPrime>() {
+        let p = P::value();
+        assert_eq!(
+            p.to_hex_string().trim_start_matches('0').len() as u32,
+            P::BITS.div_ceil(4)
+        );
+        assert_eq!(ZkperInteger::<T>::from_hex_str(P::MODULUS), p);
+
+        // p - 1 = q * 2^s with q odd
+        let q = (p.clone() - 1u64) >> P::TWO_ADICITY;
+        assert!(q.is_odd());
+        assert_eq!(q << P::TWO_ADICITY, p.clone() - 1u64);
+
+        // a generator is never a square, so its 2-adic root has exact order 2^s
+        assert_eq!(P::legendre(&P::GENERATOR.into()), -1);
+        let root = P::two_adic_root_of_unity::<T>();
+        let mut order = root.clone();
+        for _ in 1..P::TWO_ADICITY {
+            order = P::square(&order);
+            assert!(!order.is_one());
+        }
+        assert!(P::square(&order).is_one());
+        assert_eq!(
+            P::power(&root, &(ZkperInteger::one() << P::TWO_ADICITY)),
+            ZkperInteger::one()
+        );
+
+        let mont = P::montgomery::<T>();
+        let mut rng = TestRng::new();
+        for _ in 0..10 {
+            let a = P::random(&mut rng);
+            let b = P::random(&mut rng);
+            let (am, bm) = (mont.to_montgomery(&a), mont.to_montgomery(&b));
+            assert_eq!(mont.from_montgomery(&am), a);
+            assert_eq!(
+                mont.from_montgomery(&mont.mul(&am, &bm)),
+                P::multiply(&a, &b)
+            );
+            assert_eq!(mont.from_montgomery(mont.one()), ZkperInteger::one());
+        }
+    }
+
+    #[test]
+    fn test_declared_primes() {
+        check_prime::<RugBackend, Bls12_381Fr>();
+        check_prime::<RugBackend, Bls12_381Fq>();
+        check_prime::<RugBackend, Bn254Fr>();
+        check_prime::<RugBackend, Bn254Fq>();
+        check_prime::<RugBackend, Goldilocks>();
+        check_prime::<RugBackend, Mersenne61>();
+
+        assert!(ZkperInteger::<RugBackend>::from_hex_str(Bn254Fr::MODULUS).is_prime());
+        assert_eq!(Bls12_381Fr::BITS, 255);
+        assert_eq!(Bls12_381Fq::BITS, 381);
+        assert_eq!(Bls12_381Fq::LIMBS, 6);
+        assert_eq!(Goldilocks::BITS, 64);
+        assert_eq!(Mersenne61::LIMBS, 1);
+    }
+
+    #[test]
+    fn test_montgomery_constants() {
+        for (low, inv) in [
+            (Bls12_381Fr::MODULUS, Bls12_381Fr::MONTGOMERY_INV),
+            (Bn254Fq::MODULUS, Bn254Fq::MONTGOMERY_INV),
+            (Goldilocks::MODULUS, Goldilocks::MONTGOMERY_INV),
+        ] {
+            let low = crate::prime::hex_low_u64(low);
+            assert_eq!(low.wrapping_mul(inv), u64::MAX);
+        }
+        // the well-known constant of the BLS12-381 scalar field
+        assert_eq!(Bls12_381Fr::MONTGOMERY_INV, 0xfffffffeffffffff);
+    }
+
+    #[test]
+    fn test_mersenne61_on_word_backend() {
+        // p * R must fit in the word for the Montgomery reduction
+        check_prime::<U128Backend, Mersenne61>();
+    }
+}
@@ -1,15 +1,31 @@
-use std::{
-    fmt::Debug,
-    ops::{Add, Sub},
-};
+use rand_core::RngCore;
+use std::{fmt::Debug, rc::Rc};
 use zkper_integer::{traits::ZkperIntegerTrait, ZkperInteger};
 
+use crate::{barrett::BarrettContext, montgomery::MontgomeryContext};
+
 // Define a trait for ZkperPrime
-pub trait ZkperPrimeTrait<T: ZkperIntegerTrait>: Sized + Add + Sub + Clone + Debug {
+pub trait ZkperPrimeTrait<T: ZkperIntegerTrait>: Sized + Clone + Debug {
     fn value() -> ZkperInteger<T>;
 
+    /// The Barrett context backing `reduce`, and so every operation built on
+    /// it; None reduces by plain division.
+    fn barrett() -> Option<Rc<BarrettContext<T>>> {
+        None
+    }
+
+    /// Reduce into the canonical range [0, p).
     fn reduce(a: &ZkperInteger<T>) -> ZkperInteger<T> {
-        a % &Self::value()
+        if let Some(barrett) = Self::barrett() {
+            return barrett.reduce(a);
+        }
+        let p = Self::value();
+        let r = a % &p;
+        if r < ZkperInteger::zero() {
+            r + &p
+        } else {
+            r
+        }
     }
 
     // basic arithmetic operations  // to optimize clone
@@ -32,7 +48,7 @@ pub trait ZkperPrimeTrait<T: ZkperIntegerTrait>: Sized + Add + Sub + Clone + Deb
     }
     fn power(base: &ZkperInteger<T>, exp: &ZkperInteger<T>) -> ZkperInteger<T> {
         let mut result = ZkperInteger::one();
-        let mut base = base.clone();
+        let mut base = Self::reduce(base);
         let mut exp = exp.clone();
         while !exp.is_zero() {
             if exp.is_odd() {
@@ -43,4 +59,119 @@ pub trait ZkperPrimeTrait<T: ZkperIntegerTrait>: Sized + Add + Sub + Clone + Deb
         }
         result
     }
+
+    /// Multiplicative inverse, None for zero.
+    fn inverse(a: &ZkperInteger<T>) -> Option<ZkperInteger<T>> {
+        let a = Self::reduce(a);
+        if a.is_zero() {
+            return None;
+        }
+        a.invert(&Self::value()).ok().map(|inv| Self::reduce(&inv))
+    }
+
+    /// Legendre symbol (a / p) via Euler's criterion: 0, 1 or -1.
+    fn legendre(a: &ZkperInteger<T>) -> i32 {
+        let a = Self::reduce(a);
+        if a.is_zero() {
+            return 0;
+        }
+
+        let exp = (Self::value() - 1u64) >> 1u32;
+        if Self::power(&a, &exp).is_one() {
+            1
+        } else {
+            -1
+        }
+    }
+
+    /// Square root by Tonelli–Shanks, None if `a` is a non-residue.
+    fn sqrt(a: &ZkperInteger<T>) -> Option<ZkperInteger<T>> {
+        let a = Self::reduce(a);
+        let p = Self::value();
+        if a.is_zero() || p == ZkperInteger::two() {
+            return Some(a);
+        }
+        if Self::legendre(&a) != 1 {
+            return None;
+        }
+
+        // p - 1 = q * 2^s with q odd
+        let mut q = p.clone() - 1u64;
+        let mut s = 0u32;
+        while q.is_even() {
+            q >>= 1u32;
+            s += 1;
+        }
+
+        // any quadratic non-residue
+        let mut z = ZkperInteger::two();
+        while Self::legendre(&z) != -1 {
+            z += 1u64;
+        }
+
+        let mut m = s;
+        let mut c = Self::power(&z, &q);
+        let mut t = Self::power(&a, &q);
+        let mut r = Self::power(&a, &((q + 1u64) >> 1u32));
+
+        while !t.is_one() {
+            // least i with t^(2^i) = 1
+            let mut i = 0;
+            let mut t2i = t.clone();
+            while !t2i.is_one() {
+                t2i = Self::square(&t2i);
+                i += 1;
+            }
+
+            let mut b = c;
+            for _ in 0..(m - i - 1) {
+                b = Self::square(&b);
+            }
+
+            m = i;
+            c = Self::square(&b);
+            t = Self::multiply(&t, &c);
+            r = Self::multiply(&r, &b);
+        }
+
+        Some(r)
+    }
+
+    /// Uniformly random element of [0, p).
+    fn random<R: RngCore + ?Sized>(rng: &mut R) -> ZkperInteger<T> {
+        Self::value().random_below(rng)
+    }
+}
+
+/// Compile-time constants of a prime declared with [`declare_prime!`](crate::declare_prime).
+pub trait DeclaredPrime {
+    /// The modulus p, as a hex literal.
+    const MODULUS: &'static str;
+    /// Bit length of p.
+    const BITS: u32;
+    /// The largest s with 2^s dividing p - 1.
+    const TWO_ADICITY: u32;
+    /// A generator of the multiplicative group.
+    const GENERATOR: u64;
+    /// Number of 64-bit limbs of p, so the Montgomery radix is R = 2^(64 * LIMBS).
+    const LIMBS: u32 = Self::BITS.div_ceil(64);
+    /// -p^-1 mod 2^64, for limb-wise Montgomery reduction.
+    const MONTGOMERY_INV: u64;
+
+    /// A primitive 2^TWO_ADICITY-th root of unity: GENERATOR^((p - 1) / 2^TWO_ADICITY).
+    fn two_adic_root_of_unity<T: ZkperIntegerTrait>() -> ZkperInteger<T>
+    where
+        Self: ZkperPrimeTrait<T>,
+    {
+        let exp = (Self::value() - 1u64) >> Self::TWO_ADICITY;
+        Self::power(&Self::GENERATOR.into(), &exp)
+    }
+
+    /// The Montgomery context of p, built from the constants above.
+    fn montgomery<T: ZkperIntegerTrait>() -> MontgomeryContext<T>
+    where
+        Self: ZkperPrimeTrait<T>,
+    {
+        MontgomeryContext::with_constants(Self::value(), Self::LIMBS, Self::MONTGOMERY_INV)
+    }
 }
@@ -1 +1,2 @@
+#[cfg(feature = "backend-rug")]
 pub mod rug;
@@ -1,49 +1,67 @@
-use rand::RngCore;
+use rand_core::RngCore;
 use rug::{
     rand::{RandGen, ThreadRandGen},
     Integer,
 };
 
-use crate::ZkperRng;
+use crate::{TestRng, ZkperRng};
 
-impl RandGen for ZkperRng {
-    fn gen(&mut self) -> u32 {
-        self.next_u32()
-    }
+macro_rules! impl_rand_gen {
+    ($($rng:ident),*) => {$(
+        impl RandGen for $rng {
+            fn gen(&mut self) -> u32 {
+                self.next_u32()
+            }
 
-    fn gen_bits(&mut self, bits: u32) -> u32 {
-        if bits == 0 {
-            0
-        } else if bits < 32 {
-            self.next_u32() >> (32 - bits)
-        } else {
-            self.next_u32()
+            fn gen_bits(&mut self, bits: u32) -> u32 {
+                gen_bits(self, bits)
+            }
+
+            fn seed(&mut self, seed: &Integer) {
+                let seed_u64 = seed.to_u64_wrapping();
+                *self = $rng::from_seed(seed_u64);
+            }
         }
-    }
 
-    fn seed(&mut self, seed: &Integer) {
-        let seed_u64 = seed.to_u64_wrapping();
-        *self = ZkperRng::from_seed(seed_u64);
-    }
+        impl ThreadRandGen for $rng {
+            fn gen(&mut self) -> u32 {
+                self.next_u32()
+            }
+
+            fn gen_bits(&mut self, bits: u32) -> u32 {
+                gen_bits(self, bits)
+            }
+
+            fn seed(&mut self, seed: &Integer) {
+                let seed_u64 = seed.to_u64_wrapping();
+                *self = $rng::from_seed(seed_u64);
+            }
+        }
+    )*};
 }
 
-impl ThreadRandGen for ZkperRng {
+impl_rand_gen!(ZkperRng, TestRng);
+
+/// Any `RngCore` borrowed as a rug `ThreadRandGen`, drawing exactly what the
+/// impls above draw, for code generic over the generator.
+pub struct RugRng<'a, R: RngCore + ?Sized>(pub &'a mut R);
+
+impl<R: RngCore + ?Sized> ThreadRandGen for RugRng<'_, R> {
     fn gen(&mut self) -> u32 {
-        self.next_u32()
+        self.0.next_u32()
     }
 
     fn gen_bits(&mut self, bits: u32) -> u32 {
-        if bits == 0 {
-            0
-        } else if bits < 32 {
-            self.next_u32() >> (32 - bits)
-        } else {
-            self.next_u32()
-        }
+        gen_bits(self.0, bits)
     }
+}
 
-    fn seed(&mut self, seed: &Integer) {
-        let seed_u64 = seed.to_u64_wrapping();
-        *self = ZkperRng::from_seed(seed_u64);
+fn gen_bits<R: RngCore + ?Sized>(rng: &mut R, bits: u32) -> u32 {
+    if bits == 0 {
+        0
+    } else if bits < 32 {
+        rng.next_u32() >> (32 - bits)
+    } else {
+        rng.next_u32()
     }
 }
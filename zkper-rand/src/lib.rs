@@ -1,11 +1,18 @@
-use rand::{CryptoRng, RngCore, SeedableRng};
+#![cfg_attr(not(feature = "std"), no_std)]
+
 use rand_chacha::ChaCha20Rng;
+use rand_core::{CryptoRng, RngCore, SeedableRng};
 
 pub mod implements;
+pub mod test_rng;
+
+pub use test_rng::TestRng;
 
 pub struct ZkperRng(ChaCha20Rng);
 
 impl ZkperRng {
+    /// Seeded from the operating system, which needs the `std` feature.
+    #[cfg(feature = "std")]
     pub fn new() -> Self {
         Self(ChaCha20Rng::from_entropy())
     }
@@ -17,6 +24,53 @@ impl ZkperRng {
     pub fn from_seed(seed: u64) -> Self {
         Self(ChaCha20Rng::seed_from_u64(seed))
     }
+
+    /// Uses `seed` as the ChaCha20 key directly, e.g. a challenge squeezed
+    /// from a transcript.
+    pub fn from_seed_bytes(seed: [u8; 32]) -> Self {
+        Self(ChaCha20Rng::from_seed(seed))
+    }
+
+    /// Replaces the state with a fresh seed from the operating system.
+    #[cfg(feature = "std")]
+    pub fn reseed(&mut self) {
+        *self = Self::new();
+    }
+
+    /// A child generator seeded from the next 32 bytes of this one. Forking
+    /// the same generator in the same order always yields the same children.
+    pub fn fork(&mut self) -> Self {
+        let mut seed = [0u8; 32];
+        self.fill_bytes(&mut seed);
+        Self::from_seed_bytes(seed)
+    }
+
+    /// A child generator with the seed of this one on its own `stream`,
+    /// starting from the beginning of that stream. Children for different
+    /// streams are independent, and unlike `fork` they do not depend on the
+    /// order they are created in, so thread `i` can take stream `i`.
+    pub fn split(&self, stream: u64) -> Self {
+        let mut child = Self::from_seed_bytes(self.0.get_seed());
+        child.set_stream(stream);
+        child
+    }
+
+    /// Switches to `stream`, keeping the seed and the position in the
+    /// keystream.
+    pub fn set_stream(&mut self, stream: u64) {
+        self.0.set_stream(stream);
+    }
+
+    pub fn stream(&self) -> u64 {
+        self.0.get_stream()
+    }
+}
+
+#[cfg(feature = "std")]
+impl Default for ZkperRng {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl RngCore for ZkperRng {
@@ -32,9 +86,60 @@ impl RngCore for ZkperRng {
         self.0.fill_bytes(dest)
     }
 
-    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
         self.0.try_fill_bytes(dest)
     }
 }
 
 impl CryptoRng for ZkperRng {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn output(rng: &mut ZkperRng) -> [u64; 4] {
+        [(); 4].map(|_| rng.next_u64())
+    }
+
+    #[test]
+    fn test_fork_and_split() {
+        let mut parent = ZkperRng::new_test();
+        let mut again = ZkperRng::new_test();
+
+        // forks are deterministic, distinct and advance the parent
+        let (mut a, mut b) = (parent.fork(), parent.fork());
+        assert_eq!(output(&mut a), output(&mut again.fork()));
+        assert_ne!(output(&mut a), output(&mut b));
+        assert_eq!(
+            output(&mut parent),
+            output(&mut {
+                again.fork();
+                again
+            })
+        );
+
+        // splits depend only on the seed and the stream
+        let root = ZkperRng::new_test();
+        let mut streams = [0, 1, 2, 3].map(|i| root.split(i));
+        assert_eq!(streams[2].stream(), 2);
+        assert_eq!(output(&mut streams[2]), output(&mut root.split(2)));
+        assert_ne!(output(&mut streams[0]), output(&mut streams[1]));
+        assert_eq!(
+            output(&mut root.split(0)),
+            output(&mut ZkperRng::new_test())
+        );
+
+        let seed = [7u8; 32];
+        assert_eq!(
+            output(&mut ZkperRng::from_seed_bytes(seed)),
+            output(&mut ZkperRng::from_seed_bytes(seed))
+        );
+
+        #[cfg(feature = "std")]
+        {
+            let mut reseeded = ZkperRng::new_test();
+            reseeded.reseed();
+            assert_ne!(output(&mut reseeded), output(&mut ZkperRng::new_test()));
+        }
+    }
+}
@@ -0,0 +1,111 @@
+//! A small deterministic generator for tests, benches and fixtures.
+//!
+//! [`TestRng`] is xoshiro256** seeded through SplitMix64, written out here so
+//! its output is fixed by this file alone: bumping `rand` or `rand_chacha`
+//! cannot change a test vector drawn from it. It is not cryptographically
+//! secure and does not implement `CryptoRng`; use [`ZkperRng`](crate::ZkperRng)
+//! for anything that has to stay secret.
+
+use rand_core::RngCore;
+
+/// Seed of [`TestRng::new`].
+pub const TEST_SEED: u64 = 1234567890;
+
+/// xoshiro256**, see the module documentation.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TestRng {
+    state: [u64; 4],
+}
+
+impl TestRng {
+    /// The generator every test starts from.
+    pub fn new() -> Self {
+        Self::from_seed(TEST_SEED)
+    }
+
+    /// Expands `seed` into the 256-bit state with SplitMix64, which never
+    /// yields the all-zero state xoshiro cannot leave.
+    pub fn from_seed(seed: u64) -> Self {
+        let mut splitmix = seed;
+        let mut state = [0u64; 4];
+        for word in state.iter_mut() {
+            splitmix = splitmix.wrapping_add(0x9e37_79b9_7f4a_7c15);
+            let mut z = splitmix;
+            z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+            *word = z ^ (z >> 31);
+        }
+        Self { state }
+    }
+}
+
+impl Default for TestRng {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RngCore for TestRng {
+    /// The upper half of `next_u64`, the better half of xoshiro's output.
+    fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let [s0, s1, s2, s3] = &mut self.state;
+        let result = s1.wrapping_mul(5).rotate_left(7).wrapping_mul(9);
+        let t = *s1 << 17;
+
+        *s2 ^= *s0;
+        *s3 ^= *s1;
+        *s1 ^= *s2;
+        *s0 ^= *s3;
+        *s2 ^= t;
+        *s3 = s3.rotate_left(45);
+
+        result
+    }
+
+    /// Little-endian words of `next_u64`, the last one truncated.
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        for chunk in dest.chunks_mut(8) {
+            let word = self.next_u64().to_le_bytes();
+            chunk.copy_from_slice(&word[..chunk.len()]);
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_test_rng() {
+        // pinned outputs: these only change if this file does
+        let mut rng = TestRng::from_seed(0);
+        assert_eq!(
+            [rng.next_u64(), rng.next_u64(), rng.next_u64()],
+            [
+                0x99ec_5f36_cb75_f2b4,
+                0xbf6e_1f78_4956_452a,
+                0x1a5f_849d_4933_e6e0
+            ]
+        );
+
+        let mut a = TestRng::new();
+        let mut b = a.clone();
+        let mut bytes = [0u8; 13];
+        a.fill_bytes(&mut bytes);
+        assert_eq!(bytes[..8], b.next_u64().to_le_bytes());
+        assert_eq!(bytes[8..], b.next_u64().to_le_bytes()[..5]);
+        assert_eq!(a, b);
+
+        assert_ne!(TestRng::from_seed(1), TestRng::from_seed(2));
+        assert_eq!(TestRng::default(), TestRng::from_seed(TEST_SEED));
+    }
+}
@@ -1,6 +1,8 @@
-use zkper_rand::ZkperRng;
+#![allow(unused_variables, unused_mut)]
+
+use zkper_rand::TestRng;
 
 #[test]
 fn test_mimc() {
-    let mut rng = ZkperRng::new_test();
+    let mut rng = TestRng::new();
 }
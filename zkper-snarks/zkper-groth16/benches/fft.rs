@@ -0,0 +1,33 @@
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion, Throughput};
+use zkper_curves::curves::bls12_381::BLS12_381_SCALAR;
+use zkper_groth16::evaluation_domain::EvaluationDomain;
+use zkper_rand::TestRng;
+
+fn bench_fft(c: &mut Criterion) {
+    let mut rng = TestRng::new();
+    let coeffs = (0..1 << 20)
+        .map(|_| BLS12_381_SCALAR.sample_raw(&mut rng))
+        .collect::<Vec<_>>();
+
+    let mut group = c.benchmark_group("fft");
+    group.sample_size(10);
+    for log_size in (10..=20).step_by(2) {
+        let size = 1usize << log_size;
+        let domain = || EvaluationDomain::new(coeffs[..size].to_vec()).unwrap();
+        group.throughput(Throughput::Elements(size as u64));
+        group.bench_with_input(BenchmarkId::new("fft", size), &size, |b, _| {
+            b.iter_batched(domain, |mut domain| domain.fft(), BatchSize::LargeInput)
+        });
+        group.bench_with_input(BenchmarkId::new("coset_fft", size), &size, |b, _| {
+            b.iter_batched(
+                domain,
+                |mut domain| domain.coset_fft(),
+                BatchSize::LargeInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_fft);
+criterion_main!(benches);
@@ -0,0 +1,99 @@
+use std::sync::Arc;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use zkper_curves::{
+    curves::bls12_381::{
+        curves::{g1::G1Projective, g2::G2Projective},
+        Bls12_381ScalarField, Scalar,
+    },
+    traits::field::FieldTrait,
+};
+use zkper_groth16::multiexp::{
+    multiexp, multiexp_g2, multiexp_with_config, params::MAX_WINDOW_SIZE, prepare_bases,
+    prepare_bases_g2, MultiexpConfig,
+};
+use zkper_rand::TestRng;
+
+/// log2 of the largest multiexp; the smaller sizes reuse a prefix of its bases.
+const MAX_LOG_SIZE: usize = 16;
+const MAX_LOG_SIZE_G2: usize = 12;
+
+fn random_exponents(rng: &mut TestRng, size: usize) -> Arc<Vec<Scalar>> {
+    Arc::new(
+        (0..size)
+            .map(|_| Scalar::from(Bls12_381ScalarField::random(rng)))
+            .collect(),
+    )
+}
+
+fn bench_multiexp(c: &mut Criterion) {
+    let mut rng = TestRng::new();
+    // consecutive multiples of a random point, much cheaper than fresh points
+    let step = G1Projective::random(&mut rng);
+    let points = (0..1 << MAX_LOG_SIZE)
+        .scan(G1Projective::random(&mut rng), |acc, _| {
+            *acc = acc.add(&step);
+            Some(acc.clone())
+        })
+        .collect::<Vec<_>>();
+    let bases = prepare_bases(&points);
+
+    let mut group = c.benchmark_group("multiexp_g1");
+    group.sample_size(10);
+    for log_size in (8..=MAX_LOG_SIZE).step_by(2) {
+        let size = 1 << log_size;
+        let bases = Arc::new(bases[..size].to_vec());
+        let exponents = random_exponents(&mut rng, size);
+        group.throughput(Throughput::Elements(size as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(size), &size, |b, _| {
+            b.iter(|| multiexp(bases.clone(), None, None, exponents.clone()).unwrap())
+        });
+    }
+    group.finish();
+
+    // the bucket pass costs 2^c additions per window, which past the best
+    // window outgrows the additions saved on the bases; `MAX_WINDOW_SIZE`
+    // is capped from this group
+    let mut group = c.benchmark_group("multiexp_g1_window");
+    group.sample_size(10);
+    let exponents = random_exponents(&mut rng, 1 << MAX_LOG_SIZE);
+    for window_size in (10..=MAX_WINDOW_SIZE).step_by(2) {
+        let config = MultiexpConfig::new().with_window_size(window_size);
+        group.bench_with_input(
+            BenchmarkId::from_parameter(window_size),
+            &window_size,
+            |b, _| {
+                b.iter(|| {
+                    multiexp_with_config(bases.clone(), None, None, exponents.clone(), &config)
+                        .unwrap()
+                })
+            },
+        );
+    }
+    group.finish();
+
+    let step = G2Projective::random(&mut rng);
+    let points = (0..1 << MAX_LOG_SIZE_G2)
+        .scan(G2Projective::random(&mut rng), |acc, _| {
+            *acc = acc.add(&step);
+            Some(acc.clone())
+        })
+        .collect::<Vec<_>>();
+    let bases = prepare_bases_g2(&points);
+
+    let mut group = c.benchmark_group("multiexp_g2");
+    group.sample_size(10);
+    for log_size in (8..=MAX_LOG_SIZE_G2).step_by(2) {
+        let size = 1 << log_size;
+        let bases = Arc::new(bases[..size].to_vec());
+        let exponents = random_exponents(&mut rng, size);
+        group.throughput(Throughput::Elements(size as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(size), &size, |b, _| {
+            b.iter(|| multiexp_g2(bases.clone(), None, None, exponents.clone()).unwrap())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_multiexp);
+criterion_main!(benches);
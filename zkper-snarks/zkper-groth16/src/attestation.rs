@@ -0,0 +1,381 @@
+//! Attestation transcripts for the trusted setup.
+//!
+//! [`generate_proving_parameters_with_attestation`] records, next to the
+//! parameters, SHA-256 digests of the synthesized constraint system, of the
+//! verification key and of every query vector, plus a commitment to the setup
+//! RNG. The digests are chained into a single transcript digest.
+//!
+//! Anyone holding the circuit and the published parameters can recompute the
+//! digests with [`verify_setup_attestation`] and so check that the parameters
+//! were generated for that circuit and not modified afterwards. The toxic
+//! waste itself is never part of the transcript.
+//!
+//! The file format is line based text:
+//!
+//! ```text
+//! ZKPG16AT v1
+//! constraints     <decimal>
+//! public_inputs   <decimal>
+//! private_inputs  <decimal>
+//! circuit         <hex digest>
+//! vk              <hex digest>
+//! h_query         <hex digest>
+//! l_query         <hex digest>
+//! a_query         <hex digest>
+//! b_g1_query      <hex digest>
+//! b_g2_query      <hex digest>
+//! rng_commitment  <hex digest>
+//! transcript      <hex digest>
+//! ```
+
+use std::io::{BufRead, BufReader, Read, Write};
+
+use rand::RngCore;
+use rug::{integer::Order, Integer};
+use zkper_curves::curves::bls12_381::curves::{g1_affine::G1Affine, g2_affine::G2Affine};
+
+use crate::{
+    circuit::Circuit,
+    constraints::ConstraintSystem,
+    error::Groth16Error,
+    generator::{generate_from_constraint_system, synthesize_constraint_system},
+    hash::{Sha256Hash, TranscriptHash},
+    models::{proving_parameters::ProvingParameters, verification_key::VerificationKey},
+    params_source::ParamsSource,
+};
+
+/// First line of an attestation file.
+pub const ATTESTATION_HEADER: &str = "ZKPG16AT v1";
+
+pub use crate::hash::Digest32;
+
+/// What a trusted setup attests to, see the module documentation.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SetupAttestation {
+    pub num_constraints: usize,
+    pub num_public_inputs: usize,
+    pub num_private_inputs: usize,
+
+    /// Digest of the constraint system the parameters were generated for.
+    pub circuit: Digest32,
+    /// Digest of the verification key.
+    pub vk: Digest32,
+    pub h_query: Digest32,
+    pub l_query: Digest32,
+    pub a_query: Digest32,
+    pub b_g1_query: Digest32,
+    pub b_g2_query: Digest32,
+
+    /// Hash of 32 bytes drawn from the setup RNG before anything else. The
+    /// ceremony can reveal them later to show how the RNG was seeded; the
+    /// verifier only checks that it is bound into the transcript.
+    pub rng_commitment: Digest32,
+
+    /// All of the above chained together.
+    pub transcript: Digest32,
+}
+
+/// Generates parameters like `generate_proving_parameters` and the
+/// attestation transcript describing them.
+pub fn generate_proving_parameters_with_attestation<C: Circuit, R: RngCore>(
+    circuit: C,
+    rng: &mut R,
+) -> Result<(ProvingParameters, SetupAttestation), Groth16Error> {
+    let mut seed = [0u8; 32];
+    rng.fill_bytes(&mut seed);
+    let rng_commitment = tagged_hash(b"rng", |hasher| hasher.update(seed));
+
+    let cs = synthesize_constraint_system(&circuit)?;
+    let params = generate_from_constraint_system(&cs, rng)?;
+    let attestation = SetupAttestation::new(&cs, &params, rng_commitment)?;
+
+    Ok((params, attestation))
+}
+
+/// Checks that `params` were generated for `circuit` as described by
+/// `attestation`, naming the first phase whose digest does not match.
+pub fn verify_setup_attestation<C: Circuit, P: ParamsSource + ?Sized>(
+    circuit: &C,
+    params: &P,
+    attestation: &SetupAttestation,
+) -> Result<(), Groth16Error> {
+    let cs = synthesize_constraint_system(circuit)?;
+    let expected = SetupAttestation::new(&cs, params, attestation.rng_commitment)?;
+
+    let phases = [
+        (
+            "constraint count",
+            expected.num_constraints == attestation.num_constraints,
+        ),
+        (
+            "public input count",
+            expected.num_public_inputs == attestation.num_public_inputs,
+        ),
+        (
+            "private input count",
+            expected.num_private_inputs == attestation.num_private_inputs,
+        ),
+        ("circuit", expected.circuit == attestation.circuit),
+        ("verification key", expected.vk == attestation.vk),
+        ("h query", expected.h_query == attestation.h_query),
+        ("l query", expected.l_query == attestation.l_query),
+        ("a query", expected.a_query == attestation.a_query),
+        ("b g1 query", expected.b_g1_query == attestation.b_g1_query),
+        ("b g2 query", expected.b_g2_query == attestation.b_g2_query),
+        ("transcript", expected.transcript == attestation.transcript),
+    ];
+    for (phase, matches) in phases {
+        if !matches {
+            return Err(Groth16Error::AttestationMismatch(phase));
+        }
+    }
+
+    Ok(())
+}
+
+impl SetupAttestation {
+    fn new<P: ParamsSource + ?Sized>(
+        cs: &ConstraintSystem,
+        params: &P,
+        rng_commitment: Digest32,
+    ) -> Result<Self, Groth16Error> {
+        let mut attestation = Self {
+            num_constraints: cs.num_constraints,
+            num_public_inputs: cs.num_public_inputs,
+            num_private_inputs: cs.num_private_inputs,
+            circuit: circuit_digest(cs),
+            vk: vk_digest(params.vk()),
+            h_query: g1_digest(
+                b"h_query",
+                &params.h_query().map_err(Groth16Error::parameters)?,
+            ),
+            l_query: g1_digest(
+                b"l_query",
+                &params.l_query().map_err(Groth16Error::parameters)?,
+            ),
+            a_query: g1_digest(
+                b"a_query",
+                &params.a_query().map_err(Groth16Error::parameters)?,
+            ),
+            b_g1_query: g1_digest(
+                b"b_g1_query",
+                &params.b_g1_query().map_err(Groth16Error::parameters)?,
+            ),
+            b_g2_query: g2_digest(
+                b"b_g2_query",
+                &params.b_g2_query().map_err(Groth16Error::parameters)?,
+            ),
+            rng_commitment,
+            transcript: [0; 32],
+        };
+        attestation.transcript = attestation.chain();
+
+        Ok(attestation)
+    }
+
+    /// H(counts || circuit || vk || queries || rng_commitment)
+    fn chain(&self) -> Digest32 {
+        tagged_hash(b"transcript", |hasher| {
+            for count in [
+                self.num_constraints,
+                self.num_public_inputs,
+                self.num_private_inputs,
+            ] {
+                hasher.update((count as u64).to_be_bytes());
+            }
+            for (name, digest) in self.digests() {
+                if name != "transcript" {
+                    hasher.update(digest);
+                }
+            }
+        })
+    }
+
+    fn digests(&self) -> [(&'static str, &Digest32); 9] {
+        [
+            ("circuit", &self.circuit),
+            ("vk", &self.vk),
+            ("h_query", &self.h_query),
+            ("l_query", &self.l_query),
+            ("a_query", &self.a_query),
+            ("b_g1_query", &self.b_g1_query),
+            ("b_g2_query", &self.b_g2_query),
+            ("rng_commitment", &self.rng_commitment),
+            ("transcript", &self.transcript),
+        ]
+    }
+
+    /// Write the attestation in the text format described in the module docs.
+    pub fn write<W: Write>(&self, mut writer: W) -> Result<(), Groth16Error> {
+        writeln!(writer, "{}", ATTESTATION_HEADER)?;
+        writeln!(writer, "constraints {}", self.num_constraints)?;
+        writeln!(writer, "public_inputs {}", self.num_public_inputs)?;
+        writeln!(writer, "private_inputs {}", self.num_private_inputs)?;
+        for (name, digest) in self.digests() {
+            writeln!(writer, "{} {}", name, to_hex(digest))?;
+        }
+
+        Ok(())
+    }
+
+    /// Read an attestation written by `write`. The transcript digest is checked
+    /// against the other fields.
+    pub fn read<R: Read>(reader: R) -> Result<Self, Groth16Error> {
+        let mut lines = BufReader::new(reader).lines();
+
+        let header = lines
+            .next()
+            .ok_or_else(|| malformed("Empty attestation"))??;
+        if header.trim() != ATTESTATION_HEADER {
+            return Err(malformed("Bad attestation header"));
+        }
+
+        let mut fields = Vec::new();
+        for line in lines {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let (key, value) = line
+                .split_once(' ')
+                .ok_or_else(|| malformed(format!("Attestation line: {}", line)))?;
+            fields.push((key.to_string(), value.trim().to_string()));
+        }
+
+        let field = |name: &str| {
+            fields
+                .iter()
+                .find(|(key, _)| key == name)
+                .map(|(_, value)| value.as_str())
+                .ok_or_else(|| malformed(format!("Missing attestation field: {}", name)))
+        };
+        let count = |name: &str| -> Result<usize, Groth16Error> {
+            field(name)?
+                .parse()
+                .map_err(|_| malformed(format!("Attestation count: {}", name)))
+        };
+        let digest = |name: &str| from_hex(field(name)?);
+
+        let attestation = Self {
+            num_constraints: count("constraints")?,
+            num_public_inputs: count("public_inputs")?,
+            num_private_inputs: count("private_inputs")?,
+            circuit: digest("circuit")?,
+            vk: digest("vk")?,
+            h_query: digest("h_query")?,
+            l_query: digest("l_query")?,
+            a_query: digest("a_query")?,
+            b_g1_query: digest("b_g1_query")?,
+            b_g2_query: digest("b_g2_query")?,
+            rng_commitment: digest("rng_commitment")?,
+            transcript: digest("transcript")?,
+        };
+        if attestation.chain() != attestation.transcript {
+            return Err(malformed("Attestation transcript digest mismatch"));
+        }
+
+        Ok(attestation)
+    }
+}
+
+/// SHA-256 over a domain separation tag and whatever `update` feeds in.
+fn tagged_hash(tag: &[u8], update: impl FnOnce(&mut Sha256Hash)) -> Digest32 {
+    let mut hasher = Sha256Hash::default();
+    hasher.update(b"ZKPER-GROTH16-SETUP-V1/");
+    hasher.update(tag);
+    update(&mut hasher);
+    hasher.finalize()
+}
+
+/// Digest of the A, B and C matrices, column by column.
+fn circuit_digest(cs: &ConstraintSystem) -> Digest32 {
+    tagged_hash(b"circuit", |hasher| {
+        for count in [
+            cs.num_constraints,
+            cs.num_public_inputs,
+            cs.num_private_inputs,
+        ] {
+            hasher.update((count as u64).to_be_bytes());
+        }
+
+        for matrix in [
+            &cs.at_public,
+            &cs.bt_public,
+            &cs.ct_public,
+            &cs.at_private,
+            &cs.bt_private,
+            &cs.ct_private,
+        ] {
+            hasher.update((matrix.len() as u64).to_be_bytes());
+            for column in matrix {
+                hasher.update((column.len() as u64).to_be_bytes());
+                for (coeff, constraint) in column {
+                    hasher.update(scalar_bytes(coeff));
+                    hasher.update((*constraint as u64).to_be_bytes());
+                }
+            }
+        }
+    })
+}
+
+fn vk_digest(vk: &VerificationKey) -> Digest32 {
+    tagged_hash(b"vk", |hasher| {
+        hasher.update(vk.alpha_g1.to_uncompressed());
+        hasher.update(vk.beta_g1.to_uncompressed());
+        hasher.update(vk.beta_g2.to_uncompressed());
+        hasher.update(vk.gamma_g2.to_uncompressed());
+        hasher.update(vk.delta_g1.to_uncompressed());
+        hasher.update(vk.delta_g2.to_uncompressed());
+        hasher.update((vk.ic.len() as u64).to_be_bytes());
+        for point in &vk.ic {
+            hasher.update(point.to_uncompressed());
+        }
+    })
+}
+
+fn g1_digest(tag: &[u8], points: &[G1Affine]) -> Digest32 {
+    tagged_hash(tag, |hasher| {
+        hasher.update((points.len() as u64).to_be_bytes());
+        for point in points {
+            hasher.update(point.to_uncompressed());
+        }
+    })
+}
+
+fn g2_digest(tag: &[u8], points: &[G2Affine]) -> Digest32 {
+    tagged_hash(tag, |hasher| {
+        hasher.update((points.len() as u64).to_be_bytes());
+        for point in points {
+            hasher.update(point.to_uncompressed());
+        }
+    })
+}
+
+/// A scalar as 32 big-endian bytes.
+pub(crate) fn scalar_bytes(value: &Integer) -> [u8; 32] {
+    let digits = value.to_digits::<u8>(Order::Msf);
+    let mut bytes = [0u8; 32];
+    bytes[32 - digits.len()..].copy_from_slice(&digits);
+    bytes
+}
+
+pub(crate) fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn from_hex(hex: &str) -> Result<Digest32, Groth16Error> {
+    if hex.len() != 64 || !hex.is_ascii() {
+        return Err(malformed(format!("Bad digest length: {}", hex)));
+    }
+
+    let mut digest = [0u8; 32];
+    for (i, byte) in digest.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&hex[2 * i..2 * i + 2], 16)
+            .map_err(|_| malformed(format!("Bad digest: {}", hex)))?;
+    }
+    Ok(digest)
+}
+
+fn malformed(reason: impl Into<String>) -> Groth16Error {
+    Groth16Error::MalformedFile(reason.into())
+}
@@ -0,0 +1,200 @@
+//! Several statements of the same circuit in one proof.
+//!
+//! A [`BatchedCircuit`] synthesizes N instances of a sub-circuit one after the
+//! other into a single constraint system. The instances share the constant
+//! one and everything the sub-circuit bakes into its coefficients, and the
+//! whole batch costs one proof and one pairing check instead of N.
+//!
+//! Every instance has to allocate the same number k of public inputs. The
+//! public inputs of the batch are then the inputs of each instance in order,
+//! N * k values in total: [`merge_public_inputs`] builds that vector and
+//! [`split_public_inputs`] takes it apart again.
+
+use zkper_curves::curves::bls12_381::Scalar;
+
+use crate::{
+    circuit::Circuit,
+    constraints::{linear_combination::LinearCombination, ConstraintSystemTrait, Mode, Variable},
+    error::Groth16Error,
+    models::{proof::Proof, verify::PreparedVerifyingKey},
+    verifier::verify_proof,
+};
+
+/// N instances of `C` synthesized as one circuit, see the module
+/// documentation.
+#[derive(Clone, Debug)]
+pub struct BatchedCircuit<C> {
+    instances: Vec<C>,
+}
+
+impl<C> BatchedCircuit<C> {
+    pub fn new(instances: Vec<C>) -> Self {
+        Self { instances }
+    }
+
+    pub fn instances(&self) -> &[C] {
+        &self.instances
+    }
+
+    pub fn len(&self) -> usize {
+        self.instances.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.instances.is_empty()
+    }
+}
+
+impl<C: Circuit> Circuit for BatchedCircuit<C> {
+    fn synthesize<CS: ConstraintSystemTrait>(&self, cs: &mut CS) -> Result<(), Groth16Error> {
+        if self.instances.is_empty() {
+            return Err(Groth16Error::EmptyBatch);
+        }
+
+        let mut inputs_per_instance = None;
+        for (index, instance) in self.instances.iter().enumerate() {
+            let mut counter = PublicCounter {
+                inner: cs,
+                num_public_inputs: 0,
+            };
+            instance.synthesize(&mut counter)?;
+
+            let count = counter.num_public_inputs;
+            match inputs_per_instance {
+                None => inputs_per_instance = Some(count),
+                Some(expected) if expected != count => {
+                    return Err(Groth16Error::BatchInputMismatch {
+                        instance: index,
+                        expected,
+                        actual: count,
+                    });
+                }
+                Some(_) => {}
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Forwards to `inner`, counting the public inputs an instance allocates.
+struct PublicCounter<'a, CS> {
+    inner: &'a mut CS,
+    num_public_inputs: usize,
+}
+
+impl<CS: ConstraintSystemTrait> ConstraintSystemTrait for PublicCounter<'_, CS> {
+    fn mode(&self) -> Mode {
+        self.inner.mode()
+    }
+
+    fn alloc_private<F>(&mut self, value: F) -> Result<Variable, Groth16Error>
+    where
+        F: FnOnce() -> anyhow::Result<Scalar>,
+    {
+        self.inner.alloc_private(value)
+    }
+
+    fn alloc_public<F>(&mut self, value: F) -> Result<Variable, Groth16Error>
+    where
+        F: FnOnce() -> anyhow::Result<Scalar>,
+    {
+        self.num_public_inputs += 1;
+        self.inner.alloc_public(value)
+    }
+
+    fn enforce(&mut self, a: LinearCombination, b: LinearCombination, c: LinearCombination) {
+        self.inner.enforce(a, b, c);
+    }
+}
+
+/// Concatenates the public inputs of every instance into the public inputs
+/// of the batch. Every instance has to have the same number of inputs.
+pub fn merge_public_inputs<I: AsRef<[Scalar]>>(
+    instances: &[I],
+) -> Result<Vec<Scalar>, Groth16Error> {
+    let Some(first) = instances.first() else {
+        return Err(Groth16Error::EmptyBatch);
+    };
+    let k = first.as_ref().len();
+
+    let mut merged = Vec::with_capacity(instances.len() * k);
+    for (index, inputs) in instances.iter().enumerate() {
+        let inputs = inputs.as_ref();
+        if inputs.len() != k {
+            return Err(Groth16Error::BatchInputMismatch {
+                instance: index,
+                expected: k,
+                actual: inputs.len(),
+            });
+        }
+        merged.extend_from_slice(inputs);
+    }
+
+    Ok(merged)
+}
+
+/// Splits the public inputs of a batch of `instances` statements back into
+/// the inputs of each instance.
+pub fn split_public_inputs(
+    inputs: &[Scalar],
+    instances: usize,
+) -> Result<Vec<&[Scalar]>, Groth16Error> {
+    if instances == 0 || !inputs.len().is_multiple_of(instances) {
+        return Err(Groth16Error::BatchSplit {
+            inputs: inputs.len(),
+            instances,
+        });
+    }
+
+    let k = inputs.len() / instances;
+    if k == 0 {
+        return Ok(vec![&[]; instances]);
+    }
+    Ok(inputs.chunks(k).collect())
+}
+
+/// Verifies a proof of a [`BatchedCircuit`] against the public inputs of
+/// each of its instances, in the order they were batched.
+pub fn verify_batched_proof<I: AsRef<[Scalar]>>(
+    pvk: &PreparedVerifyingKey,
+    proof: &Proof,
+    instances: &[I],
+) -> Result<bool, Groth16Error> {
+    verify_proof(pvk, proof, &merge_public_inputs(instances)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_merge_public_inputs() {
+        let instances = (0..3u64)
+            .map(|i| {
+                (0..2u64)
+                    .map(|j| Scalar::from(rug::Integer::from(2 * i + j)))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        let merged = merge_public_inputs(&instances).unwrap();
+        assert_eq!(merged.len(), 6);
+        assert_eq!(merged[3], instances[1][1]);
+
+        let split = split_public_inputs(&merged, 3).unwrap();
+        assert_eq!(split, instances);
+
+        assert!(split_public_inputs(&merged, 4).is_err());
+        assert!(split_public_inputs(&merged, 0).is_err());
+        assert_eq!(
+            split_public_inputs(&[], 2).unwrap(),
+            vec![&[] as &[Scalar]; 2]
+        );
+
+        let mut ragged = instances.clone();
+        ragged[2].pop();
+        assert!(merge_public_inputs(&ragged).is_err());
+        assert!(merge_public_inputs::<Vec<Scalar>>(&[]).is_err());
+    }
+}
@@ -0,0 +1,264 @@
+//! A higher level way to write circuits.
+//!
+//! [`CircuitBuilder`] allocates the intermediate variables and constraints of
+//! common gadgets (products, sums, equality, booleans, selection) itself, so a
+//! circuit reads as the computation it checks instead of raw linear
+//! combinations. Values are carried next to the variables as
+//! `Option<Value<F>>`: `None` while generating parameters, `Some` while
+//! proving.
+//!
+//! A [`BuilderCircuit`] is written once against the builder and implements
+//! [`Circuit`] for both the setup and the prover. Written over any
+//! [`CircuitField`], the same code also runs in a
+//! [`MockSystem`](crate::mock::MockSystem) over a small field for unit tests:
+//!
+//! ```ignore
+//! impl<F: CircuitField> BuilderCircuit<F> for Cube<F> {
+//!     fn build<B: Backend<Field = F>>(&self, cs: &mut CircuitBuilder<B>) -> anyhow::Result<()> {
+//!         let x = cs.alloc(self.x.clone())?;
+//!         let x2 = cs.square(&x)?;
+//!         let x3 = cs.mul(&x2, &x)?;
+//!         let out = cs.alloc_input(x3.value().cloned())?;
+//!         cs.enforce_equal(&x3, &out);
+//!         Ok(())
+//!     }
+//! }
+//! ```
+
+use zkper_curves::curves::bls12_381::Scalar;
+
+use crate::{
+    circuit::Circuit,
+    constraints::{
+        linear_combination::LinearCombination, ConstraintSystem, ConstraintSystemTrait, Variable,
+    },
+    error::Groth16Error,
+    field::{CircuitField, Fr, Value},
+};
+
+/// Where a [`CircuitBuilder`] puts its variables and constraints.
+pub trait Backend {
+    /// The field the values of the variables live in.
+    type Field: CircuitField;
+
+    /// Allocates a private variable. `value` is only read while proving.
+    fn alloc_private(&mut self, value: Option<&Value<Self::Field>>) -> anyhow::Result<Variable>;
+
+    /// Allocates a public input. `value` is only read while proving.
+    fn alloc_public(&mut self, value: Option<&Value<Self::Field>>) -> anyhow::Result<Variable>;
+
+    /// Adds the constraint a * b = c.
+    fn enforce(&mut self, a: LinearCombination, b: LinearCombination, c: LinearCombination);
+}
+
+impl<CS: ConstraintSystemTrait> Backend for CS {
+    type Field = Fr;
+
+    fn alloc_private(&mut self, value: Option<&Scalar>) -> anyhow::Result<Variable> {
+        Ok(ConstraintSystemTrait::alloc_private(self, || {
+            value
+                .cloned()
+                .ok_or_else(|| Groth16Error::MissingAssignment("private variable").into())
+        })?)
+    }
+
+    fn alloc_public(&mut self, value: Option<&Scalar>) -> anyhow::Result<Variable> {
+        Ok(ConstraintSystemTrait::alloc_public(self, || {
+            value
+                .cloned()
+                .ok_or_else(|| Groth16Error::MissingAssignment("public input").into())
+        })?)
+    }
+
+    fn enforce(&mut self, a: LinearCombination, b: LinearCombination, c: LinearCombination) {
+        ConstraintSystemTrait::enforce(self, a, b, c);
+    }
+}
+
+/// A variable of the circuit together with its value, if known.
+#[derive(Clone, Debug)]
+pub struct AllocatedVariable<F: CircuitField = Fr> {
+    variable: Variable,
+    value: Option<Value<F>>,
+}
+
+impl<F: CircuitField> AllocatedVariable<F> {
+    pub fn variable(&self) -> Variable {
+        self.variable
+    }
+
+    pub fn value(&self) -> Option<&Value<F>> {
+        self.value.as_ref()
+    }
+
+    pub fn lc(&self) -> LinearCombination {
+        LinearCombination::new_variable(self.variable)
+    }
+}
+
+/// Gadgets over a [`Backend`], see the module documentation.
+pub struct CircuitBuilder<'a, B: Backend> {
+    backend: &'a mut B,
+}
+
+impl<'a, B: Backend> CircuitBuilder<'a, B> {
+    pub fn new(backend: &'a mut B) -> Self {
+        Self { backend }
+    }
+
+    /// The constant one.
+    pub fn one(&self) -> AllocatedVariable<B::Field> {
+        AllocatedVariable {
+            variable: ConstraintSystem::one(),
+            value: Some(B::Field::one()),
+        }
+    }
+
+    /// A new private variable.
+    pub fn alloc(
+        &mut self,
+        value: Option<Value<B::Field>>,
+    ) -> anyhow::Result<AllocatedVariable<B::Field>> {
+        let variable = self.backend.alloc_private(value.as_ref())?;
+        Ok(AllocatedVariable { variable, value })
+    }
+
+    /// A new public input.
+    pub fn alloc_input(
+        &mut self,
+        value: Option<Value<B::Field>>,
+    ) -> anyhow::Result<AllocatedVariable<B::Field>> {
+        let variable = self.backend.alloc_public(value.as_ref())?;
+        Ok(AllocatedVariable { variable, value })
+    }
+
+    /// Adds the raw constraint a * b = c.
+    pub fn enforce(&mut self, a: LinearCombination, b: LinearCombination, c: LinearCombination) {
+        self.backend.enforce(a, b, c);
+    }
+
+    /// a * b, as a new variable.
+    pub fn mul(
+        &mut self,
+        a: &AllocatedVariable<B::Field>,
+        b: &AllocatedVariable<B::Field>,
+    ) -> anyhow::Result<AllocatedVariable<B::Field>> {
+        let value = map2(a, b, B::Field::mul);
+        let c = self.alloc(value)?;
+        self.enforce(a.lc(), b.lc(), c.lc());
+        Ok(c)
+    }
+
+    /// a^2, as a new variable.
+    pub fn square(
+        &mut self,
+        a: &AllocatedVariable<B::Field>,
+    ) -> anyhow::Result<AllocatedVariable<B::Field>> {
+        self.mul(a, a)
+    }
+
+    /// a + b, as a new variable.
+    pub fn add(
+        &mut self,
+        a: &AllocatedVariable<B::Field>,
+        b: &AllocatedVariable<B::Field>,
+    ) -> anyhow::Result<AllocatedVariable<B::Field>> {
+        let value = map2(a, b, B::Field::add);
+        let c = self.alloc(value)?;
+        self.enforce(a.lc().add_variable(b.variable), self.one().lc(), c.lc());
+        Ok(c)
+    }
+
+    /// a - b, as a new variable.
+    pub fn sub(
+        &mut self,
+        a: &AllocatedVariable<B::Field>,
+        b: &AllocatedVariable<B::Field>,
+    ) -> anyhow::Result<AllocatedVariable<B::Field>> {
+        let value = map2(a, b, B::Field::sub);
+        let c = self.alloc(value)?;
+        self.enforce(a.lc().sub_variable(b.variable), self.one().lc(), c.lc());
+        Ok(c)
+    }
+
+    /// Constrains a = b.
+    pub fn enforce_equal(
+        &mut self,
+        a: &AllocatedVariable<B::Field>,
+        b: &AllocatedVariable<B::Field>,
+    ) {
+        self.enforce(
+            a.lc().sub_variable(b.variable),
+            self.one().lc(),
+            LinearCombination::zero(),
+        );
+    }
+
+    /// Constrains a to be 0 or 1.
+    pub fn enforce_boolean(&mut self, a: &AllocatedVariable<B::Field>) {
+        // a * (1 - a) = 0
+        self.enforce(
+            a.lc(),
+            self.one().lc().sub_variable(a.variable),
+            LinearCombination::zero(),
+        );
+    }
+
+    /// A new private variable constrained to be 0 or 1.
+    pub fn alloc_boolean(
+        &mut self,
+        value: Option<bool>,
+    ) -> anyhow::Result<AllocatedVariable<B::Field>> {
+        let b = self.alloc(value.map(|bit| B::Field::from_u64(bit as u64)))?;
+        self.enforce_boolean(&b);
+        Ok(b)
+    }
+
+    /// `a` if `condition` is 1 and `b` if it is 0, as a new variable.
+    /// `condition` must already be constrained to be boolean.
+    pub fn select(
+        &mut self,
+        condition: &AllocatedVariable<B::Field>,
+        a: &AllocatedVariable<B::Field>,
+        b: &AllocatedVariable<B::Field>,
+    ) -> anyhow::Result<AllocatedVariable<B::Field>> {
+        let value = match (condition.value(), a.value(), b.value()) {
+            (Some(condition), Some(a), Some(b)) => Some(if B::Field::is_zero(condition) {
+                b.clone()
+            } else {
+                a.clone()
+            }),
+            _ => None,
+        };
+        let c = self.alloc(value)?;
+
+        // condition * (a - b) = c - b
+        self.enforce(
+            condition.lc(),
+            a.lc().sub_variable(b.variable),
+            c.lc().sub_variable(b.variable),
+        );
+        Ok(c)
+    }
+}
+
+fn map2<F: CircuitField>(
+    a: &AllocatedVariable<F>,
+    b: &AllocatedVariable<F>,
+    f: impl FnOnce(&Value<F>, &Value<F>) -> Value<F>,
+) -> Option<Value<F>> {
+    Some(f(a.value()?, b.value()?))
+}
+
+/// A circuit written once against [`CircuitBuilder`]; over [`Fr`] it
+/// implements [`Circuit`] for both parameter generation and proving.
+pub trait BuilderCircuit<F: CircuitField = Fr> {
+    fn build<B: Backend<Field = F>>(&self, cs: &mut CircuitBuilder<B>) -> anyhow::Result<()>;
+}
+
+impl<T: BuilderCircuit> Circuit for T {
+    fn synthesize<CS: ConstraintSystemTrait>(&self, cs: &mut CS) -> Result<(), Groth16Error> {
+        self.build(&mut CircuitBuilder::new(cs))
+            .map_err(Groth16Error::synthesis)
+    }
+}
@@ -0,0 +1,526 @@
+//! The circom `.r1cs` and `.wtns` binary formats.
+//!
+//! Exporting lets a circuit written for this crate be cross-checked with
+//! snarkjs; importing lets a circuit compiled by circom (with
+//! `--prime bls12381`) be proven here through [`R1csCircuit`].
+//!
+//! ```text
+//! .r1cs                                   .wtns
+//! magic      "r1cs"                       magic      "wtns"
+//! version    u32 = 1                      version    u32 = 2
+//! sections   u32                          sections   u32 = 2
+//! per section: type u32, size u64         1 header:  n8 u32, prime, count u32
+//! 1 header:  n8 u32, prime, wires u32,    2 values:  count field elements
+//!            public outputs u32,
+//!            public inputs u32,
+//!            private inputs u32,
+//!            labels u64, constraints u32
+//! 2 constraints: A, B, C, each a u32 count of (wire u32, coeff) terms
+//! 3 wire to label: u64 per wire
+//! ```
+//!
+//! Integers are little-endian and field elements take n8 = 32 bytes. Wire 0
+//! is the constant one, followed by the public outputs, the public inputs and
+//! then every private wire. This crate has no separate public outputs, so it
+//! exports its public inputs as inputs and all private variables as private
+//! inputs.
+
+use std::io::Write;
+
+use rug::{integer::Order, Integer};
+use zkper_curves::curves::bls12_381::{Scalar, BLS12_381_SCALAR};
+
+use crate::{
+    circuit::Circuit,
+    constraints::{
+        linear_combination::LinearCombination, ConstraintSystem, ConstraintSystemTrait, Mode,
+        Variable,
+    },
+    error::Groth16Error,
+    prover::ProvingSystem,
+};
+
+/// Magic bytes at the start of a `.r1cs` file.
+pub const R1CS_MAGIC: &[u8; 4] = b"r1cs";
+
+/// Magic bytes at the start of a `.wtns` file.
+pub const WTNS_MAGIC: &[u8; 4] = b"wtns";
+
+/// Bytes per field element.
+const FIELD_SIZE: usize = 32;
+
+const R1CS_VERSION: u32 = 1;
+const WTNS_VERSION: u32 = 2;
+
+const HEADER_SECTION: u32 = 1;
+const CONSTRAINT_SECTION: u32 = 2;
+const WIRE_TO_LABEL_SECTION: u32 = 3;
+const WITNESS_SECTION: u32 = 2;
+
+/// One side of a constraint: (wire, coefficient) terms.
+pub type Terms = Vec<(u32, Integer)>;
+
+/// The contents of a `.r1cs` file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct R1cs {
+    pub num_wires: u32,
+    pub num_pub_out: u32,
+    pub num_pub_in: u32,
+    pub num_prv_in: u32,
+    pub num_labels: u64,
+    /// A, B and C of every constraint A * B = C.
+    pub constraints: Vec<(Terms, Terms, Terms)>,
+    pub wire_to_label: Vec<u64>,
+}
+
+impl R1cs {
+    /// Synthesizes `circuit` for parameter generation and exports the result.
+    pub fn from_circuit<C: Circuit>(circuit: &C) -> Result<Self, Groth16Error> {
+        let mut cs = ConstraintSystem::new();
+        circuit.synthesize(&mut cs)?;
+        Ok(Self::from_constraint_system(&cs))
+    }
+
+    pub fn from_constraint_system(cs: &ConstraintSystem) -> Self {
+        let num_public = cs.num_public_inputs;
+        let num_wires = num_public + cs.num_private_inputs;
+
+        let mut constraints: Vec<[Terms; 3]> = vec![Default::default(); cs.num_constraints];
+        let sides = [
+            (&cs.at_public, &cs.at_private),
+            (&cs.bt_public, &cs.bt_private),
+            (&cs.ct_public, &cs.ct_private),
+        ];
+        for (side, (public, private)) in sides.into_iter().enumerate() {
+            let wires = public
+                .iter()
+                .enumerate()
+                .chain(private.iter().enumerate().map(|(i, t)| (num_public + i, t)));
+            for (wire, terms) in wires {
+                for (coeff, constraint) in terms {
+                    constraints[*constraint][side]
+                        .push((wire as u32, BLS12_381_SCALAR.reduce(coeff)));
+                }
+            }
+        }
+
+        R1cs {
+            num_wires: num_wires as u32,
+            num_pub_out: 0,
+            num_pub_in: (num_public - 1) as u32,
+            num_prv_in: cs.num_private_inputs as u32,
+            num_labels: num_wires as u64,
+            constraints: constraints.into_iter().map(|[a, b, c]| (a, b, c)).collect(),
+            wire_to_label: (0..num_wires as u64).collect(),
+        }
+    }
+
+    /// Public wires after the constant one: outputs, then inputs.
+    pub fn num_public(&self) -> usize {
+        self.num_pub_out as usize + self.num_pub_in as usize
+    }
+
+    pub fn write<W: Write>(&self, mut writer: W) -> Result<(), Groth16Error> {
+        let mut header = vec![];
+        header.extend_from_slice(&(FIELD_SIZE as u32).to_le_bytes());
+        header.extend_from_slice(&field_bytes(BLS12_381_SCALAR.modulus_ref()));
+        header.extend_from_slice(&self.num_wires.to_le_bytes());
+        header.extend_from_slice(&self.num_pub_out.to_le_bytes());
+        header.extend_from_slice(&self.num_pub_in.to_le_bytes());
+        header.extend_from_slice(&self.num_prv_in.to_le_bytes());
+        header.extend_from_slice(&self.num_labels.to_le_bytes());
+        header.extend_from_slice(&(self.constraints.len() as u32).to_le_bytes());
+
+        let mut constraints = vec![];
+        for (a, b, c) in &self.constraints {
+            for terms in [a, b, c] {
+                constraints.extend_from_slice(&(terms.len() as u32).to_le_bytes());
+                for (wire, coeff) in terms {
+                    constraints.extend_from_slice(&wire.to_le_bytes());
+                    constraints.extend_from_slice(&field_bytes(coeff));
+                }
+            }
+        }
+
+        let labels = self
+            .wire_to_label
+            .iter()
+            .flat_map(|label| label.to_le_bytes())
+            .collect::<Vec<_>>();
+
+        write_file(
+            &mut writer,
+            R1CS_MAGIC,
+            R1CS_VERSION,
+            &[
+                (HEADER_SECTION, &header),
+                (CONSTRAINT_SECTION, &constraints),
+                (WIRE_TO_LABEL_SECTION, &labels),
+            ],
+        )
+    }
+
+    /// Parses a `.r1cs` file. It has to be over the BLS12-381 scalar field.
+    pub fn read(bytes: &[u8]) -> Result<Self, Groth16Error> {
+        let sections = read_file(bytes, R1CS_MAGIC, R1CS_VERSION)?;
+
+        let mut header = Reader::new(section(&sections, HEADER_SECTION)?);
+        header.read_prime()?;
+        let num_wires = header.read_u32()?;
+        let num_pub_out = header.read_u32()?;
+        let num_pub_in = header.read_u32()?;
+        let num_prv_in = header.read_u32()?;
+        let num_labels = header.read_u64()?;
+        let num_constraints = header.read_u32()?;
+        if num_pub_out as u64 + num_pub_in as u64 >= num_wires as u64 {
+            return Err(malformed(
+                "r1cs declares more public wires than it has wires",
+            ));
+        }
+
+        let mut reader = Reader::new(section(&sections, CONSTRAINT_SECTION)?);
+        let mut read_terms = || -> Result<Terms, Groth16Error> {
+            let len = reader.read_u32()?;
+            (0..len)
+                .map(|_| {
+                    let wire = reader.read_u32()?;
+                    if wire >= num_wires {
+                        return Err(malformed(format!(
+                            "r1cs constraint uses wire {} of {}",
+                            wire, num_wires
+                        )));
+                    }
+                    Ok((wire, reader.read_field()?))
+                })
+                .collect()
+        };
+        let constraints = (0..num_constraints)
+            .map(|_| Ok((read_terms()?, read_terms()?, read_terms()?)))
+            .collect::<Result<Vec<_>, Groth16Error>>()?;
+
+        let wire_to_label = match sections.iter().find(|(t, _)| *t == WIRE_TO_LABEL_SECTION) {
+            Some((_, bytes)) => {
+                let mut reader = Reader::new(bytes);
+                (0..num_wires)
+                    .map(|_| reader.read_u64())
+                    .collect::<Result<_, Groth16Error>>()?
+            }
+            None => (0..num_wires as u64).collect(),
+        };
+
+        Ok(R1cs {
+            num_wires,
+            num_pub_out,
+            num_pub_in,
+            num_prv_in,
+            num_labels,
+            constraints,
+            wire_to_label,
+        })
+    }
+
+    fn variable(&self, wire: u32) -> Variable {
+        let wire = wire as usize;
+        if wire <= self.num_public() {
+            Variable::Public(wire)
+        } else {
+            Variable::Private(wire - self.num_public() - 1)
+        }
+    }
+
+    fn lc(&self, terms: &Terms) -> LinearCombination {
+        LinearCombination(
+            terms
+                .iter()
+                .map(|(wire, coeff)| (self.variable(*wire), coeff.clone()))
+                .collect(),
+        )
+    }
+}
+
+/// The full assignment of `circuit` in wire order: the constant one, the
+/// public inputs, then the private variables.
+pub fn witness<C: Circuit>(circuit: &C) -> Result<Vec<Scalar>, Groth16Error> {
+    let mut prover = ProvingSystem::new();
+    circuit.synthesize(&mut prover)?;
+    Ok(prover.full_assignment())
+}
+
+pub fn write_witness<W: Write>(witness: &[Scalar], mut writer: W) -> Result<(), Groth16Error> {
+    let mut header = vec![];
+    header.extend_from_slice(&(FIELD_SIZE as u32).to_le_bytes());
+    header.extend_from_slice(&field_bytes(BLS12_381_SCALAR.modulus_ref()));
+    header.extend_from_slice(&(witness.len() as u32).to_le_bytes());
+
+    let values = witness
+        .iter()
+        .flat_map(|value| field_bytes(&BLS12_381_SCALAR.reduce(&value.0)))
+        .collect::<Vec<_>>();
+
+    write_file(
+        &mut writer,
+        WTNS_MAGIC,
+        WTNS_VERSION,
+        &[(HEADER_SECTION, &header), (WITNESS_SECTION, &values)],
+    )
+}
+
+/// Parses a `.wtns` file. It has to be over the BLS12-381 scalar field.
+pub fn read_witness(bytes: &[u8]) -> Result<Vec<Scalar>, Groth16Error> {
+    let sections = read_file(bytes, WTNS_MAGIC, WTNS_VERSION)?;
+
+    let mut header = Reader::new(section(&sections, HEADER_SECTION)?);
+    header.read_prime()?;
+    let len = header.read_u32()?;
+
+    let mut reader = Reader::new(section(&sections, WITNESS_SECTION)?);
+    (0..len)
+        .map(|_| Ok(Scalar::from(reader.read_field()?)))
+        .collect()
+}
+
+/// A circuit given as an [`R1cs`], with a witness in wire order to prove it.
+///
+/// The public inputs of a proof are the public wires after the constant one,
+/// `witness[1..=r1cs.num_public()]`.
+#[derive(Clone, Debug)]
+pub struct R1csCircuit {
+    pub r1cs: R1cs,
+    pub witness: Option<Vec<Scalar>>,
+}
+
+impl R1csCircuit {
+    /// The witness, checked to fit the r1cs.
+    fn checked_witness(&self) -> anyhow::Result<&[Scalar]> {
+        let witness = self
+            .witness
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("Missing witness of an r1cs circuit"))?;
+        if witness.len() != self.r1cs.num_wires as usize {
+            anyhow::bail!(
+                "Witness has {} values but the r1cs has {} wires",
+                witness.len(),
+                self.r1cs.num_wires
+            );
+        }
+        if witness[0].0 != 1 {
+            anyhow::bail!("Witness does not start with the constant one");
+        }
+        Ok(witness)
+    }
+}
+
+impl Circuit for R1csCircuit {
+    fn synthesize<CS: ConstraintSystemTrait>(&self, cs: &mut CS) -> Result<(), Groth16Error> {
+        // the value closures only run while proving
+        let witness = match cs.mode() {
+            Mode::Setup => &[],
+            Mode::Prove => self.checked_witness().map_err(Groth16Error::synthesis)?,
+        };
+
+        let value = |wire: usize| move || Ok(witness[wire].clone());
+
+        let num_public = self.r1cs.num_public();
+        for wire in 1..=num_public {
+            cs.alloc_public(value(wire))?;
+        }
+        for wire in num_public + 1..self.r1cs.num_wires as usize {
+            cs.alloc_private(value(wire))?;
+        }
+        for (a, b, c) in &self.r1cs.constraints {
+            cs.enforce(self.r1cs.lc(a), self.r1cs.lc(b), self.r1cs.lc(c));
+        }
+        Ok(())
+    }
+}
+
+fn field_bytes(value: &Integer) -> [u8; FIELD_SIZE] {
+    let digits = value.to_digits::<u8>(Order::Lsf);
+    let mut bytes = [0u8; FIELD_SIZE];
+    bytes[..digits.len()].copy_from_slice(&digits);
+    bytes
+}
+
+fn write_file<W: Write>(
+    writer: &mut W,
+    magic: &[u8; 4],
+    version: u32,
+    sections: &[(u32, &[u8])],
+) -> Result<(), Groth16Error> {
+    writer.write_all(magic)?;
+    writer.write_all(&version.to_le_bytes())?;
+    writer.write_all(&(sections.len() as u32).to_le_bytes())?;
+    for (section_type, bytes) in sections {
+        writer.write_all(&section_type.to_le_bytes())?;
+        writer.write_all(&(bytes.len() as u64).to_le_bytes())?;
+        writer.write_all(bytes)?;
+    }
+    Ok(())
+}
+
+/// Splits a file into its (type, contents) sections, in file order.
+fn read_file<'a>(
+    bytes: &'a [u8],
+    magic: &[u8; 4],
+    version: u32,
+) -> Result<Vec<(u32, &'a [u8])>, Groth16Error> {
+    let mut reader = Reader::new(bytes);
+    if reader.take(4)? != magic {
+        return Err(malformed(format!(
+            "Not a {} file",
+            String::from_utf8_lossy(magic).to_uppercase()
+        )));
+    }
+    let found = reader.read_u32()?;
+    if found != version {
+        return Err(malformed(format!(
+            "Unsupported version {}, expected {}",
+            found, version
+        )));
+    }
+
+    let num_sections = reader.read_u32()?;
+    (0..num_sections)
+        .map(|_| {
+            let section_type = reader.read_u32()?;
+            let size = usize::try_from(reader.read_u64()?)
+                .map_err(|_| malformed("Section does not fit in memory"))?;
+            Ok((section_type, reader.take(size)?))
+        })
+        .collect()
+}
+
+fn section<'a>(sections: &[(u32, &'a [u8])], section_type: u32) -> Result<&'a [u8], Groth16Error> {
+    sections
+        .iter()
+        .find(|(t, _)| *t == section_type)
+        .map(|(_, bytes)| *bytes)
+        .ok_or_else(|| malformed(format!("Missing section {}", section_type)))
+}
+
+fn malformed(reason: impl Into<String>) -> Groth16Error {
+    Groth16Error::MalformedFile(reason.into())
+}
+
+/// Cursor over little-endian values.
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, pos: 0 }
+    }
+
+    fn take(&mut self, n: usize) -> Result<&'a [u8], Groth16Error> {
+        let end = self
+            .pos
+            .checked_add(n)
+            .filter(|end| *end <= self.bytes.len())
+            .ok_or_else(|| malformed("Unexpected end of file"))?;
+        let out = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(out)
+    }
+
+    fn read_u32(&mut self) -> Result<u32, Groth16Error> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn read_u64(&mut self) -> Result<u64, Groth16Error> {
+        Ok(u64::from_le_bytes(self.take(8)?.try_into().unwrap()))
+    }
+
+    fn read_field(&mut self) -> Result<Integer, Groth16Error> {
+        let value = Integer::from_digits(self.take(FIELD_SIZE)?, Order::Lsf);
+        if &value >= BLS12_381_SCALAR.modulus_ref() {
+            return Err(malformed("Field element is not reduced"));
+        }
+        Ok(value)
+    }
+
+    /// Reads the field size and prime of a header, which must be BLS12-381's
+    /// scalar field.
+    fn read_prime(&mut self) -> Result<(), Groth16Error> {
+        let field_size = self.read_u32()? as usize;
+        let prime = Integer::from_digits(self.take(field_size)?, Order::Lsf);
+        if field_size != FIELD_SIZE || &prime != BLS12_381_SCALAR.modulus_ref() {
+            return Err(malformed(format!(
+                "File is over the prime {}, not the BLS12-381 scalar field; compile with --prime bls12381",
+                prime
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_r1cs_round_trip() {
+        // x * x = y, with y public
+        let mut cs = ConstraintSystem::new();
+        let y = cs.new_public().unwrap();
+        let x = cs.new_private().unwrap();
+        cs.enforce_constraint(
+            LinearCombination::new_variable(x),
+            LinearCombination::new_variable(x),
+            LinearCombination::new_variable(y),
+        );
+
+        let r1cs = R1cs::from_constraint_system(&cs);
+        assert_eq!(r1cs.num_wires, 3);
+        assert_eq!(r1cs.num_public(), 1);
+        assert_eq!(
+            r1cs.constraints,
+            vec![(
+                vec![(2, Integer::from(1))],
+                vec![(2, Integer::from(1))],
+                vec![(1, Integer::from(1))]
+            )]
+        );
+
+        let mut bytes = vec![];
+        r1cs.write(&mut bytes).unwrap();
+        assert_eq!(&bytes[..4], R1CS_MAGIC);
+        assert_eq!(R1cs::read(&bytes).unwrap(), r1cs);
+
+        // importing reproduces the same system
+        let circuit = R1csCircuit {
+            r1cs: r1cs.clone(),
+            witness: None,
+        };
+        assert_eq!(R1cs::from_circuit(&circuit).unwrap(), r1cs);
+
+        assert!(matches!(
+            R1cs::read(&bytes[..bytes.len() - 1]),
+            Err(Groth16Error::MalformedFile(message)) if message == "Unexpected end of file"
+        ));
+        assert!(matches!(
+            read_witness(&bytes),
+            Err(Groth16Error::MalformedFile(_))
+        ));
+    }
+
+    #[test]
+    fn test_witness_round_trip() {
+        let witness = [1u32, 9, 3]
+            .map(|v| Scalar::from(Integer::from(v)))
+            .to_vec();
+        let mut bytes = vec![];
+        write_witness(&witness, &mut bytes).unwrap();
+        assert_eq!(read_witness(&bytes).unwrap(), witness);
+
+        // other fields are rejected
+        let bn254 = Integer::from_str_radix(
+            "30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000001",
+            16,
+        )
+        .unwrap();
+        bytes[28..60].copy_from_slice(&field_bytes(&bn254));
+        assert!(read_witness(&bytes).is_err());
+    }
+}
@@ -1,12 +1,10 @@
-use crate::{constraints::ConstraintSystem, prover::ProvingSystem};
+use crate::{constraints::ConstraintSystemTrait, error::Groth16Error};
 
 /// Computations are expressed in terms of arithmetic circuits, in particular
 /// rank-1 quadratic constraint systems. The `Circuit` trait represents a
 /// circuit that can be synthesized. The `synthesize` method is called during
-/// CRS generation and during proving.
+/// CRS generation and during proving, see `ConstraintSystemTrait::mode`.
 pub trait Circuit {
     /// Synthesize the circuit into a rank-1 quadratic constraint system
-    fn synthesize(&self, cs: &mut ConstraintSystem) -> anyhow::Result<()>;
-
-    fn synthesize_proof(&self, cs: &mut ProvingSystem) -> anyhow::Result<()>;
+    fn synthesize<CS: ConstraintSystemTrait>(&self, cs: &mut CS) -> Result<(), Groth16Error>;
 }
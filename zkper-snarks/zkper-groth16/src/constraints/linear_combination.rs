@@ -18,11 +18,13 @@ impl LinearCombination {
         LinearCombination(vec![(var, Integer::from(1))])
     }
 
+    #[allow(clippy::should_implement_trait)]
     pub fn add(mut self, coeff_var: (Variable, Integer)) -> Self {
         self.0.push(coeff_var);
         self
     }
 
+    #[allow(clippy::should_implement_trait)]
     pub fn sub(mut self, coeff_var: (Variable, Integer)) -> Self {
         self.0
             .push((coeff_var.0, BLS12_381_SCALAR.neg(coeff_var.1)));
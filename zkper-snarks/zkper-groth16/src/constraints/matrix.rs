@@ -0,0 +1,246 @@
+//! The A, B and C matrices of a [`ConstraintSystem`] as sparse triplets.
+//!
+//! Row i is constraint i and the columns follow the circom wire order: the
+//! public variables, the constant one first, then the private variables.
+//! Coefficients are reduced into [0, r). Entries are sorted by row, then by
+//! column, so two systems built from the same circuit export identically and
+//! can be diffed.
+//!
+//! ```text
+//! Matrix Market                            JSON
+//! %%MatrixMarket matrix coordinate         { "num_constraints": 2,
+//!     integer general                        "num_public": 2,
+//! rows columns entries                       "num_private": 1,
+//! row column value    (1-based)              "a": { "rows": 2, "columns": 3,
+//! ...                                               "entries": [
+//!                                                   { "row": 0, "column": 2,
+//!                                                     "value": "1" }, ... ] },
+//!                                            "b": ..., "c": ... }
+//! ```
+//!
+//! Values are written in decimal, and as strings in JSON since they do not
+//! fit in a number.
+
+use std::io::Write;
+
+use rug::Integer;
+use serde::{Serialize, Serializer};
+use zkper_curves::curves::bls12_381::BLS12_381_SCALAR;
+
+use super::ConstraintSystem;
+
+/// A non-zero coefficient of a constraint matrix.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Entry {
+    pub row: usize,
+    pub column: usize,
+    #[serde(serialize_with = "decimal")]
+    pub value: Integer,
+}
+
+/// A matrix in coordinate form.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct SparseMatrix {
+    pub rows: usize,
+    pub columns: usize,
+    pub entries: Vec<Entry>,
+}
+
+impl SparseMatrix {
+    fn from_columns(
+        rows: usize,
+        public: &[Vec<(Integer, usize)>],
+        private: &[Vec<(Integer, usize)>],
+    ) -> Self {
+        let mut terms = public
+            .iter()
+            .chain(private)
+            .enumerate()
+            .flat_map(|(column, terms)| {
+                terms
+                    .iter()
+                    .map(move |(coeff, row)| ((*row, column), coeff))
+            })
+            .collect::<Vec<_>>();
+        terms.sort_by_key(|(coordinates, _)| *coordinates);
+
+        // a variable may appear more than once in a linear combination
+        let mut entries: Vec<Entry> = vec![];
+        for ((row, column), coeff) in terms {
+            match entries.last_mut() {
+                Some(last) if (last.row, last.column) == (row, column) => {
+                    last.value =
+                        BLS12_381_SCALAR.add(last.value.clone(), &BLS12_381_SCALAR.reduce(coeff));
+                }
+                _ => entries.push(Entry {
+                    row,
+                    column,
+                    value: BLS12_381_SCALAR.reduce(coeff),
+                }),
+            }
+        }
+        entries.retain(|entry| !entry.value.is_zero());
+
+        SparseMatrix {
+            rows,
+            columns: public.len() + private.len(),
+            entries,
+        }
+    }
+
+    /// Writes the matrix in the Matrix Market coordinate format.
+    pub fn write_matrix_market<W: Write>(&self, mut writer: W) -> anyhow::Result<()> {
+        writeln!(writer, "%%MatrixMarket matrix coordinate integer general")?;
+        writeln!(writer, "% coefficients modulo the BLS12-381 scalar field")?;
+        writeln!(
+            writer,
+            "{} {} {}",
+            self.rows,
+            self.columns,
+            self.entries.len()
+        )?;
+        for entry in &self.entries {
+            writeln!(
+                writer,
+                "{} {} {}",
+                entry.row + 1,
+                entry.column + 1,
+                entry.value
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// The three matrices of a rank-one constraint system, A * B = C row by row.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct R1csMatrices {
+    pub num_constraints: usize,
+    /// Public variables, the constant one included.
+    pub num_public: usize,
+    pub num_private: usize,
+    pub a: SparseMatrix,
+    pub b: SparseMatrix,
+    pub c: SparseMatrix,
+}
+
+impl R1csMatrices {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("matrices always serialize")
+    }
+
+    /// Writes A, B and C as three Matrix Market files, one after the other.
+    pub fn write_matrix_market<W: Write>(&self, mut writer: W) -> anyhow::Result<()> {
+        for matrix in [&self.a, &self.b, &self.c] {
+            matrix.write_matrix_market(&mut writer)?;
+        }
+        Ok(())
+    }
+}
+
+impl ConstraintSystem {
+    /// Exports the constraint matrices, see [`R1csMatrices`].
+    pub fn matrices(&self) -> R1csMatrices {
+        let rows = self.num_constraints;
+        R1csMatrices {
+            num_constraints: rows,
+            num_public: self.num_public_inputs,
+            num_private: self.num_private_inputs,
+            a: SparseMatrix::from_columns(rows, &self.at_public, &self.at_private),
+            b: SparseMatrix::from_columns(rows, &self.bt_public, &self.bt_private),
+            c: SparseMatrix::from_columns(rows, &self.ct_public, &self.ct_private),
+        }
+    }
+}
+
+fn decimal<S: Serializer>(value: &Integer, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constraints::{linear_combination::LinearCombination, Variable};
+
+    #[test]
+    fn test_matrices() {
+        // x * x = y and (y + 2x) * 1 = out
+        let mut cs = ConstraintSystem::new();
+        let out = cs.new_public().unwrap();
+        let x = cs.new_private().unwrap();
+        let y = cs.new_private().unwrap();
+        cs.enforce_constraint(
+            LinearCombination::new_variable(x),
+            LinearCombination::new_variable(x),
+            LinearCombination::new_variable(y),
+        );
+        cs.enforce_constraint(
+            LinearCombination::new_variable(y)
+                .add_variable(x)
+                .add_variable(x),
+            LinearCombination::new_variable(ConstraintSystem::one()),
+            LinearCombination::new_variable(out),
+        );
+        assert_eq!(out, Variable::Public(1));
+
+        let matrices = cs.matrices();
+        assert_eq!(
+            (
+                matrices.num_constraints,
+                matrices.num_public,
+                matrices.num_private
+            ),
+            (2, 2, 2)
+        );
+        let entry = |row, column, value: i32| Entry {
+            row,
+            column,
+            value: Integer::from(value),
+        };
+        let coordinates = |m: &SparseMatrix| {
+            m.entries
+                .iter()
+                .map(|e| (e.row, e.column))
+                .collect::<Vec<_>>()
+        };
+        // x and y are columns 2 and 3
+        assert_eq!(coordinates(&matrices.a), vec![(0, 2), (1, 2), (1, 3)]);
+        // the two x terms are merged
+        assert_eq!(matrices.a.entries[1].value, 2);
+        assert_eq!(matrices.b.entries, vec![entry(0, 2, 1), entry(1, 0, 1)]);
+        assert_eq!(matrices.c.entries, vec![entry(0, 3, 1), entry(1, 1, 1)]);
+
+        let mut market = vec![];
+        matrices.c.write_matrix_market(&mut market).unwrap();
+        assert_eq!(
+            String::from_utf8(market).unwrap(),
+            "%%MatrixMarket matrix coordinate integer general\n\
+             % coefficients modulo the BLS12-381 scalar field\n\
+             2 4 2\n1 4 1\n2 2 1\n"
+        );
+
+        let json: serde_json::Value = serde_json::from_str(&matrices.to_json()).unwrap();
+        assert_eq!(json["num_constraints"], 2);
+        assert_eq!(json["b"]["columns"], 4);
+        assert_eq!(json["c"]["entries"][1]["column"], 1);
+        assert_eq!(json["c"]["entries"][1]["value"], "1");
+    }
+
+    #[test]
+    fn test_matrices_reduce_coefficients() {
+        let mut cs = ConstraintSystem::new();
+        let x = cs.new_private().unwrap();
+        let minus_one = BLS12_381_SCALAR.modulus() - Integer::from(1);
+        cs.enforce_constraint(
+            LinearCombination::zero().add((x, minus_one.clone())),
+            LinearCombination::zero().add((x, Integer::from(-1))),
+            // cancels out
+            LinearCombination::new_variable(x).sub_variable(x),
+        );
+
+        let matrices = cs.matrices();
+        assert_eq!(matrices.a.entries, matrices.b.entries);
+        assert_eq!(matrices.a.entries[0].value, minus_one);
+        assert!(matrices.c.entries.is_empty());
+    }
+}
@@ -1,7 +1,14 @@
+use std::fmt;
+
 use self::linear_combination::LinearCombination;
+use crate::{
+    error::Groth16Error, evaluation_domain::EvaluationDomain, params_source::file::PointEncoding,
+};
 use rug::Integer;
+use zkper_curves::curves::bls12_381::Scalar;
 
 pub mod linear_combination;
+pub mod matrix;
 pub mod namespace;
 
 /// Represents the different kinds of variables present in a constraint system.
@@ -13,6 +20,39 @@ pub enum Variable {
     Private(usize),
 }
 
+/// What a circuit is being synthesized for.
+#[derive(Copy, Clone, PartialEq, Debug, Eq)]
+pub enum Mode {
+    /// Parameter generation: only the constraints matter, no value is computed.
+    Setup,
+    /// Proving: every variable is assigned its value.
+    Prove,
+}
+
+/// A rank-one constraint system that a [`Circuit`](crate::circuit::Circuit)
+/// is synthesized into.
+///
+/// The same circuit code runs against [`ConstraintSystem`] during setup and
+/// against [`ProvingSystem`](crate::prover::ProvingSystem) while proving.
+/// Values are passed as closures that only run in [`Mode::Prove`], so the
+/// witness is computed next to the constraints it has to satisfy.
+pub trait ConstraintSystemTrait {
+    fn mode(&self) -> Mode;
+
+    /// Allocates a private variable, calling `value` only while proving.
+    fn alloc_private<F>(&mut self, value: F) -> Result<Variable, Groth16Error>
+    where
+        F: FnOnce() -> anyhow::Result<Scalar>;
+
+    /// Allocates a public input, calling `value` only while proving.
+    fn alloc_public<F>(&mut self, value: F) -> Result<Variable, Groth16Error>
+    where
+        F: FnOnce() -> anyhow::Result<Scalar>;
+
+    /// Adds the constraint a * b = c.
+    fn enforce(&mut self, a: LinearCombination, b: LinearCombination, c: LinearCombination);
+}
+
 /// An Rank-One `ConstraintSystem`.
 /// Used to synthesize the circuit into a QAP.
 #[derive(Debug)]
@@ -33,6 +73,12 @@ pub struct ConstraintSystem {
     pub ct_private: Vec<Vec<(Integer, usize)>>,
 }
 
+impl Default for ConstraintSystem {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl ConstraintSystem {
     /// Creates a new empty `ConstraintSystem`.
     pub fn new() -> Self {
@@ -54,7 +100,7 @@ impl ConstraintSystem {
         Variable::Public(0)
     }
 
-    pub fn new_private(&mut self) -> anyhow::Result<Variable> {
+    pub fn new_private(&mut self) -> Result<Variable, Groth16Error> {
         let current = self.num_private_inputs;
 
         self.at_private.push(vec![]);
@@ -66,7 +112,7 @@ impl ConstraintSystem {
         Ok(Variable::Private(current))
     }
 
-    pub fn new_public(&mut self) -> anyhow::Result<Variable> {
+    pub fn new_public(&mut self) -> Result<Variable, Groth16Error> {
         let current = self.num_public_inputs;
 
         self.at_public.push(vec![]);
@@ -85,11 +131,9 @@ impl ConstraintSystem {
         this_constraint: usize,
     ) {
         for (index, coeff) in &linear_combination.0 {
-            match index {
-                &Variable::Public(id) => {
-                    public_variables[id].push((coeff.clone(), this_constraint))
-                }
-                &Variable::Private(id) => {
+            match *index {
+                Variable::Public(id) => public_variables[id].push((coeff.clone(), this_constraint)),
+                Variable::Private(id) => {
                     private_variables[id].push((coeff.clone(), this_constraint))
                 }
             }
@@ -123,4 +167,176 @@ impl ConstraintSystem {
 
         self.num_constraints += 1;
     }
+
+    /// Sizes of the system and of the parameters generated for it.
+    pub fn stats(&self) -> ConstraintSystemStats {
+        let num_variables = self.num_public_inputs + self.num_private_inputs;
+        let matrix = |public: &[Vec<(Integer, usize)>], private: &[Vec<(Integer, usize)>]| {
+            let columns = public.iter().chain(private);
+            let nonzeros = columns.clone().map(Vec::len).sum();
+            let cells = self.num_constraints * num_variables;
+            MatrixStats {
+                nonzeros,
+                variables: columns.filter(|column| !column.is_empty()).count(),
+                density: if cells == 0 {
+                    0.0
+                } else {
+                    nonzeros as f64 / cells as f64
+                },
+            }
+        };
+
+        ConstraintSystemStats {
+            num_constraints: self.num_constraints,
+            num_public_inputs: self.num_public_inputs,
+            num_private_inputs: self.num_private_inputs,
+            a: matrix(&self.at_public, &self.at_private),
+            b: matrix(&self.bt_public, &self.bt_private),
+            c: matrix(&self.ct_public, &self.ct_private),
+            domain_size: EvaluationDomain::padded_size(self.num_constraints),
+        }
+    }
+}
+
+/// Non-zero entries of one of the A, B and C matrices.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MatrixStats {
+    pub nonzeros: usize,
+    /// Variables with at least one non-zero entry.
+    pub variables: usize,
+    /// Share of non-zero entries among constraints * variables.
+    pub density: f64,
+}
+
+/// What [`ConstraintSystem::stats`] reports, to budget setup and proving
+/// before running the generator.
+///
+/// Query lengths assume no variable evaluates to zero at tau, which holds
+/// except with negligible probability.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ConstraintSystemStats {
+    pub num_constraints: usize,
+    /// Public inputs, the constant one included.
+    pub num_public_inputs: usize,
+    pub num_private_inputs: usize,
+    pub a: MatrixStats,
+    pub b: MatrixStats,
+    pub c: MatrixStats,
+    /// Size of the evaluation domain the constraints are padded to.
+    pub domain_size: usize,
+}
+
+impl ConstraintSystemStats {
+    /// Length of the H query, one base per power of tau below the domain size.
+    pub fn h_query_len(&self) -> usize {
+        self.domain_size - 1
+    }
+
+    /// G1 bases of the parameters: the verification key, IC and the H, L,
+    /// A and B queries.
+    pub fn g1_points(&self) -> usize {
+        3 + self.num_public_inputs
+            + self.h_query_len()
+            + self.num_private_inputs
+            + self.a.variables
+            + self.b.variables
+    }
+
+    /// G2 bases of the parameters: the verification key and the B query.
+    pub fn g2_points(&self) -> usize {
+        3 + self.b.variables
+    }
+
+    /// Size of the parameter file `ProvingParameters::write_with_encoding`
+    /// produces.
+    pub fn parameter_bytes(&self, encoding: PointEncoding) -> usize {
+        // magic and the six length prefixes
+        8 + 6 * 8 + self.g1_points() * encoding.g1_size() + self.g2_points() * encoding.g2_size()
+    }
+}
+
+impl fmt::Display for ConstraintSystemStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "constraints: {}", self.num_constraints)?;
+        writeln!(
+            f,
+            "variables: {} public, {} private",
+            self.num_public_inputs, self.num_private_inputs
+        )?;
+        for (name, matrix) in [("A", &self.a), ("B", &self.b), ("C", &self.c)] {
+            writeln!(
+                f,
+                "{}: {} non-zero, density {:.2e}",
+                name, matrix.nonzeros, matrix.density
+            )?;
+        }
+        writeln!(f, "domain size: {}", self.domain_size)?;
+        writeln!(
+            f,
+            "parameters: {} G1 and {} G2 points",
+            self.g1_points(),
+            self.g2_points()
+        )?;
+        write!(
+            f,
+            "parameter file: {} bytes, {} compressed",
+            self.parameter_bytes(PointEncoding::Uncompressed),
+            self.parameter_bytes(PointEncoding::Compressed)
+        )
+    }
+}
+
+impl ConstraintSystemTrait for ConstraintSystem {
+    fn mode(&self) -> Mode {
+        Mode::Setup
+    }
+
+    fn alloc_private<F>(&mut self, _value: F) -> Result<Variable, Groth16Error>
+    where
+        F: FnOnce() -> anyhow::Result<Scalar>,
+    {
+        self.new_private()
+    }
+
+    fn alloc_public<F>(&mut self, _value: F) -> Result<Variable, Groth16Error>
+    where
+        F: FnOnce() -> anyhow::Result<Scalar>,
+    {
+        self.new_public()
+    }
+
+    fn enforce(&mut self, a: LinearCombination, b: LinearCombination, c: LinearCombination) {
+        self.enforce_constraint(a, b, c);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prover::ProvingSystem;
+
+    #[test]
+    fn test_modes() {
+        let mut cs = ConstraintSystem::new();
+        assert_eq!(cs.mode(), Mode::Setup);
+        // no value is computed during setup
+        let x = cs
+            .alloc_private(|| panic!("value computed during setup"))
+            .unwrap();
+        let y = cs
+            .alloc_public(|| panic!("value computed during setup"))
+            .unwrap();
+        assert_eq!((x, y), (Variable::Private(0), Variable::Public(1)));
+
+        let mut prover = ProvingSystem::new();
+        assert_eq!(prover.mode(), Mode::Prove);
+        let y = prover
+            .alloc_public(|| Ok(Scalar::from(Integer::from(3))))
+            .unwrap();
+        assert_eq!(y, Variable::Public(1));
+        assert_eq!(prover.public_assignment[1], Scalar::from(Integer::from(3)));
+        assert!(prover
+            .alloc_private(|| Err(anyhow::anyhow!("missing value")))
+            .is_err());
+    }
 }
@@ -1,4 +1,5 @@
 use super::{ConstraintSystem, Variable};
+use crate::error::Groth16Error;
 
 /// A namespaced mutable reference to a constraint system.
 #[derive(Debug)]
@@ -23,19 +24,17 @@ impl<'a> Namespace<'a> {
     }
 
     /// Manually drop the namespace.
-    pub fn drop(self) {
-        drop(self)
-    }
+    pub fn drop(self) {}
 
     pub fn one() -> Variable {
         ConstraintSystem::one()
     }
 
-    pub fn new_private(&mut self) -> anyhow::Result<Variable> {
+    pub fn new_private(&mut self) -> Result<Variable, Groth16Error> {
         self.inner.new_private()
     }
 
-    pub fn new_public(&mut self) -> anyhow::Result<Variable> {
+    pub fn new_public(&mut self) -> Result<Variable, Groth16Error> {
         self.inner.new_public()
     }
 }
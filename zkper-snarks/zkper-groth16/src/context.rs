@@ -0,0 +1,106 @@
+//! Binding proofs to an application or session context.
+//!
+//! Every circuit gets one extra public input, allocated after the circuit's
+//! own inputs, that carries a hash of the context the proof was made for. A
+//! proof created for one context does not verify under another, so a statement
+//! proven for one application or session cannot be replayed in a different
+//! one. Without a context the input is zero and contributes nothing.
+
+#[cfg(feature = "backend-rug")]
+use rug::{integer::Order, Integer};
+#[cfg(feature = "backend-rug")]
+use zkper_curves::curves::bls12_381::BLS12_381_SCALAR;
+
+#[cfg(feature = "backend-rug")]
+use crate::hash::Sha256Hash;
+use crate::hash::TranscriptHash;
+
+/// Domain separation tag of the context hash.
+pub const CONTEXT_DST: &[u8] = b"ZKPER-GROTH16-CONTEXT-V1";
+
+/// Hashes arbitrary bytes to a scalar with SHA-256.
+#[cfg(feature = "backend-rug")]
+pub fn hash_to_scalar(dst: &[u8], msg: &[u8]) -> Integer {
+    hash_to_scalar_with::<Sha256Hash>(dst, msg)
+}
+
+/// Hashes arbitrary bytes to a scalar with `H`.
+///
+/// Two digests, each over the length of `dst`, `dst`, a block counter and
+/// `msg`, are read as a 512-bit big-endian integer and reduced mod r, leaving
+/// a negligible bias. The length is a full u64, so tags of any size stay
+/// distinct.
+#[cfg(feature = "backend-rug")]
+pub fn hash_to_scalar_with<H: TranscriptHash>(dst: &[u8], msg: &[u8]) -> Integer {
+    BLS12_381_SCALAR.reduce(&Integer::from_digits(
+        &wide_digest::<H>(dst, msg),
+        Order::Msf,
+    ))
+}
+
+/// The 512 bits `hash_to_scalar_with` reduces mod r, shared with the
+/// verifier of `crate::native`.
+pub(crate) fn wide_digest<H: TranscriptHash>(dst: &[u8], msg: &[u8]) -> [u8; 64] {
+    let mut wide = [0u8; 64];
+    for (counter, block) in wide.chunks_mut(32).enumerate() {
+        let mut hasher = H::default();
+        hasher.update((dst.len() as u64).to_be_bytes());
+        hasher.update(dst);
+        hasher.update([counter as u8]);
+        hasher.update(msg);
+        block.copy_from_slice(&hasher.finalize());
+    }
+    wide
+}
+
+/// The value of the context input: zero without a context, otherwise the
+/// hash of `context`. Never zero for a context, including an empty one.
+#[cfg(feature = "backend-rug")]
+pub fn context_scalar(context: Option<&[u8]>) -> Integer {
+    match context {
+        None => Integer::ZERO,
+        Some(context) => {
+            let scalar = hash_to_scalar(CONTEXT_DST, context);
+            if scalar.is_zero() {
+                Integer::from(1)
+            } else {
+                scalar
+            }
+        }
+    }
+}
+
+#[cfg(all(test, feature = "backend-rug"))]
+mod tests {
+    use super::*;
+    use crate::hash::Keccak256Hash;
+
+    #[test]
+    fn test_context_scalar() {
+        assert_eq!(context_scalar(None), 0);
+
+        let empty = context_scalar(Some(b""));
+        let a = context_scalar(Some(b"app-a"));
+        let b = context_scalar(Some(b"app-b"));
+        assert_ne!(empty, 0);
+        assert_ne!(a, b);
+        assert_eq!(a, context_scalar(Some(b"app-a")));
+        assert!(&a < BLS12_381_SCALAR.modulus_ref());
+
+        // the domain separation tag is part of the hash
+        assert_ne!(hash_to_scalar(b"other", b"app-a"), a);
+        // with its whole length: 256 and 512 bytes are not read as 0
+        let long = [0x5a; 512];
+        assert_ne!(hash_to_scalar(&long[..256], b""), hash_to_scalar(b"", b""));
+        assert_ne!(
+            hash_to_scalar(&long[..256], b""),
+            hash_to_scalar(&long, b"")
+        );
+
+        // the hash is selectable, SHA-256 being the default
+        let keccak = hash_to_scalar_with::<Keccak256Hash>(CONTEXT_DST, b"app-a");
+        assert_ne!(keccak, a);
+        assert!(&keccak < BLS12_381_SCALAR.modulus_ref());
+        assert_eq!(hash_to_scalar_with::<Sha256Hash>(CONTEXT_DST, b"app-a"), a);
+    }
+}
@@ -0,0 +1,211 @@
+use thiserror::Error;
+use zkper_base::error::BoxedError;
+use zkper_curves::error::CurveError;
+
+#[cfg(feature = "backend-rug")]
+use crate::prover::ProverError;
+
+/// Errors of the Groth16 crate that callers may want to tell apart.
+///
+/// The setup, proving and verification functions, circuits and the file
+/// formats return it directly. Witness closures and parameter sources still
+/// report through `anyhow`; their errors keep their variant when they are one
+/// of these, see `Groth16Error::synthesis`, and are carried as the source of
+/// `Synthesis` or `Parameters` otherwise.
+#[derive(Debug, Error)]
+pub enum Groth16Error {
+    #[cfg(feature = "backend-rug")]
+    #[error(transparent)]
+    Prover(#[from] ProverError),
+    #[error(transparent)]
+    Curve(#[from] CurveError),
+
+    // synthesis
+    /// The circuit failed for a reason of its own.
+    #[error("Synthesis failed")]
+    Synthesis(#[source] BoxedError),
+    /// A variable was allocated without the value the prover needs.
+    #[error("Missing value of a {0}")]
+    MissingAssignment(&'static str),
+    /// A batched circuit without instances, see `crate::batched`.
+    #[error("A batch needs at least one instance")]
+    EmptyBatch,
+    /// An instance of a batch allocated a different number of public inputs
+    /// than the first one.
+    #[error("Instance {instance} has {actual} public inputs, the first one {expected}")]
+    BatchInputMismatch {
+        instance: usize,
+        expected: usize,
+        actual: usize,
+    },
+    /// The public inputs of a batch do not split evenly into its instances.
+    #[error("{inputs} public inputs cannot be split into {instances} instances")]
+    BatchSplit { inputs: usize, instances: usize },
+
+    // setup
+    /// The constraint system needs a larger radix-2 domain than the scalar
+    /// field has roots of unity for.
+    #[error("radix2 evaluation domain too large")]
+    DomainTooLarge,
+    /// A private variable appears in no constraint.
+    #[error("Unconstrained variable")]
+    UnconstrainedVariable,
+
+    /// A witness commitment to a different number of values than the key or
+    /// the circuit has, see `crate::models::commitment`.
+    #[error("Commitment to {expected} witness values, got {actual}")]
+    CommitmentLength { expected: usize, actual: usize },
+
+    // proving
+    /// The parameter source failed to provide a query.
+    #[error("Parameters unavailable")]
+    Parameters(#[source] BoxedError),
+    /// A multi-exponentiation has more exponents than bases.
+    #[error("Ran out of bases")]
+    OutOfBases,
+    /// The H query was generated for another evaluation domain.
+    #[error(
+        "H query has {bases} bases but the evaluation domain needs {needed}, regenerate the parameters"
+    )]
+    HQueryMismatch { bases: usize, needed: usize },
+    /// The parameters commit to part of the witness, so proofs need a D.
+    #[error("Parameters commit to part of the witness, use create_committed_proof")]
+    CommittedParameters,
+    /// A committed proof was requested from parameters without a commitment
+    /// key.
+    #[error("Parameters have no witness commitment key")]
+    MissingCommitmentKey,
+
+    // verification
+    /// The verifying key does not match the public inputs it is used with.
+    #[error("InvalidVerifyingKey")]
+    InvalidVerifyingKey,
+    /// δ is the identity, which lets anyone forge proofs.
+    #[error("Invalid verification key, ATTACK")]
+    DegenerateVerifyingKey,
+    /// The number of public inputs differs from the verifying key's.
+    #[error("Wrong number of public inputs: expected {expected}, got {actual}")]
+    WrongInputCount { expected: usize, actual: usize },
+    /// The public input at this index is not in [0, r), see
+    /// `verifier::reduce_public_inputs`.
+    #[error("Public input {0} is not a canonical scalar")]
+    NonCanonicalScalar(usize),
+    /// The public input at this index has not been accumulated yet.
+    #[error("Public input {0} has not been accumulated")]
+    MissingInput(usize),
+    /// A proof point is the identity or outside its prime-order subgroup.
+    #[error("Invalid proof: {0}")]
+    InvalidProof(&'static str),
+    /// The two blinded runs of a double-checked verification disagree.
+    #[error("Verification fault detected: blinded pairing checks disagree")]
+    VerificationFault,
+
+    // files
+    /// Reading or writing a file failed.
+    #[error("I/O error: {0}")]
+    Io(String),
+    /// A setup attestation, circom file, verifier key or proof that does not
+    /// parse.
+    #[error("Malformed file: {0}")]
+    MalformedFile(String),
+    /// Parameters that differ from their setup attestation in this phase,
+    /// see `crate::attestation`.
+    #[error("Setup attestation mismatch: {0}")]
+    AttestationMismatch(&'static str),
+}
+
+impl From<std::io::Error> for Groth16Error {
+    fn from(e: std::io::Error) -> Self {
+        Self::Io(e.to_string())
+    }
+}
+
+#[cfg(feature = "backend-rug")]
+impl Groth16Error {
+    /// Converts an error of a witness closure, keeping the variant of the
+    /// errors of this crate and wrapping anything else in `Synthesis`.
+    pub fn synthesis(e: anyhow::Error) -> Self {
+        Self::recover(e, Self::Synthesis)
+    }
+
+    /// Converts an error of a `ParamsSource`, wrapping anything that is not
+    /// an error of this crate in `Parameters`.
+    pub fn parameters(e: anyhow::Error) -> Self {
+        Self::recover(e, Self::Parameters)
+    }
+
+    fn recover(e: anyhow::Error, other: fn(BoxedError) -> Self) -> Self {
+        let e = match e.downcast::<Groth16Error>() {
+            Ok(e) => return e,
+            Err(e) => e,
+        };
+        let e = match e.downcast::<ProverError>() {
+            Ok(e) => return e.into(),
+            Err(e) => e,
+        };
+        match e.downcast::<CurveError>() {
+            Ok(e) => e.into(),
+            Err(e) => other(e.into()),
+        }
+    }
+}
+
+impl From<Groth16Error> for zkper_base::Error {
+    fn from(e: Groth16Error) -> Self {
+        zkper_base::Error::Groth16(Box::new(e))
+    }
+}
+
+#[cfg(feature = "backend-rug")]
+impl From<ProverError> for zkper_base::Error {
+    fn from(e: ProverError) -> Self {
+        Groth16Error::from(e).into()
+    }
+}
+
+/// Converts an error returned by this crate, keeping the precise variant when
+/// it is a `Groth16Error` or `ProverError`, see `zkper_base::error`.
+#[cfg(feature = "backend-rug")]
+pub fn into_base_error(e: anyhow::Error) -> zkper_base::Error {
+    let e = match e.downcast::<Groth16Error>() {
+        Ok(e) => return e.into(),
+        Err(e) => e,
+    };
+    let e = match e.downcast::<ProverError>() {
+        Ok(e) => return e.into(),
+        Err(e) => e,
+    };
+    match e.downcast::<CurveError>() {
+        Ok(e) => e.into(),
+        Err(e) => e.into(),
+    }
+}
+
+#[cfg(all(test, feature = "backend-rug"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_into_base_error() {
+        let mismatch = ProverError::PublicInputMismatch { circuit: 1, vk: 2 };
+        let e = into_base_error(mismatch.clone().into());
+        assert!(matches!(e, zkper_base::Error::Groth16(_)));
+        assert!(matches!(
+            e.downcast_ref::<Groth16Error>(),
+            Some(Groth16Error::Prover(e)) if *e == mismatch
+        ));
+
+        let e = into_base_error(Groth16Error::InvalidVerifyingKey.into());
+        assert!(matches!(
+            e.downcast_ref::<Groth16Error>(),
+            Some(&Groth16Error::InvalidVerifyingKey)
+        ));
+        assert_eq!(e.to_string(), "InvalidVerifyingKey");
+
+        let e = into_base_error(CurveError::InvalidPoint("G2").into());
+        assert!(matches!(e, zkper_base::Error::Curve(_)));
+
+        let e = into_base_error(anyhow::anyhow!("Unexpected end of file"));
+        assert!(matches!(e, zkper_base::Error::Other(_)));
+    }
+}
@@ -1,10 +1,18 @@
 use rug::Integer;
 use zkper_curves::curves::bls12_381::{Bls12_381ScalarField, BLS12_381_SCALAR};
 
+use crate::{error::Groth16Error, polynomial::DensePolynomial};
+
+/// A multiplicative subgroup of size 2^exp * 3^exp3 and the values of a
+/// polynomial on it.
+///
+/// Sizes are the smallest such number fitting the coefficients, so a vector
+/// just over a power of two is padded by a half instead of doubled.
 #[derive(Debug)]
 pub struct EvaluationDomain {
     pub coeffs: Vec<Integer>,
     pub exp: u32,
+    pub exp3: u32,
     pub omega: Integer,
     pub omegainv: Integer,
     pub geninv: Integer,
@@ -12,15 +20,15 @@ pub struct EvaluationDomain {
 }
 
 impl EvaluationDomain {
-    pub fn new(mut coeffs: Vec<Integer>) -> anyhow::Result<Self> {
+    pub fn new(mut coeffs: Vec<Integer>) -> Result<Self, Groth16Error> {
         // Compute the size of our evaluation domain
-        let needed_size = coeffs.len().next_power_of_two();
-        let exp = needed_size.trailing_zeros();
+        let (exp, exp3) = Self::size_exponents(coeffs.len());
+        let needed_size = Self::padded_size(coeffs.len());
 
         // The pairing-friendly curve may not be able to support
         // large enough (radix2) evaluation domains.
         if exp >= Bls12_381ScalarField::TWO_ADICITY {
-            return Err(anyhow::anyhow!("radix2 evaluation domain too large"));
+            return Err(Groth16Error::DomainTooLarge);
         }
 
         // Compute omega, the 2^exp primitive root of unity
@@ -29,12 +37,18 @@ impl EvaluationDomain {
             omega = BLS12_381_SCALAR.square(omega);
         }
 
+        // times a primitive 3^exp3 root, the orders are coprime
+        if exp3 > 0 {
+            omega = BLS12_381_SCALAR.mul(omega, &Bls12_381ScalarField::cube_root_of_unity());
+        }
+
         // Extend the coeffs vector with zeroes if necessary
         coeffs.resize(needed_size, Integer::from(0));
 
         Ok(EvaluationDomain {
             coeffs,
             exp,
+            exp3,
             omega: omega.clone(),
             omegainv: BLS12_381_SCALAR.invert(omega.clone()).unwrap(),
             geninv: BLS12_381_SCALAR
@@ -44,13 +58,90 @@ impl EvaluationDomain {
         })
     }
 
+    /// The size of the domain holding `len` coefficients.
+    pub fn padded_size(len: usize) -> usize {
+        let (exp, exp3) = Self::size_exponents(len);
+        (1usize << exp) * 3usize.pow(exp3)
+    }
+
+    /// (k, m) of the smallest 2^k * 3^m, m <= THREE_ADICITY, that is at least `len`.
+    fn size_exponents(len: usize) -> (u32, u32) {
+        let power_of_two = len.next_power_of_two();
+        let exp = power_of_two.trailing_zeros();
+
+        // 3 * 2^(k - 2) lies between 2^(k - 1) and 2^k
+        if Bls12_381ScalarField::THREE_ADICITY > 0 && exp >= 2 && 3 * (power_of_two >> 2) >= len {
+            (exp - 2, 1)
+        } else {
+            (exp, 0)
+        }
+    }
+
     pub fn z(&self, tau: &Integer) -> Integer {
         BLS12_381_SCALAR.sub(
             BLS12_381_SCALAR.pow(tau.clone(), &Integer::from(self.coeffs.len())),
-            &Integer::ONE,
+            Integer::ONE,
         )
     }
 
+    /// The vanishing polynomial X^n - 1 of the domain, whose value at tau is `z`.
+    pub fn vanishing_polynomial(&self) -> DensePolynomial {
+        DensePolynomial::vanishing_on_subgroup(self.coeffs.len())
+    }
+
+    /// The Lagrange basis polynomials of the domain evaluated at `tau`, so that
+    /// sum_i L_i(tau) * a(omega^i) = a(tau) for `a` of degree below the size.
+    ///
+    /// Uses the barycentric form L_i(tau) = z(tau) * omega^i / (n * (tau - omega^i)),
+    /// n multiplications and a single inversion, instead of materializing the
+    /// powers of tau and running an inverse FFT.
+    pub fn evaluate_all_lagrange_coefficients(&self, tau: &Integer) -> Vec<Integer> {
+        let n = self.coeffs.len();
+        let z = self.z(tau);
+
+        // tau is one of the omega^i, where L_i(tau) = 1 and every other L_j vanishes
+        if z.is_zero() {
+            let mut point = Integer::from(1);
+            return (0..n)
+                .map(|_| {
+                    let value = Integer::from(point == *tau);
+                    point = BLS12_381_SCALAR.mul(point.clone(), &self.omega);
+                    value
+                })
+                .collect();
+        }
+
+        let mut points = Vec::with_capacity(n);
+        let mut point = Integer::from(1);
+        for _ in 0..n {
+            points.push(point.clone());
+            point = BLS12_381_SCALAR.mul(point, &self.omega);
+        }
+
+        // batch inversion of tau - omega^i: prefix products, one inversion, unwind
+        let mut prefix = Vec::with_capacity(n);
+        let mut acc = Integer::from(1);
+        for point in points.iter() {
+            prefix.push(acc.clone());
+            acc = BLS12_381_SCALAR.mul(acc, &BLS12_381_SCALAR.sub(tau.clone(), point));
+        }
+        let mut inverse = BLS12_381_SCALAR
+            .invert(acc)
+            .expect("tau is not in the domain");
+
+        let scale = BLS12_381_SCALAR.mul(z, &self.minv);
+        let mut coefficients = vec![Integer::ZERO; n];
+        for i in (0..n).rev() {
+            let denominator_inverse = BLS12_381_SCALAR.mul(prefix[i].clone(), &inverse);
+            inverse = BLS12_381_SCALAR.mul(inverse, &BLS12_381_SCALAR.sub(tau.clone(), &points[i]));
+
+            let numerator = BLS12_381_SCALAR.mul(scale.clone(), &points[i]);
+            coefficients[i] = BLS12_381_SCALAR.mul(numerator, &denominator_inverse);
+        }
+
+        coefficients
+    }
+
     fn bitreverse(mut n: u32, l: u32) -> u32 {
         let mut r = 0;
         for _ in 0..l {
@@ -60,15 +151,15 @@ impl EvaluationDomain {
         r
     }
 
-    fn base_fft(&mut self, omega: &Integer, log_n: u32) {
-        let n = self.coeffs.len();
+    fn radix2_fft(coeffs: &mut [Integer], omega: &Integer, log_n: u32) {
+        let n = coeffs.len();
         assert_eq!(n, 1 << log_n);
 
         // Bit-reversal permutation
         for k in 0..n {
             let rk = Self::bitreverse(k as u32, log_n) as usize;
             if k < rk {
-                self.coeffs.swap(rk, k);
+                coeffs.swap(rk, k);
             }
         }
 
@@ -79,13 +170,13 @@ impl EvaluationDomain {
             let mut k = 0;
             while k < n {
                 let mut w = Integer::from(1);
-                for j in 0..m as usize {
-                    let mut t = self.coeffs[k + j + m].clone();
+                for j in 0..m {
+                    let mut t = coeffs[k + j + m].clone();
                     t = BLS12_381_SCALAR.mul(t, &w);
-                    let mut tmp = self.coeffs[k + j].clone();
+                    let mut tmp = coeffs[k + j].clone();
                     tmp = BLS12_381_SCALAR.sub(tmp, &t);
-                    self.coeffs[k + j + m] = tmp;
-                    self.coeffs[k + j] = BLS12_381_SCALAR.add(self.coeffs[k + j].clone(), &t);
+                    coeffs[k + j + m] = tmp;
+                    coeffs[k + j] = BLS12_381_SCALAR.add(coeffs[k + j].clone(), &t);
                     w = BLS12_381_SCALAR.mul(w, &w_m);
                 }
                 k += 2 * m;
@@ -94,12 +185,46 @@ impl EvaluationDomain {
         }
     }
 
+    fn base_fft(&mut self, omega: &Integer) {
+        if self.exp3 == 0 {
+            Self::radix2_fft(&mut self.coeffs, omega, self.exp);
+            return;
+        }
+
+        // One radix-3 step: with a_j(X) holding the coefficients j, j + 3, ...
+        // a(w^t) = a_0(w^3t) + w^t a_1(w^3t) + w^2t a_2(w^3t), and w^3 generates
+        // the 2^exp subgroup the three halves are evaluated on.
+        let omega_cubed = BLS12_381_SCALAR.pow(omega.clone(), &Integer::from(3));
+        let parts: [Vec<Integer>; 3] = std::array::from_fn(|j| {
+            let mut part = self
+                .coeffs
+                .iter()
+                .skip(j)
+                .step_by(3)
+                .cloned()
+                .collect::<Vec<_>>();
+            Self::radix2_fft(&mut part, &omega_cubed, self.exp);
+            part
+        });
+
+        let sub_size = 1 << self.exp;
+        let mut w = Integer::from(1);
+        for (t, v) in self.coeffs.iter_mut().enumerate() {
+            let i = t % sub_size;
+            let w_squared = BLS12_381_SCALAR.square(w.clone());
+            let mut acc = BLS12_381_SCALAR.mul(parts[2][i].clone(), &w_squared);
+            acc = BLS12_381_SCALAR.add(acc, &BLS12_381_SCALAR.mul(parts[1][i].clone(), &w));
+            *v = BLS12_381_SCALAR.add(acc, &parts[0][i]);
+            w = BLS12_381_SCALAR.mul(w, omega);
+        }
+    }
+
     pub fn fft(&mut self) {
-        self.base_fft(&self.omega.clone(), self.exp);
+        self.base_fft(&self.omega.clone());
     }
 
     pub fn ifft(&mut self) {
-        self.base_fft(&self.omegainv.clone(), self.exp);
+        self.base_fft(&self.omegainv.clone());
 
         let minv = &self.minv;
         for v in self.coeffs.iter_mut() {
@@ -150,3 +275,212 @@ impl EvaluationDomain {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zkper_curves::traits::field::FieldTrait;
+    use zkper_rand::TestRng;
+
+    #[test]
+    fn test_domain_sizes() {
+        for (len, size) in [
+            (1, 1),
+            (3, 3),
+            (5, 6),
+            (7, 8),
+            (9, 12),
+            (13, 16),
+            (1025, 1536),
+        ] {
+            let domain = EvaluationDomain::new(vec![Integer::ZERO; len]).unwrap();
+            assert_eq!(domain.coeffs.len(), size, "len {}", len);
+            assert_eq!(
+                BLS12_381_SCALAR.pow(domain.omega.clone(), &Integer::from(size)),
+                1
+            );
+        }
+    }
+
+    #[test]
+    fn test_lagrange_coefficients() {
+        let mut rng = TestRng::new();
+        for len in [8, 12] {
+            let tau = Bls12_381ScalarField::random(&mut rng);
+            let mut domain = EvaluationDomain::new(vec![Integer::ZERO; len]).unwrap();
+            let lagrange = domain.evaluate_all_lagrange_coefficients(&tau);
+
+            // agrees with the inverse FFT of the powers of tau
+            let mut power = Integer::from(1);
+            for c in domain.coeffs.iter_mut() {
+                *c = power.clone();
+                power = BLS12_381_SCALAR.mul(power, &tau);
+            }
+            domain.ifft();
+            assert_eq!(lagrange, domain.coeffs);
+
+            // and degenerates to an indicator vector on the domain itself
+            let point = BLS12_381_SCALAR.mul(domain.omega.clone(), &domain.omega);
+            let lagrange = domain.evaluate_all_lagrange_coefficients(&point);
+            assert_eq!(lagrange[2], 1);
+            assert_eq!(lagrange.iter().filter(|c| c.is_zero()).count(), len - 1);
+        }
+    }
+
+    #[test]
+    fn test_mixed_radix_fft() {
+        let mut rng = TestRng::new();
+        for len in [6, 24, 40] {
+            let coeffs = (0..len)
+                .map(|_| Bls12_381ScalarField::random(&mut rng))
+                .collect::<Vec<_>>();
+            let poly = DensePolynomial::new(coeffs.clone());
+
+            let mut domain = EvaluationDomain::new(coeffs.clone()).unwrap();
+            assert_eq!(domain.exp3, 1);
+            domain.fft();
+
+            // evaluations at the powers of omega, omega a primitive root of unity
+            let mut point = Integer::from(1);
+            for (t, value) in domain.coeffs.iter().enumerate() {
+                assert_eq!(*value, poly.evaluate(&point));
+                assert!(t == 0 || point != 1);
+                point = BLS12_381_SCALAR.mul(point, &domain.omega);
+            }
+
+            domain.icoset_fft();
+            domain.coset_fft();
+            domain.ifft();
+            assert_eq!(&domain.coeffs[..len], &coeffs[..]);
+            assert!(domain.coeffs[len..].iter().all(|c| c.is_zero()));
+        }
+    }
+
+    /// The O(n^2) oracle: the evaluations of `coeffs` at shift * omega^t.
+    fn naive_dft(coeffs: &[Integer], omega: &Integer, shift: &Integer) -> Vec<Integer> {
+        let mut point = shift.clone();
+        (0..coeffs.len())
+            .map(|_| {
+                // Horner
+                let value = coeffs.iter().rev().fold(Integer::ZERO, |acc, c| {
+                    BLS12_381_SCALAR.add(BLS12_381_SCALAR.mul(acc, &point), c)
+                });
+                point = BLS12_381_SCALAR.mul(point.clone(), omega);
+                value
+            })
+            .collect()
+    }
+
+    fn schoolbook_mul(a: &[Integer], b: &[Integer]) -> Vec<Integer> {
+        let mut product = vec![Integer::ZERO; a.len() + b.len() - 1];
+        for (i, x) in a.iter().enumerate() {
+            for (j, y) in b.iter().enumerate() {
+                let term = BLS12_381_SCALAR.mul(x.clone(), y);
+                product[i + j] = BLS12_381_SCALAR.add(product[i + j].clone(), &term);
+            }
+        }
+        product
+    }
+
+    fn random_coeffs(rng: &mut TestRng, len: usize) -> Vec<Integer> {
+        (0..len)
+            .map(|_| Bls12_381ScalarField::random(&mut *rng))
+            .collect()
+    }
+
+    /// Every size 2^k and 3 * 2^k up to 2^10.
+    fn sizes() -> impl Iterator<Item = usize> {
+        (0..=10).map(|k| 1 << k).chain((0..=8).map(|k| 3 << k))
+    }
+
+    #[test]
+    fn test_fft_against_naive_dft() {
+        let mut rng = TestRng::new();
+        let generator = Bls12_381ScalarField::MULTIPLICATIVE_GENERATOR;
+
+        for n in sizes() {
+            let coeffs = random_coeffs(&mut rng, n);
+            let mut domain = EvaluationDomain::new(coeffs.clone()).unwrap();
+            assert_eq!(domain.coeffs.len(), n);
+            let omega = domain.omega.clone();
+
+            domain.fft();
+            let evaluations = naive_dft(&coeffs, &omega, &Integer::from(1));
+            assert_eq!(domain.coeffs, evaluations, "fft of size {}", n);
+
+            domain.ifft();
+            assert_eq!(domain.coeffs, coeffs, "ifft of size {}", n);
+
+            domain.coset_fft();
+            let coset_evaluations = naive_dft(&coeffs, &omega, generator);
+            assert_eq!(domain.coeffs, coset_evaluations, "coset_fft of size {}", n);
+
+            domain.icoset_fft();
+            assert_eq!(domain.coeffs, coeffs, "icoset_fft of size {}", n);
+
+            // the inverse transform is the DFT at omega^-1, scaled by 1/n
+            let domain = EvaluationDomain::new(vec![Integer::ZERO; n]).unwrap();
+            let inverse = naive_dft(&evaluations, &domain.omegainv, &Integer::from(1))
+                .into_iter()
+                .map(|c| BLS12_381_SCALAR.mul(c, &domain.minv))
+                .collect::<Vec<_>>();
+            assert_eq!(inverse, coeffs, "inverse dft of size {}", n);
+        }
+    }
+
+    #[test]
+    fn test_polynomial_multiplication_against_schoolbook() {
+        let mut rng = TestRng::new();
+
+        for (len_a, len_b) in [(1, 1), (1, 7), (3, 5), (16, 17), (100, 413), (512, 513)] {
+            let a = random_coeffs(&mut rng, len_a);
+            let b = random_coeffs(&mut rng, len_b);
+            let expected = schoolbook_mul(&a, &b);
+
+            // the pointwise product of the evaluations, transformed back
+            let len = len_a + len_b - 1;
+            let padded = |coeffs: &[Integer]| {
+                let mut coeffs = coeffs.to_vec();
+                coeffs.resize(len, Integer::ZERO);
+                EvaluationDomain::new(coeffs).unwrap()
+            };
+            let (mut da, mut db) = (padded(&a), padded(&b));
+            assert!(da.coeffs.len() <= 1024);
+            da.fft();
+            db.fft();
+            da.mul_assign(&db);
+            da.ifft();
+            assert_eq!(&da.coeffs[..len], &expected[..], "{} x {}", len_a, len_b);
+            assert!(da.coeffs[len..].iter().all(|c| c.is_zero()));
+
+            let product = DensePolynomial::new(a).mul(&DensePolynomial::new(b));
+            assert_eq!(product, DensePolynomial::new(expected));
+        }
+    }
+
+    #[test]
+    fn test_divide_by_z_on_coset() {
+        let mut rng = TestRng::new();
+        let generator = Bls12_381ScalarField::MULTIPLICATIVE_GENERATOR;
+
+        for n in [1, 4, 12, 64] {
+            let coeffs = random_coeffs(&mut rng, n);
+            let mut domain = EvaluationDomain::new(coeffs.clone()).unwrap();
+            domain.coset_fft();
+            domain.divide_by_z_on_coset();
+
+            // p(x) / (x^n - 1) at every point x of the coset
+            let mut point = generator.clone();
+            for (t, value) in domain.coeffs.iter().enumerate() {
+                let p = DensePolynomial::new(coeffs.clone()).evaluate(&point);
+                let z = BLS12_381_SCALAR.sub(
+                    BLS12_381_SCALAR.pow(point.clone(), &Integer::from(n)),
+                    Integer::ONE,
+                );
+                let expected = BLS12_381_SCALAR.mul(p, &BLS12_381_SCALAR.invert(z).unwrap());
+                assert_eq!(*value, expected, "point {} of {}", t, n);
+                point = BLS12_381_SCALAR.mul(point, &domain.omega);
+            }
+        }
+    }
+}
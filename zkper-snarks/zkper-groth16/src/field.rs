@@ -0,0 +1,190 @@
+//! The field a [`CircuitBuilder`](crate::builder::CircuitBuilder) computes
+//! values in.
+//!
+//! Real circuits run over [`Fr`], the BLS12-381 scalar field. Gadgets written
+//! against a generic [`CircuitField`] can also run over a [`SmallField`], a
+//! word-sized prime field whose arithmetic is a handful of machine
+//! instructions, and be checked with a [`MockSystem`](crate::mock::MockSystem)
+//! in unit tests that would be slow over a 255-bit field.
+
+use std::fmt;
+
+use rug::Integer;
+use zkper_curves::curves::bls12_381::{Scalar, BLS12_381_SCALAR};
+use zkper_integer::{backends::u128_backend::U128Backend, traits::ZkperIntegerTrait};
+
+/// Arithmetic on the values carried next to circuit variables.
+pub trait CircuitField {
+    type Value: Clone + fmt::Debug + PartialEq;
+
+    fn zero() -> Self::Value;
+
+    fn one() -> Self::Value;
+
+    fn from_u64(value: u64) -> Self::Value;
+
+    fn add(a: &Self::Value, b: &Self::Value) -> Self::Value;
+
+    fn sub(a: &Self::Value, b: &Self::Value) -> Self::Value;
+
+    fn mul(a: &Self::Value, b: &Self::Value) -> Self::Value;
+
+    fn is_zero(a: &Self::Value) -> bool;
+
+    /// Maps a coefficient of a
+    /// [`LinearCombination`](crate::constraints::linear_combination::LinearCombination),
+    /// which is always an element of Fr, into this field.
+    fn from_coeff(coeff: &Integer) -> Self::Value;
+}
+
+/// Shorthand for the value type of a field.
+pub type Value<F> = <F as CircuitField>::Value;
+
+/// The BLS12-381 scalar field, the field of every real proof.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Fr;
+
+impl CircuitField for Fr {
+    type Value = Scalar;
+
+    fn zero() -> Scalar {
+        Scalar::from(Integer::ZERO)
+    }
+
+    fn one() -> Scalar {
+        Scalar::from(Integer::from(1))
+    }
+
+    fn from_u64(value: u64) -> Scalar {
+        Scalar::from(BLS12_381_SCALAR.reduce(&Integer::from(value)))
+    }
+
+    fn add(a: &Scalar, b: &Scalar) -> Scalar {
+        a.add(b)
+    }
+
+    fn sub(a: &Scalar, b: &Scalar) -> Scalar {
+        Scalar::from(BLS12_381_SCALAR.sub(a.0.clone(), &b.0))
+    }
+
+    fn mul(a: &Scalar, b: &Scalar) -> Scalar {
+        a.mul(b)
+    }
+
+    fn is_zero(a: &Scalar) -> bool {
+        a.0.is_zero()
+    }
+
+    fn from_coeff(coeff: &Integer) -> Scalar {
+        Scalar::from(coeff.clone())
+    }
+}
+
+/// The prime field of order `P`, for any `P` above 1 that fits a u64.
+///
+/// Values are u64 residues; the arithmetic runs on the u128 word backend of
+/// zkper-integer, which holds the sum and the product of two of them.
+///
+/// Coefficients come from Fr and are read as signed integers, r - c standing
+/// for -c, so the small coefficients gadgets use (1, -1, 2, ...) mean the same
+/// thing in both fields.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SmallField<const P: u64>;
+
+/// The Mersenne prime 2^31 - 1.
+pub type Mersenne31 = SmallField<{ (1 << 31) - 1 }>;
+
+impl<const P: u64> SmallField<P> {
+    fn modulus() -> U128Backend {
+        const { assert!(P > 1, "SmallField needs a modulus above 1") };
+        U128Backend(P as u128)
+    }
+
+    fn word(value: &u64) -> U128Backend {
+        U128Backend(*value as u128)
+    }
+
+    fn reduce(value: U128Backend) -> u64 {
+        value.reduce(&Self::modulus()).0 as u64
+    }
+}
+
+impl<const P: u64> CircuitField for SmallField<P> {
+    type Value = u64;
+
+    fn zero() -> u64 {
+        Self::reduce(U128Backend::zero())
+    }
+
+    fn one() -> u64 {
+        Self::reduce(U128Backend::one())
+    }
+
+    fn from_u64(value: u64) -> u64 {
+        Self::reduce(Self::word(&value))
+    }
+
+    fn add(a: &u64, b: &u64) -> u64 {
+        Self::reduce(Self::word(a).add(&Self::word(b)))
+    }
+
+    fn sub(a: &u64, b: &u64) -> u64 {
+        Self::add(a, &(Self::word(b).neg_mod(&Self::modulus()).0 as u64))
+    }
+
+    fn mul(a: &u64, b: &u64) -> u64 {
+        Self::reduce(Self::word(a).mul(&Self::word(b)))
+    }
+
+    fn is_zero(a: &u64) -> bool {
+        *a == 0
+    }
+
+    fn from_coeff(coeff: &Integer) -> u64 {
+        let r = BLS12_381_SCALAR.modulus_ref();
+        if Integer::from(coeff << 1) > *r {
+            let magnitude = Integer::from(r - coeff) % P;
+            Self::sub(&0, &magnitude.to_u64().unwrap())
+        } else {
+            Integer::from(coeff % P).to_u64().unwrap()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_small_field() {
+        type F = Mersenne31;
+        let p = (1 << 31) - 1;
+
+        assert_eq!(F::from_u64(p + 5), 5);
+        assert_eq!(F::sub(&2, &5), p - 3);
+        assert_eq!(F::mul(&(p - 1), &(p - 1)), 1);
+        assert!(F::is_zero(&F::add(&(p - 1), &F::one())));
+
+        // -1 and 2 in Fr are -1 and 2 here too
+        let minus_one = BLS12_381_SCALAR.neg(Integer::from(1));
+        assert_eq!(F::from_coeff(&minus_one), p - 1);
+        assert_eq!(F::from_coeff(&Integer::from(2)), 2);
+        assert_eq!(Fr::from_coeff(&minus_one), Scalar::from(minus_one));
+    }
+
+    #[test]
+    fn test_small_field_full_width() {
+        // the largest prime below 2^64, where sums of residues overflow a u64
+        type F = SmallField<18446744073709551557>;
+        let p = 18446744073709551557;
+
+        assert_eq!(F::add(&(p - 1), &(p - 2)), p - 3);
+        assert_eq!(F::sub(&1, &(p - 1)), 2);
+        assert_eq!(F::mul(&(p - 1), &(p - 1)), 1);
+        assert_eq!(F::from_u64(u64::MAX), u64::MAX - p);
+        assert_eq!(
+            F::from_coeff(&BLS12_381_SCALAR.neg(Integer::from(1))),
+            p - 1
+        );
+    }
+}
@@ -1,23 +1,28 @@
-use std::sync::Arc;
+use std::fmt;
 
-use anyhow::Result;
 use rand::Rng;
 use rand::RngCore;
-use rug::Assign;
+use rayon::prelude::*;
 use rug::Integer;
+#[cfg(feature = "zeroize")]
+use zeroize::Zeroize;
+use zkper_base::redact::Redacted;
 use zkper_curves::curves::bls12_381::BLS12_381_SCALAR;
 use zkper_curves::curves::bls12_381::{
-    curves::{g1::G1Projective, g2::G2Projective},
-    Bls12_381ScalarField,
+    curves::{g1::G1Projective, g1_affine::G1Affine, g2::G2Projective, g2_affine::G2Affine},
+    Bls12_381ScalarField, Scalar,
 };
 
 use crate::circuit::Circuit;
 use crate::constraints::linear_combination::LinearCombination;
-use crate::constraints::ConstraintSystem;
 use crate::constraints::Variable;
+use crate::constraints::{ConstraintSystem, ConstraintSystemStats};
+use crate::error::Groth16Error;
 use crate::evaluation_domain::EvaluationDomain;
+use crate::models::commitment::CommitmentKey;
 use crate::models::proving_parameters::ProvingParameters;
 use crate::models::verification_key::VerificationKey;
+use crate::multiexp::{prepare_bases, prepare_bases_g2};
 
 pub struct ToxicWaste {
     pub alpha: Bls12_381ScalarField,
@@ -27,6 +32,18 @@ pub struct ToxicWaste {
     pub tau: Bls12_381ScalarField,
 }
 
+impl fmt::Debug for ToxicWaste {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ToxicWaste")
+            .field("alpha", &Redacted(&self.alpha))
+            .field("beta", &Redacted(&self.beta))
+            .field("gamma", &Redacted(&self.gamma))
+            .field("delta", &Redacted(&self.delta))
+            .field("tau", &Redacted(&self.tau))
+            .finish()
+    }
+}
+
 impl ToxicWaste {
     pub fn sample<R: RngCore>(rng: &mut R) -> Self {
         Self {
@@ -39,20 +56,71 @@ impl ToxicWaste {
     }
 }
 
+#[cfg(feature = "zeroize")]
+impl Zeroize for ToxicWaste {
+    fn zeroize(&mut self) {
+        for value in [
+            &mut self.alpha,
+            &mut self.beta,
+            &mut self.gamma,
+            &mut self.delta,
+            &mut self.tau,
+        ] {
+            value.zeroize();
+        }
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl Drop for ToxicWaste {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl zeroize::ZeroizeOnDrop for ToxicWaste {}
+
 /// Generates a random common reference string for a circuit.
 pub fn generate_proving_parameters<C: Circuit, R: RngCore>(
     circuit: C,
-    mut rng: &mut R,
-) -> Result<ProvingParameters> {
-    let g1 = G1Projective::random_mont(&mut rng).from_montgomery();
-    let g2 = G2Projective::random(&mut rng);
-    let toxic_waste = ToxicWaste::sample(&mut rng);
+    rng: &mut R,
+) -> Result<ProvingParameters, Groth16Error> {
+    let cs = synthesize_constraint_system(&circuit)?;
+    generate_from_constraint_system(&cs, rng)
+}
+
+/// Like `generate_proving_parameters`, with a key for the commitment D to the
+/// first `committed` private variables the circuit allocates, see
+/// `crate::models::commitment`.
+pub fn generate_proving_parameters_with_commitment<C: Circuit, R: RngCore>(
+    circuit: C,
+    committed: usize,
+    rng: &mut R,
+) -> Result<ProvingParameters, Groth16Error> {
+    let cs = synthesize_constraint_system(&circuit)?;
+    generate_with_commitment(&cs, Some(committed), rng)
+}
 
+/// Sizes of the constraint system and of the parameters of a circuit, without
+/// generating them.
+pub fn analyze<C: Circuit>(circuit: &C) -> Result<ConstraintSystemStats, Groth16Error> {
+    Ok(synthesize_constraint_system(circuit)?.stats())
+}
+
+/// Synthesizes the circuit into the constraint system the parameters are
+/// generated for, including the context input and the input constraints.
+pub(crate) fn synthesize_constraint_system<C: Circuit>(
+    circuit: &C,
+) -> Result<ConstraintSystem, Groth16Error> {
     let mut cs = ConstraintSystem::new();
 
     // Synthesize the circuit.
     circuit.synthesize(&mut cs)?;
 
+    // The context input, see `crate::context`.
+    cs.new_public()?;
+
     // Input constraints to ensure full density of IC query
     // x * 0 = 0
     for i in 0..cs.num_public_inputs {
@@ -62,17 +130,37 @@ pub fn generate_proving_parameters<C: Circuit, R: RngCore>(
         cs.enforce_constraint(a, b, c);
     }
 
-    // Create bases for blind evaluation of polynomials at tau
-    let powers_of_tau = vec![Integer::ZERO; cs.num_constraints];
-    let mut domain = EvaluationDomain::new(powers_of_tau)?;
-
-    // Compute powers of tau
-    let mut current_tau_power = Integer::ONE.clone();
-    for p in domain.coeffs.iter_mut() {
-        p.assign(current_tau_power.clone());
-        current_tau_power = BLS12_381_SCALAR.mul(current_tau_power, &toxic_waste.tau.0);
+    Ok(cs)
+}
+
+pub(crate) fn generate_from_constraint_system<R: RngCore>(
+    cs: &ConstraintSystem,
+    rng: &mut R,
+) -> Result<ProvingParameters, Groth16Error> {
+    generate_with_commitment(cs, None, rng)
+}
+
+fn generate_with_commitment<R: RngCore>(
+    cs: &ConstraintSystem,
+    committed: Option<usize>,
+    mut rng: &mut R,
+) -> Result<ProvingParameters, Groth16Error> {
+    if let Some(committed) = committed {
+        if committed > cs.num_private_inputs {
+            return Err(Groth16Error::CommitmentLength {
+                expected: committed,
+                actual: cs.num_private_inputs,
+            });
+        }
     }
 
+    let g1 = G1Projective::random_mont(&mut rng).from_montgomery();
+    let g2 = G2Projective::random(&mut rng);
+    let toxic_waste = ToxicWaste::sample(&mut rng);
+
+    // Create bases for blind evaluation of polynomials at tau
+    let domain = EvaluationDomain::new(vec![Integer::ZERO; cs.num_constraints])?;
+
     let gamma_inverse = BLS12_381_SCALAR
         .invert(toxic_waste.gamma.0.clone())
         .unwrap();
@@ -80,20 +168,18 @@ pub fn generate_proving_parameters<C: Circuit, R: RngCore>(
         .invert(toxic_waste.delta.0.clone())
         .unwrap();
 
-    // Compute H query
+    // Compute H query from the powers of tau, one at a time
     let mut h = vec![G1Projective::identity(); domain.coeffs.len() - 1];
     let mut coeff = domain.z(&toxic_waste.tau.0);
     coeff = BLS12_381_SCALAR.mul(coeff, &delta_inverse);
 
-    for (he, de) in h.iter_mut().zip(&domain.coeffs) {
-        let exp = BLS12_381_SCALAR.mul(de.clone(), &coeff);
-        *he = g1.mul_scalar(&exp);
+    for he in h.iter_mut() {
+        *he = g1.mul_scalar(&Scalar::from(coeff.clone()));
+        coeff = BLS12_381_SCALAR.mul(coeff, &toxic_waste.tau.0);
     }
 
-    // Use inverse FFT to convert powers of tau to Lagrange coefficients
-    domain.ifft();
-
-    let powers_of_tau = domain.coeffs;
+    // Lagrange coefficients at tau, directly rather than by an inverse FFT of the powers
+    let powers_of_tau = domain.evaluate_all_lagrange_coefficients(&toxic_waste.tau.0);
 
     // QAP A polynomial commitments for public and private variables
     let mut qap_a_commitments =
@@ -145,52 +231,87 @@ pub fn generate_proving_parameters<C: Circuit, R: RngCore>(
     // Ensure all private variable commitments are non-zero
     for e in private_commitments.iter() {
         if e.is_identity() {
-            return Err(anyhow::anyhow!("Unconstrained variable"));
+            return Err(Groth16Error::UnconstrainedVariable);
         }
     }
 
+    // Committed variables are divided by γ instead of δ and leave the L query
+    let commitment = match committed {
+        Some(committed) => {
+            let eta: Bls12_381ScalarField = rng.gen();
+            let delta_over_gamma =
+                Scalar::from(BLS12_381_SCALAR.mul(toxic_waste.delta.0.clone(), &gamma_inverse));
+            let bases = private_commitments
+                .drain(..committed)
+                .map(|p| p.mul_scalar(&delta_over_gamma))
+                .collect::<Vec<_>>();
+            let [blinding_g1, blinding_delta_g1]: [G1Affine; 2] = G1Projective::batch_normalize(&[
+                g1.mul_scalar(&Scalar::from(
+                    BLS12_381_SCALAR.mul(eta.0.clone(), &gamma_inverse),
+                )),
+                g1.mul_scalar(&Scalar::from(
+                    BLS12_381_SCALAR.mul(eta.0.clone(), &delta_inverse),
+                )),
+            ])
+            .try_into()
+            .expect("two points in, two points out");
+
+            Some(CommitmentKey {
+                bases: G1Projective::batch_normalize(&bases),
+                blinding_g1,
+                blinding_delta_g1,
+            })
+        }
+        None => None,
+    };
+
+    // Convert everything to affine, one field inversion per batch
+    let [alpha_g1, beta_g1, delta_g1]: [G1Affine; 3] = G1Projective::batch_normalize(&[
+        g1.mul_scalar(&toxic_waste.alpha),
+        g1.mul_scalar(&toxic_waste.beta),
+        g1.mul_scalar(&toxic_waste.delta),
+    ])
+    .try_into()
+    .expect("three points in, three points out");
+    let [beta_g2, gamma_g2, delta_g2]: [G2Affine; 3] = G2Projective::batch_normalize(&[
+        g2.mul_scalar_gls(&toxic_waste.beta),
+        g2.mul_scalar_gls(&toxic_waste.gamma),
+        g2.mul_scalar_gls(&toxic_waste.delta),
+    ])
+    .try_into()
+    .expect("three points in, three points out");
+
     // Create verification key
     let vk = VerificationKey {
-        alpha_g1: g1.mul_scalar(&toxic_waste.alpha.0).to_affine(),
-        beta_g1: g1.mul_scalar(&toxic_waste.beta.0).to_affine(),
-        beta_g2: g2.mul_scalar(&toxic_waste.beta.0).to_affine(),
-        gamma_g2: g2.mul_scalar(&toxic_waste.gamma.0).to_affine(),
-        delta_g1: g1.mul_scalar(&toxic_waste.delta.0).to_affine(),
-        delta_g2: g2.mul_scalar(&toxic_waste.delta.0).to_affine(),
-        ic: public_commitments.iter().map(|e| e.to_affine()).collect(),
+        alpha_g1,
+        beta_g1,
+        beta_g2,
+        gamma_g2,
+        delta_g1,
+        delta_g2,
+        ic: G1Projective::batch_normalize(&public_commitments),
     };
 
+    // The A and B queries skip the variables that never appear in them
+    qap_a_commitments.retain(|e| !e.is_identity());
+    qap_b_g1_commitments.retain(|e| !e.is_identity());
+    qap_b_g2_commitments.retain(|e| !e.is_identity());
+
     // Create proving parameters
     let pk = ProvingParameters {
         vk: vk.clone(),
-        h_query: Arc::new(h.iter().map(|e| e.to_affine()).collect()),
-        l_query: Arc::new(private_commitments.iter().map(|e| e.to_affine()).collect()),
-        a_query: Arc::new(
-            qap_a_commitments
-                .into_iter()
-                .filter(|e| !e.is_identity())
-                .map(|e| e.to_affine())
-                .collect(),
-        ),
-        b_g1_query: Arc::new(
-            qap_b_g1_commitments
-                .into_iter()
-                .filter(|e| !e.is_identity())
-                .map(|e| e.to_affine())
-                .collect(),
-        ),
-        b_g2_query: Arc::new(
-            qap_b_g2_commitments
-                .into_iter()
-                .filter(|e| !e.is_identity())
-                .map(|e| e.to_affine())
-                .collect(),
-        ),
+        h_query: prepare_bases(&h),
+        l_query: prepare_bases(&private_commitments),
+        a_query: prepare_bases(&qap_a_commitments),
+        b_g1_query: prepare_bases(&qap_b_g1_commitments),
+        b_g2_query: prepare_bases_g2(&qap_b_g2_commitments),
+        commitment,
     };
 
     Ok(pk)
 }
 
+#[allow(clippy::too_many_arguments)]
 fn compute_polynomial_commitments(
     powers_of_tau: &[Integer],
     at: &[Vec<(Integer, usize)>],
@@ -206,39 +327,169 @@ fn compute_polynomial_commitments(
     g1: G1Projective,
     g2: G2Projective,
 ) {
-    for ((((((a, b_g1), b_g2), ext), at), bt), ct) in a
-        .iter_mut()
-        .zip(b_g1.iter_mut())
-        .zip(b_g2.iter_mut())
-        .zip(ext.iter_mut())
-        .zip(at.iter())
-        .zip(bt.iter())
-        .zip(ct.iter())
-    {
-        let eval_at_tau = |p: &[(Integer, usize)]| {
-            p.iter().fold(Integer::ZERO, |mut acc, (coeff, index)| {
-                let mut term = powers_of_tau[*index].clone();
-                term = BLS12_381_SCALAR.mul(term, coeff);
-                acc = BLS12_381_SCALAR.add(acc, &term);
-                acc
+    // one task per variable; dense variables split their sums further
+    a.par_iter_mut()
+        .zip(b_g1.par_iter_mut())
+        .zip(b_g2.par_iter_mut())
+        .zip(ext.par_iter_mut())
+        .zip(at.par_iter())
+        .zip(bt.par_iter())
+        .zip(ct.par_iter())
+        .for_each(|((((((a, b_g1), b_g2), ext), at), bt), ct)| {
+            let mut at_eval = eval_at_tau(powers_of_tau, at);
+            let mut bt_eval = eval_at_tau(powers_of_tau, bt);
+            let ct_eval = eval_at_tau(powers_of_tau, ct);
+
+            *a = g1.mul_scalar(&Scalar::from(at_eval.clone()));
+            *b_g1 = g1.mul_scalar(&Scalar::from(bt_eval.clone()));
+            *b_g2 = g2.mul_scalar_gls(&Scalar::from(bt_eval.clone()));
+
+            at_eval = BLS12_381_SCALAR.mul(at_eval, beta);
+            bt_eval = BLS12_381_SCALAR.mul(bt_eval, alpha);
+
+            let mut e = BLS12_381_SCALAR.add(at_eval, &bt_eval);
+            e = BLS12_381_SCALAR.add(e, &ct_eval);
+            e = BLS12_381_SCALAR.mul(e, inv);
+
+            *ext = g1.mul_scalar(&Scalar::from(e));
+        });
+}
+
+/// Terms of a polynomial summed by one task in `eval_at_tau`.
+const EVAL_CHUNK: usize = 1 << 10;
+
+/// sum_i coeff_i * tau^index_i. Chunks of `EVAL_CHUNK` terms are summed in
+/// parallel and the partial sums added in chunk order, so the result does not
+/// depend on the number of threads.
+fn eval_at_tau(powers_of_tau: &[Integer], p: &[(Integer, usize)]) -> Integer {
+    let partial_sums = p
+        .par_chunks(EVAL_CHUNK)
+        .map(|chunk| {
+            chunk.iter().fold(Integer::ZERO, |acc, (coeff, index)| {
+                let term = BLS12_381_SCALAR.mul(powers_of_tau[*index].clone(), coeff);
+                BLS12_381_SCALAR.add(acc, &term)
             })
-        };
+        })
+        .collect::<Vec<_>>();
 
-        let mut at_eval = eval_at_tau(at);
-        let mut bt_eval = eval_at_tau(bt);
-        let ct_eval = eval_at_tau(ct);
+    partial_sums
+        .iter()
+        .fold(Integer::ZERO, |acc, sum| BLS12_381_SCALAR.add(acc, sum))
+}
 
-        *a = g1.mul_scalar(&at_eval);
-        *b_g1 = g1.mul_scalar(&bt_eval);
-        *b_g2 = g2.mul_scalar(&bt_eval);
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constraints::ConstraintSystemTrait;
+    use crate::params_source::file::PointEncoding;
+    use zkper_rand::TestRng;
 
-        at_eval = BLS12_381_SCALAR.mul(at_eval, beta);
-        bt_eval = BLS12_381_SCALAR.mul(bt_eval, alpha);
+    /// x^(rounds + 1) = y for a public y, one multiplication per round.
+    struct Chain {
+        rounds: usize,
+    }
+
+    impl Circuit for Chain {
+        fn synthesize<CS: ConstraintSystemTrait>(&self, cs: &mut CS) -> Result<(), Groth16Error> {
+            let x = cs.alloc_private(|| Ok(Scalar::from(Integer::from(2))))?;
+            let mut acc = x;
+            for _ in 0..self.rounds {
+                let next = cs.alloc_private(|| Ok(Scalar::from(Integer::from(4))))?;
+                cs.enforce(
+                    LinearCombination::new_variable(acc),
+                    LinearCombination::new_variable(x),
+                    LinearCombination::new_variable(next),
+                );
+                acc = next;
+            }
+            let y = cs.alloc_public(|| Ok(Scalar::from(Integer::from(8))))?;
+            cs.enforce(
+                LinearCombination::new_variable(acc),
+                LinearCombination::new_variable(ConstraintSystem::one()),
+                LinearCombination::new_variable(y),
+            );
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_analyze() {
+        let circuit = Chain { rounds: 5 };
+        let stats = analyze(&circuit).unwrap();
+
+        // 6 circuit constraints and one per public input: one, y, context
+        assert_eq!(stats.num_constraints, 9);
+        assert_eq!((stats.num_public_inputs, stats.num_private_inputs), (3, 6));
+        assert_eq!(stats.domain_size, 12);
+        assert_eq!(
+            (stats.a.nonzeros, stats.b.nonzeros, stats.c.nonzeros),
+            (9, 6, 6)
+        );
+        assert_eq!(stats.a.density, 9.0 / 81.0);
+
+        let mut rng = TestRng::new();
+        let params = generate_proving_parameters(circuit, &mut rng).unwrap();
+        assert_eq!(params.vk.ic.len(), stats.num_public_inputs);
+        assert_eq!(params.h_query.len(), stats.h_query_len());
+        assert_eq!(params.l_query.len(), stats.num_private_inputs);
+        assert_eq!(params.a_query.len(), stats.a.variables);
+        assert_eq!(params.b_g1_query.len(), stats.b.variables);
+        assert_eq!(params.b_g2_query.len(), stats.b.variables);
+
+        for encoding in [PointEncoding::Uncompressed, PointEncoding::Compressed] {
+            let mut bytes = vec![];
+            params.write_with_encoding(&mut bytes, encoding).unwrap();
+            assert_eq!(bytes.len(), stats.parameter_bytes(encoding));
+        }
+        assert!(stats.to_string().contains("domain size: 12"));
+    }
+
+    #[cfg(feature = "zeroize")]
+    #[test]
+    fn test_toxic_waste_zeroize() {
+        let mut rng = TestRng::new();
+        let mut waste = ToxicWaste::sample(&mut rng);
+        assert!(!waste.tau.0.is_zero());
+        let capacity = waste.tau.0.capacity();
+
+        waste.zeroize();
+        // wiped in place, not by swapping in a fresh allocation
+        assert_eq!(waste.tau.0.capacity(), capacity);
+        for value in [
+            &waste.alpha,
+            &waste.beta,
+            &waste.gamma,
+            &waste.delta,
+            &waste.tau,
+        ] {
+            assert!(value.0.is_zero());
+        }
+    }
 
-        let mut e = BLS12_381_SCALAR.add(at_eval, &bt_eval);
-        e = BLS12_381_SCALAR.add(e, &ct_eval);
-        e = BLS12_381_SCALAR.mul(e, inv);
+    #[test]
+    fn test_eval_at_tau() {
+        let mut rng = TestRng::new();
+        let powers_of_tau = (0..64)
+            .map(|_| BLS12_381_SCALAR.sample_raw(&mut rng))
+            .collect::<Vec<_>>();
+
+        // a dense variable spanning several chunks
+        let p = (0..3 * EVAL_CHUNK + 5)
+            .map(|i| {
+                (
+                    BLS12_381_SCALAR.sample_raw(&mut rng),
+                    i % powers_of_tau.len(),
+                )
+            })
+            .collect::<Vec<_>>();
+        let serial = p.iter().fold(Integer::ZERO, |acc, (coeff, index)| {
+            BLS12_381_SCALAR.add(
+                acc,
+                &BLS12_381_SCALAR.mul(powers_of_tau[*index].clone(), coeff),
+            )
+        });
 
-        *ext = g1.mul_scalar(&e);
+        assert_eq!(eval_at_tau(&powers_of_tau, &p), serial);
+        assert_eq!(eval_at_tau(&powers_of_tau, &[]), 0);
     }
 }
@@ -0,0 +1,76 @@
+//! Hash functions for transcripts and fingerprints.
+//!
+//! Everything this crate hashes, the context input, the batch verification
+//! transcript and the setup attestation, goes through [`TranscriptHash`], so
+//! the same transcript code can run over the hash a protocol calls for:
+//! [`Sha256Hash`] by default, [`Keccak256Hash`] where the result is checked on
+//! an EVM chain, which only has Keccak-256 natively, and [`Blake3Hash`] where
+//! speed matters and nothing on chain needs to recompute it.
+//!
+//! The trait and SHA-256 come from `zkper_base::transcript`, whose
+//! `Transcript` chains any of these hashes.
+
+use sha2::Digest;
+
+pub use zkper_base::transcript::{Digest32, Sha256Hash, TranscriptHash};
+
+/// Keccak-256 as used by Ethereum, not the standardized SHA3-256.
+#[derive(Clone, Default)]
+pub struct Keccak256Hash(sha3::Keccak256);
+
+impl TranscriptHash for Keccak256Hash {
+    const NAME: &'static str = "keccak256";
+
+    fn update(&mut self, bytes: impl AsRef<[u8]>) {
+        self.0.update(bytes);
+    }
+
+    fn finalize(self) -> Digest32 {
+        self.0.finalize().into()
+    }
+}
+
+/// BLAKE3 with its default 32-byte output.
+#[derive(Clone, Default)]
+pub struct Blake3Hash(blake3::Hasher);
+
+impl TranscriptHash for Blake3Hash {
+    const NAME: &'static str = "blake3";
+
+    fn update(&mut self, bytes: impl AsRef<[u8]>) {
+        self.0.update(bytes.as_ref());
+    }
+
+    fn finalize(self) -> Digest32 {
+        self.0.finalize().into()
+    }
+}
+
+#[cfg(all(test, feature = "backend-rug"))]
+mod tests {
+    use super::*;
+    use crate::attestation::to_hex;
+
+    #[test]
+    fn test_transcript_hashes() {
+        // digests of the empty string
+        assert_eq!(
+            to_hex(&Sha256Hash::digest(b"")),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+        assert_eq!(
+            to_hex(&Keccak256Hash::digest(b"")),
+            "c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470"
+        );
+        assert_eq!(
+            to_hex(&Blake3Hash::digest(b"")),
+            "af1349b9f5f9a1a6a0404dea36dcc9499bcb25c9adc112b7cc9a93cae41f3262"
+        );
+
+        // incremental updates match a single one
+        let mut hasher = Keccak256Hash::default();
+        hasher.update(b"ab");
+        hasher.update(b"c");
+        assert_eq!(hasher.finalize(), Keccak256Hash::digest(b"abc"));
+    }
+}
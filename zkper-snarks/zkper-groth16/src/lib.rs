@@ -1,9 +1,50 @@
+#[cfg(not(any(feature = "backend-rug", feature = "backend-native")))]
+compile_error!(
+    "zkper-groth16 needs at least one of the `backend-rug` or `backend-native` features"
+);
+
+#[cfg(feature = "backend-rug")]
+pub mod attestation;
+#[cfg(feature = "backend-rug")]
+pub mod batched;
+#[cfg(feature = "backend-rug")]
+pub mod builder;
+#[cfg(feature = "backend-rug")]
+pub mod circom;
+#[cfg(feature = "backend-rug")]
 pub mod circuit;
+#[cfg(feature = "backend-rug")]
 pub mod constraints;
+pub mod context;
+pub mod error;
+#[cfg(feature = "backend-rug")]
 pub mod evaluation_domain;
+#[cfg(feature = "backend-rug")]
+pub mod field;
+#[cfg(feature = "backend-rug")]
 pub mod generator;
+pub mod hash;
+#[cfg(feature = "backend-rug")]
+pub mod mock;
+#[cfg(feature = "backend-rug")]
 pub mod models;
+#[cfg(feature = "backend-rug")]
 pub mod multiexp;
+#[cfg(feature = "backend-native")]
+pub mod native;
+#[cfg(feature = "backend-rug")]
+pub mod outsourcing;
+#[cfg(feature = "backend-rug")]
 pub mod pairing;
+#[cfg(feature = "backend-rug")]
+pub mod parallel;
+#[cfg(feature = "backend-rug")]
+pub mod params_source;
+#[cfg(feature = "backend-rug")]
+pub mod polynomial;
+#[cfg(feature = "backend-rug")]
 pub mod prover;
+#[cfg(feature = "backend-rug")]
+pub mod solidity;
+#[cfg(feature = "backend-rug")]
 pub mod verifier;
@@ -0,0 +1,407 @@
+//! Checking circuits without proving them.
+//!
+//! A [`MockSystem`] is a [`Backend`] that keeps every value and every
+//! constraint and can then say which constraint, if any, the assignment
+//! breaks. Over a [`SmallField`](crate::field::SmallField) it runs gadget
+//! logic in a fraction of the time a real setup and proof would take.
+//!
+//! [`MockProver`] does the same for a [`Circuit`] and its expected public
+//! inputs, reporting every failure at once instead of the first one.
+
+use rug::Integer;
+use thiserror::Error;
+use zkper_curves::curves::bls12_381::{Scalar, BLS12_381_SCALAR};
+
+use crate::{
+    builder::{Backend, BuilderCircuit, CircuitBuilder},
+    circuit::Circuit,
+    constraints::{linear_combination::LinearCombination, ConstraintSystemTrait, Mode, Variable},
+    error::Groth16Error,
+    field::{CircuitField, Value},
+};
+
+/// A constraint system over `F` that records values instead of producing a
+/// proof.
+#[derive(Debug)]
+pub struct MockSystem<F: CircuitField> {
+    public_assignment: Vec<Value<F>>,
+    private_assignment: Vec<Value<F>>,
+    constraints: Vec<(LinearCombination, LinearCombination, LinearCombination)>,
+}
+
+impl<F: CircuitField> Default for MockSystem<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: CircuitField> MockSystem<F> {
+    /// An empty system holding only the constant one.
+    pub fn new() -> Self {
+        Self {
+            public_assignment: vec![F::one()],
+            private_assignment: vec![],
+            constraints: vec![],
+        }
+    }
+
+    /// Runs `circuit` against a new system. Every value has to be known.
+    pub fn synthesize<C: BuilderCircuit<F>>(circuit: &C) -> anyhow::Result<Self> {
+        let mut cs = Self::new();
+        circuit.build(&mut CircuitBuilder::new(&mut cs))?;
+        Ok(cs)
+    }
+
+    pub fn num_constraints(&self) -> usize {
+        self.constraints.len()
+    }
+
+    /// The public inputs, without the leading constant one.
+    pub fn public_inputs(&self) -> &[Value<F>] {
+        &self.public_assignment[1..]
+    }
+
+    /// The value of `lc` under the current assignment.
+    pub fn eval(&self, lc: &LinearCombination) -> Value<F> {
+        lc.0.iter().fold(F::zero(), |acc, (variable, coeff)| {
+            let value = match *variable {
+                Variable::Public(id) => &self.public_assignment[id],
+                Variable::Private(id) => &self.private_assignment[id],
+            };
+            F::add(&acc, &F::mul(&F::from_coeff(coeff), value))
+        })
+    }
+
+    /// The index of the first constraint a * b = c that does not hold.
+    pub fn which_is_unsatisfied(&self) -> Option<usize> {
+        self.constraints
+            .iter()
+            .position(|(a, b, c)| F::mul(&self.eval(a), &self.eval(b)) != self.eval(c))
+    }
+
+    pub fn is_satisfied(&self) -> bool {
+        self.which_is_unsatisfied().is_none()
+    }
+}
+
+impl<F: CircuitField> Backend for MockSystem<F> {
+    type Field = F;
+
+    fn alloc_private(&mut self, value: Option<&Value<F>>) -> anyhow::Result<Variable> {
+        let value = value.ok_or(Groth16Error::MissingAssignment("private variable"))?;
+        self.private_assignment.push(value.clone());
+        Ok(Variable::Private(self.private_assignment.len() - 1))
+    }
+
+    fn alloc_public(&mut self, value: Option<&Value<F>>) -> anyhow::Result<Variable> {
+        let value = value.ok_or(Groth16Error::MissingAssignment("public input"))?;
+        self.public_assignment.push(value.clone());
+        Ok(Variable::Public(self.public_assignment.len() - 1))
+    }
+
+    fn enforce(&mut self, a: LinearCombination, b: LinearCombination, c: LinearCombination) {
+        self.constraints.push((a, b, c));
+    }
+}
+
+/// Why [`MockProver::verify`] rejects a circuit.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum VerifyFailure {
+    /// Constraint `index` evaluates to a * b != c.
+    #[error("constraint {index} is not satisfied: {a} * {b} != {c}")]
+    ConstraintNotSatisfied {
+        index: usize,
+        a: Scalar,
+        b: Scalar,
+        c: Scalar,
+    },
+    /// The circuit allocates a different number of public inputs than were
+    /// expected.
+    #[error("circuit has {actual} public inputs, expected {expected}")]
+    PublicInputCount { expected: usize, actual: usize },
+    /// Public input `index` differs from the expected value.
+    #[error("public input {index} is {actual}, expected {expected}")]
+    PublicInputMismatch {
+        index: usize,
+        expected: Scalar,
+        actual: Scalar,
+    },
+    /// Private variable `index` appears in no constraint, which parameter
+    /// generation rejects.
+    #[error("private variable {0} is unconstrained")]
+    UnconstrainedVariable(usize),
+}
+
+/// Runs a [`Circuit`] with its witness and checks the constraints directly,
+/// without parameters, FFTs or pairings.
+///
+/// Public inputs are those of the circuit, without the constant one and the
+/// context input the prover appends.
+#[derive(Debug)]
+pub struct MockProver {
+    public_assignment: Vec<Scalar>,
+    private_assignment: Vec<Scalar>,
+    constraints: Vec<(LinearCombination, LinearCombination, LinearCombination)>,
+    expected_inputs: Vec<Scalar>,
+}
+
+impl MockProver {
+    /// Synthesizes `circuit`, computing every value. Fails only when the
+    /// circuit itself does, for instance on a missing witness.
+    pub fn run<C: Circuit>(circuit: &C, public_inputs: &[Scalar]) -> Result<Self, Groth16Error> {
+        let mut prover = MockProver {
+            public_assignment: vec![Scalar::from(Integer::from(1))],
+            private_assignment: vec![],
+            constraints: vec![],
+            expected_inputs: public_inputs.to_vec(),
+        };
+        circuit.synthesize(&mut prover)?;
+        Ok(prover)
+    }
+
+    pub fn num_constraints(&self) -> usize {
+        self.constraints.len()
+    }
+
+    /// The public inputs the circuit computed, without the constant one.
+    pub fn public_inputs(&self) -> &[Scalar] {
+        &self.public_assignment[1..]
+    }
+
+    /// The value of `lc` under the witness.
+    pub fn eval(&self, lc: &LinearCombination) -> Scalar {
+        let value = lc.0.iter().fold(Integer::ZERO, |acc, (variable, coeff)| {
+            let value = match *variable {
+                Variable::Public(id) => &self.public_assignment[id],
+                Variable::Private(id) => &self.private_assignment[id],
+            };
+            let term = BLS12_381_SCALAR.mul(BLS12_381_SCALAR.reduce(coeff), &value.0);
+            BLS12_381_SCALAR.add(acc, &term)
+        });
+        Scalar::from(value)
+    }
+
+    /// Every failure of the circuit: public inputs first, then unsatisfied
+    /// constraints and unconstrained variables in order.
+    pub fn verify(&self) -> Result<(), Vec<VerifyFailure>> {
+        let mut failures = vec![];
+
+        let actual = self.public_inputs();
+        if actual.len() != self.expected_inputs.len() {
+            failures.push(VerifyFailure::PublicInputCount {
+                expected: self.expected_inputs.len(),
+                actual: actual.len(),
+            });
+        }
+        for (index, (expected, actual)) in self.expected_inputs.iter().zip(actual).enumerate() {
+            // inputs are compared as field elements
+            let expected = Scalar::from(BLS12_381_SCALAR.reduce(&expected.0));
+            if expected != *actual {
+                failures.push(VerifyFailure::PublicInputMismatch {
+                    index,
+                    expected,
+                    actual: actual.clone(),
+                });
+            }
+        }
+
+        let mut constrained = vec![false; self.private_assignment.len()];
+        for (index, (a, b, c)) in self.constraints.iter().enumerate() {
+            for (variable, _) in a.0.iter().chain(&b.0).chain(&c.0) {
+                if let Variable::Private(id) = *variable {
+                    constrained[id] = true;
+                }
+            }
+
+            let (a, b, c) = (self.eval(a), self.eval(b), self.eval(c));
+            if BLS12_381_SCALAR.mul(a.0.clone(), &b.0) != c.0 {
+                failures.push(VerifyFailure::ConstraintNotSatisfied { index, a, b, c });
+            }
+        }
+        failures.extend(
+            constrained
+                .iter()
+                .enumerate()
+                .filter(|(_, constrained)| !**constrained)
+                .map(|(index, _)| VerifyFailure::UnconstrainedVariable(index)),
+        );
+
+        if failures.is_empty() {
+            Ok(())
+        } else {
+            Err(failures)
+        }
+    }
+
+    /// Panics listing every failure unless the circuit is satisfied.
+    pub fn assert_satisfied(&self) {
+        if let Err(failures) = self.verify() {
+            let report = failures
+                .iter()
+                .map(|failure| format!("  {}", failure))
+                .collect::<Vec<_>>()
+                .join("\n");
+            panic!("circuit is not satisfied:\n{}", report);
+        }
+    }
+}
+
+impl ConstraintSystemTrait for MockProver {
+    fn mode(&self) -> Mode {
+        Mode::Prove
+    }
+
+    fn alloc_private<F>(&mut self, value: F) -> Result<Variable, Groth16Error>
+    where
+        F: FnOnce() -> anyhow::Result<Scalar>,
+    {
+        self.private_assignment
+            .push(value().map_err(Groth16Error::synthesis)?);
+        Ok(Variable::Private(self.private_assignment.len() - 1))
+    }
+
+    fn alloc_public<F>(&mut self, value: F) -> Result<Variable, Groth16Error>
+    where
+        F: FnOnce() -> anyhow::Result<Scalar>,
+    {
+        self.public_assignment
+            .push(value().map_err(Groth16Error::synthesis)?);
+        Ok(Variable::Public(self.public_assignment.len() - 1))
+    }
+
+    fn enforce(&mut self, a: LinearCombination, b: LinearCombination, c: LinearCombination) {
+        self.constraints.push((a, b, c));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::field::{Fr, Mersenne31};
+    use zkper_curves::curves::bls12_381::Bls12_381ScalarField;
+
+    // x * (x - 1) = y, for whichever field the test picks
+    fn gadget<F: CircuitField>(x: u64, y: u64) -> MockSystem<F> {
+        let mut cs = MockSystem::<F>::new();
+        let mut builder = CircuitBuilder::new(&mut cs);
+        let x = builder.alloc(Some(F::from_u64(x))).unwrap();
+        let y = builder.alloc_input(Some(F::from_u64(y))).unwrap();
+        let one = builder.one();
+        let x_minus_one = builder.sub(&x, &one).unwrap();
+        let product = builder.mul(&x, &x_minus_one).unwrap();
+        builder.enforce_equal(&product, &y);
+        cs
+    }
+
+    #[test]
+    fn test_mock_system() {
+        let cs = gadget::<Mersenne31>(7, 42);
+        assert_eq!(cs.num_constraints(), 3);
+        assert_eq!(cs.public_inputs(), &[42]);
+        assert!(cs.is_satisfied());
+
+        // 0 - 1 wraps around in the small field as well
+        assert!(gadget::<Mersenne31>(0, 0).is_satisfied());
+
+        // only the final equality breaks
+        assert_eq!(gadget::<Mersenne31>(7, 41).which_is_unsatisfied(), Some(2));
+
+        // the same gadget over the real field
+        assert!(gadget::<Fr>(7, 42).is_satisfied());
+        assert!(!gadget::<Fr>(7, 41).is_satisfied());
+
+        // a bit that is not 0 or 1
+        let mut cs = MockSystem::<Mersenne31>::new();
+        let mut builder = CircuitBuilder::new(&mut cs);
+        let two = builder.alloc(Some(2)).unwrap();
+        builder.enforce_boolean(&two);
+        assert_eq!(cs.which_is_unsatisfied(), Some(0));
+
+        assert!(MockSystem::<Mersenne31>::new().alloc_private(None).is_err());
+    }
+
+    /// x * x = y for a private x and a public y, plus an unused private
+    /// variable when `dangling`.
+    struct Square {
+        x: u64,
+        y: u64,
+        dangling: bool,
+    }
+
+    impl Circuit for Square {
+        fn synthesize<CS: ConstraintSystemTrait>(&self, cs: &mut CS) -> Result<(), Groth16Error> {
+            let x = cs.alloc_private(|| Ok(Scalar::from(Integer::from(self.x))))?;
+            let y = cs.alloc_public(|| Ok(Scalar::from(Integer::from(self.y))))?;
+            cs.enforce(
+                LinearCombination::new_variable(x),
+                LinearCombination::new_variable(x),
+                LinearCombination::new_variable(y),
+            );
+            if self.dangling {
+                cs.alloc_private(|| Ok(Scalar::from(Integer::from(0))))?;
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_mock_prover() {
+        let scalar = |v: u64| Scalar::from(Integer::from(v));
+        let circuit = |x, y, dangling| Square { x, y, dangling };
+
+        let prover = MockProver::run(&circuit(3, 9, false), &[scalar(9)]).unwrap();
+        assert_eq!(prover.num_constraints(), 1);
+        assert_eq!(prover.public_inputs(), &[scalar(9)]);
+        assert_eq!(prover.verify(), Ok(()));
+        prover.assert_satisfied();
+
+        // a wrong witness and a public input other than the expected one
+        let prover = MockProver::run(&circuit(3, 10, true), &[scalar(9)]).unwrap();
+        assert_eq!(
+            prover.verify(),
+            Err(vec![
+                VerifyFailure::PublicInputMismatch {
+                    index: 0,
+                    expected: scalar(9),
+                    actual: scalar(10),
+                },
+                VerifyFailure::ConstraintNotSatisfied {
+                    index: 0,
+                    a: scalar(3),
+                    b: scalar(3),
+                    c: scalar(10),
+                },
+                VerifyFailure::UnconstrainedVariable(1),
+            ])
+        );
+
+        let prover = MockProver::run(&circuit(3, 9, false), &[]).unwrap();
+        assert_eq!(
+            prover.verify(),
+            Err(vec![VerifyFailure::PublicInputCount {
+                expected: 0,
+                actual: 1
+            }])
+        );
+
+        // expected inputs are compared modulo r
+        let wrapped = Bls12_381ScalarField(BLS12_381_SCALAR.modulus() + Integer::from(9));
+        assert!(MockProver::run(&circuit(3, 9, false), &[wrapped])
+            .unwrap()
+            .verify()
+            .is_ok());
+    }
+
+    #[test]
+    #[should_panic(expected = "constraint 0 is not satisfied")]
+    fn test_mock_prover_assert_satisfied() {
+        let circuit = Square {
+            x: 2,
+            y: 5,
+            dangling: false,
+        };
+        MockProver::run(&circuit, &[Scalar::from(Integer::from(5))])
+            .unwrap()
+            .assert_satisfied();
+    }
+}
@@ -0,0 +1,85 @@
+//! LegoGroth16 commitments to a slice of the witness.
+//!
+//! Parameters generated with a commitment key move the first private
+//! variables of the circuit out of the L query: their terms are divided by γ
+//! instead of δ and make up D, a Pedersen commitment
+//!
+//! D = sum_j w_j * (β u_j(τ) + α v_j(τ) + w_j(τ)) / γ + v * η / γ
+//!
+//! to their values w_j with opening v. The prover subtracts v * η / δ from C,
+//! and the verifier checks
+//!
+//! e(A, B) = e(α, β) * e(IC(x) + D, γ) * e(C, δ).
+//!
+//! Anyone holding the key and the opening can recompute D from the values,
+//! which links the proof to commitments made outside of it.
+
+use std::sync::Arc;
+
+use zkper_curves::curves::bls12_381::{curves::g1_affine::G1Affine, Scalar};
+
+use super::proof::Proof;
+use crate::{error::Groth16Error, multiexp::multiexp};
+
+/// Bases of the commitment D to the first `len` private variables.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CommitmentKey {
+    /// (β * u_j(τ) + α * v_j(τ) + w_j(τ)) / γ for each committed variable.
+    pub bases: Vec<G1Affine>,
+    /// η / γ, the base of the opening.
+    pub blinding_g1: G1Affine,
+    /// η / δ, subtracted from C by the prover to cancel the opening.
+    pub blinding_delta_g1: G1Affine,
+}
+
+impl CommitmentKey {
+    /// The number of committed private variables.
+    pub fn len(&self) -> usize {
+        self.bases.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bases.is_empty()
+    }
+
+    /// The commitment to `values` with `opening`, equal to the D of a proof
+    /// whose committed variables take these values.
+    pub fn commit(&self, values: &[Scalar], opening: &Scalar) -> Result<G1Affine, Groth16Error> {
+        if values.len() != self.len() {
+            return Err(Groth16Error::CommitmentLength {
+                expected: self.len(),
+                actual: values.len(),
+            });
+        }
+
+        let d = multiexp(
+            Arc::new(self.bases.clone()),
+            None,
+            None,
+            Arc::new(values.to_vec()),
+        )?;
+        Ok(d.add(&self.blinding_g1.to_curve().mul_scalar(opening))
+            .to_affine())
+    }
+}
+
+/// A proof together with its commitment D to part of the witness.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CommittedProof {
+    pub proof: Proof,
+    pub d: G1Affine,
+}
+
+impl CommittedProof {
+    /// `Proof::validate`, and D has to be a point of the prime-order
+    /// subgroup.
+    pub fn validate(&self) -> Result<(), Groth16Error> {
+        self.proof.validate()?;
+        if !self.d.is_on_curve() || !self.d.is_torsion_free() {
+            return Err(Groth16Error::InvalidProof(
+                "D is outside the prime-order subgroup",
+            ));
+        }
+        Ok(())
+    }
+}
@@ -1,3 +1,4 @@
+pub mod commitment;
 pub mod proof;
 pub mod proving_parameters;
 pub mod verification_key;
@@ -1,6 +1,20 @@
 use std::fmt::Display;
 
-use zkper_curves::curves::bls12_381::curves::{g1_affine::G1Affine, g2_affine::G2Affine};
+use rand::RngCore;
+use zkper_curves::curves::bls12_381::{
+    curves::{
+        g1_affine::{G1Affine, G1_COMPRESSED_BYTES},
+        g2_affine::{G2Affine, G2_COMPRESSED_BYTES},
+    },
+    Bls12_381ScalarField, Scalar, BLS12_381_SCALAR,
+};
+use zkper_curves::traits::field::FieldTrait;
+
+use super::verification_key::VerificationKey;
+use crate::error::Groth16Error;
+
+/// Size of an encoded [`Proof`].
+pub const PROOF_BYTES: usize = 2 * G1_COMPRESSED_BYTES + G2_COMPRESSED_BYTES;
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Proof {
@@ -9,18 +23,103 @@ pub struct Proof {
     pub c: G1Affine,
 }
 
+impl Proof {
+    /// Checks that every point is a non-identity element of its prime-order
+    /// subgroup. An identity point zeroes its pairing, and with the trapdoor
+    /// of a leaked or malicious key such degenerate proofs can balance the
+    /// verification equation on their own, so verifiers reject them up front.
+    pub fn validate(&self) -> Result<(), Groth16Error> {
+        let defect = if self.a.is_identity() {
+            Some("A is the identity")
+        } else if self.b.is_identity() {
+            Some("B is the identity")
+        } else if self.c.is_identity() {
+            Some("C is the identity")
+        } else if !self.a.is_on_curve() || !self.c.is_on_curve() || !self.b.is_on_curve() {
+            Some("a point is not on its curve")
+        } else if !self.a.is_torsion_free() || !self.c.is_torsion_free() {
+            Some("a G1 point is outside the prime-order subgroup")
+        } else if !self.b.is_torsion_free() {
+            Some("B is outside the prime-order subgroup")
+        } else {
+            None
+        };
+
+        match defect {
+            Some(defect) => Err(Groth16Error::InvalidProof(defect)),
+            None => Ok(()),
+        }
+    }
+
+    /// Whether the proof passes `validate`.
+    pub fn is_well_formed(&self) -> bool {
+        self.validate().is_ok()
+    }
+
+    /// A fresh proof of the same statement under `vk`, computed without the
+    /// witness. For random r1 != 0 and r2,
+    ///
+    /// A' = A / r1, B' = r1 * (B + r2 * delta), C' = C + r2 * A
+    ///
+    /// satisfies the verification equation whenever the proof does, and is
+    /// distributed like a proof made from scratch, so the two cannot be linked.
+    pub fn rerandomize<R: RngCore>(&self, vk: &VerificationKey, rng: &mut R) -> Proof {
+        let (r1, r1_inverse) = loop {
+            let r1 = Bls12_381ScalarField::random(rng);
+            if let Some(inverse) = BLS12_381_SCALAR.invert(r1.clone()) {
+                break (Scalar::from(r1), Scalar::from(inverse));
+            }
+        };
+        let r2 = Scalar::from(Bls12_381ScalarField::random(rng));
+
+        let a = self.a.to_curve();
+        let b = self
+            .b
+            .to_curve()
+            .add(&vk.delta_g2.to_curve().mul_scalar_gls(&r2))
+            .mul_scalar(&r1);
+        let c = self.c.to_curve().add(&a.mul_scalar(&r2));
+
+        Proof {
+            a: a.mul_scalar(&r1_inverse).to_affine(),
+            b: b.to_affine(),
+            c: c.to_affine(),
+        }
+    }
+}
+
 impl Display for Proof {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "a: {}\n, b: {}\n, c: {}", self.a, self.b, self.c)
     }
 }
 
-// impl Proof {
-//     pub fn to_bytes(&self) -> Vec<u8> {
-//         let mut bytes = vec![];
-//         bytes.extend_from_slice(&self.a.to_bytes());
-//         bytes.extend_from_slice(&self.b.to_bytes());
-//         bytes.extend_from_slice(&self.c.to_bytes());
-//         bytes
-//     }
-// }
+impl Proof {
+    /// Compressed A || compressed B || compressed C, the encoding
+    /// `crate::native::Proof::from_bytes` reads.
+    pub fn to_bytes(&self) -> [u8; PROOF_BYTES] {
+        let mut bytes = [0u8; PROOF_BYTES];
+        let (a, rest) = bytes.split_at_mut(G1_COMPRESSED_BYTES);
+        let (b, c) = rest.split_at_mut(G2_COMPRESSED_BYTES);
+        a.copy_from_slice(&self.a.to_compressed());
+        b.copy_from_slice(&self.b.to_compressed());
+        c.copy_from_slice(&self.c.to_compressed());
+        bytes
+    }
+
+    /// Inverse of `to_bytes`. Returns None for a wrong length and points
+    /// that do not decode; the subgroups are checked by `validate`.
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() != PROOF_BYTES {
+            return None;
+        }
+
+        let (a, rest) = bytes.split_at(G1_COMPRESSED_BYTES);
+        let (b, c) = rest.split_at(G2_COMPRESSED_BYTES);
+        Some(Self {
+            a: G1Affine::from_compressed(a)?,
+            b: G2Affine::from_compressed(b)?,
+            c: G1Affine::from_compressed(c)?,
+        })
+    }
+}
@@ -2,7 +2,7 @@ use std::sync::Arc;
 
 use zkper_curves::curves::bls12_381::curves::{g1_affine::G1Affine, g2_affine::G2Affine};
 
-use super::verification_key::VerificationKey;
+use super::{commitment::CommitmentKey, verification_key::VerificationKey};
 
 /// Parameters generated from the QAP for proving and verifying in the Groth16 zk-SNARK protocol.
 #[derive(Clone, Debug)]
@@ -14,8 +14,8 @@ pub struct ProvingParameters {
     /// Used in proving to ensure consistency of τ powers.
     pub h_query: Arc<Vec<G1Affine>>,
 
-    /// L query: (β * u_i(τ) + α * v_i(τ) + w_i(τ)) / δ for all auxiliary inputs.
-    /// Used in proving to handle auxiliary (private) inputs.
+    /// L query: (β * u_i(τ) + α * v_i(τ) + w_i(τ)) / δ for all auxiliary inputs
+    /// but the committed ones. Used in proving to handle auxiliary (private) inputs.
     pub l_query: Arc<Vec<G1Affine>>,
 
     /// A query: QAP "A" polynomials evaluated at τ in the Lagrange basis.
@@ -29,4 +29,8 @@ pub struct ProvingParameters {
     /// B G2 query: QAP "B" polynomials evaluated at τ in G2.
     /// Used in proving for the "B" part of the QAP in G2.
     pub b_g2_query: Arc<Vec<G2Affine>>,
+
+    /// Key of the commitment to the first private variables, for parameters
+    /// generated with `generate_proving_parameters_with_commitment`.
+    pub commitment: Option<CommitmentKey>,
 }
@@ -1,6 +1,14 @@
-use zkper_curves::curves::bls12_381::curves::{g1_affine::G1Affine, g2_affine::G2Affine};
+use zkper_curves::{
+    curves::bls12_381::curves::{
+        g1_affine::{G1Affine, G1_COMPRESSED_BYTES},
+        g2_affine::{G2Affine, G2_COMPRESSED_BYTES},
+    },
+    error::CurveError,
+};
 
-/// A verification key for the Groth16 zk-SNARK protocol.
+/// A verification key for the Groth16 zk-SNARK protocol, as produced by the
+/// setup. Besides the verifier's points it carries β and δ in G1, which only
+/// the prover needs. Ship [`VerifierKey`] to verifiers instead.
 #[derive(Clone, Debug)]
 pub struct VerificationKey {
     /// α in G1, used for verifying and creating A/C elements of the proof.
@@ -25,3 +33,151 @@ pub struct VerificationKey {
     /// These are in G1 and correspond to public input polynomials.
     pub ic: Vec<G1Affine>,
 }
+
+impl VerificationKey {
+    /// The part of the key a verifier needs.
+    pub fn verifier_key(&self) -> VerifierKey {
+        VerifierKey {
+            alpha_g1: self.alpha_g1.clone(),
+            beta_g2: self.beta_g2.clone(),
+            gamma_g2: self.gamma_g2.clone(),
+            delta_g2: self.delta_g2.clone(),
+            ic: self.ic.clone(),
+        }
+    }
+}
+
+impl From<&VerificationKey> for VerifierKey {
+    fn from(vk: &VerificationKey) -> Self {
+        vk.verifier_key()
+    }
+}
+
+/// Magic bytes at the start of an encoded [`VerifierKey`].
+pub const VERIFIER_KEY_MAGIC: &[u8; 8] = b"ZKPG16VK";
+
+/// The points of a verification key that verification reads, and nothing
+/// else: the artifact to distribute to verifiers.
+///
+/// ```text
+/// magic            8 bytes   "ZKPG16VK"
+/// alpha_g1         compressed G1
+/// beta_g2          compressed G2
+/// gamma_g2         compressed G2
+/// delta_g2         compressed G2
+/// ic               u64 big-endian length, then compressed G1 points
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VerifierKey {
+    /// α in G1.
+    pub alpha_g1: G1Affine,
+
+    /// β in G2.
+    pub beta_g2: G2Affine,
+
+    /// γ in G2.
+    pub gamma_g2: G2Affine,
+
+    /// δ in G2.
+    pub delta_g2: G2Affine,
+
+    /// IC elements, one per public input plus the constant one and the
+    /// context input.
+    pub ic: Vec<G1Affine>,
+}
+
+impl VerifierKey {
+    /// Encode the key in the format above.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = VERIFIER_KEY_MAGIC.to_vec();
+        out.extend_from_slice(&self.alpha_g1.to_compressed());
+        out.extend_from_slice(&self.beta_g2.to_compressed());
+        out.extend_from_slice(&self.gamma_g2.to_compressed());
+        out.extend_from_slice(&self.delta_g2.to_compressed());
+        out.extend_from_slice(&(self.ic.len() as u64).to_be_bytes());
+        for point in &self.ic {
+            out.extend_from_slice(&point.to_compressed());
+        }
+        out
+    }
+
+    /// Decode a key written by `to_bytes`, checking every point.
+    pub fn from_bytes(bytes: &[u8]) -> anyhow::Result<Self> {
+        let rest = bytes
+            .strip_prefix(VERIFIER_KEY_MAGIC)
+            .ok_or_else(|| anyhow::anyhow!("Not a verifier key"))?;
+
+        let (alpha_g1, rest) = split(rest, G1_COMPRESSED_BYTES)?;
+        let (beta_g2, rest) = split(rest, G2_COMPRESSED_BYTES)?;
+        let (gamma_g2, rest) = split(rest, G2_COMPRESSED_BYTES)?;
+        let (delta_g2, rest) = split(rest, G2_COMPRESSED_BYTES)?;
+        let (len, rest) = split(rest, 8)?;
+        let len = usize::try_from(u64::from_be_bytes(len.try_into()?))?;
+
+        let size = len
+            .checked_mul(G1_COMPRESSED_BYTES)
+            .ok_or_else(|| anyhow::anyhow!("IC length overflow"))?;
+        let (ic, rest) = split(rest, size)?;
+        if !rest.is_empty() {
+            return Err(anyhow::anyhow!("Trailing bytes in verifier key"));
+        }
+
+        let g1 = |bytes| G1Affine::from_compressed(bytes).ok_or(CurveError::InvalidPoint("G1"));
+        let g2 = |bytes| G2Affine::from_compressed(bytes).ok_or(CurveError::InvalidPoint("G2"));
+
+        Ok(Self {
+            alpha_g1: g1(alpha_g1)?,
+            beta_g2: g2(beta_g2)?,
+            gamma_g2: g2(gamma_g2)?,
+            delta_g2: g2(delta_g2)?,
+            ic: ic
+                .chunks(G1_COMPRESSED_BYTES)
+                .map(g1)
+                .collect::<Result<Vec<_>, _>>()?,
+        })
+    }
+}
+
+fn split(bytes: &[u8], n: usize) -> anyhow::Result<(&[u8], &[u8])> {
+    if bytes.len() < n {
+        return Err(anyhow::anyhow!("Unexpected end of verifier key"));
+    }
+    Ok(bytes.split_at(n))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zkper_curves::curves::bls12_381::curves::g1::G1Projective;
+    use zkper_rand::TestRng;
+
+    #[test]
+    fn test_verifier_key_round_trip() {
+        let mut rng = TestRng::new();
+        let vk = VerificationKey {
+            alpha_g1: G1Projective::random(&mut rng).to_affine(),
+            beta_g1: G1Affine::generator(),
+            beta_g2: G2Affine::generator(),
+            gamma_g2: G2Affine::generator().neg(),
+            delta_g1: G1Affine::generator(),
+            delta_g2: G2Affine::identity(),
+            ic: (0..3)
+                .map(|_| G1Projective::random(&mut rng).to_affine())
+                .collect(),
+        };
+
+        let key = vk.verifier_key();
+        let bytes = key.to_bytes();
+        assert_eq!(
+            bytes.len(),
+            8 + 4 * G1_COMPRESSED_BYTES + 3 * G2_COMPRESSED_BYTES + 8
+        );
+        assert_eq!(VerifierKey::from_bytes(&bytes).unwrap(), key);
+
+        assert!(VerifierKey::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+        let mut trailing = bytes.clone();
+        trailing.push(0);
+        assert!(VerifierKey::from_bytes(&trailing).is_err());
+        assert!(VerifierKey::from_bytes(&bytes[1..]).is_err());
+    }
+}
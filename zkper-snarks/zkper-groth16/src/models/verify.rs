@@ -1,13 +1,12 @@
-use zkper_curves::curves::bls12_381::{
-    curves::{g1_affine::G1Affine, g2_affine::G2Affine},
-    fields::target::TargetField,
-};
+use zkper_curves::curves::bls12_381::curves::{g1_affine::G1Affine, g2_affine::G2Affine};
 
 /// Preprocessed verification key for proof verification.
 #[derive(Clone, Debug)]
 pub struct PreparedVerifyingKey {
-    /// Pairing result of alpha_g1 and beta_g2.
-    pub alpha_g1_beta_g2: TargetField,
+    /// Negation of alpha_g1, so that e(-α, β) joins the pairing product.
+    pub neg_alpha_g1: G1Affine,
+    /// beta_g2, paired with neg_alpha_g1.
+    pub beta_g2: G2Affine,
     /// Negation of gamma_g2, prepared for pairing.
     pub neg_gamma_g2: G2Affine,
     /// Negation of delta_g2, prepared for pairing.
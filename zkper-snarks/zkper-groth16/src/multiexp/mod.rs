@@ -1,196 +1,579 @@
-use std::sync::Arc;
+use std::{
+    borrow::Cow,
+    collections::{hash_map::Entry, HashMap},
+    hash::Hash,
+    sync::Arc,
+};
 
 use rug::Integer;
-use zkper_curves::curves::bls12_381::curves::{
-    g1::G1Projective, g1_affine::G1Affine, g2::G2Projective, g2_affine::G2Affine,
+use zkper_curves::curves::bls12_381::{
+    curves::{g1::G1Projective, g1_affine::G1Affine, g2::G2Projective, g2_affine::G2Affine},
+    Scalar, BLS12_381_SCALAR,
 };
 
-use crate::prover::Density;
+use crate::{error::Groth16Error, parallel::Schedule, prover::DensityTracker};
 
-struct BaseSource {
-    bases: Arc<Vec<G1Affine>>,
-    current_index: usize,
-}
+pub mod params;
+pub mod prepare;
+
+pub use params::MultiexpConfig;
+pub use prepare::{partition_by_bit_length, prepare_bases, prepare_bases_g2, BitLengthClass};
+
+/// Pairs every non-zero exponent with the index of the base it is multiplied
+/// with. Bases are laid out from `bases_start_idx`: one per exponent, or with a
+/// density map one per used entry, found through its compacted index.
+fn select_bases<'a>(
+    num_bases: usize,
+    bases_start_idx: Option<usize>,
+    density_map: Option<&DensityTracker>,
+    exponents: &'a [Scalar],
+) -> Result<Vec<(usize, Cow<'a, Integer>)>, Groth16Error> {
+    let positions = density_map.map(|density_map| {
+        // If the density map has a known query size, it should not be
+        // inconsistent with the number of exponents.
+        assert_eq!(density_map.len(), exponents.len());
+        density_map.compacted_indices()
+    });
 
-impl BaseSource {
-    fn new(bases: Arc<Vec<G1Affine>>) -> Self {
-        BaseSource {
-            bases,
-            current_index: 0,
+    let start = bases_start_idx.unwrap_or(0);
+    let mut terms = vec![];
+    for (i, exponent) in exponents.iter().enumerate() {
+        let position = match &positions {
+            Some(positions) => match positions[i] {
+                Some(position) => position,
+                None => continue,
+            },
+            None => i,
+        };
+
+        if !exponent.0.is_zero() {
+            let index = start + position;
+            if index >= num_bases {
+                return Err(Groth16Error::OutOfBases);
+            }
+            terms.push((index, Cow::Borrowed(&exponent.0)));
         }
     }
 
-    fn new_with_start_idx(bases: Arc<Vec<G1Affine>>, start_idx: usize) -> Self {
-        BaseSource {
-            bases,
-            current_index: start_idx,
+    Ok(terms)
+}
+
+/// Merges terms whose bases are equal into one, adding their exponents mod r,
+/// and drops the terms whose exponents cancel out. The first occurrence of a
+/// base keeps its position.
+fn merge_repeated_bases<'a, B: Eq + Hash>(
+    bases: &[B],
+    terms: Vec<(usize, Cow<'a, Integer>)>,
+) -> Vec<(usize, Cow<'a, Integer>)> {
+    let mut positions: HashMap<&B, usize> = HashMap::with_capacity(terms.len());
+    let mut merged: Vec<(usize, Cow<'a, Integer>)> = Vec::with_capacity(terms.len());
+
+    for (index, exponent) in terms {
+        match positions.entry(&bases[index]) {
+            Entry::Occupied(position) => {
+                let sum = &mut merged[*position.get()].1;
+                *sum = Cow::Owned(BLS12_381_SCALAR.add(sum.as_ref().clone(), &exponent));
+            }
+            Entry::Vacant(position) => {
+                position.insert(merged.len());
+                merged.push((index, exponent));
+            }
         }
     }
 
-    fn next(&mut self) -> Option<&G1Affine> {
-        if self.current_index < self.bases.len() {
-            let base = &self.bases[self.current_index];
-            self.current_index += 1;
-            Some(base)
-        } else {
-            None
-        }
+    merged.retain(|(_, exponent)| !exponent.is_zero());
+    merged
+}
+
+/// Perform multi-exponentiation.
+pub fn multiexp(
+    bases: Arc<Vec<G1Affine>>,
+    bases_start_idx: Option<usize>,
+    density_map: Option<&DensityTracker>,
+    exponents: Arc<Vec<Scalar>>,
+) -> Result<G1Projective, Groth16Error> {
+    multiexp_with_config(
+        bases,
+        bases_start_idx,
+        density_map,
+        exponents,
+        &MultiexpConfig::default(),
+    )
+}
+
+/// Perform multi-exponentiation with explicit tuning parameters.
+pub fn multiexp_with_config(
+    bases: Arc<Vec<G1Affine>>,
+    bases_start_idx: Option<usize>,
+    density_map: Option<&DensityTracker>,
+    exponents: Arc<Vec<Scalar>>,
+    config: &MultiexpConfig,
+) -> Result<G1Projective, Groth16Error> {
+    let mut selected = select_bases(bases.len(), bases_start_idx, density_map, &exponents)?;
+    if config.dedup_bases() {
+        selected = merge_repeated_bases(&bases, selected);
     }
 
-    fn skip(&mut self, amt: usize) {
-        self.current_index += amt;
+    let terms = selected
+        .iter()
+        .map(|(index, exponent)| (G1Projective::from(&bases[*index]), exponent.as_ref()))
+        .collect::<Vec<_>>();
+
+    // a single term is a plain scalar multiplication
+    if let [(base, exponent)] = terms.as_slice() {
+        return Ok(mul_wnaf(base, &Scalar::from((*exponent).clone()), config));
     }
+
+    Ok(bucket_multiexp(
+        &terms,
+        config.window_size(terms.len()),
+        config.schedule(),
+    ))
 }
 
-struct BaseSourceG2 {
+/// Perform multi-exponentiation.
+pub fn multiexp_g2(
     bases: Arc<Vec<G2Affine>>,
-    current_index: usize,
+    bases_start_idx: Option<usize>,
+    density_map: Option<&DensityTracker>,
+    exponents: Arc<Vec<Scalar>>,
+) -> Result<G2Projective, Groth16Error> {
+    multiexp_g2_with_config(
+        bases,
+        bases_start_idx,
+        density_map,
+        exponents,
+        &MultiexpConfig::default(),
+    )
 }
 
-impl BaseSourceG2 {
-    fn new(bases: Arc<Vec<G2Affine>>) -> Self {
-        Self {
-            bases,
-            current_index: 0,
+/// Perform multi-exponentiation over G2 with explicit tuning parameters.
+pub fn multiexp_g2_with_config(
+    bases: Arc<Vec<G2Affine>>,
+    bases_start_idx: Option<usize>,
+    density_map: Option<&DensityTracker>,
+    exponents: Arc<Vec<Scalar>>,
+    config: &MultiexpConfig,
+) -> Result<G2Projective, Groth16Error> {
+    let mut selected = select_bases(bases.len(), bases_start_idx, density_map, &exponents)?;
+    if config.dedup_bases() {
+        selected = merge_repeated_bases(&bases, selected);
+    }
+
+    let terms = selected
+        .iter()
+        .map(|(index, exponent)| (G2Projective::from(&bases[*index]), exponent.as_ref()))
+        .collect::<Vec<_>>();
+
+    // a single term is a plain scalar multiplication
+    if let [(base, exponent)] = terms.as_slice() {
+        return Ok(mul_wnaf_g2(
+            base,
+            &Scalar::from((*exponent).clone()),
+            config,
+        ));
+    }
+
+    Ok(bucket_multiexp_g2(
+        &terms,
+        config.window_size(terms.len()),
+        config.schedule(),
+    ))
+}
+
+/// The `c`-bit window of `exponent` starting at bit `offset`.
+fn window_value(exponent: &Integer, offset: u32, c: usize) -> usize {
+    let mut window = Integer::from(exponent >> offset);
+    window.keep_bits_mut(c as u32);
+    window.to_usize().expect("window fits in usize")
+}
+
+/// Bucket (Pippenger) method over G1 with window size `c`.
+fn bucket_multiexp(
+    terms: &[(G1Projective, &Integer)],
+    c: usize,
+    schedule: Schedule,
+) -> G1Projective {
+    let num_bits = terms
+        .iter()
+        .map(|(_, exponent)| exponent.significant_bits())
+        .max()
+        .unwrap_or(0);
+
+    let mut acc = G1Projective::identity();
+    let mut offset = num_bits.div_ceil(c as u32) * c as u32;
+    while offset > 0 {
+        offset -= c as u32;
+        for _ in 0..c {
+            acc = acc.double();
         }
+
+        let buckets = schedule.fold_reduce(
+            terms,
+            || vec![G1Projective::identity(); (1 << c) - 1],
+            |mut buckets, (base, exponent)| {
+                let index = window_value(exponent, offset, c);
+                if index != 0 {
+                    buckets[index - 1] = buckets[index - 1].add(base);
+                }
+                buckets
+            },
+            |mut buckets, other| {
+                for (bucket, other) in buckets.iter_mut().zip(&other) {
+                    *bucket = bucket.add(other);
+                }
+                buckets
+            },
+        );
+
+        // sum_{i} (i + 1) * buckets[i], via running sums from the top bucket down
+        let mut running_sum = G1Projective::identity();
+        let mut window_sum = G1Projective::identity();
+        for bucket in buckets.iter().rev() {
+            running_sum = running_sum.add(bucket);
+            window_sum = window_sum.add(&running_sum);
+        }
+        acc = acc.add(&window_sum);
     }
 
-    fn new_with_start_idx(bases: Arc<Vec<G2Affine>>, start_idx: usize) -> Self {
-        Self {
-            bases,
-            current_index: start_idx,
+    acc
+}
+
+/// Bucket (Pippenger) method over G2 with window size `c`.
+fn bucket_multiexp_g2(
+    terms: &[(G2Projective, &Integer)],
+    c: usize,
+    schedule: Schedule,
+) -> G2Projective {
+    let num_bits = terms
+        .iter()
+        .map(|(_, exponent)| exponent.significant_bits())
+        .max()
+        .unwrap_or(0);
+
+    let mut acc = G2Projective::identity();
+    let mut offset = num_bits.div_ceil(c as u32) * c as u32;
+    while offset > 0 {
+        offset -= c as u32;
+        for _ in 0..c {
+            acc = acc.double();
         }
+
+        let buckets = schedule.fold_reduce(
+            terms,
+            || vec![G2Projective::identity(); (1 << c) - 1],
+            |mut buckets, (base, exponent)| {
+                let index = window_value(exponent, offset, c);
+                if index != 0 {
+                    buckets[index - 1] = buckets[index - 1].add(base);
+                }
+                buckets
+            },
+            |mut buckets, other| {
+                for (bucket, other) in buckets.iter_mut().zip(&other) {
+                    *bucket = bucket.add(other);
+                }
+                buckets
+            },
+        );
+
+        // sum_{i} (i + 1) * buckets[i], via running sums from the top bucket down
+        let mut running_sum = G2Projective::identity();
+        let mut window_sum = G2Projective::identity();
+        for bucket in buckets.iter().rev() {
+            running_sum = running_sum.add(bucket);
+            window_sum = window_sum.add(&running_sum);
+        }
+        acc = acc.add(&window_sum);
     }
 
-    fn next(&mut self) -> Option<&G2Affine> {
-        if self.current_index < self.bases.len() {
-            let base = &self.bases[self.current_index];
-            self.current_index += 1;
-            Some(base)
+    acc
+}
+
+/// Width-`w` NAF of a non-negative scalar, least significant digit first.
+///
+/// Every non-zero digit is odd and lies in `(-2^(w - 1), 2^(w - 1))`.
+pub fn wnaf_form(scalar: &Integer, w: usize) -> Vec<i64> {
+    let width = 1i64 << w;
+    let mut k = scalar.clone();
+    let mut digits = vec![];
+
+    while k > 0 {
+        if k.is_odd() {
+            let mut digit = Integer::from(k.keep_bits_ref(w as u32))
+                .to_i64()
+                .expect("digit fits in i64");
+            if digit >= width / 2 {
+                digit -= width;
+            }
+            k -= digit;
+            digits.push(digit);
         } else {
-            None
+            digits.push(0);
         }
+        k >>= 1;
     }
 
-    fn skip(&mut self, amt: usize) {
-        self.current_index += amt;
-    }
+    digits
 }
 
-/// Perform multi-exponentiation.
-pub fn multiexp(
-    bases: Arc<Vec<G1Affine>>,
-    bases_start_idx: Option<usize>,
-    density_map: Option<&Density>,
-    exponents: Arc<Vec<Integer>>,
-) -> anyhow::Result<G1Projective> {
+/// Scalar multiplication of a G1 point using a wNAF whose width is taken from `config`.
+pub fn mul_wnaf(base: &G1Projective, scalar: &Scalar, config: &MultiexpConfig) -> G1Projective {
+    let w = config.wnaf_width(scalar.0.significant_bits());
+
+    // odd multiples P, 3P, 5P, ..., (2^(w - 1) - 1)P
+    let double = base.double();
+    let mut table = vec![base.clone()];
+    for i in 1..(1 << (w - 2)) {
+        table.push(table[i - 1].add(&double));
+    }
+
     let mut acc = G1Projective::identity();
-    // let mut base_iter = bases.iter();
-    let mut base_source = if let Some(start_idx) = bases_start_idx {
-        BaseSource::new_with_start_idx(bases, start_idx)
-    } else {
-        BaseSource::new(bases)
-    };
-
-    if let Some(density_map) = density_map {
-        // If the density map has a known query size, it should not be
-        // inconsistent with the number of exponents.
-        assert_eq!(density_map.0.len(), exponents.len());
-
-        for (exponent, density) in exponents.iter().zip(density_map.0.iter()) {
-            if *density {
-                if !exponent.is_zero() {
-                    if let Some(base) = base_source.next() {
-                        let mut current = G1Projective::from(base);
-                        if *exponent != Integer::from(1) {
-                            current = current.mul_scalar(exponent);
-                        }
-                        acc = acc.add(&current);
-                    } else {
-                        return Err(anyhow::anyhow!("Ran out of bases"));
-                    }
-                } else {
-                    base_source.skip(1);
-                }
-            }
-        }
-    } else {
-        for exponent in exponents.iter() {
-            if !exponent.is_zero() {
-                if let Some(base) = base_source.next() {
-                    let mut current = G1Projective::identity().add(&base.into());
-
-                    if *exponent != Integer::from(1) {
-                        current = current.mul_scalar(exponent);
-                    }
-                    acc = acc.add(&current);
-                } else {
-                    return Err(anyhow::anyhow!("Ran out of bases"));
-                }
-            } else {
-                base_source.skip(1);
-            }
+    for digit in wnaf_form(&scalar.0, w).iter().rev() {
+        acc = acc.double();
+        if *digit > 0 {
+            acc = acc.add(&table[(*digit as usize) / 2]);
+        } else if *digit < 0 {
+            acc = acc.sub(&table[(-*digit as usize) / 2]);
         }
     }
 
-    Ok(acc)
+    acc
 }
 
-/// Perform multi-exponentiation.
-pub fn multiexp_g2(
-    bases: Arc<Vec<G2Affine>>,
-    bases_start_idx: Option<usize>,
-    density_map: Option<&Density>,
-    exponents: Arc<Vec<Integer>>,
-) -> anyhow::Result<G2Projective> {
+/// Scalar multiplication of a G2 point using a wNAF whose width is taken from `config`.
+pub fn mul_wnaf_g2(base: &G2Projective, scalar: &Scalar, config: &MultiexpConfig) -> G2Projective {
+    let w = config.wnaf_width(scalar.0.significant_bits());
+
+    // odd multiples P, 3P, 5P, ..., (2^(w - 1) - 1)P
+    let double = base.double();
+    let mut table = vec![base.clone()];
+    for i in 1..(1 << (w - 2)) {
+        table.push(table[i - 1].add(&double));
+    }
+
     let mut acc = G2Projective::identity();
-    // let mut base_iter = bases.iter();
-    let mut base_source = if let Some(start_idx) = bases_start_idx {
-        BaseSourceG2::new_with_start_idx(bases, start_idx)
-    } else {
-        BaseSourceG2::new(bases)
-    };
-
-    if let Some(density_map) = density_map {
-        // If the density map has a known query size, it should not be
-        // inconsistent with the number of exponents.
-        assert_eq!(density_map.0.len(), exponents.len());
-
-        for (exponent, density) in exponents.iter().zip(density_map.0.iter()) {
-            if *density {
-                if !exponent.is_zero() {
-                    if let Some(base) = base_source.next() {
-                        let mut current = G2Projective::from(base);
-                        if *exponent != Integer::from(1) {
-                            current = current.mul_scalar(exponent);
-                        }
-                        acc = acc.add(&current);
-                    } else {
-                        return Err(anyhow::anyhow!("Ran out of bases"));
-                    }
-                } else {
-                    base_source.skip(1);
-                }
-            }
+    for digit in wnaf_form(&scalar.0, w).iter().rev() {
+        acc = acc.double();
+        if *digit > 0 {
+            acc = acc.add(&table[(*digit as usize) / 2]);
+        } else if *digit < 0 {
+            acc = acc.sub(&table[(-*digit as usize) / 2]);
         }
-    } else {
-        for exponent in exponents.iter() {
-            if !exponent.is_zero() {
-                if let Some(base) = base_source.next() {
-                    let mut current = G2Projective::identity().add(&base.into());
-
-                    if *exponent != Integer::from(1) {
-                        current = current.mul_scalar(exponent);
-                    }
-                    acc = acc.add(&current);
-                } else {
-                    return Err(anyhow::anyhow!("Ran out of bases"));
-                }
-            } else {
-                base_source.skip(1);
+    }
+
+    acc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::RngCore;
+    use zkper_rand::TestRng;
+
+    fn random_exponents(rng: &mut TestRng, n: usize) -> Vec<Scalar> {
+        (0..n)
+            .map(|_| Scalar::from(Integer::from(rng.next_u64()) * Integer::from(rng.next_u64())))
+            .collect()
+    }
+
+    #[test]
+    fn test_wnaf_form_recomposes() {
+        let scalar = Integer::from_str_radix(
+            "73eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000000",
+            16,
+        )
+        .unwrap();
+        for w in 2..8 {
+            let digits = wnaf_form(&scalar, w);
+            let mut acc = Integer::ZERO;
+            for digit in digits.iter().rev() {
+                acc = acc * 2 + digit;
+                assert!(*digit == 0 || (digit % 2 != 0 && digit.abs() < 1 << (w - 1)));
             }
+            assert_eq!(acc, scalar);
+        }
+    }
+
+    #[test]
+    fn test_mul_wnaf() {
+        let mut rng = TestRng::new();
+        let g1 = G1Projective::generator();
+        let g2 = G2Projective::generator();
+        let scalar = Scalar::from(Integer::from(rng.next_u64()) * Integer::from(rng.next_u64()));
+
+        for w in [2, 4, 6] {
+            let config = MultiexpConfig::new().with_wnaf_width(w);
+            assert_eq!(mul_wnaf(&g1, &scalar, &config), g1.mul_scalar(&scalar));
+            assert_eq!(mul_wnaf_g2(&g2, &scalar, &config), g2.mul_scalar(&scalar));
+
+            // a one-term MSM goes through the wNAF
+            let exponents = Arc::new(vec![Scalar::from(Integer::ZERO), scalar.clone()]);
+            let bases = Arc::new(vec![G1Affine::identity(), g1.to_affine()]);
+            let result = multiexp_with_config(bases, None, None, exponents.clone(), &config);
+            assert_eq!(result.unwrap(), g1.mul_scalar(&scalar));
+            let bases = Arc::new(vec![G2Affine::identity(), g2.to_affine()]);
+            let result = multiexp_g2_with_config(bases, None, None, exponents, &config);
+            assert_eq!(result.unwrap(), g2.mul_scalar(&scalar));
+        }
+    }
+
+    #[test]
+    fn test_multiexp_schedules() {
+        use crate::parallel::{Schedule, DETERMINISTIC_CHUNK};
+
+        // enough terms for several chunks; consecutive multiples of a random
+        // point are cheaper to make than random points
+        let mut rng = TestRng::new();
+        let n = 2 * DETERMINISTIC_CHUNK + 5;
+        let step = G1Projective::random(&mut rng);
+        let mut bases = vec![step.clone()];
+        for i in 1..n {
+            bases.push(bases[i - 1].add(&step));
         }
+        let bases = Arc::new(G1Projective::batch_normalize(&bases));
+        let exponents = Arc::new(random_exponents(&mut rng, n));
+
+        let run = |schedule| {
+            let config = MultiexpConfig::new()
+                .with_window_size(4)
+                .with_schedule(schedule);
+            multiexp_with_config(bases.clone(), None, None, exponents.clone(), &config).unwrap()
+        };
+        let serial = run(Schedule::Serial);
+        let deterministic = run(Schedule::Deterministic);
+        assert_eq!(run(Schedule::Parallel), serial);
+
+        // the same coordinates, not only the same point
+        assert_eq!(
+            (&deterministic.x, &deterministic.y, &deterministic.z),
+            (&serial.x, &serial.y, &serial.z)
+        );
     }
 
-    Ok(acc)
+    #[test]
+    fn test_multiexp_window_overrides() {
+        let mut rng = TestRng::new();
+        let n = 6;
+        let bases: Vec<G1Affine> = (0..n)
+            .map(|_| G1Projective::random(&mut rng).to_affine())
+            .collect();
+        let mut exponents = random_exponents(&mut rng, n);
+        exponents[2] = Scalar::from(Integer::ZERO);
+        exponents[4] = Scalar::from(Integer::from(1));
+
+        let expected = bases
+            .iter()
+            .zip(exponents.iter())
+            .fold(G1Projective::identity(), |acc, (base, exponent)| {
+                acc.add(&base.to_curve().mul_scalar(exponent))
+            });
+
+        let bases = Arc::new(bases);
+        let exponents = Arc::new(exponents);
+        for c in [1, 3, 8] {
+            let config = MultiexpConfig::new().with_window_size(c);
+            let result =
+                multiexp_with_config(bases.clone(), None, None, exponents.clone(), &config)
+                    .unwrap();
+            assert_eq!(result, expected);
+        }
+    }
+
+    #[test]
+    fn test_multiexp_g2_window_overrides() {
+        let mut rng = TestRng::new();
+        let n = 3;
+        let bases: Vec<G2Affine> = (0..n)
+            .map(|_| G2Projective::random(&mut rng).to_affine())
+            .collect();
+        let exponents = random_exponents(&mut rng, n);
+
+        let expected = bases
+            .iter()
+            .zip(exponents.iter())
+            .fold(G2Projective::identity(), |acc, (base, exponent)| {
+                acc.add(&base.to_curve().mul_scalar(exponent))
+            });
+
+        let bases = Arc::new(bases);
+        let exponents = Arc::new(exponents);
+        for c in [2, 5] {
+            let config = MultiexpConfig::new().with_window_size(c);
+            let result =
+                multiexp_g2_with_config(bases.clone(), None, None, exponents.clone(), &config)
+                    .unwrap();
+            assert_eq!(result, expected);
+        }
+    }
+
+    #[test]
+    fn test_multiexp_dedup_bases() {
+        let mut rng = TestRng::new();
+        let distinct: Vec<G1Affine> = (0..3)
+            .map(|_| G1Projective::random(&mut rng).to_affine())
+            .collect();
+        let bases = Arc::new(
+            [0, 1, 0, 2, 0, 1, 2]
+                .iter()
+                .map(|i| distinct[*i].clone())
+                .collect::<Vec<_>>(),
+        );
+
+        // the exponents of base 2 cancel out
+        let mut exponents = random_exponents(&mut rng, bases.len());
+        exponents[6] = Scalar::from(BLS12_381_SCALAR.neg(exponents[3].0.clone()));
+        let exponents = Arc::new(exponents);
+
+        let dedup = MultiexpConfig::new().with_dedup_bases(true);
+        let expected = multiexp_with_config(
+            bases.clone(),
+            None,
+            None,
+            exponents.clone(),
+            &MultiexpConfig::new(),
+        )
+        .unwrap();
+        assert_eq!(
+            multiexp_with_config(bases.clone(), None, None, exponents.clone(), &dedup).unwrap(),
+            expected
+        );
+
+        let selected = select_bases(bases.len(), None, None, &exponents).unwrap();
+        let merged = merge_repeated_bases(&bases, selected);
+        assert_eq!(
+            merged.iter().map(|(index, _)| *index).collect::<Vec<_>>(),
+            vec![0, 1]
+        );
+
+        // with a density map and a start index
+        let mut density = DensityTracker::new();
+        for used in [true, false, true, true, false, true, true] {
+            density.add_element();
+            if used {
+                density.inc(density.len() - 1);
+            }
+        }
+        let expected = multiexp_with_config(
+            bases.clone(),
+            Some(1),
+            Some(&density),
+            exponents.clone(),
+            &MultiexpConfig::new(),
+        )
+        .unwrap();
+        assert_eq!(
+            multiexp_with_config(bases.clone(), Some(1), Some(&density), exponents, &dedup)
+                .unwrap(),
+            expected
+        );
+
+        let g2_bases = Arc::new(vec![G2Projective::random(&mut rng).to_affine(); 4]);
+        let g2_exponents = Arc::new(random_exponents(&mut rng, 4));
+        assert_eq!(
+            multiexp_g2_with_config(g2_bases.clone(), None, None, g2_exponents.clone(), &dedup)
+                .unwrap(),
+            multiexp_g2(g2_bases, None, None, g2_exponents).unwrap()
+        );
+    }
 }
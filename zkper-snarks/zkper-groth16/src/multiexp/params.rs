@@ -0,0 +1,178 @@
+//! Tuning tables for multi-exponentiation.
+//!
+//! The bucket method splits every exponent into windows of `c` bits. A larger
+//! window means fewer passes over the bases but `2^c - 1` buckets per pass, so
+//! the best `c` grows roughly with `log2(n)` of the MSM length `n`. An MSM of
+//! a single term is a plain scalar multiplication and uses a width-`w` NAF
+//! instead, which trades a table of `2^(w - 2)` precomputed odd multiples for
+//! fewer additions.
+//!
+//! The defaults below are the ones used by `multiexp`/`multiexp_g2`. They are
+//! exposed so the heuristics can be inspected, and `MultiexpConfig` lets a
+//! caller override them per call when tuning for specific hardware.
+//!
+//! Queries where one base recurs with many exponents can merge those terms
+//! before the bucket method runs, see `MultiexpConfig::with_dedup_bases`.
+//!
+//! The buckets of every window are filled in parallel under the `Schedule` of
+//! the config, see `MultiexpConfig::with_schedule`.
+
+use crate::parallel::Schedule;
+
+/// Window size (in bits) for the bucket method, keyed by MSM length.
+///
+/// Each entry is `(max_len, window_size)`; the first entry whose `max_len` is
+/// at least the number of terms is used.
+pub const MSM_WINDOW_SIZE_TABLE: &[(usize, usize)] = &[
+    (4, 1),
+    (16, 2),
+    (32, 3),
+    (128, 4),
+    (512, 5),
+    (1 << 11, 7),
+    (1 << 13, 9),
+    (1 << 15, 11),
+    (1 << 17, 13),
+    (1 << 20, 15),
+    (usize::MAX, 16),
+];
+
+/// wNAF width keyed by the bit length of the scalar.
+///
+/// Each entry is `(max_bits, width)`; the first entry whose `max_bits` is at
+/// least the scalar's significant bits is used.
+pub const WNAF_WIDTH_TABLE: &[(u32, usize)] =
+    &[(32, 2), (64, 3), (128, 4), (256, 5), (u32::MAX, 6)];
+
+/// Smallest window size accepted by the bucket method.
+pub const MIN_WINDOW_SIZE: usize = 1;
+/// Largest window size accepted by the bucket method, the largest default of
+/// `MSM_WINDOW_SIZE_TABLE`.
+///
+/// Past the best window the `2^c` buckets of every window cost more than the
+/// additions they save: on the 2^16 G1 terms of the `multiexp_g1_window`
+/// bench, 16 bits take twice as long as the best 12 and 18 bits twice as long
+/// again, so a larger override would only be slower.
+pub const MAX_WINDOW_SIZE: usize = 16;
+/// Smallest wNAF width accepted.
+pub const MIN_WNAF_WIDTH: usize = 2;
+/// Largest wNAF width accepted.
+pub const MAX_WNAF_WIDTH: usize = 16;
+
+/// Default window size for an MSM of `len` terms, read from `MSM_WINDOW_SIZE_TABLE`.
+pub fn default_window_size(len: usize) -> usize {
+    MSM_WINDOW_SIZE_TABLE
+        .iter()
+        .find(|(max_len, _)| len <= *max_len)
+        .map(|(_, c)| *c)
+        .unwrap_or(MAX_WINDOW_SIZE)
+}
+
+/// Default wNAF width for a scalar of `bits` significant bits, read from `WNAF_WIDTH_TABLE`.
+pub fn default_wnaf_width(bits: u32) -> usize {
+    WNAF_WIDTH_TABLE
+        .iter()
+        .find(|(max_bits, _)| bits <= *max_bits)
+        .map(|(_, w)| *w)
+        .unwrap_or(MAX_WNAF_WIDTH)
+}
+
+/// Per-call overrides for the multi-exponentiation heuristics.
+///
+/// Unset values fall back to the const tables in this module.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MultiexpConfig {
+    window_size: Option<usize>,
+    wnaf_width: Option<usize>,
+    dedup_bases: bool,
+    schedule: Schedule,
+}
+
+impl MultiexpConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Force the bucket window size, clamped to `[MIN_WINDOW_SIZE, MAX_WINDOW_SIZE]`.
+    pub fn with_window_size(mut self, window_size: usize) -> Self {
+        self.window_size = Some(window_size.clamp(MIN_WINDOW_SIZE, MAX_WINDOW_SIZE));
+        self
+    }
+
+    /// Force the wNAF width, clamped to `[MIN_WNAF_WIDTH, MAX_WNAF_WIDTH]`.
+    pub fn with_wnaf_width(mut self, wnaf_width: usize) -> Self {
+        self.wnaf_width = Some(wnaf_width.clamp(MIN_WNAF_WIDTH, MAX_WNAF_WIDTH));
+        self
+    }
+
+    /// Merge the terms of repeated bases, adding their exponents, before the
+    /// bucket method. Off by default: finding the repeats costs a hash of
+    /// every base, which only pays off when bases actually repeat.
+    pub fn with_dedup_bases(mut self, dedup_bases: bool) -> Self {
+        self.dedup_bases = dedup_bases;
+        self
+    }
+
+    /// How the terms are spread over threads. All schedules give the same
+    /// point; `Schedule::Deterministic` also gives the same coordinates.
+    pub fn with_schedule(mut self, schedule: Schedule) -> Self {
+        self.schedule = schedule;
+        self
+    }
+
+    /// Window size to use for an MSM of `len` terms.
+    pub fn window_size(&self, len: usize) -> usize {
+        self.window_size.unwrap_or_else(|| default_window_size(len))
+    }
+
+    /// wNAF width to use for a scalar of `bits` significant bits.
+    pub fn wnaf_width(&self, bits: u32) -> usize {
+        self.wnaf_width.unwrap_or_else(|| default_wnaf_width(bits))
+    }
+
+    /// Whether repeated bases are merged before the bucket method.
+    pub fn dedup_bases(&self) -> bool {
+        self.dedup_bases
+    }
+
+    /// The schedule of the bucket filling.
+    pub fn schedule(&self) -> Schedule {
+        self.schedule
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tables_are_sorted() {
+        for pair in MSM_WINDOW_SIZE_TABLE.windows(2) {
+            assert!(pair[0].0 < pair[1].0);
+            assert!(pair[0].1 <= pair[1].1);
+        }
+        assert_eq!(MSM_WINDOW_SIZE_TABLE.last().unwrap().1, MAX_WINDOW_SIZE);
+        for pair in WNAF_WIDTH_TABLE.windows(2) {
+            assert!(pair[0].0 < pair[1].0);
+            assert!(pair[0].1 <= pair[1].1);
+        }
+    }
+
+    #[test]
+    fn test_config_overrides() {
+        let config = MultiexpConfig::new();
+        assert_eq!(config.window_size(100), 4);
+        assert_eq!(config.wnaf_width(255), 5);
+        assert!(!config.dedup_bases());
+        assert_eq!(config.schedule(), Schedule::Parallel);
+
+        let config = MultiexpConfig::new()
+            .with_window_size(8)
+            .with_wnaf_width(100);
+        assert_eq!(config.window_size(100), 8);
+        assert_eq!(config.wnaf_width(255), MAX_WNAF_WIDTH);
+
+        let config = MultiexpConfig::new().with_window_size(20);
+        assert_eq!(config.window_size(1 << 20), MAX_WINDOW_SIZE);
+    }
+}
@@ -0,0 +1,153 @@
+//! Preparing the inputs of [`multiexp`](super::multiexp).
+//!
+//! Bases are normalized in one batch, with a single field inversion, and
+//! terms can be grouped by the bit length of their exponents: Pippenger runs
+//! one round of buckets per window of the longest exponent, so a separate
+//! multiexp over the short exponents of a witness, bits and small counters,
+//! skips most of the rounds.
+
+use std::sync::Arc;
+
+use zkper_curves::curves::bls12_381::{
+    curves::{g1::G1Projective, g1_affine::G1Affine, g2::G2Projective, g2_affine::G2Affine},
+    Scalar,
+};
+
+use crate::error::Groth16Error;
+
+/// The affine bases of `points`, normalized together.
+pub fn prepare_bases(points: &[G1Projective]) -> Arc<Vec<G1Affine>> {
+    Arc::new(G1Projective::batch_normalize(points))
+}
+
+/// The affine bases of `points`, normalized together.
+pub fn prepare_bases_g2(points: &[G2Projective]) -> Arc<Vec<G2Affine>> {
+    Arc::new(G2Projective::batch_normalize(points))
+}
+
+/// Terms of a multiexp whose exponents are at most `bits` bits long.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BitLengthClass<B> {
+    /// The length of the longest exponent of the class.
+    pub bits: u32,
+    pub bases: Arc<Vec<B>>,
+    pub exponents: Arc<Vec<Scalar>>,
+}
+
+/// Splits the terms `bases[i] * exponents[i]` into classes by exponent length:
+/// one per bound of `bounds` for the exponents no longer than it, and one for
+/// the rest, shortest first. Terms keep their order within a class, zero
+/// exponents are dropped and empty classes left out, so the multiexps of the
+/// classes add up to the multiexp of all terms.
+pub fn partition_by_bit_length<B: Clone>(
+    bases: &[B],
+    exponents: &[Scalar],
+    bounds: &[u32],
+) -> Result<Vec<BitLengthClass<B>>, Groth16Error> {
+    if exponents.len() > bases.len() {
+        return Err(Groth16Error::OutOfBases);
+    }
+
+    let mut bounds = bounds.to_vec();
+    bounds.sort_unstable();
+    bounds.dedup();
+
+    let mut classes = vec![(0, vec![], vec![]); bounds.len() + 1];
+    for (base, exponent) in bases.iter().zip(exponents) {
+        let bits = exponent.0.significant_bits();
+        if bits == 0 {
+            continue;
+        }
+        let class = &mut classes[bounds.partition_point(|bound| *bound < bits)];
+        class.0 = class.0.max(bits);
+        class.1.push(base.clone());
+        class.2.push(exponent.clone());
+    }
+
+    Ok(classes
+        .into_iter()
+        .filter(|(_, bases, _)| !bases.is_empty())
+        .map(|(bits, bases, exponents)| BitLengthClass {
+            bits,
+            bases: Arc::new(bases),
+            exponents: Arc::new(exponents),
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use rug::Integer;
+    use zkper_rand::TestRng;
+
+    use super::*;
+    use crate::multiexp::{multiexp, multiexp_g2};
+
+    #[test]
+    fn test_prepare_bases() {
+        let mut rng = TestRng::new();
+        let mut points = (0..4)
+            .map(|_| G1Projective::random(&mut rng))
+            .collect::<Vec<_>>();
+        points.push(G1Projective::identity());
+        let bases = prepare_bases(&points);
+        for (point, base) in points.iter().zip(bases.iter()) {
+            assert_eq!(&point.to_affine(), base);
+        }
+
+        let points = vec![G2Projective::random(&mut rng), G2Projective::identity()];
+        let bases = prepare_bases_g2(&points);
+        for (point, base) in points.iter().zip(bases.iter()) {
+            assert_eq!(&point.to_affine(), base);
+        }
+    }
+
+    #[test]
+    fn test_partition_by_bit_length() {
+        let mut rng = TestRng::new();
+        let points = (0..8)
+            .map(|_| G1Projective::random(&mut rng))
+            .collect::<Vec<_>>();
+        let bases = prepare_bases(&points);
+        let mut exponents = [1u64, 0, 200, 1, 0, 3, 0, 1 << 40]
+            .map(|e| Scalar::from(Integer::from(e)))
+            .to_vec();
+        exponents[4] = Scalar::from(Integer::from(u64::MAX) * Integer::from(u64::MAX));
+
+        let classes = partition_by_bit_length(&bases, &exponents, &[64, 1, 8, 1]).unwrap();
+        assert_eq!(
+            classes
+                .iter()
+                .map(|class| (class.bits, class.exponents.len()))
+                .collect::<Vec<_>>(),
+            // {1, 1}, {200, 3}, {2^40}, {(2^64 - 1)^2}
+            vec![(1, 2), (8, 2), (41, 1), (128, 1)]
+        );
+        assert_eq!(classes[0].bases[1], bases[3]);
+
+        let expected = multiexp(bases.clone(), None, None, Arc::new(exponents.clone())).unwrap();
+        let sum = classes.iter().fold(G1Projective::identity(), |acc, class| {
+            acc.add(&multiexp(class.bases.clone(), None, None, class.exponents.clone()).unwrap())
+        });
+        assert_eq!(sum, expected);
+
+        // G2 bases go through the same path
+        let g2_bases =
+            prepare_bases_g2(&[G2Projective::generator(), G2Projective::random(&mut rng)]);
+        let g2_exponents = exponents[2..4].to_vec();
+        let classes = partition_by_bit_length(&g2_bases, &g2_exponents, &[4]).unwrap();
+        assert_eq!(classes.len(), 2);
+        let sum = classes.iter().fold(G2Projective::identity(), |acc, class| {
+            acc.add(&multiexp_g2(class.bases.clone(), None, None, class.exponents.clone()).unwrap())
+        });
+        assert_eq!(
+            sum,
+            multiexp_g2(g2_bases, None, None, Arc::new(g2_exponents)).unwrap()
+        );
+
+        assert!(matches!(
+            partition_by_bit_length(&bases[..2], &exponents, &[]).unwrap_err(),
+            Groth16Error::OutOfBases
+        ));
+    }
+}
@@ -0,0 +1,223 @@
+//! Groth16 verification without rug or GMP, on `zkper_curves::native`.
+//!
+//! A second verifier next to `crate::verifier`, for browsers and light
+//! clients, built by the `backend-native` feature. It reads what the rest of
+//! the crate writes: keys encoded by `VerifierKey::to_bytes`, proofs by
+//! `Proof::to_bytes` and public inputs as canonical big-endian scalars, and
+//! accepts exactly the proofs `verifier::verify_proof_with_context` accepts.
+//!
+//! ```text
+//! proof            compressed G1 A || compressed G2 B || compressed G1 C
+//! ```
+
+pub use zkper_curves::native::{G1Affine, G2Affine, Scalar};
+use zkper_curves::{
+    error::CurveError,
+    native::{g1::G1_COMPRESSED_BYTES, g2::G2_COMPRESSED_BYTES, pairing_product_is_one},
+};
+
+use crate::{
+    context::{wide_digest, CONTEXT_DST},
+    error::Groth16Error,
+    hash::Sha256Hash,
+};
+
+/// Magic bytes at the start of an encoded verifier key, the same as
+/// `crate::models::verification_key::VERIFIER_KEY_MAGIC`.
+pub const VERIFIER_KEY_MAGIC: &[u8; 8] = b"ZKPG16VK";
+
+/// Size of an encoded proof, the same as `crate::models::proof::PROOF_BYTES`.
+pub const PROOF_BYTES: usize = 2 * G1_COMPRESSED_BYTES + G2_COMPRESSED_BYTES;
+
+/// The points of a verification key that verification reads, decoded from
+/// the format of `crate::models::verification_key::VerifierKey`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VerifierKey {
+    pub alpha_g1: G1Affine,
+    pub beta_g2: G2Affine,
+    pub gamma_g2: G2Affine,
+    pub delta_g2: G2Affine,
+    /// IC elements, one per public input plus the constant one and the
+    /// context input.
+    pub ic: Vec<G1Affine>,
+}
+
+impl VerifierKey {
+    /// Decode a key written by `VerifierKey::to_bytes`, checking that every
+    /// point is on its curve. Subgroup membership is checked when verifying.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Groth16Error> {
+        let rest = bytes
+            .strip_prefix(VERIFIER_KEY_MAGIC)
+            .ok_or_else(|| malformed("Not a verifier key"))?;
+
+        let (alpha_g1, rest) = split(rest, G1_COMPRESSED_BYTES)?;
+        let (beta_g2, rest) = split(rest, G2_COMPRESSED_BYTES)?;
+        let (gamma_g2, rest) = split(rest, G2_COMPRESSED_BYTES)?;
+        let (delta_g2, rest) = split(rest, G2_COMPRESSED_BYTES)?;
+        let (len, rest) = split(rest, 8)?;
+        let len = u64::from_be_bytes(len.try_into().expect("split off 8 bytes"));
+
+        let size = usize::try_from(len)
+            .ok()
+            .and_then(|len| len.checked_mul(G1_COMPRESSED_BYTES))
+            .ok_or_else(|| malformed("IC length overflow"))?;
+        let (ic, rest) = split(rest, size)?;
+        if !rest.is_empty() {
+            return Err(malformed("Trailing bytes in verifier key"));
+        }
+
+        Ok(Self {
+            alpha_g1: g1(alpha_g1)?,
+            beta_g2: g2(beta_g2)?,
+            gamma_g2: g2(gamma_g2)?,
+            delta_g2: g2(delta_g2)?,
+            ic: ic
+                .chunks(G1_COMPRESSED_BYTES)
+                .map(g1)
+                .collect::<Result<Vec<_>, _>>()?,
+        })
+    }
+}
+
+/// A verifier key with the points negated that join the pairing product
+/// negated, like `crate::models::verify::PreparedVerifyingKey`.
+#[derive(Clone, Debug)]
+pub struct PreparedVerifyingKey {
+    pub neg_alpha_g1: G1Affine,
+    pub beta_g2: G2Affine,
+    pub neg_gamma_g2: G2Affine,
+    pub neg_delta_g2: G2Affine,
+    pub ic: Vec<G1Affine>,
+}
+
+pub fn prepare_verifier_key(vk: &VerifierKey) -> PreparedVerifyingKey {
+    PreparedVerifyingKey {
+        neg_alpha_g1: vk.alpha_g1.neg(),
+        beta_g2: vk.beta_g2,
+        neg_gamma_g2: vk.gamma_g2.neg(),
+        neg_delta_g2: vk.delta_g2.neg(),
+        ic: vk.ic.clone(),
+    }
+}
+
+/// A Groth16 proof, decoded from `Proof::to_bytes`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Proof {
+    pub a: G1Affine,
+    pub b: G2Affine,
+    pub c: G1Affine,
+}
+
+impl Proof {
+    /// Decode a proof in the format of the module docs, checking that every
+    /// point is on its curve.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Groth16Error> {
+        if bytes.len() != PROOF_BYTES {
+            return Err(malformed("Proof has the wrong length"));
+        }
+
+        let (a, rest) = bytes.split_at(G1_COMPRESSED_BYTES);
+        let (b, c) = rest.split_at(G2_COMPRESSED_BYTES);
+        Ok(Self {
+            a: g1(a)?,
+            b: g2(b)?,
+            c: g1(c)?,
+        })
+    }
+
+    /// Whether every point is a non-identity element of its prime-order
+    /// subgroup, see `crate::models::proof::Proof::validate`.
+    pub fn is_well_formed(&self) -> bool {
+        !self.a.is_identity()
+            && !self.b.is_identity()
+            && !self.c.is_identity()
+            && self.a.is_on_curve()
+            && self.b.is_on_curve()
+            && self.c.is_on_curve()
+            && self.a.is_torsion_free()
+            && self.b.is_torsion_free()
+            && self.c.is_torsion_free()
+    }
+}
+
+/// Verify a proof made without a context.
+pub fn verify_proof(
+    pvk: &PreparedVerifyingKey,
+    proof: &Proof,
+    public_inputs: &[Scalar],
+) -> Result<bool, Groth16Error> {
+    verify_proof_with_context(pvk, proof, public_inputs, None)
+}
+
+/// Verify a proof bound to `context`, see `crate::context`. Checks
+/// e(A, B) * e(acc, -γ) * e(C, -δ) * e(-α, β) == 1 for
+/// acc = IC_0 + sum_i input_i * IC_{i + 1} + context * IC_last.
+pub fn verify_proof_with_context(
+    pvk: &PreparedVerifyingKey,
+    proof: &Proof,
+    public_inputs: &[Scalar],
+    context: Option<&[u8]>,
+) -> Result<bool, Groth16Error> {
+    // IC_0 for the constant one and IC_last for the context input
+    if pvk.ic.len() < 2 {
+        return Err(Groth16Error::InvalidVerifyingKey);
+    }
+    if public_inputs.len() + 2 != pvk.ic.len() {
+        return Err(Groth16Error::WrongInputCount {
+            expected: pvk.ic.len() - 2,
+            actual: public_inputs.len(),
+        });
+    }
+
+    if !proof.is_well_formed() {
+        return Ok(false);
+    }
+
+    let mut acc = pvk.ic[0].to_jacobian();
+    for (input, base) in public_inputs.iter().zip(&pvk.ic[1..]) {
+        acc = acc.add(&base.mul(input));
+    }
+    let ic_context = pvk.ic.last().expect("checked above");
+    acc = acc.add(&ic_context.mul(&context_scalar(context)));
+
+    Ok(pairing_product_is_one(&[
+        (proof.a, proof.b),
+        (acc.to_affine(), pvk.neg_gamma_g2),
+        (proof.c, pvk.neg_delta_g2),
+        (pvk.neg_alpha_g1, pvk.beta_g2),
+    ]))
+}
+
+/// The value of the context input, see `crate::context::context_scalar`.
+pub fn context_scalar(context: Option<&[u8]>) -> Scalar {
+    match context {
+        None => Scalar::ZERO,
+        Some(context) => {
+            let scalar = Scalar::from_bytes_wide(&wide_digest::<Sha256Hash>(CONTEXT_DST, context));
+            if scalar == Scalar::ZERO {
+                Scalar::ONE
+            } else {
+                scalar
+            }
+        }
+    }
+}
+
+fn malformed(what: &str) -> Groth16Error {
+    Groth16Error::MalformedFile(what.to_string())
+}
+
+fn split(bytes: &[u8], n: usize) -> Result<(&[u8], &[u8]), Groth16Error> {
+    if bytes.len() < n {
+        return Err(malformed("Unexpected end of verifier key"));
+    }
+    Ok(bytes.split_at(n))
+}
+
+fn g1(bytes: &[u8]) -> Result<G1Affine, CurveError> {
+    G1Affine::from_compressed(bytes).ok_or(CurveError::InvalidPoint("G1"))
+}
+
+fn g2(bytes: &[u8]) -> Result<G2Affine, CurveError> {
+    G2Affine::from_compressed(bytes).ok_or(CurveError::InvalidPoint("G2"))
+}
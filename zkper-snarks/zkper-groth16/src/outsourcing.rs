@@ -0,0 +1,267 @@
+//! Delegating the multi-exponentiations of a proof to an untrusted server.
+//!
+//! Proving is split in two phases:
+//!
+//! 1. Locally, [`prepare_outsourced_proof`] synthesizes the witness, lays the
+//!    exponents of every query out densely against the public query bases and
+//!    adds a uniformly random mask to each of them. The masked vectors in the
+//!    [`OutsourcedMsmRequest`] are independent of the witness.
+//! 2. The server runs [`OutsourcedMsmRequest::compute`] over the public bases and
+//!    returns the [`OutsourcedMsmResponse`]; [`PendingProof::finish`] subtracts
+//!    the answers of the masks and assembles the proof with the local `r`, `s`.
+//!
+//! The answers of the masks are computed ahead of time with
+//! [`MsmMasks::generate`], e.g. while the prover is idle. A mask set is consumed
+//! by the proof that uses it, reusing one would let the server difference two
+//! requests and learn the difference of two witnesses.
+//!
+//! The server is not trusted for correctness either: a wrong response yields a
+//! proof that fails verification, so check the proof before publishing it.
+
+use std::fmt;
+
+use rand::RngCore;
+use zkper_base::redact::{Redacted, RedactedSlice};
+use zkper_curves::curves::bls12_381::{
+    curves::{g1::G1Projective, g2::G2Projective},
+    Bls12_381ScalarField, Scalar, BLS12_381_SCALAR,
+};
+use zkper_curves::traits::field::FieldTrait;
+
+use crate::{
+    circuit::Circuit,
+    error::Groth16Error,
+    models::{proof::Proof, verification_key::VerificationKey},
+    multiexp::{multiexp, multiexp_g2},
+    params_source::ParamsSource,
+    prover::{assemble_proof, check_h_query_len, synthesize, DensityTracker, QueryAnswers},
+};
+
+/// Random exponent vectors for every query, together with their answers.
+pub struct MsmMasks {
+    exponents: OutsourcedMsmRequest,
+    answers: QueryAnswers,
+}
+
+impl fmt::Debug for MsmMasks {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MsmMasks")
+            .field("h", &RedactedSlice(&self.exponents.h))
+            .field("l", &RedactedSlice(&self.exponents.l))
+            .field("a", &RedactedSlice(&self.exponents.a))
+            .field("b_g1", &RedactedSlice(&self.exponents.b_g1))
+            .field("b_g2", &RedactedSlice(&self.exponents.b_g2))
+            .finish_non_exhaustive()
+    }
+}
+
+impl MsmMasks {
+    /// Samples the masks and computes their multi-exponentiations locally.
+    ///
+    /// This costs as much as the proof's own MSMs, but does not depend on the
+    /// witness, so it can be done before the statement is known.
+    pub fn generate<P: ParamsSource + ?Sized, R: RngCore>(
+        params: &P,
+        rng: &mut R,
+    ) -> Result<Self, Groth16Error> {
+        let mut sample = |len: usize| {
+            (0..len)
+                .map(|_| Scalar::from(BLS12_381_SCALAR.sample_raw(rng)))
+                .collect::<Vec<_>>()
+        };
+
+        let exponents = OutsourcedMsmRequest {
+            h: sample(params.h_query_len().map_err(Groth16Error::parameters)?),
+            l: sample(params.l_query_len().map_err(Groth16Error::parameters)?),
+            a: sample(params.a_query_len().map_err(Groth16Error::parameters)?),
+            b_g1: sample(params.b_g1_query_len().map_err(Groth16Error::parameters)?),
+            b_g2: sample(params.b_g2_query_len().map_err(Groth16Error::parameters)?),
+        };
+        let answers = exponents.compute(params)?.into();
+
+        Ok(Self { exponents, answers })
+    }
+}
+
+/// Masked exponent vectors, one per query and as long as its bases.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutsourcedMsmRequest {
+    pub h: Vec<Scalar>,
+    pub l: Vec<Scalar>,
+    pub a: Vec<Scalar>,
+    pub b_g1: Vec<Scalar>,
+    pub b_g2: Vec<Scalar>,
+}
+
+impl OutsourcedMsmRequest {
+    /// The server side: plain multi-exponentiations over the public query bases.
+    pub fn compute<P: ParamsSource + ?Sized>(
+        &self,
+        params: &P,
+    ) -> Result<OutsourcedMsmResponse, Groth16Error> {
+        Ok(OutsourcedMsmResponse {
+            h: multiexp(
+                params.h_query().map_err(Groth16Error::parameters)?,
+                None,
+                None,
+                self.h.clone().into(),
+            )?,
+            l: multiexp(
+                params.l_query().map_err(Groth16Error::parameters)?,
+                None,
+                None,
+                self.l.clone().into(),
+            )?,
+            a: multiexp(
+                params.a_query().map_err(Groth16Error::parameters)?,
+                None,
+                None,
+                self.a.clone().into(),
+            )?,
+            b_g1: multiexp(
+                params.b_g1_query().map_err(Groth16Error::parameters)?,
+                None,
+                None,
+                self.b_g1.clone().into(),
+            )?,
+            b_g2: multiexp_g2(
+                params.b_g2_query().map_err(Groth16Error::parameters)?,
+                None,
+                None,
+                self.b_g2.clone().into(),
+            )?,
+        })
+    }
+}
+
+/// The server's answers to an [`OutsourcedMsmRequest`].
+#[derive(Debug, Clone)]
+pub struct OutsourcedMsmResponse {
+    pub h: G1Projective,
+    pub l: G1Projective,
+    pub a: G1Projective,
+    pub b_g1: G1Projective,
+    pub b_g2: G2Projective,
+}
+
+impl From<OutsourcedMsmResponse> for QueryAnswers {
+    fn from(response: OutsourcedMsmResponse) -> Self {
+        QueryAnswers {
+            h: response.h,
+            l: response.l,
+            a: response.a,
+            b_g1: response.b_g1,
+            b_g2: response.b_g2,
+        }
+    }
+}
+
+/// The local state kept between the two phases. Never send it to the server.
+pub struct PendingProof {
+    vk: VerificationKey,
+    masks: QueryAnswers,
+    r: Scalar,
+    s: Scalar,
+}
+
+impl fmt::Debug for PendingProof {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PendingProof")
+            .field("vk", &self.vk)
+            .field("r", &Redacted(&self.r))
+            .field("s", &Redacted(&self.s))
+            .finish_non_exhaustive()
+    }
+}
+
+impl PendingProof {
+    /// Removes the masks from the server's answers and assembles the proof.
+    pub fn finish(self, response: OutsourcedMsmResponse) -> Proof {
+        let answers = QueryAnswers {
+            h: response.h.sub(&self.masks.h),
+            l: response.l.sub(&self.masks.l),
+            a: response.a.sub(&self.masks.a),
+            b_g1: response.b_g1.sub(&self.masks.b_g1),
+            b_g2: response.b_g2.sub(&self.masks.b_g2),
+        };
+
+        assemble_proof(&self.vk, answers, &self.r, &self.s)
+    }
+}
+
+/// Adds `exponents` into `dense` at the positions of the bases the prover's
+/// multiexp would consume: one base per exponent, or per used entry of
+/// `density`.
+fn scatter_exponents(
+    dense: &mut [Scalar],
+    density: Option<&DensityTracker>,
+    exponents: &[Scalar],
+) -> Result<(), Groth16Error> {
+    let positions = density.map(DensityTracker::compacted_indices);
+    for (i, exponent) in exponents.iter().enumerate() {
+        let index = match &positions {
+            Some(positions) => match positions[i] {
+                Some(index) => index,
+                None => continue,
+            },
+            None => i,
+        };
+
+        if !exponent.0.is_zero() {
+            dense
+                .get_mut(index)
+                .ok_or(Groth16Error::OutOfBases)?
+                .add_assign(exponent);
+        }
+    }
+
+    Ok(())
+}
+
+/// Local phase of an outsourced proof: synthesizes the witness and masks the
+/// exponents of every query with `masks`. The proof is bound to `context` as
+/// in `create_proof_with_context`.
+pub fn prepare_outsourced_proof<C: Circuit, P: ParamsSource + ?Sized, R: RngCore>(
+    circuit: C,
+    params: &P,
+    context: Option<&[u8]>,
+    masks: MsmMasks,
+    mut rng: &mut R,
+) -> Result<(OutsourcedMsmRequest, PendingProof), Groth16Error> {
+    if params.commitment().is_some() {
+        return Err(Groth16Error::CommittedParameters);
+    }
+    if params.vk().delta_g1.is_identity() || params.vk().delta_g2.is_identity() {
+        return Err(Groth16Error::DegenerateVerifyingKey);
+    }
+
+    let (prover, h) = synthesize(circuit, context, params.vk().ic.len())?;
+    let assignment = prover.full_assignment();
+
+    let MsmMasks {
+        exponents: mut request,
+        answers: mask_answers,
+    } = masks;
+
+    check_h_query_len(&request.h, &h)?;
+    scatter_exponents(&mut request.h, None, &h)?;
+    scatter_exponents(&mut request.l, None, &prover.private_assignment)?;
+    scatter_exponents(&mut request.a, Some(&prover.a_density()), &assignment)?;
+
+    let b_density = prover.b_density();
+    for dense in [&mut request.b_g1, &mut request.b_g2] {
+        scatter_exponents(dense, Some(&b_density), &assignment)?;
+    }
+
+    let r = Scalar::from(Bls12_381ScalarField::random(&mut rng));
+    let s = Scalar::from(Bls12_381ScalarField::random(&mut rng));
+
+    let pending = PendingProof {
+        vk: params.vk().clone(),
+        masks: mask_answers,
+        r,
+        s,
+    };
+
+    Ok((request, pending))
+}
@@ -0,0 +1,95 @@
+//! Scheduling of the parallel parts of proving.
+//!
+//! Field and group arithmetic is exact, so every order of additions yields the
+//! same field element or point, and no reduction in this crate can change its
+//! result with the number of threads. What can change is the representation:
+//! a projective point has many coordinates for one point, and which ones a sum
+//! ends in depends on the order of its additions. Proofs leave the prover in
+//! affine form and are bit-identical under every [`Schedule`];
+//! [`Schedule::Deterministic`] additionally pins the intermediate projective
+//! values, so they match a serial run exactly whatever the thread count.
+
+use rayon::prelude::*;
+
+/// Items per chunk for [`Schedule::Deterministic`] and [`Schedule::Serial`].
+pub const DETERMINISTIC_CHUNK: usize = 1 << 10;
+
+/// How parallel work is split and combined.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Schedule {
+    /// Split adaptively across the rayon pool; partial results are combined in
+    /// whatever order the split produced.
+    #[default]
+    Parallel,
+    /// Split in chunks of `DETERMINISTIC_CHUNK` items across the rayon pool,
+    /// combining the partial results in index order.
+    Deterministic,
+    /// The chunks and order of `Deterministic`, on the calling thread.
+    Serial,
+}
+
+impl Schedule {
+    /// Folds every item into a partial result starting from `identity`, then
+    /// combines the partial results with `reduce`.
+    pub fn fold_reduce<T, R, I, F, C>(self, items: &[T], identity: I, fold: F, reduce: C) -> R
+    where
+        T: Sync,
+        R: Send,
+        I: Fn() -> R + Sync + Send,
+        F: Fn(R, &T) -> R + Sync + Send,
+        C: Fn(R, R) -> R + Sync + Send,
+    {
+        let fold_chunk = |chunk: &[T]| chunk.iter().fold(identity(), &fold);
+
+        match self {
+            Schedule::Parallel => items
+                .par_iter()
+                .fold(&identity, &fold)
+                .reduce(&identity, &reduce),
+            Schedule::Deterministic => items
+                .par_chunks(DETERMINISTIC_CHUNK)
+                .map(fold_chunk)
+                .collect::<Vec<_>>()
+                .into_iter()
+                .fold(identity(), &reduce),
+            Schedule::Serial => items
+                .chunks(DETERMINISTIC_CHUNK)
+                .map(fold_chunk)
+                .fold(identity(), &reduce),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fold_reduce() {
+        let items = (0..3 * DETERMINISTIC_CHUNK as u64 + 7).collect::<Vec<_>>();
+        let expected = items.iter().sum::<u64>();
+
+        // record the order partial results are combined in
+        let trace = |schedule: Schedule| {
+            schedule.fold_reduce(
+                &items,
+                || (0u64, vec![]),
+                |(sum, trace), item| (sum + item, trace),
+                |(a, mut trace), (b, other)| {
+                    trace.extend(other);
+                    trace.push(b);
+                    (a + b, trace)
+                },
+            )
+        };
+
+        for schedule in [
+            Schedule::Parallel,
+            Schedule::Deterministic,
+            Schedule::Serial,
+        ] {
+            assert_eq!(trace(schedule).0, expected);
+        }
+        assert_eq!(trace(Schedule::Deterministic), trace(Schedule::Serial));
+    }
+}
@@ -0,0 +1,601 @@
+//! On-disk format for proving parameters.
+//!
+//! ```text
+//! magic            8 bytes   "ZKPG16PK" (uncompressed) or "ZKPG16PC" (compressed)
+//! alpha_g1         G1
+//! beta_g1          G1
+//! beta_g2          G2
+//! gamma_g2         G2
+//! delta_g1         G1
+//! delta_g2         G2
+//! ic               u64 length, then G1 points
+//! h_query          u64 length, then G1 points
+//! l_query          u64 length, then G1 points
+//! a_query          u64 length, then G1 points
+//! b_g1_query       u64 length, then G1 points
+//! b_g2_query       u64 length, then G2 points
+//! commitment       optional: u64 length, then G1 bases, η/γ and η/δ in G1
+//! ```
+//!
+//! Lengths are big-endian. The magic selects the point encoding: the
+//! uncompressed encodings from `G1Affine::to_uncompressed` and
+//! `G2Affine::to_uncompressed`, or the compressed ones, which halve the file at
+//! the cost of a square root per point when the queries are decoded.
+//!
+//! The commitment key is only present for parameters generated with
+//! `generate_proving_parameters_with_commitment`.
+//!
+//! With the `zstd` feature the whole file may additionally be wrapped in a zstd
+//! frame for distribution. Such a file is decompressed into memory on open
+//! instead of being mapped.
+
+use std::{fs::File, io::Write, ops::Deref, path::Path, sync::Arc};
+
+use memmap2::Mmap;
+use zkper_curves::{
+    curves::bls12_381::curves::{
+        g1_affine::{G1Affine, G1_COMPRESSED_BYTES, G1_UNCOMPRESSED_BYTES},
+        g2_affine::{G2Affine, G2_COMPRESSED_BYTES, G2_UNCOMPRESSED_BYTES},
+    },
+    error::CurveError,
+};
+
+use crate::models::{
+    commitment::CommitmentKey, proving_parameters::ProvingParameters,
+    verification_key::VerificationKey,
+};
+
+use super::ParamsSource;
+
+/// Magic bytes at the start of a proving parameter file with uncompressed points.
+pub const PARAMS_MAGIC: &[u8; 8] = b"ZKPG16PK";
+
+/// Magic bytes at the start of a proving parameter file with compressed points.
+pub const COMPRESSED_PARAMS_MAGIC: &[u8; 8] = b"ZKPG16PC";
+
+/// Magic bytes of a zstd frame.
+const ZSTD_MAGIC: &[u8; 4] = &[0x28, 0xb5, 0x2f, 0xfd];
+
+/// How curve points are encoded in a parameter file.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PointEncoding {
+    /// x and y, decoded with a curve check only.
+    #[default]
+    Uncompressed,
+    /// x and a sign flag, decoded with a square root.
+    Compressed,
+}
+
+impl PointEncoding {
+    fn magic(self) -> &'static [u8; 8] {
+        match self {
+            PointEncoding::Uncompressed => PARAMS_MAGIC,
+            PointEncoding::Compressed => COMPRESSED_PARAMS_MAGIC,
+        }
+    }
+
+    fn from_magic(magic: &[u8]) -> Option<Self> {
+        [PointEncoding::Uncompressed, PointEncoding::Compressed]
+            .into_iter()
+            .find(|encoding| encoding.magic() == magic)
+    }
+
+    pub(crate) fn g1_size(self) -> usize {
+        match self {
+            PointEncoding::Uncompressed => G1_UNCOMPRESSED_BYTES,
+            PointEncoding::Compressed => G1_COMPRESSED_BYTES,
+        }
+    }
+
+    pub(crate) fn g2_size(self) -> usize {
+        match self {
+            PointEncoding::Uncompressed => G2_UNCOMPRESSED_BYTES,
+            PointEncoding::Compressed => G2_COMPRESSED_BYTES,
+        }
+    }
+
+    fn encode_g1(self, point: &G1Affine) -> Vec<u8> {
+        match self {
+            PointEncoding::Uncompressed => point.to_uncompressed().to_vec(),
+            PointEncoding::Compressed => point.to_compressed().to_vec(),
+        }
+    }
+
+    fn encode_g2(self, point: &G2Affine) -> Vec<u8> {
+        match self {
+            PointEncoding::Uncompressed => point.to_uncompressed().to_vec(),
+            PointEncoding::Compressed => point.to_compressed().to_vec(),
+        }
+    }
+
+    fn decode_g1(self, bytes: &[u8]) -> anyhow::Result<G1Affine> {
+        match self {
+            PointEncoding::Uncompressed => G1Affine::from_uncompressed(bytes),
+            PointEncoding::Compressed => G1Affine::from_compressed(bytes),
+        }
+        .ok_or_else(|| CurveError::InvalidPoint("G1").into())
+    }
+
+    fn decode_g2(self, bytes: &[u8]) -> anyhow::Result<G2Affine> {
+        match self {
+            PointEncoding::Uncompressed => G2Affine::from_uncompressed(bytes),
+            PointEncoding::Compressed => G2Affine::from_compressed(bytes),
+        }
+        .ok_or_else(|| CurveError::InvalidPoint("G2").into())
+    }
+}
+
+impl ProvingParameters {
+    /// Write the parameters with uncompressed points, in the format read by
+    /// `MappedParameters`.
+    pub fn write<W: Write>(&self, writer: W) -> anyhow::Result<()> {
+        self.write_with_encoding(writer, PointEncoding::Uncompressed)
+    }
+
+    /// Write the parameters with the given point encoding.
+    pub fn write_with_encoding<W: Write>(
+        &self,
+        mut writer: W,
+        encoding: PointEncoding,
+    ) -> anyhow::Result<()> {
+        writer.write_all(encoding.magic())?;
+
+        writer.write_all(&encoding.encode_g1(&self.vk.alpha_g1))?;
+        writer.write_all(&encoding.encode_g1(&self.vk.beta_g1))?;
+        writer.write_all(&encoding.encode_g2(&self.vk.beta_g2))?;
+        writer.write_all(&encoding.encode_g2(&self.vk.gamma_g2))?;
+        writer.write_all(&encoding.encode_g1(&self.vk.delta_g1))?;
+        writer.write_all(&encoding.encode_g2(&self.vk.delta_g2))?;
+        write_g1_segment(&mut writer, &self.vk.ic, encoding)?;
+
+        write_g1_segment(&mut writer, &self.h_query, encoding)?;
+        write_g1_segment(&mut writer, &self.l_query, encoding)?;
+        write_g1_segment(&mut writer, &self.a_query, encoding)?;
+        write_g1_segment(&mut writer, &self.b_g1_query, encoding)?;
+
+        writer.write_all(&(self.b_g2_query.len() as u64).to_be_bytes())?;
+        for point in self.b_g2_query.iter() {
+            writer.write_all(&encoding.encode_g2(point))?;
+        }
+
+        if let Some(key) = &self.commitment {
+            write_g1_segment(&mut writer, &key.bases, encoding)?;
+            writer.write_all(&encoding.encode_g1(&key.blinding_g1))?;
+            writer.write_all(&encoding.encode_g1(&key.blinding_delta_g1))?;
+        }
+
+        Ok(())
+    }
+
+    /// Write the parameters wrapped in a zstd frame at the given compression
+    /// level. `MappedParameters::open` recognizes and decompresses the frame.
+    #[cfg(feature = "zstd")]
+    pub fn write_zstd<W: Write>(
+        &self,
+        writer: W,
+        encoding: PointEncoding,
+        level: i32,
+    ) -> anyhow::Result<()> {
+        let mut encoder = zstd::Encoder::new(writer, level)?;
+        self.write_with_encoding(&mut encoder, encoding)?;
+        encoder.finish()?;
+        Ok(())
+    }
+}
+
+fn write_g1_segment<W: Write>(
+    writer: &mut W,
+    points: &[G1Affine],
+    encoding: PointEncoding,
+) -> anyhow::Result<()> {
+    writer.write_all(&(points.len() as u64).to_be_bytes())?;
+    for point in points {
+        writer.write_all(&encoding.encode_g1(point))?;
+    }
+    Ok(())
+}
+
+/// Location of a query vector inside the file.
+#[derive(Clone, Copy, Debug)]
+struct Segment {
+    offset: usize,
+    len: usize,
+}
+
+/// Cursor over the raw file bytes.
+struct Cursor<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+    encoding: PointEncoding,
+}
+
+impl<'a> Cursor<'a> {
+    fn take(&mut self, n: usize) -> anyhow::Result<&'a [u8]> {
+        let end = self
+            .pos
+            .checked_add(n)
+            .filter(|end| *end <= self.bytes.len())
+            .ok_or_else(|| anyhow::anyhow!("Unexpected end of parameter file"))?;
+        let out = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(out)
+    }
+
+    fn read_len(&mut self) -> anyhow::Result<usize> {
+        let bytes: [u8; 8] = self.take(8)?.try_into()?;
+        Ok(usize::try_from(u64::from_be_bytes(bytes))?)
+    }
+
+    fn read_g1(&mut self) -> anyhow::Result<G1Affine> {
+        let bytes = self.take(self.encoding.g1_size())?;
+        self.encoding.decode_g1(bytes)
+    }
+
+    fn read_g2(&mut self) -> anyhow::Result<G2Affine> {
+        let bytes = self.take(self.encoding.g2_size())?;
+        self.encoding.decode_g2(bytes)
+    }
+
+    /// Skip over a segment, recording where it lives.
+    fn segment(&mut self, point_size: usize) -> anyhow::Result<Segment> {
+        let len = self.read_len()?;
+        let size = len
+            .checked_mul(point_size)
+            .ok_or_else(|| anyhow::anyhow!("Segment length overflow"))?;
+        let offset = self.pos;
+        self.take(size)?;
+        Ok(Segment { offset, len })
+    }
+}
+
+/// The bytes of a parameter file: mapped, or decompressed into memory.
+enum Backing {
+    Mapped(Mmap),
+    Owned(Vec<u8>),
+}
+
+impl Deref for Backing {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match self {
+            Backing::Mapped(mmap) => mmap,
+            Backing::Owned(bytes) => bytes,
+        }
+    }
+}
+
+#[cfg(feature = "zstd")]
+fn decompress(bytes: &[u8]) -> anyhow::Result<Vec<u8>> {
+    Ok(zstd::decode_all(bytes)?)
+}
+
+#[cfg(not(feature = "zstd"))]
+fn decompress(_bytes: &[u8]) -> anyhow::Result<Vec<u8>> {
+    Err(anyhow::anyhow!(
+        "Parameter file is zstd compressed, but zstd support is not enabled"
+    ))
+}
+
+/// Proving parameters backed by a memory-mapped file.
+///
+/// Only the verification key is decoded when the file is opened; the query
+/// vectors are decoded from the mapping each time the prover asks for them.
+pub struct MappedParameters {
+    data: Backing,
+    encoding: PointEncoding,
+    vk: VerificationKey,
+    h_query: Segment,
+    l_query: Segment,
+    a_query: Segment,
+    b_g1_query: Segment,
+    b_g2_query: Segment,
+    commitment: Option<CommitmentKey>,
+}
+
+impl MappedParameters {
+    /// Map a file written by `ProvingParameters::write` or one of its
+    /// variants.
+    pub fn open<P: AsRef<Path>>(path: P) -> anyhow::Result<Self> {
+        let file = File::open(path)?;
+        // SAFETY: the mapping is read-only; callers must not truncate or
+        // rewrite the file while it is mapped.
+        let mmap = unsafe { Mmap::map(&file)? };
+
+        let data = if mmap.starts_with(ZSTD_MAGIC) {
+            Backing::Owned(decompress(&mmap)?)
+        } else {
+            Backing::Mapped(mmap)
+        };
+
+        Self::parse(data)
+    }
+
+    fn parse(data: Backing) -> anyhow::Result<Self> {
+        let magic = data
+            .get(..PARAMS_MAGIC.len())
+            .ok_or_else(|| anyhow::anyhow!("Unexpected end of parameter file"))?;
+        let encoding = PointEncoding::from_magic(magic)
+            .ok_or_else(|| anyhow::anyhow!("Not a proving parameter file"))?;
+
+        let mut cursor = Cursor {
+            bytes: &data,
+            pos: PARAMS_MAGIC.len(),
+            encoding,
+        };
+
+        let alpha_g1 = cursor.read_g1()?;
+        let beta_g1 = cursor.read_g1()?;
+        let beta_g2 = cursor.read_g2()?;
+        let gamma_g2 = cursor.read_g2()?;
+        let delta_g1 = cursor.read_g1()?;
+        let delta_g2 = cursor.read_g2()?;
+        let ic_len = cursor.read_len()?;
+        let ic = (0..ic_len)
+            .map(|_| cursor.read_g1())
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        let h_query = cursor.segment(encoding.g1_size())?;
+        let l_query = cursor.segment(encoding.g1_size())?;
+        let a_query = cursor.segment(encoding.g1_size())?;
+        let b_g1_query = cursor.segment(encoding.g1_size())?;
+        let b_g2_query = cursor.segment(encoding.g2_size())?;
+
+        let commitment = if cursor.pos < data.len() {
+            let len = cursor.read_len()?;
+            let bases = (0..len)
+                .map(|_| cursor.read_g1())
+                .collect::<anyhow::Result<Vec<_>>>()?;
+            Some(CommitmentKey {
+                bases,
+                blinding_g1: cursor.read_g1()?,
+                blinding_delta_g1: cursor.read_g1()?,
+            })
+        } else {
+            None
+        };
+
+        if cursor.pos != data.len() {
+            return Err(anyhow::anyhow!("Trailing bytes in parameter file"));
+        }
+
+        Ok(Self {
+            vk: VerificationKey {
+                alpha_g1,
+                beta_g1,
+                beta_g2,
+                gamma_g2,
+                delta_g1,
+                delta_g2,
+                ic,
+            },
+            data,
+            encoding,
+            h_query,
+            l_query,
+            a_query,
+            b_g1_query,
+            b_g2_query,
+            commitment,
+        })
+    }
+
+    /// The point encoding of the underlying file.
+    pub fn encoding(&self) -> PointEncoding {
+        self.encoding
+    }
+
+    /// Decode every segment into an in-memory `ProvingParameters`.
+    pub fn load(&self) -> anyhow::Result<ProvingParameters> {
+        Ok(ProvingParameters {
+            vk: self.vk.clone(),
+            h_query: self.h_query()?,
+            l_query: self.l_query()?,
+            a_query: self.a_query()?,
+            b_g1_query: self.b_g1_query()?,
+            b_g2_query: self.b_g2_query()?,
+            commitment: self.commitment.clone(),
+        })
+    }
+
+    fn g1_segment(&self, segment: Segment) -> anyhow::Result<Arc<Vec<G1Affine>>> {
+        let size = self.encoding.g1_size();
+        let end = segment.offset + segment.len * size;
+        let points = self.data[segment.offset..end]
+            .chunks(size)
+            .map(|bytes| self.encoding.decode_g1(bytes))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        Ok(Arc::new(points))
+    }
+}
+
+impl ParamsSource for MappedParameters {
+    fn vk(&self) -> &VerificationKey {
+        &self.vk
+    }
+
+    fn h_query(&self) -> anyhow::Result<Arc<Vec<G1Affine>>> {
+        self.g1_segment(self.h_query)
+    }
+
+    fn l_query(&self) -> anyhow::Result<Arc<Vec<G1Affine>>> {
+        self.g1_segment(self.l_query)
+    }
+
+    fn a_query(&self) -> anyhow::Result<Arc<Vec<G1Affine>>> {
+        self.g1_segment(self.a_query)
+    }
+
+    fn b_g1_query(&self) -> anyhow::Result<Arc<Vec<G1Affine>>> {
+        self.g1_segment(self.b_g1_query)
+    }
+
+    fn b_g2_query(&self) -> anyhow::Result<Arc<Vec<G2Affine>>> {
+        let segment = self.b_g2_query;
+        let size = self.encoding.g2_size();
+        let end = segment.offset + segment.len * size;
+        let points = self.data[segment.offset..end]
+            .chunks(size)
+            .map(|bytes| self.encoding.decode_g2(bytes))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        Ok(Arc::new(points))
+    }
+
+    // the lengths come from the segment headers read by `open`
+
+    fn h_query_len(&self) -> anyhow::Result<usize> {
+        Ok(self.h_query.len)
+    }
+
+    fn l_query_len(&self) -> anyhow::Result<usize> {
+        Ok(self.l_query.len)
+    }
+
+    fn a_query_len(&self) -> anyhow::Result<usize> {
+        Ok(self.a_query.len)
+    }
+
+    fn b_g1_query_len(&self) -> anyhow::Result<usize> {
+        Ok(self.b_g1_query.len)
+    }
+
+    fn b_g2_query_len(&self) -> anyhow::Result<usize> {
+        Ok(self.b_g2_query.len)
+    }
+
+    fn commitment(&self) -> Option<&CommitmentKey> {
+        self.commitment.as_ref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zkper_curves::curves::bls12_381::curves::{g1::G1Projective, g2::G2Projective};
+    use zkper_rand::TestRng;
+
+    fn sample_parameters() -> ProvingParameters {
+        let mut rng = TestRng::new();
+        let mut g1 = |n: usize| -> Vec<G1Affine> {
+            (0..n)
+                .map(|_| G1Projective::random(&mut rng).to_affine())
+                .collect()
+        };
+
+        let vk = VerificationKey {
+            alpha_g1: G1Affine::generator(),
+            beta_g1: G1Affine::identity(),
+            beta_g2: G2Affine::generator(),
+            gamma_g2: G2Affine::generator().neg(),
+            delta_g1: G1Affine::generator(),
+            delta_g2: G2Affine::identity(),
+            ic: g1(2),
+        };
+
+        ProvingParameters {
+            vk,
+            h_query: Arc::new(g1(3)),
+            l_query: Arc::new(g1(1)),
+            a_query: Arc::new(g1(4)),
+            b_g1_query: Arc::new(vec![]),
+            b_g2_query: Arc::new(vec![
+                G2Affine::generator(),
+                G2Projective::generator().double().to_affine(),
+            ]),
+            commitment: None,
+        }
+    }
+
+    #[test]
+    fn test_mapped_round_trip() {
+        let params = sample_parameters();
+        let path = std::env::temp_dir().join(format!("zkper-params-{}.bin", std::process::id()));
+        params.write(File::create(&path).unwrap()).unwrap();
+
+        let mapped = MappedParameters::open(&path).unwrap();
+        assert_eq!(mapped.vk().ic, params.vk.ic);
+        assert_eq!(mapped.vk().gamma_g2, params.vk.gamma_g2);
+        assert_eq!(mapped.h_query().unwrap(), params.h_query);
+        assert_eq!(mapped.l_query().unwrap(), params.l_query);
+        assert_eq!(mapped.a_query().unwrap(), params.a_query);
+        assert_eq!(mapped.b_g1_query().unwrap(), params.b_g1_query);
+        assert_eq!(mapped.b_g2_query().unwrap(), params.b_g2_query);
+        for (mapped_len, len) in [
+            (mapped.h_query_len(), params.h_query.len()),
+            (mapped.l_query_len(), params.l_query.len()),
+            (mapped.a_query_len(), params.a_query.len()),
+            (mapped.b_g1_query_len(), params.b_g1_query.len()),
+            (mapped.b_g2_query_len(), params.b_g2_query.len()),
+        ] {
+            assert_eq!(mapped_len.unwrap(), len);
+        }
+
+        let mut truncated = vec![];
+        params.write(&mut truncated).unwrap();
+        truncated.pop();
+        std::fs::write(&path, &truncated).unwrap();
+        assert!(MappedParameters::open(&path).is_err());
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_compressed_round_trip() {
+        let params = sample_parameters();
+        let path = std::env::temp_dir().join(format!(
+            "zkper-params-compressed-{}.bin",
+            std::process::id()
+        ));
+
+        let mut uncompressed = vec![];
+        params.write(&mut uncompressed).unwrap();
+        let mut compressed = vec![];
+        params
+            .write_with_encoding(&mut compressed, PointEncoding::Compressed)
+            .unwrap();
+        // every point halves, the magic and the six segment lengths do not
+        assert_eq!(
+            compressed.len() * 2,
+            uncompressed.len() + PARAMS_MAGIC.len() + 6 * 8
+        );
+
+        std::fs::write(&path, &compressed).unwrap();
+        let mapped = MappedParameters::open(&path).unwrap();
+        assert_eq!(mapped.encoding(), PointEncoding::Compressed);
+        let loaded = mapped.load().unwrap();
+        assert_eq!(loaded.vk.ic, params.vk.ic);
+        assert_eq!(loaded.vk.delta_g2, params.vk.delta_g2);
+        assert_eq!(loaded.h_query, params.h_query);
+        assert_eq!(loaded.a_query, params.a_query);
+        assert_eq!(loaded.b_g2_query, params.b_g2_query);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[cfg(feature = "zstd")]
+    #[test]
+    fn test_zstd_round_trip() {
+        let params = sample_parameters();
+        let path =
+            std::env::temp_dir().join(format!("zkper-params-zstd-{}.bin", std::process::id()));
+
+        params
+            .write_zstd(File::create(&path).unwrap(), PointEncoding::Compressed, 3)
+            .unwrap();
+        let mapped = MappedParameters::open(&path).unwrap();
+        assert_eq!(mapped.vk().ic, params.vk.ic);
+        assert_eq!(mapped.h_query().unwrap(), params.h_query);
+        assert_eq!(mapped.b_g2_query().unwrap(), params.b_g2_query);
+        for (mapped_len, len) in [
+            (mapped.h_query_len(), params.h_query.len()),
+            (mapped.l_query_len(), params.l_query.len()),
+            (mapped.a_query_len(), params.a_query.len()),
+            (mapped.b_g1_query_len(), params.b_g1_query.len()),
+            (mapped.b_g2_query_len(), params.b_g2_query.len()),
+        ] {
+            assert_eq!(mapped_len.unwrap(), len);
+        }
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
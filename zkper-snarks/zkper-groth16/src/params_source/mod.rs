@@ -0,0 +1,98 @@
+use std::sync::Arc;
+
+use zkper_curves::curves::bls12_381::curves::{g1_affine::G1Affine, g2_affine::G2Affine};
+
+use crate::models::{
+    commitment::CommitmentKey, proving_parameters::ProvingParameters,
+    verification_key::VerificationKey,
+};
+
+pub mod file;
+
+pub use file::{MappedParameters, PointEncoding};
+
+/// Where the prover reads its query vectors from.
+///
+/// Each query is requested only when the prover needs it, so an implementation
+/// may decode it on demand instead of keeping every segment resident.
+pub trait ParamsSource {
+    /// The verification key.
+    fn vk(&self) -> &VerificationKey;
+
+    /// H query: ((τ^i * t(τ)) / δ) for i from 0 to m-2.
+    fn h_query(&self) -> anyhow::Result<Arc<Vec<G1Affine>>>;
+
+    /// L query for all auxiliary (private) inputs.
+    fn l_query(&self) -> anyhow::Result<Arc<Vec<G1Affine>>>;
+
+    /// A query in G1.
+    fn a_query(&self) -> anyhow::Result<Arc<Vec<G1Affine>>>;
+
+    /// B query in G1.
+    fn b_g1_query(&self) -> anyhow::Result<Arc<Vec<G1Affine>>>;
+
+    /// B query in G2.
+    fn b_g2_query(&self) -> anyhow::Result<Arc<Vec<G2Affine>>>;
+
+    /// Number of points in the H query. Override this when the length is
+    /// known without decoding the points.
+    fn h_query_len(&self) -> anyhow::Result<usize> {
+        Ok(self.h_query()?.len())
+    }
+
+    /// Number of points in the L query.
+    fn l_query_len(&self) -> anyhow::Result<usize> {
+        Ok(self.l_query()?.len())
+    }
+
+    /// Number of points in the A query.
+    fn a_query_len(&self) -> anyhow::Result<usize> {
+        Ok(self.a_query()?.len())
+    }
+
+    /// Number of points in the B query in G1.
+    fn b_g1_query_len(&self) -> anyhow::Result<usize> {
+        Ok(self.b_g1_query()?.len())
+    }
+
+    /// Number of points in the B query in G2.
+    fn b_g2_query_len(&self) -> anyhow::Result<usize> {
+        Ok(self.b_g2_query()?.len())
+    }
+
+    /// Key of the commitment to the first private variables, if the
+    /// parameters have one.
+    fn commitment(&self) -> Option<&CommitmentKey> {
+        None
+    }
+}
+
+impl ParamsSource for ProvingParameters {
+    fn vk(&self) -> &VerificationKey {
+        &self.vk
+    }
+
+    fn h_query(&self) -> anyhow::Result<Arc<Vec<G1Affine>>> {
+        Ok(self.h_query.clone())
+    }
+
+    fn l_query(&self) -> anyhow::Result<Arc<Vec<G1Affine>>> {
+        Ok(self.l_query.clone())
+    }
+
+    fn a_query(&self) -> anyhow::Result<Arc<Vec<G1Affine>>> {
+        Ok(self.a_query.clone())
+    }
+
+    fn b_g1_query(&self) -> anyhow::Result<Arc<Vec<G1Affine>>> {
+        Ok(self.b_g1_query.clone())
+    }
+
+    fn b_g2_query(&self) -> anyhow::Result<Arc<Vec<G2Affine>>> {
+        Ok(self.b_g2_query.clone())
+    }
+
+    fn commitment(&self) -> Option<&CommitmentKey> {
+        self.commitment.as_ref()
+    }
+}
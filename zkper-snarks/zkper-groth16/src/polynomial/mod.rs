@@ -0,0 +1,314 @@
+//! Dense univariate polynomials over the BLS12-381 scalar field.
+//!
+//! Coefficients are stored lowest degree first with no trailing zeros, so two
+//! equal polynomials always have equal coefficient vectors. Products of large
+//! polynomials go through `EvaluationDomain`, everything else is schoolbook.
+
+use rug::Integer;
+use zkper_curves::curves::bls12_381::BLS12_381_SCALAR;
+
+use crate::evaluation_domain::EvaluationDomain;
+
+/// Below this many coefficients in the smaller factor, `mul` multiplies
+/// directly instead of through an FFT.
+const FFT_MUL_THRESHOLD: usize = 32;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DensePolynomial {
+    coeffs: Vec<Integer>,
+}
+
+impl DensePolynomial {
+    /// Builds a polynomial from coefficients, lowest degree first. The
+    /// coefficients are reduced mod r and trailing zeros dropped.
+    pub fn new(coeffs: Vec<Integer>) -> Self {
+        let mut poly = Self {
+            coeffs: coeffs
+                .into_iter()
+                .map(|c| BLS12_381_SCALAR.reduce(&c))
+                .collect(),
+        };
+        poly.truncate();
+        poly
+    }
+
+    pub fn zero() -> Self {
+        Self { coeffs: vec![] }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.coeffs.is_empty()
+    }
+
+    /// The constant polynomial `c`.
+    pub fn constant(c: Integer) -> Self {
+        Self::new(vec![c])
+    }
+
+    /// Coefficients, lowest degree first, without trailing zeros.
+    pub fn coeffs(&self) -> &[Integer] {
+        &self.coeffs
+    }
+
+    /// The degree, taken to be 0 for the zero polynomial.
+    pub fn degree(&self) -> usize {
+        self.coeffs.len().saturating_sub(1)
+    }
+
+    /// The vanishing polynomial ∏ (X - root) of a set of roots.
+    pub fn vanishing(roots: &[Integer]) -> Self {
+        roots
+            .iter()
+            .fold(Self::constant(Integer::from(1)), |acc, root| {
+                acc.mul(&Self::new(vec![
+                    BLS12_381_SCALAR.neg(root.clone()),
+                    Integer::from(1),
+                ]))
+            })
+    }
+
+    /// X^n - 1, the vanishing polynomial of the order n multiplicative subgroup.
+    pub fn vanishing_on_subgroup(n: usize) -> Self {
+        let mut coeffs = vec![Integer::ZERO; n + 1];
+        coeffs[0] = BLS12_381_SCALAR.neg(Integer::from(1));
+        coeffs[n] = Integer::from(1);
+        Self::new(coeffs)
+    }
+
+    /// The unique polynomial of degree below `points.len()` through the given
+    /// (x, y) points. Returns None if two points share an x coordinate.
+    pub fn interpolate(points: &[(Integer, Integer)]) -> Option<Self> {
+        let xs = points.iter().map(|(x, _)| x.clone()).collect::<Vec<_>>();
+        let vanishing = Self::vanishing(&xs);
+
+        let mut result = Self::zero();
+        for (x, y) in points {
+            // basis numerator ∏_{j != i} (X - x_j), exact division by X - x_i
+            let (numerator, _) = vanishing.div_rem(&Self::new(vec![
+                BLS12_381_SCALAR.neg(x.clone()),
+                Integer::from(1),
+            ]))?;
+            let denominator = BLS12_381_SCALAR.invert(numerator.evaluate(x))?;
+            let scale = BLS12_381_SCALAR.mul(y.clone(), &denominator);
+            result = result.add(&numerator.mul_scalar(&scale));
+        }
+
+        Some(result)
+    }
+
+    /// Evaluates the polynomial at `x` with Horner's rule.
+    pub fn evaluate(&self, x: &Integer) -> Integer {
+        self.coeffs.iter().rev().fold(Integer::ZERO, |acc, c| {
+            BLS12_381_SCALAR.add(BLS12_381_SCALAR.mul(acc, x), c)
+        })
+    }
+
+    pub fn add(&self, other: &Self) -> Self {
+        let len = self.coeffs.len().max(other.coeffs.len());
+        let coeffs = (0..len)
+            .map(|i| match (self.coeffs.get(i), other.coeffs.get(i)) {
+                (Some(a), Some(b)) => BLS12_381_SCALAR.add(a.clone(), b),
+                (Some(a), None) | (None, Some(a)) => a.clone(),
+                (None, None) => unreachable!(),
+            })
+            .collect();
+        Self::from_reduced(coeffs)
+    }
+
+    pub fn neg(&self) -> Self {
+        Self::from_reduced(
+            self.coeffs
+                .iter()
+                .map(|c| BLS12_381_SCALAR.neg(c.clone()))
+                .collect(),
+        )
+    }
+
+    pub fn sub(&self, other: &Self) -> Self {
+        self.add(&other.neg())
+    }
+
+    /// Multiplies every coefficient by `scalar`.
+    pub fn mul_scalar(&self, scalar: &Integer) -> Self {
+        Self::from_reduced(
+            self.coeffs
+                .iter()
+                .map(|c| BLS12_381_SCALAR.mul(c.clone(), scalar))
+                .collect(),
+        )
+    }
+
+    /// Product of two polynomials, through an FFT once both are large enough
+    /// for it to pay off.
+    pub fn mul(&self, other: &Self) -> Self {
+        if self.is_zero() || other.is_zero() {
+            return Self::zero();
+        }
+
+        if self.coeffs.len().min(other.coeffs.len()) < FFT_MUL_THRESHOLD {
+            return self.naive_mul(other);
+        }
+
+        self.fft_mul(other)
+            .expect("product degree exceeds the two-adicity of the scalar field")
+    }
+
+    fn naive_mul(&self, other: &Self) -> Self {
+        let mut coeffs = vec![Integer::ZERO; self.coeffs.len() + other.coeffs.len() - 1];
+        for (i, a) in self.coeffs.iter().enumerate() {
+            for (j, b) in other.coeffs.iter().enumerate() {
+                let term = BLS12_381_SCALAR.mul(a.clone(), b);
+                coeffs[i + j] = BLS12_381_SCALAR.add(std::mem::take(&mut coeffs[i + j]), &term);
+            }
+        }
+        Self::from_reduced(coeffs)
+    }
+
+    fn fft_mul(&self, other: &Self) -> anyhow::Result<Self> {
+        let len = self.coeffs.len() + other.coeffs.len() - 1;
+        let padded = |coeffs: &[Integer]| {
+            let mut coeffs = coeffs.to_vec();
+            coeffs.resize(len, Integer::ZERO);
+            coeffs
+        };
+
+        let mut a = EvaluationDomain::new(padded(&self.coeffs))?;
+        let mut b = EvaluationDomain::new(padded(&other.coeffs))?;
+        a.fft();
+        b.fft();
+        a.mul_assign(&b);
+        a.ifft();
+
+        Ok(Self::from_reduced(a.coeffs))
+    }
+
+    /// Long division: returns (q, r) with self = q * divisor + r and
+    /// deg r < deg divisor. Returns None if the divisor is zero.
+    pub fn div_rem(&self, divisor: &Self) -> Option<(Self, Self)> {
+        let lead_inverse = BLS12_381_SCALAR.invert(divisor.coeffs.last()?.clone())?;
+        if self.coeffs.len() < divisor.coeffs.len() {
+            return Some((Self::zero(), self.clone()));
+        }
+
+        let mut remainder = self.coeffs.clone();
+        let mut quotient = vec![Integer::ZERO; self.coeffs.len() - divisor.coeffs.len() + 1];
+        for i in (0..quotient.len()).rev() {
+            let factor = BLS12_381_SCALAR.mul(
+                remainder[i + divisor.coeffs.len() - 1].clone(),
+                &lead_inverse,
+            );
+            for (j, d) in divisor.coeffs.iter().enumerate() {
+                let term = BLS12_381_SCALAR.mul(factor.clone(), d);
+                remainder[i + j] =
+                    BLS12_381_SCALAR.sub(std::mem::take(&mut remainder[i + j]), &term);
+            }
+            quotient[i] = factor;
+        }
+
+        Some((Self::from_reduced(quotient), Self::from_reduced(remainder)))
+    }
+
+    /// Wraps coefficients that are already reduced mod r.
+    fn from_reduced(coeffs: Vec<Integer>) -> Self {
+        let mut poly = Self { coeffs };
+        poly.truncate();
+        poly
+    }
+
+    fn truncate(&mut self) {
+        while self.coeffs.last().is_some_and(|c| c.is_zero()) {
+            self.coeffs.pop();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zkper_curves::{curves::bls12_381::Bls12_381ScalarField, traits::field::FieldTrait};
+    use zkper_rand::TestRng;
+
+    fn random_poly(rng: &mut TestRng, len: usize) -> DensePolynomial {
+        DensePolynomial::new(
+            (0..len)
+                .map(|_| Bls12_381ScalarField::random(rng))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let mut rng = TestRng::new();
+        let a = random_poly(&mut rng, 40);
+        let b = random_poly(&mut rng, 50);
+        let x = Bls12_381ScalarField::random(&mut rng);
+
+        let sum = a.add(&b);
+        assert_eq!(
+            sum.evaluate(&x),
+            BLS12_381_SCALAR.add(a.evaluate(&x), &b.evaluate(&x))
+        );
+        assert!(a.sub(&a).is_zero());
+        assert_eq!(
+            DensePolynomial::new(vec![Integer::from(5), Integer::ZERO]).degree(),
+            0
+        );
+
+        // FFT and schoolbook products agree
+        let product = a.mul(&b);
+        assert_eq!(product, a.naive_mul(&b));
+        assert_eq!(product.degree(), a.degree() + b.degree());
+        assert_eq!(
+            product.evaluate(&x),
+            BLS12_381_SCALAR.mul(a.evaluate(&x), &b.evaluate(&x))
+        );
+    }
+
+    #[test]
+    fn test_div_rem() {
+        let mut rng = TestRng::new();
+        let a = random_poly(&mut rng, 20);
+        let b = random_poly(&mut rng, 7);
+
+        let (q, r) = a.div_rem(&b).unwrap();
+        assert!(r.degree() < b.degree());
+        assert_eq!(q.mul(&b).add(&r), a);
+
+        let (q, r) = b.div_rem(&a).unwrap();
+        assert!(q.is_zero());
+        assert_eq!(r, b);
+
+        assert!(a.div_rem(&DensePolynomial::zero()).is_none());
+    }
+
+    #[test]
+    fn test_interpolate_and_vanishing() {
+        let mut rng = TestRng::new();
+        let poly = random_poly(&mut rng, 6);
+        let points = (0..6)
+            .map(|_| {
+                let x = Bls12_381ScalarField::random(&mut rng);
+                let y = poly.evaluate(&x);
+                (x, y)
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(DensePolynomial::interpolate(&points), Some(poly));
+
+        let duplicate = vec![points[0].clone(), points[0].clone()];
+        assert_eq!(DensePolynomial::interpolate(&duplicate), None);
+
+        let roots = points.iter().map(|(x, _)| x.clone()).collect::<Vec<_>>();
+        let z = DensePolynomial::vanishing(&roots);
+        assert_eq!(z.degree(), roots.len());
+        assert!(roots.iter().all(|root| z.evaluate(root).is_zero()));
+
+        // X^n - 1 vanishes on the FFT domain of size n
+        let domain = EvaluationDomain::new(vec![Integer::ZERO; 8]).unwrap();
+        let z = domain.vanishing_polynomial();
+        assert!(z.evaluate(&domain.omega).is_zero());
+        assert_eq!(
+            z.evaluate(Bls12_381ScalarField::MULTIPLICATIVE_GENERATOR),
+            domain.z(Bls12_381ScalarField::MULTIPLICATIVE_GENERATOR)
+        );
+    }
+}
@@ -1,54 +1,109 @@
 use crate::{
     circuit::Circuit,
-    constraints::{linear_combination::LinearCombination, Variable},
+    constraints::{linear_combination::LinearCombination, ConstraintSystemTrait, Mode, Variable},
+    context::context_scalar,
+    error::Groth16Error,
     evaluation_domain::EvaluationDomain,
-    models::{proof::Proof, proving_parameters::ProvingParameters},
-    multiexp::{multiexp, multiexp_g2},
+    models::{commitment::CommittedProof, proof::Proof, verification_key::VerificationKey},
+    multiexp::{multiexp_g2_with_config, multiexp_with_config, MultiexpConfig},
+    parallel::Schedule,
+    params_source::ParamsSource,
 };
 use rand::RngCore;
 use rug::Integer;
-use zkper_curves::curves::bls12_381::{Bls12_381ScalarField, BLS12_381_SCALAR};
+use std::{fmt, sync::Arc};
+use thiserror::Error;
+#[cfg(feature = "zeroize")]
+use zeroize::Zeroize;
+use zkper_base::redact::RedactedSlice;
+use zkper_curves::curves::bls12_381::{
+    curves::{g1::G1Projective, g1_affine::G1Affine, g2::G2Projective},
+    Bls12_381ScalarField, Scalar, BLS12_381_SCALAR,
+};
 use zkper_curves::traits::field::FieldTrait;
 
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub struct Density(pub Vec<bool>);
+/// Which entries of an assignment a query uses, with their number kept up to
+/// date so the offset of the next query segment is known without a scan.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DensityTracker {
+    bv: Vec<bool>,
+    total_density: usize,
+}
 
-impl Density {
+impl DensityTracker {
     pub fn new() -> Self {
-        Density(vec![])
+        Self::default()
+    }
+
+    /// A tracker of `len` entries that are all used.
+    pub fn full(len: usize) -> Self {
+        Self {
+            bv: vec![true; len],
+            total_density: len,
+        }
     }
 
     pub fn len(&self) -> usize {
-        self.0.len()
+        self.bv.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bv.is_empty()
     }
 
     pub fn set(&mut self, index: usize) {
-        self.0[index] = true;
+        self.inc(index);
     }
 
     pub fn get(&self, index: usize) -> bool {
-        self.0[index]
+        self.bv[index]
     }
 
     pub fn add_element(&mut self) {
-        self.0.push(false);
+        self.bv.push(false);
     }
 
     pub fn inc(&mut self, idx: usize) {
-        self.0[idx] = true;
+        if !self.bv[idx] {
+            self.bv[idx] = true;
+            self.total_density += 1;
+        }
     }
 
+    /// The number of used entries.
     pub fn count(&self) -> usize {
-        self.0.iter().filter(|&&x| x).count()
+        self.total_density
+    }
+
+    /// For every entry, its position among the used entries (the number of
+    /// used entries before it), or None if it is unused. This is the index of
+    /// its base in a query that only stores bases for used entries.
+    pub fn compacted_indices(&self) -> Vec<Option<usize>> {
+        let mut position = 0;
+        self.bv
+            .iter()
+            .map(|&used| {
+                used.then(|| {
+                    position += 1;
+                    position - 1
+                })
+            })
+            .collect()
+    }
+
+    /// Appends the entries of `other`, for a query whose bases for `other`
+    /// follow the bases for `self`.
+    pub fn extend(&mut self, other: &DensityTracker) {
+        self.bv.extend_from_slice(&other.bv);
+        self.total_density += other.total_density;
     }
 }
 
-#[derive(Debug)]
 pub struct ProvingSystem {
     // Density of queries
-    pub a_private_density: Density,
-    pub b_public_density: Density,
-    pub b_private_density: Density,
+    pub a_private_density: DensityTracker,
+    pub b_public_density: DensityTracker,
+    pub b_private_density: DensityTracker,
 
     // Evaluations of A, B, C polynomials
     pub a: Vec<Integer>,
@@ -56,12 +111,88 @@ pub struct ProvingSystem {
     pub c: Vec<Integer>,
 
     // Assignments of variables
-    pub public_assignment: Vec<Integer>,
-    pub private_assignment: Vec<Integer>,
+    pub public_assignment: Vec<Scalar>,
+    pub private_assignment: Vec<Scalar>,
+}
+
+// The evaluations and the private assignment are derived from the witness,
+// only their lengths are printed.
+impl fmt::Debug for ProvingSystem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ProvingSystem")
+            .field("a_private_density", &self.a_private_density)
+            .field("b_public_density", &self.b_public_density)
+            .field("b_private_density", &self.b_private_density)
+            .field("a", &RedactedSlice(&self.a))
+            .field("b", &RedactedSlice(&self.b))
+            .field("c", &RedactedSlice(&self.c))
+            .field("public_assignment", &self.public_assignment)
+            .field(
+                "private_assignment",
+                &RedactedSlice(&self.private_assignment),
+            )
+            .finish()
+    }
 }
 
+impl Default for ProvingSystem {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Wipes the evaluations and both assignments, which are all derived from
+/// the witness.
+#[cfg(feature = "zeroize")]
+impl Zeroize for ProvingSystem {
+    fn zeroize(&mut self) {
+        zeroize_integers(&mut self.a);
+        zeroize_integers(&mut self.b);
+        zeroize_integers(&mut self.c);
+        self.public_assignment.zeroize();
+        self.private_assignment.zeroize();
+    }
+}
+
+/// Wipes and empties `values`, one at a time through the `Zeroize` of
+/// `Scalar`, which works whether or not they are reduced.
+#[cfg(feature = "zeroize")]
+fn zeroize_integers(values: &mut Vec<Integer>) {
+    for value in values.drain(..) {
+        Bls12_381ScalarField(value).zeroize();
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl Drop for ProvingSystem {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl zeroize::ZeroizeOnDrop for ProvingSystem {}
+
 impl ProvingSystem {
-    pub fn new_private(&mut self, val: Integer) -> anyhow::Result<Variable> {
+    /// Creates a proving system holding only the constant one, like
+    /// `ConstraintSystem::new`.
+    pub fn new() -> Self {
+        let mut b_public_density = DensityTracker::new();
+        b_public_density.add_element();
+
+        ProvingSystem {
+            a_private_density: DensityTracker::new(),
+            b_public_density,
+            b_private_density: DensityTracker::new(),
+            a: vec![],
+            b: vec![],
+            c: vec![],
+            public_assignment: vec![Scalar::from(Integer::from(1))],
+            private_assignment: vec![],
+        }
+    }
+
+    pub fn new_private(&mut self, val: Scalar) -> Result<Variable, Groth16Error> {
         self.private_assignment.push(val);
         self.a_private_density.add_element();
         self.b_private_density.add_element();
@@ -69,19 +200,42 @@ impl ProvingSystem {
         Ok(Variable::Private(self.private_assignment.len() - 1))
     }
 
-    pub fn new_public(&mut self, val: Integer) -> anyhow::Result<Variable> {
+    pub fn new_public(&mut self, val: Scalar) -> Result<Variable, Groth16Error> {
         self.public_assignment.push(val);
         self.b_public_density.add_element();
 
         Ok(Variable::Public(self.public_assignment.len() - 1))
     }
 
+    /// The public assignment followed by the private one, the order of the
+    /// bases in the A and B queries.
+    pub fn full_assignment(&self) -> Vec<Scalar> {
+        let mut assignment = self.public_assignment.clone();
+        assignment.extend_from_slice(&self.private_assignment);
+        assignment
+    }
+
+    /// Density of the A query over `full_assignment`: every public input has
+    /// a base, private ones only where they appear in A.
+    pub fn a_density(&self) -> DensityTracker {
+        let mut density = DensityTracker::full(self.public_assignment.len());
+        density.extend(&self.a_private_density);
+        density
+    }
+
+    /// Density of the B queries over `full_assignment`.
+    pub fn b_density(&self) -> DensityTracker {
+        let mut density = self.b_public_density.clone();
+        density.extend(&self.b_private_density);
+        density
+    }
+
     fn eval(
         lc: &LinearCombination,
-        mut input_density: Option<&mut Density>,
-        mut aux_density: Option<&mut Density>,
-        input_assignment: &[Integer],
-        aux_assignment: &[Integer],
+        mut input_density: Option<&mut DensityTracker>,
+        mut aux_density: Option<&mut DensityTracker>,
+        input_assignment: &[Scalar],
+        aux_assignment: &[Scalar],
     ) -> Integer {
         let mut acc = Integer::ZERO;
 
@@ -91,13 +245,13 @@ impl ProvingSystem {
             if !coeff.is_zero() {
                 match index {
                     Variable::Public(i) => {
-                        tmp = input_assignment[*i].clone();
+                        tmp = input_assignment[*i].0.clone();
                         if let Some(ref mut v) = input_density {
                             v.inc(*i);
                         }
                     }
                     Variable::Private(i) => {
-                        tmp = aux_assignment[*i].clone();
+                        tmp = aux_assignment[*i].0.clone();
                         if let Some(ref mut v) = aux_density {
                             v.inc(*i);
                         }
@@ -149,31 +303,65 @@ impl ProvingSystem {
     }
 }
 
-/// Create a Groth16 proof using randomness `r` and `s` and the provided
-/// R1CS-to-QAP reduction.
-pub fn create_proof<C: Circuit, R: RngCore>(
-    circuit: C,
-    params: &ProvingParameters,
-    mut rng: &mut R,
-) -> anyhow::Result<Proof> {
-    if params.vk.delta_g1.is_identity() || params.vk.delta_g2.is_identity() {
-        return Err(anyhow::anyhow!("Invalid verification key, ATTACK"));
-    }
-
-    let mut prover = ProvingSystem {
-        a_private_density: Density::new(),
-        b_public_density: Density::new(),
-        b_private_density: Density::new(),
-        a: vec![],
-        b: vec![],
-        c: vec![],
-        public_assignment: vec![],
-        private_assignment: vec![],
-    };
+/// Runs the circuit and the R1CS-to-QAP reduction, returning the synthesized
+/// system and the coefficients of h(x) used against the H query.
+///
+/// `context` is assigned to the context input that follows the circuit's own
+/// public inputs.
+impl ConstraintSystemTrait for ProvingSystem {
+    fn mode(&self) -> Mode {
+        Mode::Prove
+    }
 
-    prover.new_public(Integer::from(1))?;
+    fn alloc_private<F>(&mut self, value: F) -> Result<Variable, Groth16Error>
+    where
+        F: FnOnce() -> anyhow::Result<Scalar>,
+    {
+        self.new_private(value().map_err(Groth16Error::synthesis)?)
+    }
+
+    fn alloc_public<F>(&mut self, value: F) -> Result<Variable, Groth16Error>
+    where
+        F: FnOnce() -> anyhow::Result<Scalar>,
+    {
+        self.new_public(value().map_err(Groth16Error::synthesis)?)
+    }
 
-    circuit.synthesize_proof(&mut prover)?;
+    fn enforce(&mut self, a: LinearCombination, b: LinearCombination, c: LinearCombination) {
+        ProvingSystem::enforce(self, a, b, c);
+    }
+}
+
+/// Errors of the prover that callers may want to tell apart.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum ProverError {
+    /// The circuit allocates a different number of public inputs than the
+    /// verification key has IC points for. Counts exclude the constant one
+    /// and the context input.
+    #[error("circuit has {circuit} public inputs but the verification key expects {vk}")]
+    PublicInputMismatch { circuit: usize, vk: usize },
+}
+
+/// Runs `circuit` and computes the coefficients of H. `num_inputs` is the
+/// length of the IC vector of the verification key; the public inputs are
+/// checked against it before the FFTs, so wrong parameters fail right away.
+pub(crate) fn synthesize<C: Circuit>(
+    circuit: C,
+    context: Option<&[u8]>,
+    num_inputs: usize,
+) -> Result<(ProvingSystem, Vec<Scalar>), Groth16Error> {
+    let mut prover = ProvingSystem::new();
+
+    circuit.synthesize(&mut prover)?;
+    prover.new_public(Scalar::from(context_scalar(context)))?;
+
+    if prover.public_assignment.len() != num_inputs {
+        return Err(ProverError::PublicInputMismatch {
+            circuit: prover.public_assignment.len() - 2,
+            vk: num_inputs.saturating_sub(2),
+        }
+        .into());
+    }
 
     for i in 0..prover.public_assignment.len() {
         let a = LinearCombination::new_variable(Variable::Public(i));
@@ -183,11 +371,9 @@ pub fn create_proof<C: Circuit, R: RngCore>(
         prover.enforce(a, b, c);
     }
 
-    let verify_key = params.vk.clone();
-
-    let mut a = EvaluationDomain::new(prover.a)?;
-    let mut b = EvaluationDomain::new(prover.b)?;
-    let mut c = EvaluationDomain::new(prover.c)?;
+    let mut a = EvaluationDomain::new(std::mem::take(&mut prover.a))?;
+    let mut b = EvaluationDomain::new(std::mem::take(&mut prover.b))?;
+    let mut c = EvaluationDomain::new(std::mem::take(&mut prover.c))?;
 
     a.ifft();
     a.coset_fft();
@@ -196,10 +382,13 @@ pub fn create_proof<C: Circuit, R: RngCore>(
     c.ifft();
     c.coset_fft();
 
+    // b and c are derived from the witness as well
     a.mul_assign(&b);
-    drop(b);
+    #[cfg(feature = "zeroize")]
+    zeroize_integers(&mut b.coeffs);
     a.sub_assign(&c);
-    drop(c);
+    #[cfg(feature = "zeroize")]
+    zeroize_integers(&mut c.coeffs);
     a.divide_by_z_on_coset();
     a.icoset_fft();
 
@@ -207,87 +396,420 @@ pub fn create_proof<C: Circuit, R: RngCore>(
     let a_len = a.len() - 1;
     a.truncate(a_len);
 
-    let h_query = multiexp(params.h_query.clone(), None, None, a.into())?;
-    let l_query = multiexp(
-        params.l_query.clone(),
-        None,
-        None,
-        prover.private_assignment.clone().into(),
-    )?;
+    Ok((prover, a.into_iter().map(Scalar::from).collect()))
+}
+
+/// The H query has one base per coefficient of h(x), both sized by the same
+/// evaluation domain. Parameters generated for a different domain size are
+/// rejected rather than yielding a proof that fails to verify.
+pub(crate) fn check_h_query_len<T>(h_query: &[T], h: &[Scalar]) -> Result<(), Groth16Error> {
+    if h_query.len() != h.len() {
+        return Err(Groth16Error::HQueryMismatch {
+            bases: h_query.len(),
+            needed: h.len(),
+        });
+    }
+
+    Ok(())
+}
+
+/// Results of the five multi-exponentiations of a proof, with the public and
+/// private halves of the A and B queries already summed.
+pub(crate) struct QueryAnswers {
+    pub h: G1Projective,
+    pub l: G1Projective,
+    pub a: G1Projective,
+    pub b_g1: G1Projective,
+    pub b_g2: G2Projective,
+}
+
+/// Combines the query answers with the blinding factors `r` and `s`.
+pub(crate) fn assemble_proof(
+    verify_key: &VerificationKey,
+    answers: QueryAnswers,
+    r: &Scalar,
+    s: &Scalar,
+) -> Proof {
+    let mut g_a = verify_key.delta_g1.to_curve().mul_scalar(r);
+    g_a = g_a.add(&verify_key.alpha_g1.to_curve());
+
+    let mut g_b = verify_key.delta_g2.to_curve().mul_scalar_gls(s);
+    g_b = g_b.add(&verify_key.beta_g2.to_curve());
+
+    let rs = r.mul(s);
+    let mut g_c = verify_key.delta_g1.to_curve().mul_scalar(&rs);
+    g_c = g_c.add(&(verify_key.alpha_g1.to_curve().mul_scalar(s)));
+    g_c = g_c.add(&(verify_key.beta_g1.to_curve().mul_scalar(r)));
+
+    let a_answer = answers.a;
+    let g_a = g_a.add(&a_answer);
+
+    let a_answer = a_answer.mul_scalar(s);
+    let g_c = g_c.add(&a_answer);
+
+    let g_b = g_b.add(&answers.b_g2);
 
-    let a_public = multiexp(
-        params.a_query.clone(),
+    let b1_answer = answers.b_g1.mul_scalar(r);
+    let g_c = g_c.add(&b1_answer);
+
+    let g_c = g_c.add(&answers.h);
+    let g_c = g_c.add(&answers.l);
+
+    let [a, c]: [G1Affine; 2] = G1Projective::batch_normalize(&[g_a, g_c])
+        .try_into()
+        .expect("two points in, two points out");
+
+    Proof {
+        a,
+        b: g_b.to_affine(),
+        c,
+    }
+}
+
+/// Create a Groth16 proof using randomness `r` and `s` and the provided
+/// R1CS-to-QAP reduction.
+///
+/// The query vectors are requested from `params` one at a time, right before
+/// the multi-exponentiation that consumes them.
+pub fn create_proof<C: Circuit, P: ParamsSource + ?Sized, R: RngCore>(
+    circuit: C,
+    params: &P,
+    rng: &mut R,
+) -> Result<Proof, Groth16Error> {
+    create_proof_with_context(circuit, params, None, rng)
+}
+
+/// Like `create_proof`, binding the proof to `context` (see `crate::context`).
+/// It then only verifies with `verify_proof_with_context` and the same context.
+pub fn create_proof_with_context<C: Circuit, P: ParamsSource + ?Sized, R: RngCore>(
+    circuit: C,
+    params: &P,
+    context: Option<&[u8]>,
+    rng: &mut R,
+) -> Result<Proof, Groth16Error> {
+    create_proof_with_schedule(circuit, params, context, Schedule::default(), rng)
+}
+
+/// Like `create_proof_with_context`, spreading the multi-exponentiations over
+/// threads according to `schedule`. The proof is the same under every
+/// schedule for the same RNG, see `crate::parallel`.
+pub fn create_proof_with_schedule<C: Circuit, P: ParamsSource + ?Sized, R: RngCore>(
+    circuit: C,
+    params: &P,
+    context: Option<&[u8]>,
+    schedule: Schedule,
+    rng: &mut R,
+) -> Result<Proof, Groth16Error> {
+    if params.commitment().is_some() {
+        return Err(Groth16Error::CommittedParameters);
+    }
+
+    let (proof, _) = prove(circuit, params, context, schedule, rng)?;
+    Ok(proof)
+}
+
+/// Like `create_proof_with_context` for parameters with a commitment key,
+/// see `crate::models::commitment`. Returns the proof with its commitment D
+/// and the opening of D.
+pub fn create_committed_proof<C: Circuit, P: ParamsSource + ?Sized, R: RngCore>(
+    circuit: C,
+    params: &P,
+    context: Option<&[u8]>,
+    rng: &mut R,
+) -> Result<(CommittedProof, Scalar), Groth16Error> {
+    if params.commitment().is_none() {
+        return Err(Groth16Error::MissingCommitmentKey);
+    }
+
+    let (proof, commitment) = prove(circuit, params, context, Schedule::default(), rng)?;
+    let (d, opening) = commitment.expect("parameters have a commitment key");
+    Ok((CommittedProof { proof, d }, opening))
+}
+
+/// Creates a proof, and the commitment D with its opening when the
+/// parameters have a commitment key.
+fn prove<C: Circuit, P: ParamsSource + ?Sized, R: RngCore>(
+    circuit: C,
+    params: &P,
+    context: Option<&[u8]>,
+    schedule: Schedule,
+    mut rng: &mut R,
+) -> Result<(Proof, Option<(G1Affine, Scalar)>), Groth16Error> {
+    if params.vk().delta_g1.is_identity() || params.vk().delta_g2.is_identity() {
+        return Err(Groth16Error::DegenerateVerifyingKey);
+    }
+
+    let (prover, h) = synthesize(circuit, context, params.vk().ic.len())?;
+
+    let verify_key = params.vk().clone();
+    let config = MultiexpConfig::new().with_schedule(schedule);
+
+    let h_bases = params.h_query().map_err(Groth16Error::parameters)?;
+    check_h_query_len(&h_bases, &h)?;
+    let h_query = multiexp_with_config(h_bases, None, None, h.into(), &config)?;
+
+    // committed variables have no L query bases, their terms go into D
+    let committed = params.commitment().map_or(0, |key| key.len());
+    if committed > prover.private_assignment.len() {
+        return Err(Groth16Error::CommitmentLength {
+            expected: committed,
+            actual: prover.private_assignment.len(),
+        });
+    }
+    let l_query = multiexp_with_config(
+        params.l_query().map_err(Groth16Error::parameters)?,
         None,
         None,
-        prover.public_assignment.clone().into(),
-    )?;
-    let a_private = multiexp(
-        params.a_query.clone(),
-        prover.public_assignment.len().into(),
-        Some(&prover.a_private_density),
-        prover.private_assignment.clone().into(),
+        prover.private_assignment[committed..].to_vec().into(),
+        &config,
     )?;
 
-    let b_g1_public = multiexp(
-        params.b_g1_query.clone(),
+    // public and private variables share the A and B queries, one MSM each
+    let assignment: Arc<Vec<Scalar>> = prover.full_assignment().into();
+    let a_density = prover.a_density();
+    let b_density = prover.b_density();
+
+    let a = multiexp_with_config(
+        params.a_query().map_err(Groth16Error::parameters)?,
         None,
-        Some(&prover.b_public_density),
-        prover.public_assignment.clone().into(),
+        Some(&a_density),
+        assignment.clone(),
+        &config,
     )?;
-    let b_g1_private = multiexp(
-        params.b_g1_query.clone(),
-        prover.b_public_density.count().into(),
-        Some(&prover.b_private_density),
-        prover.private_assignment.clone().into(),
-    )?;
-
-    let b_g2_public = multiexp_g2(
-        params.b_g2_query.clone(),
+    let b_g1 = multiexp_with_config(
+        params.b_g1_query().map_err(Groth16Error::parameters)?,
         None,
-        Some(&prover.b_public_density),
-        prover.public_assignment.clone().into(),
+        Some(&b_density),
+        assignment.clone(),
+        &config,
     )?;
-    let b_g2_private = multiexp_g2(
-        params.b_g2_query.clone(),
-        prover.b_public_density.count().into(),
-        Some(&prover.b_private_density),
-        prover.private_assignment.clone().into(),
+    let b_g2 = multiexp_g2_with_config(
+        params.b_g2_query().map_err(Groth16Error::parameters)?,
+        None,
+        Some(&b_density),
+        assignment,
+        &config,
     )?;
 
-    let r = Bls12_381ScalarField::random(&mut rng);
-    let s = Bls12_381ScalarField::random(&mut rng);
+    let r = Scalar::from(Bls12_381ScalarField::random(&mut rng));
+    let s = Scalar::from(Bls12_381ScalarField::random(&mut rng));
 
-    let mut g_a = verify_key.delta_g1.to_curve().mul_scalar(&r);
-    g_a = g_a.add(&verify_key.alpha_g1.to_curve());
+    // D commits to the first variables with a fresh opening v, cancelled in C
+    let (l_query, commitment) = match params.commitment() {
+        Some(key) => {
+            let opening = Scalar::from(Bls12_381ScalarField::random(&mut rng));
+            let d = key.commit(&prover.private_assignment[..committed], &opening)?;
+            let l_query = l_query.sub(&key.blinding_delta_g1.to_curve().mul_scalar(&opening));
+            (l_query, Some((d, opening)))
+        }
+        None => (l_query, None),
+    };
 
-    let mut g_b = verify_key.delta_g2.to_curve().mul_scalar(&s);
-    g_b = g_b.add(&verify_key.beta_g2.to_curve());
+    let answers = QueryAnswers {
+        h: h_query,
+        l: l_query,
+        a,
+        b_g1,
+        b_g2,
+    };
 
-    let rs = BLS12_381_SCALAR.mul(r.clone(), &s);
-    let mut g_c = verify_key.delta_g1.to_curve().mul_scalar(&rs);
-    g_c = g_c.add(&(verify_key.alpha_g1.to_curve().mul_scalar(&s)));
-    g_c = g_c.add(&(verify_key.beta_g1.to_curve().mul_scalar(&r)));
+    Ok((assemble_proof(&verify_key, answers, &r, &s), commitment))
+}
 
-    let a_answer = a_public.add(&a_private);
-    let g_a = g_a.add(&a_answer);
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::generate_proving_parameters;
+    use zkper_rand::TestRng;
 
-    let a_answer = a_answer.mul_scalar(&s);
-    let g_c = g_c.add(&a_answer);
+    /// `inputs` public squares of 2.
+    struct Squares {
+        inputs: usize,
+    }
 
-    let b1_answer = b_g1_public.add(&b_g1_private);
-    let b2_answer = b_g2_public.add(&b_g2_private);
+    impl Circuit for Squares {
+        fn synthesize<CS: ConstraintSystemTrait>(&self, cs: &mut CS) -> Result<(), Groth16Error> {
+            for _ in 0..self.inputs {
+                let x = cs.alloc_private(|| Ok(Scalar::from(Integer::from(2))))?;
+                let y = cs.alloc_public(|| Ok(Scalar::from(Integer::from(4))))?;
+                cs.enforce(
+                    LinearCombination::new_variable(x),
+                    LinearCombination::new_variable(x),
+                    LinearCombination::new_variable(y),
+                );
+            }
+            Ok(())
+        }
+    }
 
-    let g_b = g_b.add(&b2_answer);
+    /// One private variable, constrained unless `unconstrained`, whose value
+    /// comes from `value`.
+    struct Single<F> {
+        value: F,
+        unconstrained: bool,
+    }
 
-    let b1_answer = b1_answer.mul_scalar(&r);
-    let g_c = g_c.add(&b1_answer);
+    impl<F: Fn() -> anyhow::Result<Scalar>> Circuit for Single<F> {
+        fn synthesize<CS: ConstraintSystemTrait>(&self, cs: &mut CS) -> Result<(), Groth16Error> {
+            let x = cs.alloc_private(&self.value)?;
+            if !self.unconstrained {
+                cs.enforce(
+                    LinearCombination::new_variable(x),
+                    LinearCombination::new_variable(x),
+                    LinearCombination::new_variable(x),
+                );
+            }
+            Ok(())
+        }
+    }
 
-    let g_c = g_c.add(&h_query);
-    let g_c = g_c.add(&l_query);
+    #[test]
+    fn test_typed_errors() {
+        let mut rng = TestRng::new();
+        let one = || Ok(Scalar::from(Integer::from(1)));
+
+        let unconstrained = Single {
+            value: one,
+            unconstrained: true,
+        };
+        assert!(matches!(
+            generate_proving_parameters(unconstrained, &mut rng).unwrap_err(),
+            Groth16Error::UnconstrainedVariable
+        ));
 
-    Ok(Proof {
-        a: g_a.to_affine(),
-        b: g_b.to_affine(),
-        c: g_c.to_affine(),
-    })
+        let params = generate_proving_parameters(
+            Single {
+                value: one,
+                unconstrained: false,
+            },
+            &mut rng,
+        )
+        .unwrap();
+
+        // errors of this crate raised inside the circuit keep their variant
+        let missing = Single {
+            value: || Err(Groth16Error::MissingAssignment("private variable").into()),
+            unconstrained: false,
+        };
+        assert!(matches!(
+            create_proof(missing, &params, &mut rng).unwrap_err(),
+            Groth16Error::MissingAssignment("private variable")
+        ));
+
+        let failing = Single {
+            value: || Err(anyhow::anyhow!("no witness")),
+            unconstrained: false,
+        };
+        assert!(matches!(
+            create_proof(failing, &params, &mut rng).unwrap_err(),
+            Groth16Error::Synthesis(source) if source.to_string() == "no witness"
+        ));
+    }
+
+    #[test]
+    fn test_public_input_mismatch() {
+        let mut rng = TestRng::new();
+        let params = generate_proving_parameters(Squares { inputs: 2 }, &mut rng).unwrap();
+        assert!(create_proof(Squares { inputs: 2 }, &params, &mut rng).is_ok());
+
+        for inputs in [1, 3] {
+            let err = create_proof(Squares { inputs }, &params, &mut rng).unwrap_err();
+            assert!(matches!(
+                err,
+                Groth16Error::Prover(ProverError::PublicInputMismatch { circuit, vk: 2 })
+                    if circuit == inputs
+            ));
+        }
+    }
+
+    #[test]
+    fn test_schedules_give_identical_proofs() {
+        use crate::verifier::{prepare_verifying_key, verify_proof};
+
+        let mut rng = TestRng::new();
+        let params = generate_proving_parameters(Squares { inputs: 3 }, &mut rng).unwrap();
+        let pvk = prepare_verifying_key(&params.vk);
+        let inputs = vec![Scalar::from(Integer::from(4)); 3];
+
+        let prove = |schedule| {
+            let mut rng = TestRng::from_seed(7);
+            create_proof_with_schedule(Squares { inputs: 3 }, &params, None, schedule, &mut rng)
+                .unwrap()
+        };
+        let serial = prove(Schedule::Serial);
+        assert!(verify_proof(&pvk, &serial, &inputs).unwrap());
+
+        for schedule in [Schedule::Parallel, Schedule::Deterministic] {
+            let proof = prove(schedule);
+            assert_eq!(proof, serial);
+            assert_eq!(proof.a.to_compressed(), serial.a.to_compressed());
+            assert_eq!(proof.b.to_compressed(), serial.b.to_compressed());
+            assert_eq!(proof.c.to_compressed(), serial.c.to_compressed());
+        }
+    }
+
+    #[test]
+    fn test_rerandomize() {
+        use crate::verifier::{prepare_verifying_key, verify_proof};
+
+        let mut rng = TestRng::new();
+        let params = generate_proving_parameters(Squares { inputs: 2 }, &mut rng).unwrap();
+        let pvk = prepare_verifying_key(&params.vk);
+        let inputs = vec![Scalar::from(Integer::from(4)); 2];
+
+        let proof = create_proof(Squares { inputs: 2 }, &params, &mut rng).unwrap();
+        let rerandomized = proof.rerandomize(&params.vk, &mut rng);
+        assert!(verify_proof(&pvk, &rerandomized, &inputs).unwrap());
+        assert_ne!(rerandomized.a, proof.a);
+        assert_ne!(rerandomized.b, proof.b);
+        assert_ne!(rerandomized.c, proof.c);
+
+        // still bound to the statement
+        let other = vec![Scalar::from(Integer::from(5)); 2];
+        assert!(!verify_proof(&pvk, &rerandomized, &other).unwrap());
+
+        // and rerandomizing an invalid proof does not make it valid
+        let forged = Proof {
+            c: proof.a.clone(),
+            ..proof
+        };
+        let forged = forged.rerandomize(&params.vk, &mut rng);
+        assert!(!verify_proof(&pvk, &forged, &inputs).unwrap());
+    }
+
+    #[cfg(feature = "zeroize")]
+    #[test]
+    fn test_zeroize() {
+        // one, the two squares and the context
+        let (mut prover, h) = synthesize(Squares { inputs: 2 }, None, 4).unwrap();
+        assert!(!h.is_empty());
+        assert_eq!(prover.private_assignment.len(), 2);
+
+        prover.zeroize();
+        assert!(prover.a.is_empty() && prover.b.is_empty() && prover.c.is_empty());
+        assert!(prover.public_assignment.is_empty());
+        assert!(prover.private_assignment.is_empty());
+    }
+
+    #[test]
+    fn test_density_tracker() {
+        let mut density = DensityTracker::new();
+        for _ in 0..5 {
+            density.add_element();
+        }
+        density.inc(1);
+        density.inc(3);
+        density.inc(3);
+        assert_eq!(density.count(), 2);
+        assert_eq!(
+            density.compacted_indices(),
+            vec![None, Some(0), None, Some(1), None]
+        );
+
+        let mut merged = DensityTracker::full(2);
+        merged.extend(&density);
+        assert_eq!(merged.len(), 7);
+        assert_eq!(merged.count(), 4);
+        assert_eq!(merged.compacted_indices()[5], Some(3));
+    }
 }
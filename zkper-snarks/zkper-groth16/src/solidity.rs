@@ -0,0 +1,286 @@
+//! Verifying proofs on chain.
+//!
+//! [`solidity_verifier`] turns a [`VerifierKey`] into a Solidity contract
+//! that checks proofs with the BLS12-381 precompiles of EIP-2537 (G1ADD at
+//! 0x0b, G1MSM at 0x0c and PAIRING_CHECK at 0x0f). BN254 precompiles cannot
+//! verify proofs of this crate, which are over BLS12-381.
+//!
+//! The contract exposes
+//!
+//! ```solidity
+//! function verifyProof(bytes calldata proof, uint256[] calldata input, uint256 context)
+//!     external view returns (bool);
+//! ```
+//!
+//! where `proof` is [`encode_proof`], `input` the public inputs of the circuit
+//! and `context` the [`context_scalar`] the proof was bound to, zero without a
+//! context. [`encode_calldata`] builds the whole call.
+//!
+//! Points use the EIP-2537 encoding: every base field element is 64 bytes, 16
+//! zero bytes and then 48 big-endian bytes, a G1 point is x || y, a G2 point is
+//! x.c0 || x.c1 || y.c0 || y.c1 and the point at infinity is all zeros.
+
+use rug::Integer;
+use zkper_curves::curves::bls12_381::{
+    curves::{g1_affine::G1Affine, g2_affine::G2Affine},
+    fields::base::Bls12_381BaseField,
+    Scalar, BLS12_381_SCALAR,
+};
+
+use crate::{
+    attestation::{scalar_bytes, to_hex},
+    context::context_scalar,
+    error::Groth16Error,
+    models::{proof::Proof, verification_key::VerifierKey},
+    verifier::prepare_verifier_key,
+};
+
+/// Bytes of a base field element in the EIP-2537 encoding.
+pub const FP_BYTES: usize = 64;
+
+/// Bytes of a G1 point in the EIP-2537 encoding.
+pub const G1_BYTES: usize = 2 * FP_BYTES;
+
+/// Bytes of a G2 point in the EIP-2537 encoding.
+pub const G2_BYTES: usize = 4 * FP_BYTES;
+
+/// Bytes of an encoded proof: A, B and C.
+pub const PROOF_BYTES: usize = 2 * G1_BYTES + G2_BYTES;
+
+/// The first four bytes of keccak256("verifyProof(bytes,uint256[],uint256)").
+pub const VERIFY_PROOF_SELECTOR: [u8; 4] = [0x63, 0xc3, 0x2f, 0xc3];
+
+fn encode_fp(out: &mut Vec<u8>, value: &Integer) {
+    out.extend_from_slice(&[0u8; FP_BYTES - 48]);
+    out.extend_from_slice(&Bls12_381BaseField::to_bytes_be(value));
+}
+
+/// A G1 point in the EIP-2537 encoding.
+pub fn encode_g1(point: &G1Affine) -> Vec<u8> {
+    if point.infinity {
+        return vec![0; G1_BYTES];
+    }
+
+    let mut out = Vec::with_capacity(G1_BYTES);
+    encode_fp(&mut out, &point.x.0);
+    encode_fp(&mut out, &point.y.0);
+    out
+}
+
+/// A G2 point in the EIP-2537 encoding.
+pub fn encode_g2(point: &G2Affine) -> Vec<u8> {
+    if point.infinity {
+        return vec![0; G2_BYTES];
+    }
+
+    let mut out = Vec::with_capacity(G2_BYTES);
+    for value in [&point.x.c0, &point.x.c1, &point.y.c0, &point.y.c1] {
+        encode_fp(&mut out, value);
+    }
+    out
+}
+
+/// A || B || C, the `proof` argument of `verifyProof`.
+pub fn encode_proof(proof: &Proof) -> Vec<u8> {
+    let mut out = encode_g1(&proof.a);
+    out.extend_from_slice(&encode_g2(&proof.b));
+    out.extend_from_slice(&encode_g1(&proof.c));
+    out
+}
+
+/// ABI encoded calldata of `verifyProof(proof, public_inputs, context)`.
+pub fn encode_calldata(proof: &Proof, public_inputs: &[Scalar], context: Option<&[u8]>) -> Vec<u8> {
+    let word = |value: usize| scalar_bytes(&Integer::from(value));
+
+    // head: offset of proof, offset of input, context
+    let proof_offset = 3 * 32;
+    let input_offset = proof_offset + 32 + PROOF_BYTES;
+
+    let mut out = VERIFY_PROOF_SELECTOR.to_vec();
+    out.extend_from_slice(&word(proof_offset));
+    out.extend_from_slice(&word(input_offset));
+    out.extend_from_slice(&scalar_bytes(&context_scalar(context)));
+
+    // PROOF_BYTES is a multiple of 32, so the proof needs no padding
+    out.extend_from_slice(&word(PROOF_BYTES));
+    out.extend_from_slice(&encode_proof(proof));
+
+    out.extend_from_slice(&word(public_inputs.len()));
+    for input in public_inputs {
+        out.extend_from_slice(&scalar_bytes(&BLS12_381_SCALAR.reduce(&input.0)));
+    }
+    out
+}
+
+/// A Solidity contract `Groth16Verifier` that accepts exactly the proofs
+/// `verify_proof_with_context` accepts for `vk`.
+pub fn solidity_verifier(vk: &VerifierKey) -> Result<String, Groth16Error> {
+    // IC_0 for the constant one and IC_last for the context input
+    if vk.ic.len() < 2 {
+        return Err(Groth16Error::InvalidVerifyingKey);
+    }
+    let pvk = prepare_verifier_key(vk);
+    let num_inputs = vk.ic.len() - 2;
+    let ic = vk.ic[1..].iter().flat_map(encode_g1).collect::<Vec<_>>();
+
+    Ok(VERIFIER_TEMPLATE
+        .replace("{R}", &format!("{:#x}", BLS12_381_SCALAR.modulus_ref()))
+        .replace("{NUM_INPUTS}", &num_inputs.to_string())
+        .replace("{NEG_ALPHA_G1}", &to_hex(&encode_g1(&pvk.neg_alpha_g1)))
+        .replace("{BETA_G2}", &to_hex(&encode_g2(&pvk.beta_g2)))
+        .replace("{NEG_GAMMA_G2}", &to_hex(&encode_g2(&pvk.neg_gamma_g2)))
+        .replace("{NEG_DELTA_G2}", &to_hex(&encode_g2(&pvk.neg_delta_g2)))
+        .replace("{IC_0}", &to_hex(&encode_g1(&vk.ic[0])))
+        .replace("{IC}", &to_hex(&ic)))
+}
+
+const VERIFIER_TEMPLATE: &str = r#"// SPDX-License-Identifier: MIT
+// Generated by zkper-groth16 from a verification key.
+pragma solidity ^0.8.24;
+
+/// Groth16 verifier over BLS12-381, through the EIP-2537 precompiles.
+contract Groth16Verifier {
+    uint256 constant R = {R};
+    uint256 constant NUM_INPUTS = {NUM_INPUTS};
+
+    address constant G1ADD = address(0x0b);
+    address constant G1MSM = address(0x0c);
+    address constant PAIRING_CHECK = address(0x0f);
+
+    bytes constant NEG_ALPHA_G1 = hex"{NEG_ALPHA_G1}";
+    bytes constant BETA_G2 = hex"{BETA_G2}";
+    bytes constant NEG_GAMMA_G2 = hex"{NEG_GAMMA_G2}";
+    bytes constant NEG_DELTA_G2 = hex"{NEG_DELTA_G2}";
+    bytes constant IC_0 = hex"{IC_0}";
+    // one point per public input, then the point of the context input
+    bytes constant IC = hex"{IC}";
+
+    /// Checks e(A, B) * e(acc, -gamma) * e(C, -delta) * e(-alpha, beta) == 1
+    /// with acc = IC_0 + sum_i input_i * IC_{i + 1} + context * IC_last.
+    /// proof is A (128 bytes), B (256 bytes) and C (128 bytes).
+    function verifyProof(bytes calldata proof, uint256[] calldata input, uint256 context)
+        external
+        view
+        returns (bool)
+    {
+        if (proof.length != 512 || input.length != NUM_INPUTS) {
+            return false;
+        }
+
+        bytes memory ic = IC;
+        bytes memory msm = new bytes(160 * (NUM_INPUTS + 1));
+        for (uint256 i = 0; i <= NUM_INPUTS; i++) {
+            uint256 scalar = i < NUM_INPUTS ? input[i] : context;
+            if (scalar >= R) {
+                return false;
+            }
+            assembly {
+                let src := add(add(ic, 32), mul(128, i))
+                let dst := add(add(msm, 32), mul(160, i))
+                mstore(dst, mload(src))
+                mstore(add(dst, 32), mload(add(src, 32)))
+                mstore(add(dst, 64), mload(add(src, 64)))
+                mstore(add(dst, 96), mload(add(src, 96)))
+                mstore(add(dst, 128), scalar)
+            }
+        }
+        bytes memory acc = precompile(G1ADD, abi.encodePacked(IC_0, precompile(G1MSM, msm)));
+
+        // the precompile rejects points off the curve or outside the subgroup
+        (bool ok, bytes memory result) = PAIRING_CHECK.staticcall(
+            abi.encodePacked(
+                proof[0:384],
+                acc,
+                NEG_GAMMA_G2,
+                proof[384:512],
+                NEG_DELTA_G2,
+                NEG_ALPHA_G1,
+                BETA_G2
+            )
+        );
+        return ok && result.length == 32 && abi.decode(result, (uint256)) == 1;
+    }
+
+    function precompile(address target, bytes memory input) private view returns (bytes memory) {
+        (bool ok, bytes memory result) = target.staticcall(input);
+        require(ok && result.length == 128, "precompile failed");
+        return result;
+    }
+}
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        builder::{Backend, BuilderCircuit, CircuitBuilder},
+        field::Fr,
+        generator::generate_proving_parameters,
+        hash::{Keccak256Hash, TranscriptHash},
+        prover::create_proof,
+    };
+    use zkper_rand::TestRng;
+
+    // x^2 = y with public y
+    struct Square(Option<Scalar>);
+
+    impl BuilderCircuit for Square {
+        fn build<B: Backend<Field = Fr>>(&self, cs: &mut CircuitBuilder<B>) -> anyhow::Result<()> {
+            let x = cs.alloc(self.0.clone())?;
+            let x2 = cs.square(&x)?;
+            let y = cs.alloc_input(x2.value().cloned())?;
+            cs.enforce_equal(&x2, &y);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_solidity_verifier() {
+        let mut rng = TestRng::new();
+        let params = generate_proving_parameters(Square(None), &mut rng).unwrap();
+        let x = Scalar::from(Integer::from(3));
+        let proof = create_proof(Square(Some(x)), &params, &mut rng).unwrap();
+
+        let contract = solidity_verifier(&params.vk.verifier_key()).unwrap();
+        assert!(contract.contains("uint256 constant NUM_INPUTS = 1;"));
+        assert!(contract.contains(
+            "uint256 constant R = 0x73eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000001;"
+        ));
+        assert!(contract.contains(&format!(
+            "bytes constant IC_0 = hex\"{}\";",
+            to_hex(&encode_g1(&params.vk.ic[0]))
+        )));
+        assert!(!contract.contains("hex\"{"));
+
+        let proof_bytes = encode_proof(&proof);
+        assert_eq!(proof_bytes.len(), PROOF_BYTES);
+        assert_eq!(&proof_bytes[..16], &[0; 16]);
+        assert_eq!(
+            &proof_bytes[16..64],
+            &Bls12_381BaseField::to_bytes_be(&proof.a.x.0)
+        );
+        // B starts with x.c0
+        assert_eq!(
+            &proof_bytes[G1_BYTES + 16..G1_BYTES + 64],
+            &Bls12_381BaseField::to_bytes_be(&proof.b.x.c0)
+        );
+        assert_eq!(encode_g1(&G1Affine::identity()), vec![0; G1_BYTES]);
+
+        let nine = Scalar::from(Integer::from(9));
+        let calldata = encode_calldata(&proof, &[nine], Some(b"app"));
+        assert_eq!(calldata.len(), 4 + 3 * 32 + 32 + PROOF_BYTES + 32 + 32);
+        assert_eq!(&calldata[..4], &VERIFY_PROOF_SELECTOR);
+        assert_eq!(
+            Keccak256Hash::digest("verifyProof(bytes,uint256[],uint256)")[..4],
+            VERIFY_PROOF_SELECTOR
+        );
+        assert_eq!(calldata[4 + 31], 0x60);
+        assert_eq!(
+            &calldata[4 + 64..4 + 96],
+            &scalar_bytes(&context_scalar(Some(b"app")))
+        );
+        assert_eq!(&calldata[4 + 128..4 + 128 + PROOF_BYTES], &proof_bytes[..]);
+        assert_eq!(calldata[calldata.len() - 33], 1);
+        assert_eq!(calldata[calldata.len() - 1], 9);
+    }
+}
@@ -1,44 +1,823 @@
+use rand::RngCore;
 use rug::Integer;
-use zkper_curves::curves::bls12_381::paring::BLS12_381Pairing;
+use zkper_base::transcript::Transcript;
+use zkper_curves::curves::bls12_381::{
+    curves::{g1::G1Projective, g1_affine::G1Affine},
+    paring::BLS12_381Pairing,
+    Bls12_381ScalarField, Scalar, BLS12_381_SCALAR,
+};
+use zkper_curves::traits::{field::FieldTrait, transcript::TranscriptTrait};
 
-use crate::models::{
-    proof::Proof, verification_key::VerificationKey, verify::PreparedVerifyingKey,
+use crate::{
+    context::context_scalar,
+    error::Groth16Error,
+    hash::{Sha256Hash, TranscriptHash},
+    models::{
+        commitment::CommittedProof,
+        proof::Proof,
+        verification_key::{VerificationKey, VerifierKey},
+        verify::PreparedVerifyingKey,
+    },
 };
 
+/// Domain separation tag of the batch verification transcript. The version is
+/// bumped whenever the transcript layout changes, so combiners derived by
+/// different layouts never coincide.
+pub const BATCH_TRANSCRIPT_DST: &[u8] = b"ZKPER-GROTH16-BATCH-V2";
+
 pub fn prepare_verifying_key(vk: &VerificationKey) -> PreparedVerifyingKey {
+    prepare_verifier_key(&vk.verifier_key())
+}
+
+/// Prepare the minimal key a verifier receives, see `VerifierKey`.
+pub fn prepare_verifier_key(vk: &VerifierKey) -> PreparedVerifyingKey {
     let gamma = vk.gamma_g2.neg();
     let delta = vk.delta_g2.neg();
 
     PreparedVerifyingKey {
-        alpha_g1_beta_g2: BLS12_381Pairing::pairing(&vk.alpha_g1, &vk.beta_g2),
-        neg_gamma_g2: gamma.into(),
-        neg_delta_g2: delta.into(),
+        neg_alpha_g1: vk.alpha_g1.neg(),
+        beta_g2: vk.beta_g2.clone(),
+        neg_gamma_g2: gamma,
+        neg_delta_g2: delta,
         ic: vk.ic.clone(),
     }
 }
 
+/// How many times, and how, the pairing check is evaluated.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum VerificationMode {
+    /// A single unblinded pairing check.
+    #[default]
+    Standard,
+    /// Evaluate the check twice, each time with an independent random blinding
+    /// factor, and fail if the two runs disagree. A single fault injected into
+    /// either run then surfaces as an error instead of a flipped result.
+    DoubleCheck,
+}
+
+/// Whether the points are checked before the pairing check.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ProofValidation {
+    /// Reject proofs that fail `Proof::validate` and key points outside the
+    /// prime-order subgroups.
+    #[default]
+    Full,
+    /// Skip the curve and subgroup checks, for proofs and keys from a trusted
+    /// source such as the local prover. Untrusted input must not take this
+    /// path.
+    Trusted,
+}
+
 pub fn verify_proof(
     pvk: &PreparedVerifyingKey,
     proof: &Proof,
-    public_inputs: &[Integer],
-) -> anyhow::Result<bool> {
-    if (public_inputs.len() + 1) != pvk.ic.len() {
-        return Err(anyhow::anyhow!("InvalidVerifyingKey"));
+    public_inputs: &[Scalar],
+) -> Result<bool, Groth16Error> {
+    verify_proof_with_context(pvk, proof, public_inputs, None)
+}
+
+/// Verify a proof that was bound to `context` with `create_proof_with_context`.
+pub fn verify_proof_with_context(
+    pvk: &PreparedVerifyingKey,
+    proof: &Proof,
+    public_inputs: &[Scalar],
+    context: Option<&[u8]>,
+) -> Result<bool, Groth16Error> {
+    verify_proof_with_validation(pvk, proof, public_inputs, context, ProofValidation::Full)
+}
+
+/// Verify a proof bound to `context`, checking its points as `validation`
+/// says. Every other `verify_proof*` function validates in full.
+pub fn verify_proof_with_validation(
+    pvk: &PreparedVerifyingKey,
+    proof: &Proof,
+    public_inputs: &[Scalar],
+    context: Option<&[u8]>,
+    validation: ProofValidation,
+) -> Result<bool, Groth16Error> {
+    let acc = prepare_inputs(pvk, public_inputs, context)?;
+
+    Ok(pairing_check(pvk, proof, &acc, None, validation))
+}
+
+/// Verify a proof with a commitment D to part of the witness, made with
+/// `create_committed_proof`: D joins the public input term against γ.
+pub fn verify_committed_proof(
+    pvk: &PreparedVerifyingKey,
+    proof: &CommittedProof,
+    public_inputs: &[Scalar],
+    context: Option<&[u8]>,
+) -> Result<bool, Groth16Error> {
+    // the proof itself is validated by the pairing check
+    if !proof.d.is_on_curve() || !proof.d.is_torsion_free() {
+        return Ok(false);
+    }
+
+    let acc = prepare_inputs(pvk, public_inputs, context)?.add(&proof.d.to_curve());
+
+    Ok(pairing_check(
+        pvk,
+        &proof.proof,
+        &acc,
+        None,
+        ProofValidation::Full,
+    ))
+}
+
+/// Verify a proof bound to `context` using the given `mode`. `rng` is only
+/// used by `VerificationMode::DoubleCheck` to sample the blinding factors.
+pub fn verify_proof_with_mode<R: RngCore>(
+    pvk: &PreparedVerifyingKey,
+    proof: &Proof,
+    public_inputs: &[Scalar],
+    context: Option<&[u8]>,
+    mode: VerificationMode,
+    rng: &mut R,
+) -> Result<bool, Groth16Error> {
+    match mode {
+        VerificationMode::Standard => verify_proof_with_context(pvk, proof, public_inputs, context),
+        VerificationMode::DoubleCheck => {
+            let mut results = [false; 2];
+            for result in results.iter_mut() {
+                // recompute everything so a fault in one run cannot leak into the other
+                let acc = prepare_inputs(pvk, public_inputs, context)?;
+                let blind = sample_blinding_factor(rng);
+                *result = pairing_check(pvk, proof, &acc, Some(&blind), ProofValidation::Full);
+            }
+
+            if results[0] != results[1] {
+                return Err(Groth16Error::VerificationFault);
+            }
+
+            Ok(results[0])
+        }
     }
+}
+
+/// Verify several proofs for the same key at once, see
+/// `verify_proofs_batch_with_context`.
+pub fn verify_proofs_batch(
+    pvk: &PreparedVerifyingKey,
+    batch: &[(&Proof, &[Scalar])],
+) -> Result<bool, Groth16Error> {
+    verify_proofs_batch_with_context(pvk, batch, None)
+}
+
+/// Verify proofs bound to the same `context` with a single pairing product.
+///
+/// Each proof's check is raised to a combiner z_i and the checks multiplied:
+/// e(z_i A_i, B_i) for every proof, then Σ z_i acc_i against -γ, Σ z_i C_i
+/// against -δ and -(Σ z_i) α against β. The combiners come from
+/// `batch_challenges` instead of an RNG, so the outcome is reproducible and
+/// does not depend on the verifier's entropy. Returns true only if every proof
+/// verifies, up to a negligible probability.
+pub fn verify_proofs_batch_with_context(
+    pvk: &PreparedVerifyingKey,
+    batch: &[(&Proof, &[Scalar])],
+    context: Option<&[u8]>,
+) -> Result<bool, Groth16Error> {
+    // a point outside the prime-order subgroup could be cancelled by its combiner
+    if !batch.iter().all(|(proof, _)| proof.is_well_formed()) {
+        return Ok(false);
+    }
+
+    let challenges = batch_challenges(pvk, batch, context);
 
-    let mut acc = pvk.ic[0].to_curve();
-    for (public_input, b) in public_inputs.iter().zip(pvk.ic.iter().skip(1)) {
-        acc = acc.add(&b.to_curve().mul_scalar(public_input));
+    let mut g1_points = Vec::with_capacity(batch.len() + 3);
+    let mut acc = G1Projective::identity();
+    let mut c = G1Projective::identity();
+    let mut z_sum = Scalar::from(Integer::ZERO);
+    for ((proof, public_inputs), z) in batch.iter().zip(&challenges) {
+        let inputs = prepare_inputs(pvk, public_inputs, context)?;
+        acc = acc.add(&inputs.mul_scalar(z));
+        c = c.add(&proof.c.to_curve().mul_scalar(z));
+        z_sum.add_assign(z);
+        g1_points.push(proof.a.to_curve().mul_scalar(z));
     }
+    g1_points.push(acc);
+    g1_points.push(c);
+    g1_points.push(pvk.neg_alpha_g1.to_curve().mul_scalar(&z_sum));
 
-    let answer = BLS12_381Pairing::multi_miller_loop(&[
-        (&proof.a, &proof.b.clone().into()),
-        (&acc.to_affine(), &pvk.neg_gamma_g2),
-        (&proof.c, &pvk.neg_delta_g2),
+    let g1_points = G1Projective::batch_normalize(&g1_points);
+    let g2_points = batch.iter().map(|(proof, _)| proof.b.clone()).chain([
+        pvk.neg_gamma_g2.clone(),
+        pvk.neg_delta_g2.clone(),
+        pvk.beta_g2.clone(),
     ]);
 
-    let answer = BLS12_381Pairing::final_exponentiation(&answer);
-    let is_equal = answer == pvk.alpha_g1_beta_g2;
+    Ok(BLS12_381Pairing::pairing_product_is_one(
+        &g1_points.into_iter().zip(g2_points).collect::<Vec<_>>(),
+    ))
+}
+
+/// The batch verification combiners, one non-zero scalar per proof, over a
+/// SHA-256 transcript.
+pub fn batch_challenges(
+    pvk: &PreparedVerifyingKey,
+    batch: &[(&Proof, &[Scalar])],
+    context: Option<&[u8]>,
+) -> Vec<Scalar> {
+    batch_challenges_with::<Sha256Hash>(pvk, batch, context)
+}
+
+/// The batch verification combiners over a transcript hashed with `H`.
+///
+/// The key, the context and every proof with its public inputs are absorbed
+/// into a `Transcript` under `BATCH_TRANSCRIPT_DST`, and the z_i are squeezed
+/// out of it. Changing any proof or input changes every z_i.
+pub fn batch_challenges_with<H: TranscriptHash>(
+    pvk: &PreparedVerifyingKey,
+    batch: &[(&Proof, &[Scalar])],
+    context: Option<&[u8]>,
+) -> Vec<Scalar> {
+    let mut transcript = Transcript::<H>::new_with(BATCH_TRANSCRIPT_DST);
+
+    transcript.append(b"neg_alpha_g1", &pvk.neg_alpha_g1);
+    transcript.append(b"beta_g2", &pvk.beta_g2);
+    transcript.append(b"neg_gamma_g2", &pvk.neg_gamma_g2);
+    transcript.append(b"neg_delta_g2", &pvk.neg_delta_g2);
+    transcript.append_all(b"ic", &pvk.ic);
+
+    transcript.append(b"context", &Scalar::from(context_scalar(context)));
+
+    transcript.append(b"proofs", &(batch.len() as u64));
+    for (proof, public_inputs) in batch {
+        transcript.append(b"a", &proof.a);
+        transcript.append(b"b", &proof.b);
+        transcript.append(b"c", &proof.c);
+        transcript.append_all(b"inputs", public_inputs);
+    }
+
+    transcript
+        .challenge_scalars(b"z", batch.len())
+        .into_iter()
+        .map(|z| {
+            if z.0.is_zero() {
+                Scalar::from(Integer::from(1))
+            } else {
+                z
+            }
+        })
+        .collect()
+}
+
+/// IC_0 + sum_i public_input_i * IC_{i + 1} + context * IC_last
+fn prepare_inputs(
+    pvk: &PreparedVerifyingKey,
+    public_inputs: &[Scalar],
+    context: Option<&[u8]>,
+) -> Result<G1Projective, Groth16Error> {
+    PreparedPublicInputs::new(pvk, &[])?.accumulate(public_inputs, context)
+}
+
+/// The public input term IC_0 + sum_i input_i * IC_{i + 1} for a prefix of
+/// the public inputs, computed once and shared by every proof whose inputs
+/// start with that prefix.
+///
+/// Inputs can be appended with `push` and replaced with `set`, each at the
+/// cost of a single scalar multiplication, instead of recomputing the sum.
+#[derive(Clone, Debug)]
+pub struct PreparedPublicInputs<'a> {
+    pvk: &'a PreparedVerifyingKey,
+    inputs: Vec<Scalar>,
+    acc: G1Projective,
+}
+
+impl<'a> PreparedPublicInputs<'a> {
+    /// Accumulates `prefix`, the first public inputs of the circuit.
+    pub fn new(pvk: &'a PreparedVerifyingKey, prefix: &[Scalar]) -> Result<Self, Groth16Error> {
+        // IC_0 for the constant one and IC_last for the context input
+        if pvk.ic.len() < 2 {
+            return Err(Groth16Error::InvalidVerifyingKey);
+        }
+
+        let mut prepared = Self {
+            pvk,
+            inputs: Vec::with_capacity(pvk.ic.len() - 2),
+            acc: pvk.ic[0].to_curve(),
+        };
+        for input in prefix {
+            prepared.push(input.clone())?;
+        }
+        Ok(prepared)
+    }
+
+    /// The public inputs accumulated so far.
+    pub fn inputs(&self) -> &[Scalar] {
+        &self.inputs
+    }
+
+    /// Appends the next public input.
+    pub fn push(&mut self, input: Scalar) -> Result<(), Groth16Error> {
+        if self.inputs.len() + 2 >= self.pvk.ic.len() {
+            return Err(Groth16Error::WrongInputCount {
+                expected: self.pvk.ic.len() - 2,
+                actual: self.inputs.len() + 1,
+            });
+        }
+        check_canonical(self.inputs.len(), &input)?;
+
+        self.acc = self.acc.add(&self.term(self.inputs.len(), &input));
+        self.inputs.push(input);
+        Ok(())
+    }
+
+    /// Replaces the public input at `index`, adding (new - old) * IC_{index + 1}.
+    pub fn set(&mut self, index: usize, input: Scalar) -> Result<(), Groth16Error> {
+        let old = self
+            .inputs
+            .get(index)
+            .ok_or(Groth16Error::MissingInput(index))?;
+        check_canonical(index, &input)?;
+
+        let diff = BLS12_381_SCALAR.sub(
+            BLS12_381_SCALAR.reduce(&input.0),
+            &BLS12_381_SCALAR.reduce(&old.0),
+        );
+        self.acc = self.acc.add(&self.term(index, &Scalar::from(diff)));
+        self.inputs[index] = input;
+        Ok(())
+    }
+
+    /// The full term for a proof: the accumulated prefix, the remaining
+    /// public inputs `suffix` and the context input.
+    pub fn accumulate(
+        &self,
+        suffix: &[Scalar],
+        context: Option<&[u8]>,
+    ) -> Result<G1Projective, Groth16Error> {
+        if self.inputs.len() + suffix.len() + 2 != self.pvk.ic.len() {
+            return Err(Groth16Error::WrongInputCount {
+                expected: self.pvk.ic.len() - 2,
+                actual: self.inputs.len() + suffix.len(),
+            });
+        }
+
+        let mut acc = self.acc.clone();
+        for (i, input) in suffix.iter().enumerate() {
+            let index = self.inputs.len() + i;
+            check_canonical(index, input)?;
+            acc = acc.add(&self.term(index, input));
+        }
+
+        let context = context_scalar(context);
+        if !context.is_zero() {
+            let ic_context = self.pvk.ic.last().expect("checked in new");
+            acc = acc.add(&ic_context.to_curve().mul_scalar(&Scalar::from(context)));
+        }
+
+        Ok(acc)
+    }
+
+    /// input * IC_{index + 1}
+    fn term(&self, index: usize, input: &Scalar) -> G1Projective {
+        self.pvk.ic[index + 1].to_curve().mul_scalar(input)
+    }
+}
+
+/// Public inputs have to be canonical, in [0, r), since the verifier would
+/// otherwise accept several encodings of the same statement.
+fn check_canonical(index: usize, input: &Scalar) -> Result<(), Groth16Error> {
+    if input.0 < 0 || input.0 >= *BLS12_381_SCALAR.modulus_ref() {
+        return Err(Groth16Error::NonCanonicalScalar(index));
+    }
+    Ok(())
+}
+
+/// Reduces public inputs into [0, r), for callers that hold them as
+/// arbitrary integers and accept every value congruent mod r.
+pub fn reduce_public_inputs(inputs: &[Scalar]) -> Vec<Scalar> {
+    inputs
+        .iter()
+        .map(|input| Scalar::from(input.0.clone()))
+        .collect()
+}
+
+/// Verify a proof whose public inputs start with the inputs of `prepared`
+/// and end with `suffix`, see `PreparedPublicInputs`.
+pub fn verify_proof_with_prepared_inputs(
+    prepared: &PreparedPublicInputs,
+    proof: &Proof,
+    suffix: &[Scalar],
+    context: Option<&[u8]>,
+) -> Result<bool, Groth16Error> {
+    let acc = prepared.accumulate(suffix, context)?;
+
+    Ok(pairing_check(
+        prepared.pvk,
+        proof,
+        &acc,
+        None,
+        ProofValidation::Full,
+    ))
+}
+
+/// Non-zero scalar used to blind a pairing check.
+fn sample_blinding_factor<R: RngCore>(rng: &mut R) -> Scalar {
+    loop {
+        let blind = Bls12_381ScalarField::random(rng);
+        if !blind.is_zero() {
+            return Scalar::from(blind);
+        }
+    }
+}
+
+/// Checks e(A, B) * e(acc, -γ) * e(C, -δ) * e(-α, β) == 1.
+///
+/// With a blinding factor ρ every G1 input is scaled by ρ, raising the product
+/// to ρ, which leaves the outcome unchanged for honest hardware. Unless
+/// `validation` is `Trusted`, proofs that fail `Proof::validate` and key
+/// points outside the prime-order subgroups fail the check.
+fn pairing_check(
+    pvk: &PreparedVerifyingKey,
+    proof: &Proof,
+    acc: &G1Projective,
+    blind: Option<&Scalar>,
+    validation: ProofValidation,
+) -> bool {
+    if validation == ProofValidation::Full && proof.validate().is_err() {
+        return false;
+    }
+
+    let g1_points = [
+        proof.a.to_curve(),
+        acc.clone(),
+        proof.c.to_curve(),
+        pvk.neg_alpha_g1.to_curve(),
+    ];
+    let g1_points = match blind {
+        Some(blind) => g1_points.map(|p| p.mul_scalar(blind)),
+        None => g1_points,
+    };
+    let [a, acc, c, neg_alpha]: [G1Affine; 4] = G1Projective::batch_normalize(&g1_points)
+        .try_into()
+        .expect("four points in, four points out");
+
+    match validation {
+        ProofValidation::Full => BLS12_381Pairing::pairing_product_is_one(&[
+            (a, proof.b.clone()),
+            (acc, pvk.neg_gamma_g2.clone()),
+            (c, pvk.neg_delta_g2.clone()),
+            (neg_alpha, pvk.beta_g2.clone()),
+        ]),
+        ProofValidation::Trusted => BLS12_381Pairing::multi_pairing(&[
+            (&a, &proof.b),
+            (&acc, &pvk.neg_gamma_g2),
+            (&c, &pvk.neg_delta_g2),
+            (&neg_alpha, &pvk.beta_g2),
+        ])
+        .is_identity(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zkper_curves::curves::bls12_381::{
+        curves::{g1::G1Projective, g2::G2Projective, g2_affine::G2Affine},
+        Bls12_381BaseField, BLS12_381_SCALAR,
+    };
+    use zkper_rand::TestRng;
+
+    /// A verifying key with known trapdoor for no public inputs, bound to
+    /// `context`, with the scalars a proof has to satisfy:
+    /// a * b = alpha * beta + (ic0 + context * ic_context) * gamma + c * delta.
+    struct Trapdoor {
+        vk: VerificationKey,
+        /// alpha * beta + (ic0 + context * ic_context) * gamma
+        rhs: Integer,
+        delta: Integer,
+    }
+
+    impl Trapdoor {
+        fn new(rng: &mut TestRng, context: Option<&[u8]>) -> Self {
+            let [alpha, beta, gamma, delta, ic0, ic_context] =
+                std::array::from_fn(|_| Bls12_381ScalarField::random(rng));
+
+            let ic = BLS12_381_SCALAR.add(
+                ic0.clone(),
+                &BLS12_381_SCALAR.mul(context_scalar(context), &ic_context),
+            );
+            let rhs = BLS12_381_SCALAR.add(
+                BLS12_381_SCALAR.mul(alpha.clone(), &beta),
+                &BLS12_381_SCALAR.mul(ic, &gamma),
+            );
+
+            let vk = VerificationKey {
+                alpha_g1: g1(&alpha),
+                beta_g1: g1(&beta),
+                beta_g2: g2(&beta),
+                gamma_g2: g2(&gamma),
+                delta_g1: g1(&delta),
+                delta_g2: g2(&delta),
+                ic: vec![g1(&ic0), g1(&ic_context)],
+            };
+
+            Self { vk, rhs, delta }
+        }
+
+        /// The proof with the given a and b, solving for c.
+        fn proof(&self, a: &Integer, b: &Integer) -> Proof {
+            let c = BLS12_381_SCALAR.mul(
+                BLS12_381_SCALAR.sub(BLS12_381_SCALAR.mul(a.clone(), b), &self.rhs),
+                &BLS12_381_SCALAR.invert(self.delta.clone()).unwrap(),
+            );
+
+            Proof {
+                a: g1(a),
+                b: g2(b),
+                c: g1(&c),
+            }
+        }
+    }
+
+    fn g1(s: &Integer) -> G1Affine {
+        G1Projective::generator()
+            .mul_scalar(&Scalar::from(s.clone()))
+            .to_affine()
+    }
+
+    fn g2(s: &Integer) -> G2Affine {
+        G2Projective::generator()
+            .mul_scalar(&Scalar::from(s.clone()))
+            .to_affine()
+    }
+
+    /// A verifying key with known trapdoor and a proof for no public inputs,
+    /// bound to `context`.
+    fn trapdoor_proof(rng: &mut TestRng, context: Option<&[u8]>) -> (VerificationKey, Proof) {
+        let trapdoor = Trapdoor::new(rng, context);
+        let [a, b] = std::array::from_fn(|_| Bls12_381ScalarField::random(rng));
+        let proof = trapdoor.proof(&a, &b);
+
+        (trapdoor.vk, proof)
+    }
+
+    #[test]
+    fn test_identity_points_rejected() {
+        let mut rng = TestRng::new();
+        let trapdoor = Trapdoor::new(&mut rng, None);
+        let pvk = prepare_verifying_key(&trapdoor.vk);
+        let random = Bls12_381ScalarField::random(&mut rng);
 
-    Ok(is_equal)
+        // with the trapdoor, A, B or C can each be forced to the identity
+        let b_for_zero_c = BLS12_381_SCALAR.mul(
+            trapdoor.rhs.clone(),
+            &BLS12_381_SCALAR.invert(random.clone()).unwrap(),
+        );
+        let forged = [
+            trapdoor.proof(&Integer::ZERO, &random),
+            trapdoor.proof(&random, &Integer::ZERO),
+            trapdoor.proof(&random, &b_for_zero_c),
+        ];
+        assert!(forged[0].a.is_identity());
+        assert!(forged[1].b.is_identity());
+        assert!(forged[2].c.is_identity());
+
+        let acc = prepare_inputs(&pvk, &[], None).unwrap().to_affine();
+        for proof in &forged {
+            assert!(!proof.is_well_formed());
+
+            // the bare pairing equation holds for each of them
+            assert!(BLS12_381Pairing::pairing_product_is_one(&[
+                (proof.a.clone(), proof.b.clone()),
+                (acc.clone(), pvk.neg_gamma_g2.clone()),
+                (proof.c.clone(), pvk.neg_delta_g2.clone()),
+                (pvk.neg_alpha_g1.clone(), pvk.beta_g2.clone()),
+            ]));
+
+            assert!(!verify_proof(&pvk, proof, &[]).unwrap());
+            assert!(!verify_proof_with_mode(
+                &pvk,
+                proof,
+                &[],
+                None,
+                VerificationMode::DoubleCheck,
+                &mut rng
+            )
+            .unwrap());
+            let prepared = PreparedPublicInputs::new(&pvk, &[]).unwrap();
+            assert!(!verify_proof_with_prepared_inputs(&prepared, proof, &[], None).unwrap());
+            assert!(!verify_proofs_batch(&pvk, &[(proof, &[])]).unwrap());
+        }
+
+        // an honest proof next to a forged one fails the batch too
+        let honest = trapdoor.proof(&random, &random);
+        assert!(honest.is_well_formed());
+        assert!(verify_proof(&pvk, &honest, &[]).unwrap());
+        assert!(!verify_proofs_batch(&pvk, &[(&honest, &[]), (&forged[2], &[])]).unwrap());
+    }
+
+    #[test]
+    fn test_proof_validation() {
+        let mut rng = TestRng::new();
+        let trapdoor = Trapdoor::new(&mut rng, None);
+        let pvk = prepare_verifying_key(&trapdoor.vk);
+        let random = Bls12_381ScalarField::random(&mut rng);
+
+        let honest = trapdoor.proof(&random, &random);
+        assert!(honest.validate().is_ok());
+        for validation in [ProofValidation::Full, ProofValidation::Trusted] {
+            assert!(verify_proof_with_validation(&pvk, &honest, &[], None, validation).unwrap());
+        }
+
+        let forged = trapdoor.proof(&Integer::ZERO, &random);
+        assert!(matches!(
+            forged.validate(),
+            Err(Groth16Error::InvalidProof("A is the identity"))
+        ));
+        assert!(trapdoor
+            .proof(&random, &Integer::ZERO)
+            .validate()
+            .unwrap_err()
+            .to_string()
+            .contains("B is the identity"));
+
+        // trusted verification takes the degenerate proof at face value
+        assert!(
+            !verify_proof_with_validation(&pvk, &forged, &[], None, ProofValidation::Full).unwrap()
+        );
+        assert!(
+            verify_proof_with_validation(&pvk, &forged, &[], None, ProofValidation::Trusted)
+                .unwrap()
+        );
+
+        // a point on the curve whose cofactor has not been cleared
+        let mut x = Integer::from(1);
+        let point = loop {
+            let rhs =
+                Bls12_381BaseField::add(Bls12_381BaseField::cubic(x.clone()), &Integer::from(4));
+            if let Some(y) = Bls12_381BaseField::sqrt(rhs) {
+                break G1Affine::new(x.into(), y.into(), false);
+            }
+            x += 1;
+        };
+        assert!(point.is_on_curve());
+        let mut outside = honest.clone();
+        outside.c = point;
+        assert!(outside
+            .validate()
+            .unwrap_err()
+            .to_string()
+            .contains("outside the prime-order subgroup"));
+        assert!(!verify_proof(&pvk, &outside, &[]).unwrap());
+
+        let mut off_curve = honest.clone();
+        off_curve.a = G1Affine::new(1.into(), 1.into(), false);
+        assert!(!off_curve.is_well_formed());
+    }
+
+    #[test]
+    fn test_double_check_mode() {
+        let mut rng = TestRng::new();
+        let (vk, proof) = trapdoor_proof(&mut rng, None);
+        let pvk = prepare_verifying_key(&vk);
+
+        for mode in [VerificationMode::Standard, VerificationMode::DoubleCheck] {
+            assert!(verify_proof_with_mode(&pvk, &proof, &[], None, mode, &mut rng).unwrap());
+        }
+
+        let mut bad_proof = proof.clone();
+        bad_proof.c = proof.a.clone();
+        for mode in [VerificationMode::Standard, VerificationMode::DoubleCheck] {
+            assert!(!verify_proof_with_mode(&pvk, &bad_proof, &[], None, mode, &mut rng).unwrap());
+        }
+
+        assert!(verify_proof_with_mode(
+            &pvk,
+            &proof,
+            &[Scalar::from(Integer::from(1))],
+            None,
+            VerificationMode::DoubleCheck,
+            &mut rng
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_public_input_validation() {
+        let mut rng = TestRng::new();
+        let (vk, proof) = trapdoor_proof(&mut rng, None);
+        let mut pvk = prepare_verifying_key(&vk);
+
+        let one = Scalar::from(Integer::from(1));
+        assert!(matches!(
+            verify_proof(&pvk, &proof, std::slice::from_ref(&one)),
+            Err(Groth16Error::WrongInputCount {
+                expected: 0,
+                actual: 1
+            })
+        ));
+
+        // two public inputs
+        pvk.ic = (0..4)
+            .map(|_| G1Projective::random(&mut rng).to_affine())
+            .collect();
+        let r = BLS12_381_SCALAR.modulus();
+        let x = Scalar::from(Bls12_381ScalarField::random(&mut rng));
+        let non_canonical = [
+            Bls12_381ScalarField(Integer::from(&x.0 + &r)),
+            Bls12_381ScalarField(Integer::from(&x.0 - &r)),
+            Bls12_381ScalarField(r.clone()),
+        ];
+        for input in non_canonical.iter() {
+            let inputs = [one.clone(), input.clone()];
+            assert!(matches!(
+                prepare_inputs(&pvk, &inputs, None).unwrap_err(),
+                Groth16Error::NonCanonicalScalar(1)
+            ));
+
+            let mut prepared = PreparedPublicInputs::new(&pvk, std::slice::from_ref(&one)).unwrap();
+            assert!(prepared.push(input.clone()).is_err());
+            assert!(prepared.set(0, input.clone()).is_err());
+            assert!(PreparedPublicInputs::new(&pvk, std::slice::from_ref(input)).is_err());
+        }
+
+        // reduction is up to the caller
+        let reduced = reduce_public_inputs(&[one.clone(), non_canonical[0].clone()]);
+        assert_eq!(reduced, [one.clone(), x.clone()]);
+        assert_eq!(
+            prepare_inputs(&pvk, &reduced, None).unwrap(),
+            prepare_inputs(&pvk, &[one, x], None).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_verifier_key() {
+        let mut rng = TestRng::new();
+        let (vk, proof) = trapdoor_proof(&mut rng, None);
+
+        // a verifier holding only the shipped key accepts the same proofs
+        let shipped = VerifierKey::from_bytes(&vk.verifier_key().to_bytes()).unwrap();
+        let pvk = prepare_verifier_key(&shipped);
+        assert!(verify_proof(&pvk, &proof, &[]).unwrap());
+
+        let mut bad_proof = proof.clone();
+        bad_proof.c = proof.a.clone();
+        assert!(!verify_proof(&pvk, &bad_proof, &[]).unwrap());
+    }
+
+    #[test]
+    fn test_context_binding() {
+        let mut rng = TestRng::new();
+        let (vk, proof) = trapdoor_proof(&mut rng, Some(b"session-1"));
+        let pvk = prepare_verifying_key(&vk);
+
+        assert!(verify_proof_with_context(&pvk, &proof, &[], Some(b"session-1")).unwrap());
+        assert!(!verify_proof_with_context(&pvk, &proof, &[], Some(b"session-2")).unwrap());
+        assert!(!verify_proof(&pvk, &proof, &[]).unwrap());
+        assert!(verify_proof_with_mode(
+            &pvk,
+            &proof,
+            &[],
+            Some(b"session-1"),
+            VerificationMode::DoubleCheck,
+            &mut rng
+        )
+        .unwrap());
+    }
+
+    #[test]
+    fn test_prepared_public_inputs() {
+        let mut rng = TestRng::new();
+        let (vk, proof) = trapdoor_proof(&mut rng, Some(b"session-1"));
+        let pvk = prepare_verifying_key(&vk);
+
+        let prepared = PreparedPublicInputs::new(&pvk, &[]).unwrap();
+        assert!(
+            verify_proof_with_prepared_inputs(&prepared, &proof, &[], Some(b"session-1")).unwrap()
+        );
+        assert!(
+            !verify_proof_with_prepared_inputs(&prepared, &proof, &[], Some(b"session-2")).unwrap()
+        );
+
+        // three public inputs, only the accumulation is checked
+        let mut pvk = pvk;
+        pvk.ic = (0..5)
+            .map(|_| G1Projective::random(&mut rng).to_affine())
+            .collect();
+        let [x, y, z, w]: [Scalar; 4] =
+            std::array::from_fn(|_| Scalar::from(Bls12_381ScalarField::random(&mut rng)));
+        let full = |inputs: &[Scalar]| prepare_inputs(&pvk, inputs, Some(b"ctx")).unwrap();
+
+        let mut prepared = PreparedPublicInputs::new(&pvk, &[x.clone(), y.clone()]).unwrap();
+        assert_eq!(
+            prepared
+                .accumulate(std::slice::from_ref(&z), Some(b"ctx"))
+                .unwrap(),
+            full(&[x.clone(), y.clone(), z.clone()])
+        );
+
+        prepared.set(0, w.clone()).unwrap();
+        prepared.push(z.clone()).unwrap();
+        assert_eq!(prepared.inputs(), &[w.clone(), y.clone(), z.clone()]);
+        assert_eq!(
+            prepared.accumulate(&[], Some(b"ctx")).unwrap(),
+            full(&[w.clone(), y.clone(), z.clone()])
+        );
+
+        assert!(prepared.push(x.clone()).is_err());
+        assert!(prepared.set(3, x.clone()).is_err());
+        assert!(prepared.accumulate(&[x], Some(b"ctx")).is_err());
+    }
 }
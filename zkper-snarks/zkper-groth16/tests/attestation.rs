@@ -0,0 +1,77 @@
+use std::sync::Arc;
+
+use zkper_curves::curves::bls12_381::curves::g1::G1Projective;
+use zkper_groth16::{
+    attestation::{
+        generate_proving_parameters_with_attestation, verify_setup_attestation, SetupAttestation,
+    },
+    error::Groth16Error,
+};
+use zkper_rand::TestRng;
+
+use crate::test_mimc::{mimc_constants, MiMCDemo};
+
+pub mod test_mimc;
+
+pub const MIMC_ROUNDS: usize = 16;
+
+#[test]
+fn test_setup_attestation() {
+    let mut rng = TestRng::new();
+
+    let constants = mimc_constants(&mut rng);
+    let circuit = MiMCDemo {
+        xl: None,
+        xr: None,
+        constants: &constants,
+    };
+
+    let (params, attestation) = generate_proving_parameters_with_attestation(
+        MiMCDemo {
+            xl: None,
+            xr: None,
+            constants: &constants,
+        },
+        &mut rng,
+    )
+    .unwrap();
+    verify_setup_attestation(&circuit, &params, &attestation).unwrap();
+
+    // the text file round trips
+    let mut file = vec![];
+    attestation.write(&mut file).unwrap();
+    let read = SetupAttestation::read(file.as_slice()).unwrap();
+    assert_eq!(read, attestation);
+
+    // editing a digest breaks the transcript chain
+    let text = String::from_utf8(file).unwrap();
+    let line = text.lines().find(|l| l.starts_with("vk ")).unwrap();
+    let forged = text.replace(line, &format!("vk {}", "00".repeat(32)));
+    assert!(matches!(
+        SetupAttestation::read(forged.as_bytes()),
+        Err(Groth16Error::MalformedFile(_))
+    ));
+
+    // parameters for another circuit
+    let mut other_constants = constants.clone();
+    other_constants[0] += 1;
+    let other = MiMCDemo {
+        xl: None,
+        xr: None,
+        constants: &other_constants,
+    };
+    assert!(matches!(
+        verify_setup_attestation(&other, &params, &attestation),
+        Err(Groth16Error::AttestationMismatch("circuit"))
+    ));
+
+    // parameters modified after the setup
+    let mut tampered = params.clone();
+    let mut h_query = tampered.h_query.as_ref().clone();
+    h_query[0] = G1Projective::generator().to_affine();
+    tampered.h_query = Arc::new(h_query);
+    assert!(matches!(
+        verify_setup_attestation(&circuit, &tampered, &attestation),
+        Err(Groth16Error::AttestationMismatch("h query"))
+    ));
+}
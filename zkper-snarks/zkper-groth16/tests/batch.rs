@@ -0,0 +1,90 @@
+use zkper_curves::curves::bls12_381::Scalar;
+use zkper_groth16::{
+    hash::{Blake3Hash, Keccak256Hash, Sha256Hash},
+    models::proof::Proof,
+    prover::{create_proof, create_proof_with_context},
+    verifier::{
+        batch_challenges, batch_challenges_with, verify_proofs_batch,
+        verify_proofs_batch_with_context,
+    },
+};
+use zkper_rand::TestRng;
+
+use crate::test_mimc::{mimc_fixture, mimc_instance, MiMCDemo};
+
+pub mod test_mimc;
+
+pub const MIMC_ROUNDS: usize = 16;
+
+fn batch<'a>(proofs: &'a [Proof], images: &'a [Vec<Scalar>]) -> Vec<(&'a Proof, &'a [Scalar])> {
+    proofs
+        .iter()
+        .zip(images.iter())
+        .map(|(proof, image)| (proof, image.as_slice()))
+        .collect()
+}
+
+#[test]
+fn test_batch_verification() {
+    let mut rng = TestRng::new();
+
+    let (params, pvk, constants, ..) = mimc_fixture(&mut rng);
+
+    let mut proofs = vec![];
+    let mut images = vec![];
+    let mut bound_proofs = vec![];
+    for _ in 0..3 {
+        let (xl, xr, image) = mimc_instance(&mut rng, &constants);
+        images.push(vec![image]);
+
+        let circuit = || MiMCDemo {
+            xl: Some(xl.clone()),
+            xr: Some(xr.clone()),
+            constants: &constants,
+        };
+        proofs.push(create_proof(circuit(), &params, &mut rng).unwrap());
+        bound_proofs
+            .push(create_proof_with_context(circuit(), &params, Some(b"app"), &mut rng).unwrap());
+    }
+
+    assert!(verify_proofs_batch(&pvk, &batch(&proofs, &images)).unwrap());
+    assert!(verify_proofs_batch(&pvk, &[]).unwrap());
+    assert!(
+        verify_proofs_batch_with_context(&pvk, &batch(&bound_proofs, &images), Some(b"app"))
+            .unwrap()
+    );
+    assert!(!verify_proofs_batch(&pvk, &batch(&bound_proofs, &images)).unwrap());
+
+    // one wrong public input fails the whole batch
+    let mut swapped = images.clone();
+    swapped.swap(0, 1);
+    assert!(!verify_proofs_batch(&pvk, &batch(&proofs, &swapped)).unwrap());
+
+    // the combiners are a deterministic function of the batch
+    let challenges = batch_challenges(&pvk, &batch(&proofs, &images), None);
+    assert_eq!(challenges.len(), 3);
+    assert_eq!(
+        challenges,
+        batch_challenges(&pvk, &batch(&proofs, &images), None)
+    );
+    assert_ne!(
+        challenges,
+        batch_challenges(&pvk, &batch(&proofs, &images), Some(b"app"))
+    );
+    assert!(batch_challenges(&pvk, &batch(&proofs, &swapped), None)
+        .iter()
+        .zip(&challenges)
+        .all(|(a, b)| a != b));
+
+    // the transcript hash is selectable, SHA-256 being the default
+    let batch = batch(&proofs, &images);
+    assert_eq!(
+        batch_challenges_with::<Sha256Hash>(&pvk, &batch, None),
+        challenges
+    );
+    let keccak = batch_challenges_with::<Keccak256Hash>(&pvk, &batch, None);
+    let blake3 = batch_challenges_with::<Blake3Hash>(&pvk, &batch, None);
+    assert_ne!(keccak, challenges);
+    assert_ne!(blake3, challenges);
+    assert_ne!(keccak, blake3);
+}
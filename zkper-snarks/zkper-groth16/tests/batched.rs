@@ -0,0 +1,81 @@
+use zkper_curves::{
+    curves::bls12_381::{Bls12_381ScalarField, Scalar},
+    traits::field::FieldTrait,
+};
+use zkper_groth16::{
+    batched::{split_public_inputs, verify_batched_proof, BatchedCircuit},
+    generator::generate_proving_parameters,
+    prover::create_proof,
+    verifier::prepare_verifying_key,
+};
+use zkper_rand::TestRng;
+
+use crate::test_mimc::{mimc_constants, mimc_instance, MiMCDemo};
+
+pub mod test_mimc;
+
+pub const MIMC_ROUNDS: usize = 16;
+
+const INSTANCES: usize = 3;
+
+#[test]
+fn test_batched_circuit() {
+    let mut rng = TestRng::new();
+
+    let constants = mimc_constants(&mut rng);
+
+    let setup = BatchedCircuit::new(
+        (0..INSTANCES)
+            .map(|_| MiMCDemo {
+                xl: None,
+                xr: None,
+                constants: &constants,
+            })
+            .collect(),
+    );
+    let params = generate_proving_parameters(setup, &mut rng).unwrap();
+    let pvk = prepare_verifying_key(&params.vk);
+
+    // the constant one, the context and one image per instance
+    assert_eq!(params.vk.ic.len(), 2 + INSTANCES);
+
+    let mut instances = vec![];
+    let mut images = vec![];
+    for _ in 0..INSTANCES {
+        let (xl, xr, image) = mimc_instance(&mut rng, &constants);
+        images.push(vec![image]);
+
+        instances.push(MiMCDemo {
+            xl: Some(xl),
+            xr: Some(xr),
+            constants: &constants,
+        });
+    }
+
+    let proof = create_proof(BatchedCircuit::new(instances), &params, &mut rng).unwrap();
+    assert!(verify_batched_proof(&pvk, &proof, &images).unwrap());
+
+    // the statements are bound to their position in the batch
+    let mut swapped = images.clone();
+    swapped.swap(0, 2);
+    assert!(!verify_batched_proof(&pvk, &proof, &swapped).unwrap());
+
+    // the image of every instance counts
+    let mut wrong = images.clone();
+    wrong[1][0] = Scalar::from(Bls12_381ScalarField::random(&mut rng));
+    assert!(!verify_batched_proof(&pvk, &proof, &wrong).unwrap());
+
+    // a batch of a different size does not fit the key
+    assert!(verify_batched_proof(&pvk, &proof, &images[..2]).is_err());
+
+    let merged = images.concat();
+    let split = split_public_inputs(&merged, INSTANCES).unwrap();
+    assert_eq!(split, images);
+}
+
+#[test]
+fn test_batched_circuit_rejects_empty_batch() {
+    let mut rng = TestRng::new();
+    let empty = BatchedCircuit::<MiMCDemo>::new(vec![]);
+    assert!(generate_proving_parameters(empty, &mut rng).is_err());
+}
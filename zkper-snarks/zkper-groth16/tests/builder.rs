@@ -0,0 +1,106 @@
+use rug::Integer;
+use zkper_curves::{
+    curves::bls12_381::{Bls12_381ScalarField, Scalar},
+    traits::field::FieldTrait,
+};
+use zkper_groth16::{
+    builder::{Backend, BuilderCircuit, CircuitBuilder},
+    field::{CircuitField, Fr, Mersenne31},
+    generator::generate_proving_parameters,
+    mock::MockSystem,
+    prover::create_proof,
+    verifier::{prepare_verifying_key, verify_proof},
+};
+use zkper_rand::TestRng;
+
+/// out = (flag ? x^3 : x - y) + y, with flag a private bit.
+struct SelectCube<F: CircuitField = Fr> {
+    x: Option<F::Value>,
+    y: Option<F::Value>,
+    flag: Option<bool>,
+}
+
+impl SelectCube {
+    fn output(x: &Scalar, y: &Scalar, flag: bool) -> Scalar {
+        if flag {
+            x.square().mul(x).add(y)
+        } else {
+            x.clone()
+        }
+    }
+}
+
+impl<F: CircuitField> BuilderCircuit<F> for SelectCube<F> {
+    fn build<B: Backend<Field = F>>(&self, cs: &mut CircuitBuilder<B>) -> anyhow::Result<()> {
+        let x = cs.alloc(self.x.clone())?;
+        let y = cs.alloc(self.y.clone())?;
+        let flag = cs.alloc_boolean(self.flag)?;
+
+        let x2 = cs.square(&x)?;
+        let x3 = cs.mul(&x2, &x)?;
+        let difference = cs.sub(&x, &y)?;
+        let selected = cs.select(&flag, &x3, &difference)?;
+        let sum = cs.add(&selected, &y)?;
+
+        let out = cs.alloc_input(sum.value().cloned())?;
+        cs.enforce_equal(&sum, &out);
+
+        Ok(())
+    }
+}
+
+#[test]
+fn test_builder_circuit() {
+    let mut rng = TestRng::new();
+
+    let params = generate_proving_parameters(
+        SelectCube {
+            x: None,
+            y: None,
+            flag: None,
+        },
+        &mut rng,
+    )
+    .unwrap();
+    let pvk = prepare_verifying_key(&params.vk);
+
+    for flag in [true, false] {
+        let x = Scalar::from(Bls12_381ScalarField::random(&mut rng));
+        let y = Scalar::from(Bls12_381ScalarField::random(&mut rng));
+        let out = SelectCube::output(&x, &y, flag);
+
+        let circuit = SelectCube {
+            x: Some(x.clone()),
+            y: Some(y.clone()),
+            flag: Some(flag),
+        };
+        let proof = create_proof(circuit, &params, &mut rng).unwrap();
+        assert!(verify_proof(&pvk, &proof, std::slice::from_ref(&out)).unwrap());
+
+        let wrong = out.add(&Scalar::from(Integer::from(1)));
+        assert!(!verify_proof(&pvk, &proof, &[wrong]).unwrap());
+    }
+
+    // the prover needs every value
+    let circuit = SelectCube {
+        x: None,
+        y: None,
+        flag: Some(true),
+    };
+    assert!(create_proof(circuit, &params, &mut rng).is_err());
+}
+
+#[test]
+fn test_builder_circuit_mock() {
+    // the same circuit, checked over 2^31 - 1 without a setup or a proof
+    for (flag, out) in [(true, 3 * 3 * 3 + 5), (false, 3)] {
+        let cs = MockSystem::<Mersenne31>::synthesize(&SelectCube {
+            x: Some(3),
+            y: Some(5),
+            flag: Some(flag),
+        })
+        .unwrap();
+        assert!(cs.is_satisfied());
+        assert_eq!(cs.public_inputs(), &[out]);
+    }
+}
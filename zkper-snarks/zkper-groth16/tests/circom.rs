@@ -0,0 +1,71 @@
+use zkper_groth16::{
+    circom::{read_witness, witness, write_witness, R1cs, R1csCircuit},
+    generator::generate_proving_parameters,
+    prover::create_proof,
+    verifier::{prepare_verifying_key, verify_proof},
+};
+use zkper_rand::TestRng;
+
+use crate::test_mimc::{mimc_constants, mimc_instance, MiMCDemo};
+
+pub mod test_mimc;
+
+pub const MIMC_ROUNDS: usize = 16;
+
+#[test]
+fn test_prove_exported_circuit() {
+    let mut rng = TestRng::new();
+
+    let constants = mimc_constants(&mut rng);
+    let (xl, xr, image) = mimc_instance(&mut rng, &constants);
+    let circuit = MiMCDemo {
+        xl: Some(xl),
+        xr: Some(xr),
+        constants: &constants,
+    };
+
+    // export both files and read them back, as snarkjs would
+    let mut r1cs_file = vec![];
+    R1cs::from_circuit(&circuit)
+        .unwrap()
+        .write(&mut r1cs_file)
+        .unwrap();
+    let mut wtns_file = vec![];
+    write_witness(&witness(&circuit).unwrap(), &mut wtns_file).unwrap();
+
+    let r1cs = R1cs::read(&r1cs_file).unwrap();
+    let witness = read_witness(&wtns_file).unwrap();
+    assert_eq!(r1cs.num_wires as usize, witness.len());
+    assert_eq!(r1cs.num_public(), 1);
+    assert_eq!(witness[1], image);
+
+    // prove the imported circuit
+    let params = generate_proving_parameters(
+        R1csCircuit {
+            r1cs: r1cs.clone(),
+            witness: None,
+        },
+        &mut rng,
+    )
+    .unwrap();
+    let pvk = prepare_verifying_key(&params.vk);
+
+    let public_inputs = witness[1..=r1cs.num_public()].to_vec();
+    let proof = create_proof(
+        R1csCircuit {
+            r1cs: r1cs.clone(),
+            witness: Some(witness.clone()),
+        },
+        &params,
+        &mut rng,
+    )
+    .unwrap();
+    assert!(verify_proof(&pvk, &proof, &public_inputs).unwrap());
+
+    // a witness of the wrong length is rejected
+    let circuit = R1csCircuit {
+        r1cs,
+        witness: Some(witness[1..].to_vec()),
+    };
+    assert!(create_proof(circuit, &params, &mut rng).is_err());
+}
@@ -0,0 +1,105 @@
+use std::fs::File;
+
+use rug::Integer;
+use zkper_curves::curves::bls12_381::{Scalar, BLS12_381_SCALAR};
+use zkper_groth16::{
+    error::Groth16Error,
+    generator::{generate_proving_parameters, generate_proving_parameters_with_commitment},
+    models::commitment::CommittedProof,
+    params_source::{MappedParameters, ParamsSource},
+    prover::{create_committed_proof, create_proof},
+    verifier::{prepare_verifying_key, verify_committed_proof, verify_proof},
+};
+use zkper_rand::TestRng;
+
+use crate::test_mimc::{mimc_constants, mimc_instance, MiMCDemo};
+
+pub mod test_mimc;
+
+pub const MIMC_ROUNDS: usize = 16;
+
+#[test]
+fn test_committed_preimage() {
+    let mut rng = TestRng::new();
+
+    let constants = mimc_constants(&mut rng);
+
+    let setup = || MiMCDemo {
+        xl: None,
+        xr: None,
+        constants: &constants,
+    };
+    // xl and xr are the first private variables
+    let params = generate_proving_parameters_with_commitment(setup(), 2, &mut rng).unwrap();
+    let key = params.commitment.clone().unwrap();
+    assert_eq!(key.len(), 2);
+    let plain = generate_proving_parameters(setup(), &mut rng).unwrap();
+    assert!(plain.commitment.is_none());
+    assert_eq!(params.l_query.len() + 2, plain.l_query.len());
+    let pvk = prepare_verifying_key(&params.vk);
+
+    let (xl, xr, image) = mimc_instance(&mut rng, &constants);
+    let inputs = std::slice::from_ref(&image);
+    let circuit = || MiMCDemo {
+        xl: Some(xl.clone()),
+        xr: Some(xr.clone()),
+        constants: &constants,
+    };
+
+    let (proof, opening) = create_committed_proof(circuit(), &params, None, &mut rng).unwrap();
+    assert!(verify_committed_proof(&pvk, &proof, inputs, None).unwrap());
+    assert!(proof.validate().is_ok());
+
+    // D opens to the preimage
+    let preimage = [Scalar::from(xl.clone()), Scalar::from(xr.clone())];
+    assert_eq!(key.commit(&preimage, &opening).unwrap(), proof.d);
+    let other = [Scalar::from(xr.clone()), Scalar::from(xl.clone())];
+    assert_ne!(key.commit(&other, &opening).unwrap(), proof.d);
+    assert!(matches!(
+        key.commit(&preimage[..1], &opening).unwrap_err(),
+        Groth16Error::CommitmentLength {
+            expected: 2,
+            actual: 1
+        }
+    ));
+
+    // D is part of the statement, and the proof alone does not verify
+    let wrong_image = Scalar::from(BLS12_381_SCALAR.add(image.0.clone(), &Integer::from(1)));
+    assert!(!verify_committed_proof(&pvk, &proof, &[wrong_image], None).unwrap());
+    let swapped = CommittedProof {
+        d: key.commit(&other, &Scalar::from(Integer::from(1))).unwrap(),
+        ..proof.clone()
+    };
+    assert!(!verify_committed_proof(&pvk, &swapped, inputs, None).unwrap());
+    assert!(!verify_proof(&pvk, &proof.proof, inputs).unwrap());
+    assert!(!verify_committed_proof(&pvk, &proof, inputs, Some(b"context")).unwrap());
+
+    // a second proof commits with a fresh opening
+    let (again, _) = create_committed_proof(circuit(), &params, None, &mut rng).unwrap();
+    assert_ne!(again.d, proof.d);
+
+    // plain proofs need plain parameters and the other way around
+    assert!(matches!(
+        create_proof(circuit(), &params, &mut rng).unwrap_err(),
+        Groth16Error::CommittedParameters
+    ));
+    assert!(matches!(
+        create_committed_proof(circuit(), &plain, None, &mut rng).unwrap_err(),
+        Groth16Error::MissingCommitmentKey
+    ));
+    let private = plain.l_query.len();
+    assert!(matches!(
+        generate_proving_parameters_with_commitment(setup(), private + 1, &mut rng).unwrap_err(),
+        Groth16Error::CommitmentLength { expected, actual }
+            if expected == private + 1 && actual == private
+    ));
+
+    // the key survives a round trip through a parameter file
+    let path = std::env::temp_dir().join(format!("zkper-committed-{}.bin", std::process::id()));
+    params.write(File::create(&path).unwrap()).unwrap();
+    let mapped = MappedParameters::open(&path).unwrap();
+    assert_eq!(mapped.commitment(), Some(&key));
+    let (proof, _) = create_committed_proof(circuit(), &mapped, None, &mut rng).unwrap();
+    assert!(verify_committed_proof(&pvk, &proof, inputs, None).unwrap());
+    std::fs::remove_file(&path).unwrap();
+}
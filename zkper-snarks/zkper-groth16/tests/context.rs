@@ -0,0 +1,35 @@
+use zkper_groth16::{
+    prover::{create_proof, create_proof_with_context},
+    verifier::{verify_proof, verify_proof_with_context},
+};
+use zkper_rand::TestRng;
+
+use crate::test_mimc::{mimc_fixture, MiMCDemo};
+
+pub mod test_mimc;
+
+pub const MIMC_ROUNDS: usize = 16;
+
+#[test]
+fn test_proof_bound_to_context() {
+    let mut rng = TestRng::new();
+
+    let (params, pvk, constants, xl, xr, image) = mimc_fixture(&mut rng);
+    let inputs = std::slice::from_ref(&image);
+
+    let circuit = || MiMCDemo {
+        xl: Some(xl.clone()),
+        xr: Some(xr.clone()),
+        constants: &constants,
+    };
+
+    let proof = create_proof_with_context(circuit(), &params, Some(b"app-a"), &mut rng).unwrap();
+    assert!(verify_proof_with_context(&pvk, &proof, inputs, Some(b"app-a")).unwrap());
+    assert!(!verify_proof_with_context(&pvk, &proof, inputs, Some(b"app-b")).unwrap());
+    assert!(!verify_proof(&pvk, &proof, inputs).unwrap());
+
+    // a proof without context does not verify under any context
+    let proof = create_proof(circuit(), &params, &mut rng).unwrap();
+    assert!(verify_proof(&pvk, &proof, inputs).unwrap());
+    assert!(!verify_proof_with_context(&pvk, &proof, inputs, Some(b"")).unwrap());
+}
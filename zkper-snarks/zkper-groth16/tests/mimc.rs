@@ -9,7 +9,7 @@ use zkper_groth16::{
     prover::create_proof,
     verifier::{prepare_verifying_key, verify_proof},
 };
-use zkper_rand::ZkperRng;
+use zkper_rand::TestRng;
 
 use crate::test_mimc::{implemention::mimc_implemention, MiMCDemo};
 // use zkper_curves::curves::bls12_381::Bls12_381ScalarField;
@@ -20,7 +20,7 @@ pub const MIMC_ROUNDS: usize = 322;
 
 #[test]
 fn test_mimc() {
-    let mut rng = ZkperRng::new_test();
+    let mut rng = TestRng::new();
 
     // Generate the MiMC round constants
     let constants = (0..MIMC_ROUNDS)
@@ -86,7 +86,7 @@ fn test_mimc() {
 
             // verify the proof
             let start = Instant::now();
-            let verified = verify_proof(&pvk, &proof, &[image.0]).unwrap();
+            let verified = verify_proof(&pvk, &proof, std::slice::from_ref(&image)).unwrap();
             println!("verified: {:#}", verified);
             total_verifying += start.elapsed();
         }
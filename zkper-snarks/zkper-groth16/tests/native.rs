@@ -0,0 +1,64 @@
+use zkper_curves::curves::bls12_381::Scalar;
+use zkper_groth16::{
+    context::context_scalar,
+    native::{self, prepare_verifier_key, VerifierKey},
+    prover::{create_proof, create_proof_with_context},
+    verifier::verify_proof_with_context,
+};
+use zkper_rand::TestRng;
+
+use crate::test_mimc::{mimc_fixture, MiMCDemo};
+
+pub mod test_mimc;
+
+pub const MIMC_ROUNDS: usize = 16;
+
+#[test]
+fn test_native_verifier_matches_rug() {
+    let mut rng = TestRng::new();
+
+    let (params, pvk, constants, xl, xr, image) = mimc_fixture(&mut rng);
+    let inputs = std::slice::from_ref(&image);
+    let native_inputs = [native::Scalar::from_bytes_be(&image.to_bytes()).unwrap()];
+
+    let vk = VerifierKey::from_bytes(&params.vk.verifier_key().to_bytes()).unwrap();
+    let native_pvk = prepare_verifier_key(&vk);
+
+    let circuit = || MiMCDemo {
+        xl: Some(xl.clone()),
+        xr: Some(xr.clone()),
+        constants: &constants,
+    };
+
+    let plain = create_proof(circuit(), &params, &mut rng).unwrap();
+    let bound = create_proof_with_context(circuit(), &params, Some(b"app"), &mut rng).unwrap();
+    let mut tampered = plain.clone();
+    std::mem::swap(&mut tampered.a, &mut tampered.c);
+
+    for proof in [&plain, &bound, &tampered] {
+        let native_proof = native::Proof::from_bytes(&proof.to_bytes()).unwrap();
+        for context in [None, Some(&b"app"[..]), Some(&b"other"[..])] {
+            assert_eq!(
+                native::verify_proof_with_context(
+                    &native_pvk,
+                    &native_proof,
+                    &native_inputs,
+                    context
+                )
+                .unwrap(),
+                verify_proof_with_context(&pvk, proof, inputs, context).unwrap()
+            );
+        }
+    }
+
+    let native_plain = native::Proof::from_bytes(&plain.to_bytes()).unwrap();
+    assert!(native::verify_proof(&native_pvk, &native_plain, &native_inputs).unwrap());
+    assert!(native::verify_proof(&native_pvk, &native_plain, &[]).is_err());
+
+    for context in [None, Some(&b""[..]), Some(&b"app"[..])] {
+        assert_eq!(
+            native::context_scalar(context).to_bytes_be(),
+            Scalar::from(context_scalar(context)).to_bytes()
+        );
+    }
+}
@@ -0,0 +1,106 @@
+//! The pure-Rust verifier on its own, against a key and proofs pinned from
+//! the shared MiMC fixture of `test_mimc`. Needs only `backend-native`, so it
+//! also runs in the rug-free builds.
+
+use zkper_groth16::{
+    error::Groth16Error,
+    native::{
+        prepare_verifier_key, verify_proof, verify_proof_with_context, Proof, Scalar, VerifierKey,
+    },
+};
+
+/// `VerifierKey::to_bytes` of the MiMC circuit, one public input.
+const VERIFIER_KEY: &str = concat!(
+    "5a4b50473136564bb38fe3cbe4f907fdbc28b043e47d6b9e9572bdb47aa7ef00",
+    "91601943170ff75f825b53d5d8462b1f1a04e1dfc81168848466caef9f225ef5",
+    "028d5b5b4b2127107a8a3e42edb5847177f02a61fbe73e55eecb07f9c20c8220",
+    "3ba02d71d7fb045c13af7dabd2c1060bdfaaa78c67fea61be3b2cb08525c1bdb",
+    "140369474e265cecc2d5b035ab481d0eab55d5190e652de5a57bab7cb67aa045",
+    "2ddac484397609b13592aad32eebe29c1cdf0fc99f34d510bf4fa9379ecc8312",
+    "7fce6f43462696371838d8c3f376ae33dadb08e64e9edb15bb603f5afb9c0f7c",
+    "8448c5bf815bd5052677dd6ea18c3489765bc16330b76cc0b37640273a9669d3",
+    "b1531b95bcd0086d3f8054006df142b75bf565664dfec0e3f201a0b518998704",
+    "38c57d63610b4dff06f81de5e90a2550a07add2b1fd11dbba135717343ec24f3",
+    "514b86aa714278e7b05ccfbd2eaf336b2c04eb1fbd28b0340000000000000003",
+    "8a5ac25e8730c7ebed508663d1b2ff7c1fa9fa05df172b71dcad9416623c3ed5",
+    "4160205975476638eea3c8d95170025b8e9d9a3250fb4363486a32fe0eef2ebe",
+    "aa1c9f84ec7f682da37ab6062a731ed63043e75f1c4b068a8fec395fca59157b",
+    "af2a7ba993eed2ebdb6bf01d53d4a3412aa1c6ef0f52fabf7e7fe850571ff0e7",
+    "1574358f4b90316a5f2e5ffe015312f8",
+);
+
+/// The public input, the MiMC image of the proven preimage.
+const IMAGE: &str = "51e53cbe45e0ce749eac0ada27cf9222c45eb77f3934815ee190eae5bd77bc99";
+
+/// A proof without a context.
+const PROOF: &str = concat!(
+    "94acd23e3b6fc8b520da13090c2fad270716ccff8144fe84c6a862baad58d9a6",
+    "057c619a067780af4cbf474ac534ff5bb41a78ccf5e3e6eb0b23c1a517a5a35d",
+    "eb3ddaf568163a19d97fec3226e0095b9ab4e63ab078d2d477162af0837b6209",
+    "12a1b1301c5b8285fa240fb8bf32b5a3a2d3dadaa16739432da6ca3dca6dd777",
+    "d5c154fc41019e97aea012db7961afc5b693f0bb728495fb594992f29d431abf",
+    "9e6907d34e24528aafc1400806fa2a88c76d797203288f1a8d8e406fc3b284b2",
+);
+
+/// A proof bound to the context `app`.
+const PROOF_WITH_CONTEXT: &str = concat!(
+    "805fee3db9f80aec559d1acb7f8780fb02c3b8bae9045035bbc8286780e8af76",
+    "294581f57b2ff546713179359d145872b750a484f8fe0e156b2cefed977d4ab8",
+    "8026db97fdc07461c008a7b8002e1557e72e1b527b8925701463c9342c5bc40b",
+    "19f4081ea3b051a298fc1bd1929f590abd96d92563516761e2638bd82538c3cd",
+    "a44ad2e3014470e3a5c2b254333c3e1d86b8624e58348fc2b384c1344ae1c8ec",
+    "81f40960eb8b3bb62aee85a0d6470a0c23147e056836d383c74c2dece5a58fcc",
+);
+
+fn unhex(hex: &str) -> Vec<u8> {
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+        .collect()
+}
+
+#[test]
+fn test_pinned_proofs() {
+    let pvk = prepare_verifier_key(&VerifierKey::from_bytes(&unhex(VERIFIER_KEY)).unwrap());
+    let image = Scalar::from_bytes_be(&unhex(IMAGE)).unwrap();
+    let proof = Proof::from_bytes(&unhex(PROOF)).unwrap();
+    let bound = Proof::from_bytes(&unhex(PROOF_WITH_CONTEXT)).unwrap();
+
+    assert!(verify_proof(&pvk, &proof, &[image]).unwrap());
+    assert!(verify_proof_with_context(&pvk, &bound, &[image], Some(b"app")).unwrap());
+
+    // a different statement, context or proof
+    assert!(!verify_proof(&pvk, &proof, &[image + Scalar::ONE]).unwrap());
+    assert!(!verify_proof(&pvk, &bound, &[image]).unwrap());
+    assert!(!verify_proof_with_context(&pvk, &bound, &[image], Some(b"other")).unwrap());
+    assert!(!verify_proof_with_context(&pvk, &proof, &[image], Some(b"app")).unwrap());
+    let swapped = Proof {
+        a: proof.c,
+        c: proof.a,
+        ..proof.clone()
+    };
+    assert!(!verify_proof(&pvk, &swapped, &[image]).unwrap());
+
+    assert!(matches!(
+        verify_proof(&pvk, &proof, &[]),
+        Err(Groth16Error::WrongInputCount {
+            expected: 1,
+            actual: 0
+        })
+    ));
+}
+
+#[test]
+fn test_pinned_encodings_rejected() {
+    let key = unhex(VERIFIER_KEY);
+    assert!(VerifierKey::from_bytes(&key[..key.len() - 1]).is_err());
+    assert!(VerifierKey::from_bytes(&[key.as_slice(), &[0]].concat()).is_err());
+    assert!(VerifierKey::from_bytes(&key[1..]).is_err());
+
+    let proof = unhex(PROOF);
+    assert!(Proof::from_bytes(&proof[1..]).is_err());
+    let mut flipped = proof.clone();
+    // not a point: clear the compression flag of A
+    flipped[0] &= 0x7f;
+    assert!(Proof::from_bytes(&flipped).is_err());
+}
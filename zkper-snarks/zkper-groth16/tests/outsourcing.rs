@@ -0,0 +1,44 @@
+use zkper_curves::curves::bls12_381::curves::g1::G1Projective;
+use zkper_groth16::{
+    outsourcing::{prepare_outsourced_proof, MsmMasks},
+    verifier::verify_proof,
+};
+use zkper_rand::TestRng;
+
+use crate::test_mimc::{mimc_fixture, MiMCDemo};
+
+pub mod test_mimc;
+
+pub const MIMC_ROUNDS: usize = 16;
+
+#[test]
+fn test_outsourced_proof() {
+    let mut rng = TestRng::new();
+
+    let (params, pvk, constants, xl, xr, image) = mimc_fixture(&mut rng);
+
+    let circuit = || MiMCDemo {
+        xl: Some(xl.clone()),
+        xr: Some(xr.clone()),
+        constants: &constants,
+    };
+
+    let masks = MsmMasks::generate(&params, &mut rng).unwrap();
+    let (request, pending) =
+        prepare_outsourced_proof(circuit(), &params, None, masks, &mut rng).unwrap();
+    let response = request.compute(&params).unwrap();
+    let proof = pending.finish(response);
+    assert!(verify_proof(&pvk, &proof, std::slice::from_ref(&image)).unwrap());
+
+    // a fresh mask set hides the same witness behind different exponents
+    let masks = MsmMasks::generate(&params, &mut rng).unwrap();
+    let (other_request, pending) =
+        prepare_outsourced_proof(circuit(), &params, None, masks, &mut rng).unwrap();
+    assert_ne!(request.l, other_request.l);
+
+    // a dishonest server produces a proof that does not verify
+    let mut response = other_request.compute(&params).unwrap();
+    response.h = response.h.add(&G1Projective::generator());
+    let proof = pending.finish(response);
+    assert!(!verify_proof(&pvk, &proof, std::slice::from_ref(&image)).unwrap());
+}
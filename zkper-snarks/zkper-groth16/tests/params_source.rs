@@ -0,0 +1,43 @@
+use std::fs::File;
+
+use zkper_groth16::{
+    params_source::{MappedParameters, PointEncoding},
+    prover::create_proof,
+    verifier::verify_proof,
+};
+use zkper_rand::TestRng;
+
+use crate::test_mimc::{mimc_fixture, MiMCDemo};
+
+pub mod test_mimc;
+
+pub const MIMC_ROUNDS: usize = 16;
+
+#[test]
+fn test_prove_from_mapped_parameters() {
+    let mut rng = TestRng::new();
+
+    let (params, pvk, constants, xl, xr, image) = mimc_fixture(&mut rng);
+
+    for encoding in [PointEncoding::Uncompressed, PointEncoding::Compressed] {
+        let path = std::env::temp_dir().join(format!(
+            "zkper-mimc-params-{:?}-{}.bin",
+            encoding,
+            std::process::id()
+        ));
+        params
+            .write_with_encoding(File::create(&path).unwrap(), encoding)
+            .unwrap();
+        let mapped = MappedParameters::open(&path).unwrap();
+
+        let c = MiMCDemo {
+            xl: Some(xl.clone()),
+            xr: Some(xr.clone()),
+            constants: &constants,
+        };
+        let proof = create_proof(c, &mapped, &mut rng).unwrap();
+        assert!(verify_proof(&pvk, &proof, std::slice::from_ref(&image)).unwrap());
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,156 @@
+//! Soak test for long-running provers, run with
+//! `cargo test -p zkper-groth16 --features soak --release --test soak -- --nocapture`.
+//!
+//! Creates and verifies `SOAK_ITERATIONS` proofs (2000 by default) against one
+//! set of parameters and fails if heap or resident memory keeps growing after
+//! a warm-up, or if the parameters are still shared once the proofs are gone.
+//! Heap usage is counted by the global allocator below; GMP allocates through
+//! libc, so the integers behind field elements only show up in the RSS.
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+};
+
+use zkper_groth16::{prover::create_proof, verifier::verify_proof};
+use zkper_rand::TestRng;
+
+use crate::test_mimc::{mimc_fixture, mimc_instance, MiMCDemo};
+
+pub mod test_mimc;
+
+pub const MIMC_ROUNDS: usize = 16;
+
+const DEFAULT_ITERATIONS: usize = 2000;
+const WARMUP_ITERATIONS: usize = 20;
+const REPORT_EVERY: usize = 100;
+
+/// Allowed growth after the warm-up. Proofs are dropped every iteration, so
+/// steady state usage should stay flat up to allocator noise.
+const MAX_LIVE_GROWTH: usize = 1 << 20;
+const MAX_RSS_GROWTH: usize = 64 << 20;
+
+struct CountingAllocator;
+
+static LIVE_BYTES: AtomicUsize = AtomicUsize::new(0);
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            LIVE_BYTES.fetch_add(layout.size(), Ordering::Relaxed);
+            ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        LIVE_BYTES.fetch_sub(layout.size(), Ordering::Relaxed);
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() {
+            LIVE_BYTES.fetch_sub(layout.size(), Ordering::Relaxed);
+            LIVE_BYTES.fetch_add(new_size, Ordering::Relaxed);
+            ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        }
+        new_ptr
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+/// Resident set size in bytes, from /proc/self/statm. None off Linux.
+fn rss_bytes() -> Option<usize> {
+    let statm = std::fs::read_to_string("/proc/self/statm").ok()?;
+    let pages = statm.split_whitespace().nth(1)?.parse::<usize>().ok()?;
+    Some(pages * 4096)
+}
+
+fn iterations() -> usize {
+    std::env::var("SOAK_ITERATIONS")
+        .ok()
+        .and_then(|n| n.parse().ok())
+        .unwrap_or(DEFAULT_ITERATIONS)
+}
+
+#[test]
+fn soak_create_proofs() {
+    let mut rng = TestRng::new();
+
+    let (params, pvk, constants, ..) = mimc_fixture(&mut rng);
+
+    let prove_and_verify = |rng: &mut TestRng| {
+        let (xl, xr, image) = mimc_instance(rng, &constants);
+
+        let c = MiMCDemo {
+            xl: Some(xl),
+            xr: Some(xr),
+            constants: &constants,
+        };
+        let proof = create_proof(c, &params, rng).unwrap();
+        assert!(verify_proof(&pvk, &proof, std::slice::from_ref(&image)).unwrap());
+    };
+
+    // lazily initialized constants settle during the warm-up
+    for _ in 0..WARMUP_ITERATIONS {
+        prove_and_verify(&mut rng);
+    }
+
+    let baseline_live = LIVE_BYTES.load(Ordering::Relaxed);
+    let baseline_allocations = ALLOCATIONS.load(Ordering::Relaxed);
+    let baseline_rss = rss_bytes();
+
+    let iterations = iterations();
+    for i in 1..=iterations {
+        prove_and_verify(&mut rng);
+
+        if i % REPORT_EVERY == 0 || i == iterations {
+            eprintln!(
+                "{:>6} proofs: live heap {:+} bytes, {} allocations/proof, rss {:?}",
+                i,
+                LIVE_BYTES.load(Ordering::Relaxed) as isize - baseline_live as isize,
+                (ALLOCATIONS.load(Ordering::Relaxed) - baseline_allocations) / i,
+                rss_bytes(),
+            );
+        }
+    }
+
+    let live_growth = LIVE_BYTES
+        .load(Ordering::Relaxed)
+        .saturating_sub(baseline_live);
+    assert!(
+        live_growth < MAX_LIVE_GROWTH,
+        "live heap grew by {} bytes over {} proofs",
+        live_growth,
+        iterations
+    );
+
+    if let (Some(baseline), Some(rss)) = (baseline_rss, rss_bytes()) {
+        let rss_growth = rss.saturating_sub(baseline);
+        assert!(
+            rss_growth < MAX_RSS_GROWTH,
+            "rss grew by {} bytes over {} proofs",
+            rss_growth,
+            iterations
+        );
+    }
+
+    // nothing created while proving may keep the parameters alive
+    for count in [
+        Arc::strong_count(&params.h_query),
+        Arc::strong_count(&params.l_query),
+        Arc::strong_count(&params.a_query),
+        Arc::strong_count(&params.b_g1_query),
+        Arc::strong_count(&params.b_g2_query),
+    ] {
+        assert_eq!(count, 1);
+    }
+}
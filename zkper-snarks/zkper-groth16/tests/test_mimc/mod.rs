@@ -1,10 +1,19 @@
+#![allow(clippy::let_and_return)]
+
 use rug::Integer;
-use zkper_curves::curves::bls12_381::BLS12_381_SCALAR;
+use zkper_curves::{
+    curves::bls12_381::{Bls12_381ScalarField, Scalar, BLS12_381_SCALAR},
+    traits::field::FieldTrait,
+};
 use zkper_groth16::{
     circuit::Circuit,
-    constraints::{linear_combination::LinearCombination, ConstraintSystem},
-    prover::ProvingSystem,
+    constraints::{linear_combination::LinearCombination, ConstraintSystem, ConstraintSystemTrait},
+    error::Groth16Error,
+    generator::generate_proving_parameters,
+    models::{proving_parameters::ProvingParameters, verify::PreparedVerifyingKey},
+    verifier::prepare_verifying_key,
 };
+use zkper_rand::TestRng;
 
 use crate::MIMC_ROUNDS;
 pub mod implemention;
@@ -16,17 +25,18 @@ pub struct MiMCDemo<'a> {
 }
 
 impl Circuit for MiMCDemo<'_> {
-    /// Generate the constraints for the MiMC circuit
-    fn synthesize(&self, cs: &mut ConstraintSystem) -> anyhow::Result<()> {
+    /// Generate the constraints for the MiMC circuit, with their values
+    /// while proving
+    fn synthesize<CS: ConstraintSystemTrait>(&self, cs: &mut CS) -> Result<(), Groth16Error> {
         assert_eq!(self.constants.len(), MIMC_ROUNDS);
 
         // Allocate the first component of the preimage.
-        let mut xl = cs.new_private()?;
         let mut xl_value = self.xl.clone();
+        let mut xl = cs.alloc_private(|| value(&xl_value))?;
 
         // Allocate the second component of the preimage.
-        let mut xr = cs.new_private()?;
         let mut xr_value = self.xr.clone();
+        let mut xr = cs.alloc_private(|| value(&xr_value))?;
 
         for i in 0..MIMC_ROUNDS {
             // tmp = (xL + Ci)^2
@@ -36,7 +46,7 @@ impl Circuit for MiMCDemo<'_> {
                 t
             });
 
-            let tmp = cs.new_private()?;
+            let tmp = cs.alloc_private(|| value(&tmp_value))?;
 
             let a = LinearCombination::zero()
                 .add((xl, Integer::from(1)))
@@ -45,7 +55,7 @@ impl Circuit for MiMCDemo<'_> {
                 .add((xl, Integer::from(1)))
                 .add((ConstraintSystem::one(), self.constants[i].clone()));
             let c = LinearCombination::zero().add((tmp, Integer::from(1)));
-            cs.enforce_constraint(a, b.clone(), c.clone());
+            cs.enforce(a, b.clone(), c.clone());
 
             // new_xL = xR + (xL + Ci)^3
             // new_xL = xR + tmp * (xL + Ci)
@@ -60,9 +70,9 @@ impl Circuit for MiMCDemo<'_> {
             let new_xl = if i == (MIMC_ROUNDS - 1) {
                 // This is the last round, xL is our image and so
                 // we allocate a public input.
-                cs.new_public()?
+                cs.alloc_public(|| value(&new_xl_value))?
             } else {
-                cs.new_private()?
+                cs.alloc_private(|| value(&new_xl_value))?
             };
 
             let a = c;
@@ -70,7 +80,7 @@ impl Circuit for MiMCDemo<'_> {
             let c = LinearCombination::zero()
                 .add_variable(new_xl)
                 .sub_variable(xr);
-            cs.enforce_constraint(a, b, c);
+            cs.enforce(a, b, c);
 
             // xR = xL
             xr = xl;
@@ -83,72 +93,66 @@ impl Circuit for MiMCDemo<'_> {
 
         Ok(())
     }
+}
 
-    /// Generate the constraints for the MiMC circuit
-    fn synthesize_proof(&self, cs: &mut ProvingSystem) -> anyhow::Result<()> {
-        assert_eq!(self.constants.len(), MIMC_ROUNDS);
-
-        // Allocate the first component of the preimage.
-        let mut xl = cs.new_private(self.xl.clone().unwrap())?;
-        let mut xl_value = self.xl.clone();
-
-        // Allocate the second component of the preimage.
-        let mut xr = cs.new_private(self.xr.clone().unwrap())?;
-        let mut xr_value = self.xr.clone();
-
-        for i in 0..MIMC_ROUNDS {
-            // tmp = (xL + Ci)^2
-            let tmp_value = xl_value.clone().map(|mut e| {
-                e = BLS12_381_SCALAR.add(e, &self.constants[i]);
-                let t = BLS12_381_SCALAR.square(e);
-                t
-            });
-
-            let tmp = cs.new_private(tmp_value.clone().unwrap())?;
-
-            let a = LinearCombination::zero()
-                .add((xl, Integer::from(1)))
-                .add((ConstraintSystem::one(), self.constants[i].clone()));
-            let b = LinearCombination::zero()
-                .add((xl, Integer::from(1)))
-                .add((ConstraintSystem::one(), self.constants[i].clone()));
-            let c = LinearCombination::zero().add((tmp, Integer::from(1)));
-            cs.enforce(a, b.clone(), c.clone());
-
-            // new_xL = xR + (xL + Ci)^3
-            // new_xL = xR + tmp * (xL + Ci)
-            // new_xL - xR = tmp * (xL + Ci)
-            let new_xl_value = xl_value.clone().map(|mut e| {
-                e = BLS12_381_SCALAR.add(e, &self.constants[i]);
-                e = BLS12_381_SCALAR.mul(e, &tmp_value.clone().unwrap());
-                e = BLS12_381_SCALAR.add(e, &xr_value.clone().unwrap());
-                e
-            });
-
-            let new_xl = if i == (MIMC_ROUNDS - 1) {
-                // This is the last round, xL is our image and so
-                // we allocate a public input.
-                cs.new_public(new_xl_value.clone().unwrap())?
-            } else {
-                cs.new_private(new_xl_value.clone().unwrap())?
-            };
-
-            let a = c;
-            let b = b;
-            let c = LinearCombination::zero()
-                .add_variable(new_xl)
-                .sub_variable(xr);
-            cs.enforce(a, b, c);
+/// The value of a variable, only needed while proving.
+fn value(value: &Option<Integer>) -> anyhow::Result<Scalar> {
+    value
+        .clone()
+        .map(Scalar::from)
+        .ok_or_else(|| anyhow::anyhow!("Missing value of a MiMC variable"))
+}
 
-            // xR = xL
-            xr = xl;
-            xr_value = xl_value;
+/// `MIMC_ROUNDS` random round constants.
+#[allow(dead_code)]
+pub fn mimc_constants(rng: &mut TestRng) -> Vec<Integer> {
+    (0..MIMC_ROUNDS)
+        .map(|_| BLS12_381_SCALAR.sample_raw(rng))
+        .collect()
+}
 
-            // xL = new_xL
-            xl = new_xl;
-            xl_value = new_xl_value;
-        }
+/// A random preimage `(xl, xr)` and its image under `constants`.
+#[allow(dead_code)]
+pub fn mimc_instance(rng: &mut TestRng, constants: &[Integer]) -> (Integer, Integer, Scalar) {
+    let constants_scalar = constants
+        .iter()
+        .cloned()
+        .map(Bls12_381ScalarField::from)
+        .collect::<Vec<_>>();
+
+    let xl = Bls12_381ScalarField::random(rng);
+    let xr = Bls12_381ScalarField::random(rng);
+    let image =
+        implemention::mimc_implemention(xl.clone().into(), xr.clone().into(), &constants_scalar);
+    (xl, xr, image)
+}
 
-        Ok(())
-    }
+/// The setup most tests start from: round constants, parameters and the
+/// prepared key of the circuit over them, and one preimage with its image,
+/// as `(params, pvk, constants, xl, xr, image)`.
+#[allow(dead_code)]
+pub fn mimc_fixture(
+    rng: &mut TestRng,
+) -> (
+    ProvingParameters,
+    PreparedVerifyingKey,
+    Vec<Integer>,
+    Integer,
+    Integer,
+    Scalar,
+) {
+    let constants = mimc_constants(rng);
+    let params = generate_proving_parameters(
+        MiMCDemo {
+            xl: None,
+            xr: None,
+            constants: &constants,
+        },
+        rng,
+    )
+    .unwrap();
+    let pvk = prepare_verifying_key(&params.vk);
+
+    let (xl, xr, image) = mimc_instance(rng, &constants);
+    (params, pvk, constants, xl, xr, image)
 }
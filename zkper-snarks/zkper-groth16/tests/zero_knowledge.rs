@@ -0,0 +1,126 @@
+//! Smoke test for the zero-knowledge property of the prover.
+//!
+//! Proves one statement with one witness many times and checks that the
+//! serialized proofs look like independent random points: every proof is
+//! different, no bit is fixed that is not also fixed in the encoding of
+//! random points, and two proofs differ in about as many bits as two random
+//! points do. A prover that stopped applying the r and s blinding would
+//! produce the same proof every time and fail all three checks.
+
+use zkper_curves::curves::bls12_381::curves::{g1::G1Projective, g2::G2Projective};
+use zkper_groth16::{models::proof::Proof, prover::create_proof, verifier::verify_proof};
+use zkper_rand::TestRng;
+
+use crate::test_mimc::{mimc_fixture, MiMCDemo};
+
+pub mod test_mimc;
+
+pub const MIMC_ROUNDS: usize = 16;
+
+const SAMPLES: usize = 32;
+
+/// Allowed difference between the mean distance of two proofs and of two
+/// random points, as a fraction of all bits.
+const MAX_DISTANCE_BIAS: f64 = 0.02;
+
+fn encode(proof: &Proof) -> Vec<u8> {
+    let mut bytes = proof.a.to_compressed().to_vec();
+    bytes.extend_from_slice(&proof.b.to_compressed());
+    bytes.extend_from_slice(&proof.c.to_compressed());
+    bytes
+}
+
+fn bit(bytes: &[u8], i: usize) -> bool {
+    bytes[i / 8] >> (7 - i % 8) & 1 == 1
+}
+
+/// The bit positions that hold the same value in every sample.
+fn constant_bits(samples: &[Vec<u8>]) -> Vec<usize> {
+    (0..samples[0].len() * 8)
+        .filter(|&i| samples.iter().all(|s| bit(s, i) == bit(&samples[0], i)))
+        .collect()
+}
+
+/// The mean number of differing bits over all pairs, as a fraction.
+fn mean_distance(samples: &[Vec<u8>]) -> f64 {
+    let mut total = 0;
+    let mut pairs = 0;
+    for (i, a) in samples.iter().enumerate() {
+        for b in &samples[i + 1..] {
+            total += a
+                .iter()
+                .zip(b)
+                .map(|(x, y)| (x ^ y).count_ones())
+                .sum::<u32>();
+            pairs += 1;
+        }
+    }
+    total as f64 / (pairs * samples[0].len() * 8) as f64
+}
+
+#[test]
+fn test_proofs_are_blinded() {
+    let mut rng = TestRng::new();
+
+    let (params, pvk, constants, xl, xr, image) = mimc_fixture(&mut rng);
+    let prove = |rng: &mut TestRng| {
+        let circuit = MiMCDemo {
+            xl: Some(xl.clone()),
+            xr: Some(xr.clone()),
+            constants: &constants,
+        };
+        create_proof(circuit, &params, rng).unwrap()
+    };
+
+    let proofs = (0..SAMPLES).map(|_| prove(&mut rng)).collect::<Vec<_>>();
+    for proof in &proofs {
+        assert!(verify_proof(&pvk, proof, std::slice::from_ref(&image)).unwrap());
+    }
+
+    // every element of every proof is fresh
+    for (i, p) in proofs.iter().enumerate() {
+        for q in &proofs[i + 1..] {
+            assert_ne!(p.a, q.a);
+            assert_ne!(p.b, q.b);
+            assert_ne!(p.c, q.c);
+        }
+    }
+
+    // the same statistics for the encodings of random points, which fix the
+    // flag bits and the top bits of every coordinate
+    let reference = (0..SAMPLES)
+        .map(|_| {
+            encode(&Proof {
+                a: G1Projective::random(&mut rng).to_affine(),
+                b: G2Projective::random(&mut rng).to_affine(),
+                c: G1Projective::random(&mut rng).to_affine(),
+            })
+        })
+        .collect::<Vec<_>>();
+    let encoded = proofs.iter().map(encode).collect::<Vec<_>>();
+
+    let fixed = constant_bits(&reference);
+    let proof_fixed = constant_bits(&encoded);
+    assert!(
+        proof_fixed.iter().all(|i| fixed.contains(i)),
+        "bits {:?} are the same in every proof",
+        proof_fixed
+            .iter()
+            .filter(|i| !fixed.contains(i))
+            .collect::<Vec<_>>()
+    );
+
+    let distance = mean_distance(&encoded);
+    let expected = mean_distance(&reference);
+    assert!(
+        (distance - expected).abs() < MAX_DISTANCE_BIAS,
+        "proofs differ in {distance:.3} of their bits, random points in {expected:.3}"
+    );
+
+    // the checks catch a prover without fresh randomness
+    let deterministic = (0..SAMPLES)
+        .map(|_| encode(&prove(&mut TestRng::from_seed(1))))
+        .collect::<Vec<_>>();
+    assert_eq!(constant_bits(&deterministic).len(), encoded[0].len() * 8);
+    assert_eq!(mean_distance(&deterministic), 0.0);
+}